@@ -266,6 +266,7 @@ def drop_and_recreate_schema(conn):
                 indicator_name VARCHAR NOT NULL,
                 parameters JSONB NOT NULL,
                 enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                priority INTEGER NOT NULL DEFAULT 0,
                 created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
                 updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
                 UNIQUE(symbol, interval, indicator_name, parameters)
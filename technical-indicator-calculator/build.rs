@@ -1,14 +1,23 @@
 fn main() {
-    // Link with TA-Lib - note the hyphen in the library name
-    println!("cargo:rustc-link-lib=ta-lib");
+    // The TA-Lib bindings are only built with the native feature; the wasm build of the
+    // pure-Rust code links nothing
+    if std::env::var_os("CARGO_FEATURE_NATIVE").is_some() {
+        // Link with TA-Lib - note the hyphen in the library name
+        println!("cargo:rustc-link-lib=ta-lib");
+        
+        // Specify where to find the library
+        println!("cargo:rustc-link-search=/lib");
+        
+        // Add to runtime path so it's found at runtime too
+        println!("cargo:rustc-link-arg=-Wl,-rpath,/lib");
+    }
     
-    // Specify where to find the library
-    println!("cargo:rustc-link-search=/lib");
-    
-    // Add to runtime path so it's found at runtime too
-    println!("cargo:rustc-link-arg=-Wl,-rpath,/lib");
+    // Generate the gRPC service from the protobuf definition
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/backtester.proto").expect("Failed to compile proto/backtester.proto");
     
     // Regenerate if these files change
     println!("cargo:rerun-if-changed=src/talib_bindings.rs");
+    println!("cargo:rerun-if-changed=proto/backtester.proto");
     println!("cargo:rerun-if-changed=build.rs");
 }
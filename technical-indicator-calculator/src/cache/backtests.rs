@@ -0,0 +1,137 @@
+use crate::cache::redis::RedisManager;
+use crate::database::models::{AlignedFrame, CandleData};
+use crate::strategy::schema::StrategyPerformance;
+use crate::utils::session::SessionCalendar;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Checkpoints outlive the cache TTL so an interrupted backtest can be resumed later
+const CHECKPOINT_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Cache of backtest intermediate state in Redis
+///
+/// Indicator frames are keyed by the strategy version and indicators, symbol, interval,
+/// date range and indicator source; results and checkpoints additionally by the whole
+/// strategy, engine settings, contract and capital (see `fingerprint`). Frames and
+/// results expire after the Redis cache TTL, checkpoints after a week or once the
+/// backtest completes.
+pub struct BacktestCache {
+    redis: Arc<RedisManager>,
+}
+
+impl BacktestCache {
+    pub fn new(redis: Arc<RedisManager>) -> Self {
+        Self { redis }
+    }
+
+    /// Get a cached indicator frame
+    pub async fn get_frame(&self, frame_key: &str) -> Result<Option<AlignedFrame>> {
+        let frame = self.redis.get::<CachedFrame>(&Self::key("frame", frame_key)).await?;
+
+        debug!("Backtest frame cache {} for {}", if frame.is_some() { "hit" } else { "miss" }, frame_key);
+        Ok(frame.map(CachedFrame::into_frame))
+    }
+
+    /// Store an indicator frame
+    pub async fn put_frame(&self, frame_key: &str, frame: &AlignedFrame) -> Result<()> {
+        self.redis.set(&Self::key("frame", frame_key), &CachedFrame::from_frame(frame), None).await
+    }
+
+    /// Get the result of a completed backtest
+    pub async fn get_result(&self, run_key: &str) -> Result<Option<StrategyPerformance>> {
+        self.redis.get(&Self::key("result", run_key)).await
+    }
+
+    /// Store the result of a completed backtest and drop its checkpoint
+    pub async fn put_result(&self, run_key: &str, performance: &StrategyPerformance) -> Result<()> {
+        self.redis.set(&Self::key("result", run_key), performance, None).await?;
+        self.redis.delete(&Self::key("checkpoint", run_key)).await?;
+        Ok(())
+    }
+
+    /// Get the last checkpoint of an interrupted backtest
+    pub async fn get_checkpoint<T: DeserializeOwned>(&self, run_key: &str) -> Result<Option<T>> {
+        self.redis.get(&Self::key("checkpoint", run_key)).await
+    }
+
+    /// Store the state of a backtest after a completed month
+    pub async fn put_checkpoint<T: Serialize>(&self, run_key: &str, state: &T) -> Result<()> {
+        self.redis.set(&Self::key("checkpoint", run_key), state, Some(CHECKPOINT_TTL)).await
+    }
+
+    fn key(kind: &str, fingerprint: &str) -> String {
+        RedisManager::backtest_key(kind, fingerprint)
+    }
+}
+
+/// SHA-256 of a JSON value, hex encoded
+///
+/// Object keys serialize sorted, so equal values always give the same fingerprint.
+pub fn fingerprint(value: &serde_json::Value) -> String {
+    hex::encode(Sha256::digest(value.to_string().as_bytes()))
+}
+
+// AlignedFrame in a form that survives JSON: unknown taker buy volumes are NaN in
+// CandleData, which JSON can't represent
+#[derive(Serialize, Deserialize)]
+struct CachedFrame {
+    symbol: String,
+    interval: String,
+    open_time: Vec<DateTime<Utc>>,
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+    close_time: Vec<DateTime<Utc>>,
+    taker_buy_volume: Vec<Option<f64>>,
+    #[serde(default)]
+    session: SessionCalendar,
+    indicators: HashMap<String, Vec<Option<serde_json::Value>>>,
+}
+
+impl CachedFrame {
+    fn from_frame(frame: &AlignedFrame) -> Self {
+        let candles = &frame.candles;
+        Self {
+            symbol: candles.symbol.clone(),
+            interval: candles.interval.clone(),
+            open_time: candles.open_time.clone(),
+            open: candles.open.clone(),
+            high: candles.high.clone(),
+            low: candles.low.clone(),
+            close: candles.close.clone(),
+            volume: candles.volume.clone(),
+            close_time: candles.close_time.clone(),
+            taker_buy_volume: candles.taker_buy_volume.iter()
+                .map(|volume| volume.is_finite().then_some(*volume))
+                .collect(),
+            session: candles.session,
+            indicators: frame.indicators.clone(),
+        }
+    }
+
+    fn into_frame(self) -> AlignedFrame {
+        let mut candles = CandleData::new(self.symbol, self.interval).with_session(self.session);
+        candles.open_time = self.open_time;
+        candles.open = self.open;
+        candles.high = self.high;
+        candles.low = self.low;
+        candles.close = self.close;
+        candles.volume = self.volume;
+        candles.close_time = self.close_time;
+        candles.taker_buy_volume = self.taker_buy_volume.into_iter()
+            .map(|volume| volume.unwrap_or(f64::NAN))
+            .collect();
+
+        let mut frame = AlignedFrame::new(candles);
+        frame.indicators = self.indicators;
+        frame
+    }
+}
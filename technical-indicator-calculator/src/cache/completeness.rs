@@ -1,12 +1,13 @@
 use crate::processor::job::CalculationJob;
 use chrono::{DateTime, Duration, Utc};
 use parking_lot::RwLock;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::info;
 
 /// Represents the completeness status of an indicator
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CompletenessInfo {
     /// Symbol for the indicator (e.g., "BTCUSDT")
     pub symbol: String,
@@ -61,6 +62,16 @@ impl CompletenessInfo {
         }
     }
     
+    /// How far the last calculated value lags behind the last candle
+    pub fn staleness(&self) -> Option<Duration> {
+        match (self.last_candle_time, self.last_calculated_time) {
+            (Some(last_candle), Some(last_calculated)) => {
+                Some(last_candle.signed_duration_since(last_calculated).max(Duration::zero()))
+            },
+            _ => None,
+        }
+    }
+    
     /// Check if the completeness info is still valid
     pub fn is_valid(&self, ttl_minutes: i64) -> bool {
         let now = Utc::now();
@@ -122,6 +133,11 @@ impl CompletenessCache {
         cache.remove(&key);
     }
     
+    /// Get a copy of every cached entry, including expired ones
+    pub fn entries(&self) -> Vec<CompletenessInfo> {
+        self.cache.read().values().cloned().collect()
+    }
+    
     /// Clear the entire cache
     pub fn clear(&self) {
         let mut cache = self.cache.write();
@@ -122,6 +122,17 @@ impl CompletenessController {
         Ok(())
     }
     
+    /// Completeness of every cached configuration, ordered by symbol, interval and indicator
+    pub fn report(&self) -> Vec<CompletenessInfo> {
+        let mut entries = self.cache.entries();
+        entries.sort_by(|a, b| {
+            (&a.symbol, &a.interval, &a.indicator_name, a.parameters.to_string())
+                .cmp(&(&b.symbol, &b.interval, &b.indicator_name, b.parameters.to_string()))
+        });
+        
+        entries
+    }
+    
     /// Check if a job is complete based on cached information
     pub fn is_job_complete(&self, job: &CalculationJob) -> bool {
         if let Some(info) = self.cache.get(job) {
@@ -1,3 +1,5 @@
 pub mod redis;
 pub mod completeness;
 pub mod completeness_controller;
+pub mod results;
+pub mod backtests;
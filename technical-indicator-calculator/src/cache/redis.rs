@@ -33,6 +33,11 @@ impl RedisManager {
         })
     }
     
+    // Get a pooled connection for commands not wrapped here
+    pub async fn connection(&self) -> Result<deadpool_redis::Connection> {
+        Ok(self.pool.get().await?)
+    }
+    
     // Set a key with serialized value and TTL
     pub async fn set<T: Serialize>(&self, key: &str, value: &T, ttl: Option<Duration>) -> Result<()> {
         let serialized = serde_json::to_string(value)?;
@@ -51,7 +56,6 @@ impl RedisManager {
     }
     
     // Get and deserialize a value by key
-    #[allow(dead_code)]
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
         let mut conn = self.pool.get().await?;
         let result: Option<String> = conn.get(key).await?;
@@ -137,6 +141,25 @@ impl RedisManager {
         )
     }
     
+    // Cache key for an indicator series calculated from the candles with this hash
+    pub fn indicator_result_key(
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters_json: &str,
+        candle_hash: &str,
+    ) -> String {
+        format!(
+            "indicator_result:{}:{}:{}:{}:{}",
+            symbol, interval, indicator_name, parameters_json, candle_hash
+        )
+    }
+    
+    // Cache key for backtest frames, results and checkpoints by their fingerprint
+    pub fn backtest_key(kind: &str, fingerprint: &str) -> String {
+        format!("backtest:{}:{}", kind, fingerprint)
+    }
+    
     // Cache key for intermediate calculation results
     #[allow(dead_code)]
     pub fn intermediate_key(
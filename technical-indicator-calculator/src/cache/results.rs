@@ -0,0 +1,66 @@
+use crate::cache::redis::RedisManager;
+use crate::database::models::{CandleData, IndicatorSeries};
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Cache of calculated indicator series in Redis
+///
+/// Series are keyed by symbol, interval, indicator, parameters and a hash of the
+/// candles they were calculated from, so any change to the candle range or data
+/// (a new candle, a corrected price) misses the cache instead of returning stale
+/// values. Entries expire after the Redis cache TTL.
+pub struct IndicatorResultCache {
+    redis: Arc<RedisManager>,
+}
+
+impl IndicatorResultCache {
+    pub fn new(redis: Arc<RedisManager>) -> Self {
+        Self { redis }
+    }
+
+    /// Get a cached series calculated from exactly these candles
+    pub async fn get(&self, candles: &CandleData, indicator_name: &str,
+                     parameters: &serde_json::Value) -> Result<Option<IndicatorSeries>> {
+        let key = Self::key(candles, indicator_name, parameters);
+        let series = self.redis.get::<IndicatorSeries>(&key).await?;
+
+        debug!("Indicator result cache {} for {}", if series.is_some() { "hit" } else { "miss" }, key);
+        Ok(series)
+    }
+
+    /// Store a series calculated from these candles
+    pub async fn put(&self, candles: &CandleData, series: &IndicatorSeries) -> Result<()> {
+        let key = Self::key(candles, &series.indicator_name, &series.parameters);
+        self.redis.set(&key, series, None).await
+    }
+
+    fn key(candles: &CandleData, indicator_name: &str, parameters: &serde_json::Value) -> String {
+        // Object keys serialize sorted, so equal parameters always give the same key
+        RedisManager::indicator_result_key(
+            &candles.symbol,
+            &candles.interval,
+            indicator_name,
+            &parameters.to_string(),
+            &candle_hash(candles),
+        )
+    }
+}
+
+/// SHA-256 over the session calendar, times and OHLCV values of the candles, hex encoded
+pub fn candle_hash(candles: &CandleData) -> String {
+    let mut hasher = Sha256::new();
+
+    // Session-anchored indicators differ between calendars on the same candles
+    hasher.update(candles.session.to_string().as_bytes());
+
+    for i in 0..candles.close.len() {
+        hasher.update(candles.open_time[i].timestamp_millis().to_le_bytes());
+        for value in [candles.open[i], candles.high[i], candles.low[i], candles.close[i], candles.volume[i]] {
+            hasher.update(value.to_bits().to_le_bytes());
+        }
+    }
+
+    hex::encode(hasher.finalize())
+}
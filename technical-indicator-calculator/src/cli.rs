@@ -1,13 +1,15 @@
 // src/cli.rs
 use chrono::{DateTime, Utc};
 use clap::{Parser, Subcommand};
-use crate::database::postgres::PostgresManager;
+use clap_complete::Shell;
+use crate::cli_help;
+use crate::indicators::export::{ExportFormat, PrintFormat};
 use crate::strategy::evaluator::StrategyEvaluator;
-use crate::strategy::repository::StrategyRepository;
+use crate::strategy::cli_handler::create_repository;
+use crate::strategy::genetic::Objective;
 use crate::strategy::schema::Strategy;
-use std::sync::Arc;
+use crate::strategy::templates::StrategyTemplate;
 use std::path::PathBuf;
-use std::process::Command;
 use anyhow::{Result, Context};
 use serde_json;
 
@@ -17,54 +19,902 @@ use serde_json;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    
+    /// Output format of list, view and backtest, given before the subcommand
+    #[arg(long, value_enum, default_value = "table")]
+    pub output: PrintFormat,
+    
+    /// Namespace of strategies, indicator configurations and backtest results
+    /// (overrides DB_NAMESPACE and [database] namespace)
+    #[arg(long, global = true)]
+    pub namespace: Option<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Start the indicator calculation service
+    #[command(long_about = cli_help::START)]
     Start {
         /// Number of worker threads to use
         #[arg(short, long)]
         concurrency: Option<usize>,
         
-        /// Run in background (daemon mode)
-        #[arg(short, long)]
-        detached: bool,
+        /// Run in background (daemon mode)
+        #[arg(short, long)]
+        detached: bool,
+    },
+    
+    /// Stop the indicator calculation service running in the background
+    #[command(long_about = cli_help::STOP)]
+    Stop,
+    
+    /// Check the status of the indicator calculation service
+    #[command(long_about = cli_help::STATUS, args_conflicts_with_subcommands = true)]
+    Status {
+        #[command(subcommand)]
+        command: Option<StatusCommands>,
+        
+        /// Show the workers sharing the job queue through Redis
+        #[arg(long)]
+        cluster: bool,
+    },
+    
+    /// List all available strategies
+    #[command(long_about = cli_help::LIST)]
+    List {
+        /// Show only enabled strategies
+        #[arg(short, long)]
+        enabled_only: bool,
+        
+        /// Only strategies trading this symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        asset: Option<String>,
+        
+        /// Only strategies on this interval (e.g., "1h")
+        #[arg(long)]
+        timeframe: Option<String>,
+        
+        /// Only strategies by this author
+        #[arg(long)]
+        author: Option<String>,
+        
+        /// Only strategies with this tag, repeatable (e.g., "momentum")
+        #[arg(long)]
+        tag: Vec<String>,
+        
+        /// Condition on the latest backtest, repeatable (e.g., "sharpe>1.5")
+        #[arg(long)]
+        metric: Vec<String>,
+        
+        /// Text to find in the name or description
+        #[arg(short, long)]
+        search: Option<String>,
+        
+        /// Order by name, created, updated or a metric of the latest backtest
+        #[arg(long, default_value = "name")]
+        sort: String,
+        
+        /// Sort in descending order
+        #[arg(long)]
+        desc: bool,
+    },
+    
+    /// View details of a strategy
+    #[command(long_about = cli_help::VIEW)]
+    View {
+        /// Strategy ID
+        #[arg(short, long)]
+        id: String,
+        
+        /// Export to a JSON, YAML or TOML file
+        #[arg(short, long)]
+        export: Option<PathBuf>,
+    },
+    
+    /// Import a strategy from a JSON, YAML or TOML file
+    #[command(long_about = cli_help::IMPORT)]
+    Import {
+        /// Input file
+        #[arg(short, long)]
+        file: PathBuf,
+        
+        /// Create the indicator configurations the strategy needs instead of listing them
+        #[arg(long)]
+        provision: bool,
+    },
+    
+    /// Run a backtest for a strategy
+    #[command(long_about = cli_help::BACKTEST)]
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Backtest {
+        #[command(subcommand)]
+        command: Option<BacktestCommands>,
+        
+        /// Strategy ID
+        #[arg(short, long, required = true)]
+        strategy_id: Option<String>,
+        
+        /// Symbol (e.g., "BTCUSDT")
+        #[arg(short, long, required = true)]
+        symbol: Option<String>,
+        
+        /// Interval (e.g., "1h", "4h", "1d")
+        #[arg(short, long, required = true)]
+        interval: Option<String>,
+        
+        /// Start date for backtest (ISO format)
+        #[arg(long)]
+        start_date: Option<String>,
+        
+        /// End date for backtest (ISO format)
+        #[arg(long)]
+        end_date: Option<String>,
+        
+        /// Initial capital
+        #[arg(long, default_value = "10000.0")]
+        initial_capital: f64,
+        
+        /// Export results to JSON file
+        #[arg(long)]
+        export: Option<PathBuf>,
+        
+        /// Engine settings profile used for fills and costs
+        #[arg(long, default_value = "default")]
+        engine_profile: String,
+        
+        /// Calculate every indicator from candles instead of loading stored values
+        #[arg(long)]
+        recalculate: bool,
+        
+        /// Don't reuse cached frames and results or resume from a checkpoint
+        #[arg(long)]
+        no_cache: bool,
+        
+        /// Seed of random components such as slippage jitter
+        #[arg(long, default_value = "0")]
+        seed: u64,
+    },
+    
+    /// Optimize a strategy using the LLM
+    #[command(long_about = cli_help::OPTIMIZE)]
+    Optimize {
+        /// Strategy ID
+        #[arg(short, long)]
+        strategy_id: String,
+        
+        /// Symbol (e.g., "BTCUSDT")
+        #[arg(short, long)]
+        symbol: String,
+        
+        /// Interval (e.g., "1h", "4h", "1d")
+        #[arg(short, long)]
+        interval: String,
+        
+        /// Start date for backtest (ISO format)
+        #[arg(long)]
+        start_date: Option<String>,
+        
+        /// End date for backtest (ISO format)
+        #[arg(long)]
+        end_date: Option<String>,
+        
+        /// Initial capital
+        #[arg(long, default_value = "10000.0")]
+        initial_capital: f64,
+        
+        /// Maximum iterations
+        #[arg(long, default_value = "10")]
+        max_iterations: usize,
+        
+        /// Engine settings profile used for fills and costs
+        #[arg(long, default_value = "default")]
+        engine_profile: String,
+        
+        /// Output file for report
+        #[arg(long, default_value = "optimization_report.md")]
+        output: PathBuf,
+        
+        /// Write the report without saving the best configuration
+        #[arg(long)]
+        dry_run: bool,
+    },
+    
+    /// Search strategy parameters with a genetic algorithm
+    #[command(long_about = cli_help::TUNE)]
+    Tune {
+        #[command(subcommand)]
+        command: TuneCommands,
+    },
+    
+    /// Re-run backtests of enabled strategies over a recent window
+    #[command(long_about = cli_help::REEVALUATE)]
+    Reevaluate {
+        #[command(subcommand)]
+        command: ReevaluateCommands,
+    },
+    
+    /// Show the event log of jobs, configuration changes, imports, backtests and signals
+    #[command(long_about = cli_help::EVENTS)]
+    Events {
+        #[command(subcommand)]
+        command: EventsCommands,
+    },
+    
+    /// List supported indicators and their parameters and outputs
+    #[command(long_about = cli_help::INDICATORS)]
+    Indicators {
+        #[command(subcommand)]
+        command: IndicatorsCommands,
+    },
+    
+    /// Check calculations against stored reference outputs
+    #[command(long_about = cli_help::VERIFY)]
+    Verify {
+        #[command(subcommand)]
+        command: VerifyCommands,
+    },
+    
+    /// Create and edit strategies
+    #[command(long_about = cli_help::STRATEGY)]
+    Strategy {
+        #[command(subcommand)]
+        command: StrategyCommands,
+    },
+    
+    /// Manage backtest engine settings profiles
+    #[command(long_about = cli_help::ENGINE)]
+    Engine {
+        #[command(subcommand)]
+        command: EngineCommands,
+    },
+    
+    /// Archive old backtest results to object storage
+    #[command(long_about = cli_help::ARCHIVE)]
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommands,
+    },
+    
+    /// Export a calculated indicator series to a file
+    #[command(long_about = cli_help::EXPORT)]
+    Export {
+        /// Symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: String,
+        
+        /// Interval (e.g., "1h", "4h", "1d")
+        #[arg(long)]
+        interval: String,
+        
+        /// Indicator name (e.g., "RSI", "MACD")
+        #[arg(long)]
+        indicator: String,
+        
+        /// Indicator parameters as JSON, matching indicator_config (e.g., '{"period": 14}')
+        #[arg(long, default_value = "{}")]
+        parameters: String,
+        
+        /// Start date (ISO format)
+        #[arg(long)]
+        start_date: Option<String>,
+        
+        /// End date (ISO format)
+        #[arg(long)]
+        end_date: Option<String>,
+        
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+        
+        /// Output file; a .gz extension enables gzip compression
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    
+    /// Check stored candle data
+    #[command(long_about = cli_help::DATA)]
+    Data {
+        #[command(subcommand)]
+        command: DataCommands,
+    },
+    
+    /// Manage symbol metadata and the watchlists built from it
+    #[command(long_about = cli_help::SYMBOLS)]
+    Symbols {
+        #[command(subcommand)]
+        command: SymbolsCommands,
+    },
+    
+    /// Inspect and requeue worker jobs
+    #[command(long_about = cli_help::JOBS)]
+    Jobs {
+        #[command(subcommand)]
+        command: JobsCommands,
+    },
+    
+    /// Recalculate stored indicator values on demand
+    #[command(long_about = cli_help::CALC)]
+    Calc {
+        #[command(subcommand)]
+        command: CalcCommands,
+    },
+    
+    /// Manage indicator configurations and application settings
+    #[command(long_about = cli_help::CONFIG)]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    
+    /// Database maintenance
+    #[command(long_about = cli_help::DB)]
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+    
+    /// Trade live signals on a simulated account
+    #[command(long_about = cli_help::PAPER)]
+    Paper {
+        #[command(subcommand)]
+        command: PaperCommands,
+    },
+    
+    /// Inspect and reset the trading kill switch
+    #[command(long_about = cli_help::RISK)]
+    Risk {
+        #[command(subcommand)]
+        command: RiskCommands,
+    },
+    
+    /// Serve indicator calculation, backtests and signals over gRPC
+    #[cfg(feature = "grpc")]
+    #[command(long_about = cli_help::SERVE)]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:50051")]
+        listen: String,
+    },
+    
+    /// Generate shell completions
+    #[command(long_about = cli_help::COMPLETIONS)]
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StatusCommands {
+    /// Show how far each indicator configuration is calculated
+    #[command(long_about = cli_help::STATUS_COVERAGE)]
+    Coverage {
+        /// Only show this symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: Option<String>,
+        
+        /// Only show this interval (e.g., "1h")
+        #[arg(long)]
+        interval: Option<String>,
+        
+        /// Only show this indicator (e.g., "RSI")
+        #[arg(long)]
+        indicator: Option<String>,
+        
+        /// Only show configurations that are not complete
+        #[arg(long)]
+        incomplete: bool,
+        
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    
+    /// Show which indicators dominate calculation and insert time
+    #[command(long_about = cli_help::STATUS_TIMINGS)]
+    Timings {
+        /// Maximum number of indicator/symbol rows to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        
+        /// Print Prometheus histograms instead of a table
+        #[arg(long)]
+        prometheus: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DataCommands {
+    /// Scan candles for gaps, duplicates and zero-volume anomalies
+    #[command(long_about = cli_help::DATA_AUDIT)]
+    Audit {
+        /// Only audit this symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: Option<String>,
+        
+        /// Only audit this interval (e.g., "1h")
+        #[arg(long)]
+        interval: Option<String>,
+        
+        /// Fetch missing candles from Binance and store them
+        #[arg(long)]
+        backfill: bool,
+    },
+    
+    /// Import candles from a CSV file into the configured database
+    #[command(long_about = cli_help::DATA_IMPORT)]
+    Import {
+        /// CSV file in Binance kline format
+        file: PathBuf,
+        
+        /// Symbol of the candles (e.g., "BTCUSDT")
+        #[arg(short, long)]
+        symbol: String,
+        
+        /// Interval of the candles (e.g., "1h")
+        #[arg(short, long)]
+        interval: String,
+    },
+    
+    /// Download funding rates of a perpetual futures symbol from Binance
+    #[command(long_about = cli_help::DATA_FUNDING)]
+    Funding {
+        /// Perpetual futures symbol (e.g., "BTCUSDT")
+        #[arg(short, long)]
+        symbol: String,
+        
+        /// Start date (ISO format); defaults to after the latest stored rate
+        #[arg(long)]
+        start_date: Option<String>,
+        
+        /// End date (ISO format)
+        #[arg(long)]
+        end_date: Option<String>,
+    },
+    
+    /// Manage redenominations and splits that candles are back-adjusted for
+    #[command(long_about = cli_help::DATA_ADJUSTMENTS)]
+    Adjustments {
+        #[command(subcommand)]
+        command: AdjustmentCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AdjustmentCommands {
+    /// Record a redenomination or split of a symbol
+    #[command(long_about = cli_help::DATA_ADJUSTMENTS_ADD)]
+    Add {
+        /// Symbol (e.g., "BTCUSDT")
+        #[arg(short, long)]
+        symbol: String,
+        
+        /// Time the new units apply from (ISO format)
+        #[arg(long)]
+        effective_time: String,
+        
+        /// New units per old unit (e.g., 2 for a 2:1 split, 0.001 for 1000 old tokens becoming 1)
+        #[arg(long)]
+        ratio: f64,
+        
+        /// redenomination, split or reverse_split
+        #[arg(long, default_value = "redenomination")]
+        kind: String,
+        
+        /// Free-text note, e.g. the announcement
+        #[arg(long)]
+        note: Option<String>,
+    },
+    
+    /// List recorded adjustments
+    #[command(long_about = cli_help::DATA_ADJUSTMENTS_LIST)]
+    List {
+        /// Only list adjustments of this symbol
+        #[arg(short, long)]
+        symbol: Option<String>,
+    },
+    
+    /// Delete an adjustment
+    #[command(long_about = cli_help::DATA_ADJUSTMENTS_REMOVE)]
+    Remove {
+        /// Adjustment ID, as shown by `data adjustments list`
+        id: i32,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SymbolsCommands {
+    /// Add a symbol or update its metadata
+    #[command(long_about = cli_help::SYMBOLS_ADD)]
+    Add {
+        /// Symbol (e.g., "BTCUSDT")
+        symbol: String,
+        
+        /// Base asset (e.g., "BTC")
+        #[arg(long)]
+        base: Option<String>,
+        
+        /// Quote asset (e.g., "USDT")
+        #[arg(long)]
+        quote: Option<String>,
+        
+        /// Minimum price increment
+        #[arg(long)]
+        tick_size: Option<f64>,
+        
+        /// Date the symbol was listed (ISO format)
+        #[arg(long)]
+        listing_date: Option<String>,
+        
+        /// Trading session calendar: a preset (crypto, nyse, lse, xetra, cme) or
+        /// "<timezone> <open>-<close> <days>"
+        #[arg(long)]
+        session: Option<String>,
+        
+        /// Mark the symbol as no longer trading
+        #[arg(long)]
+        inactive: bool,
+    },
+    
+    /// List stored symbols, or the symbols of a watchlist
+    #[command(long_about = cli_help::SYMBOLS_LIST)]
+    List {
+        /// Only list symbols quoted in this asset (e.g., "USDT")
+        #[arg(long)]
+        quote: Option<String>,
+        
+        /// Also list inactive symbols
+        #[arg(long)]
+        all: bool,
+        
+        /// List the current symbols of a watchlist (e.g., "top50_volume")
+        #[arg(long, conflicts_with_all = ["quote", "all"])]
+        watchlist: Option<String>,
+    },
+    
+    /// Download symbol metadata and 24h volumes from Binance
+    #[command(long_about = cli_help::SYMBOLS_IMPORT_FROM_EXCHANGE)]
+    ImportFromExchange {
+        /// Only import symbols quoted in this asset (e.g., "USDT")
+        #[arg(long)]
+        quote: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PaperCommands {
+    /// Execute new signals from the signal ledger until interrupted
+    #[command(long_about = cli_help::PAPER_RUN)]
+    Run {
+        /// Paper account to trade
+        #[arg(long, default_value = "default")]
+        account: String,
+        
+        /// Also execute signals sent since this time (ISO format)
+        #[arg(long)]
+        since: Option<String>,
+        
+        /// Send every fill to the Binance testnet as well (requires [execution] enabled)
+        #[arg(long)]
+        route_orders: bool,
+    },
+    
+    /// Show open positions marked to the latest prices
+    #[command(long_about = cli_help::PAPER_POSITIONS)]
+    Positions {
+        /// Paper account to show
+        #[arg(long, default_value = "default")]
+        account: String,
+    },
+    
+    /// Show the most recent paper fills
+    #[command(long_about = cli_help::PAPER_TRADES)]
+    Trades {
+        /// Paper account to show
+        #[arg(long, default_value = "default")]
+        account: String,
+        
+        /// Maximum number of trades to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: i64,
+    },
+    
+    /// Show the order journal of routed exchange orders
+    #[command(long_about = cli_help::PAPER_ORDERS)]
+    Orders {
+        /// Maximum number of orders to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: i64,
+    },
+    
+    /// Show the equity history of a paper account
+    #[command(long_about = cli_help::PAPER_EQUITY)]
+    Equity {
+        /// Paper account to show
+        #[arg(long, default_value = "default")]
+        account: String,
+        
+        /// Maximum number of snapshots to show
+        #[arg(short, long, default_value_t = 20)]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RiskCommands {
+    /// Show the risk limits and whether trading is halted
+    #[command(long_about = cli_help::RISK_STATUS)]
+    Status {
+        /// Paper account to check
+        #[arg(long, default_value = "default")]
+        account: String,
+    },
+    
+    /// Lift a halt and restart loss tracking from the current equity
+    #[command(long_about = cli_help::RISK_RESET)]
+    Reset {
+        /// Paper account to reset
+        #[arg(long, default_value = "default")]
+        account: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// Expire and downsample calculated indicator values
+    #[command(long_about = cli_help::DB_RETENTION)]
+    Retention {
+        #[command(subcommand)]
+        command: RetentionCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RetentionCommands {
+    /// Apply the configured retention policies now
+    #[command(long_about = cli_help::DB_RETENTION_APPLY)]
+    Apply {
+        /// Show how many values each policy would remove without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JobsCommands {
+    /// List jobs that failed after all retries
+    #[command(long_about = cli_help::JOBS_FAILED)]
+    Failed {
+        /// Maximum number of jobs to show
+        #[arg(long, default_value = "50")]
+        limit: i64,
+    },
+    
+    /// Requeue failed jobs so the worker calculates them again
+    #[command(long_about = cli_help::JOBS_REQUEUE)]
+    Requeue {
+        /// Failed job IDs as shown by `jobs failed`
+        #[arg(required_unless_present = "all")]
+        ids: Vec<i64>,
+        
+        /// Requeue every failed job
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+    
+    /// List suspicious calculated values flagged by the worker
+    #[command(long_about = cli_help::JOBS_ANOMALIES)]
+    Anomalies {
+        /// Only show this symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: Option<String>,
+        
+        /// Only show this indicator (e.g., "RSI")
+        #[arg(long)]
+        indicator: Option<String>,
+        
+        /// Maximum number of anomalies to show
+        #[arg(long, default_value = "50")]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CalcCommands {
+    /// Calculate an indicator from stored candles and print the series
+    #[command(long_about = cli_help::CALC_RUN)]
+    Run {
+        /// Symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: String,
+        
+        /// Interval (e.g., "1h", "4h", "1d")
+        #[arg(long)]
+        interval: String,
+        
+        /// Indicator name (e.g., "RSI", "MACD")
+        #[arg(long)]
+        indicator: String,
+        
+        /// Indicator parameter as name=value, repeatable (e.g., --param period=14)
+        #[arg(long = "param")]
+        params: Vec<String>,
+        
+        /// Number of latest values to print
+        #[arg(long, default_value = "50")]
+        last: usize,
+        
+        /// Output format
+        #[arg(long, value_enum, default_value = "table")]
+        format: PrintFormat,
+        
+        /// Draw close prices with the indicator instead of printing the series
+        #[arg(long)]
+        chart: bool,
+        
+        /// Chart height in rows
+        #[arg(long, default_value = "20")]
+        height: usize,
+    },
+    
+    /// Recalculate indicators for a symbol and time range, bypassing the completeness check
+    #[command(long_about = cli_help::CALC_BACKFILL)]
+    Backfill {
+        /// Symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: String,
+        
+        /// Interval (e.g., "1h", "4h", "1d")
+        #[arg(long)]
+        interval: String,
+        
+        /// Indicator name (e.g., "RSI"); every enabled indicator for the symbol/interval when omitted
+        #[arg(long)]
+        indicator: Option<String>,
+        
+        /// Only the configuration with these parameters (JSON)
+        #[arg(long)]
+        parameters: Option<String>,
+        
+        /// First value to overwrite (ISO format); the start of the candle history when omitted
+        #[arg(long)]
+        from: Option<String>,
+        
+        /// Last candle to include (ISO format); the latest candle when omitted
+        #[arg(long)]
+        to: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Reconcile indicator_config with a manifest file
+    #[command(long_about = cli_help::CONFIG_APPLY)]
+    Apply {
+        /// Manifest file (YAML or JSON)
+        file: PathBuf,
+        
+        /// Show the changes without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    
+    /// Show the effective settings from config.toml and the environment
+    #[command(long_about = cli_help::CONFIG_SHOW)]
+    Show,
+}
+
+#[derive(Subcommand)]
+pub enum BacktestCommands {
+    /// Compare saved backtest results side by side
+    #[command(long_about = cli_help::BACKTEST_COMPARE)]
+    Compare {
+        /// Backtest result IDs; the first one is the baseline
+        #[arg(required = true, num_args = 2..)]
+        ids: Vec<i32>,
+        
+        /// Also write the comparison to this file (.json or .csv)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    
+    /// Backtest a set of strategies on several symbols and intervals and rank the results
+    #[command(long_about = cli_help::BACKTEST_BATCH)]
+    Batch {
+        /// Strategies with this tag, repeatable
+        #[arg(long)]
+        tag: Vec<String>,
+        
+        /// Strategy ID, repeatable
+        #[arg(long)]
+        strategy: Vec<String>,
+        
+        /// Symbols, comma-separated (defaults to each strategy's assets)
+        #[arg(long, value_delimiter = ',')]
+        symbols: Vec<String>,
+        
+        /// Intervals, comma-separated (defaults to each strategy's timeframes)
+        #[arg(long, value_delimiter = ',')]
+        intervals: Vec<String>,
+        
+        /// Start date for the backtests (ISO format)
+        #[arg(long)]
+        start_date: Option<String>,
+        
+        /// End date for the backtests (ISO format)
+        #[arg(long)]
+        end_date: Option<String>,
+        
+        /// Initial capital
+        #[arg(long, default_value = "10000.0")]
+        initial_capital: f64,
+        
+        /// Engine settings profile used for fills and costs
+        #[arg(long, default_value = "default")]
+        engine_profile: String,
+        
+        /// Number of backtests running at the same time
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+        
+        /// Metric to rank the results by (e.g., "sharpe", "return", "drawdown")
+        #[arg(long, default_value = "sharpe")]
+        rank_by: String,
     },
     
-    /// Stop the indicator calculation service running in the background
-    Stop,
-    
-    /// Check the status of the indicator calculation service
-    Status,
-    
-    /// List all available strategies
+    /// List saved backtest results, newest first
+    #[command(long_about = cli_help::BACKTEST_LIST)]
     List {
-        /// Show only enabled strategies
-        #[arg(short, long)]
-        enabled_only: bool,
+        /// Only results of this strategy
+        #[arg(long)]
+        strategy: Option<String>,
+        
+        /// Only results on this symbol (e.g., "BTCUSDT")
+        #[arg(long)]
+        symbol: Option<String>,
+        
+        /// Only results on this interval (e.g., "1h")
+        #[arg(long)]
+        interval: Option<String>,
+        
+        /// Only results created at or after this time (ISO format)
+        #[arg(long)]
+        since: Option<String>,
+        
+        /// Maximum number of results to show
+        #[arg(long, default_value = "20")]
+        limit: i64,
     },
     
-    /// View details of a strategy
+    /// Show all metrics of a saved backtest result
+    #[command(long_about = cli_help::BACKTEST_VIEW)]
     View {
-        /// Strategy ID
-        #[arg(short, long)]
-        id: String,
-        
-        /// Export to JSON file
-        #[arg(short, long)]
-        export: Option<PathBuf>,
+        /// Backtest result ID
+        id: i32,
     },
     
-    /// Import a strategy from JSON file
-    Import {
-        /// Input file
-        #[arg(short, long)]
-        file: PathBuf,
+    /// Show the equity curve of a saved backtest result
+    #[command(long_about = cli_help::BACKTEST_SHOW_EQUITY)]
+    ShowEquity {
+        /// Backtest result ID
+        id: i32,
+        
+        /// Draw the curve as a chart instead of listing it
+        #[arg(long)]
+        chart: bool,
+        
+        /// Chart height in rows
+        #[arg(long, default_value = "20")]
+        height: usize,
     },
-    
-    /// Run a backtest for a strategy
-    Backtest {
+}
+
+#[derive(Subcommand)]
+pub enum TuneCommands {
+    /// Run a genetic search over a strategy's parameters
+    #[command(long_about = cli_help::TUNE_RUN)]
+    Run {
         /// Strategy ID
         #[arg(short, long)]
         strategy_id: String,
@@ -77,11 +927,11 @@ pub enum Commands {
         #[arg(short, long)]
         interval: String,
         
-        /// Start date for backtest (ISO format)
+        /// Start date for backtests (ISO format)
         #[arg(long)]
         start_date: Option<String>,
         
-        /// End date for backtest (ISO format)
+        /// End date for backtests (ISO format)
         #[arg(long)]
         end_date: Option<String>,
         
@@ -89,73 +939,445 @@ pub enum Commands {
         #[arg(long, default_value = "10000.0")]
         initial_capital: f64,
         
-        /// Export results to JSON file
+        /// Engine settings profile used for fills and costs
+        #[arg(long, default_value = "default")]
+        engine_profile: String,
+        
+        /// Metric to maximize
+        #[arg(long, value_enum, default_value = "expectancy")]
+        objective: Objective,
+        
+        /// Candidates per generation
+        #[arg(long, default_value = "20")]
+        population: usize,
+        
+        /// Maximum number of generations
+        #[arg(long, default_value = "10")]
+        generations: usize,
+        
+        /// Probability that a parameter of a child mutates
+        #[arg(long, default_value = "0.2")]
+        mutation_rate: f64,
+        
+        /// Probability that a child mixes two parents
+        #[arg(long, default_value = "0.7")]
+        crossover_rate: f64,
+        
+        /// Best candidates kept unchanged in the next generation
+        #[arg(long, default_value = "2")]
+        elite: usize,
+        
+        /// Stop after this many generations without improvement (0 = never)
+        #[arg(long, default_value = "3")]
+        patience: usize,
+        
+        /// Candidates with fewer trades get no fitness
+        #[arg(long, default_value = "5")]
+        min_trades: i32,
+        
+        /// Seed for a reproducible search (random and printed when omitted)
         #[arg(long)]
-        export: Option<PathBuf>,
+        seed: Option<u64>,
+        
+        /// Share of the period held out for out-of-sample checks (0 disables them)
+        #[arg(long, default_value = "0.25")]
+        holdout: f64,
+        
+        /// Best candidates whose parameter stability is checked
+        #[arg(long, default_value = "3")]
+        stability_top: usize,
+        
+        /// Flag candidates whose out-of-sample metric is this many percent worse
+        #[arg(long, default_value = "50.0")]
+        max_degradation: f64,
+        
+        /// Flag candidates with a lower stability score (0..1)
+        #[arg(long, default_value = "0.5")]
+        min_stability: f64,
+        
+        /// Save the best parameters to the strategy
+        #[arg(long)]
+        apply: bool,
     },
     
-    /// Optimize a strategy using the LLM
-    Optimize {
-        /// Strategy ID
+    /// Show the best candidates of a search run
+    #[command(long_about = cli_help::TUNE_SHOW)]
+    Show {
+        /// Run ID printed by tune run
+        run_id: i32,
+        
+        /// Maximum number of candidates to show
+        #[arg(long, default_value = "20")]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReevaluateCommands {
+    /// Re-evaluate enabled strategies now
+    #[command(long_about = cli_help::REEVALUATE_RUN)]
+    Run {
+        /// Only this strategy (it doesn't need to be enabled)
         #[arg(short, long)]
-        strategy_id: String,
+        strategy_id: Option<String>,
         
-        /// Symbol (e.g., "BTCUSDT")
+        /// Days of recent candles to backtest (default: reevaluation.window_days)
+        #[arg(long)]
+        window_days: Option<u32>,
+    },
+    
+    /// Show the latest re-evaluations
+    #[command(long_about = cli_help::REEVALUATE_SHOW)]
+    Show {
+        /// Only this strategy
         #[arg(short, long)]
-        symbol: String,
+        strategy_id: Option<String>,
         
-        /// Interval (e.g., "1h", "4h", "1d")
+        /// Only degraded windows
+        #[arg(long)]
+        degraded: bool,
+        
+        /// Maximum number of re-evaluations to show
+        #[arg(long, default_value = "20")]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EventsCommands {
+    /// Show the latest events, optionally following new ones
+    #[command(long_about = cli_help::EVENTS_TAIL)]
+    Tail {
+        /// Keep printing new events until interrupted
         #[arg(short, long)]
-        interval: String,
+        follow: bool,
         
-        /// Start date for backtest (ISO format)
+        /// Only these kinds, comma-separated (e.g. job_failed,data_quality)
+        #[arg(short, long, value_delimiter = ',')]
+        kind: Vec<String>,
+        
+        /// Only events about this subject (e.g. BTCUSDT:1h:RSI or a strategy ID)
+        #[arg(short, long)]
+        subject: Option<String>,
+        
+        /// Number of past events to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: i64,
+        
+        /// Seconds between polls with --follow
+        #[arg(long, default_value = "2")]
+        poll_seconds: u64,
+        
+        /// Print events as JSON lines
         #[arg(long)]
-        start_date: Option<String>,
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IndicatorsCommands {
+    /// List supported indicators
+    #[command(long_about = cli_help::INDICATORS_LIST)]
+    List {
+        /// Only indicators of this category (e.g. momentum, pattern, plugin)
+        #[arg(short, long)]
+        category: Option<String>,
         
-        /// End date for backtest (ISO format)
+        /// Print the catalog as JSON
         #[arg(long)]
-        end_date: Option<String>,
+        json: bool,
+    },
+    
+    /// Show the inputs, parameters and outputs of an indicator
+    #[command(long_about = cli_help::INDICATORS_DESCRIBE)]
+    Describe {
+        /// Indicator name or alias (e.g. MACD, ENGULFING)
+        name: String,
         
-        /// Initial capital
-        #[arg(long, default_value = "10000.0")]
-        initial_capital: f64,
+        /// Print the description as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VerifyCommands {
+    /// Compare every built-in indicator on the reference candles with its golden outputs
+    #[command(long_about = cli_help::VERIFY_INDICATORS)]
+    Indicators {
+        /// Only check this indicator (e.g. RSI)
+        #[arg(long)]
+        indicator: Option<String>,
         
-        /// Maximum iterations
-        #[arg(long, default_value = "10")]
-        max_iterations: usize,
+        /// Relative difference tolerated for every indicator, overriding the stored tolerances
+        #[arg(long)]
+        tolerance: Option<f64>,
         
-        /// Output file for report
-        #[arg(long, default_value = "optimization_report.md")]
+        /// Rewrite the golden outputs in the source tree from the current build
+        #[arg(long)]
+        update: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArchiveCommands {
+    /// Move old backtest results to object storage, keeping summaries in the database
+    #[command(long_about = cli_help::ARCHIVE_RUN)]
+    Run {
+        /// Archive results older than this many days
+        #[arg(long, default_value = "90")]
+        older_than_days: i64,
+        
+        /// Maximum number of results to archive
+        #[arg(long, default_value = "1000")]
+        limit: i64,
+        
+        /// Show what would be archived without uploading or modifying anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    
+    /// Retrieve an archived backtest result
+    #[command(long_about = cli_help::ARCHIVE_FETCH)]
+    Fetch {
+        /// Backtest result ID
+        #[arg(short, long)]
+        id: i32,
+        
+        /// Write the result to this file instead of printing it
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EngineCommands {
+    /// List available engine settings profiles
+    #[command(long_about = cli_help::ENGINE_LIST)]
+    List,
+    
+    /// Show an engine settings profile
+    #[command(long_about = cli_help::ENGINE_SHOW)]
+    Show {
+        /// Profile name
+        #[arg(short, long)]
+        name: String,
+    },
+    
+    /// Save an engine settings profile from a JSON file
+    #[command(long_about = cli_help::ENGINE_SAVE)]
+    Save {
+        /// JSON file containing the profile
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StrategyCommands {
+    /// Generate a new strategy from a built-in template
+    #[command(long_about = cli_help::STRATEGY_NEW)]
+    New {
+        /// Template to generate the strategy from
+        #[arg(short, long, value_enum)]
+        template: StrategyTemplate,
+        
+        /// Name of the new strategy (defaults to the template name)
+        #[arg(short, long)]
+        name: Option<String>,
+        
+        /// Write the generated strategy to this file (JSON, YAML or TOML by extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Import the generated strategy into the database
+        #[arg(long)]
+        import: bool,
+    },
+    
+    /// Validate a strategy file without importing it
+    ///
+    /// Exits with 0 when the strategy is valid, 1 when validation fails
+    /// and 2 when the file cannot be read or parsed.
+    #[command(long_about = cli_help::STRATEGY_VALIDATE)]
+    Validate {
+        /// Strategy file (JSON, YAML or TOML)
+        #[arg(short, long)]
+        file: PathBuf,
+        
+        /// Treat warnings as errors
+        #[arg(long)]
+        strict: bool,
+        
+        /// Skip the database coverage checks
+        #[arg(long)]
+        offline: bool,
+    },
+    
+    /// Convert a strategy file between JSON, YAML and TOML
+    #[command(long_about = cli_help::STRATEGY_CONVERT)]
+    Convert {
+        /// Strategy file to read
+        #[arg(short, long)]
+        input: PathBuf,
+        
+        /// File to write; the format follows its extension
+        #[arg(short, long)]
         output: PathBuf,
     },
+    
+    /// Add an indicator to a stored strategy
+    #[command(long_about = cli_help::STRATEGY_ADD_INDICATOR)]
+    AddIndicator {
+        /// Strategy ID
+        #[arg(short, long)]
+        id: String,
+        
+        /// Indicator ID within the strategy
+        #[arg(long)]
+        indicator_id: String,
+        
+        /// Indicator type (oscillator, overlap, volume, volatility, pattern)
+        #[arg(long)]
+        indicator_type: String,
+        
+        /// Indicator name (e.g., "RSI", "MACD")
+        #[arg(long)]
+        indicator_name: String,
+        
+        /// Indicator parameters as JSON (e.g., '{"period": 14}')
+        #[arg(long, default_value = "{}")]
+        parameters: String,
+        
+        /// Description of how the indicator is used
+        #[arg(long, default_value = "")]
+        description: String,
+    },
+    
+    /// Remove an indicator from a stored strategy
+    #[command(long_about = cli_help::STRATEGY_REMOVE_INDICATOR)]
+    RemoveIndicator {
+        /// Strategy ID
+        #[arg(short, long)]
+        id: String,
+        
+        /// Indicator ID within the strategy
+        #[arg(long)]
+        indicator_id: String,
+    },
+    
+    /// Add a rule to a stored strategy from a JSON file
+    #[command(long_about = cli_help::STRATEGY_ADD_RULE)]
+    AddRule {
+        /// Strategy ID
+        #[arg(short, long)]
+        id: String,
+        
+        /// JSON file containing the rule definition
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    
+    /// Remove a rule from a stored strategy
+    #[command(long_about = cli_help::STRATEGY_REMOVE_RULE)]
+    RemoveRule {
+        /// Strategy ID
+        #[arg(short, long)]
+        id: String,
+        
+        /// Rule ID within the strategy
+        #[arg(long)]
+        rule_id: String,
+    },
+    
+    /// Copy a strategy under a new ID, optionally overriding parameters
+    #[command(name = "clone", long_about = cli_help::STRATEGY_CLONE)]
+    CloneStrategy {
+        /// ID of the strategy to clone
+        id: String,
+        
+        /// Name of the new strategy (defaults to "<name> (copy)")
+        #[arg(short, long)]
+        name: Option<String>,
+        
+        /// Parameter overrides (can be repeated)
+        #[arg(long = "set", value_name = "PARAM=VALUE")]
+        set: Vec<String>,
+    },
+    
+    /// Set the value of a strategy parameter
+    #[command(long_about = cli_help::STRATEGY_SET_PARAM)]
+    SetParam {
+        /// Strategy ID
+        #[arg(short, long)]
+        id: String,
+        
+        /// Parameter name
+        #[arg(short, long)]
+        name: String,
+        
+        /// New parameter value
+        #[arg(short, long)]
+        value: String,
+    },
+    
+    /// Package a strategy with its backtests and indicator configurations
+    #[command(long_about = cli_help::STRATEGY_EXPORT_BUNDLE)]
+    ExportBundle {
+        /// ID of the strategy to export
+        id: String,
+        
+        /// Bundle file to write (.tar.gz)
+        #[arg(short, long)]
+        out: PathBuf,
+        
+        /// Number of most recent backtest results to include
+        #[arg(long, default_value = "10")]
+        backtests: i64,
+    },
+    
+    /// Import a strategy bundle written by export-bundle
+    #[command(long_about = cli_help::STRATEGY_IMPORT_BUNDLE)]
+    ImportBundle {
+        /// Bundle file to read (.tar.gz)
+        file: PathBuf,
+    },
+    
+    /// Group strategies into families with tags
+    #[command(long_about = cli_help::STRATEGY_TAG)]
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
 }
 
-/// Connect to the database and create a repository
-pub async fn create_repository() -> Result<StrategyRepository> {
-    // Get database configuration from environment
-    let db_host = std::env::var("DB_HOST").unwrap_or_else(|_| "localhost".to_string());
-    let db_port = std::env::var("DB_PORT").unwrap_or_else(|_| "5432".to_string()).parse::<u16>()?;
-    let db_user = std::env::var("DB_USER").unwrap_or_else(|_| "binanceuser".to_string());
-    let db_password = std::env::var("DB_PASSWORD").unwrap_or_else(|_| "binancepass".to_string());
-    let db_name = std::env::var("DB_NAME").unwrap_or_else(|_| "binancedb".to_string());
-    
-    // Create PostgreSQL connection
-    let pg: Arc<PostgresManager> = Arc::new(
-        PostgresManager::new(
-            &db_host,
-            db_port,
-            &db_user,
-            &db_password,
-            &db_name,
-            10, // Max connections
-        )
-        .await?
-    );
-    
-    // Create the repository
-    let repository = StrategyRepository::new(pg);
-    
-    Ok(repository)
+#[derive(Subcommand)]
+pub enum TagCommands {
+    /// Add tags to a strategy
+    #[command(long_about = cli_help::STRATEGY_TAG_ADD)]
+    Add {
+        /// Strategy ID
+        id: String,
+        
+        /// Tags to add (e.g., "momentum")
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    
+    /// Remove tags from a strategy
+    #[command(long_about = cli_help::STRATEGY_TAG_REMOVE)]
+    Remove {
+        /// Strategy ID
+        id: String,
+        
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    
+    /// List the tags in use with their number of strategies
+    #[command(long_about = cli_help::STRATEGY_TAG_LIST)]
+    List,
 }
 
 /// Parse an ISO date string to DateTime<Utc>
@@ -164,14 +1386,18 @@ pub fn parse_date(date_str: &str) -> Result<DateTime<Utc>> {
 }
 
 /// Execute a command from the CLI
-pub async fn execute_command(command: Commands) -> Result<()> {
+pub async fn execute_command(command: Commands, output: PrintFormat) -> Result<()> {
     match command {
-        Commands::Start { .. } | Commands::Stop | Commands::Status => {
+        Commands::Start { .. } | Commands::Stop | Commands::Status { .. } => {
             // These commands are handled in main.rs
             unreachable!("Start/Stop/Status commands should be handled in main.rs");
         },
+        #[cfg(feature = "grpc")]
+        Commands::Serve { .. } => {
+            unreachable!("Serve is handled in main.rs");
+        },
         
-        Commands::List { enabled_only } => {
+        Commands::List { enabled_only, .. } => {
             // Create repository
             let repository = create_repository().await?;
             
@@ -241,7 +1467,7 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             }
         },
         
-        Commands::Import { file } => {
+        Commands::Import { file, .. } => {
             // Create repository
             let repository = create_repository().await?;
             
@@ -252,7 +1478,13 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             println!("Strategy imported successfully with ID: {}", strategy.id);
         },
         
-        Commands::Backtest { strategy_id, symbol, interval, start_date, end_date, initial_capital, export } => {
+        Commands::Backtest { 
+            command: None, 
+            strategy_id: Some(strategy_id), 
+            symbol: Some(symbol), 
+            interval: Some(interval), 
+            start_date, end_date, initial_capital, export, .. 
+        } => {
             // Create repository
             let repository = create_repository().await?;
             
@@ -292,46 +1524,9 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             }
         },
         
-        Commands::Optimize { strategy_id, symbol, interval, start_date, end_date, initial_capital, max_iterations, output } => {
-            // Since the optimizer is in Python, call it as a separate process
-            let mut command = Command::new("python3");
-            
-            command.arg("llm_optimizer.py")
-                   .arg("--strategy_id").arg(strategy_id)
-                   .arg("--symbol").arg(symbol)
-                   .arg("--interval").arg(interval)
-                   .arg("--initial_capital").arg(initial_capital.to_string())
-                   .arg("--max_iterations").arg(max_iterations.to_string())
-                   .arg("--output").arg(output);
-                   
-            if let Some(start) = start_date {
-                command.arg("--start_date").arg(start);
-            }
-            
-            if let Some(end) = end_date {
-                command.arg("--end_date").arg(end);
-            }
-            
-            println!("Starting LLM optimization process...");
-            
-            let output = command.output().context("Failed to run LLM optimizer")?;
-            
-            // Print any stdout/stderr from the process
-            if !output.stdout.is_empty() {
-                println!("{}", String::from_utf8_lossy(&output.stdout));
-            }
-            
-            if !output.stderr.is_empty() {
-                eprintln!("{}", String::from_utf8_lossy(&output.stderr));
-            }
-            
-            // Check exit status
-            if output.status.success() {
-                println!("Optimization completed successfully.");
-            } else {
-                eprintln!("Optimization failed with exit code: {:?}", output.status.code());
-                return Err(anyhow::anyhow!("Optimization failed"));
-            }
+        other => {
+            // Newer commands are only implemented in the strategy CLI handler
+            crate::strategy::cli_handler::execute_command(other, output).await?;
         },
     }
     
@@ -0,0 +1,1266 @@
+// Long help texts with runnable examples for the CLI subcommands
+
+pub const START: &str = "\
+Start the indicator calculation service
+
+Reads indicator_config from PostgreSQL and keeps calculated_indicators up to date.
+Only configurations of the worker's namespace are calculated; run one worker per
+namespace sharing the database. Calculated values are shared between namespaces.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  DB_NAMESPACE                                      Namespace of configurations to calculate (default: default)
+  REDIS_URL                                         Redis connection (default redis://localhost:6379)
+  CONCURRENCY                                       Default number of concurrent jobs
+  WORKER_POOLS                                      Interval pools, e.g. fast:1m,5m:4;slow:1d:1
+  BATCH_VARIANTS                                    Calculate all parameter sets of an indicator in one job (default: true)
+  CANDLE_CACHE_MAX_CANDLES                          Candles cached in memory across jobs, 0 disables (default: 2000000)
+  METRICS_ADDR                                      Prometheus metrics endpoint, e.g. 0.0.0.0:9184
+  INDICATOR_NAN_POLICY                              NaN/Inf outputs: null, skip or error (default: null)
+  ANOMALY_JUMP_FACTOR, ANOMALY_CONSTANT_RUN         Flag jumps and constant runs in calculated values (default: 10, 200)
+  ANOMALY_NOTIFY                                    Post flagged values to the notification webhook (default: false)
+  REEVALUATION_ENABLED                              Re-run backtests of enabled strategies (default: false)
+  SIGNAL_POLL_SECONDS                               Evaluate enabled strategies for new signals, 0 disables (default: 0)
+  RUST_LOG, LOG_FORMAT                              Log filter and line format, text or json (default: info, text)
+  LOG_DIR, LOG_ROTATION                             Also write rolling log files to a directory (default: daily)
+  OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_SERVICE_NAME    Export spans and job metrics over OTLP (needs the otel feature)
+
+Examples:
+  technical-indicator-calculator start
+  technical-indicator-calculator start --concurrency 8
+  technical-indicator-calculator start --detached";
+
+pub const STOP: &str = "\
+Stop the indicator calculation service running in the background
+
+Sends SIGTERM so the service stops taking new jobs, lets running jobs finish
+(long jobs are checkpointed at the next candle chunk) and releases their Redis
+locks. The process is killed if it is still running after the grace period.
+
+Environment:
+  STOP_TIMEOUT_SECONDS   Grace period before SIGKILL (default: 60)
+
+Examples:
+  technical-indicator-calculator stop";
+
+pub const STATUS: &str = "\
+Check the status of the indicator calculation service
+
+Reads the status file the running worker rewrites every 5 seconds: uptime,
+enabled configurations, job counts, candle cache hit rate, queue depth per pool
+and the last error.
+
+With --cluster, lists the worker instances registered in Redis (started with
+WORKER_CLUSTER=true) with their throughput, plus the shared queue depth.
+
+Environment:
+  STATUS_FILE   Status file (default: indicator-calculator.status.json in the temp directory)
+  REDIS_URL     Redis connection for --cluster (default: redis://localhost:6379)
+
+Examples:
+  technical-indicator-calculator status
+  technical-indicator-calculator status --cluster
+  technical-indicator-calculator status coverage --incomplete
+  technical-indicator-calculator status timings";
+
+pub const STATUS_COVERAGE: &str = "\
+Show how far each enabled indicator configuration is calculated
+
+For every configuration in indicator_config the table shows the share of the
+stored candle range that has calculated values, the time of the last calculated
+value and its staleness: how far it lags behind the last stored candle. A
+configuration is complete when coverage is at least 95% and staleness at most
+24 hours, the same rule the worker uses to skip up-to-date jobs.
+
+With --json every configuration is printed as an object including
+staleness_seconds, for use in scripts and monitoring.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator status coverage
+  technical-indicator-calculator status coverage --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator status coverage --indicator RSI --incomplete
+  technical-indicator-calculator status coverage --json";
+
+pub const STATUS_TIMINGS: &str = "\
+Show which indicators dominate calculation and insert time
+
+Reads the per-indicator timings the running worker publishes in its status
+file: how often each indicator was calculated per symbol, the total, mean and
+slowest wall-time of the calculations and of the database inserts, and each
+row's share of all recorded time. Rows are ordered by total time. Timings are
+collected since the worker started.
+
+With --prometheus the same data is printed as Prometheus histograms. The worker
+serves them continuously when METRICS_ADDR is set.
+
+Environment:
+  STATUS_FILE    Status file (default: indicator-calculator.status.json in the temp directory)
+  METRICS_ADDR   Prometheus endpoint of the worker, e.g. 0.0.0.0:9184 (default: disabled)
+
+Examples:
+  technical-indicator-calculator status timings
+  technical-indicator-calculator status timings --limit 5
+  technical-indicator-calculator status timings --prometheus";
+
+pub const LIST: &str = "\
+List all available strategies
+
+Strategies can be narrowed by asset, timeframe, author, tag and text in the name
+or description (--search), and by metrics of their latest backtest result with
+--metric, e.g. \"sharpe>1.5\" or \"drawdown<=20\"; several --metric conditions
+must all hold, and with several --tag options strategies need all the tags.
+Strategies without a backtest don't match metric conditions and are listed last
+when sorting by a metric.
+
+Metrics (for --metric and --sort):
+  sharpe, sortino, calmar, return, annualized_return, excess_return, drawdown,
+  win_rate, profit_factor, expectancy, trades, exposure
+
+With --output json or csv (given before the subcommand) the strategies are
+printed for scripts instead of as a table.
+
+Only strategies of the current namespace are listed. Strategies, indicator
+configurations and backtest results belong to the namespace given with
+--namespace, DB_NAMESPACE or [database] namespace (\"default\" when unset), so
+several users or dev, staging and prod environments can share one database.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  DB_NAMESPACE                                      Namespace to work in (default: default)
+
+Examples:
+  technical-indicator-calculator list
+  technical-indicator-calculator list --namespace staging
+  technical-indicator-calculator list --enabled-only
+  technical-indicator-calculator list --asset BTCUSDT --timeframe 1h --search breakout
+  technical-indicator-calculator list --tag momentum
+  technical-indicator-calculator list --metric \"sharpe>1.5\" --metric \"trades>=30\" --sort sharpe --desc
+  technical-indicator-calculator --output json list";
+
+pub const VIEW: &str = "\
+View details of a strategy
+
+With --output json (given before the subcommand) the strategy is printed as
+stored; --output csv lists its indicators, rules and parameters, one per row.
+
+Examples:
+  technical-indicator-calculator view --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c
+  technical-indicator-calculator view --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --export strategy.json
+  technical-indicator-calculator view --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --export strategy.yaml
+  technical-indicator-calculator --output json view --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c";
+
+pub const IMPORT: &str = "\
+Import a strategy from a JSON, YAML or TOML file
+
+The format follows the file extension (.yaml/.yml, .toml, otherwise JSON).
+The strategy is validated before it is stored. A missing or nil ID is replaced by a new UUID.
+
+The indicator configurations the strategy needs (each indicator for each of its assets
+and timeframes) that are missing or disabled are listed afterwards. With --provision
+they are created or re-enabled, so the worker starts calculating them. Script
+indicators and statistics calculated during the backtest need no configuration.
+
+Examples:
+  technical-indicator-calculator import --file strategy.json
+  technical-indicator-calculator import --file strategy.json --provision";
+
+pub const BACKTEST: &str = "\
+Run a backtest for a strategy
+
+Uses the engine settings profile for costs and fills and the symbols table for the
+contract type (linear, inverse or quanto). Results are stored in strategy_backtest_results.
+
+Indicator values already calculated by the worker are loaded from calculated_indicators;
+indicators with missing or incomplete values are calculated from the candles. Use
+--recalculate to calculate every indicator, e.g. after changing an indicator implementation.
+
+Calculated series are cached in Redis, keyed by the indicator, its parameters and a hash
+of the candles, so repeated backtests over identical data skip the calculation. Without
+Redis every backtest calculates them again.
+
+Backtests with an --end-date are cached as a whole: the indicator frame is keyed by the
+strategy version and indicators, symbol, interval, date range and indicator source, and
+the result additionally by the full strategy, engine profile, contract and capital, so
+re-running an identical backtest returns at once. Long backtests are simulated month by
+month with a checkpoint in Redis after each month; an interrupted run resumes from the
+last completed month when started again with the same arguments. Use --no-cache to
+ignore all of this and run from scratch.
+
+With --output json or csv (given before the subcommand) only the result is written
+to stdout, as one JSON document or one metric per CSV row; progress and warnings
+go to stderr.
+
+Random components, such as the slippage jitter of engine profiles with
+slippage_jitter_percent, draw from a generator seeded with --seed (default 0). The
+seed is stored with the result, so rerunning with it reproduces the result exactly.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  REDIS_URL                                         Indicator result cache (default: redis://localhost:6379)
+  CACHE_TTL_SECONDS                                 Lifetime of cached series (default: 3600)
+
+Examples:
+  technical-indicator-calculator backtest --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator backtest --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol ETHUSDT --interval 4h \\
+      --start-date 2024-01-01T00:00:00Z --end-date 2024-06-30T00:00:00Z --engine-profile conservative
+  technical-indicator-calculator backtest --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1d --export results.json
+  technical-indicator-calculator backtest --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h --recalculate
+  technical-indicator-calculator backtest --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h \\
+      --end-date 2024-12-31T00:00:00Z --no-cache
+  technical-indicator-calculator --output json backtest --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator backtest list --symbol BTCUSDT
+  technical-indicator-calculator backtest view 1201
+  technical-indicator-calculator backtest compare 1201 1202 1203
+  technical-indicator-calculator backtest batch --tag momentum --symbols BTCUSDT,ETHUSDT --intervals 1h,4h
+  technical-indicator-calculator backtest show-equity 1201 --chart";
+
+pub const BACKTEST_COMPARE: &str = "\
+Compare saved backtest results side by side
+
+Prints the key metrics of each result and tests whether its mean trade return differs
+from the first (baseline) result using Welch's t-test on the trades in
+strategy_backtest_trades. Results without at least two stored trades, e.g. archived
+ones, are shown without a p-value.
+
+Examples:
+  technical-indicator-calculator backtest compare 1201 1202
+  technical-indicator-calculator backtest compare 1201 1202 1203 --output comparison.csv
+  technical-indicator-calculator backtest compare 1201 1202 --output comparison.json";
+
+pub const BACKTEST_BATCH: &str = "\
+Backtest a set of strategies on several symbols and intervals and rank the results
+
+Strategies are selected with --tag (strategies with all the tags) and --strategy
+IDs. Every strategy runs on every symbol and interval; without --symbols or
+--intervals each strategy uses its own assets or timeframes. Watchlists such as
+@top50_volume, in --symbols or in the assets, are resolved to their current
+symbols (see `symbols --help`). Up to --concurrency
+backtests run at the same time and every result is saved to
+strategy_backtest_results, as with a single backtest.
+
+The summary ranks the results best first by --rank-by, one of the metrics of
+list --metric; the drawdown ranks lowest first. Failed backtests are listed
+after the ranking and don't stop the batch. With --output json or csv (given
+before the subcommand) one record per backtest is printed.
+
+Metrics (for --rank-by):
+  sharpe, sortino, calmar, return, annualized_return, excess_return, drawdown,
+  win_rate, profit_factor, expectancy, trades, exposure
+
+Examples:
+  technical-indicator-calculator backtest batch --tag momentum --symbols BTCUSDT,ETHUSDT --intervals 1h,4h
+  technical-indicator-calculator backtest batch --tag momentum --rank-by return --concurrency 8
+  technical-indicator-calculator backtest batch --tag momentum --symbols @top20_volume --intervals 4h
+  technical-indicator-calculator --output csv backtest batch --strategy 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbols BTCUSDT";
+
+pub const BACKTEST_LIST: &str = "\
+List saved backtest results, newest first
+
+Shows the headline metrics of each result in strategy_backtest_results; use
+`backtest view <id>` for all of them. Filters can be combined. With --output json
+or csv (given before the subcommand) the rows are printed for scripts.
+
+Examples:
+  technical-indicator-calculator backtest list
+  technical-indicator-calculator backtest list --strategy 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --limit 50
+  technical-indicator-calculator backtest list --symbol BTCUSDT --interval 1h --since 2025-01-01T00:00:00Z
+  technical-indicator-calculator --output csv backtest list --symbol ETHUSDT > results.csv";
+
+pub const BACKTEST_VIEW: &str = "\
+Show all metrics of a saved backtest result
+
+Prints the period, capital, engine profile and seed of the run with every stored
+metric. With --output json (given before the subcommand) the stored row is
+printed as is.
+
+Examples:
+  technical-indicator-calculator backtest view 1201
+  technical-indicator-calculator --output json backtest view 1201";
+
+pub const BACKTEST_SHOW_EQUITY: &str = "\
+Show the equity curve of a saved backtest result
+
+The curve starts at the initial capital on the start date and adds the profit or
+loss of each trade in strategy_backtest_trades at its exit time. Without --chart
+the points are listed with a sparkline of the curve; --chart draws it in the
+terminal, sized to $COLUMNS. Archived results no longer have their trades and
+only show the initial capital.
+
+Examples:
+  technical-indicator-calculator backtest show-equity 1201
+  technical-indicator-calculator backtest show-equity 1201 --chart --height 30";
+
+pub const OPTIMIZE: &str = "\
+Optimize a strategy using the LLM
+
+Each iteration sends the strategy's parameters, indicators and risk management
+with the backtest metrics so far to the LLM configured in [optimizer] (any
+OpenAI-compatible chat completions endpoint; a local Ollama by default). Proposed
+values outside a parameter's type, min/max or options are rejected, the changed
+strategy is validated and backtested with indicators calculated from candles, and
+it is kept when its expectancy is higher. The best configuration is saved to the
+strategy unless --dry-run is given; the report lists every iteration.
+
+Examples:
+  technical-indicator-calculator optimize --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator optimize --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 4h \\
+      --max-iterations 20 --output report.md";
+
+pub const TUNE: &str = "\
+Search strategy parameters with a genetic algorithm
+
+Examples:
+  technical-indicator-calculator tune run --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator tune show 12";
+
+pub const TUNE_RUN: &str = "\
+Run a genetic search over a strategy's parameters
+
+Integer and float parameters are searched within their min and max (floats on
+their step), booleans and string parameters with options over their values. The
+first generation holds the strategy's current values and random candidates; each
+next one keeps the --elite best and breeds the rest from tournament-selected
+parents with crossover and mutation. The search stops after --generations, or
+when the best fitness hasn't improved for --patience generations.
+
+Every candidate is backtested once, its result saved to strategy_backtest_results
+and the candidate stored in optimization_candidates with its fitness and backtest
+ID. Candidates with fewer than --min-trades trades get no fitness. The search
+fails after the first generation when all its candidates have the same fitness,
+since the parameters then make no difference to the backtest. The strategy
+is only changed with --apply. Without --seed a seed is drawn; it is printed and
+stored with the run and its backtests, so passing it again repeats the search.
+
+Overfitting guards: the last --holdout share of the period is held out. The
+search only sees in-sample results; every candidate is also backtested on the
+hold-out and its degradation (how many percent the metric drops out of sample)
+stored. The --stability-top best candidates are re-run with each parameter one
+step up and down; their stability score is 1 / (1 + mean relative change of the
+metric), 1 meaning no change. Candidates degrading more than --max-degradation
+or scoring below --min-stability are flagged as likely overfit, and the best
+unflagged candidate is the result. A warning is printed when every candidate has
+the same out-of-sample fitness or no neighbour moves the metric at all, since the
+guards then pass every candidate without telling them apart.
+
+Examples:
+  technical-indicator-calculator tune run --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator tune run --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --symbol BTCUSDT --interval 4h \\
+      --objective sharpe-ratio --population 40 --generations 25 --seed 7 --apply";
+
+pub const TUNE_SHOW: &str = "\
+Show the best candidates of a search run
+
+Examples:
+  technical-indicator-calculator tune show 12
+  technical-indicator-calculator tune show 12 --limit 50";
+
+pub const REEVALUATE: &str = "\
+Re-run backtests of enabled strategies over a recent window
+
+With reevaluation.enabled the worker does this every reevaluation.interval_hours.
+Every enabled strategy is backtested on each of its assets and timeframes over
+the last reevaluation.window_days days; the backtests are saved and recorded in
+strategy_reevaluations. A window with at least reevaluation.min_trades trades is
+degraded when its return, drawdown, win rate or profit factor breaches the
+configured threshold. The notification webhook is told when a strategy becomes
+degraded on a symbol/interval and when it recovers.
+
+Examples:
+  technical-indicator-calculator reevaluate run
+  technical-indicator-calculator reevaluate show --degraded";
+
+pub const REEVALUATE_RUN: &str = "\
+Re-evaluate enabled strategies now
+
+Uses the [reevaluation] settings, whether or not the worker schedule is enabled.
+
+Examples:
+  technical-indicator-calculator reevaluate run
+  technical-indicator-calculator reevaluate run --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --window-days 7";
+
+pub const REEVALUATE_SHOW: &str = "\
+Show the latest re-evaluations
+
+Examples:
+  technical-indicator-calculator reevaluate show
+  technical-indicator-calculator reevaluate show --strategy-id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --limit 50";
+
+pub const EVENTS: &str = "\
+Show the event log
+
+The worker, the CLI and the gRPC and Python interfaces record what they do in the
+events table: worker start and stop, job started/finished/failed, data quality
+issues, suspicious indicator values, retention and re-evaluation runs,
+configuration changes, strategy imports, saved backtests and delivered signals.
+The worker drops events older than retention.event_keep_days.
+
+Kinds:
+  worker_started, worker_stopped, job_started, job_finished, job_failed,
+  data_quality, indicator_anomaly, maintenance, config_changed, strategy_imported,
+  backtest_run, signal_generated
+
+Examples:
+  technical-indicator-calculator events tail --follow
+  technical-indicator-calculator events tail --kind job_failed,data_quality -n 50";
+
+pub const EVENTS_TAIL: &str = "\
+Show the latest events, optionally following new ones
+
+Past events are printed oldest first. With --follow the events table is polled
+for new events until Ctrl+C.
+
+Examples:
+  technical-indicator-calculator events tail
+  technical-indicator-calculator events tail --follow --kind backtest_run,signal_generated
+  technical-indicator-calculator events tail --subject BTCUSDT:1h:RSI --json";
+
+pub const INDICATORS: &str = "\
+List supported indicators and their parameters and outputs
+
+Covers the TA-Lib functions, the indicators implemented in Rust and registered
+plugin indicators. Any indicator also accepts a \"transform\" block in its
+parameters to normalize its output (zscore, percentile or minmax).
+
+Examples:
+  technical-indicator-calculator indicators list
+  technical-indicator-calculator indicators list --category pattern
+  technical-indicator-calculator indicators describe MACD";
+
+pub const INDICATORS_LIST: &str = "\
+List supported indicators with their category, inputs and outputs
+
+Categories:
+  momentum, overlap, volatility, volume, pattern, statistics, cross_symbol,
+  order_flow, plugin
+
+Examples:
+  technical-indicator-calculator indicators list
+  technical-indicator-calculator indicators list --category volume
+  technical-indicator-calculator indicators list --json";
+
+pub const INDICATORS_DESCRIBE: &str = "\
+Show the inputs, parameters and outputs of an indicator
+
+Parameters are listed with their type, default and accepted range; they go in
+the parameters object of indicator_config rows and strategy indicators.
+Outputs are the properties a strategy references as indicator.property;
+indicators without outputs produce a single value. Aliases such as ENGULFING
+are accepted.
+
+Examples:
+  technical-indicator-calculator indicators describe RSI
+  technical-indicator-calculator indicators describe bbands
+  technical-indicator-calculator indicators describe CHART_PATTERNS --json";
+
+pub const VERIFY: &str = "\
+Check calculations against stored reference outputs
+
+Examples:
+  technical-indicator-calculator verify indicators
+  technical-indicator-calculator verify indicators --indicator RSI";
+
+pub const VERIFY_INDICATORS: &str = "\
+Compare every built-in indicator on the reference candles with its golden outputs
+
+Calculates each built-in indicator with its default parameters on the reference
+dataset bundled in golden/ (500 hourly candles of REFAUSDT, with REFBUSDT as the
+other symbol of cross-symbol indicators) and compares every value with
+golden/indicators.json. Numbers may differ by the tolerance relative to the
+golden value (absolute below 1), 1e-8 unless the case stores its own. Any
+other difference, or a missing or extra value, fails the indicator and the
+command exits with an error. Indicators without golden outputs are listed as
+missing.
+
+No database is needed. After an intended change to a calculation, --update
+rewrites golden/indicators.json in the source tree from the current build;
+review the diff before committing it and rebuild to check against it.
+
+Examples:
+  technical-indicator-calculator verify indicators
+  technical-indicator-calculator verify indicators --indicator BBANDS --tolerance 1e-6
+  technical-indicator-calculator verify indicators --indicator ZIGZAG --update";
+
+pub const STRATEGY: &str = "\
+Create and edit strategies
+
+Examples:
+  technical-indicator-calculator strategy new --template rsi-reversal --output rsi.json
+  technical-indicator-calculator strategy validate --file rsi.json
+  technical-indicator-calculator strategy clone 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --set oversold=25
+  technical-indicator-calculator strategy export-bundle 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --out rsi.tar.gz
+  technical-indicator-calculator strategy tag add 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c momentum";
+
+pub const STRATEGY_NEW: &str = "\
+Generate a new strategy from a built-in template
+
+Without --output or --import the generated strategy JSON is printed.
+
+Examples:
+  technical-indicator-calculator strategy new --template rsi-reversal
+  technical-indicator-calculator strategy new --template macd-trend --name \"MACD 4h\" --output macd.json
+  technical-indicator-calculator strategy new --template bbands-breakout --import";
+
+pub const STRATEGY_VALIDATE: &str = "\
+Validate a strategy file without importing it
+
+Exits with 0 when the strategy is valid, 1 when validation fails and 2 when the
+file cannot be read or parsed. The database coverage checks need DB_HOST and the
+other database variables; use --offline when no database is available.
+
+Examples:
+  technical-indicator-calculator strategy validate --file strategy.json
+  technical-indicator-calculator strategy validate --file strategy.json --strict --offline";
+
+pub const STRATEGY_CONVERT: &str = "\
+Convert a strategy file between JSON, YAML and TOML
+
+Formats follow the file extensions (.yaml/.yml, .toml, otherwise JSON). The
+strategy is not validated or stored. TOML has no null value, so strategies with
+null indicator parameters can't be written as TOML.
+
+Examples:
+  technical-indicator-calculator strategy convert --input rsi.json --output rsi.yaml
+  technical-indicator-calculator strategy convert --input rsi.yaml --output rsi.toml";
+
+pub const STRATEGY_ADD_INDICATOR: &str = "\
+Add an indicator to a stored strategy
+
+Examples:
+  technical-indicator-calculator strategy add-indicator --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c \\
+      --indicator-id atr --indicator-type volatility --indicator-name ATR --parameters '{\"period\": 14}'";
+
+pub const STRATEGY_REMOVE_INDICATOR: &str = "\
+Remove an indicator from a stored strategy
+
+Examples:
+  technical-indicator-calculator strategy remove-indicator --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --indicator-id atr";
+
+pub const STRATEGY_ADD_RULE: &str = "\
+Add a rule to a stored strategy from a JSON file
+
+Examples:
+  technical-indicator-calculator strategy add-rule --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --file rule.json";
+
+pub const STRATEGY_REMOVE_RULE: &str = "\
+Remove a rule from a stored strategy
+
+Examples:
+  technical-indicator-calculator strategy remove-rule --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --rule-id exit_long";
+
+pub const STRATEGY_CLONE: &str = "\
+Copy a strategy under a new ID, optionally overriding parameters
+
+Examples:
+  technical-indicator-calculator strategy clone 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c
+  technical-indicator-calculator strategy clone 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --name \"RSI 25/75\" \\
+      --set oversold=25 --set overbought=75";
+
+pub const STRATEGY_SET_PARAM: &str = "\
+Set the value of a strategy parameter
+
+Examples:
+  technical-indicator-calculator strategy set-param --id 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --name oversold --value 25";
+
+pub const STRATEGY_EXPORT_BUNDLE: &str = "\
+Package a strategy with its backtests and indicator configurations
+
+The bundle is a .tar.gz with manifest.json, strategy.json, indicator_configs.json
+(the configurations its indicators are calculated from, including pipeline inputs),
+backtests.jsonl (the most recent results with their trades) and equity/<id>.csv
+(the equity curve of each result, derived from its trades). Archived results are
+included without trades.
+
+Examples:
+  technical-indicator-calculator strategy export-bundle 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --out rsi.tar.gz
+  technical-indicator-calculator strategy export-bundle 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c --out rsi.tar.gz --backtests 50";
+
+pub const STRATEGY_IMPORT_BUNDLE: &str = "\
+Import a strategy bundle written by export-bundle
+
+The strategy is validated and saved under its own ID, replacing a stored strategy
+with that ID. Missing indicator configurations are created and disabled ones
+enabled. Backtest results get new IDs; results imported before (same strategy,
+symbol, interval and creation time) are skipped. Equity curves are not imported,
+they are derived from the trades.
+
+Examples:
+  technical-indicator-calculator strategy import-bundle rsi.tar.gz";
+
+pub const STRATEGY_TAG: &str = "\
+Group strategies into families with tags
+
+Tags are lowercase words stored with the strategy. Use list --tag to show a
+family, e.g. to backtest its strategies together.
+
+Examples:
+  technical-indicator-calculator strategy tag add 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c momentum trend
+  technical-indicator-calculator strategy tag remove 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c trend
+  technical-indicator-calculator strategy tag list
+  technical-indicator-calculator list --tag momentum";
+
+pub const STRATEGY_TAG_ADD: &str = "\
+Add tags to a strategy
+
+Tags are lowercased; they can't contain whitespace or commas. Tags the strategy
+already has are ignored.
+
+Examples:
+  technical-indicator-calculator strategy tag add 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c momentum
+  technical-indicator-calculator strategy tag add 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c mean-reversion crypto";
+
+pub const STRATEGY_TAG_REMOVE: &str = "\
+Remove tags from a strategy
+
+Fails when the strategy has none of the given tags.
+
+Examples:
+  technical-indicator-calculator strategy tag remove 3f2a9c1e-4b7d-4e8a-9f0c-1d2e3f4a5b6c momentum";
+
+pub const STRATEGY_TAG_LIST: &str = "\
+List the tags in use with their number of strategies
+
+Only strategies of the current namespace are counted.
+
+Examples:
+  technical-indicator-calculator strategy tag list";
+
+pub const ENGINE: &str = "\
+Manage backtest engine settings profiles
+
+The default, conservative and optimistic profiles are always available.
+
+Examples:
+  technical-indicator-calculator engine list
+  technical-indicator-calculator engine show --name conservative
+  technical-indicator-calculator engine save --file my-profile.json";
+
+pub const ENGINE_LIST: &str = "\
+List available engine settings profiles
+
+Examples:
+  technical-indicator-calculator engine list";
+
+pub const ENGINE_SHOW: &str = "\
+Show an engine settings profile
+
+Examples:
+  technical-indicator-calculator engine show --name default
+  technical-indicator-calculator engine show --name conservative > my-profile.json";
+
+pub const ENGINE_SAVE: &str = "\
+Save an engine settings profile from a JSON file
+
+A profile with the same name is replaced. Start from an existing profile with
+`engine show` and edit it. slippage_jitter_percent (default 0) varies the slippage of
+every fill randomly by up to that many percentage points, reproducibly per --seed.
+
+Examples:
+  technical-indicator-calculator engine save --file my-profile.json";
+
+pub const ARCHIVE: &str = "\
+Archive old backtest results to object storage
+
+Environment:
+  ARCHIVE_BUCKET      S3 bucket; configure access with the standard AWS_* variables
+                      (AWS_ENDPOINT for S3-compatible stores such as MinIO)
+  ARCHIVE_PREFIX      Key prefix for archive files (default backtests)
+  ARCHIVE_LOCAL_DIR   Local directory used when ARCHIVE_BUCKET is not set (default ./archive)
+
+Examples:
+  technical-indicator-calculator archive run --older-than-days 180 --dry-run
+  technical-indicator-calculator archive run --older-than-days 90 --limit 5000
+  technical-indicator-calculator archive fetch --id 1234 --output backtest-1234.json";
+
+pub const ARCHIVE_RUN: &str = "\
+Move old backtest results to object storage, keeping summaries in the database
+
+Examples:
+  technical-indicator-calculator archive run --older-than-days 180 --dry-run
+  ARCHIVE_BUCKET=backtest-archive technical-indicator-calculator archive run --older-than-days 90";
+
+pub const ARCHIVE_FETCH: &str = "\
+Retrieve an archived backtest result
+
+Examples:
+  technical-indicator-calculator archive fetch --id 1234
+  technical-indicator-calculator archive fetch --id 1234 --output backtest-1234.json";
+
+pub const EXPORT: &str = "\
+Export a calculated indicator series to a file
+
+Rows are streamed from the database and written as they arrive, so multi-year
+1m series can be exported without loading them into memory. Multi-output
+indicators such as MACD get one CSV column per property.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator export --symbol BTCUSDT --interval 1h --indicator RSI \\
+      --parameters '{\"period\": 14}' --output rsi.csv
+  technical-indicator-calculator export --symbol BTCUSDT --interval 1m --indicator MACD \\
+      --parameters '{\"fast_period\": 12, \"slow_period\": 26, \"signal_period\": 9}' \\
+      --start-date 2020-01-01T00:00:00Z --format jsonl --output macd.jsonl.gz";
+
+pub const CONFIG: &str = "\
+Manage indicator configurations and application settings
+
+Examples:
+  technical-indicator-calculator config apply indicators.yaml --dry-run
+  technical-indicator-calculator config apply indicators.yaml
+  technical-indicator-calculator config show";
+
+pub const CONFIG_APPLY: &str = "\
+Reconcile indicator_config with a manifest file
+
+Creates configurations that are missing, re-enables disabled ones listed in the
+manifest and disables enabled configurations the manifest no longer contains.
+Calculated data of disabled configurations is kept.
+
+Manifest format:
+  sets:
+    - symbols: [BTCUSDT, ETHUSDT]
+      intervals: [1h, 4h, 1d]
+      indicators:
+        - name: RSI
+          type: oscillator
+          parameters:
+            - { period: 14 }
+            - { period: 21 }
+            - { period: 14, label: rsi14 }
+        - name: EMA
+          type: overlap
+          parameters:
+            - { period: 9, input: { label: rsi14 } }
+
+An indicator with an input is calculated from the output of the configuration
+with that label on the same symbol and interval (here an EMA of the RSI). Unknown
+labels and input cycles are rejected.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator config apply indicators.yaml --dry-run
+  technical-indicator-calculator config apply indicators.yaml";
+
+pub const CONFIG_SHOW: &str = "\
+Show the effective application settings
+
+Settings are read from the file named by CONFIG_FILE, or config.toml in the
+working directory if it exists. Environment variables override the file, so
+the output is exactly what the worker and CLI commands will use. The database
+password, exchange API secret and LLM API key are masked.
+
+Sections:
+  [database]        backend, sqlite_path, host, port, user, password, name,
+                    max_connections, acquire_timeout_seconds,
+                    idle_timeout_seconds, namespace
+  [redis]           url, cache_ttl_seconds
+  [worker]          concurrency, pools, batch_size, retry_max, retry_delay_ms,
+                    completeness_cache_minutes, candle_chunk_size, max_gap_ratio,
+                    scheduling_policy, cluster, cluster_lease_seconds,
+                    streaming, stream_url
+  [logging]         level, format, directory, rotation, file_prefix
+  [telemetry]       otlp_endpoint, service_name, sample_ratio, metrics_interval_seconds
+  [notifications]   signal_dedup_window_seconds, webhook_url, signal_poll_seconds,
+                    signal_lookback_days
+  [retention]       check_interval_hours, event_keep_days, [[retention.policies]]
+  [paper]           initial_capital, poll_interval_seconds
+  [execution]       enabled, rest_url, api_key, api_secret, recv_window_ms,
+                    max_order_notional, max_orders_per_day, allowed_symbols,
+                    status_poll_seconds, status_timeout_seconds
+  [risk]            max_daily_loss_percent, max_drawdown_percent, max_open_positions
+  [optimizer]       endpoint, model, api_key, temperature, timeout_seconds
+  [reevaluation]    enabled, interval_hours, window_days, initial_capital,
+                    engine_profile, min_trades, min_total_return_percent,
+                    max_drawdown_percent, min_win_rate_percent, min_profit_factor
+
+Examples:
+  technical-indicator-calculator config show
+  CONFIG_FILE=/etc/indicators/config.toml technical-indicator-calculator config show";
+
+pub const DB: &str = "\
+Database maintenance
+
+Examples:
+  technical-indicator-calculator db retention apply --dry-run
+  technical-indicator-calculator db retention apply";
+
+pub const DB_RETENTION: &str = "\
+Expire and downsample calculated indicator values
+
+Policies are configured in config.toml or INDICATOR_RETENTION and are applied
+by the worker every retention.check_interval_hours.
+
+Examples:
+  technical-indicator-calculator db retention apply --dry-run";
+
+pub const DB_RETENTION_APPLY: &str = "\
+Apply the configured retention policies now
+
+Each policy removes calculated_indicators values of one interval that are older
+than keep_days. With downsample_to, the last value of every bucket of that
+interval is first copied to indicator_downsamples, e.g. hourly values of 1m
+indicators. The '*' policy covers every interval without a policy of its own;
+when TimescaleDB is installed and every other policy expires values sooner it
+is registered as a TimescaleDB retention job that drops whole chunks.
+
+Configuration (config.toml):
+  [[retention.policies]]
+  interval = \"1m\"
+  keep_days = 30
+  downsample_to = \"1h\"
+
+Environment:
+  INDICATOR_RETENTION     Policies as interval:keep_days[:downsample_to] separated
+                          by ';' (e.g. 1m:30:1h;5m:90;*:365), replacing config.toml
+  RETENTION_CHECK_HOURS   Hours between retention runs in the worker (default: 24)
+  EVENT_KEEP_DAYS         Days of events kept by the worker (default: 90, 0 keeps all)
+
+Examples:
+  technical-indicator-calculator db retention apply --dry-run
+  INDICATOR_RETENTION='1m:30:1h;*:365' technical-indicator-calculator db retention apply";
+
+pub const DATA: &str = "\
+Check stored candle data
+
+Examples:
+  technical-indicator-calculator data audit
+  technical-indicator-calculator data audit --symbol BTCUSDT --interval 1h --backfill
+  technical-indicator-calculator data import BTCUSDT-1h.csv --symbol BTCUSDT --interval 1h
+  technical-indicator-calculator data funding --symbol BTCUSDT
+  technical-indicator-calculator data adjustments list";
+
+pub const SYMBOLS: &str = "\
+Manage symbol metadata and the watchlists built from it
+
+The symbols table holds the base and quote asset, tick size, listing date,
+active flag and trading session of each symbol, next to its contract
+specification. Strategy assets
+starting with @ name a watchlist instead of a symbol; watchlists are resolved
+from the active symbols each time a strategy is backtested in a batch,
+re-evaluated, validated or provisioned:
+  @top<N>_volume    the N symbols with the highest 24h quote volume
+  @quote_<asset>    every symbol quoted in the asset, e.g. @quote_usdt
+
+Examples:
+  technical-indicator-calculator symbols import-from-exchange --quote USDT
+  technical-indicator-calculator symbols list --quote USDT
+  technical-indicator-calculator symbols list --watchlist top50_volume
+  technical-indicator-calculator symbols add BTCUSDT --base BTC --quote USDT --tick-size 0.01 --listing-date 2017-08-17T00:00:00Z";
+
+pub const SYMBOLS_ADD: &str = "\
+Add a symbol to the symbols table or update its metadata
+
+Options that are not given keep their stored value; the symbol is marked active
+unless --inactive is given. Contract settings (contract_type, apply_funding,
+...) are not changed.
+
+--session sets the trading hours that daily boundaries follow: session-anchored
+indicators (ANCHORED_VWAP, VOLUME_PROFILE and CUMULATIVE_DELTA with \"session\":
+\"day\") reset at the start of each trading day, and the time_of_day,
+day_of_week and in_session rule values use the session's timezone. Symbols
+without a session trade 24/7 on UTC days. A session is a preset or
+\"<timezone> <open>-<close> <days>\"; one that closes at or before its open runs
+overnight and belongs to the trading day it closes on.
+
+Presets:
+  crypto   UTC 00:00-00:00 mon-sun (the default)
+  nyse     America/New_York 09:30-16:00 mon-fri
+  lse      Europe/London 08:00-16:30 mon-fri
+  xetra    Europe/Berlin 09:00-17:30 mon-fri
+  cme      America/Chicago 17:00-16:00 mon-fri
+
+Setting a session deletes the symbol's calculated values of session-anchored
+indicators; the running service recalculates them on the new boundaries.
+
+Examples:
+  technical-indicator-calculator symbols add BTCUSDT --base BTC --quote USDT --tick-size 0.01
+  technical-indicator-calculator symbols add SPY --base SPY --quote USD --session nyse
+  technical-indicator-calculator symbols add ES --session \"America/Chicago 17:00-16:00 mon-fri\"
+  technical-indicator-calculator symbols add LUNAUSDT --inactive";
+
+pub const SYMBOLS_LIST: &str = "\
+List stored symbols by descending 24h quote volume
+
+Only active symbols are listed unless --all is given. With --watchlist the
+symbols the watchlist currently resolves to are listed, in the order backtests
+use them. With --output json or csv (given before the subcommand) the rows are
+printed for scripts.
+
+Examples:
+  technical-indicator-calculator symbols list
+  technical-indicator-calculator symbols list --quote BTC --all
+  technical-indicator-calculator symbols list --watchlist top20_volume
+  technical-indicator-calculator --output json symbols list";
+
+pub const SYMBOLS_IMPORT_FROM_EXCHANGE: &str = "\
+Download symbol metadata and 24h volumes from Binance into the symbols table
+
+Base and quote asset, tick size and trading status come from exchangeInfo, the
+24h quote volume that @top<N>_volume watchlists rank by from the 24h ticker.
+Symbols that stopped trading are marked inactive. Binance doesn't publish
+listing dates of spot symbols; set them with `symbols add --listing-date`. Run
+the import regularly to keep volume watchlists current.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  BINANCE_API_URL                                   REST base URL (default: https://api.binance.com)
+
+Examples:
+  technical-indicator-calculator symbols import-from-exchange
+  technical-indicator-calculator symbols import-from-exchange --quote USDT";
+
+pub const DATA_IMPORT: &str = "\
+Import candles from a CSV file into the configured database
+
+Columns follow the Binance kline export (data.binance.vision):
+  open_time, open, high, low, close, volume, close_time, quote_asset_volume,
+  number_of_trades, taker_buy_base_volume
+Only the first six are required; further columns are ignored. Times are epoch
+milliseconds or RFC 3339. A header row is skipped. Candles that already exist
+are replaced.
+
+With DB_BACKEND=sqlite (or backend = \"sqlite\" in config.toml) candles go to a
+local SQLite file, so strategies can be backtested without PostgreSQL. SQLite
+support requires building with --features sqlite.
+
+Environment:
+  DB_BACKEND       postgres (default) or sqlite
+  SQLITE_PATH      SQLite database file (default: indicators.db)
+
+Examples:
+  technical-indicator-calculator data import BTCUSDT-1h.csv --symbol BTCUSDT --interval 1h
+  DB_BACKEND=sqlite technical-indicator-calculator data import ETHUSDT-1d.csv -s ETHUSDT -i 1d";
+
+pub const DATA_FUNDING: &str = "\
+Download funding rates of a perpetual futures symbol into funding_rates
+
+Rates come from the Binance USD-M futures API. Without --start-date the download
+continues after the latest stored rate, or starts at the first listed rate.
+
+Backtests charge funding to open positions only for symbols with apply_funding
+set in the symbols table: longs pay positive rates and shorts receive them. A
+short_borrow_rate (annual percent) in the same table is charged to shorts pro
+rata, with or without funding:
+  UPDATE symbols SET apply_funding = TRUE, short_borrow_rate = 5.0 WHERE symbol = 'BTCUSDT';
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  BINANCE_FUTURES_API_URL                           REST base URL (default: https://fapi.binance.com)
+
+Examples:
+  technical-indicator-calculator data funding --symbol BTCUSDT
+  technical-indicator-calculator data funding --symbol ETHUSDT --start-date 2023-01-01T00:00:00Z";
+
+pub const DATA_ADJUSTMENTS: &str = "\
+Manage redenominations and splits that candles are back-adjusted for
+
+Stored candles keep the prices the exchange reported. Whenever candles are
+loaded for indicator calculation or backtests, prices before each
+adjustment of the symbol are divided by its ratio and base volumes multiplied by
+it, so the series continues on the new scale instead of jumping. Adding or
+removing an adjustment deletes the symbol's calculated indicator values; the
+running service recalculates them from the adjusted candles.
+
+Examples:
+  technical-indicator-calculator data adjustments add --symbol LUNAUSDT --effective-time 2022-05-28T00:00:00Z --ratio 0.001
+  technical-indicator-calculator data adjustments list --symbol LUNAUSDT
+  technical-indicator-calculator data adjustments remove 3";
+
+pub const DATA_ADJUSTMENTS_ADD: &str = "\
+Record a redenomination or split of a symbol
+
+--ratio is the number of new units per old unit: 2 for a 2:1 split (prices
+halve), 0.1 for a 1:10 reverse split and 0.001 when 1000 old tokens become one.
+Candles opening before --effective-time are adjusted. The kind is informational.
+
+Examples:
+  technical-indicator-calculator data adjustments add --symbol AAPL --effective-time 2020-08-31T00:00:00Z --ratio 4 --kind split
+  technical-indicator-calculator data adjustments add --symbol LUNAUSDT --effective-time 2022-05-28T00:00:00Z --ratio 0.001 --note \"Terra 2.0\"";
+
+pub const DATA_ADJUSTMENTS_LIST: &str = "\
+List recorded adjustments with the cumulative factor applied to older candles
+
+Examples:
+  technical-indicator-calculator data adjustments list
+  technical-indicator-calculator data adjustments list --symbol LUNAUSDT";
+
+pub const DATA_ADJUSTMENTS_REMOVE: &str = "\
+Delete an adjustment; candles are loaded without it from then on
+
+The symbol's calculated indicator values are deleted so they are recalculated.
+
+Examples:
+  technical-indicator-calculator data adjustments remove 3";
+
+pub const DATA_AUDIT: &str = "\
+Scan binance_candles for gaps, duplicates and zero-volume anomalies
+
+Every symbol/interval with stored candles is audited unless --symbol or
+--interval narrow the scan. Gaps are runs of missing candles between two stored
+ones; monthly candles are not checked for gaps. With --backfill the missing
+candles are fetched from the Binance REST API and inserted.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  BINANCE_API_URL                                   REST base URL (default: https://api.binance.com)
+
+Examples:
+  technical-indicator-calculator data audit
+  technical-indicator-calculator data audit --symbol BTCUSDT
+  technical-indicator-calculator data audit --symbol BTCUSDT --interval 1h --backfill";
+
+pub const PAPER: &str = "\
+Trade live signals on a simulated account
+
+Examples:
+  technical-indicator-calculator paper run
+  technical-indicator-calculator paper positions
+  technical-indicator-calculator paper trades --limit 50
+  technical-indicator-calculator paper orders
+  technical-indicator-calculator paper equity --account aggressive";
+
+pub const PAPER_RUN: &str = "\
+Execute new signals from the signal ledger until interrupted
+
+Signals delivered to the sent_signals ledger by the worker (enabled with
+notifications.signal_poll_seconds) are polled and filled at the signal price.
+Entries open a position per strategy and symbol when none is open; exits,
+scale_in and scale_out change it. Entry sizes are percentages of the account
+equity, defaulting to the strategy's default_position_size. Each signal is
+executed at most once per account, so restarting is safe; signals sent while the
+executor was stopped are skipped unless --since covers them.
+
+After every poll the account is marked to the latest stored candle close and a
+snapshot is written to paper_equity. Trades go to paper_trades and open
+positions to paper_positions.
+
+With --route-orders every paper fill is also sent as a market order to the
+Binance spot testnet, using the keys and risk limits in the [execution] section
+of config.toml. Orders over max_order_notional, beyond max_orders_per_day or for
+symbols outside allowed_symbols are blocked. Every attempt is journaled to
+order_journal; see 'paper orders'.
+
+Signals are skipped while the risk guard has the account halted; see 'risk status'.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  PAPER_INITIAL_CAPITAL                             Starting balance (default: 10000)
+  PAPER_POLL_SECONDS                                Seconds between polls (default: 10)
+  EXECUTION_ENABLED                                 Allow --route-orders (default: false)
+  BINANCE_TESTNET_API_KEY, BINANCE_TESTNET_API_SECRET
+                                                    Testnet API keys
+  BINANCE_TESTNET_URL                               REST base URL (default: https://testnet.binance.vision)
+
+Examples:
+  technical-indicator-calculator paper run
+  technical-indicator-calculator paper run --account swing --since 2024-06-01T00:00:00Z
+  EXECUTION_ENABLED=true technical-indicator-calculator paper run --route-orders";
+
+pub const PAPER_ORDERS: &str = "\
+Show the order journal, newest first
+
+Lists every order attempt of 'paper run --route-orders': blocked by a risk limit,
+failed to submit, or sent with the latest status reported by the exchange.
+
+Examples:
+  technical-indicator-calculator paper orders
+  technical-indicator-calculator paper orders --limit 100";
+
+pub const PAPER_POSITIONS: &str = "\
+Show open positions of a paper account
+
+Unrealized P/L uses the latest stored candle close of each symbol.
+
+Examples:
+  technical-indicator-calculator paper positions
+  technical-indicator-calculator paper positions --account swing";
+
+pub const PAPER_TRADES: &str = "\
+Show the most recent fills of a paper account, newest first
+
+Examples:
+  technical-indicator-calculator paper trades
+  technical-indicator-calculator paper trades --account swing --limit 100";
+
+pub const PAPER_EQUITY: &str = "\
+Show the equity snapshots recorded by paper run, newest first, followed by the
+current value of the account
+
+Examples:
+  technical-indicator-calculator paper equity
+  technical-indicator-calculator paper equity --limit 200";
+
+pub const RISK: &str = "\
+Inspect and reset the trading kill switch
+
+Examples:
+  technical-indicator-calculator risk status
+  technical-indicator-calculator risk reset --account swing";
+
+pub const RISK_STATUS: &str = "\
+Show the risk limits of a paper account and whether trading is halted
+
+'paper run' checks the account before every signal. Signal execution, including
+orders routed to the exchange, halts when:
+  - equity falls max_daily_loss_percent below its value at the start of the UTC day
+  - equity falls max_drawdown_percent below its peak since the last reset
+  - more than max_open_positions positions are open
+A halt lasts until 'risk reset', even if the equity recovers. Halts and resets
+are logged and posted to the notification webhook, if configured.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  RISK_MAX_DAILY_LOSS_PERCENT                       Daily loss limit (default: 5)
+  RISK_MAX_DRAWDOWN_PERCENT                         Drawdown limit (default: 20)
+  RISK_MAX_OPEN_POSITIONS                           Open position limit (default: 10)
+  NOTIFY_WEBHOOK_URL                                Webhook for alerts, posted as {\"text\": ...}
+
+Examples:
+  technical-indicator-calculator risk status
+  technical-indicator-calculator risk status --account swing";
+
+pub const RISK_RESET: &str = "\
+Lift a halt and restart loss tracking from the current equity
+
+The daily start and peak equity are set to the current equity, so the limits
+apply afresh from now on. Open positions are kept.
+
+Examples:
+  technical-indicator-calculator risk reset
+  technical-indicator-calculator risk reset --account swing";
+
+pub const JOBS: &str = "\
+Inspect and requeue worker jobs
+
+Examples:
+  technical-indicator-calculator jobs failed
+  technical-indicator-calculator jobs requeue 12 15
+  technical-indicator-calculator jobs anomalies";
+
+pub const JOBS_FAILED: &str = "\
+List jobs that failed after all retries
+
+The worker retries a failed job RETRY_MAX times with exponential backoff
+starting at RETRY_DELAY_MS. Jobs that still fail are recorded in the
+failed_jobs table with their last error and are not scheduled again until
+requeued.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator jobs failed
+  technical-indicator-calculator jobs failed --limit 10";
+
+pub const JOBS_REQUEUE: &str = "\
+Requeue failed jobs so the worker calculates them again
+
+Removes the jobs from failed_jobs; a running worker picks them up on its next
+scheduling cycle.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator jobs requeue 12 15
+  technical-indicator-calculator jobs requeue --all";
+
+pub const JOBS_ANOMALIES: &str = "\
+List suspicious calculated values flagged by the worker
+
+After each job the worker checks the new values for NaN/Inf, for jumps to more
+than worker.anomaly_jump_factor times the largest magnitude of the previous 20
+values, and for worker.anomaly_constant_run identical values in a row while the
+close price moved. Such values usually mean corrupt candles or a broken
+calculation. Flagged values are still stored; each is recorded once in
+indicator_anomalies, newest first here, and announced as an indicator_anomaly
+event and, with worker.anomaly_notify, on the notification webhook.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator jobs anomalies
+  technical-indicator-calculator jobs anomalies --symbol BTCUSDT --indicator MACD --limit 10";
+
+pub const CALC: &str = "\
+Calculate indicators on demand
+
+Examples:
+  technical-indicator-calculator calc run --symbol BTCUSDT --interval 1h --indicator RSI --param period=14
+  technical-indicator-calculator calc backfill --symbol BTCUSDT --interval 1h --indicator RSI";
+
+pub const CALC_RUN: &str = "\
+Calculate an indicator from stored candles and print the series
+
+Nothing is written: indicator_config, calculated_indicators and the worker are
+left alone, so this is a quick way to inspect an indicator or try parameters
+before configuring them. The indicator is calculated over the full candle
+history, so values warm up exactly as in the worker, and the last --last values
+are printed. Parameter values are read as JSON when they parse (numbers, lists)
+and as strings otherwise. Cross-symbol indicators load the candles of
+other_symbol as well. `indicators describe <name>` lists the parameters.
+
+With --chart the close prices of the last --last candles are drawn in the
+terminal instead. Moving averages, bands and other series on the price scale
+are drawn over the prices; oscillators and other indicators get a panel of
+their own below them.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+
+Examples:
+  technical-indicator-calculator calc run --symbol BTCUSDT --interval 1h --indicator RSI --param period=14 --last 200
+  technical-indicator-calculator calc run --symbol BTCUSDT --interval 4h --indicator MACD --format csv > macd.csv
+  technical-indicator-calculator calc run --symbol BTCUSDT --interval 1h --indicator BBANDS --last 300 --chart
+  technical-indicator-calculator calc run --symbol ETHUSDT --interval 1d --indicator RATIO \\
+    --param other_symbol=BTCUSDT --format json";
+
+pub const CALC_BACKFILL: &str = "\
+Recalculate indicators for a symbol and time range, bypassing the completeness check
+
+Runs the calculation directly instead of waiting for the worker, which skips
+configurations it already considers complete. Use it after repairing candle
+data or changing an indicator implementation. Candles before --from are still
+loaded so the indicator warms up exactly as in a full run; only values from
+--from to --to are overwritten. Configurations whose candles fail the data
+quality checks are reported as failed and left unchanged.
+
+Environment:
+  DB_HOST, DB_PORT, DB_USER, DB_PASSWORD, DB_NAME   PostgreSQL connection
+  REDIS_URL                                         Redis connection (default redis://localhost:6379)
+
+Examples:
+  technical-indicator-calculator calc backfill --symbol BTCUSDT --interval 1h --indicator RSI
+  technical-indicator-calculator calc backfill --symbol BTCUSDT --interval 1h --indicator RSI \\
+    --from 2025-01-01T00:00:00Z --to 2025-02-01T00:00:00Z
+  technical-indicator-calculator calc backfill --symbol ETHUSDT --interval 4h \\
+    --indicator SMA --parameters '{\"period\": 50}'";
+
+pub const SERVE: &str = "\
+Serve indicator calculation, backtests and signals over gRPC
+
+Runs the Backtester service defined in proto/backtester.proto until Ctrl+C:
+CalculateIndicator calculates an indicator on stored or supplied candles,
+RunBacktest backtests a stored strategy or a strategy definition, and
+StreamSignals streams signals from the sent_signals ledger as they are sent; a
+worker with notifications.signal_poll_seconds > 0 must be running to generate them.
+Only available when built with the grpc feature.
+
+Examples:
+  technical-indicator-calculator serve
+  technical-indicator-calculator serve --listen 0.0.0.0:50051";
+
+pub const COMPLETIONS: &str = "\
+Generate shell completions
+
+Examples:
+  technical-indicator-calculator completions bash > /etc/bash_completion.d/technical-indicator-calculator
+  technical-indicator-calculator completions zsh > \"${fpath[1]}/_technical-indicator-calculator\"
+  technical-indicator-calculator completions fish > ~/.config/fish/completions/technical-indicator-calculator.fish";
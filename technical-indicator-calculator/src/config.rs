@@ -0,0 +1,895 @@
+// Application settings (config.toml) and declarative indicator configuration manifests
+use crate::database::models::IndicatorConfig;
+use crate::database::postgres::{PoolSettings, PostgresManager, DEFAULT_NAMESPACE};
+use crate::database::retention::{validate_policies, RetentionPolicy};
+use crate::events::{record_event, EventKind};
+use crate::indicators::pipeline::{self, PipelineNode};
+use anyhow::{Result, Context, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::info;
+
+/// Default settings file, read from the working directory when CONFIG_FILE is not set
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Application settings loaded from config.toml, with environment variables taking precedence
+///
+/// ```toml
+/// [database]
+/// host = "localhost"
+/// port = 5432
+///
+/// [worker]
+/// concurrency = 8
+/// pools = "fast:1m,5m,15m:4;slow:1d,1w:1"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub database: DatabaseSettings,
+    pub redis: RedisSettings,
+    pub worker: WorkerSettings,
+    pub logging: LoggingSettings,
+    pub telemetry: TelemetrySettings,
+    pub notifications: NotificationSettings,
+    pub retention: RetentionSettings,
+    pub paper: PaperSettings,
+    pub execution: ExecutionSettings,
+    pub risk: RiskSettings,
+    pub optimizer: OptimizerSettings,
+    pub reevaluation: ReevaluationSettings,
+}
+
+/// Database connection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    /// "postgres", or "sqlite" for local use (requires the sqlite feature)
+    pub backend: String,
+    /// Database file used by the sqlite backend
+    pub sqlite_path: String,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub name: String,
+    /// Connection pool size for CLI commands; the worker sizes its pool from its concurrency
+    pub max_connections: usize,
+    /// Seconds a query waits for a free pooled connection before failing
+    pub acquire_timeout_seconds: u64,
+    /// Seconds after which idle pooled connections are closed; 0 keeps them open
+    pub idle_timeout_seconds: u64,
+    /// Owner or environment of strategies, indicator configurations and backtest results,
+    /// so several users or environments can share one database
+    pub namespace: String,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            backend: "postgres".to_string(),
+            sqlite_path: "indicators.db".to_string(),
+            host: "localhost".to_string(),
+            port: 5432,
+            user: "binanceuser".to_string(),
+            password: "binancepass".to_string(),
+            name: "binancedb".to_string(),
+            max_connections: 10,
+            acquire_timeout_seconds: 30,
+            idle_timeout_seconds: 600,
+            namespace: DEFAULT_NAMESPACE.to_string(),
+        }
+    }
+}
+
+// Pool shared by the CLI commands run in this process
+static SHARED_POSTGRES: OnceCell<Arc<PostgresManager>> = OnceCell::const_new();
+
+impl DatabaseSettings {
+    /// Pool size and timeouts with the given number of connections
+    pub fn pool_settings(&self, max_connections: usize) -> PoolSettings {
+        PoolSettings {
+            max_connections,
+            acquire_timeout: Duration::from_secs(self.acquire_timeout_seconds),
+            idle_timeout: (self.idle_timeout_seconds > 0).then(|| Duration::from_secs(self.idle_timeout_seconds)),
+        }
+    }
+
+    /// Connect to PostgreSQL with the given pool size, scoped to the configured namespace
+    pub async fn connect(&self, max_connections: usize) -> Result<PostgresManager> {
+        let pg = PostgresManager::new(
+            &self.host,
+            self.port,
+            &self.user,
+            &self.password,
+            &self.name,
+            &self.pool_settings(max_connections),
+        )
+        .await?
+        .with_namespace(&self.namespace);
+
+        // Every query filters on the namespace, strategies are read with their tags and
+        // candles are back-adjusted and given their session when loaded, so these must
+        // exist before the first query
+        pg.init_namespace_columns().await?;
+        pg.init_strategy_tags_column().await?;
+        pg.init_price_adjustments_table().await?;
+        pg.init_symbols_table().await?;
+        Ok(pg)
+    }
+
+    /// Connection pool shared by every command of this process, opened with max_connections
+    /// on first use; later calls return it regardless of their settings
+    pub async fn shared(&self) -> Result<Arc<PostgresManager>> {
+        SHARED_POSTGRES
+            .get_or_try_init(|| async { self.connect(self.max_connections).await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+}
+
+/// Redis connection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedisSettings {
+    pub url: String,
+    pub cache_ttl_seconds: u64,
+}
+
+impl Default for RedisSettings {
+    fn default() -> Self {
+        Self {
+            url: "redis://localhost:6379".to_string(),
+            cache_ttl_seconds: 3600,
+        }
+    }
+}
+
+/// Indicator worker tuning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkerSettings {
+    /// Default pool concurrency; the number of CPUs when unset
+    pub concurrency: Option<usize>,
+    /// Per-interval pools as name:intervals:concurrency, separated by ';'
+    pub pools: Option<String>,
+    pub batch_size: usize,
+    pub retry_max: usize,
+    pub retry_delay_ms: u64,
+    pub completeness_cache_minutes: i64,
+    pub candle_chunk_size: usize,
+    pub max_gap_ratio: f64,
+    /// "staleness-first" or "round-robin"
+    pub scheduling_policy: String,
+    pub cluster: bool,
+    pub cluster_lease_seconds: u64,
+    pub streaming: bool,
+    pub stream_url: String,
+    /// Address to serve Prometheus metrics on; disabled when unset
+    pub metrics_addr: Option<String>,
+    /// Calculate all parameter sets of an indicator on a symbol/interval in one job
+    pub batch_variants: bool,
+    /// Candles held in memory and shared between jobs; 0 disables the cache
+    pub candle_cache_max_candles: u64,
+    /// NaN/Inf in indicator outputs: "null" stores JSON null, "skip" drops the row, "error" fails the job
+    pub nan_policy: String,
+    /// Flag values whose magnitude exceeds this multiple of the preceding values; 0 disables the check
+    pub anomaly_jump_factor: f64,
+    /// Flag this many identical consecutive values while the price moves; 0 disables the check
+    pub anomaly_constant_run: usize,
+    /// Also post flagged values to the notification webhook
+    pub anomaly_notify: bool,
+}
+
+impl Default for WorkerSettings {
+    fn default() -> Self {
+        Self {
+            concurrency: None,
+            pools: None,
+            batch_size: 1000,
+            retry_max: 3,
+            retry_delay_ms: 500,
+            completeness_cache_minutes: 30,
+            candle_chunk_size: 100_000,
+            max_gap_ratio: 0.05,
+            scheduling_policy: "staleness-first".to_string(),
+            cluster: false,
+            cluster_lease_seconds: 60,
+            streaming: false,
+            stream_url: "wss://stream.binance.com:9443".to_string(),
+            metrics_addr: None,
+            batch_variants: true,
+            candle_cache_max_candles: 2_000_000,
+            nan_policy: "null".to_string(),
+            anomaly_jump_factor: 10.0,
+            anomaly_constant_run: 200,
+            anomaly_notify: false,
+        }
+    }
+}
+
+/// Log output settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingSettings {
+    /// tracing filter directive, e.g. "info" or "technical_indicator_calculator=debug"
+    pub level: String,
+    /// Line format of stdout and log files: "text" or "json"
+    pub format: String,
+    /// Directory to also write rolling log files to; None logs to stdout only
+    pub directory: Option<String>,
+    /// When log files roll over: "minutely", "hourly", "daily" or "never"
+    pub rotation: String,
+    /// Log file name, suffixed with the date and time of each rollover
+    pub file_prefix: String,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: "text".to_string(),
+            directory: None,
+            rotation: "daily".to_string(),
+            file_prefix: "technical-indicator-calculator.log".to_string(),
+        }
+    }
+}
+
+/// OpenTelemetry export, used when built with the "otel" feature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetrySettings {
+    /// OTLP/gRPC collector receiving spans and metrics, e.g. "http://localhost:4317"; None disables export
+    pub otlp_endpoint: Option<String>,
+    /// service.name of the exported spans and metrics
+    pub service_name: String,
+    /// Share of traces exported, from 0.0 to 1.0
+    pub sample_ratio: f64,
+    /// Time between metric exports
+    pub metrics_interval_seconds: u64,
+}
+
+impl Default for TelemetrySettings {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "technical-indicator-calculator".to_string(),
+            sample_ratio: 1.0,
+            metrics_interval_seconds: 60,
+        }
+    }
+}
+
+/// Signal notification settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationSettings {
+    /// Window in which the same signal is never sent twice
+    pub signal_dedup_window_seconds: u64,
+    /// Webhook receiving signals and risk alerts as {"text": "..."}
+    pub webhook_url: Option<String>,
+    /// How often the worker evaluates enabled strategies for new signals; 0 disables it
+    pub signal_poll_seconds: u64,
+    /// Days of candles simulated before the evaluated candle to establish the open position
+    pub signal_lookback_days: u32,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            signal_dedup_window_seconds: 7 * 24 * 3600,
+            webhook_url: None,
+            signal_poll_seconds: 0,
+            signal_lookback_days: 30,
+        }
+    }
+}
+
+/// Retention of calculated indicator values
+///
+/// ```toml
+/// [[retention.policies]]
+/// interval = "1m"
+/// keep_days = 30
+/// downsample_to = "1h"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// How often the worker applies the policies
+    pub check_interval_hours: u64,
+    /// Days events are kept in the event log; 0 keeps them forever
+    pub event_keep_days: u64,
+    pub policies: Vec<RetentionPolicy>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            check_interval_hours: 24,
+            event_keep_days: 90,
+            policies: Vec::new(),
+        }
+    }
+}
+
+/// Paper trading of live signals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaperSettings {
+    /// Starting balance of every paper account
+    pub initial_capital: f64,
+    /// Seconds between polls of the signal ledger
+    pub poll_interval_seconds: u64,
+}
+
+impl Default for PaperSettings {
+    fn default() -> Self {
+        Self {
+            initial_capital: 10_000.0,
+            poll_interval_seconds: 10,
+        }
+    }
+}
+
+/// Routing of paper fills to the Binance spot testnet
+///
+/// ```toml
+/// [execution]
+/// enabled = true
+/// api_key = "..."
+/// api_secret = "..."
+/// max_order_notional = 500.0
+/// allowed_symbols = ["BTCUSDT", "ETHUSDT"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutionSettings {
+    /// Allow paper run --route-orders to send orders
+    pub enabled: bool,
+    pub rest_url: String,
+    pub api_key: String,
+    pub api_secret: String,
+    /// Milliseconds a signed request stays valid
+    pub recv_window_ms: u64,
+    /// Largest order value in quote currency
+    pub max_order_notional: f64,
+    /// Most orders sent in any 24 hours
+    pub max_orders_per_day: u32,
+    /// Symbols that may be traded; empty allows every symbol
+    pub allowed_symbols: Vec<String>,
+    /// Seconds between order status polls
+    pub status_poll_seconds: u64,
+    /// Stop polling an order that is still open after this many seconds
+    pub status_timeout_seconds: u64,
+}
+
+impl Default for ExecutionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rest_url: "https://testnet.binance.vision".to_string(),
+            api_key: String::new(),
+            api_secret: String::new(),
+            recv_window_ms: 5000,
+            max_order_notional: 1000.0,
+            max_orders_per_day: 50,
+            allowed_symbols: Vec::new(),
+            status_poll_seconds: 2,
+            status_timeout_seconds: 30,
+        }
+    }
+}
+
+/// Limits of the risk guard that halts paper and live trading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskSettings {
+    /// Halt when equity falls this many percent below the start of the UTC day
+    pub max_daily_loss_percent: f64,
+    /// Halt when equity falls this many percent below its peak
+    pub max_drawdown_percent: f64,
+    /// Halt when more positions than this are open
+    pub max_open_positions: u32,
+}
+
+impl Default for RiskSettings {
+    fn default() -> Self {
+        Self {
+            max_daily_loss_percent: 5.0,
+            max_drawdown_percent: 20.0,
+            max_open_positions: 10,
+        }
+    }
+}
+
+/// LLM used by the optimize command
+///
+/// Any OpenAI-compatible chat completions endpoint works, e.g. OpenAI itself or a local
+/// Ollama (the default).
+///
+/// ```toml
+/// [optimizer]
+/// endpoint = "https://api.openai.com/v1/chat/completions"
+/// model = "gpt-4o-mini"
+/// api_key = "..."
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OptimizerSettings {
+    pub endpoint: String,
+    pub model: String,
+    /// Sent as a bearer token when set
+    pub api_key: String,
+    pub temperature: f64,
+    /// Seconds to wait for one completion
+    pub timeout_seconds: u64,
+}
+
+impl Default for OptimizerSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:11434/v1/chat/completions".to_string(),
+            model: "llama3.2".to_string(),
+            api_key: String::new(),
+            temperature: 0.2,
+            timeout_seconds: 300,
+        }
+    }
+}
+
+/// Scheduled re-evaluation of enabled strategies by the worker
+///
+/// A window with fewer than `min_trades` trades is not judged; any other window
+/// breaching one of the thresholds marks the strategy as degraded on that symbol
+/// and interval.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReevaluationSettings {
+    pub enabled: bool,
+    /// Hours between re-evaluations
+    pub interval_hours: u64,
+    /// Days of recent candles each backtest covers
+    pub window_days: u32,
+    pub initial_capital: f64,
+    /// Engine settings profile used for fills and costs
+    pub engine_profile: String,
+    pub min_trades: i32,
+    pub min_total_return_percent: f64,
+    pub max_drawdown_percent: f64,
+    pub min_win_rate_percent: f64,
+    pub min_profit_factor: f64,
+}
+
+impl Default for ReevaluationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            window_days: 30,
+            initial_capital: 10_000.0,
+            engine_profile: "default".to_string(),
+            min_trades: 3,
+            min_total_return_percent: -10.0,
+            max_drawdown_percent: 20.0,
+            min_win_rate_percent: 0.0,
+            min_profit_factor: 0.8,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Load settings from CONFIG_FILE or ./config.toml if present, then apply environment overrides
+    pub fn load() -> Result<Self> {
+        let mut config = match Self::config_file_path() {
+            Some(path) => Self::from_file(&path)?,
+            None => Self::default(),
+        };
+
+        config.apply_env()?;
+        validate_namespace(&config.database.namespace)?;
+        validate_policies(&config.retention.policies)?;
+        Ok(config)
+    }
+
+    /// The settings file in use, if any
+    pub fn config_file_path() -> Option<PathBuf> {
+        match env::var("CONFIG_FILE") {
+            Ok(path) => Some(PathBuf::from(path)),
+            Err(_) => {
+                let path = PathBuf::from(DEFAULT_CONFIG_FILE);
+                path.exists().then_some(path)
+            }
+        }
+    }
+
+    /// Read settings from a TOML file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path.display()))?;
+
+        toml::from_str(&content)
+            .context(format!("Failed to parse config file: {}", path.display()))
+    }
+
+    // Environment variables override the file, using the names from before config.toml existed
+    fn apply_env(&mut self) -> Result<()> {
+        env_override("DB_BACKEND", &mut self.database.backend)?;
+        env_override("SQLITE_PATH", &mut self.database.sqlite_path)?;
+        env_override("DB_HOST", &mut self.database.host)?;
+        env_override("DB_PORT", &mut self.database.port)?;
+        env_override("DB_USER", &mut self.database.user)?;
+        env_override("DB_PASSWORD", &mut self.database.password)?;
+        env_override("DB_NAME", &mut self.database.name)?;
+        env_override("DB_NAMESPACE", &mut self.database.namespace)?;
+        env_override("DB_MAX_CONNECTIONS", &mut self.database.max_connections)?;
+        env_override("DB_ACQUIRE_TIMEOUT_SECONDS", &mut self.database.acquire_timeout_seconds)?;
+        env_override("DB_IDLE_TIMEOUT_SECONDS", &mut self.database.idle_timeout_seconds)?;
+
+        env_override("REDIS_URL", &mut self.redis.url)?;
+        env_override("CACHE_TTL_SECONDS", &mut self.redis.cache_ttl_seconds)?;
+
+        env_override_option("CONCURRENCY", &mut self.worker.concurrency)?;
+        env_override_option("WORKER_POOLS", &mut self.worker.pools)?;
+        env_override("RETRY_MAX", &mut self.worker.retry_max)?;
+        env_override("RETRY_DELAY_MS", &mut self.worker.retry_delay_ms)?;
+        env_override("COMPLETENESS_CACHE_MINUTES", &mut self.worker.completeness_cache_minutes)?;
+        env_override("CANDLE_CHUNK_SIZE", &mut self.worker.candle_chunk_size)?;
+        env_override("DATA_MAX_GAP_RATIO", &mut self.worker.max_gap_ratio)?;
+        env_override("SCHEDULING_POLICY", &mut self.worker.scheduling_policy)?;
+        env_flag("WORKER_CLUSTER", &mut self.worker.cluster);
+        env_override("CLUSTER_LEASE_SECONDS", &mut self.worker.cluster_lease_seconds)?;
+        env_flag("BINANCE_STREAMING", &mut self.worker.streaming);
+        env_override("BINANCE_WS_URL", &mut self.worker.stream_url)?;
+        env_override_option("METRICS_ADDR", &mut self.worker.metrics_addr)?;
+        env_flag("BATCH_VARIANTS", &mut self.worker.batch_variants);
+        env_override("CANDLE_CACHE_MAX_CANDLES", &mut self.worker.candle_cache_max_candles)?;
+        env_override("INDICATOR_NAN_POLICY", &mut self.worker.nan_policy)?;
+        env_override("ANOMALY_JUMP_FACTOR", &mut self.worker.anomaly_jump_factor)?;
+        env_override("ANOMALY_CONSTANT_RUN", &mut self.worker.anomaly_constant_run)?;
+        env_flag("ANOMALY_NOTIFY", &mut self.worker.anomaly_notify);
+
+        env_override("RUST_LOG", &mut self.logging.level)?;
+        env_override("LOG_FORMAT", &mut self.logging.format)?;
+        env_override_option("LOG_DIR", &mut self.logging.directory)?;
+        env_override("LOG_ROTATION", &mut self.logging.rotation)?;
+
+        env_override_option("OTEL_EXPORTER_OTLP_ENDPOINT", &mut self.telemetry.otlp_endpoint)?;
+        env_override("OTEL_SERVICE_NAME", &mut self.telemetry.service_name)?;
+        env_override("OTEL_TRACES_SAMPLER_ARG", &mut self.telemetry.sample_ratio)?;
+
+        env_override("SIGNAL_DEDUP_WINDOW_SECONDS", &mut self.notifications.signal_dedup_window_seconds)?;
+        env_override_option("NOTIFY_WEBHOOK_URL", &mut self.notifications.webhook_url)?;
+        env_override("SIGNAL_POLL_SECONDS", &mut self.notifications.signal_poll_seconds)?;
+        env_override("SIGNAL_LOOKBACK_DAYS", &mut self.notifications.signal_lookback_days)?;
+
+        env_override("PAPER_INITIAL_CAPITAL", &mut self.paper.initial_capital)?;
+        env_override("PAPER_POLL_SECONDS", &mut self.paper.poll_interval_seconds)?;
+
+        env_flag("EXECUTION_ENABLED", &mut self.execution.enabled);
+        env_override("BINANCE_TESTNET_URL", &mut self.execution.rest_url)?;
+        env_override("BINANCE_TESTNET_API_KEY", &mut self.execution.api_key)?;
+        env_override("BINANCE_TESTNET_API_SECRET", &mut self.execution.api_secret)?;
+        env_override("EXECUTION_MAX_ORDER_NOTIONAL", &mut self.execution.max_order_notional)?;
+        env_override("EXECUTION_MAX_ORDERS_PER_DAY", &mut self.execution.max_orders_per_day)?;
+
+        env_override("RISK_MAX_DAILY_LOSS_PERCENT", &mut self.risk.max_daily_loss_percent)?;
+        env_override("RISK_MAX_DRAWDOWN_PERCENT", &mut self.risk.max_drawdown_percent)?;
+        env_override("RISK_MAX_OPEN_POSITIONS", &mut self.risk.max_open_positions)?;
+
+        env_override("LLM_ENDPOINT", &mut self.optimizer.endpoint)?;
+        env_override("LLM_MODEL", &mut self.optimizer.model)?;
+        env_override("LLM_API_KEY", &mut self.optimizer.api_key)?;
+
+        env_flag("REEVALUATION_ENABLED", &mut self.reevaluation.enabled);
+        env_override("REEVALUATION_INTERVAL_HOURS", &mut self.reevaluation.interval_hours)?;
+        env_override("REEVALUATION_WINDOW_DAYS", &mut self.reevaluation.window_days)?;
+
+        env_override("RETENTION_CHECK_HOURS", &mut self.retention.check_interval_hours)?;
+        env_override("EVENT_KEEP_DAYS", &mut self.retention.event_keep_days)?;
+        if let Ok(spec) = env::var("INDICATOR_RETENTION") {
+            self.retention.policies = RetentionPolicy::parse_list(&spec)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render the effective settings as TOML with the database password and API secrets masked
+    pub fn to_masked_toml(&self) -> Result<String> {
+        let mut masked = self.clone();
+        if !masked.database.password.is_empty() {
+            masked.database.password = "********".to_string();
+        }
+        if !masked.execution.api_secret.is_empty() {
+            masked.execution.api_secret = "********".to_string();
+        }
+        if !masked.optimizer.api_key.is_empty() {
+            masked.optimizer.api_key = "********".to_string();
+        }
+
+        Ok(toml::to_string_pretty(&masked)?)
+    }
+}
+
+// Namespaces are stored in every row and shown in listings, so keep them short identifiers
+fn validate_namespace(namespace: &str) -> Result<()> {
+    let valid = !namespace.is_empty()
+        && namespace.len() <= 64
+        && namespace.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid namespace '{}', expected up to 64 letters, digits, '-' or '_'", namespace))
+    }
+}
+
+// Replace a setting with a parsed environment variable, if set
+fn env_override<T: FromStr>(name: &str, target: &mut T) -> Result<()>
+where
+    T::Err: std::fmt::Display,
+{
+    if let Ok(value) = env::var(name) {
+        *target = value.parse()
+            .map_err(|e| anyhow!("Invalid value for {} '{}': {}", name, value, e))?;
+    }
+
+    Ok(())
+}
+
+// Same as env_override for optional settings
+fn env_override_option<T: FromStr>(name: &str, target: &mut Option<T>) -> Result<()>
+where
+    T::Err: std::fmt::Display,
+{
+    if let Ok(value) = env::var(name) {
+        *target = Some(value.parse()
+            .map_err(|e| anyhow!("Invalid value for {} '{}': {}", name, value, e))?);
+    }
+
+    Ok(())
+}
+
+// Boolean switches accept "true" or "1"
+fn env_flag(name: &str, target: &mut bool) {
+    if let Ok(value) = env::var(name) {
+        *target = value == "true" || value == "1";
+    }
+}
+
+/// Manifest describing the complete set of indicator configurations
+///
+/// ```yaml
+/// sets:
+///   - symbols: [BTCUSDT, ETHUSDT]
+///     intervals: [1h, 4h, 1d]
+///     indicators:
+///       - name: RSI
+///         type: oscillator
+///         parameters:
+///           - { period: 14 }
+///           - { period: 21 }
+///       - name: OBV
+///         type: volume
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorManifest {
+    pub sets: Vec<ManifestSet>,
+}
+
+/// Indicators calculated for every combination of symbols and intervals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSet {
+    pub symbols: Vec<String>,
+    pub intervals: Vec<String>,
+    pub indicators: Vec<ManifestIndicator>,
+}
+
+/// An indicator with one or more parameter sets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestIndicator {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub indicator_type: String,
+    /// Parameter sets; an empty list means the indicator's default parameters
+    #[serde(default)]
+    pub parameters: Vec<serde_json::Value>,
+}
+
+/// A single indicator configuration requested by a manifest
+#[derive(Debug, Clone)]
+pub struct DesiredConfig {
+    pub symbol: String,
+    pub interval: String,
+    pub indicator_type: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+}
+
+/// Changes needed to make indicator_config match a manifest
+#[derive(Debug, Default)]
+pub struct ReconcilePlan {
+    pub create: Vec<DesiredConfig>,
+    pub enable: Vec<IndicatorConfig>,
+    pub disable: Vec<IndicatorConfig>,
+    pub unchanged: usize,
+}
+
+impl ReconcilePlan {
+    /// Check if the database already matches the manifest
+    pub fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.enable.is_empty() && self.disable.is_empty()
+    }
+}
+
+// Identity of a configuration, matching the indicator_config unique constraint.
+// serde_json objects serialize with sorted keys, so equal parameters give equal keys.
+fn config_key(symbol: &str, interval: &str, indicator_name: &str, parameters: &serde_json::Value) -> String {
+    format!("{}|{}|{}|{}", symbol, interval, indicator_name, parameters)
+}
+
+impl IndicatorManifest {
+    /// Load a manifest from a YAML (or JSON) file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read manifest: {}", path.display()))?;
+
+        let manifest: IndicatorManifest = serde_yaml::from_str(&content)
+            .context(format!("Failed to parse manifest: {}", path.display()))?;
+
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Check the manifest for empty lists, non-object parameter sets and pipeline inputs
+    /// that are unknown or form a cycle
+    pub fn validate(&self) -> Result<()> {
+        for (i, set) in self.sets.iter().enumerate() {
+            if set.symbols.is_empty() || set.intervals.is_empty() || set.indicators.is_empty() {
+                return Err(anyhow!("Manifest set {} needs at least one symbol, interval and indicator", i + 1));
+            }
+
+            for indicator in &set.indicators {
+                if let Some(parameters) = indicator.parameters.iter().find(|p| !p.is_object()) {
+                    return Err(anyhow!("Parameters of {} in set {} must be objects, got: {}",
+                                       indicator.name, i + 1, parameters));
+                }
+            }
+        }
+
+        let configs = self.expand();
+        let nodes: Vec<PipelineNode> = configs.iter()
+            .map(|config| PipelineNode {
+                symbol: &config.symbol,
+                interval: &config.interval,
+                indicator_name: &config.indicator_name,
+                parameters: &config.parameters,
+            })
+            .collect();
+        for (idx, inputs) in pipeline::resolve(&nodes) {
+            if let Err(e) = inputs {
+                let config = &configs[idx];
+                return Err(anyhow!("{} on {}:{} with parameters {}: {}",
+                                   config.indicator_name, config.symbol, config.interval, config.parameters, e));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Expand the manifest into individual configurations, without duplicates
+    pub fn expand(&self) -> Vec<DesiredConfig> {
+        let mut seen = HashSet::new();
+        let mut configs = Vec::new();
+
+        for set in &self.sets {
+            for symbol in &set.symbols {
+                for interval in &set.intervals {
+                    for indicator in &set.indicators {
+                        let default_parameters = [serde_json::json!({})];
+                        let parameter_sets = if indicator.parameters.is_empty() {
+                            &default_parameters[..]
+                        } else {
+                            &indicator.parameters[..]
+                        };
+
+                        for parameters in parameter_sets {
+                            if !seen.insert(config_key(symbol, interval, &indicator.name, parameters)) {
+                                continue;
+                            }
+
+                            configs.push(DesiredConfig {
+                                symbol: symbol.clone(),
+                                interval: interval.clone(),
+                                indicator_type: indicator.indicator_type.clone(),
+                                indicator_name: indicator.name.clone(),
+                                parameters: parameters.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        configs
+    }
+}
+
+/// Compare a manifest with the existing configurations
+pub fn plan_reconcile(manifest: &IndicatorManifest, existing: Vec<IndicatorConfig>) -> ReconcilePlan {
+    let mut existing: HashMap<String, IndicatorConfig> = existing
+        .into_iter()
+        .map(|c| (config_key(&c.symbol, &c.interval, &c.indicator_name, &c.parameters), c))
+        .collect();
+
+    let mut plan = ReconcilePlan::default();
+
+    for desired in manifest.expand() {
+        let key = config_key(&desired.symbol, &desired.interval, &desired.indicator_name, &desired.parameters);
+
+        match existing.remove(&key) {
+            Some(config) if config.enabled => plan.unchanged += 1,
+            Some(config) => plan.enable.push(config),
+            None => plan.create.push(desired),
+        }
+    }
+
+    // Whatever is left is not in the manifest anymore
+    plan.disable = existing.into_values().filter(|c| c.enabled).collect();
+    plan.disable.sort_by_key(|c| c.id);
+
+    plan
+}
+
+/// Reconcile indicator_config with a manifest
+pub async fn apply_manifest(pg: &PostgresManager, manifest: &IndicatorManifest, dry_run: bool) -> Result<ReconcilePlan> {
+    let existing = pg.get_all_indicator_configs().await?;
+    let plan = plan_reconcile(manifest, existing);
+
+    if dry_run || plan.is_empty() {
+        return Ok(plan);
+    }
+
+    for config in &plan.create {
+        pg.insert_indicator_config(
+            &config.symbol,
+            &config.interval,
+            &config.indicator_type,
+            &config.indicator_name,
+            &config.parameters,
+        ).await?;
+    }
+
+    for config in &plan.enable {
+        pg.set_indicator_config_enabled(config.id, true).await?;
+    }
+
+    for config in &plan.disable {
+        pg.set_indicator_config_enabled(config.id, false).await?;
+    }
+
+    info!("Applied indicator manifest: {} created, {} enabled, {} disabled, {} unchanged",
+          plan.create.len(), plan.enable.len(), plan.disable.len(), plan.unchanged);
+    record_event(
+        pg,
+        EventKind::ConfigChanged,
+        None,
+        &format!("Applied indicator manifest: {} created, {} enabled, {} disabled",
+                 plan.create.len(), plan.enable.len(), plan.disable.len()),
+        serde_json::json!({
+            "created": plan.create.iter().map(|c| format!("{}:{}:{}", c.symbol, c.interval, c.indicator_name)).collect::<Vec<_>>(),
+            "enabled": plan.enable.iter().map(|c| c.id).collect::<Vec<_>>(),
+            "disabled": plan.disable.iter().map(|c| c.id).collect::<Vec<_>>(),
+            "unchanged": plan.unchanged,
+        }),
+    ).await;
+
+    Ok(plan)
+}
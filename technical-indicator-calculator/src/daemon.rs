@@ -1,9 +1,18 @@
 // src/daemon.rs
+use crate::cache::completeness::{CompletenessCache, CompletenessInfo};
+use crate::cache::completeness_controller::CompletenessController;
+use crate::cache::redis::RedisManager;
+use crate::config::AppConfig;
+use crate::processor::cluster::{list_workers, queue_depth};
+use crate::processor::status::{read_status, status_file_path};
+use crate::processor::timings::render_prometheus;
 use anyhow::{Result, Context};
+use serde::Serialize;
 use std::fs::{self, File};
 use std::io::Read;
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::Arc;
 use tracing::{error, warn};
 
 const PID_FILE: &str = "/tmp/indicator-calculator.pid";
@@ -41,7 +50,6 @@ pub async fn start_daemon(concurrency: Option<usize>) -> Result<()> {
             .stdout(Stdio::from(File::create("/tmp/indicator-calculator.log")?))
             .stderr(Stdio::from(File::create("/tmp/indicator-calculator.err")?))
             .stdin(Stdio::null())
-            .env("RUST_LOG", "info")  // Ensure logging is set
             .spawn()?;
             
         // Wait a moment for the process to start
@@ -140,18 +148,31 @@ pub async fn stop_daemon() -> Result<()> {
         if kill_status.success() {
             println!("Sent termination signal to indicator calculator daemon (PID {}).", pid);
             
-            // Wait a bit to ensure the process has time to shut down
-            std::thread::sleep(std::time::Duration::from_secs(2));
+            // Give running jobs time to finish before forcing the process down
+            let grace_seconds = std::env::var("STOP_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(60);
             
-            // Check if process is still running
-            let check_status = Command::new("ps")
-                .arg("-p")
-                .arg(pid.to_string())
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .status()?;
+            let mut still_running = true;
+            for _ in 0..grace_seconds.max(1) {
+                std::thread::sleep(std::time::Duration::from_secs(1));
                 
-            if check_status.success() {
+                // Check if process is still running
+                still_running = Command::new("ps")
+                    .arg("-p")
+                    .arg(pid.to_string())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()?
+                    .success();
+                
+                if !still_running {
+                    break;
+                }
+            }
+                
+            if still_running {
                 // Process is still running, try SIGKILL
                 println!("Process still running, attempting force kill...");
                 let force_kill_status = Command::new("kill")
@@ -248,79 +269,216 @@ fn is_daemon_running() -> Result<bool> {
     }
 }
 
-/// Get the last few lines of the log file
-fn get_recent_logs(lines: usize) -> Result<String> {
-    if !Path::new(LOG_FILE).exists() {
-        return Ok("No log file found. The daemon may have just started or no logs have been written yet.".to_string());
+/// Check the status of the daemon from the status file the worker maintains
+pub async fn check_daemon_status() -> Result<()> {
+    let path = status_file_path();
+    
+    let status = match read_status(&path)? {
+        Some(status) if status.state != "stopped" && status.is_fresh() => status,
+        Some(status) => {
+            println!("Indicator calculator is not running (last seen {}).", 
+                     status.updated_at.format("%Y-%m-%d %H:%M:%S UTC"));
+            return Ok(());
+        },
+        None => {
+            println!("Indicator calculator is not running.");
+            return Ok(());
+        }
+    };
+    
+    println!("Indicator calculator is {} (PID {}).", status.state, status.pid);
+    println!("Started:         {}", status.started_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    println!("Uptime:          {}", format_uptime(status.uptime_seconds));
+    println!("Configurations:  {} enabled", status.configs_enabled);
+    println!("Jobs:            {} completed, {} skipped, {} failed", 
+             status.jobs_completed, status.jobs_skipped, status.jobs_failed);
+    
+    if let Some(cache) = &status.candle_cache {
+        println!("Candle cache:    {} hits, {} misses ({:.1}% hit rate), {} bypassed; {} histories, {} of {} candles", 
+                 cache.hits, cache.misses, cache.hit_rate_percent(), cache.bypassed, 
+                 cache.entries, cache.cached_candles, cache.max_candles);
     }
     
-    let output = Command::new("tail")
-        .arg("-n")
-        .arg(lines.to_string())
-        .arg(LOG_FILE)
-        .output()?;
+    if !status.queue_depth.is_empty() {
+        println!("\nQueue depth:");
+        for (pool, depth) in &status.queue_depth {
+            println!("  {:<20} {}", pool, depth);
+        }
+    }
     
-    if output.stdout.is_empty() {
-        return Ok("Log file exists but is empty.".to_string());
+    match &status.last_error {
+        Some(error) => {
+            println!("\nLast error ({}):", error.at.format("%Y-%m-%d %H:%M:%S UTC"));
+            println!("  Job:   {}", error.job);
+            if let Some(job_id) = error.job_id {
+                println!("  ID:    {}", job_id);
+            }
+            println!("  Error: {}", error.message);
+        },
+        None => println!("\nNo errors since start."),
     }
     
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(())
 }
 
-/// Check the status of the daemon
-pub async fn check_daemon_status() -> Result<()> {
-    // Check if daemon is running
-    let running = is_daemon_running()?;
+// Format seconds as e.g. "2d 03:04:05"
+fn format_uptime(seconds: i64) -> String {
+    let days = seconds / 86_400;
+    let rest = seconds % 86_400;
+    let time = format!("{:02}:{:02}:{:02}", rest / 3600, (rest % 3600) / 60, rest % 60);
     
-    if running {
-        // Read PID from file
-        let pid_str = fs::read_to_string(PID_FILE)?;
-        let pid = pid_str.trim();
-        
-        println!("Indicator calculator daemon is running (PID {}).", pid);
+    if days > 0 {
+        format!("{}d {}", days, time)
+    } else {
+        time
+    }
+}
+
+/// Show the workers sharing the job queue through Redis
+pub async fn check_cluster_status(config: &AppConfig) -> Result<()> {
+    let redis = RedisManager::new(&config.redis.url, config.redis.cache_ttl_seconds, 1).await?;
+    
+    let workers = list_workers(&redis).await?;
+    let (queued, leased) = queue_depth(&redis).await?;
+    
+    if workers.is_empty() {
+        println!("No cluster workers registered.");
+    } else {
+        let now = chrono::Utc::now();
         
-        // Show uptime information
-        #[cfg(unix)]
-        {
-            let uptime = Command::new("ps")
-                .arg("-p")
-                .arg(pid)
-                .arg("-o")
-                .arg("etime=")
-                .output()?;
-            
-            let uptime_str = String::from_utf8_lossy(&uptime.stdout).trim().to_string();
-            if !uptime_str.is_empty() {
-                println!("Uptime: {}", uptime_str);
-            }
-        }
+        println!("{:<40} | {:<8} | {:<10} | {:<9} | {:<6} | {:<6} | {:<8}", 
+                 "Worker", "PID", "Heartbeat", "Completed", "Failed", "Active", "Jobs/min");
+        println!("{:-<40}-+-{:-<8}-+-{:-<10}-+-{:-<9}-+-{:-<6}-+-{:-<6}-+-{:-<8}", "", "", "", "", "", "", "");
         
-        // Show process details
-        #[cfg(unix)]
-        {
-            let proc_info = Command::new("ps")
-                .arg("-p")
-                .arg(pid)
-                .arg("-o")
-                .arg("pid,ppid,%cpu,%mem,command")
-                .output()?;
-                
-            let info_str = String::from_utf8_lossy(&proc_info.stdout).to_string();
-            println!("\nProcess information:");
-            println!("{}", info_str);
+        for worker in &workers {
+            let heartbeat = format!("{}s ago", (now - worker.last_heartbeat).num_seconds());
+            println!("{:<40} | {:<8} | {:<10} | {:<9} | {:<6} | {:<6} | {:<8.2}", 
+                     worker.id, worker.pid, heartbeat, worker.jobs_completed, 
+                     worker.jobs_failed, worker.active_jobs, worker.throughput());
         }
         
-        #[cfg(not(unix))]
-        {
-            println!("Process is running. Status details not available on this platform.");
+        let total: f64 = workers.iter().map(|w| w.throughput()).sum();
+        println!("\n{} workers, {:.2} jobs/min combined", workers.len(), total);
+    }
+    
+    println!("Queued jobs: {}, running: {}", queued, leased);
+    
+    Ok(())
+}
+
+/// Which configurations `status coverage` reports on
+#[derive(Debug, Clone, Default)]
+pub struct CoverageFilter {
+    pub symbol: Option<String>,
+    pub interval: Option<String>,
+    pub indicator: Option<String>,
+    /// Only configurations that are not complete
+    pub incomplete_only: bool,
+}
+
+impl CoverageFilter {
+    fn matches(&self, info: &CompletenessInfo) -> bool {
+        self.symbol.as_ref().map_or(true, |symbol| &info.symbol == symbol)
+            && self.interval.as_ref().map_or(true, |interval| &info.interval == interval)
+            && self.indicator.as_ref().map_or(true, |indicator| info.indicator_name.eq_ignore_ascii_case(indicator))
+            && !(self.incomplete_only && info.is_complete)
+    }
+}
+
+#[derive(Serialize)]
+struct CoverageRow {
+    #[serde(flatten)]
+    info: CompletenessInfo,
+    /// Seconds the last calculated value lags behind the last candle
+    staleness_seconds: Option<i64>,
+}
+
+/// Show the coverage of every enabled indicator configuration, as a table or JSON
+pub async fn check_coverage_status(config: &AppConfig, filter: &CoverageFilter, json: bool) -> Result<()> {
+    let pg = config.database.shared().await?;
+    let cache = Arc::new(CompletenessCache::new(config.worker.completeness_cache_minutes));
+    let controller = CompletenessController::new(cache, pg);
+    controller.initialize_cache().await?;
+    
+    let rows: Vec<CoverageRow> = controller.report().into_iter()
+        .filter(|info| filter.matches(info))
+        .map(|info| CoverageRow {
+            staleness_seconds: info.staleness().map(|s| s.num_seconds()),
+            info,
+        })
+        .collect();
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+    
+    if rows.is_empty() {
+        println!("No matching indicator configurations.");
+        return Ok(());
+    }
+    
+    println!("{:<12} | {:<8} | {:<15} | {:<24} | {:<8} | {:<19} | {:<12} | {:<8}", 
+             "Symbol", "Interval", "Indicator", "Parameters", "Coverage", "Last Calculated", "Staleness", "Complete");
+    println!("{:-<12}-+-{:-<8}-+-{:-<15}-+-{:-<24}-+-{:-<8}-+-{:-<19}-+-{:-<12}-+-{:-<8}", 
+             "", "", "", "", "", "", "", "");
+    
+    for row in &rows {
+        let info = &row.info;
+        println!("{:<12} | {:<8} | {:<15} | {:<24} | {:<8} | {:<19} | {:<12} | {:<8}", 
+                 info.symbol, info.interval, info.indicator_name, info.parameters.to_string(),
+                 format!("{}%", info.coverage_percent),
+                 info.last_calculated_time.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                     .unwrap_or_else(|| "never".to_string()),
+                 row.staleness_seconds.map(format_uptime).unwrap_or_else(|| "N/A".to_string()),
+                 if info.is_complete { "yes" } else { "no" });
+    }
+    
+    let complete = rows.iter().filter(|row| row.info.is_complete).count();
+    println!("\n{} of {} configurations complete", complete, rows.len());
+    
+    Ok(())
+}
+
+/// Show the calculation and insert timings the worker publishes, as a table or Prometheus histograms
+pub async fn check_timings_status(limit: usize, prometheus: bool) -> Result<()> {
+    let status = match read_status(&status_file_path())? {
+        Some(status) => status,
+        None => {
+            println!("Indicator calculator is not running.");
+            return Ok(());
         }
+    };
+    
+    if prometheus {
+        print!("{}", render_prometheus(&status.timings));
+        return Ok(());
+    }
+    
+    if status.timings.is_empty() {
+        println!("No indicator timings recorded yet.");
+        return Ok(());
+    }
+    
+    let total: f64 = status.timings.iter().map(|t| t.total_seconds()).sum();
+    
+    println!("{:<15} | {:<12} | {:<6} | {:<10} | {:<9} | {:<9} | {:<10} | {:<10} | {:<6}", 
+             "Indicator", "Symbol", "Calcs", "Calc Total", "Calc Mean", "Calc Max", "Insert Tot", "Rows", "Share");
+    println!("{:-<15}-+-{:-<12}-+-{:-<6}-+-{:-<10}-+-{:-<9}-+-{:-<9}-+-{:-<10}-+-{:-<10}-+-{:-<6}", 
+             "", "", "", "", "", "", "", "", "");
+    
+    for timing in status.timings.iter().take(limit) {
+        let share = if total > 0.0 { timing.total_seconds() / total * 100.0 } else { 0.0 };
         
-        // Show recent logs
-        println!("\nRecent logs:");
-        println!("{}", get_recent_logs(10)?);
-    } else {
-        println!("Indicator calculator daemon is not running.");
+        println!("{:<15} | {:<12} | {:<6} | {:<10.2} | {:<9.3} | {:<9.3} | {:<10.2} | {:<10} | {:<5.1}%", 
+                 timing.indicator_name, timing.symbol, timing.calculation.count,
+                 timing.calculation.sum_seconds, timing.calculation.mean_seconds(), timing.calculation.max_seconds,
+                 timing.insert.sum_seconds, timing.rows_inserted, share);
     }
     
+    println!("\n{:.2}s recorded over {} indicator/symbol pairs since {}{}", 
+             total, status.timings.len(), status.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+             if status.state == "stopped" || !status.is_fresh() { " (worker not running)" } else { "" });
+    
     Ok(())
 }
@@ -0,0 +1,462 @@
+// Candle data from the Binance REST and WebSocket APIs
+use crate::data_source::DataSource;
+use crate::database::models::{BinanceCandle, CandleData, FundingRate, SymbolRecord};
+use anyhow::{Result, Context, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, error, info, warn};
+
+// Maximum candles per klines request
+const KLINES_LIMIT: usize = 1000;
+
+// Maximum entries per fundingRate request
+const FUNDING_RATE_LIMIT: usize = 1000;
+
+// Binance allows up to 1024 streams per connection; keep URLs reasonably short
+const STREAMS_PER_CONNECTION: usize = 200;
+
+// Reconnect backoff bounds
+const MIN_BACKOFF_SECS: u64 = 1;
+const MAX_BACKOFF_SECS: u64 = 60;
+
+// Combined stream wrapper: {"stream": "btcusdt@kline_1m", "data": {...}}
+#[derive(Debug, Deserialize)]
+struct CombinedMessage {
+    data: KlineEvent,
+}
+
+#[derive(Debug, Deserialize)]
+struct KlineEvent {
+    #[serde(rename = "k")]
+    kline: Kline,
+}
+
+#[derive(Debug, Deserialize)]
+struct Kline {
+    #[serde(rename = "t")]
+    open_time: i64,
+    #[serde(rename = "T")]
+    close_time: i64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "i")]
+    interval: String,
+    #[serde(rename = "o")]
+    open: String,
+    #[serde(rename = "h")]
+    high: String,
+    #[serde(rename = "l")]
+    low: String,
+    #[serde(rename = "c")]
+    close: String,
+    #[serde(rename = "v")]
+    volume: String,
+    #[serde(rename = "q")]
+    quote_asset_volume: String,
+    #[serde(rename = "n")]
+    number_of_trades: i64,
+    #[serde(rename = "V", default)]
+    taker_buy_volume: Option<String>,
+    #[serde(rename = "x")]
+    is_closed: bool,
+}
+
+impl Kline {
+    fn to_candle(&self) -> Result<BinanceCandle> {
+        Ok(BinanceCandle {
+            id: 0,
+            symbol: self.symbol.clone(),
+            interval: self.interval.clone(),
+            open_time: millis_to_time(self.open_time)?,
+            open_price: self.open.parse()?,
+            high_price: self.high.parse()?,
+            low_price: self.low.parse()?,
+            close_price: self.close.parse()?,
+            volume: self.volume.parse()?,
+            close_time: millis_to_time(self.close_time)?,
+            quote_asset_volume: self.quote_asset_volume.parse()?,
+            number_of_trades: self.number_of_trades as i32,
+            taker_buy_volume: self.taker_buy_volume.as_deref().map(str::parse).transpose()?,
+        })
+    }
+}
+
+fn millis_to_time(ms: i64) -> Result<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(ms).single()
+        .ok_or_else(|| anyhow!("Invalid Binance timestamp: {}", ms))
+}
+
+// Parse a REST kline: [open_time, open, high, low, close, volume, close_time, quote_volume, trades,
+// taker_buy_base_volume, ...]
+fn parse_rest_kline(symbol: &str, interval: &str, kline: &Value) -> Result<BinanceCandle> {
+    let field = |i: usize| kline.get(i).ok_or_else(|| anyhow!("Kline is missing field {}", i));
+    let price = |i: usize| -> Result<f64> {
+        field(i)?.as_str()
+            .ok_or_else(|| anyhow!("Kline field {} is not a string", i))?
+            .parse::<f64>()
+            .map_err(|e| anyhow!("Kline field {} is not a number: {}", i, e))
+    };
+    let integer = |i: usize| -> Result<i64> {
+        field(i)?.as_i64().ok_or_else(|| anyhow!("Kline field {} is not an integer", i))
+    };
+
+    Ok(BinanceCandle {
+        id: 0,
+        symbol: symbol.to_string(),
+        interval: interval.to_string(),
+        open_time: millis_to_time(integer(0)?)?,
+        open_price: price(1)?,
+        high_price: price(2)?,
+        low_price: price(3)?,
+        close_price: price(4)?,
+        volume: price(5)?,
+        close_time: millis_to_time(integer(6)?)?,
+        quote_asset_volume: price(7)?,
+        number_of_trades: integer(8)? as i32,
+        taker_buy_volume: if kline.get(9).is_some() { Some(price(9)?) } else { None },
+    })
+}
+
+// REST funding rate: {"symbol": "BTCUSDT", "fundingTime": 1698768000000, "fundingRate": "0.00010000", ...}
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FundingRateEntry {
+    symbol: String,
+    funding_time: i64,
+    funding_rate: String,
+}
+
+impl FundingRateEntry {
+    fn to_funding_rate(&self) -> Result<FundingRate> {
+        Ok(FundingRate {
+            symbol: self.symbol.clone(),
+            funding_time: millis_to_time(self.funding_time)?,
+            funding_rate: self.funding_rate.parse()
+                .map_err(|e| anyhow!("Funding rate is not a number: {} ({})", self.funding_rate, e))?,
+        })
+    }
+}
+
+/// Data source for the Binance spot market
+pub struct BinanceSource {
+    client: reqwest::Client,
+    rest_url: String,
+    ws_url: String,
+}
+
+impl BinanceSource {
+    pub fn new(rest_url: &str, ws_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            rest_url: rest_url.trim_end_matches('/').to_string(),
+            ws_url: ws_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Fetch full kline records, including quote volume and trade counts, in ascending order
+    pub async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<BinanceCandle>> {
+        let mut candles = Vec::new();
+        let mut from = start.map(|t| t.timestamp_millis()).unwrap_or(0);
+        let end_ms = end.map(|t| t.timestamp_millis());
+
+        // Page forward through the klines endpoint
+        loop {
+            let mut request = self.client
+                .get(format!("{}/api/v3/klines", self.rest_url))
+                .query(&[("symbol", symbol), ("interval", interval)])
+                .query(&[("startTime", from), ("limit", KLINES_LIMIT as i64)]);
+            if let Some(end_ms) = end_ms {
+                request = request.query(&[("endTime", end_ms)]);
+            }
+
+            let page: Vec<Value> = request.send().await?
+                .error_for_status()
+                .context(format!("Failed to fetch klines for {}:{}", symbol, interval))?
+                .json()
+                .await?;
+
+            for kline in &page {
+                candles.push(parse_rest_kline(symbol, interval, kline)?);
+            }
+
+            match candles.last() {
+                Some(last) if page.len() == KLINES_LIMIT => {
+                    from = last.open_time.timestamp_millis() + 1;
+                },
+                _ => break,
+            }
+        }
+
+        Ok(candles)
+    }
+
+    /// Fetch metadata of every listed symbol from exchangeInfo, with the 24h quote volume
+    /// from the ticker
+    ///
+    /// Binance doesn't publish listing dates of spot symbols, so listing_date is None.
+    pub async fn get_symbol_metadata(&self) -> Result<Vec<SymbolRecord>> {
+        let info: Value = self.client
+            .get(format!("{}/api/v3/exchangeInfo", self.rest_url))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to fetch Binance exchange info")?
+            .json()
+            .await?;
+
+        let tickers: Vec<Value> = self.client
+            .get(format!("{}/api/v3/ticker/24hr", self.rest_url))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to fetch Binance 24h tickers")?
+            .json()
+            .await?;
+
+        let volumes: HashMap<&str, f64> = tickers.iter()
+            .filter_map(|t| Some((t["symbol"].as_str()?, t["quoteVolume"].as_str()?.parse::<f64>().ok()?)))
+            .collect();
+
+        let now = Utc::now();
+        let symbols = info["symbols"].as_array()
+            .ok_or_else(|| anyhow!("Unexpected exchange info response"))?
+            .iter()
+            .filter_map(|s| {
+                let symbol = s["symbol"].as_str()?;
+                let tick_size = s["filters"].as_array()
+                    .and_then(|filters| filters.iter().find(|f| f["filterType"] == "PRICE_FILTER"))
+                    .and_then(|f| f["tickSize"].as_str()?.parse::<f64>().ok());
+
+                Some(SymbolRecord {
+                    symbol: symbol.to_string(),
+                    base_asset: s["baseAsset"].as_str().map(|a| a.to_string()),
+                    quote_asset: s["quoteAsset"].as_str().map(|a| a.to_string()),
+                    tick_size,
+                    listing_date: None,
+                    active: s["status"] == "TRADING",
+                    quote_volume: volumes.get(symbol).copied(),
+                    session: None,
+                    updated_at: now,
+                })
+            })
+            .collect();
+
+        Ok(symbols)
+    }
+
+    // Keep one combined-stream connection alive until the receiver goes away
+    async fn run_connection(ws_url: Arc<String>, streams: Vec<String>, candle_tx: mpsc::Sender<BinanceCandle>) {
+        let url = format!("{}/stream?streams={}", ws_url, streams.join("/"));
+        let mut backoff = MIN_BACKOFF_SECS;
+
+        while !candle_tx.is_closed() {
+            match Self::stream_once(&url, &candle_tx).await {
+                // A server that closes every connection right away is retried with the same
+                // backoff as a failing one; only a connection that delivered messages resets it
+                Ok(received) => {
+                    if received {
+                        backoff = MIN_BACKOFF_SECS;
+                    }
+                    if candle_tx.is_closed() {
+                        break;
+                    }
+                    info!("Kline stream closed by server, reconnecting in {}s ({} streams)", backoff, streams.len());
+                },
+                Err(e) => error!("Kline stream error: {}", e),
+            }
+
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+        }
+    }
+
+    // Read from a single connection until it closes or fails; returns whether any message arrived
+    async fn stream_once(url: &str, candle_tx: &mpsc::Sender<BinanceCandle>) -> Result<bool> {
+        let (mut socket, _) = connect_async(url).await
+            .context("Failed to connect to kline stream")?;
+
+        info!("Connected to kline stream");
+        let mut received = false;
+
+        while let Some(message) = socket.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => return Ok(received),
+                // Pings are answered automatically
+                _ => continue,
+            };
+
+            let event: CombinedMessage = match serde_json::from_str(&text) {
+                Ok(event) => event,
+                Err(e) => {
+                    debug!("Ignoring unexpected stream message: {} ({})", text, e);
+                    continue;
+                }
+            };
+            received = true;
+
+            // Only closed candles are final
+            if !event.data.kline.is_closed {
+                continue;
+            }
+
+            if candle_tx.send(event.data.kline.to_candle()?).await.is_err() {
+                return Ok(received);
+            }
+        }
+
+        Ok(received)
+    }
+}
+
+impl Default for BinanceSource {
+    fn default() -> Self {
+        Self::new("https://api.binance.com", "wss://stream.binance.com:9443")
+    }
+}
+
+#[async_trait]
+impl DataSource for BinanceSource {
+    fn name(&self) -> &str {
+        "binance"
+    }
+
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        let candles = self.get_klines(symbol, interval, start, end).await?;
+
+        if candles.is_empty() {
+            return Ok(CandleData::new(symbol.to_string(), interval.to_string()));
+        }
+
+        Ok(CandleData::from_candles(candles))
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<String>> {
+        let info: Value = self.client
+            .get(format!("{}/api/v3/exchangeInfo", self.rest_url))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to fetch Binance exchange info")?
+            .json()
+            .await?;
+
+        let symbols = info["symbols"].as_array()
+            .ok_or_else(|| anyhow!("Unexpected exchange info response"))?
+            .iter()
+            .filter(|s| s["status"] == "TRADING")
+            .filter_map(|s| s["symbol"].as_str().map(|s| s.to_string()))
+            .collect();
+
+        Ok(symbols)
+    }
+
+    async fn subscribe(&self, pairs: Vec<(String, String)>, candle_tx: mpsc::Sender<BinanceCandle>) -> Result<()> {
+        if pairs.is_empty() {
+            warn!("No symbol/interval pairs to stream");
+            return Ok(());
+        }
+
+        info!("Streaming {} symbol/interval pairs from {}", pairs.len(), self.ws_url);
+
+        let ws_url = Arc::new(self.ws_url.clone());
+        let mut connections = Vec::new();
+
+        for chunk in pairs.chunks(STREAMS_PER_CONNECTION) {
+            let streams: Vec<String> = chunk.iter()
+                .map(|(symbol, interval)| format!("{}@kline_{}", symbol.to_lowercase(), interval))
+                .collect();
+
+            connections.push(tokio::spawn(Self::run_connection(ws_url.clone(), streams, candle_tx.clone())));
+        }
+
+        for connection in connections {
+            connection.await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Funding rate history of Binance USD-M perpetual futures
+pub struct BinanceFundingSource {
+    client: reqwest::Client,
+    rest_url: String,
+}
+
+impl BinanceFundingSource {
+    pub fn new(rest_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            rest_url: rest_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Fetch the funding rates of a symbol in ascending order, optionally within [start, end]
+    pub async fn get_funding_rates(
+        &self,
+        symbol: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<FundingRate>> {
+        let mut rates = Vec::new();
+        let mut from = start.map(|t| t.timestamp_millis()).unwrap_or(0);
+        let end_ms = end.map(|t| t.timestamp_millis());
+
+        // Page forward through the fundingRate endpoint
+        loop {
+            let mut request = self.client
+                .get(format!("{}/fapi/v1/fundingRate", self.rest_url))
+                .query(&[("symbol", symbol)])
+                .query(&[("startTime", from), ("limit", FUNDING_RATE_LIMIT as i64)]);
+            if let Some(end_ms) = end_ms {
+                request = request.query(&[("endTime", end_ms)]);
+            }
+
+            let page: Vec<FundingRateEntry> = request.send().await?
+                .error_for_status()
+                .context(format!("Failed to fetch funding rates for {}", symbol))?
+                .json()
+                .await?;
+
+            for entry in &page {
+                rates.push(entry.to_funding_rate()?);
+            }
+
+            match rates.last() {
+                Some(last) if page.len() == FUNDING_RATE_LIMIT => {
+                    from = last.funding_time.timestamp_millis() + 1;
+                },
+                _ => break,
+            }
+        }
+
+        Ok(rates)
+    }
+}
+
+impl Default for BinanceFundingSource {
+    fn default() -> Self {
+        Self::new("https://fapi.binance.com")
+    }
+}
@@ -0,0 +1,197 @@
+// Candle history held in memory and shared by concurrent jobs on the same symbol/interval
+use crate::data_source::{DataSource, DatabaseSource};
+use crate::database::models::{BinanceCandle, CandleData};
+use crate::database::postgres::PostgresManager;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Histories not read by any job for this long are dropped
+const IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+// A history is identified by its newest candle, so a closed candle makes the next read reload it
+type HistoryKey = (String, String, DateTime<Utc>);
+
+/// Hit and size counters of the candle cache
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CandleCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Reads of histories larger than the cache, served from the database
+    pub bypassed: u64,
+    pub entries: u64,
+    pub cached_candles: u64,
+    pub max_candles: u64,
+}
+
+impl CandleCacheStats {
+    pub fn hit_rate_percent(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64 * 100.0
+        }
+    }
+
+    /// Render the counters in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP candle_cache_requests_total Candle history reads by outcome");
+        let _ = writeln!(out, "# TYPE candle_cache_requests_total counter");
+        let _ = writeln!(out, "candle_cache_requests_total{{result=\"hit\"}} {}", self.hits);
+        let _ = writeln!(out, "candle_cache_requests_total{{result=\"miss\"}} {}", self.misses);
+        let _ = writeln!(out, "candle_cache_requests_total{{result=\"bypass\"}} {}", self.bypassed);
+        let _ = writeln!(out, "# HELP candle_cache_entries Candle histories held in memory");
+        let _ = writeln!(out, "# TYPE candle_cache_entries gauge");
+        let _ = writeln!(out, "candle_cache_entries {}", self.entries);
+        let _ = writeln!(out, "# HELP candle_cache_candles Candles held in memory");
+        let _ = writeln!(out, "# TYPE candle_cache_candles gauge");
+        let _ = writeln!(out, "candle_cache_candles {}", self.cached_candles);
+
+        out
+    }
+}
+
+/// Database candles with an in-memory LRU of full histories in front of PostgreSQL
+///
+/// Histories are keyed by symbol, interval and newest open time and weighted by their
+/// number of candles. Concurrent reads of a history that is not cached yet share one
+/// database query. Histories larger than the whole cache are streamed from the database.
+pub struct CachedSource {
+    pg: Arc<PostgresManager>,
+    inner: DatabaseSource,
+    cache: Cache<HistoryKey, Arc<CandleData>>,
+    max_candles: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bypassed: AtomicU64,
+}
+
+impl CachedSource {
+    pub fn new(pg: Arc<PostgresManager>, max_candles: u64) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(max_candles)
+            .weigher(|_key: &HistoryKey, data: &Arc<CandleData>| u32::try_from(data.len()).unwrap_or(u32::MAX))
+            .time_to_idle(IDLE_TIMEOUT)
+            .build();
+
+        Self {
+            inner: DatabaseSource::new(pg.clone()),
+            pg,
+            cache,
+            max_candles,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            bypassed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> CandleCacheStats {
+        CandleCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
+            entries: self.cache.entry_count(),
+            cached_candles: self.cache.weighted_size(),
+            max_candles: self.max_candles,
+        }
+    }
+
+    // Full history from the cache, loading it on a miss; None when it is too large to cache
+    async fn history(&self, symbol: &str, interval: &str) -> Result<Option<Arc<CandleData>>> {
+        let (last_open, count) = self.pg.get_candle_summary(symbol, interval).await?;
+
+        let last_open = match last_open {
+            Some(last_open) => last_open,
+            None => return Ok(Some(Arc::new(CandleData::new(symbol.to_string(), interval.to_string())))),
+        };
+
+        if count as u64 > self.max_candles {
+            self.bypassed.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        let entry = self.cache
+            .entry((symbol.to_string(), interval.to_string(), last_open))
+            .or_try_insert_with(async { self.pg.get_candle_data(symbol, interval).await.map(Arc::new) })
+            .await
+            .map_err(|e| anyhow!("Failed to load candles for {}:{}: {}", symbol, interval, e))?;
+
+        if entry.is_fresh() {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        Ok(Some(entry.into_value()))
+    }
+}
+
+// Windows of up to `chunk_size` new candles, each repeating the last `overlap` candles of the previous one
+fn windows(data: Arc<CandleData>, chunk_size: usize, overlap: usize) -> BoxStream<'static, Result<CandleData>> {
+    let chunk_size = chunk_size.max(1);
+
+    stream::iter((0..data.len()).step_by(chunk_size))
+        .map(move |start| {
+            let end = start.saturating_add(chunk_size).min(data.len());
+            Ok(data.slice(start.saturating_sub(overlap)..end))
+        })
+        .boxed()
+}
+
+#[async_trait]
+impl DataSource for CachedSource {
+    fn name(&self) -> &str {
+        "database (cached)"
+    }
+
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        if start.is_none() && end.is_none() {
+            if let Some(data) = self.history(symbol, interval).await? {
+                return Ok(data.slice(0..data.len()));
+            }
+        }
+
+        self.inner.get_candles(symbol, interval, start, end).await
+    }
+
+    fn stream_candles<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: &'a str,
+        chunk_size: usize,
+        overlap: usize,
+    ) -> BoxStream<'a, Result<CandleData>> {
+        stream::once(self.history(symbol, interval))
+            .map_ok(move |history| match history {
+                Some(data) => windows(data, chunk_size, overlap),
+                None => self.inner.stream_candles(symbol, interval, chunk_size, overlap),
+            })
+            .try_flatten()
+            .boxed()
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<String>> {
+        self.inner.get_symbols().await
+    }
+
+    async fn subscribe(&self, pairs: Vec<(String, String)>, candle_tx: mpsc::Sender<BinanceCandle>) -> Result<()> {
+        self.inner.subscribe(pairs, candle_tx).await
+    }
+}
@@ -0,0 +1,155 @@
+// Candles from a local CSV export
+use crate::data_source::DataSource;
+use crate::database::models::{BinanceCandle, CandleData};
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// Candles of one symbol and interval read from a CSV file
+///
+/// Columns follow the Binance kline export: open_time, open, high, low, close,
+/// volume, close_time, quote_asset_volume, number_of_trades. Extra columns are
+/// ignored and a header row is skipped. Times are epoch milliseconds or RFC 3339.
+pub struct CsvSource {
+    path: PathBuf,
+    symbol: String,
+    interval: String,
+}
+
+impl CsvSource {
+    pub fn new(path: &Path, symbol: &str, interval: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+        }
+    }
+
+    /// Read every candle in the file in file order
+    pub fn read_candles(&self) -> Result<Vec<BinanceCandle>> {
+        let content = std::fs::read_to_string(&self.path)
+            .context(format!("Failed to read CSV file: {}", self.path.display()))?;
+
+        parse_candles(&content, &self.symbol, &self.interval)
+            .context(format!("Invalid CSV file: {}", self.path.display()))
+    }
+}
+
+/// Parse candles in the CSV format of `CsvSource`, in order
+pub fn parse_candles(content: &str, symbol: &str, interval: &str) -> Result<Vec<BinanceCandle>> {
+    let mut candles = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && is_header(line)) {
+            continue;
+        }
+
+        let candle = parse_csv_candle(symbol, interval, line)
+            .context(format!("Line {}", index + 1))?;
+        candles.push(candle);
+    }
+
+    Ok(candles)
+}
+
+// A header row starts with a column name instead of a time
+fn is_header(line: &str) -> bool {
+    line.split(',')
+        .next()
+        .map_or(false, |field| field.trim().trim_matches('"').starts_with(|c: char| c.is_ascii_alphabetic()))
+}
+
+fn parse_csv_candle(symbol: &str, interval: &str, line: &str) -> Result<BinanceCandle> {
+    let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+    if fields.len() < 6 {
+        return Err(anyhow!("Expected at least 6 columns, found {}", fields.len()));
+    }
+
+    let number = |i: usize| -> Result<f64> {
+        fields[i].parse::<f64>()
+            .map_err(|e| anyhow!("Column {} is not a number: {}", i + 1, e))
+    };
+
+    let open_time = parse_time(fields[0])?;
+    let close_time = match fields.get(6) {
+        Some(field) if !field.is_empty() => parse_time(field)?,
+        _ => open_time,
+    };
+
+    Ok(BinanceCandle {
+        id: 0,
+        symbol: symbol.to_string(),
+        interval: interval.to_string(),
+        open_time,
+        open_price: number(1)?,
+        high_price: number(2)?,
+        low_price: number(3)?,
+        close_price: number(4)?,
+        volume: number(5)?,
+        close_time,
+        quote_asset_volume: if fields.len() > 7 { number(7)? } else { 0.0 },
+        number_of_trades: match fields.get(8) {
+            Some(field) => field.parse()
+                .map_err(|e| anyhow!("Column 9 is not an integer: {}", e))?,
+            None => 0,
+        },
+        taker_buy_volume: match fields.get(9) {
+            Some(field) if !field.is_empty() => Some(number(9)?),
+            _ => None,
+        },
+    })
+}
+
+// Parse epoch milliseconds or an RFC 3339 timestamp
+fn parse_time(field: &str) -> Result<DateTime<Utc>> {
+    if let Ok(ms) = field.parse::<i64>() {
+        return Utc.timestamp_millis_opt(ms).single()
+            .ok_or_else(|| anyhow!("Invalid timestamp: {}", ms));
+    }
+
+    DateTime::parse_from_rfc3339(field)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| anyhow!("Invalid time '{}': {}", field, e))
+}
+
+#[async_trait]
+impl DataSource for CsvSource {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        if symbol != self.symbol || interval != self.interval {
+            return Ok(CandleData::new(symbol.to_string(), interval.to_string()));
+        }
+
+        let mut candles: Vec<BinanceCandle> = self.read_candles()?
+            .into_iter()
+            .filter(|c| start.map_or(true, |start| c.open_time >= start))
+            .filter(|c| end.map_or(true, |end| c.open_time <= end))
+            .collect();
+
+        if candles.is_empty() {
+            return Ok(CandleData::new(symbol.to_string(), interval.to_string()));
+        }
+
+        candles.sort_by_key(|c| c.open_time);
+        Ok(CandleData::from_candles(candles))
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<String>> {
+        Ok(vec![self.symbol.clone()])
+    }
+
+    async fn subscribe(&self, _pairs: Vec<(String, String)>, _candle_tx: mpsc::Sender<BinanceCandle>) -> Result<()> {
+        Err(anyhow!("CSV files do not provide live candles"))
+    }
+}
@@ -0,0 +1,63 @@
+// Candle data stored in PostgreSQL
+use crate::data_source::DataSource;
+use crate::database::models::{BinanceCandle, CandleData};
+use crate::database::postgres::PostgresManager;
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Data source reading candles loaded into the binance_candles table
+pub struct DatabaseSource {
+    pg: Arc<PostgresManager>,
+}
+
+impl DatabaseSource {
+    pub fn new(pg: Arc<PostgresManager>) -> Self {
+        Self { pg }
+    }
+}
+
+#[async_trait]
+impl DataSource for DatabaseSource {
+    fn name(&self) -> &str {
+        "database"
+    }
+
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        let candles = match (start, end) {
+            (None, None) => self.pg.get_candle_data(symbol, interval).await?,
+            _ => self.pg.get_candle_data_between(symbol, interval, start, end).await?,
+        };
+
+        Ok(candles)
+    }
+
+    fn stream_candles<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: &'a str,
+        chunk_size: usize,
+        overlap: usize,
+    ) -> BoxStream<'a, Result<CandleData>> {
+        self.pg.stream_candle_data(symbol, interval, chunk_size, overlap)
+            .map_err(anyhow::Error::from)
+            .boxed()
+    }
+
+    async fn get_symbols(&self) -> Result<Vec<String>> {
+        Ok(self.pg.get_candle_symbols().await?)
+    }
+
+    async fn subscribe(&self, _pairs: Vec<(String, String)>, _candle_tx: mpsc::Sender<BinanceCandle>) -> Result<()> {
+        Err(anyhow!("The database source does not support live subscriptions"))
+    }
+}
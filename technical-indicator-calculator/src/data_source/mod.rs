@@ -0,0 +1,58 @@
+// Exchange-agnostic access to candle data
+pub mod binance;
+pub mod cached;
+pub mod csv;
+pub mod database;
+
+use crate::database::models::{BinanceCandle, CandleData};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use tokio::sync::mpsc;
+
+pub use binance::{BinanceFundingSource, BinanceSource};
+pub use cached::{CachedSource, CandleCacheStats};
+pub use csv::CsvSource;
+pub use database::DatabaseSource;
+
+/// A source of candle data, such as an exchange API or the local database
+///
+/// The worker and evaluator only depend on this trait, so new exchanges or
+/// asset classes can be added without touching them.
+#[async_trait]
+pub trait DataSource: Send + Sync {
+    /// Short name of the source used in logs (e.g. "binance")
+    fn name(&self) -> &str;
+
+    /// Get candles for a symbol and interval in ascending time order,
+    /// optionally limited to open times within [start, end]
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData>;
+
+    /// Stream the full history of a symbol and interval in windows of up to
+    /// `chunk_size` new candles, each repeating the last `overlap` candles of the
+    /// previous window. Sources without chunked reads return the whole history at once.
+    fn stream_candles<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: &'a str,
+        _chunk_size: usize,
+        _overlap: usize,
+    ) -> BoxStream<'a, Result<CandleData>> {
+        Box::pin(futures::stream::once(self.get_candles(symbol, interval, None, None)))
+    }
+
+    /// List the symbols available from this source
+    async fn get_symbols(&self) -> Result<Vec<String>>;
+
+    /// Send every closed candle of the given symbol/interval pairs to `candle_tx`
+    ///
+    /// Runs until `candle_tx` is closed. Sources without live data return an error.
+    async fn subscribe(&self, pairs: Vec<(String, String)>, candle_tx: mpsc::Sender<BinanceCandle>) -> Result<()>;
+}
@@ -0,0 +1,184 @@
+// src/database/adjustments.rs
+//
+// Price adjustment events: token redenominations and stock splits. Stored candles stay as
+// the exchange reported them; candles are back-adjusted when loaded, so prices before an
+// event are on the same scale as after it and indicators don't see a fake gap.
+use crate::database::models::BinanceCandle;
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Kinds of adjustment events; the kind is informational, only the ratio is applied
+pub const ADJUSTMENT_KINDS: [&str; 3] = ["redenomination", "split", "reverse_split"];
+
+/// A change in the units of a symbol from `effective_time` on
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PriceAdjustment {
+    pub id: i32,
+    pub symbol: String,
+    /// Candles opening before this time are adjusted
+    pub effective_time: DateTime<Utc>,
+    /// New units per old unit, e.g. 2 for a 2:1 split or 0.001 for 1000 old tokens becoming 1
+    pub ratio: f64,
+    pub kind: String,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Check an adjustment before it is stored
+pub fn validate_adjustment(kind: &str, ratio: f64) -> Result<()> {
+    if !ADJUSTMENT_KINDS.contains(&kind) {
+        return Err(Error::validation(format!(
+            "Unknown adjustment kind '{}', expected one of: {}", kind, ADJUSTMENT_KINDS.join(", ")
+        )));
+    }
+    if !ratio.is_finite() || ratio <= 0.0 {
+        return Err(Error::validation(format!("Adjustment ratio must be a positive number, got {}", ratio)));
+    }
+    if ratio == 1.0 {
+        return Err(Error::validation("An adjustment ratio of 1 changes nothing"));
+    }
+
+    Ok(())
+}
+
+/// Back-adjust candles of one symbol to the units after its latest adjustment
+///
+/// Prices of a candle are divided by the product of the ratios of every event after its
+/// open time, and base volumes multiplied by it. Quote volume and trade counts are
+/// unchanged.
+pub fn back_adjust(candles: &mut [BinanceCandle], adjustments: &[PriceAdjustment]) {
+    if adjustments.is_empty() {
+        return;
+    }
+
+    for candle in candles.iter_mut() {
+        let factor: f64 = adjustments.iter()
+            .filter(|adjustment| adjustment.symbol == candle.symbol && candle.open_time < adjustment.effective_time)
+            .map(|adjustment| adjustment.ratio)
+            .product();
+        if factor == 1.0 {
+            continue;
+        }
+
+        candle.open_price /= factor;
+        candle.high_price /= factor;
+        candle.low_price /= factor;
+        candle.close_price /= factor;
+        candle.volume *= factor;
+        candle.taker_buy_volume = candle.taker_buy_volume.map(|volume| volume * factor);
+    }
+}
+
+/// Queries for price adjustment events
+impl PostgresManager {
+    // Create the price adjustments table if it doesn't exist
+    pub async fn init_price_adjustments_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS price_adjustments (
+                id SERIAL PRIMARY KEY,
+                symbol VARCHAR NOT NULL,
+                effective_time TIMESTAMPTZ NOT NULL,
+                ratio DOUBLE PRECISION NOT NULL CHECK (ratio > 0),
+                kind VARCHAR NOT NULL,
+                note TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                UNIQUE (symbol, effective_time)
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Store an adjustment and delete the symbol's calculated indicator values, which were
+    // calculated from unadjusted candles. Returns the adjustment ID and the deleted rows.
+    pub async fn insert_price_adjustment(
+        &self,
+        symbol: &str,
+        effective_time: DateTime<Utc>,
+        ratio: f64,
+        kind: &str,
+        note: Option<&str>,
+    ) -> Result<(i32, u64)> {
+        let mut tx = self.pool.begin().await?;
+
+        let id: i32 = sqlx::query_scalar(
+            "INSERT INTO price_adjustments (symbol, effective_time, ratio, kind, note)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (symbol, effective_time) DO NOTHING
+             RETURNING id"
+        )
+        .bind(symbol)
+        .bind(effective_time)
+        .bind(ratio)
+        .bind(kind)
+        .bind(note)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| Error::validation(format!(
+            "{} already has an adjustment at {}", symbol, effective_time.to_rfc3339()
+        )))?;
+
+        let deleted = sqlx::query("DELETE FROM calculated_indicators WHERE symbol = $1")
+            .bind(symbol)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok((id, deleted))
+    }
+
+    // Delete an adjustment and the symbol's calculated indicator values. Returns the deleted
+    // adjustment and the deleted rows.
+    pub async fn delete_price_adjustment(&self, id: i32) -> Result<(PriceAdjustment, u64)> {
+        let mut tx = self.pool.begin().await?;
+
+        let adjustment = sqlx::query_as::<_, PriceAdjustment>(
+            "DELETE FROM price_adjustments WHERE id = $1
+             RETURNING id, symbol, effective_time, ratio, kind, note, created_at"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| Error::not_found(format!("Price adjustment {}", id)))?;
+
+        let deleted = sqlx::query("DELETE FROM calculated_indicators WHERE symbol = $1")
+            .bind(&adjustment.symbol)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok((adjustment, deleted))
+    }
+
+    // Get the adjustments of a symbol, or of every symbol, in time order
+    pub async fn get_price_adjustments(&self, symbol: Option<&str>) -> Result<Vec<PriceAdjustment>> {
+        let adjustments = sqlx::query_as::<_, PriceAdjustment>(
+            "SELECT id, symbol, effective_time, ratio, kind, note, created_at
+             FROM price_adjustments
+             WHERE $1::text IS NULL OR symbol = $1
+             ORDER BY symbol, effective_time"
+        )
+        .bind(symbol)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(adjustments)
+    }
+
+    // Back-adjust candles of a symbol loaded from binance_candles
+    pub(crate) async fn back_adjust_candles(&self, symbol: &str, candles: &mut [BinanceCandle]) -> Result<()> {
+        if candles.is_empty() {
+            return Ok(());
+        }
+
+        back_adjust(candles, &self.get_price_adjustments(Some(symbol)).await?);
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+// Candle data audit: gaps, duplicates and zero-volume candles
+use crate::data_source::{BinanceSource, DataSource};
+use crate::database::postgres::PostgresManager;
+use crate::error::Result;
+use crate::utils::utils::interval_to_seconds;
+use chrono::{DateTime, Duration, Utc};
+use tracing::{info, warn};
+
+/// A run of missing candles between two stored candles
+#[derive(Debug, Clone)]
+pub struct CandleGap {
+    /// Open time of the last candle before the gap
+    pub after: DateTime<Utc>,
+    /// Open time of the first candle after the gap
+    pub before: DateTime<Utc>,
+    /// Number of candles missing in between
+    pub missing: i64,
+}
+
+/// Audit findings for one symbol/interval
+#[derive(Debug, Clone)]
+pub struct CandleAudit {
+    pub symbol: String,
+    pub interval: String,
+    pub gaps: Vec<CandleGap>,
+    pub duplicates: i64,
+    pub zero_volume: i64,
+    /// Candles written while backfilling gaps
+    pub backfilled: usize,
+}
+
+impl CandleAudit {
+    pub fn missing_candles(&self) -> i64 {
+        self.gaps.iter().map(|gap| gap.missing).sum()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.gaps.is_empty() && self.duplicates == 0 && self.zero_volume == 0
+    }
+}
+
+/// Scan the stored candles of a symbol/interval for anomalies
+pub async fn audit_candles(pg: &PostgresManager, symbol: &str, interval: &str) -> Result<CandleAudit> {
+    let gaps = match interval_to_seconds(interval) {
+        Some(step) => pg.find_candle_gaps(symbol, interval, step).await?
+            .into_iter()
+            .map(|(after, before)| CandleGap {
+                after,
+                before,
+                missing: (before - after).num_seconds() / step - 1,
+            })
+            .collect(),
+        None => {
+            // Monthly candles vary in length, so gaps cannot be measured
+            warn!("Skipping gap detection for {}:{}, unsupported interval", symbol, interval);
+            Vec::new()
+        }
+    };
+
+    Ok(CandleAudit {
+        symbol: symbol.to_string(),
+        interval: interval.to_string(),
+        gaps,
+        duplicates: pg.count_duplicate_candles(symbol, interval).await?,
+        zero_volume: pg.count_zero_volume_candles(symbol, interval).await?,
+        backfilled: 0,
+    })
+}
+
+/// Fetch the candles missing from each gap from the exchange and store them
+pub async fn backfill_gaps(pg: &PostgresManager, source: &BinanceSource, audit: &mut CandleAudit) -> Result<()> {
+    for gap in &audit.gaps {
+        // Exclusive of the candles bounding the gap
+        let start = gap.after + Duration::milliseconds(1);
+        let end = gap.before - Duration::milliseconds(1);
+
+        let candles = source.get_klines(&audit.symbol, &audit.interval, Some(start), Some(end)).await?;
+
+        for candle in &candles {
+            pg.upsert_candle(candle).await?;
+        }
+
+        info!("Backfilled {} of {} missing {}:{} candles after {} from {}", 
+              candles.len(), gap.missing, audit.symbol, audit.interval, gap.after, source.name());
+        audit.backfilled += candles.len();
+    }
+
+    Ok(())
+}
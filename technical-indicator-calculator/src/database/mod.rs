@@ -1,4 +1,17 @@
+#[cfg(feature = "native")]
+pub mod adjustments;
+#[cfg(feature = "native")]
+pub mod audit;
 pub mod models;
+#[cfg(feature = "native")]
 pub mod postgres;
-pub mod postgres_helpers;
+#[cfg(feature = "native")]
+pub mod retention;
+#[cfg(feature = "native")]
 pub mod schema;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "native")]
+pub mod storage;
+#[cfg(feature = "native")]
+pub mod strategy_queries;
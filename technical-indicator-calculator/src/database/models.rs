@@ -1,9 +1,17 @@
+use crate::indicators::output::IndicatorOutput;
+use crate::utils::session::SessionCalendar;
 use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "native")]
 use sqlx::FromRow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
 
 // Binance candle model from database
-#[derive(Debug, Clone, FromRow)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "native", derive(FromRow))]
 pub struct BinanceCandle {
     pub id: i32,
     pub symbol: String,
@@ -17,6 +25,8 @@ pub struct BinanceCandle {
     pub close_time: DateTime<Utc>,
     pub quote_asset_volume: f64,
     pub number_of_trades: i32,
+    // Base asset volume bought by takers; None where the source doesn't provide it
+    pub taker_buy_volume: Option<f64>,
 }
 
 // Candle data for calculations
@@ -31,6 +41,59 @@ pub struct CandleData {
     pub close: Vec<f64>,
     pub volume: Vec<f64>,
     pub close_time: Vec<DateTime<Utc>>,
+    // Taker buy volume, NaN where unknown; empty when no candle has it
+    pub taker_buy_volume: Vec<f64>,
+    // Trading hours of the symbol, for session boundaries; 24/7 UTC unless set in symbols
+    pub session: SessionCalendar,
+    // Derived columns, computed on first use
+    derived: DerivedColumns,
+}
+
+// Lazily computed columns shared by all indicators and patterns using the same series.
+// Each column remembers the price vectors it was computed from and is recomputed once
+// they were replaced or resized; prices overwritten in place need clear_derived.
+#[derive(Debug, Default)]
+struct DerivedColumns {
+    columns: RwLock<HashMap<DerivedColumn, (PriceStamp, Arc<[f64]>)>>,
+}
+
+impl Clone for DerivedColumns {
+    // A clone owns new price vectors, so nothing cached applies to it
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DerivedColumn {
+    BodySize,
+    UpperShadow,
+    LowerShadow,
+    TypicalPrice,
+    TrueRange,
+}
+
+// Address and length of the open, high, low and close vectors
+type PriceStamp = [(usize, usize); 4];
+
+/// Absolute size of a candle body (|close - open|)
+pub fn candle_body(open: f64, close: f64) -> f64 {
+    (close - open).abs()
+}
+
+/// Body of a candle relative to its high-low range; 0 for a candle without range
+pub fn candle_body_ratio(open: f64, high: f64, low: f64, close: f64) -> f64 {
+    let range = high - low;
+    if range > 0.0 { candle_body(open, close) / range } else { 0.0 }
+}
+
+/// True range of a candle; without a previous close it is high - low
+pub fn candle_true_range(high: f64, low: f64, prev_close: Option<f64>) -> f64 {
+    let range = high - low;
+    match prev_close {
+        Some(prev_close) => range.max((high - prev_close).abs()).max((low - prev_close).abs()),
+        None => range,
+    }
 }
 
 impl CandleData {
@@ -45,9 +108,18 @@ impl CandleData {
             close: Vec::new(),
             volume: Vec::new(),
             close_time: Vec::new(),
+            taker_buy_volume: Vec::new(),
+            session: SessionCalendar::default(),
+            derived: DerivedColumns::default(),
         }
     }
 
+    /// Use a symbol's session calendar for session boundaries
+    pub fn with_session(mut self, session: SessionCalendar) -> Self {
+        self.session = session;
+        self
+    }
+
     pub fn from_candles(candles: Vec<BinanceCandle>) -> Self {
         let symbol = if let Some(candle) = candles.first() {
             candle.symbol.clone()
@@ -62,6 +134,7 @@ impl CandleData {
         };
 
         let mut data = Self::new(symbol, interval);
+        let has_taker_volume = candles.iter().any(|candle| candle.taker_buy_volume.is_some());
 
         for candle in candles {
             data.open_time.push(candle.open_time);
@@ -71,6 +144,9 @@ impl CandleData {
             data.close.push(candle.close_price);
             data.volume.push(candle.volume);
             data.close_time.push(candle.close_time);
+            if has_taker_volume {
+                data.taker_buy_volume.push(candle.taker_buy_volume.unwrap_or(f64::NAN));
+            }
         }
 
         data
@@ -85,10 +161,103 @@ impl CandleData {
     pub fn is_empty(&self) -> bool {
         self.close.is_empty()
     }
+
+    /// Copy of the candles in `range`, without derived columns
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Self {
+        Self {
+            symbol: self.symbol.clone(),
+            interval: self.interval.clone(),
+            open_time: self.open_time[range.clone()].to_vec(),
+            open: self.open[range.clone()].to_vec(),
+            high: self.high[range.clone()].to_vec(),
+            low: self.low[range.clone()].to_vec(),
+            close: self.close[range.clone()].to_vec(),
+            volume: self.volume[range.clone()].to_vec(),
+            close_time: self.close_time[range.clone()].to_vec(),
+            taker_buy_volume: self.taker_buy_volume.get(range).map(<[f64]>::to_vec).unwrap_or_default(),
+            session: self.session,
+            derived: DerivedColumns::default(),
+        }
+    }
+
+    /// Taker buy volume of candle `idx`, if the source recorded it
+    pub fn taker_buy(&self, idx: usize) -> Option<f64> {
+        self.taker_buy_volume.get(idx).copied().filter(|volume| volume.is_finite())
+    }
+
+    /// Absolute size of each candle body (|close - open|)
+    pub fn body_size(&self) -> Arc<[f64]> {
+        self.derived_column(DerivedColumn::BodySize, |candles| {
+            (0..candles.len())
+                .map(|i| candle_body(candles.open[i], candles.close[i]))
+                .collect()
+        })
+    }
+
+    /// Distance from the top of the body to the high
+    pub fn upper_shadow(&self) -> Arc<[f64]> {
+        self.derived_column(DerivedColumn::UpperShadow, |candles| {
+            (0..candles.len())
+                .map(|i| candles.high[i] - candles.open[i].max(candles.close[i]))
+                .collect()
+        })
+    }
+
+    /// Distance from the bottom of the body to the low
+    pub fn lower_shadow(&self) -> Arc<[f64]> {
+        self.derived_column(DerivedColumn::LowerShadow, |candles| {
+            (0..candles.len())
+                .map(|i| candles.open[i].min(candles.close[i]) - candles.low[i])
+                .collect()
+        })
+    }
+
+    /// Typical price ((high + low + close) / 3)
+    pub fn typical_price(&self) -> Arc<[f64]> {
+        self.derived_column(DerivedColumn::TypicalPrice, |candles| {
+            (0..candles.len())
+                .map(|i| (candles.high[i] + candles.low[i] + candles.close[i]) / 3.0)
+                .collect()
+        })
+    }
+
+    /// True range; the first candle has no previous close and uses high - low
+    pub fn true_range(&self) -> Arc<[f64]> {
+        self.derived_column(DerivedColumn::TrueRange, |candles| {
+            (0..candles.len())
+                .map(|i| candle_true_range(candles.high[i], candles.low[i], i.checked_sub(1).map(|prev| candles.close[prev])))
+                .collect()
+        })
+    }
+
+    /// Drop cached derived columns; needed after overwriting prices in place
+    pub fn clear_derived(&mut self) {
+        self.derived = DerivedColumns::default();
+    }
+
+    // Address and length of the price vectors the derived columns are computed from
+    fn price_stamp(&self) -> PriceStamp {
+        [&self.open, &self.high, &self.low, &self.close].map(|series| (series.as_ptr() as usize, series.len()))
+    }
+
+    // Cached column, calculated when missing or computed from other price vectors
+    fn derived_column(&self, column: DerivedColumn, calculate: impl FnOnce(&Self) -> Vec<f64>) -> Arc<[f64]> {
+        let stamp = self.price_stamp();
+        if let Some((cached_stamp, values)) = self.derived.columns.read().get(&column) {
+            if *cached_stamp == stamp {
+                return values.clone();
+            }
+        }
+
+        let values: Arc<[f64]> = calculate(self).into();
+        self.derived.columns.write().insert(column, (stamp, values.clone()));
+        values
+    }
 }
 
 // Technical indicator configuration from database
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(FromRow))]
 pub struct IndicatorConfig {
     pub id: i32,
     pub symbol: String,
@@ -97,10 +266,198 @@ pub struct IndicatorConfig {
     pub indicator_name: String,
     pub parameters: serde_json::Value,
     pub enabled: bool,
+    // Higher priorities are calculated first
+    pub priority: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Indicator configuration whose parameters were edited after values were calculated
+#[derive(Debug, Clone)]
+pub struct InvalidatedIndicatorConfig {
+    pub config: IndicatorConfig,
+    // Parameters the deleted values were calculated with
+    pub previous_parameters: serde_json::Value,
+    pub deleted_rows: u64,
+}
+
+// Calculation job that kept failing after all retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct FailedJob {
+    pub id: i64,
+    pub job_key: String,
+    /// Correlation ID of the last failed run
+    pub job_id: Option<Uuid>,
+    pub pool: String,
+    pub symbol: String,
+    pub interval: String,
+    pub indicator_type: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+    pub attempts: i32,
+    pub error: String,
+    pub failure_count: i32,
+    pub first_failed_at: DateTime<Utc>,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+// Suspicious calculated value flagged by the worker's anomaly checks
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct IndicatorAnomalyRecord {
+    pub id: i64,
+    pub symbol: String,
+    pub interval: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+    pub time: DateTime<Utc>,
+    /// Property of a multi-valued output; None for scalars
+    pub property: Option<String>,
+    pub check_name: String,
+    pub value: f64,
+    pub detail: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+// Strategy header stored in strategies; indicators and rules have their own tables
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct StrategyRecord {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub version: String,
+    pub author: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub enabled: bool,
+    pub assets: serde_json::Value,
+    pub timeframes: serde_json::Value,
+    pub tags: serde_json::Value,
+    pub parameters: serde_json::Value,
+    pub risk_management: serde_json::Value,
+    pub metadata: Option<serde_json::Value>,
+}
+
+// Indicator of a strategy from strategy_indicators
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct StrategyIndicatorRecord {
+    pub indicator_id: String,
+    pub indicator_type: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+    pub description: Option<String>,
+}
+
+// Rule of a strategy from strategy_rules
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct StrategyRuleRecord {
+    pub rule_id: String,
+    pub name: String,
+    pub condition: serde_json::Value,
+    pub action: serde_json::Value,
+    pub priority: i32,
+    pub description: Option<String>,
+}
+
+// Performance summary of a backtest from strategy_backtest_results
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct BacktestResultRecord {
+    pub id: i32,
+    pub symbol: String,
+    pub interval: String,
+    pub total_trades: i32,
+    pub winning_trades: i32,
+    pub losing_trades: i32,
+    pub win_rate: Option<f64>,
+    pub max_drawdown: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub total_return: Option<f64>,
+    pub annualized_return: Option<f64>,
+    pub max_consecutive_wins: Option<i32>,
+    pub max_consecutive_losses: Option<i32>,
+    pub avg_profit_per_win: Option<f64>,
+    pub avg_loss_per_loss: Option<f64>,
+    pub avg_win_holding_period: Option<f64>,
+    pub avg_loss_holding_period: Option<f64>,
+    pub expectancy: Option<f64>,
+    pub benchmark_return: Option<f64>,
+    pub excess_return: Option<f64>,
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+    pub calmar_ratio: Option<f64>,
+    pub value_at_risk: Option<f64>,
+    pub expected_shortfall: Option<f64>,
+    pub exposure_percent: Option<f64>,
+    pub turnover: Option<f64>,
+    pub unfilled_orders: Option<i32>,
+}
+
+// Row of the backtest result list, with the strategy's name and headline metrics
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BacktestListRecord {
+    pub id: i32,
+    pub strategy_id: String,
+    pub strategy_name: Option<String>,
+    pub symbol: String,
+    pub interval: String,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    pub total_trades: i32,
+    pub total_return: Option<f64>,
+    pub sharpe_ratio: Option<f64>,
+    pub max_drawdown: Option<f64>,
+    pub win_rate: Option<f64>,
+    pub profit_factor: Option<f64>,
+}
+
+// Contract specification of a symbol from symbols
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct SymbolContractRecord {
+    pub symbol: String,
+    pub contract_type: String,
+    pub contract_multiplier: f64,
+    pub quanto_rate: Option<f64>,
+    pub settlement_asset: Option<String>,
+    pub apply_funding: bool,
+    pub short_borrow_rate: Option<f64>,
+}
+
+// Exchange metadata of a symbol from symbols
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SymbolRecord {
+    pub symbol: String,
+    pub base_asset: Option<String>,
+    pub quote_asset: Option<String>,
+    /// Minimum price increment
+    pub tick_size: Option<f64>,
+    pub listing_date: Option<DateTime<Utc>>,
+    /// Whether the symbol is trading on the exchange
+    pub active: bool,
+    /// 24h volume in the quote asset when the symbol was last imported, for volume watchlists
+    pub quote_volume: Option<f64>,
+    /// Session calendar, a preset name or spec; None trades 24/7 on UTC days
+    pub session: Option<String>,
     pub updated_at: DateTime<Utc>,
 }
 
+// Funding rate of a perpetual futures symbol, charged at funding_time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "native", derive(FromRow))]
+pub struct FundingRate {
+    pub symbol: String,
+    pub funding_time: DateTime<Utc>,
+    /// Fraction of the position notional paid by longs to shorts (negative: shorts pay)
+    pub funding_rate: f64,
+}
+
 // Batch data for calculated indicators
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalculatedIndicatorBatch {
@@ -112,3 +469,114 @@ pub struct CalculatedIndicatorBatch {
     pub time: DateTime<Utc>,
     pub value: serde_json::Value,
 }
+
+// Stored values of one indicator in ascending time order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorSeries {
+    pub symbol: String,
+    pub interval: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+    pub time: Vec<DateTime<Utc>>,
+    pub value: Vec<serde_json::Value>,
+}
+
+impl IndicatorSeries {
+    pub fn new(symbol: &str, interval: &str, indicator_name: &str, parameters: &serde_json::Value) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            indicator_name: indicator_name.to_string(),
+            parameters: parameters.clone(),
+            time: Vec::new(),
+            value: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    pub fn push(&mut self, time: DateTime<Utc>, value: serde_json::Value) {
+        self.time.push(time);
+        self.value.push(value);
+    }
+}
+
+// Indicator requested in an aligned frame, keyed by the caller's ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameIndicator {
+    pub id: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+}
+
+// Candles with indicator values aligned to each candle's open time
+#[derive(Debug, Clone)]
+pub struct AlignedFrame {
+    pub candles: CandleData,
+    // One entry per candle; None where the indicator has no value (warmup or gaps)
+    pub indicators: HashMap<String, Vec<Option<serde_json::Value>>>,
+}
+
+impl AlignedFrame {
+    pub fn new(candles: CandleData) -> Self {
+        Self {
+            candles,
+            indicators: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.candles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candles.is_empty()
+    }
+
+    // Align a series to the candles by timestamp; values without a matching candle are dropped
+    pub fn insert_series(&mut self, id: &str, series: &IndicatorSeries) {
+        let mut aligned = vec![None; self.candles.len()];
+        let mut j = 0;
+
+        // Both sides are sorted by time, so a single merge pass is enough
+        for (i, open_time) in self.candles.open_time.iter().enumerate() {
+            while j < series.len() && series.time[j] < *open_time {
+                j += 1;
+            }
+            if j < series.len() && series.time[j] == *open_time {
+                aligned[i] = Some(series.value[j].clone());
+            }
+        }
+
+        self.indicators.insert(id.to_string(), aligned);
+    }
+
+    pub fn value_at(&self, id: &str, idx: usize) -> Option<&serde_json::Value> {
+        self.indicators.get(id)?.get(idx)?.as_ref()
+    }
+
+    // Typed value of an indicator at candle `idx`; None when missing or of an unknown shape
+    pub fn output_at(&self, id: &str, idx: usize) -> Option<IndicatorOutput> {
+        IndicatorOutput::from_value(self.value_at(id, idx)?)
+    }
+
+    // Numeric value of an indicator (or one property of a multi-value indicator) at candle `idx`
+    pub fn numeric_at(&self, id: &str, property: Option<&str>, idx: usize) -> Option<f64> {
+        let value = self.value_at(id, idx)?;
+        match property {
+            Some(property) => value.get(property)?.as_f64(),
+            None => value.as_f64(),
+        }
+    }
+
+    // First candle index at which every indicator has a value
+    pub fn first_complete_index(&self) -> Option<usize> {
+        (0..self.len()).find(|&idx| self.indicators.values().all(|values| values[idx].is_some()))
+    }
+}
@@ -1,13 +1,40 @@
-use crate::database::models::{BinanceCandle, CalculatedIndicatorBatch, CandleData, IndicatorConfig};
-use anyhow::{Context, Result};
+use crate::database::models::{
+    BinanceCandle, CalculatedIndicatorBatch, CandleData, FailedJob, FundingRate, IndicatorAnomalyRecord,
+    IndicatorConfig, IndicatorSeries, InvalidatedIndicatorConfig,
+};
+use crate::processor::anomaly::IndicatorAnomaly;
+use crate::processor::job::CalculationJob;
+use crate::processor::quality::QualityIssue;
+use crate::error::{Error, Result};
+use crate::utils::resources::ResourceUsage;
 use chrono::{DateTime, Utc};
-use sqlx::{postgres::PgPoolOptions, PgPool, Row};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use sqlx::{postgres::PgPoolOptions, FromRow, PgPool, Row};
+use std::collections::HashSet;
 use std::sync::Arc;
-use tracing::{debug, error, info, warn};
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
+
+/// Namespace of rows written before namespaces existed, and of connections without one
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Size and timeouts of a connection pool
+#[derive(Debug, Clone)]
+pub struct PoolSettings {
+    pub max_connections: usize,
+    /// How long a query waits for a free connection before failing
+    pub acquire_timeout: Duration,
+    /// Idle connections are closed after this long; None keeps them open
+    pub idle_timeout: Option<Duration>,
+}
 
 pub struct PostgresManager {
     // Make the pool public so our helper methods can access it
     pub(crate) pool: PgPool,
+    // Strategies, indicator configurations and backtest results are read and written in this namespace
+    pub(crate) namespace: String,
 }
 
 impl PostgresManager {
@@ -17,7 +44,7 @@ impl PostgresManager {
         user: &str,
         password: &str,
         dbname: &str,
-        max_connections: usize,
+        pool_settings: &PoolSettings,
     ) -> Result<Self> {
         // Create SQLx pool
         let connection_string = format!(
@@ -26,12 +53,28 @@ impl PostgresManager {
         );
         
         let pool = PgPoolOptions::new()
-            .max_connections(max_connections as u32)
+            .max_connections(pool_settings.max_connections as u32)
+            .acquire_timeout(pool_settings.acquire_timeout)
+            .idle_timeout(pool_settings.idle_timeout)
             .connect(&connection_string)
             .await
-            .context("Failed to create database connection pool")?;
+            .map_err(|e| {
+                error!("Failed to create database connection pool: {}", e);
+                e
+            })?;
             
-        Ok(Self { pool })
+        Ok(Self { pool, namespace: DEFAULT_NAMESPACE.to_string() })
+    }
+
+    /// Scope strategies, indicator configurations and backtest results to a namespace
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = namespace.to_string();
+        self
+    }
+
+    /// The namespace this connection reads and writes
+    pub fn namespace(&self) -> &str {
+        &self.namespace
     }
 
     // Create tables if they don't exist
@@ -46,14 +89,45 @@ impl PostgresManager {
                 indicator_name VARCHAR NOT NULL,
                 parameters JSONB NOT NULL,
                 enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                priority INTEGER NOT NULL DEFAULT 0,
+                namespace VARCHAR NOT NULL DEFAULT 'default',
                 created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                UNIQUE(symbol, interval, indicator_name, parameters)
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
             )"
         )
         .execute(&self.pool)
         .await?;
 
+        // Tables created before job priorities were introduced
+        sqlx::query("ALTER TABLE indicator_config ADD COLUMN IF NOT EXISTS priority INTEGER NOT NULL DEFAULT 0")
+            .execute(&self.pool)
+            .await?;
+
+        // Unique key of a configuration, which includes its namespace
+        self.init_namespace_columns().await?;
+
+        // Candles are back-adjusted for these events and given the symbol's session
+        // calendar whenever they are loaded
+        self.init_price_adjustments_table().await?;
+        self.init_symbols_table().await?;
+
+        // Engine profiles that backtests run by the worker look up
+        self.init_engine_settings_table().await?;
+
+        // Parameters the stored values were calculated with, to detect edited configurations
+        sqlx::query(
+            "ALTER TABLE indicator_config 
+            ADD COLUMN IF NOT EXISTS parameters_hash TEXT, 
+            ADD COLUMN IF NOT EXISTS calculated_parameters JSONB"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Taker buy volume for order flow indicators; the candles table is created by the loader
+        sqlx::query("ALTER TABLE IF EXISTS binance_candles ADD COLUMN IF NOT EXISTS taker_buy_volume DOUBLE PRECISION")
+            .execute(&self.pool)
+            .await?;
+
         // Check if the calculated_indicators table already exists
         let table_exists = sqlx::query("SELECT EXISTS (SELECT FROM pg_tables WHERE tablename = 'calculated_indicators')")
             .fetch_one(&self.pool)
@@ -61,10 +135,7 @@ impl PostgresManager {
             .get::<bool, _>(0);
 
         // Check if TimescaleDB extension is available
-        let timescale_available = sqlx::query("SELECT COUNT(*) FROM pg_extension WHERE extname = 'timescaledb'")
-            .fetch_one(&self.pool)
-            .await?
-            .get::<i64, _>(0) > 0;
+        let timescale_available = self.timescale_available().await?;
 
         if !table_exists {
             // Create calculated_indicators table - WITHOUT unique index yet
@@ -86,21 +157,7 @@ impl PostgresManager {
 
             if timescale_available {
                 // Convert to hypertable BEFORE creating unique index
-                debug!("Converting to hypertable first before creating unique index");
-                match sqlx::query(
-                    "SELECT create_hypertable('calculated_indicators', 'time', if_not_exists => TRUE)"
-                )
-                .execute(&self.pool)
-                .await {
-                    Ok(_) => info!("Successfully created hypertable for calculated_indicators"),
-                    Err(e) => {
-                        if e.to_string().contains("already a hypertable") {
-                            debug!("Table is already a hypertable");
-                        } else {
-                            return Err(e.into());
-                        }
-                    }
-                }
+                self.create_indicator_hypertable().await?;
             }
 
             // Now create the unique index WITH the time column included
@@ -114,81 +171,789 @@ impl PostgresManager {
         }
 
         if timescale_available {
-            debug!("Setting up TimescaleDB compression policies");
-            
-            // Set up compression policy for calculated_indicators
-            let res = sqlx::query(
-                "ALTER TABLE calculated_indicators SET (
-                    timescaledb.compress,
-                    timescaledb.compress_segmentby = 'symbol,interval,indicator_name'
-                )"
+            self.enable_indicator_compression().await;
+        } else {
+            info!("TimescaleDB extension not available, skipping hypertable creation");
+        }
+
+        // Create indices for better query performance
+        debug!("Creating additional indices for query performance");
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_calculated_indicators_symbol_interval ON calculated_indicators(symbol, interval)"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_calculated_indicators_time ON calculated_indicators(time DESC)"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create the worker job history table with resource usage per job
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS worker_job_history (
+                id BIGSERIAL PRIMARY KEY,
+                pool VARCHAR NOT NULL,
+                symbol VARCHAR NOT NULL,
+                interval VARCHAR NOT NULL,
+                indicator_name VARCHAR NOT NULL,
+                parameters JSONB NOT NULL,
+                status VARCHAR NOT NULL,
+                error TEXT,
+                started_at TIMESTAMPTZ NOT NULL,
+                finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                wall_time_ms BIGINT NOT NULL,
+                cpu_time_ms BIGINT NOT NULL,
+                peak_memory_bytes BIGINT NOT NULL,
+                rows_read BIGINT NOT NULL,
+                rows_written BIGINT NOT NULL
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_worker_job_history_started_at ON worker_job_history(started_at DESC)"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Correlation ID of the job run, also found in logs, events and Redis job entries
+        sqlx::query("ALTER TABLE worker_job_history ADD COLUMN IF NOT EXISTS job_id UUID")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_worker_job_history_job_id ON worker_job_history(job_id)"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create the data quality issues table, one row per failed check and dataset
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS data_quality_issues (
+                id BIGSERIAL PRIMARY KEY,
+                symbol VARCHAR NOT NULL,
+                interval VARCHAR NOT NULL,
+                check_name VARCHAR NOT NULL,
+                detail TEXT NOT NULL,
+                occurrences BIGINT NOT NULL DEFAULT 1,
+                first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                UNIQUE(symbol, interval, check_name)
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.init_indicator_anomalies_table().await?;
+
+        // Create the failed jobs table for jobs that exhausted their retries
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS failed_jobs (
+                id BIGSERIAL PRIMARY KEY,
+                job_key TEXT NOT NULL UNIQUE,
+                pool VARCHAR NOT NULL,
+                symbol VARCHAR NOT NULL,
+                interval VARCHAR NOT NULL,
+                indicator_type VARCHAR NOT NULL,
+                indicator_name VARCHAR NOT NULL,
+                parameters JSONB NOT NULL,
+                attempts INTEGER NOT NULL,
+                error TEXT NOT NULL,
+                failure_count INTEGER NOT NULL DEFAULT 1,
+                first_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("ALTER TABLE failed_jobs ADD COLUMN IF NOT EXISTS job_id UUID")
+            .execute(&self.pool)
+            .await?;
+
+        // Last value per bucket of indicator values removed by retention policies
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS indicator_downsamples (
+                id BIGSERIAL PRIMARY KEY,
+                symbol VARCHAR NOT NULL,
+                interval VARCHAR NOT NULL,
+                bucket_interval VARCHAR NOT NULL,
+                indicator_type VARCHAR NOT NULL,
+                indicator_name VARCHAR NOT NULL,
+                parameters JSONB NOT NULL,
+                time TIMESTAMPTZ NOT NULL,
+                value JSONB NOT NULL,
+                UNIQUE(symbol, interval, bucket_interval, indicator_name, parameters, time)
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        info!("Database tables initialized successfully");
+        Ok(())
+    }
+
+    // Flag data quality issues found for a symbol and interval
+    pub async fn record_data_quality_issues(&self, symbol: &str, interval: &str, issues: &[QualityIssue]) -> Result<()> {
+        for issue in issues {
+            sqlx::query(
+                "INSERT INTO data_quality_issues (symbol, interval, check_name, detail) 
+                VALUES ($1, $2, $3, $4) 
+                ON CONFLICT (symbol, interval, check_name) 
+                DO UPDATE SET detail = EXCLUDED.detail, 
+                              occurrences = data_quality_issues.occurrences + 1, 
+                              last_seen_at = NOW()"
             )
+            .bind(symbol)
+            .bind(interval)
+            .bind(issue.check)
+            .bind(&issue.detail)
             .execute(&self.pool)
-            .await;
-            
-            if let Err(e) = res {
-                warn!("Failed to set compression properties: {}", e);
-                // Continue even if this fails
-            }
-            
-            // Add compression policy
-            let res = sqlx::query(
-                "SELECT add_compression_policy('calculated_indicators', INTERVAL '7 days', if_not_exists => TRUE)"
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    // Create the indicator anomalies table if it doesn't exist
+    pub async fn init_indicator_anomalies_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS indicator_anomalies (
+                id BIGSERIAL PRIMARY KEY,
+                symbol VARCHAR NOT NULL,
+                interval VARCHAR NOT NULL,
+                indicator_name VARCHAR NOT NULL,
+                parameters JSONB NOT NULL,
+                time TIMESTAMPTZ NOT NULL,
+                property VARCHAR NOT NULL DEFAULT '',
+                check_name VARCHAR NOT NULL,
+                value DOUBLE PRECISION NOT NULL,
+                detail TEXT NOT NULL,
+                detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                UNIQUE(symbol, interval, indicator_name, parameters, time, property, check_name)
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_indicator_anomalies_detected_at ON indicator_anomalies(detected_at)"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Flag suspicious values calculated by a job. Values flagged by an earlier run are
+    // left alone; returns the anomalies that were not recorded before.
+    pub async fn record_indicator_anomalies(&self, job: &CalculationJob, anomalies: Vec<IndicatorAnomaly>) -> Result<Vec<IndicatorAnomaly>> {
+        let mut recorded = Vec::new();
+        for anomaly in anomalies {
+            let inserted = sqlx::query(
+                "INSERT INTO indicator_anomalies 
+                (symbol, interval, indicator_name, parameters, time, property, check_name, value, detail) 
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) 
+                ON CONFLICT (symbol, interval, indicator_name, parameters, time, property, check_name) 
+                DO NOTHING"
             )
+            .bind(&job.symbol)
+            .bind(&job.interval)
+            .bind(&job.indicator_name)
+            .bind(&job.parameters)
+            .bind(anomaly.time)
+            .bind(anomaly.property.as_deref().unwrap_or(""))
+            .bind(anomaly.check)
+            .bind(anomaly.value)
+            .bind(&anomaly.detail)
             .execute(&self.pool)
-            .await;
-            
-            if let Err(e) = res {
-                if e.to_string().contains("already exists") {
-                    debug!("Compression policy already exists");
-                } else {
-                    warn!("Failed to add compression policy: {}", e);
-                }
-                // Continue even if this fails
+            .await?
+            .rows_affected();
+
+            if inserted > 0 {
+                recorded.push(anomaly);
             }
-        } else {
-            info!("TimescaleDB extension not available, skipping hypertable creation");
         }
 
-        // Create indices for better query performance
-        debug!("Creating additional indices for query performance");
+        Ok(recorded)
+    }
+
+    // Get the latest flagged values, optionally of one symbol and indicator
+    pub async fn get_indicator_anomalies(
+        &self,
+        symbol: Option<&str>,
+        indicator_name: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<IndicatorAnomalyRecord>> {
+        let anomalies = sqlx::query_as::<_, IndicatorAnomalyRecord>(
+            "SELECT id, symbol, interval, indicator_name, parameters, time, NULLIF(property, '') AS property, 
+                    check_name, value, detail, detected_at 
+            FROM indicator_anomalies 
+            WHERE ($1::text IS NULL OR symbol = $1) AND ($2::text IS NULL OR indicator_name = $2) 
+            ORDER BY detected_at DESC, time DESC 
+            LIMIT $3"
+        )
+        .bind(symbol)
+        .bind(indicator_name)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(anomalies)
+    }
+
+    // Record a job that failed after all retries, counting repeated failures
+    pub async fn insert_failed_job(&self, pool: &str, job: &CalculationJob, attempts: u32, error: &str) -> Result<()> {
+        sqlx::query(
+"INSERT INTO failed_jobs 
+            (job_key, pool, symbol, interval, indicator_type, indicator_name, parameters, attempts, error, job_id) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) 
+            ON CONFLICT (job_key) 
+            DO UPDATE SET pool = EXCLUDED.pool, 
+                          job_id = EXCLUDED.job_id, 
+                          attempts = EXCLUDED.attempts, 
+                          error = EXCLUDED.error, 
+                          failure_count = failed_jobs.failure_count + 1, 
+                          last_failed_at = NOW()"
+        )
+        .bind(job.cache_key())
+        .bind(pool)
+        .bind(&job.symbol)
+        .bind(&job.interval)
+        .bind(job.indicator_type.to_string())
+        .bind(&job.indicator_name)
+        .bind(&job.parameters)
+        .bind(attempts as i32)
+        .bind(error)
+        .bind(job.id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Get failed jobs, most recent failures first
+    pub async fn get_failed_jobs(&self, limit: i64) -> Result<Vec<FailedJob>> {
+        let jobs = sqlx::query_as::<_, FailedJob>(
+            "SELECT id, job_key, job_id, pool, symbol, interval, indicator_type, indicator_name, parameters, 
+                    attempts, error, failure_count, first_failed_at, last_failed_at 
+            FROM failed_jobs 
+            ORDER BY last_failed_at DESC 
+            LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    // Get the job keys of all failed jobs
+    pub async fn get_failed_job_keys(&self) -> Result<HashSet<String>> {
+        let rows = sqlx::query("SELECT job_key FROM failed_jobs")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    // Remove failed jobs so the producers pick them up again; None requeues every job
+    pub async fn delete_failed_jobs(&self, ids: Option<&[i64]>) -> Result<u64> {
+        let result = match ids {
+            Some(ids) => sqlx::query("DELETE FROM failed_jobs WHERE id = ANY($1)")
+                .bind(ids)
+                .execute(&self.pool)
+                .await?,
+            None => sqlx::query("DELETE FROM failed_jobs")
+                .execute(&self.pool)
+                .await?,
+        };
+
+        Ok(result.rows_affected())
+    }
+
+    // Record a processed worker job with its resource usage
+    pub async fn insert_job_history(
+        &self,
+        job_id: Uuid,
+        pool: &str,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+        status: &str,
+        error: Option<&str>,
+        started_at: DateTime<Utc>,
+        usage: &ResourceUsage,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO worker_job_history 
+            (pool, symbol, interval, indicator_name, parameters, status, error, started_at, 
+             wall_time_ms, cpu_time_ms, peak_memory_bytes, rows_read, rows_written, job_id) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)"
+        )
+        .bind(pool)
+        .bind(symbol)
+        .bind(interval)
+        .bind(indicator_name)
+        .bind(parameters)
+        .bind(status)
+        .bind(error)
+        .bind(started_at)
+        .bind(usage.wall_time_ms as i64)
+        .bind(usage.cpu_time_ms as i64)
+        .bind(usage.peak_memory_bytes as i64)
+        .bind(usage.rows_read as i64)
+        .bind(usage.rows_written as i64)
+        .bind(job_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Add the namespace column to strategies, indicator configurations and backtest results
+    //
+    // Existing rows move to the default namespace. An indicator configuration is unique
+    // within its namespace, so the same indicator can be configured in several; the
+    // calculated values themselves are shared. Tables not created yet are skipped.
+    pub async fn init_namespace_columns(&self) -> Result<()> {
+        for table in ["indicator_config", "strategies", "strategy_backtest_results"] {
+            sqlx::query(&format!(
+                "ALTER TABLE IF EXISTS {} ADD COLUMN IF NOT EXISTS namespace VARCHAR NOT NULL DEFAULT '{}'",
+                table, DEFAULT_NAMESPACE
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        let config_exists = sqlx::query("SELECT to_regclass('indicator_config') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?
+            .get::<bool, _>(0);
+        if config_exists {
+            // Replaced by the unique index below
+            sqlx::query(
+                "ALTER TABLE indicator_config 
+                DROP CONSTRAINT IF EXISTS indicator_config_symbol_interval_indicator_name_parameters_key"
+            )
+            .execute(&self.pool)
+            .await?;
+
+            sqlx::query(
+                "CREATE UNIQUE INDEX IF NOT EXISTS idx_indicator_config_namespace_unique 
+                ON indicator_config(namespace, symbol, interval, indicator_name, parameters)"
+            )
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    // Add the tags column to strategies, with an index for finding strategies by tag
+    pub async fn init_strategy_tags_column(&self) -> Result<()> {
+        let strategies_exist = sqlx::query("SELECT to_regclass('strategies') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await?
+            .get::<bool, _>(0);
+        if !strategies_exist {
+            return Ok(());
+        }
+
+        sqlx::query("ALTER TABLE strategies ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}'")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_strategies_tags ON strategies USING GIN (tags)")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // Add resource usage columns to the backtest results
+    pub async fn init_backtest_resource_columns(&self) -> Result<()> {
+        sqlx::query(
+            "ALTER TABLE strategy_backtest_results 
+             ADD COLUMN IF NOT EXISTS wall_time_ms BIGINT,
+             ADD COLUMN IF NOT EXISTS cpu_time_ms BIGINT,
+             ADD COLUMN IF NOT EXISTS peak_memory_bytes BIGINT,
+             ADD COLUMN IF NOT EXISTS rows_read BIGINT,
+             ADD COLUMN IF NOT EXISTS rows_written BIGINT"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Add benchmark and risk metric columns to the backtest results
+    pub async fn init_backtest_metric_columns(&self) -> Result<()> {
+        sqlx::query(
+            "ALTER TABLE strategy_backtest_results 
+             ADD COLUMN IF NOT EXISTS benchmark_return NUMERIC,
+             ADD COLUMN IF NOT EXISTS excess_return NUMERIC,
+             ADD COLUMN IF NOT EXISTS alpha NUMERIC,
+             ADD COLUMN IF NOT EXISTS beta NUMERIC,
+             ADD COLUMN IF NOT EXISTS sortino_ratio NUMERIC,
+             ADD COLUMN IF NOT EXISTS calmar_ratio NUMERIC,
+             ADD COLUMN IF NOT EXISTS value_at_risk NUMERIC,
+             ADD COLUMN IF NOT EXISTS expected_shortfall NUMERIC,
+             ADD COLUMN IF NOT EXISTS exposure_percent NUMERIC,
+             ADD COLUMN IF NOT EXISTS turnover NUMERIC,
+             ADD COLUMN IF NOT EXISTS unfilled_orders INTEGER"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Record the resource usage of a backtest
+    pub async fn update_backtest_resource_usage(&self, backtest_id: i32, usage: &ResourceUsage) -> Result<()> {
+        sqlx::query(
+            "UPDATE strategy_backtest_results 
+             SET wall_time_ms = $2, cpu_time_ms = $3, peak_memory_bytes = $4, rows_read = $5, rows_written = $6 
+             WHERE id = $1"
+        )
+        .bind(backtest_id)
+        .bind(usage.wall_time_ms as i64)
+        .bind(usage.cpu_time_ms as i64)
+        .bind(usage.peak_memory_bytes as i64)
+        .bind(usage.rows_read as i64)
+        .bind(usage.rows_written as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Create the engine settings table and link backtest results to engine profiles; run once
+    // when a process starts, as profile lookups and saves assume the table exists
+    pub async fn init_engine_settings_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS engine_settings (
+                name VARCHAR PRIMARY KEY,
+                description VARCHAR NOT NULL DEFAULT '',
+                settings JSONB NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Record which engine profile produced each backtest result
+        let res = sqlx::query(
+            "ALTER TABLE strategy_backtest_results
+                ADD COLUMN IF NOT EXISTS engine_profile VARCHAR,
+                ADD COLUMN IF NOT EXISTS engine_settings JSONB,
+                ADD COLUMN IF NOT EXISTS seed BIGINT"
+        )
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            warn!("Failed to add engine settings columns to strategy_backtest_results: {}", e);
+            // Continue even if this fails
+        }
+
+        Ok(())
+    }
+
+    // Create the symbols metadata table if it doesn't exist
+    pub async fn init_symbols_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS symbols (
+                symbol VARCHAR PRIMARY KEY,
+                base_asset VARCHAR,
+                quote_asset VARCHAR,
+                contract_type VARCHAR NOT NULL DEFAULT 'linear',
+                contract_multiplier DOUBLE PRECISION NOT NULL DEFAULT 1.0,
+                quanto_rate DOUBLE PRECISION,
+                settlement_asset VARCHAR,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Carry costs: funding payments for perpetuals and an annual borrow rate (percent) for shorts
+        sqlx::query(
+            "ALTER TABLE symbols
+                ADD COLUMN IF NOT EXISTS apply_funding BOOLEAN NOT NULL DEFAULT FALSE,
+                ADD COLUMN IF NOT EXISTS short_borrow_rate DOUBLE PRECISION"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Exchange metadata, and the 24h quote volume that volume watchlists rank by
+        sqlx::query(
+            "ALTER TABLE symbols
+                ADD COLUMN IF NOT EXISTS tick_size DOUBLE PRECISION,
+                ADD COLUMN IF NOT EXISTS listing_date TIMESTAMPTZ,
+                ADD COLUMN IF NOT EXISTS active BOOLEAN NOT NULL DEFAULT TRUE,
+                ADD COLUMN IF NOT EXISTS quote_volume DOUBLE PRECISION"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Trading session calendar (preset name or spec); NULL trades 24/7 on UTC days
+        sqlx::query("ALTER TABLE symbols ADD COLUMN IF NOT EXISTS session VARCHAR")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // Create the funding rates table if it doesn't exist
+    pub async fn init_funding_rates_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS funding_rates (
+                symbol VARCHAR NOT NULL,
+                funding_time TIMESTAMPTZ NOT NULL,
+                funding_rate DOUBLE PRECISION NOT NULL,
+                PRIMARY KEY (symbol, funding_time)
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Insert funding rates, replacing any already stored for the same time
+    pub async fn upsert_funding_rates(&self, rates: &[FundingRate]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for rate in rates {
+            sqlx::query(
+                "INSERT INTO funding_rates (symbol, funding_time, funding_rate)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (symbol, funding_time) DO UPDATE SET funding_rate = EXCLUDED.funding_rate"
+            )
+            .bind(&rate.symbol)
+            .bind(rate.funding_time)
+            .bind(rate.funding_rate)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Get the funding rates of a symbol in ascending time order, optionally within [start, end]
+    pub async fn get_funding_rates(
+        &self,
+        symbol: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<FundingRate>> {
+        let rates = sqlx::query_as::<_, FundingRate>(
+            "SELECT symbol, funding_time, funding_rate
+             FROM funding_rates
+             WHERE symbol = $1
+               AND ($2::timestamptz IS NULL OR funding_time >= $2)
+               AND ($3::timestamptz IS NULL OR funding_time <= $3)
+             ORDER BY funding_time"
+        )
+        .bind(symbol)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rates)
+    }
+
+    // Get the time of the latest stored funding rate of a symbol
+    pub async fn get_last_funding_time(&self, symbol: &str) -> Result<Option<DateTime<Utc>>> {
+        let time = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT MAX(funding_time) FROM funding_rates WHERE symbol = $1"
+        )
+        .bind(symbol)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(time)
+    }
+
+    // Get all enabled indicator configurations
+    pub async fn get_enabled_indicator_configs(&self) -> Result<Vec<IndicatorConfig>> {
+        let configs = sqlx::query_as::<_, IndicatorConfig>(
+            "SELECT id, symbol, interval, indicator_type, indicator_name, parameters, enabled, priority, created_at, updated_at 
+            FROM indicator_config 
+            WHERE enabled = TRUE AND namespace = $1"
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(configs)
+    }
+
+    /// Look up whether an indicator configuration exists and is enabled
+    /// Returns None when no configuration matches
+    pub async fn get_indicator_config_enabled(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<Option<bool>> {
+        let row = sqlx::query(
+            "SELECT enabled 
+            FROM indicator_config 
+            WHERE symbol = $1 AND interval = $2 AND indicator_name = $3 AND parameters = $4 AND namespace = $5"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(indicator_name)
+        .bind(parameters)
+        .bind(&self.namespace)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| row.get::<bool, _>(0)))
+    }
+
+    // Get all indicator configurations, including disabled ones
+    pub async fn get_all_indicator_configs(&self) -> Result<Vec<IndicatorConfig>> {
+        let configs = sqlx::query_as::<_, IndicatorConfig>(
+            "SELECT id, symbol, interval, indicator_type, indicator_name, parameters, enabled, priority, created_at, updated_at 
+            FROM indicator_config 
+            WHERE namespace = $1 
+            ORDER BY id"
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(configs)
+    }
+
+    /// Delete values calculated with parameters a configuration no longer has
+    ///
+    /// A configuration is edited when the hash of its parameters differs from the hash recorded
+    /// for its stored values. Configurations without a recorded hash (new, or created before
+    /// tracking) adopt their current parameters. Values still matching another configuration, in any
+    /// namespace, are kept.
+    pub async fn invalidate_changed_indicator_configs(&self) -> Result<Vec<InvalidatedIndicatorConfig>> {
+        let mut tx = self.pool.begin().await?;
+
         sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_calculated_indicators_symbol_interval ON calculated_indicators(symbol, interval)"
+            "UPDATE indicator_config 
+            SET parameters_hash = md5(parameters::text), calculated_parameters = parameters 
+            WHERE calculated_parameters IS NULL AND namespace = $1"
         )
-        .execute(&self.pool)
+        .bind(&self.namespace)
+        .execute(&mut *tx)
         .await?;
 
-        sqlx::query(
-            "CREATE INDEX IF NOT EXISTS idx_calculated_indicators_time ON calculated_indicators(time DESC)"
+        let rows = sqlx::query(
+            "SELECT id, symbol, interval, indicator_type, indicator_name, parameters, enabled, priority, 
+            created_at, updated_at, calculated_parameters 
+            FROM indicator_config 
+            WHERE parameters_hash IS DISTINCT FROM md5(parameters::text) AND namespace = $1 
+            FOR UPDATE"
         )
-        .execute(&self.pool)
+        .bind(&self.namespace)
+        .fetch_all(&mut *tx)
         .await?;
 
-        info!("Database tables initialized successfully");
-        Ok(())
+        let mut invalidated = Vec::with_capacity(rows.len());
+        for row in rows {
+            let config = IndicatorConfig::from_row(&row)?;
+            let previous_parameters: serde_json::Value = row.get("calculated_parameters");
+
+            let deleted = sqlx::query(
+                "DELETE FROM calculated_indicators 
+                WHERE symbol = $1 AND interval = $2 AND indicator_name = $3 AND parameters = $4 
+                  AND NOT EXISTS (
+                    SELECT 1 FROM indicator_config o 
+                    WHERE o.symbol = $1 AND o.interval = $2 AND o.indicator_name = $3 AND o.parameters = $4
+                  )"
+            )
+            .bind(&config.symbol)
+            .bind(&config.interval)
+            .bind(&config.indicator_name)
+            .bind(&previous_parameters)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            // Bump updated_at so the edit shows up in listings
+            sqlx::query(
+                "UPDATE indicator_config 
+                SET parameters_hash = md5(parameters::text), calculated_parameters = parameters, updated_at = NOW() 
+                WHERE id = $1"
+            )
+            .bind(config.id)
+            .execute(&mut *tx)
+            .await?;
+
+            invalidated.push(InvalidatedIndicatorConfig { config, previous_parameters, deleted_rows: deleted });
+        }
+
+        tx.commit().await?;
+
+        Ok(invalidated)
     }
 
-    // Get all enabled indicator configurations
-    pub async fn get_enabled_indicator_configs(&self) -> Result<Vec<IndicatorConfig>> {
-        let configs = sqlx::query_as::<_, IndicatorConfig>(
-            "SELECT id, symbol, interval, indicator_type, indicator_name, parameters, enabled, created_at, updated_at 
-            FROM indicator_config 
-            WHERE enabled = TRUE"
+    // Create an enabled indicator configuration
+    pub async fn insert_indicator_config(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_type: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<i32> {
+        let row = sqlx::query(
+            "INSERT INTO indicator_config (symbol, interval, indicator_type, indicator_name, parameters, enabled, namespace) 
+            VALUES ($1, $2, $3, $4, $5, TRUE, $6) 
+            RETURNING id"
         )
-        .fetch_all(&self.pool)
+        .bind(symbol)
+        .bind(interval)
+        .bind(indicator_type)
+        .bind(indicator_name)
+        .bind(parameters)
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
         .await?;
 
-        Ok(configs)
+        Ok(row.get(0))
+    }
+
+    // Enable or disable an indicator configuration of this namespace
+    pub async fn set_indicator_config_enabled(&self, id: i32, enabled: bool) -> Result<()> {
+        sqlx::query(
+            "UPDATE indicator_config SET enabled = $2, updated_at = NOW() WHERE id = $1 AND namespace = $3"
+        )
+        .bind(id)
+        .bind(enabled)
+        .bind(&self.namespace)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
     }
 
     // Get unique symbol-interval pairs from the configuration
-    #[allow(dead_code)]
     pub async fn get_unique_symbol_intervals(&self) -> Result<Vec<(String, String)>> {
         let rows = sqlx::query(
             "SELECT DISTINCT symbol, interval 
             FROM indicator_config 
-            WHERE enabled = TRUE"
+            WHERE enabled = TRUE AND namespace = $1"
         )
+        .bind(&self.namespace)
         .fetch_all(&self.pool)
         .await?;
 
@@ -202,15 +967,47 @@ impl PostgresManager {
 
     // Get candle data for a specific symbol and interval
     pub async fn get_candle_data(&self, symbol: &str, interval: &str) -> Result<CandleData> {
-        let candles = sqlx::query_as::<_, BinanceCandle>(
+        let mut candles = sqlx::query_as::<_, BinanceCandle>(
+            "SELECT id, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, 
+            close_time, quote_asset_volume, number_of_trades, taker_buy_volume 
+            FROM binance_candles 
+            WHERE symbol = $1 AND interval = $2 
+            ORDER BY open_time ASC"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if candles.is_empty() {
+            return Ok(CandleData::new(symbol.to_string(), interval.to_string()));
+        }
+
+        self.back_adjust_candles(symbol, &mut candles).await?;
+        Ok(CandleData::from_candles(candles).with_session(self.get_symbol_session(symbol).await?))
+    }
+
+    // Get candle data for a symbol and interval with open times in an optional range
+    pub async fn get_candle_data_between(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        let mut candles = sqlx::query_as::<_, BinanceCandle>(
             "SELECT id, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, 
-            close_time, quote_asset_volume, number_of_trades 
+            close_time, quote_asset_volume, number_of_trades, taker_buy_volume 
             FROM binance_candles 
             WHERE symbol = $1 AND interval = $2 
+              AND ($3::timestamptz IS NULL OR open_time >= $3) 
+              AND ($4::timestamptz IS NULL OR open_time <= $4) 
             ORDER BY open_time ASC"
         )
         .bind(symbol)
         .bind(interval)
+        .bind(start)
+        .bind(end)
         .fetch_all(&self.pool)
         .await?;
 
@@ -218,7 +1015,295 @@ impl PostgresManager {
             return Ok(CandleData::new(symbol.to_string(), interval.to_string()));
         }
 
-        Ok(CandleData::from_candles(candles))
+        self.back_adjust_candles(symbol, &mut candles).await?;
+        Ok(CandleData::from_candles(candles).with_session(self.get_symbol_session(symbol).await?))
+    }
+
+    // Get up to `limit` candles opening after `after`, oldest first (usize::MAX for all)
+    #[instrument(name = "db.load_candles", skip(self))]
+    async fn get_candles_after(
+        &self,
+        symbol: &str,
+        interval: &str,
+        after: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<BinanceCandle>> {
+        let mut candles = sqlx::query_as::<_, BinanceCandle>(
+            "SELECT id, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, 
+            close_time, quote_asset_volume, number_of_trades, taker_buy_volume 
+            FROM binance_candles 
+            WHERE symbol = $1 AND interval = $2 
+              AND ($3::timestamptz IS NULL OR open_time > $3) 
+            ORDER BY open_time ASC 
+            LIMIT $4"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(after)
+        // LIMIT NULL returns every remaining candle
+        .bind(i64::try_from(limit).ok())
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.back_adjust_candles(symbol, &mut candles).await?;
+        Ok(candles)
+    }
+
+    // Stream the candle history in windows of up to `chunk_size` new candles.
+    // Each window starts with the last `overlap` candles of the previous one so
+    // indicators with that lookback produce the same values as on the full history.
+    pub fn stream_candle_data<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: &'a str,
+        chunk_size: usize,
+        overlap: usize,
+    ) -> BoxStream<'a, Result<CandleData>> {
+        let state = (None::<DateTime<Utc>>, Vec::<BinanceCandle>::new());
+
+        Box::pin(futures::stream::try_unfold(state, move |(after, tail)| async move {
+            let candles = match self.get_candles_after(symbol, interval, after, chunk_size.max(1)).await {
+                Ok(candles) => candles,
+                Err(e) => return Err(e),
+            };
+
+            let last_open = match candles.last() {
+                Some(candle) => candle.open_time,
+                None => return Ok(None),
+            };
+
+            let session = self.get_symbol_session(symbol).await?;
+            let mut window = tail;
+            window.extend(candles);
+            let next_tail = window[window.len().saturating_sub(overlap)..].to_vec();
+
+            Ok(Some((CandleData::from_candles(window).with_session(session), (Some(last_open), next_tail))))
+        }))
+    }
+
+    // Latest open time and number of stored candles for a symbol and interval
+    pub async fn get_candle_summary(&self, symbol: &str, interval: &str) -> Result<(Option<DateTime<Utc>>, i64)> {
+        let row = sqlx::query(
+            "SELECT MAX(open_time), COUNT(*) 
+            FROM binance_candles 
+            WHERE symbol = $1 AND interval = $2"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.get(0), row.get(1)))
+    }
+
+    // Get all symbols with candle data
+    pub async fn get_candle_symbols(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT DISTINCT symbol FROM binance_candles ORDER BY symbol")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get(0)).collect())
+    }
+
+    // Get every symbol-interval pair with candle data
+    pub async fn get_candle_symbol_intervals(&self) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT symbol, interval 
+            FROM binance_candles 
+            ORDER BY symbol, interval"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    // Find gaps between consecutive candles larger than one interval step.
+    // Returns the open times of the candles on either side of each gap.
+    pub async fn find_candle_gaps(
+        &self,
+        symbol: &str,
+        interval: &str,
+        step_seconds: i64,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let rows = sqlx::query(
+            "SELECT previous_open, open_time 
+            FROM (
+                SELECT open_time, LAG(open_time) OVER (ORDER BY open_time) AS previous_open 
+                FROM binance_candles 
+                WHERE symbol = $1 AND interval = $2
+            ) t 
+            WHERE previous_open IS NOT NULL 
+              AND open_time - previous_open > make_interval(secs => $3) 
+            ORDER BY open_time"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(step_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    // Count open times that appear more than once for a symbol and interval
+    pub async fn count_duplicate_candles(&self, symbol: &str, interval: &str) -> Result<i64> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) 
+            FROM (
+                SELECT open_time 
+                FROM binance_candles 
+                WHERE symbol = $1 AND interval = $2 
+                GROUP BY open_time 
+                HAVING COUNT(*) > 1
+            ) t"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get(0))
+    }
+
+    // Count candles without any traded volume for a symbol and interval
+    pub async fn count_zero_volume_candles(&self, symbol: &str, interval: &str) -> Result<i64> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) 
+            FROM binance_candles 
+            WHERE symbol = $1 AND interval = $2 AND volume = 0"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get(0))
+    }
+
+    // Count indicator values before the cutoff, for one interval or all intervals except `exclude`
+    pub async fn count_indicators_before(
+        &self,
+        interval: Option<&str>,
+        exclude: &[String],
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) 
+            FROM calculated_indicators 
+            WHERE ($1::varchar IS NULL OR interval = $1) 
+              AND NOT (interval = ANY($2)) 
+              AND time < $3"
+        )
+        .bind(interval)
+        .bind(exclude)
+        .bind(cutoff)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get::<i64, _>(0) as u64)
+    }
+
+    // Keep the last value per bucket of an interval's indicator values before the cutoff
+    //
+    // A plain table rather than a continuous aggregate: deleting the source rows
+    // would invalidate the aggregate and empty its buckets on the next refresh.
+    pub async fn downsample_indicators_before(
+        &self,
+        interval: &str,
+        bucket_interval: &str,
+        bucket_seconds: i64,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            "INSERT INTO indicator_downsamples 
+            (symbol, interval, bucket_interval, indicator_type, indicator_name, parameters, time, value) 
+            SELECT DISTINCT ON (symbol, indicator_name, parameters, bucket) 
+                symbol, interval, $2, indicator_type, indicator_name, parameters, 
+                to_timestamp(floor(extract(epoch FROM time) / $3) * $3) AS bucket, value 
+            FROM calculated_indicators 
+            WHERE interval = $1 AND time < $4 
+            ORDER BY symbol, indicator_name, parameters, bucket, time DESC 
+            ON CONFLICT (symbol, interval, bucket_interval, indicator_name, parameters, time) 
+            DO UPDATE SET value = EXCLUDED.value"
+        )
+        .bind(interval)
+        .bind(bucket_interval)
+        .bind(bucket_seconds as f64)
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // Delete indicator values before the cutoff, for one interval or all intervals except `exclude`
+    pub async fn delete_indicators_before(
+        &self,
+        interval: Option<&str>,
+        exclude: &[String],
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM calculated_indicators 
+            WHERE ($1::varchar IS NULL OR interval = $1) 
+              AND NOT (interval = ANY($2)) 
+              AND time < $3"
+        )
+        .bind(interval)
+        .bind(exclude)
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // Insert or update a single candle
+    pub async fn upsert_candle(&self, candle: &BinanceCandle) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO binance_candles 
+            (symbol, interval, open_time, open_price, high_price, low_price, close_price, volume, 
+             close_time, quote_asset_volume, number_of_trades, taker_buy_volume) 
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (symbol, interval, open_time) 
+            DO UPDATE SET open_price = EXCLUDED.open_price, high_price = EXCLUDED.high_price, 
+                low_price = EXCLUDED.low_price, close_price = EXCLUDED.close_price, volume = EXCLUDED.volume, 
+                close_time = EXCLUDED.close_time, quote_asset_volume = EXCLUDED.quote_asset_volume, 
+                number_of_trades = EXCLUDED.number_of_trades, 
+                taker_buy_volume = COALESCE(EXCLUDED.taker_buy_volume, binance_candles.taker_buy_volume)"
+        )
+        .bind(&candle.symbol)
+        .bind(&candle.interval)
+        .bind(candle.open_time)
+        .bind(candle.open_price)
+        .bind(candle.high_price)
+        .bind(candle.low_price)
+        .bind(candle.close_price)
+        .bind(candle.volume)
+        .bind(candle.close_time)
+        .bind(candle.quote_asset_volume)
+        .bind(candle.number_of_trades)
+        .bind(candle.taker_buy_volume)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Delete a symbol's values of indicators that reset at session boundaries, so they are
+    // recalculated with a changed session calendar
+    pub async fn delete_session_indicator_values(&self, symbol: &str) -> Result<u64> {
+        let deleted = sqlx::query(
+            "DELETE FROM calculated_indicators 
+            WHERE symbol = $1 AND (indicator_name = 'ANCHORED_VWAP' OR parameters->>'session' = 'day')"
+        )
+        .bind(symbol)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(deleted)
     }
 
     // Get the last calculated time for a specific indicator
@@ -249,7 +1334,55 @@ impl PostgresManager {
         }
     }
 
+    // Stream a calculated indicator series in time order without buffering it in memory
+    pub fn stream_calculated_indicators<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: &'a str,
+        indicator_name: &'a str,
+        parameters: &'a serde_json::Value,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> BoxStream<'a, Result<(DateTime<Utc>, serde_json::Value), sqlx::Error>> {
+        sqlx::query_as::<_, (DateTime<Utc>, serde_json::Value)>(
+            "SELECT time, value 
+            FROM calculated_indicators 
+            WHERE symbol = $1 AND interval = $2 AND indicator_name = $3 AND parameters = $4 
+              AND ($5::timestamptz IS NULL OR time >= $5) 
+              AND ($6::timestamptz IS NULL OR time <= $6) 
+            ORDER BY time ASC"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(indicator_name)
+        .bind(parameters)
+        .bind(start)
+        .bind(end)
+        .fetch(&self.pool)
+    }
+
+    // Get a calculated indicator series, optionally limited to times within [start, end]
+    pub async fn get_indicator_series(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<IndicatorSeries> {
+        let mut series = IndicatorSeries::new(symbol, interval, indicator_name, parameters);
+        let mut rows = self.stream_calculated_indicators(symbol, interval, indicator_name, parameters, start, end);
+
+        while let Some((time, value)) = rows.try_next().await? {
+            series.push(time, value);
+        }
+
+        Ok(series)
+    }
+
     // Batch insert calculated indicators
+    #[instrument(name = "db.insert_indicators", skip_all, fields(rows = batch.len()))]
     pub async fn insert_calculated_indicators_batch(
         &self,
         batch: Vec<CalculatedIndicatorBatch>,
@@ -312,7 +1445,7 @@ impl PostgresManager {
 
         match (first, last) {
             (Some(first), Some(last)) => Ok((first, last)),
-            _ => Err(anyhow::anyhow!("No candle data found for {}:{}", symbol, interval)),
+            _ => Err(Error::not_found(format!("Candle data for {}:{}", symbol, interval))),
         }
     }
 
@@ -342,3 +1475,109 @@ impl PostgresManager {
         Ok((last_time, count))
     }
 }
+
+// TimescaleDB stores calculated_indicators as a compressed hypertable
+#[cfg(feature = "timescaledb")]
+impl PostgresManager {
+    // Check if the TimescaleDB extension is installed
+    pub async fn timescale_available(&self) -> Result<bool> {
+        let count = sqlx::query("SELECT COUNT(*) FROM pg_extension WHERE extname = 'timescaledb'")
+            .fetch_one(&self.pool)
+            .await?
+            .get::<i64, _>(0);
+
+        Ok(count > 0)
+    }
+
+    // Convert calculated_indicators to a hypertable; must run before the unique index is created
+    async fn create_indicator_hypertable(&self) -> Result<()> {
+        debug!("Converting to hypertable first before creating unique index");
+        match sqlx::query(
+            "SELECT create_hypertable('calculated_indicators', 'time', if_not_exists => TRUE)"
+        )
+        .execute(&self.pool)
+        .await {
+            Ok(_) => info!("Successfully created hypertable for calculated_indicators"),
+            Err(e) => {
+                if e.to_string().contains("already a hypertable") {
+                    debug!("Table is already a hypertable");
+                } else {
+                    return Err(e.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Replace the TimescaleDB job dropping indicator chunks older than `keep_days`; None removes it
+    pub async fn set_indicator_retention_job(&self, keep_days: Option<u32>) -> Result<()> {
+        sqlx::query("SELECT remove_retention_policy('calculated_indicators', if_exists => TRUE)")
+            .execute(&self.pool)
+            .await?;
+
+        if let Some(days) = keep_days {
+            sqlx::query("SELECT add_retention_policy('calculated_indicators', make_interval(days => $1))")
+                .bind(days as i32)
+                .execute(&self.pool)
+                .await?;
+            info!("TimescaleDB retention job drops calculated_indicators chunks older than {} days", days);
+        }
+
+        Ok(())
+    }
+
+    // Compress indicator chunks older than 7 days; failures only disable compression
+    async fn enable_indicator_compression(&self) {
+        debug!("Setting up TimescaleDB compression policies");
+
+        // Set up compression policy for calculated_indicators
+        let res = sqlx::query(
+            "ALTER TABLE calculated_indicators SET (
+                timescaledb.compress,
+                timescaledb.compress_segmentby = 'symbol,interval,indicator_name'
+            )"
+        )
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            warn!("Failed to set compression properties: {}", e);
+            // Continue even if this fails
+        }
+
+        // Add compression policy
+        let res = sqlx::query(
+            "SELECT add_compression_policy('calculated_indicators', INTERVAL '7 days', if_not_exists => TRUE)"
+        )
+        .execute(&self.pool)
+        .await;
+
+        if let Err(e) = res {
+            if e.to_string().contains("already exists") {
+                debug!("Compression policy already exists");
+            } else {
+                warn!("Failed to add compression policy: {}", e);
+            }
+            // Continue even if this fails
+        }
+    }
+}
+
+// Without the timescaledb feature calculated_indicators stays a plain table
+#[cfg(not(feature = "timescaledb"))]
+impl PostgresManager {
+    pub async fn timescale_available(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn create_indicator_hypertable(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn enable_indicator_compression(&self) {}
+
+    pub async fn set_indicator_retention_job(&self, _keep_days: Option<u32>) -> Result<()> {
+        Err(Error::config("TimescaleDB support is not compiled in"))
+    }
+}
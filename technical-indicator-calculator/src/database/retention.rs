@@ -0,0 +1,183 @@
+// src/database/retention.rs
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::utils::utils::interval_to_seconds;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Interval of a policy that applies to every interval without a policy of its own
+pub const ALL_INTERVALS: &str = "*";
+
+/// How long calculated indicator values of one interval are kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionPolicy {
+    /// Candle interval the policy applies to, or "*" for all other intervals
+    pub interval: String,
+    /// Values older than this many days are removed from calculated_indicators
+    pub keep_days: u32,
+    /// Keep the last value per bucket of this interval (e.g. "1h") in indicator_downsamples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downsample_to: Option<String>,
+}
+
+impl RetentionPolicy {
+    /// Parse a policy list such as `1m:30:1h;5m:90;*:365` (interval:keep_days[:downsample_to])
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>> {
+        let mut policies = Vec::new();
+
+        for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+            if parts.len() < 2 || parts.len() > 3 {
+                return Err(Error::config(format!(
+                    "Invalid retention policy '{}', expected interval:keep_days[:downsample_to]", entry
+                )));
+            }
+
+            let keep_days = parts[1].parse::<u32>()
+                .map_err(|_| Error::config(format!("Invalid keep_days for retention policy '{}': {}", parts[0], parts[1])))?;
+
+            policies.push(Self {
+                interval: parts[0].to_string(),
+                keep_days,
+                downsample_to: parts.get(2).map(|s| s.to_string()),
+            });
+        }
+
+        validate_policies(&policies)?;
+        Ok(policies)
+    }
+
+    /// Values with times before this are expired
+    pub fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.keep_days as i64)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.interval != ALL_INTERVALS && interval_to_seconds(&self.interval).is_none() {
+            return Err(Error::config(format!("Unknown interval in retention policy: {}", self.interval)));
+        }
+
+        if let Some(bucket) = &self.downsample_to {
+            if self.interval == ALL_INTERVALS {
+                return Err(Error::config("The '*' retention policy cannot downsample"));
+            }
+
+            let bucket_seconds = interval_to_seconds(bucket)
+                .ok_or_else(|| Error::config(format!("Unknown downsample interval in retention policy: {}", bucket)))?;
+            let interval_seconds = interval_to_seconds(&self.interval).unwrap_or_default();
+            if bucket_seconds <= interval_seconds {
+                return Err(Error::config(format!(
+                    "Retention policy for {} must downsample to a longer interval, got {}", self.interval, bucket
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check every policy and reject duplicate intervals
+pub fn validate_policies(policies: &[RetentionPolicy]) -> Result<()> {
+    for (i, policy) in policies.iter().enumerate() {
+        policy.validate()?;
+
+        if policies[..i].iter().any(|p| p.interval == policy.interval) {
+            return Err(Error::config(format!("Duplicate retention policy for interval {}", policy.interval)));
+        }
+    }
+
+    Ok(())
+}
+
+/// What applying (or previewing) one policy did
+#[derive(Debug, Clone)]
+pub struct RetentionOutcome {
+    pub policy: RetentionPolicy,
+    pub cutoff: DateTime<Utc>,
+    /// Values older than the cutoff before the policy ran
+    pub expired: u64,
+    pub downsampled: u64,
+    pub deleted: u64,
+    /// Expiry is left to a TimescaleDB retention job instead of deleting rows
+    pub timescale_job: bool,
+}
+
+/// Downsample and delete expired indicator values; with `dry_run` only count them
+///
+/// Interval policies delete rows directly. The "*" policy is registered as a
+/// TimescaleDB retention job (which drops whole chunks) when the extension is
+/// available and every interval policy expires values sooner; otherwise it deletes
+/// the rows of every interval without its own policy.
+pub async fn apply_retention(pg: &PostgresManager, policies: &[RetentionPolicy], dry_run: bool) -> Result<Vec<RetentionOutcome>> {
+    validate_policies(policies)?;
+
+    let now = Utc::now();
+    let explicit: Vec<String> = policies.iter()
+        .filter(|p| p.interval != ALL_INTERVALS)
+        .map(|p| p.interval.clone())
+        .collect();
+
+    let timescale = pg.timescale_available().await?;
+    let mut timescale_job_set = false;
+    let mut outcomes = Vec::with_capacity(policies.len());
+
+    for policy in policies {
+        let cutoff = policy.cutoff(now);
+        let (interval, exclude) = if policy.interval == ALL_INTERVALS {
+            (None, explicit.as_slice())
+        } else {
+            (Some(policy.interval.as_str()), &[][..])
+        };
+
+        let expired = pg.count_indicators_before(interval, exclude, cutoff).await?;
+        let mut outcome = RetentionOutcome {
+            policy: policy.clone(),
+            cutoff,
+            expired,
+            downsampled: 0,
+            deleted: 0,
+            timescale_job: false,
+        };
+
+        let use_timescale_job = interval.is_none()
+            && timescale
+            && policies.iter()
+                .filter(|p| p.interval != ALL_INTERVALS)
+                .all(|p| p.keep_days < policy.keep_days);
+
+        if use_timescale_job {
+            outcome.timescale_job = true;
+            if !dry_run {
+                pg.set_indicator_retention_job(Some(policy.keep_days)).await?;
+                timescale_job_set = true;
+            }
+            outcomes.push(outcome);
+            continue;
+        }
+
+        if dry_run || expired == 0 {
+            outcomes.push(outcome);
+            continue;
+        }
+
+        if let (Some(interval), Some(bucket)) = (interval, &policy.downsample_to) {
+            let bucket_seconds = interval_to_seconds(bucket).unwrap_or_default();
+            outcome.downsampled = pg.downsample_indicators_before(interval, bucket, bucket_seconds, cutoff).await?;
+        }
+
+        outcome.deleted = pg.delete_indicators_before(interval, exclude, cutoff).await?;
+
+        info!("Retention for {}: {} values before {} deleted, {} downsampled buckets",
+              policy.interval, outcome.deleted, cutoff.format("%Y-%m-%d"), outcome.downsampled);
+        outcomes.push(outcome);
+    }
+
+    // A job left over from an earlier "*" policy would keep dropping chunks
+    if timescale && !dry_run && !timescale_job_set {
+        pg.set_indicator_retention_job(None).await?;
+    }
+
+    Ok(outcomes)
+}
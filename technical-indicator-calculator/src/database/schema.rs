@@ -16,6 +16,7 @@
 //     close_time TIMESTAMPTZ NOT NULL,
 //     quote_asset_volume DOUBLE PRECISION NOT NULL,
 //     number_of_trades INTEGER NOT NULL,
+//     taker_buy_volume DOUBLE PRECISION,
 //     UNIQUE(symbol, interval, open_time)
 // );
 // "#;
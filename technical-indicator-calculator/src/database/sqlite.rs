@@ -0,0 +1,476 @@
+// src/database/sqlite.rs
+use crate::database::models::{
+    BinanceCandle, CalculatedIndicatorBatch, CandleData, IndicatorConfig, IndicatorSeries,
+    StrategyIndicatorRecord, StrategyRecord, StrategyRuleRecord,
+};
+use crate::database::storage::Storage;
+use crate::error::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Row;
+use std::str::FromStr;
+use tracing::{error, info};
+
+/// Local SQLite storage for backtesting without PostgreSQL
+///
+/// Timestamps are stored as RFC 3339 text and JSON columns as text, so range
+/// filters and parameter lookups compare the same encodings sqlx writes.
+pub struct SqliteManager {
+    pub pool: SqlitePool,
+}
+
+impl SqliteManager {
+    /// Open (or create) the database file
+    pub async fn new(path: &str, max_connections: usize) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path))?
+            .create_if_missing(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections as u32)
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                error!("Failed to open SQLite database {}: {}", path, e);
+                e
+            })?;
+
+        info!("Opened SQLite database at {}", path);
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteManager {
+    fn backend(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn init(&self) -> Result<()> {
+        let statements = [
+            "CREATE TABLE IF NOT EXISTS binance_candles (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                interval TEXT NOT NULL,
+                open_time TEXT NOT NULL,
+                open_price REAL NOT NULL,
+                high_price REAL NOT NULL,
+                low_price REAL NOT NULL,
+                close_price REAL NOT NULL,
+                volume REAL NOT NULL,
+                close_time TEXT NOT NULL,
+                quote_asset_volume REAL NOT NULL,
+                number_of_trades INTEGER NOT NULL,
+                taker_buy_volume REAL,
+                UNIQUE(symbol, interval, open_time)
+            )",
+            "CREATE TABLE IF NOT EXISTS indicator_config (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                interval TEXT NOT NULL,
+                indicator_type TEXT NOT NULL,
+                indicator_name TEXT NOT NULL,
+                parameters TEXT NOT NULL,
+                enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                priority INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(symbol, interval, indicator_name, parameters)
+            )",
+            "CREATE TABLE IF NOT EXISTS calculated_indicators (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                symbol TEXT NOT NULL,
+                interval TEXT NOT NULL,
+                indicator_type TEXT NOT NULL,
+                indicator_name TEXT NOT NULL,
+                parameters TEXT NOT NULL,
+                time TEXT NOT NULL,
+                value TEXT NOT NULL,
+                UNIQUE(symbol, interval, indicator_name, parameters, time)
+            )",
+            "CREATE TABLE IF NOT EXISTS strategies (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                version TEXT NOT NULL,
+                author TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                enabled BOOLEAN NOT NULL DEFAULT TRUE,
+                assets TEXT NOT NULL,
+                timeframes TEXT NOT NULL,
+                tags TEXT NOT NULL DEFAULT '[]',
+                parameters TEXT NOT NULL,
+                risk_management TEXT NOT NULL,
+                metadata TEXT
+            )",
+            "CREATE TABLE IF NOT EXISTS strategy_indicators (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                strategy_id TEXT NOT NULL REFERENCES strategies(id) ON DELETE CASCADE,
+                indicator_id TEXT NOT NULL,
+                indicator_type TEXT NOT NULL,
+                indicator_name TEXT NOT NULL,
+                parameters TEXT NOT NULL,
+                description TEXT,
+                UNIQUE(strategy_id, indicator_id)
+            )",
+            "CREATE TABLE IF NOT EXISTS strategy_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                strategy_id TEXT NOT NULL REFERENCES strategies(id) ON DELETE CASCADE,
+                rule_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                condition TEXT NOT NULL,
+                action TEXT NOT NULL,
+                priority INTEGER NOT NULL DEFAULT 0,
+                description TEXT,
+                UNIQUE(strategy_id, rule_id)
+            )",
+        ];
+
+        for statement in statements {
+            sqlx::query(statement).execute(&self.pool).await?;
+        }
+
+        // Databases created before taker buy volume was stored
+        let has_taker_volume = sqlx::query("SELECT 1 FROM pragma_table_info('binance_candles') WHERE name = 'taker_buy_volume'")
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+        if !has_taker_volume {
+            sqlx::query("ALTER TABLE binance_candles ADD COLUMN taker_buy_volume REAL")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        // Databases created before strategies had tags
+        let has_tags = sqlx::query("SELECT 1 FROM pragma_table_info('strategies') WHERE name = 'tags'")
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+        if !has_tags {
+            sqlx::query("ALTER TABLE strategies ADD COLUMN tags TEXT NOT NULL DEFAULT '[]'")
+                .execute(&self.pool)
+                .await?;
+        }
+
+        info!("SQLite tables initialized successfully");
+        Ok(())
+    }
+
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        let candles = sqlx::query_as::<_, BinanceCandle>(
+            "SELECT id, symbol, interval, open_time, open_price, high_price, low_price, close_price, volume,
+            close_time, quote_asset_volume, number_of_trades, taker_buy_volume
+            FROM binance_candles
+            WHERE symbol = ?1 AND interval = ?2
+              AND (?3 IS NULL OR open_time >= ?3)
+              AND (?4 IS NULL OR open_time <= ?4)
+            ORDER BY open_time ASC"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if candles.is_empty() {
+            return Ok(CandleData::new(symbol.to_string(), interval.to_string()));
+        }
+
+        Ok(CandleData::from_candles(candles))
+    }
+
+    async fn get_candle_symbol_intervals(&self) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query("SELECT DISTINCT symbol, interval FROM binance_candles ORDER BY symbol, interval")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn upsert_candles(&self, candles: &[BinanceCandle]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for candle in candles {
+            sqlx::query(
+                "INSERT INTO binance_candles
+                (symbol, interval, open_time, open_price, high_price, low_price, close_price, volume,
+                 close_time, quote_asset_volume, number_of_trades, taker_buy_volume)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                ON CONFLICT (symbol, interval, open_time)
+                DO UPDATE SET open_price = excluded.open_price, high_price = excluded.high_price,
+                    low_price = excluded.low_price, close_price = excluded.close_price, volume = excluded.volume,
+                    close_time = excluded.close_time, quote_asset_volume = excluded.quote_asset_volume,
+                    number_of_trades = excluded.number_of_trades,
+                    taker_buy_volume = COALESCE(excluded.taker_buy_volume, binance_candles.taker_buy_volume)"
+            )
+            .bind(&candle.symbol)
+            .bind(&candle.interval)
+            .bind(candle.open_time)
+            .bind(candle.open_price)
+            .bind(candle.high_price)
+            .bind(candle.low_price)
+            .bind(candle.close_price)
+            .bind(candle.volume)
+            .bind(candle.close_time)
+            .bind(candle.quote_asset_volume)
+            .bind(candle.number_of_trades)
+            .bind(candle.taker_buy_volume)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_enabled_indicator_configs(&self) -> Result<Vec<IndicatorConfig>> {
+        let configs = sqlx::query_as::<_, IndicatorConfig>(
+            "SELECT id, symbol, interval, indicator_type, indicator_name, parameters, enabled, priority, created_at, updated_at
+            FROM indicator_config
+            WHERE enabled = TRUE"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(configs)
+    }
+
+    async fn get_last_calculated_time(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<Option<DateTime<Utc>>> {
+        // Select the column itself rather than MAX() so the value decodes as a timestamp
+        let row = sqlx::query(
+            "SELECT time
+            FROM calculated_indicators
+            WHERE symbol = ?1 AND interval = ?2 AND indicator_name = ?3 AND parameters = ?4
+            ORDER BY time DESC
+            LIMIT 1"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(indicator_name)
+        .bind(parameters)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| row.get("time")))
+    }
+
+    async fn insert_calculated_indicators_batch(&self, batch: Vec<CalculatedIndicatorBatch>) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        for indicator in batch {
+            let result = sqlx::query(
+                "INSERT INTO calculated_indicators
+                (symbol, interval, indicator_type, indicator_name, parameters, time, value)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT (symbol, interval, indicator_name, parameters, time)
+                DO UPDATE SET value = excluded.value"
+            )
+            .bind(&indicator.symbol)
+            .bind(&indicator.interval)
+            .bind(&indicator.indicator_type)
+            .bind(&indicator.indicator_name)
+            .bind(&indicator.parameters)
+            .bind(indicator.time)
+            .bind(&indicator.value)
+            .execute(&mut *tx)
+            .await;
+
+            if let Err(e) = result {
+                error!("Error inserting indicator: {}", e);
+                // Continue with the rest of the batch
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_indicator_series(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<IndicatorSeries> {
+        let rows = sqlx::query_as::<_, (DateTime<Utc>, serde_json::Value)>(
+            "SELECT time, value
+            FROM calculated_indicators
+            WHERE symbol = ?1 AND interval = ?2 AND indicator_name = ?3 AND parameters = ?4
+              AND (?5 IS NULL OR time >= ?5)
+              AND (?6 IS NULL OR time <= ?6)
+            ORDER BY time ASC"
+        )
+        .bind(symbol)
+        .bind(interval)
+        .bind(indicator_name)
+        .bind(parameters)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut series = IndicatorSeries::new(symbol, interval, indicator_name, parameters);
+        for (time, value) in rows {
+            series.push(time, value);
+        }
+
+        Ok(series)
+    }
+
+    async fn list_strategy_ids(&self, enabled_only: bool) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT id FROM strategies WHERE enabled OR NOT ?1 ORDER BY name")
+            .bind(enabled_only)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    async fn get_strategy_record(&self, id: &str) -> Result<Option<StrategyRecord>> {
+        let record = sqlx::query_as::<_, StrategyRecord>(
+            "SELECT id, name, description, version, author, created_at, updated_at,
+                    enabled, assets, timeframes, tags, parameters, risk_management, metadata
+             FROM strategies
+             WHERE id = ?1"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    async fn get_strategy_indicator_records(&self, strategy_id: &str) -> Result<Vec<StrategyIndicatorRecord>> {
+        let records = sqlx::query_as::<_, StrategyIndicatorRecord>(
+            "SELECT indicator_id, indicator_type, indicator_name, parameters, description
+             FROM strategy_indicators
+             WHERE strategy_id = ?1
+             ORDER BY indicator_id"
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    async fn get_strategy_rule_records(&self, strategy_id: &str) -> Result<Vec<StrategyRuleRecord>> {
+        let records = sqlx::query_as::<_, StrategyRuleRecord>(
+            "SELECT rule_id, name, condition, action, priority, description
+             FROM strategy_rules
+             WHERE strategy_id = ?1
+             ORDER BY priority"
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    async fn save_strategy_records(
+        &self,
+        strategy: &StrategyRecord,
+        indicators: &[StrategyIndicatorRecord],
+        rules: &[StrategyRuleRecord],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // created_at is kept when the strategy already exists
+        sqlx::query(
+            "INSERT INTO strategies
+             (id, name, description, version, author, created_at, updated_at,
+              enabled, assets, timeframes, parameters, risk_management, metadata, tags)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+             ON CONFLICT (id) DO UPDATE
+             SET name = excluded.name, description = excluded.description,
+                 version = excluded.version, author = excluded.author,
+                 updated_at = excluded.updated_at, enabled = excluded.enabled,
+                 assets = excluded.assets, timeframes = excluded.timeframes, tags = excluded.tags,
+                 parameters = excluded.parameters, risk_management = excluded.risk_management,
+                 metadata = excluded.metadata"
+        )
+        .bind(&strategy.id)
+        .bind(&strategy.name)
+        .bind(&strategy.description)
+        .bind(&strategy.version)
+        .bind(&strategy.author)
+        .bind(strategy.created_at)
+        .bind(strategy.updated_at)
+        .bind(strategy.enabled)
+        .bind(&strategy.assets)
+        .bind(&strategy.timeframes)
+        .bind(&strategy.parameters)
+        .bind(&strategy.risk_management)
+        .bind(&strategy.metadata)
+        .bind(&strategy.tags)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM strategy_indicators WHERE strategy_id = ?1")
+            .bind(&strategy.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for indicator in indicators {
+            sqlx::query(
+                "INSERT INTO strategy_indicators
+                 (strategy_id, indicator_id, indicator_type, indicator_name, parameters, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            )
+            .bind(&strategy.id)
+            .bind(&indicator.indicator_id)
+            .bind(&indicator.indicator_type)
+            .bind(&indicator.indicator_name)
+            .bind(&indicator.parameters)
+            .bind(&indicator.description)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("DELETE FROM strategy_rules WHERE strategy_id = ?1")
+            .bind(&strategy.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for rule in rules {
+            sqlx::query(
+                "INSERT INTO strategy_rules
+                 (strategy_id, rule_id, name, condition, action, priority, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            )
+            .bind(&strategy.id)
+            .bind(&rule.rule_id)
+            .bind(&rule.name)
+            .bind(&rule.condition)
+            .bind(&rule.action)
+            .bind(rule.priority)
+            .bind(&rule.description)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
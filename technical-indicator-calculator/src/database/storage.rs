@@ -0,0 +1,224 @@
+// src/database/storage.rs
+use crate::config::DatabaseSettings;
+use crate::database::models::{
+    AlignedFrame, BinanceCandle, CalculatedIndicatorBatch, CandleData, FrameIndicator, IndicatorConfig,
+    IndicatorSeries, StrategyIndicatorRecord, StrategyRecord, StrategyRuleRecord,
+};
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+/// Candle, indicator and strategy storage shared by the PostgreSQL and SQLite backends
+///
+/// Features that depend on PostgreSQL (cluster coordination, archival, job history)
+/// keep using `PostgresManager` directly.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Short name of the backend used in logs (e.g. "postgres")
+    fn backend(&self) -> &str;
+
+    /// Create the tables this trait reads and writes if they don't exist
+    async fn init(&self) -> Result<()>;
+
+    /// Get candles for a symbol and interval in ascending time order,
+    /// optionally limited to open times within [start, end]
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData>;
+
+    /// List the symbol/interval pairs that have candles
+    async fn get_candle_symbol_intervals(&self) -> Result<Vec<(String, String)>>;
+
+    /// Insert candles, replacing existing candles with the same open time
+    async fn upsert_candles(&self, candles: &[BinanceCandle]) -> Result<()>;
+
+    /// Get all enabled indicator configurations
+    async fn get_enabled_indicator_configs(&self) -> Result<Vec<IndicatorConfig>>;
+
+    /// Get the time of the latest calculated value of an indicator
+    async fn get_last_calculated_time(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<Option<DateTime<Utc>>>;
+
+    /// Store calculated indicator values, replacing existing values at the same time
+    async fn insert_calculated_indicators_batch(&self, batch: Vec<CalculatedIndicatorBatch>) -> Result<()>;
+
+    /// Get the stored values of an indicator in ascending time order,
+    /// optionally limited to times within [start, end]
+    async fn get_indicator_series(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<IndicatorSeries>;
+
+    /// Get candles together with stored indicator values aligned to each candle's open time
+    ///
+    /// Candles without a stored value of an indicator get `None` for it, so callers
+    /// can tell warmup periods and calculation gaps apart from real values.
+    async fn get_aligned_frame(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicators: &[FrameIndicator],
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<AlignedFrame> {
+        let candles = self.get_candles(symbol, interval, start, end).await?;
+        let mut frame = AlignedFrame::new(candles);
+
+        // Only the candle range is needed, even when the caller left the bounds open
+        let first = frame.candles.open_time.first().copied();
+        let last = frame.candles.open_time.last().copied();
+
+        for indicator in indicators {
+            let series = match (first, last) {
+                (Some(first), Some(last)) => self.get_indicator_series(
+                    symbol, interval, &indicator.indicator_name, &indicator.parameters, Some(first), Some(last),
+                ).await?,
+                _ => IndicatorSeries::new(symbol, interval, &indicator.indicator_name, &indicator.parameters),
+            };
+            frame.insert_series(&indicator.id, &series);
+        }
+
+        Ok(frame)
+    }
+
+    /// List strategy IDs ordered by name
+    async fn list_strategy_ids(&self, enabled_only: bool) -> Result<Vec<String>>;
+
+    /// Get the header of a strategy, without indicators and rules
+    async fn get_strategy_record(&self, id: &str) -> Result<Option<StrategyRecord>>;
+
+    /// Get the indicators of a strategy
+    async fn get_strategy_indicator_records(&self, strategy_id: &str) -> Result<Vec<StrategyIndicatorRecord>>;
+
+    /// Get the rules of a strategy in evaluation order
+    async fn get_strategy_rule_records(&self, strategy_id: &str) -> Result<Vec<StrategyRuleRecord>>;
+
+    /// Insert or replace a strategy together with its indicators and rules
+    async fn save_strategy_records(
+        &self,
+        strategy: &StrategyRecord,
+        indicators: &[StrategyIndicatorRecord],
+        rules: &[StrategyRuleRecord],
+    ) -> Result<()>;
+}
+
+impl DatabaseSettings {
+    /// Connect to the configured storage backend ("postgres" or "sqlite")
+    pub async fn connect_storage(&self) -> Result<Arc<dyn Storage>> {
+        match self.backend.as_str() {
+            "postgres" => Ok(self.shared().await?),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => Ok(Arc::new(
+                crate::database::sqlite::SqliteManager::new(&self.sqlite_path, self.max_connections).await?
+            )),
+            #[cfg(not(feature = "sqlite"))]
+            "sqlite" => Err(Error::config("SQLite support is not compiled in; rebuild with --features sqlite")),
+            other => Err(Error::config(format!("Unknown database backend '{}', expected postgres or sqlite", other))),
+        }
+    }
+}
+
+// PostgresManager already implements every query; the trait methods delegate to them
+#[async_trait]
+impl Storage for PostgresManager {
+    fn backend(&self) -> &str {
+        "postgres"
+    }
+
+    async fn init(&self) -> Result<()> {
+        self.init_tables().await
+    }
+
+    async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        self.get_candle_data_between(symbol, interval, start, end).await
+    }
+
+    async fn get_candle_symbol_intervals(&self) -> Result<Vec<(String, String)>> {
+        PostgresManager::get_candle_symbol_intervals(self).await
+    }
+
+    async fn upsert_candles(&self, candles: &[BinanceCandle]) -> Result<()> {
+        for candle in candles {
+            self.upsert_candle(candle).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_enabled_indicator_configs(&self) -> Result<Vec<IndicatorConfig>> {
+        PostgresManager::get_enabled_indicator_configs(self).await
+    }
+
+    async fn get_last_calculated_time(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+    ) -> Result<Option<DateTime<Utc>>> {
+        PostgresManager::get_last_calculated_time(self, symbol, interval, indicator_name, parameters).await
+    }
+
+    async fn insert_calculated_indicators_batch(&self, batch: Vec<CalculatedIndicatorBatch>) -> Result<()> {
+        PostgresManager::insert_calculated_indicators_batch(self, batch).await
+    }
+
+    async fn get_indicator_series(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &serde_json::Value,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<IndicatorSeries> {
+        PostgresManager::get_indicator_series(self, symbol, interval, indicator_name, parameters, start, end).await
+    }
+
+    async fn list_strategy_ids(&self, enabled_only: bool) -> Result<Vec<String>> {
+        PostgresManager::list_strategy_ids(self, enabled_only).await
+    }
+
+    async fn get_strategy_record(&self, id: &str) -> Result<Option<StrategyRecord>> {
+        PostgresManager::get_strategy_record(self, id).await
+    }
+
+    async fn get_strategy_indicator_records(&self, strategy_id: &str) -> Result<Vec<StrategyIndicatorRecord>> {
+        PostgresManager::get_strategy_indicator_records(self, strategy_id).await
+    }
+
+    async fn get_strategy_rule_records(&self, strategy_id: &str) -> Result<Vec<StrategyRuleRecord>> {
+        PostgresManager::get_strategy_rule_records(self, strategy_id).await
+    }
+
+    async fn save_strategy_records(
+        &self,
+        strategy: &StrategyRecord,
+        indicators: &[StrategyIndicatorRecord],
+        rules: &[StrategyRuleRecord],
+    ) -> Result<()> {
+        PostgresManager::save_strategy_records(self, strategy, indicators, rules).await
+    }
+}
@@ -0,0 +1,677 @@
+// src/database/strategy_queries.rs
+use crate::database::models::{
+    BacktestListRecord, BacktestResultRecord, StrategyIndicatorRecord, StrategyRecord, StrategyRuleRecord,
+    SymbolContractRecord, SymbolRecord,
+};
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::strategy::listing::{StrategyFilter, StrategySort};
+use crate::strategy::schema::StrategyPerformance;
+use crate::utils::session::SessionCalendar;
+use chrono::{DateTime, Utc};
+use sqlx::{Postgres, Row, Transaction};
+
+/// Summary of a finished backtest to store in strategy_backtest_results
+pub struct NewBacktestResult<'a> {
+    pub strategy_id: &'a str,
+    pub symbol: &'a str,
+    pub interval: &'a str,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub initial_capital: f64,
+    pub final_capital: f64,
+    pub performance: &'a StrategyPerformance,
+    pub parameters_snapshot: serde_json::Value,
+}
+
+/// Queries for strategies, their indicators and rules, backtest results and engine settings
+impl PostgresManager {
+    // List strategy IDs of this namespace ordered by name
+    pub async fn list_strategy_ids(&self, enabled_only: bool) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT id::text AS id
+             FROM strategies
+             WHERE (enabled OR NOT $1) AND namespace = $2
+             ORDER BY name"
+        )
+        .bind(enabled_only)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    // List IDs of the strategies matching a filter, in the filter's order; metric filters
+    // and sorting use each strategy's latest backtest result
+    pub async fn find_strategy_ids(&self, filter: &StrategyFilter) -> Result<Vec<String>> {
+        let mut sql = String::from(
+            "SELECT s.id::text AS id
+             FROM strategies s
+             LEFT JOIN LATERAL (
+                 SELECT * FROM strategy_backtest_results r
+                 WHERE r.strategy_id = s.id
+                 ORDER BY r.created_at DESC
+                 LIMIT 1
+             ) b ON TRUE
+             WHERE (s.enabled OR NOT $1)
+               AND ($2::text IS NULL OR s.assets ? $2)
+               AND ($3::text IS NULL OR s.timeframes ? $3)
+               AND ($4::text IS NULL OR lower(s.author) = lower($4))
+               AND ($5::text IS NULL OR s.name ILIKE '%' || $5 || '%' OR s.description ILIKE '%' || $5 || '%')
+               AND s.namespace = $6
+               AND s.tags @> $7"
+        );
+
+        // Columns and operators come from MetricFilter's fixed lists, values are bound
+        for (i, metric) in filter.metrics.iter().enumerate() {
+            sql.push_str(&format!(" AND b.{}::float8 {} ${}", metric.column, metric.operator, i + 8));
+        }
+
+        let order = match filter.sort {
+            StrategySort::Name => "s.name".to_string(),
+            StrategySort::Created => "s.created_at".to_string(),
+            StrategySort::Updated => "s.updated_at".to_string(),
+            StrategySort::Metric(column) => format!("b.{}", column),
+        };
+        sql.push_str(&format!(
+            " ORDER BY {} {} NULLS LAST, s.name",
+            order,
+            if filter.descending { "DESC" } else { "ASC" }
+        ));
+
+        let mut query = sqlx::query(&sql)
+            .bind(filter.enabled_only)
+            .bind(filter.asset.as_deref().map(str::to_uppercase))
+            .bind(filter.timeframe.as_deref())
+            .bind(filter.author.as_deref())
+            .bind(filter.search.as_deref())
+            .bind(&self.namespace)
+            .bind(&filter.tags);
+        for metric in &filter.metrics {
+            query = query.bind(metric.value);
+        }
+
+        let rows = query.fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|row| row.get("id")).collect())
+    }
+
+    // Get the header of a strategy of this namespace, without indicators and rules
+    pub async fn get_strategy_record(&self, id: &str) -> Result<Option<StrategyRecord>> {
+        let record = sqlx::query_as::<_, StrategyRecord>(
+            "SELECT id::text AS id, name, description, version, author, created_at, updated_at,
+                    enabled, assets, timeframes, to_jsonb(tags) AS tags, parameters, risk_management, metadata
+             FROM strategies
+             WHERE id = $1::uuid AND namespace = $2"
+        )
+        .bind(id)
+        .bind(&self.namespace)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    // Count the strategies of this namespace with each tag, ordered by tag
+    pub async fn list_strategy_tags(&self) -> Result<Vec<(String, i64)>> {
+        let rows = sqlx::query(
+            "SELECT tag, COUNT(*) AS strategies
+             FROM strategies, unnest(tags) AS tag
+             WHERE namespace = $1
+             GROUP BY tag
+             ORDER BY tag"
+        )
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| (row.get("tag"), row.get("strategies"))).collect())
+    }
+
+    // Get the namespace a strategy ID is stored in, whichever namespace that is
+    pub async fn get_strategy_namespace(&self, id: &str) -> Result<Option<String>> {
+        let row = sqlx::query("SELECT namespace FROM strategies WHERE id = $1::uuid")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("namespace")))
+    }
+
+    // Get the indicators of a strategy
+    pub async fn get_strategy_indicator_records(&self, strategy_id: &str) -> Result<Vec<StrategyIndicatorRecord>> {
+        let records = sqlx::query_as::<_, StrategyIndicatorRecord>(
+            "SELECT indicator_id, indicator_type, indicator_name, parameters, description
+             FROM strategy_indicators
+             WHERE strategy_id = $1::uuid
+             ORDER BY indicator_id"
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    // Get the rules of a strategy in evaluation order
+    pub async fn get_strategy_rule_records(&self, strategy_id: &str) -> Result<Vec<StrategyRuleRecord>> {
+        let records = sqlx::query_as::<_, StrategyRuleRecord>(
+            "SELECT rule_id, name, condition, action, priority, description
+             FROM strategy_rules
+             WHERE strategy_id = $1::uuid
+             ORDER BY priority"
+        )
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    // Insert or replace a strategy together with its indicators and rules in one transaction
+    pub async fn save_strategy_records(
+        &self,
+        strategy: &StrategyRecord,
+        indicators: &[StrategyIndicatorRecord],
+        rules: &[StrategyRuleRecord],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // created_at is kept when the strategy already exists; a strategy of another
+        // namespace with the same ID is left alone
+        let saved = sqlx::query(
+            "INSERT INTO strategies
+             (id, name, description, version, author, created_at, updated_at,
+              enabled, assets, timeframes, parameters, risk_management, metadata, namespace, tags)
+             VALUES ($1::uuid, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14,
+                     ARRAY(SELECT jsonb_array_elements_text($15::jsonb)))
+             ON CONFLICT (id) DO UPDATE
+             SET name = EXCLUDED.name, description = EXCLUDED.description,
+                 version = EXCLUDED.version, author = EXCLUDED.author,
+                 updated_at = EXCLUDED.updated_at, enabled = EXCLUDED.enabled,
+                 assets = EXCLUDED.assets, timeframes = EXCLUDED.timeframes, tags = EXCLUDED.tags,
+                 parameters = EXCLUDED.parameters, risk_management = EXCLUDED.risk_management,
+                 metadata = EXCLUDED.metadata
+             WHERE strategies.namespace = EXCLUDED.namespace"
+        )
+        .bind(&strategy.id)
+        .bind(&strategy.name)
+        .bind(&strategy.description)
+        .bind(&strategy.version)
+        .bind(&strategy.author)
+        .bind(strategy.created_at)
+        .bind(strategy.updated_at)
+        .bind(strategy.enabled)
+        .bind(&strategy.assets)
+        .bind(&strategy.timeframes)
+        .bind(&strategy.parameters)
+        .bind(&strategy.risk_management)
+        .bind(&strategy.metadata)
+        .bind(&self.namespace)
+        .bind(&strategy.tags)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if saved == 0 {
+            return Err(Error::validation(format!(
+                "Strategy {} already exists in another namespace; save it under a new ID", strategy.id
+            )));
+        }
+
+        replace_strategy_indicators(&mut tx, &strategy.id, indicators).await?;
+        replace_strategy_rules(&mut tx, &strategy.id, rules).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Store a backtest summary and return its ID
+    pub async fn insert_backtest_result(&self, result: &NewBacktestResult<'_>) -> Result<i32> {
+        let performance = result.performance;
+
+        let row = sqlx::query(
+            "INSERT INTO strategy_backtest_results
+             (strategy_id, symbol, interval, start_date, end_date, initial_capital,
+              final_capital, total_trades, winning_trades, losing_trades, win_rate,
+              max_drawdown, profit_factor, sharpe_ratio, total_return, annualized_return,
+              max_consecutive_wins, max_consecutive_losses, avg_profit_per_win,
+              avg_loss_per_loss, avg_win_holding_period, avg_loss_holding_period,
+              expectancy, parameters_snapshot, benchmark_return, excess_return, alpha, beta,
+              sortino_ratio, calmar_ratio, value_at_risk, expected_shortfall, exposure_percent,
+              turnover, unfilled_orders, namespace, created_at)
+             VALUES
+             ($1::uuid, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+              $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33,
+              $34, $35, $36, NOW())
+             RETURNING id"
+        )
+        .bind(result.strategy_id)
+        .bind(result.symbol)
+        .bind(result.interval)
+        .bind(result.start_date)
+        .bind(result.end_date)
+        .bind(result.initial_capital)
+        .bind(result.final_capital)
+        .bind(performance.total_trades)
+        .bind(performance.winning_trades)
+        .bind(performance.losing_trades)
+        .bind(performance.win_rate)
+        .bind(performance.max_drawdown)
+        .bind(performance.profit_factor)
+        .bind(performance.sharpe_ratio)
+        .bind(performance.total_return)
+        .bind(performance.annualized_return)
+        .bind(performance.max_consecutive_wins)
+        .bind(performance.max_consecutive_losses)
+        .bind(performance.avg_profit_per_win)
+        .bind(performance.avg_loss_per_loss)
+        .bind(performance.avg_win_holding_period)
+        .bind(performance.avg_loss_holding_period)
+        .bind(performance.expectancy)
+        .bind(&result.parameters_snapshot)
+        .bind(performance.benchmark_return)
+        .bind(performance.excess_return)
+        .bind(performance.alpha)
+        .bind(performance.beta)
+        .bind(performance.sortino_ratio)
+        .bind(performance.calmar_ratio)
+        .bind(performance.value_at_risk)
+        .bind(performance.expected_shortfall)
+        .bind(performance.exposure_percent)
+        .bind(performance.turnover)
+        .bind(performance.unfilled_orders)
+        .bind(&self.namespace)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("id"))
+    }
+
+    // Get the latest backtest summaries of a strategy
+    pub async fn get_recent_backtest_records(&self, strategy_id: &str, limit: i64) -> Result<Vec<BacktestResultRecord>> {
+        // NUMERIC columns are cast so they decode as f64
+        let records = sqlx::query_as::<_, BacktestResultRecord>(
+            "SELECT id, symbol, interval,
+                    total_trades, winning_trades, losing_trades,
+                    win_rate::float8 AS win_rate,
+                    max_drawdown::float8 AS max_drawdown,
+                    profit_factor::float8 AS profit_factor,
+                    sharpe_ratio::float8 AS sharpe_ratio,
+                    total_return::float8 AS total_return,
+                    annualized_return::float8 AS annualized_return,
+                    max_consecutive_wins, max_consecutive_losses,
+                    avg_profit_per_win::float8 AS avg_profit_per_win,
+                    avg_loss_per_loss::float8 AS avg_loss_per_loss,
+                    avg_win_holding_period::float8 AS avg_win_holding_period,
+                    avg_loss_holding_period::float8 AS avg_loss_holding_period,
+                    expectancy::float8 AS expectancy,
+                    benchmark_return::float8 AS benchmark_return,
+                    excess_return::float8 AS excess_return,
+                    alpha::float8 AS alpha,
+                    beta::float8 AS beta,
+                    sortino_ratio::float8 AS sortino_ratio,
+                    calmar_ratio::float8 AS calmar_ratio,
+                    value_at_risk::float8 AS value_at_risk,
+                    expected_shortfall::float8 AS expected_shortfall,
+                    exposure_percent::float8 AS exposure_percent,
+                    turnover::float8 AS turnover,
+                    unfilled_orders
+             FROM strategy_backtest_results
+             WHERE strategy_id = $1::uuid AND namespace = $3
+             ORDER BY created_at DESC
+             LIMIT $2"
+        )
+        .bind(strategy_id)
+        .bind(limit)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    // List backtest results of this namespace, newest first, optionally of one strategy,
+    // symbol or interval and created since a time
+    pub async fn list_backtest_records(
+        &self,
+        strategy_id: Option<&str>,
+        symbol: Option<&str>,
+        interval: Option<&str>,
+        since: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<BacktestListRecord>> {
+        let records = sqlx::query_as::<_, BacktestListRecord>(
+            "SELECT r.id, r.strategy_id::text AS strategy_id, s.name AS strategy_name,
+                    r.symbol, r.interval, r.start_date, r.end_date, r.created_at, r.total_trades,
+                    r.total_return::float8 AS total_return,
+                    r.sharpe_ratio::float8 AS sharpe_ratio,
+                    r.max_drawdown::float8 AS max_drawdown,
+                    r.win_rate::float8 AS win_rate,
+                    r.profit_factor::float8 AS profit_factor
+             FROM strategy_backtest_results r
+             LEFT JOIN strategies s ON s.id = r.strategy_id
+             WHERE ($1::text IS NULL OR r.strategy_id = $1::uuid)
+               AND ($2::text IS NULL OR r.symbol = $2)
+               AND ($3::text IS NULL OR r.interval = $3)
+               AND ($4::timestamptz IS NULL OR r.created_at >= $4)
+               AND r.namespace = $6
+             ORDER BY r.created_at DESC, r.id DESC
+             LIMIT $5"
+        )
+        .bind(strategy_id)
+        .bind(symbol)
+        .bind(interval)
+        .bind(since)
+        .bind(limit)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    // Get every column of a backtest result as JSON, with the strategy's name; columns
+    // added by later features (engine profile, resource usage, archival) are included
+    // when present
+    pub async fn get_backtest_result_json(&self, backtest_id: i32) -> Result<Option<serde_json::Value>> {
+        let row = sqlx::query(
+            "SELECT to_jsonb(r) || jsonb_build_object('strategy_name', s.name) AS result
+             FROM strategy_backtest_results r
+             LEFT JOIN strategies s ON s.id = r.strategy_id
+             WHERE r.id = $1 AND r.namespace = $2"
+        )
+        .bind(backtest_id)
+        .bind(&self.namespace)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| row.get("result")))
+    }
+
+    // Get backtest summaries by ID, in ID order; unknown IDs and those of other namespaces are skipped
+    pub async fn get_backtest_records(&self, ids: &[i32]) -> Result<Vec<BacktestResultRecord>> {
+        let records = sqlx::query_as::<_, BacktestResultRecord>(
+            "SELECT id, symbol, interval,
+                    total_trades, winning_trades, losing_trades,
+                    win_rate::float8 AS win_rate,
+                    max_drawdown::float8 AS max_drawdown,
+                    profit_factor::float8 AS profit_factor,
+                    sharpe_ratio::float8 AS sharpe_ratio,
+                    total_return::float8 AS total_return,
+                    annualized_return::float8 AS annualized_return,
+                    max_consecutive_wins, max_consecutive_losses,
+                    avg_profit_per_win::float8 AS avg_profit_per_win,
+                    avg_loss_per_loss::float8 AS avg_loss_per_loss,
+                    avg_win_holding_period::float8 AS avg_win_holding_period,
+                    avg_loss_holding_period::float8 AS avg_loss_holding_period,
+                    expectancy::float8 AS expectancy,
+                    benchmark_return::float8 AS benchmark_return,
+                    excess_return::float8 AS excess_return,
+                    alpha::float8 AS alpha,
+                    beta::float8 AS beta,
+                    sortino_ratio::float8 AS sortino_ratio,
+                    calmar_ratio::float8 AS calmar_ratio,
+                    value_at_risk::float8 AS value_at_risk,
+                    expected_shortfall::float8 AS expected_shortfall,
+                    exposure_percent::float8 AS exposure_percent,
+                    turnover::float8 AS turnover,
+                    unfilled_orders
+             FROM strategy_backtest_results
+             WHERE id = ANY($1) AND namespace = $2
+             ORDER BY id"
+        )
+        .bind(ids)
+        .bind(&self.namespace)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    // Get the return of each trade of a backtest in entry order
+    pub async fn get_backtest_trade_returns(&self, backtest_id: i32) -> Result<Vec<f64>> {
+        let rows = sqlx::query(
+            "SELECT profit_loss_percent::float8 AS profit_loss_percent
+             FROM strategy_backtest_trades
+             WHERE backtest_id = $1
+             ORDER BY entry_time"
+        )
+        .bind(backtest_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("profit_loss_percent")).collect())
+    }
+
+    // Get the equity of a backtest after each trade, starting with the initial capital at
+    // the start date; trades of archived backtests are no longer stored
+    pub async fn get_backtest_equity_curve(&self, backtest_id: i32) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        let row = sqlx::query(
+            "SELECT start_date, initial_capital::float8 AS initial_capital
+             FROM strategy_backtest_results
+             WHERE id = $1 AND namespace = $2"
+        )
+        .bind(backtest_id)
+        .bind(&self.namespace)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| Error::not_found(format!("Backtest result {}", backtest_id)))?;
+
+        let mut equity: f64 = row.get("initial_capital");
+        let mut curve = vec![(row.get("start_date"), equity)];
+
+        let trades = sqlx::query(
+            "SELECT exit_time, profit_loss_amount::float8 AS profit_loss_amount
+             FROM strategy_backtest_trades
+             WHERE backtest_id = $1
+             ORDER BY exit_time"
+        )
+        .bind(backtest_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for trade in &trades {
+            equity += trade.get::<f64, _>("profit_loss_amount");
+            curve.push((trade.get("exit_time"), equity));
+        }
+
+        Ok(curve)
+    }
+
+    // Get a stored engine settings profile
+    pub async fn get_engine_settings_json(&self, name: &str) -> Result<Option<serde_json::Value>> {
+        let row = sqlx::query("SELECT settings FROM engine_settings WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("settings")))
+    }
+
+    // List stored engine settings profiles ordered by name
+    pub async fn list_engine_settings_json(&self) -> Result<Vec<serde_json::Value>> {
+        let rows = sqlx::query("SELECT settings FROM engine_settings ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("settings")).collect())
+    }
+
+    // Insert or replace an engine settings profile
+    pub async fn upsert_engine_settings(&self, name: &str, description: &str, settings: &serde_json::Value) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO engine_settings (name, description, settings, created_at, updated_at)
+             VALUES ($1, $2, $3, NOW(), NOW())
+             ON CONFLICT (name) DO UPDATE
+             SET description = EXCLUDED.description, settings = EXCLUDED.settings, updated_at = NOW()"
+        )
+        .bind(name)
+        .bind(description)
+        .bind(settings)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Record which engine profile and seed produced a backtest result
+    pub async fn update_backtest_engine_settings(&self, backtest_id: i32, profile: &str, settings: &serde_json::Value, seed: u64) -> Result<()> {
+        sqlx::query(
+            "UPDATE strategy_backtest_results
+             SET engine_profile = $2, engine_settings = $3, seed = $4
+             WHERE id = $1"
+        )
+        .bind(backtest_id)
+        .bind(profile)
+        .bind(settings)
+        .bind(seed as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Get the contract specification of a symbol
+    pub async fn get_symbol_contract(&self, symbol: &str) -> Result<Option<SymbolContractRecord>> {
+        let record = sqlx::query_as::<_, SymbolContractRecord>(
+            "SELECT symbol, contract_type, contract_multiplier, quanto_rate, settlement_asset,
+                    apply_funding, short_borrow_rate
+             FROM symbols
+             WHERE symbol = $1"
+        )
+        .bind(symbol)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    // Get the session calendar of a symbol; 24/7 on UTC days when none is set
+    pub async fn get_symbol_session(&self, symbol: &str) -> Result<SessionCalendar> {
+        let session: Option<String> = sqlx::query_scalar("SELECT session FROM symbols WHERE symbol = $1")
+            .bind(symbol)
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten();
+
+        match session {
+            Some(session) => SessionCalendar::parse(&session)
+                .map_err(|e| Error::config(format!("Session of {} in symbols: {}", symbol, e))),
+            None => Ok(SessionCalendar::default()),
+        }
+    }
+
+    // Insert or update symbol metadata; fields that are None keep their stored value
+    pub async fn upsert_symbols(&self, symbols: &[SymbolRecord]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for symbol in symbols {
+            sqlx::query(
+                "INSERT INTO symbols (symbol, base_asset, quote_asset, tick_size, listing_date, active, quote_volume, session)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT (symbol) DO UPDATE SET
+                    base_asset = COALESCE(EXCLUDED.base_asset, symbols.base_asset),
+                    quote_asset = COALESCE(EXCLUDED.quote_asset, symbols.quote_asset),
+                    tick_size = COALESCE(EXCLUDED.tick_size, symbols.tick_size),
+                    listing_date = COALESCE(EXCLUDED.listing_date, symbols.listing_date),
+                    active = EXCLUDED.active,
+                    quote_volume = COALESCE(EXCLUDED.quote_volume, symbols.quote_volume),
+                    session = COALESCE(EXCLUDED.session, symbols.session),
+                    updated_at = NOW()"
+            )
+            .bind(&symbol.symbol)
+            .bind(&symbol.base_asset)
+            .bind(&symbol.quote_asset)
+            .bind(symbol.tick_size)
+            .bind(symbol.listing_date)
+            .bind(symbol.active)
+            .bind(symbol.quote_volume)
+            .bind(&symbol.session)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // List symbol metadata by descending 24h quote volume, optionally only active symbols
+    // quoted in an asset
+    pub async fn list_symbols(&self, active_only: bool, quote_asset: Option<&str>) -> Result<Vec<SymbolRecord>> {
+        let symbols = sqlx::query_as::<_, SymbolRecord>(
+            "SELECT symbol, base_asset, quote_asset, tick_size, listing_date, active, quote_volume, session, updated_at
+             FROM symbols
+             WHERE (NOT $1 OR active)
+               AND ($2::text IS NULL OR UPPER(quote_asset) = UPPER($2))
+             ORDER BY quote_volume DESC NULLS LAST, symbol"
+        )
+        .bind(active_only)
+        .bind(quote_asset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(symbols)
+    }
+}
+
+// Replace the indicators of a strategy inside a transaction
+async fn replace_strategy_indicators(
+    tx: &mut Transaction<'_, Postgres>,
+    strategy_id: &str,
+    indicators: &[StrategyIndicatorRecord],
+) -> Result<()> {
+    sqlx::query("DELETE FROM strategy_indicators WHERE strategy_id = $1::uuid")
+        .bind(strategy_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for indicator in indicators {
+        sqlx::query(
+            "INSERT INTO strategy_indicators
+             (strategy_id, indicator_id, indicator_type, indicator_name, parameters, description, created_at)
+             VALUES ($1::uuid, $2, $3, $4, $5, $6, NOW())"
+        )
+        .bind(strategy_id)
+        .bind(&indicator.indicator_id)
+        .bind(&indicator.indicator_type)
+        .bind(&indicator.indicator_name)
+        .bind(&indicator.parameters)
+        .bind(&indicator.description)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// Replace the rules of a strategy inside a transaction
+async fn replace_strategy_rules(
+    tx: &mut Transaction<'_, Postgres>,
+    strategy_id: &str,
+    rules: &[StrategyRuleRecord],
+) -> Result<()> {
+    sqlx::query("DELETE FROM strategy_rules WHERE strategy_id = $1::uuid")
+        .bind(strategy_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for rule in rules {
+        sqlx::query(
+            "INSERT INTO strategy_rules
+             (strategy_id, rule_id, name, condition, action, priority, description, created_at)
+             VALUES ($1::uuid, $2, $3, $4, $5, $6, $7, NOW())"
+        )
+        .bind(strategy_id)
+        .bind(&rule.rule_id)
+        .bind(&rule.name)
+        .bind(&rule.condition)
+        .bind(&rule.action)
+        .bind(rule.priority)
+        .bind(&rule.description)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
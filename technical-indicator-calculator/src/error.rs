@@ -0,0 +1,80 @@
+// Error type of the library modules
+//
+// Database access, indicator calculation, strategies and the TA-Lib bindings return this
+// error so callers can match on the kind of failure. The CLI and the worker binaries keep
+// using anyhow and convert with `?`. The variants wrapping database, TA-Lib and archive
+// errors only exist with the native feature.
+#[cfg(feature = "native")]
+use crate::talib_bindings::TaLibError;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A query or connection failed
+    #[cfg(feature = "native")]
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    /// A TA-Lib call failed or returned unusable output
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    TaLib(#[from] TaLibError),
+
+    /// Input was rejected before anything was calculated or written
+    #[error("{0}")]
+    Validation(String),
+
+    /// A requested record does not exist
+    #[error("{0} not found")]
+    NotFound(String),
+
+    /// Invalid or unsupported configuration
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    /// JSON could not be parsed or serialized
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The backtest archive store failed
+    #[cfg(feature = "native")]
+    #[error("Archive storage error: {0}")]
+    Archive(#[from] object_store::Error),
+
+    /// Reading or writing a file failed
+    #[error("Failed to {action} {}: {source}", path.display())]
+    File {
+        action: &'static str,
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// Failure of a dependency without a dedicated variant
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl Error {
+    pub fn validation(message: impl Into<String>) -> Self {
+        Error::Validation(message.into())
+    }
+
+    pub fn not_found(what: impl Into<String>) -> Self {
+        Error::NotFound(what.into())
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Error::Config(message.into())
+    }
+
+    /// Wrap an I/O error with the file it happened on, for use with `map_err`
+    pub fn file(action: &'static str, path: &Path) -> impl FnOnce(std::io::Error) -> Self + '_ {
+        move |source| Error::File { action, path: path.to_path_buf(), source }
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,248 @@
+// src/events.rs
+//
+// Structured event log
+//
+// Job lifecycle, configuration changes, strategy imports, backtests and delivered
+// signals are recorded in the events table so that history can be queried and tailed
+// (`events tail --follow`) instead of grepping a log file. Recording an event never
+// fails the operation that emitted it; a failed insert is only logged.
+use crate::database::postgres::PostgresManager;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{broadcast, OnceCell};
+use tracing::warn;
+
+// Process recorded as the source of events; "cli" unless set at startup
+static SOURCE: OnceLock<&'static str> = OnceLock::new();
+
+// Set once the events table is known to exist in this process
+static TABLE_READY: OnceCell<()> = OnceCell::const_new();
+
+/// Name this process in the events it records ("worker", "grpc" or "python")
+///
+/// Only the first call has an effect.
+pub fn set_source(source: &'static str) {
+    let _ = SOURCE.set(source);
+}
+
+fn source() -> &'static str {
+    SOURCE.get().copied().unwrap_or("cli")
+}
+
+/// Kind of a recorded event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    WorkerStarted,
+    WorkerStopped,
+    JobStarted,
+    JobFinished,
+    JobFailed,
+    DataQuality,
+    IndicatorAnomaly,
+    Maintenance,
+    ConfigChanged,
+    StrategyImported,
+    BacktestRun,
+    SignalGenerated,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 12] = [
+        EventKind::WorkerStarted,
+        EventKind::WorkerStopped,
+        EventKind::JobStarted,
+        EventKind::JobFinished,
+        EventKind::JobFailed,
+        EventKind::DataQuality,
+        EventKind::IndicatorAnomaly,
+        EventKind::Maintenance,
+        EventKind::ConfigChanged,
+        EventKind::StrategyImported,
+        EventKind::BacktestRun,
+        EventKind::SignalGenerated,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::WorkerStarted => "worker_started",
+            EventKind::WorkerStopped => "worker_stopped",
+            EventKind::JobStarted => "job_started",
+            EventKind::JobFinished => "job_finished",
+            EventKind::JobFailed => "job_failed",
+            EventKind::DataQuality => "data_quality",
+            EventKind::IndicatorAnomaly => "indicator_anomaly",
+            EventKind::Maintenance => "maintenance",
+            EventKind::ConfigChanged => "config_changed",
+            EventKind::StrategyImported => "strategy_imported",
+            EventKind::BacktestRun => "backtest_run",
+            EventKind::SignalGenerated => "signal_generated",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self> {
+        Self::ALL.iter()
+            .copied()
+            .find(|kind| kind.as_str() == name)
+            .ok_or_else(|| anyhow::anyhow!(
+                "Unknown event kind '{}', expected one of: {}",
+                name,
+                Self::ALL.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", ")
+            ))
+    }
+}
+
+/// A recorded event
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Event {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub kind: String,
+    /// Process that emitted the event: "worker", "cli", "grpc" or "python"
+    pub source: String,
+    /// What the event is about, e.g. "BTCUSDT:1h:RSI" or a strategy ID
+    pub subject: Option<String>,
+    pub message: String,
+    pub data: Value,
+}
+
+/// Create the events table if it doesn't exist
+pub async fn init_table(pg: &PostgresManager) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS events (
+            id BIGSERIAL PRIMARY KEY,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            kind VARCHAR NOT NULL,
+            source VARCHAR NOT NULL,
+            subject VARCHAR,
+            message TEXT NOT NULL,
+            data JSONB NOT NULL DEFAULT '{}'
+        )"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_events_kind ON events(kind, id DESC)")
+        .execute(&pg.pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Insert an event, creating the events table on first use
+pub async fn insert_event(
+    pg: &PostgresManager,
+    kind: EventKind,
+    subject: Option<&str>,
+    message: &str,
+    data: Value,
+) -> Result<Event> {
+    TABLE_READY.get_or_try_init(|| init_table(pg)).await?;
+
+    let event = sqlx::query_as::<_, Event>(
+        "INSERT INTO events (kind, source, subject, message, data)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING *"
+    )
+    .bind(kind.as_str())
+    .bind(source())
+    .bind(subject)
+    .bind(message)
+    .bind(data)
+    .fetch_one(&pg.pool)
+    .await?;
+
+    Ok(event)
+}
+
+/// Record an event, logging instead of failing when it can't be stored
+pub async fn record_event(
+    pg: &PostgresManager,
+    kind: EventKind,
+    subject: Option<&str>,
+    message: &str,
+    data: Value,
+) -> Option<Event> {
+    match insert_event(pg, kind, subject, message, data).await {
+        Ok(event) => Some(event),
+        Err(e) => {
+            warn!("Failed to record {} event: {}", kind.as_str(), e);
+            None
+        }
+    }
+}
+
+/// Latest events, newest first, optionally of some kinds or one subject
+pub async fn recent_events(pg: &PostgresManager, kinds: &[EventKind], subject: Option<&str>, limit: i64) -> Result<Vec<Event>> {
+    events_after(pg, None, kinds, subject, limit).await
+}
+
+/// Events with an ID above `after`, oldest first; newest first when `after` is None
+pub async fn events_after(
+    pg: &PostgresManager,
+    after: Option<i64>,
+    kinds: &[EventKind],
+    subject: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Event>> {
+    let kinds: Vec<&str> = kinds.iter().map(|k| k.as_str()).collect();
+    let order = if after.is_some() { "ASC" } else { "DESC" };
+
+    let rows = sqlx::query_as::<_, Event>(&format!(
+        "SELECT * FROM events
+         WHERE ($1::BIGINT IS NULL OR id > $1)
+           AND (cardinality($2::VARCHAR[]) = 0 OR kind = ANY($2))
+           AND ($3::VARCHAR IS NULL OR subject = $3)
+         ORDER BY id {}
+         LIMIT $4",
+        order
+    ))
+    .bind(after)
+    .bind(&kinds)
+    .bind(subject)
+    .bind(limit)
+    .fetch_all(&pg.pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Delete events older than `keep_days` days
+pub async fn prune_events(pg: &PostgresManager, keep_days: u64) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM events WHERE created_at < NOW() - make_interval(days => $1)")
+        .bind(keep_days as i32)
+        .execute(&pg.pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Publishes events of a long-running process to the events table and to in-process subscribers
+#[derive(Clone)]
+pub struct EventBus {
+    pg: Arc<PostgresManager>,
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    pub fn new(pg: Arc<PostgresManager>) -> Self {
+        let (sender, _) = broadcast::channel(1024);
+        Self { pg, sender }
+    }
+
+    /// Subscribe to events recorded from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+
+    /// Record an event and publish it to subscribers
+    pub async fn emit(&self, kind: EventKind, subject: Option<&str>, message: &str, data: Value) {
+        if let Some(event) = record_event(&self.pg, kind, subject, message, data).await {
+            // No subscribers is fine
+            let _ = self.sender.send(event);
+        }
+    }
+}
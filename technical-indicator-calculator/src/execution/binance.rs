@@ -0,0 +1,129 @@
+// src/execution/binance.rs
+use crate::execution::{Executor, OrderReport, OrderRequest, OrderStatus};
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+/// REST endpoint of the Binance spot testnet
+pub const TESTNET_REST_URL: &str = "https://testnet.binance.vision";
+
+// Order as returned by POST and GET /api/v3/order
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderResponse {
+    order_id: i64,
+    client_order_id: String,
+    status: String,
+    executed_qty: String,
+    cummulative_quote_qty: String,
+}
+
+impl OrderResponse {
+    fn to_report(&self) -> Result<OrderReport> {
+        let status = OrderStatus::parse(&self.status)
+            .ok_or_else(|| anyhow!("Unknown order status: {}", self.status))?;
+        let executed_quantity: f64 = self.executed_qty.parse()
+            .map_err(|e| anyhow!("Executed quantity is not a number: {} ({})", self.executed_qty, e))?;
+        let quote_quantity: f64 = self.cummulative_quote_qty.parse()
+            .map_err(|e| anyhow!("Quote quantity is not a number: {} ({})", self.cummulative_quote_qty, e))?;
+
+        Ok(OrderReport {
+            client_order_id: self.client_order_id.clone(),
+            exchange_order_id: Some(self.order_id.to_string()),
+            status,
+            executed_quantity,
+            average_price: (executed_quantity > 0.0).then(|| quote_quantity / executed_quantity),
+        })
+    }
+}
+
+/// Market orders on the Binance spot testnet, signed with HMAC-SHA256 API keys
+pub struct BinanceTestnetExecutor {
+    client: reqwest::Client,
+    rest_url: String,
+    api_key: String,
+    api_secret: String,
+    recv_window_ms: u64,
+}
+
+impl BinanceTestnetExecutor {
+    pub fn new(rest_url: &str, api_key: &str, api_secret: &str, recv_window_ms: u64) -> Result<Self> {
+        if api_key.is_empty() || api_secret.is_empty() {
+            return Err(anyhow!("Binance testnet API key and secret are required for order execution"));
+        }
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            rest_url: rest_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            recv_window_ms,
+        })
+    }
+
+    // Append timestamp, receive window and signature to a query string
+    fn sign(&self, query: &str) -> Result<String> {
+        let query = format!("{}&recvWindow={}&timestamp={}", query, self.recv_window_ms, Utc::now().timestamp_millis());
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.as_bytes())
+            .map_err(|e| anyhow!("Invalid API secret: {}", e))?;
+        mac.update(query.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        Ok(format!("{}&signature={}", query, signature))
+    }
+
+    // Send a signed request and decode the order in the response
+    async fn send(&self, method: reqwest::Method, query: &str) -> Result<OrderResponse> {
+        let url = format!("{}/api/v3/order?{}", self.rest_url, self.sign(query)?);
+        let response = self.client
+            .request(method, url)
+            .header("X-MBX-APIKEY", &self.api_key)
+            .send()
+            .await?;
+
+        // Errors come back as {"code": -2010, "msg": "..."}
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(anyhow!("Binance testnet returned {}: {}", status, body));
+        }
+
+        serde_json::from_str(&body).context(format!("Unexpected order response: {}", body))
+    }
+}
+
+/// Format a quantity without exponent or trailing zeros, as the REST API expects
+fn format_quantity(quantity: f64) -> String {
+    let formatted = format!("{:.8}", quantity);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[async_trait]
+impl Executor for BinanceTestnetExecutor {
+    fn name(&self) -> &str {
+        "binance-testnet"
+    }
+
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderReport> {
+        let query = format!(
+            "symbol={}&side={}&type=MARKET&quantity={}&newClientOrderId={}&newOrderRespType=RESULT",
+            order.symbol, order.side.as_str(), format_quantity(order.quantity), order.client_order_id,
+        );
+
+        self.send(reqwest::Method::POST, &query).await
+            .context(format!("Failed to submit order {}", order.client_order_id))?
+            .to_report()
+    }
+
+    async fn order_status(&self, symbol: &str, client_order_id: &str) -> Result<OrderReport> {
+        let query = format!("symbol={}&origClientOrderId={}", symbol, client_order_id);
+
+        self.send(reqwest::Method::GET, &query).await
+            .context(format!("Failed to get status of order {}", client_order_id))?
+            .to_report()
+    }
+}
@@ -0,0 +1,162 @@
+// src/execution/journal.rs
+use crate::database::postgres::PostgresManager;
+use crate::execution::{OrderReport, OrderRequest};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::sync::Arc;
+
+/// Journal status of an order blocked by a risk limit before it was sent
+pub const STATUS_BLOCKED: &str = "BLOCKED";
+/// Journal status of an order whose submission failed
+pub const STATUS_FAILED: &str = "FAILED";
+/// Journal status of an order being submitted
+pub const STATUS_SUBMITTING: &str = "SUBMITTING";
+
+/// One order attempt and its latest known state
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JournaledOrder {
+    pub id: i64,
+    pub executor: String,
+    pub client_order_id: String,
+    pub exchange_order_id: Option<String>,
+    pub symbol: String,
+    pub side: String,
+    pub quantity: f64,
+    pub status: String,
+    pub executed_quantity: f64,
+    pub average_price: Option<f64>,
+    pub error: Option<String>,
+    pub signal_key: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Journal of every order attempt, including blocked and failed ones
+pub struct OrderJournal {
+    pg: Arc<PostgresManager>,
+}
+
+impl OrderJournal {
+    pub fn new(pg: Arc<PostgresManager>) -> Self {
+        Self { pg }
+    }
+
+    /// Create the order_journal table if it doesn't exist
+    pub async fn init_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS order_journal (
+                id BIGSERIAL PRIMARY KEY,
+                executor VARCHAR NOT NULL,
+                client_order_id VARCHAR NOT NULL UNIQUE,
+                exchange_order_id VARCHAR,
+                symbol VARCHAR NOT NULL,
+                side VARCHAR NOT NULL,
+                quantity DOUBLE PRECISION NOT NULL,
+                status VARCHAR NOT NULL,
+                executed_quantity DOUBLE PRECISION NOT NULL DEFAULT 0,
+                average_price DOUBLE PRECISION,
+                error TEXT,
+                signal_key VARCHAR,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_order_journal_created_at ON order_journal(executor, created_at DESC)"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record an order attempt with its initial status
+    pub async fn record(&self, executor: &str, order: &OrderRequest, status: &str,
+                        error: Option<&str>, signal_key: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO order_journal
+             (executor, client_order_id, symbol, side, quantity, status, error, signal_key)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+        )
+        .bind(executor)
+        .bind(&order.client_order_id)
+        .bind(&order.symbol)
+        .bind(order.side.as_str())
+        .bind(order.quantity)
+        .bind(status)
+        .bind(error)
+        .bind(signal_key)
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the latest state reported by the exchange
+    pub async fn update_report(&self, report: &OrderReport) -> Result<()> {
+        sqlx::query(
+            "UPDATE order_journal
+             SET exchange_order_id = COALESCE($2, exchange_order_id), status = $3,
+                 executed_quantity = $4, average_price = $5, updated_at = NOW()
+             WHERE client_order_id = $1"
+        )
+        .bind(&report.client_order_id)
+        .bind(&report.exchange_order_id)
+        .bind(report.status.as_str())
+        .bind(report.executed_quantity)
+        .bind(report.average_price)
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark an order as failed with the error that stopped it
+    pub async fn update_failed(&self, client_order_id: &str, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE order_journal SET status = $2, error = $3, updated_at = NOW() WHERE client_order_id = $1"
+        )
+        .bind(client_order_id)
+        .bind(STATUS_FAILED)
+        .bind(error)
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Number of orders sent (not blocked) by an executor since `since`
+    pub async fn count_sent_since(&self, executor: &str, since: DateTime<Utc>) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM order_journal WHERE executor = $1 AND created_at >= $2 AND status <> $3"
+        )
+        .bind(executor)
+        .bind(since)
+        .bind(STATUS_BLOCKED)
+        .fetch_one(&self.pg.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Most recent order attempts, newest first
+    pub async fn recent(&self, limit: i64) -> Result<Vec<JournaledOrder>> {
+        let orders = sqlx::query_as::<_, JournaledOrder>(
+            "SELECT id, executor, client_order_id, exchange_order_id, symbol, side, quantity, status,
+                    executed_quantity, average_price, error, signal_key, created_at, updated_at
+             FROM order_journal
+             ORDER BY created_at DESC, id DESC
+             LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        Ok(orders)
+    }
+}
@@ -0,0 +1,112 @@
+// src/execution/mod.rs
+pub mod binance;
+pub mod journal;
+pub mod paper;
+pub mod risk;
+pub mod router;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+pub use binance::BinanceTestnetExecutor;
+pub use risk::{RiskGuard, RiskStatus};
+pub use router::{OrderRouter, RiskLimits};
+
+/// Side of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        }
+    }
+}
+
+/// Market order sent to an exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    /// Quantity in base asset units
+    pub quantity: f64,
+    /// Our id of the order, unique per exchange account (at most 36 characters)
+    pub client_order_id: String,
+}
+
+/// Lifecycle state of an order on the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    Expired,
+}
+
+impl OrderStatus {
+    /// Parse an exchange status such as "PARTIALLY_FILLED"
+    pub fn parse(status: &str) -> Option<Self> {
+        match status {
+            "NEW" | "PENDING_NEW" => Some(OrderStatus::New),
+            "PARTIALLY_FILLED" => Some(OrderStatus::PartiallyFilled),
+            "FILLED" => Some(OrderStatus::Filled),
+            "CANCELED" | "PENDING_CANCEL" => Some(OrderStatus::Canceled),
+            "REJECTED" => Some(OrderStatus::Rejected),
+            "EXPIRED" | "EXPIRED_IN_MATCH" => Some(OrderStatus::Expired),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::New => "NEW",
+            OrderStatus::PartiallyFilled => "PARTIALLY_FILLED",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Canceled => "CANCELED",
+            OrderStatus::Rejected => "REJECTED",
+            OrderStatus::Expired => "EXPIRED",
+        }
+    }
+
+    /// Whether the order can no longer change
+    pub fn is_final(&self) -> bool {
+        !matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+}
+
+/// State of an order as reported by the exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderReport {
+    pub client_order_id: String,
+    pub exchange_order_id: Option<String>,
+    pub status: OrderStatus,
+    pub executed_quantity: f64,
+    /// Average fill price, once anything has filled
+    pub average_price: Option<f64>,
+}
+
+/// Places orders on an exchange account
+///
+/// Implementations only talk to the exchange; risk limits and journaling are
+/// handled by the OrderRouter in front of them.
+#[async_trait]
+pub trait Executor: Send + Sync {
+    /// Short name of the executor used in logs and the order journal (e.g. "binance-testnet")
+    fn name(&self) -> &str;
+
+    /// Submit an order and return the exchange's acknowledgement
+    async fn submit_order(&self, order: &OrderRequest) -> Result<OrderReport>;
+
+    /// Get the current state of an order by our client order id
+    async fn order_status(&self, symbol: &str, client_order_id: &str) -> Result<OrderReport>;
+}
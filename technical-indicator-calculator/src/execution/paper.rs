@@ -0,0 +1,555 @@
+// src/execution/paper.rs
+use crate::database::postgres::PostgresManager;
+use crate::execution::{OrderRouter, OrderSide, RiskGuard};
+use crate::signals::Signal;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::{RiskManagement, RuleAction};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Open paper position of one strategy on one symbol
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaperPosition {
+    pub account: String,
+    pub strategy_id: String,
+    pub symbol: String,
+    pub is_long: bool,
+    pub quantity: f64,
+    /// Quantity-weighted average entry price
+    pub entry_price: f64,
+    pub opened_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PaperPosition {
+    /// Profit or loss of `quantity` units closed at `price`
+    pub fn pnl(&self, quantity: f64, price: f64) -> f64 {
+        let direction = if self.is_long { 1.0 } else { -1.0 };
+        (price - self.entry_price) * quantity * direction
+    }
+}
+
+/// Simulated fill of a signal
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaperTrade {
+    pub id: i64,
+    pub account: String,
+    pub strategy_id: String,
+    pub symbol: String,
+    /// "buy" or "sell"
+    pub side: String,
+    pub quantity: f64,
+    pub price: f64,
+    /// Profit or loss realized by a fill that reduces a position
+    pub realized_pnl: f64,
+    pub rule_id: String,
+    pub signal_key: String,
+    pub executed_at: DateTime<Utc>,
+}
+
+/// Value of a paper account at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaperEquity {
+    pub account: String,
+    pub time: DateTime<Utc>,
+    /// Initial capital plus realized profit and loss
+    pub balance: f64,
+    /// Open positions marked to the latest candle close
+    pub unrealized_pnl: f64,
+    pub equity: f64,
+    pub open_positions: i32,
+}
+
+/// Paper-trading executor that fills live signals against simulated positions
+///
+/// Signals are read from the sent_signals ledger the worker's signal generator
+/// claims them in (see `signals::generator`), so the paper account trades exactly
+/// what was delivered to notification sinks. Each signal fills at most
+/// once, even across restarts. Fills happen at the signal price; entry orders with
+/// an order type fill like market orders. Open positions are marked to the latest
+/// stored candle close of their symbol.
+///
+/// With an order router, every paper fill is also sent to an exchange sandbox.
+/// With a risk guard, the account is checked before every signal and signals
+/// are skipped while it is halted.
+pub struct PaperExecutor {
+    pg: Arc<PostgresManager>,
+    repository: StrategyRepository,
+    account: String,
+    initial_capital: f64,
+    // Risk settings of the strategies seen so far
+    risk: HashMap<String, RiskManagement>,
+    // Also sends every paper fill to an exchange sandbox
+    router: Option<OrderRouter>,
+    // Halts execution when the account breaches its risk limits
+    guard: Option<RiskGuard>,
+}
+
+impl PaperExecutor {
+    /// Create an executor for a named paper account
+    pub fn new(pg: Arc<PostgresManager>, account: &str, initial_capital: f64) -> Self {
+        Self {
+            repository: StrategyRepository::new(pg.clone()),
+            pg,
+            account: account.to_string(),
+            initial_capital,
+            risk: HashMap::new(),
+            router: None,
+            guard: None,
+        }
+    }
+
+    /// Skip signals while the risk guard has the account halted
+    pub fn with_risk_guard(mut self, guard: RiskGuard) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    /// Send every paper fill as a market order through the router as well
+    pub fn with_order_router(mut self, router: OrderRouter) -> Self {
+        self.router = Some(router);
+        self
+    }
+
+    /// Create the paper trading tables if they don't exist
+    pub async fn init_tables(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS paper_positions (
+                account VARCHAR NOT NULL,
+                strategy_id VARCHAR NOT NULL,
+                symbol VARCHAR NOT NULL,
+                is_long BOOLEAN NOT NULL,
+                quantity DOUBLE PRECISION NOT NULL,
+                entry_price DOUBLE PRECISION NOT NULL,
+                opened_at TIMESTAMPTZ NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL,
+                PRIMARY KEY (account, strategy_id, symbol)
+            )"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS paper_trades (
+                id BIGSERIAL PRIMARY KEY,
+                account VARCHAR NOT NULL,
+                strategy_id VARCHAR NOT NULL,
+                symbol VARCHAR NOT NULL,
+                side VARCHAR NOT NULL,
+                quantity DOUBLE PRECISION NOT NULL,
+                price DOUBLE PRECISION NOT NULL,
+                realized_pnl DOUBLE PRECISION NOT NULL DEFAULT 0,
+                rule_id VARCHAR NOT NULL,
+                signal_key VARCHAR NOT NULL,
+                executed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                UNIQUE (account, signal_key)
+            )"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS paper_equity (
+                account VARCHAR NOT NULL,
+                time TIMESTAMPTZ NOT NULL,
+                balance DOUBLE PRECISION NOT NULL,
+                unrealized_pnl DOUBLE PRECISION NOT NULL,
+                equity DOUBLE PRECISION NOT NULL,
+                open_positions INTEGER NOT NULL,
+                PRIMARY KEY (account, time)
+            )"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Poll the signal ledger and execute new signals until interrupted
+    ///
+    /// Only signals delivered after `since` are executed. An equity snapshot is
+    /// recorded after every poll.
+    pub async fn run(&mut self, since: DateTime<Utc>, poll_interval: Duration) -> Result<()> {
+        let mut cursor = since;
+        info!("Paper account '{}' executing signals sent after {}", self.account, cursor);
+
+        loop {
+            match self.poll(cursor).await {
+                Ok(next) => cursor = next,
+                Err(e) => error!("Paper trading poll failed: {}", e),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(poll_interval) => {},
+                _ = tokio::signal::ctrl_c() => {
+                    info!("Received SIGINT, stopping paper trading");
+                    return Ok(());
+                },
+            }
+        }
+    }
+
+    /// Execute the signals sent after `cursor` and return the new cursor
+    pub async fn poll(&mut self, cursor: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let rows: Vec<(serde_json::Value, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT signal, sent_at FROM sent_signals
+             WHERE sent_at > $1
+             ORDER BY sent_at, bar_time"
+        )
+        .bind(cursor)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        let mut next = cursor;
+        for (value, sent_at) in rows {
+            next = next.max(sent_at);
+
+            let signal: Signal = match serde_json::from_value(value) {
+                Ok(signal) => signal,
+                Err(e) => {
+                    error!("Skipping unreadable signal sent at {}: {}", sent_at, e);
+                    continue;
+                }
+            };
+
+            if let Some(guard) = &self.guard {
+                let status = guard.check(&self.snapshot().await?).await?;
+                if status.state.halted {
+                    warn!("Skipping signal {}: trading is halted ({})", signal.idempotency_key(),
+                          status.state.reason.as_deref().unwrap_or("risk limit"));
+                    continue;
+                }
+            }
+
+            if let Err(e) = self.execute(&signal).await {
+                error!("Failed to execute signal {}: {}", signal.idempotency_key(), e);
+            }
+        }
+
+        let snapshot = self.record_equity().await?;
+        if let Some(guard) = &self.guard {
+            guard.check(&snapshot).await?;
+        }
+        Ok(next)
+    }
+
+    /// Apply one signal to the paper account; returns the fill, if any
+    pub async fn execute(&mut self, signal: &Signal) -> Result<Option<PaperTrade>> {
+        let key = signal.idempotency_key();
+        if self.signal_executed(&key).await? {
+            debug!("Signal already executed: {}", key);
+            return Ok(None);
+        }
+
+        if signal.price <= 0.0 {
+            return Err(anyhow!("Signal {} has no price", key));
+        }
+
+        let risk = self.risk_management(&signal.strategy_id).await?;
+        let position = self.position(&signal.strategy_id, &signal.symbol).await?;
+        let equity = self.snapshot().await?.equity;
+        if equity <= 0.0 {
+            return Err(anyhow!("Paper account '{}' has no equity left", self.account));
+        }
+        let price = signal.price;
+
+        let trade = match (&signal.action, position) {
+            (RuleAction::EnterLong { size_percent, .. } | RuleAction::EnterShort { size_percent, .. }, None) => {
+                let is_long = matches!(signal.action, RuleAction::EnterLong { .. });
+                let size = size_percent.unwrap_or(risk.default_position_size);
+                let quantity = equity * size / 100.0 / price;
+
+                let position = PaperPosition {
+                    account: self.account.clone(),
+                    strategy_id: signal.strategy_id.clone(),
+                    symbol: signal.symbol.clone(),
+                    is_long,
+                    quantity,
+                    entry_price: price,
+                    opened_at: Utc::now(),
+                    updated_at: Utc::now(),
+                };
+                Some(self.fill(signal, is_long, quantity, 0.0, Some(&position)).await?)
+            },
+            (RuleAction::ExitLong { size_percent } | RuleAction::ExitShort { size_percent }, Some(position)) => {
+                let exits_long = matches!(signal.action, RuleAction::ExitLong { .. });
+                if position.is_long == exits_long {
+                    Some(self.reduce(signal, position, size_percent.unwrap_or(100.0)).await?)
+                } else {
+                    None
+                }
+            },
+            (RuleAction::ScaleIn { size_percent, max_position_percent }, Some(mut position)) => {
+                let max_percent = max_position_percent.unwrap_or(risk.max_total_risk);
+                let current_percent = position.quantity * price / equity * 100.0;
+                let added_percent = size_percent.min(max_percent - current_percent);
+
+                if added_percent > 0.0 {
+                    let added = equity * added_percent / 100.0 / price;
+                    position.entry_price = (position.entry_price * position.quantity + price * added)
+                        / (position.quantity + added);
+                    position.quantity += added;
+                    position.updated_at = Utc::now();
+                    Some(self.fill(signal, position.is_long, added, 0.0, Some(&position)).await?)
+                } else {
+                    None
+                }
+            },
+            (RuleAction::ScaleOut { percent }, Some(position)) => {
+                Some(self.reduce(signal, position, *percent).await?)
+            },
+            _ => None,
+        };
+
+        match &trade {
+            Some(trade) => info!("Paper {} {:.6} {} at {:.2} for strategy {} (rule {})",
+                                 trade.side, trade.quantity, trade.symbol, trade.price,
+                                 trade.strategy_id, trade.rule_id),
+            None => debug!("Signal {} does not apply to the open paper positions", key),
+        }
+
+        // The paper fill stands even if the exchange order fails; the journal has the details
+        if let (Some(router), Some(trade)) = (&self.router, &trade) {
+            let side = if trade.side == "buy" { OrderSide::Buy } else { OrderSide::Sell };
+            if let Err(e) = router.route(&trade.symbol, side, trade.quantity, trade.price, Some(&key)).await {
+                error!("Failed to route paper trade {} to the exchange: {:#}", trade.id, e);
+            }
+        }
+
+        Ok(trade)
+    }
+
+    // Close `percent` of a position at the signal price
+    async fn reduce(&self, signal: &Signal, mut position: PaperPosition, percent: f64) -> Result<PaperTrade> {
+        let quantity = position.quantity * percent.clamp(0.0, 100.0) / 100.0;
+        let realized_pnl = position.pnl(quantity, signal.price);
+
+        position.quantity -= quantity;
+        position.updated_at = Utc::now();
+        let remaining = (position.quantity > f64::EPSILON).then_some(&position);
+
+        self.fill(signal, !position.is_long, quantity, realized_pnl, remaining).await
+    }
+
+    // Record a fill and store the resulting position (None closes it) in one transaction
+    async fn fill(&self, signal: &Signal, is_buy: bool, quantity: f64, realized_pnl: f64,
+                  position: Option<&PaperPosition>) -> Result<PaperTrade> {
+        let mut tx = self.pg.pool.begin().await?;
+
+        let trade = sqlx::query_as::<_, PaperTrade>(
+            "INSERT INTO paper_trades
+             (account, strategy_id, symbol, side, quantity, price, realized_pnl, rule_id, signal_key)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             RETURNING id, account, strategy_id, symbol, side, quantity, price, realized_pnl,
+                       rule_id, signal_key, executed_at"
+        )
+        .bind(&self.account)
+        .bind(&signal.strategy_id)
+        .bind(&signal.symbol)
+        .bind(if is_buy { "buy" } else { "sell" })
+        .bind(quantity)
+        .bind(signal.price)
+        .bind(realized_pnl)
+        .bind(&signal.rule_id)
+        .bind(signal.idempotency_key())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        match position {
+            Some(position) => {
+                sqlx::query(
+                    "INSERT INTO paper_positions
+                     (account, strategy_id, symbol, is_long, quantity, entry_price, opened_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT (account, strategy_id, symbol) DO UPDATE SET
+                         quantity = EXCLUDED.quantity,
+                         entry_price = EXCLUDED.entry_price,
+                         updated_at = EXCLUDED.updated_at"
+                )
+                .bind(&position.account)
+                .bind(&position.strategy_id)
+                .bind(&position.symbol)
+                .bind(position.is_long)
+                .bind(position.quantity)
+                .bind(position.entry_price)
+                .bind(position.opened_at)
+                .bind(position.updated_at)
+                .execute(&mut *tx)
+                .await?;
+            },
+            None => {
+                sqlx::query(
+                    "DELETE FROM paper_positions WHERE account = $1 AND strategy_id = $2 AND symbol = $3"
+                )
+                .bind(&self.account)
+                .bind(&signal.strategy_id)
+                .bind(&signal.symbol)
+                .execute(&mut *tx)
+                .await?;
+            },
+        }
+
+        tx.commit().await?;
+        Ok(trade)
+    }
+
+    // Risk settings of a strategy, loaded once
+    async fn risk_management(&mut self, strategy_id: &str) -> Result<RiskManagement> {
+        if let Some(risk) = self.risk.get(strategy_id) {
+            return Ok(risk.clone());
+        }
+
+        let risk = self.repository.get_strategy(strategy_id).await?.risk_management;
+        self.risk.insert(strategy_id.to_string(), risk.clone());
+        Ok(risk)
+    }
+
+    async fn signal_executed(&self, key: &str) -> Result<bool> {
+        let executed = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM paper_trades WHERE account = $1 AND signal_key = $2)"
+        )
+        .bind(&self.account)
+        .bind(key)
+        .fetch_one(&self.pg.pool)
+        .await?;
+
+        Ok(executed)
+    }
+
+    async fn position(&self, strategy_id: &str, symbol: &str) -> Result<Option<PaperPosition>> {
+        let position = sqlx::query_as::<_, PaperPosition>(
+            "SELECT account, strategy_id, symbol, is_long, quantity, entry_price, opened_at, updated_at
+             FROM paper_positions
+             WHERE account = $1 AND strategy_id = $2 AND symbol = $3"
+        )
+        .bind(&self.account)
+        .bind(strategy_id)
+        .bind(symbol)
+        .fetch_optional(&self.pg.pool)
+        .await?;
+
+        Ok(position)
+    }
+
+    /// Open positions of the account
+    pub async fn positions(&self) -> Result<Vec<PaperPosition>> {
+        let positions = sqlx::query_as::<_, PaperPosition>(
+            "SELECT account, strategy_id, symbol, is_long, quantity, entry_price, opened_at, updated_at
+             FROM paper_positions
+             WHERE account = $1
+             ORDER BY opened_at"
+        )
+        .bind(&self.account)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        Ok(positions)
+    }
+
+    /// Most recent fills of the account, newest first
+    pub async fn trades(&self, limit: i64) -> Result<Vec<PaperTrade>> {
+        let trades = sqlx::query_as::<_, PaperTrade>(
+            "SELECT id, account, strategy_id, symbol, side, quantity, price, realized_pnl,
+                    rule_id, signal_key, executed_at
+             FROM paper_trades
+             WHERE account = $1
+             ORDER BY executed_at DESC, id DESC
+             LIMIT $2"
+        )
+        .bind(&self.account)
+        .bind(limit)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        Ok(trades)
+    }
+
+    /// Most recent equity snapshots of the account, newest first
+    pub async fn equity_history(&self, limit: i64) -> Result<Vec<PaperEquity>> {
+        let history = sqlx::query_as::<_, PaperEquity>(
+            "SELECT account, time, balance, unrealized_pnl, equity, open_positions
+             FROM paper_equity
+             WHERE account = $1
+             ORDER BY time DESC
+             LIMIT $2"
+        )
+        .bind(&self.account)
+        .bind(limit)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        Ok(history)
+    }
+
+    /// Latest stored close price of a symbol across all intervals
+    pub async fn latest_price(&self, symbol: &str) -> Result<Option<f64>> {
+        let price = sqlx::query_scalar::<_, f64>(
+            "SELECT close_price FROM binance_candles
+             WHERE symbol = $1
+             ORDER BY close_time DESC
+             LIMIT 1"
+        )
+        .bind(symbol)
+        .fetch_optional(&self.pg.pool)
+        .await?;
+
+        Ok(price)
+    }
+
+    /// Current value of the account with open positions marked to the latest prices
+    ///
+    /// Positions of symbols without candles are valued at their entry price.
+    pub async fn snapshot(&self) -> Result<PaperEquity> {
+        let realized = sqlx::query_scalar::<_, f64>(
+            "SELECT COALESCE(SUM(realized_pnl), 0)::float8 FROM paper_trades WHERE account = $1"
+        )
+        .bind(&self.account)
+        .fetch_one(&self.pg.pool)
+        .await?;
+
+        let positions = self.positions().await?;
+        let mut unrealized_pnl = 0.0;
+        for position in &positions {
+            if let Some(price) = self.latest_price(&position.symbol).await? {
+                unrealized_pnl += position.pnl(position.quantity, price);
+            }
+        }
+
+        let balance = self.initial_capital + realized;
+        Ok(PaperEquity {
+            account: self.account.clone(),
+            time: Utc::now(),
+            balance,
+            unrealized_pnl,
+            equity: balance + unrealized_pnl,
+            open_positions: positions.len() as i32,
+        })
+    }
+
+    /// Store a snapshot of the account value in paper_equity
+    pub async fn record_equity(&self) -> Result<PaperEquity> {
+        let snapshot = self.snapshot().await?;
+
+        sqlx::query(
+            "INSERT INTO paper_equity (account, time, balance, unrealized_pnl, equity, open_positions)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (account, time) DO NOTHING"
+        )
+        .bind(&snapshot.account)
+        .bind(snapshot.time)
+        .bind(snapshot.balance)
+        .bind(snapshot.unrealized_pnl)
+        .bind(snapshot.equity)
+        .bind(snapshot.open_positions)
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,226 @@
+// src/execution/risk.rs
+use crate::config::RiskSettings;
+use crate::database::postgres::PostgresManager;
+use crate::execution::paper::PaperEquity;
+use crate::signals::webhook::WebhookNotifier;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+/// Persisted state of the risk guard of one account
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RiskGuardState {
+    pub account: String,
+    pub halted: bool,
+    pub reason: Option<String>,
+    pub halted_at: Option<DateTime<Utc>>,
+    pub reset_at: Option<DateTime<Utc>>,
+    /// UTC day the daily loss is measured over
+    pub day: NaiveDate,
+    /// Equity at the first check of the day
+    pub day_start_equity: f64,
+    /// Highest equity since the last reset
+    pub peak_equity: f64,
+}
+
+/// Result of checking an account against the risk limits
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskStatus {
+    pub state: RiskGuardState,
+    pub equity: f64,
+    /// Loss since the start of the UTC day in percent (negative when up)
+    pub daily_loss_percent: f64,
+    /// Distance below the peak equity in percent
+    pub drawdown_percent: f64,
+    pub open_positions: i32,
+}
+
+/// Kill switch that halts signal execution when an account breaches its risk limits
+///
+/// Once halted, an account stays halted until `reset`, even if the equity recovers.
+pub struct RiskGuard {
+    pg: Arc<PostgresManager>,
+    limits: RiskSettings,
+    notifier: Option<WebhookNotifier>,
+}
+
+impl RiskGuard {
+    pub fn new(pg: Arc<PostgresManager>, limits: RiskSettings, notifier: Option<WebhookNotifier>) -> Self {
+        Self { pg, limits, notifier }
+    }
+
+    /// The limits this guard enforces
+    pub fn limits(&self) -> &RiskSettings {
+        &self.limits
+    }
+
+    /// Create the risk_guard_state table if it doesn't exist
+    pub async fn init_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS risk_guard_state (
+                account VARCHAR PRIMARY KEY,
+                halted BOOLEAN NOT NULL DEFAULT FALSE,
+                reason TEXT,
+                halted_at TIMESTAMPTZ,
+                reset_at TIMESTAMPTZ,
+                day DATE NOT NULL,
+                day_start_equity DOUBLE PRECISION NOT NULL,
+                peak_equity DOUBLE PRECISION NOT NULL
+            )"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Update the daily and peak equity and halt the account if a limit is breached
+    pub async fn check(&self, equity: &PaperEquity) -> Result<RiskStatus> {
+        let mut state = self.load_state(equity).await?;
+
+        let today = equity.time.date_naive();
+        if state.day != today {
+            state.day = today;
+            state.day_start_equity = equity.equity;
+        }
+        state.peak_equity = state.peak_equity.max(equity.equity);
+
+        let status = self.status_of(state, equity);
+        let mut state = status.state.clone();
+
+        if !state.halted {
+            if let Some(reason) = self.breach(&status) {
+                state.halted = true;
+                state.reason = Some(reason.clone());
+                state.halted_at = Some(Utc::now());
+
+                self.notify(&format!("Trading halted for account '{}': {}", state.account, reason)).await;
+            }
+        }
+
+        self.save_state(&state).await?;
+        Ok(RiskStatus { state, ..status })
+    }
+
+    /// Current state and limit usage of an account without changing it
+    pub async fn status(&self, equity: &PaperEquity) -> Result<RiskStatus> {
+        let state = self.load_state(equity).await?;
+        Ok(self.status_of(state, equity))
+    }
+
+    /// Lift a halt and measure daily loss and drawdown from the current equity
+    pub async fn reset(&self, equity: &PaperEquity) -> Result<RiskStatus> {
+        let mut state = self.load_state(equity).await?;
+        let was_halted = state.halted;
+
+        state.halted = false;
+        state.reason = None;
+        state.reset_at = Some(Utc::now());
+        state.day = equity.time.date_naive();
+        state.day_start_equity = equity.equity;
+        state.peak_equity = equity.equity;
+        self.save_state(&state).await?;
+
+        if was_halted {
+            self.notify(&format!("Trading resumed for account '{}' at equity {:.2}", state.account, equity.equity)).await;
+        }
+
+        Ok(self.status_of(state, equity))
+    }
+
+    // Reason the status breaks a limit, if any
+    fn breach(&self, status: &RiskStatus) -> Option<String> {
+        if status.daily_loss_percent >= self.limits.max_daily_loss_percent {
+            Some(format!("daily loss {:.2}% reached the {:.2}% limit",
+                         status.daily_loss_percent, self.limits.max_daily_loss_percent))
+        } else if status.drawdown_percent >= self.limits.max_drawdown_percent {
+            Some(format!("drawdown {:.2}% reached the {:.2}% limit",
+                         status.drawdown_percent, self.limits.max_drawdown_percent))
+        } else if status.open_positions > self.limits.max_open_positions as i32 {
+            Some(format!("{} open positions exceed the limit of {}",
+                         status.open_positions, self.limits.max_open_positions))
+        } else {
+            None
+        }
+    }
+
+    fn status_of(&self, state: RiskGuardState, equity: &PaperEquity) -> RiskStatus {
+        let percent_below = |reference: f64| {
+            if reference > 0.0 { (reference - equity.equity) / reference * 100.0 } else { 0.0 }
+        };
+
+        RiskStatus {
+            daily_loss_percent: percent_below(state.day_start_equity),
+            drawdown_percent: percent_below(state.peak_equity).max(0.0),
+            equity: equity.equity,
+            open_positions: equity.open_positions,
+            state,
+        }
+    }
+
+    // Stored state of the account, or a fresh one starting at the current equity
+    async fn load_state(&self, equity: &PaperEquity) -> Result<RiskGuardState> {
+        let state = sqlx::query_as::<_, RiskGuardState>(
+            "SELECT account, halted, reason, halted_at, reset_at, day, day_start_equity, peak_equity
+             FROM risk_guard_state
+             WHERE account = $1"
+        )
+        .bind(&equity.account)
+        .fetch_optional(&self.pg.pool)
+        .await?;
+
+        Ok(state.unwrap_or_else(|| RiskGuardState {
+            account: equity.account.clone(),
+            halted: false,
+            reason: None,
+            halted_at: None,
+            reset_at: None,
+            day: equity.time.date_naive(),
+            day_start_equity: equity.equity,
+            peak_equity: equity.equity,
+        }))
+    }
+
+    async fn save_state(&self, state: &RiskGuardState) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO risk_guard_state
+             (account, halted, reason, halted_at, reset_at, day, day_start_equity, peak_equity)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (account) DO UPDATE SET
+                 halted = EXCLUDED.halted,
+                 reason = EXCLUDED.reason,
+                 halted_at = EXCLUDED.halted_at,
+                 reset_at = EXCLUDED.reset_at,
+                 day = EXCLUDED.day,
+                 day_start_equity = EXCLUDED.day_start_equity,
+                 peak_equity = EXCLUDED.peak_equity"
+        )
+        .bind(&state.account)
+        .bind(state.halted)
+        .bind(&state.reason)
+        .bind(state.halted_at)
+        .bind(state.reset_at)
+        .bind(state.day)
+        .bind(state.day_start_equity)
+        .bind(state.peak_equity)
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Log an alert and send it to the webhook; delivery failures never stop the guard
+    async fn notify(&self, message: &str) {
+        warn!("{}", message);
+
+        if let Some(notifier) = &self.notifier {
+            match notifier.send(message).await {
+                Ok(()) => info!("Risk alert sent to webhook"),
+                Err(e) => error!("Failed to send risk alert: {:#}", e),
+            }
+        }
+    }
+}
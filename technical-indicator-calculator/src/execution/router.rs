@@ -0,0 +1,132 @@
+// src/execution/router.rs
+use crate::config::ExecutionSettings;
+use crate::database::postgres::PostgresManager;
+use crate::execution::journal::{OrderJournal, STATUS_BLOCKED, STATUS_SUBMITTING};
+use crate::execution::{BinanceTestnetExecutor, Executor, OrderReport, OrderRequest, OrderSide};
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Utc};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Limits checked before an order is sent
+#[derive(Debug, Clone)]
+pub struct RiskLimits {
+    /// Largest order value in quote currency
+    pub max_order_notional: f64,
+    /// Most orders sent in any 24 hours
+    pub max_orders_per_day: u32,
+    /// Symbols that may be traded; empty allows every symbol
+    pub allowed_symbols: Vec<String>,
+}
+
+impl RiskLimits {
+    // Reason the order breaks a limit, if any
+    fn violation(&self, order: &OrderRequest, price: f64, orders_today: i64) -> Option<String> {
+        let notional = order.quantity * price;
+
+        if !self.allowed_symbols.is_empty() && !self.allowed_symbols.contains(&order.symbol) {
+            Some(format!("{} is not in allowed_symbols", order.symbol))
+        } else if notional > self.max_order_notional {
+            Some(format!("Order value {:.2} exceeds max_order_notional {:.2}", notional, self.max_order_notional))
+        } else if orders_today >= self.max_orders_per_day as i64 {
+            Some(format!("{} orders sent in the last 24 hours (max_orders_per_day)", orders_today))
+        } else if order.quantity <= 0.0 {
+            Some("Order quantity must be positive".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Sends orders through an executor after checking risk limits, journaling every attempt
+pub struct OrderRouter {
+    executor: Box<dyn Executor>,
+    journal: OrderJournal,
+    limits: RiskLimits,
+    status_poll_interval: Duration,
+    status_timeout: Duration,
+}
+
+impl OrderRouter {
+    pub fn new(executor: Box<dyn Executor>, journal: OrderJournal, limits: RiskLimits,
+               status_poll_interval: Duration, status_timeout: Duration) -> Self {
+        Self { executor, journal, limits, status_poll_interval, status_timeout }
+    }
+
+    /// Route orders to the Binance testnet with the configured keys and limits
+    pub async fn from_config(pg: Arc<PostgresManager>, settings: &ExecutionSettings) -> Result<Self> {
+        let executor = BinanceTestnetExecutor::new(
+            &settings.rest_url, &settings.api_key, &settings.api_secret, settings.recv_window_ms,
+        )?;
+
+        let journal = OrderJournal::new(pg);
+        journal.init_table().await?;
+
+        let limits = RiskLimits {
+            max_order_notional: settings.max_order_notional,
+            max_orders_per_day: settings.max_orders_per_day,
+            allowed_symbols: settings.allowed_symbols.clone(),
+        };
+
+        Ok(Self::new(
+            Box::new(executor), journal, limits,
+            Duration::from_secs(settings.status_poll_seconds),
+            Duration::from_secs(settings.status_timeout_seconds),
+        ))
+    }
+
+    /// Send a market order and follow it until it is final or the status timeout passes
+    ///
+    /// `price` is the expected fill price, used for the notional limit. Returns None
+    /// when a risk limit blocked the order.
+    pub async fn route(&self, symbol: &str, side: OrderSide, quantity: f64, price: f64,
+                       signal_key: Option<&str>) -> Result<Option<OrderReport>> {
+        let order = OrderRequest {
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            client_order_id: uuid::Uuid::new_v4().simple().to_string(),
+        };
+        let executor = self.executor.name();
+
+        let orders_today = self.journal.count_sent_since(executor, Utc::now() - ChronoDuration::days(1)).await?;
+        if let Some(reason) = self.limits.violation(&order, price, orders_today) {
+            warn!("Blocked {} {} {}: {}", side.as_str(), quantity, symbol, reason);
+            self.journal.record(executor, &order, STATUS_BLOCKED, Some(&reason), signal_key).await?;
+            return Ok(None);
+        }
+
+        // Journal first so an order is never sent without a record of it
+        self.journal.record(executor, &order, STATUS_SUBMITTING, None, signal_key).await?;
+
+        let mut report = match self.executor.submit_order(&order).await {
+            Ok(report) => report,
+            Err(e) => {
+                self.journal.update_failed(&order.client_order_id, &format!("{:#}", e)).await?;
+                return Err(e);
+            }
+        };
+        self.journal.update_report(&report).await?;
+
+        let started = Instant::now();
+        while !report.status.is_final() && started.elapsed() < self.status_timeout {
+            tokio::time::sleep(self.status_poll_interval).await;
+
+            match self.executor.order_status(symbol, &order.client_order_id).await {
+                Ok(latest) => {
+                    report = latest;
+                    self.journal.update_report(&report).await?;
+                },
+                Err(e) => warn!("Failed to poll order {}: {}", order.client_order_id, e),
+            }
+        }
+
+        info!("{} order {} {} {} on {}: {} ({} filled{})",
+              executor, order.client_order_id, side.as_str(), quantity, symbol, report.status.as_str(),
+              report.executed_quantity,
+              report.average_price.map(|p| format!(" at {:.2}", p)).unwrap_or_default());
+
+        Ok(Some(report))
+    }
+}
@@ -0,0 +1,343 @@
+// gRPC server (feature "grpc")
+//
+// Serves the Backtester service of proto/backtester.proto so other services in a trading
+// stack can calculate indicators, run backtests and follow signals over the network.
+// Started with `serve`; uses the database configured by config.toml and the environment.
+use crate::config::AppConfig;
+use crate::database::models::CandleData;
+use crate::database::storage::Storage;
+use crate::error::Error;
+use crate::events;
+use crate::indicators::calculator::IndicatorCalculator;
+use crate::indicators::output::{IndicatorOutput, PatternDirection};
+use crate::signals::ledger::SignalLedger;
+use crate::strategy::evaluator::IndicatorSource;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::{Strategy, StrategyPerformance};
+use crate::strategy::script;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use tracing::{debug, error, info, warn};
+
+pub mod proto {
+    tonic::include_proto!("backtester.v1");
+}
+
+use proto::backtester_server::{Backtester, BacktesterServer};
+use proto::calculate_indicator_request::Candles;
+use proto::indicator_value::Value as ProtoValue;
+use proto::run_backtest_request::Strategy as StrategySource;
+
+// How often StreamSignals checks the ledger for new signals
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Signals buffered per stream before the poller waits for the client
+const SIGNAL_BUFFER: usize = 64;
+
+impl From<Error> for Status {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Validation(_) | Error::Json(_) => Status::invalid_argument(error.to_string()),
+            Error::NotFound(_) => Status::not_found(error.to_string()),
+            _ => Status::internal(error.to_string()),
+        }
+    }
+}
+
+fn from_millis(field: &str, millis: i64) -> Result<DateTime<Utc>, Status> {
+    DateTime::<Utc>::from_timestamp_millis(millis)
+        .ok_or_else(|| Status::invalid_argument(format!("{} is out of range: {}", field, millis)))
+}
+
+fn optional_millis(field: &str, millis: Option<i64>) -> Result<Option<DateTime<Utc>>, Status> {
+    millis.map(|millis| from_millis(field, millis)).transpose()
+}
+
+/// Candles passed in a request
+fn candles_from_series(series: proto::CandleSeries) -> Result<CandleData, Status> {
+    let len = series.open_time_ms.len();
+    for (name, values) in [
+        ("open", &series.open),
+        ("high", &series.high),
+        ("low", &series.low),
+        ("close", &series.close),
+        ("volume", &series.volume),
+    ] {
+        if values.len() != len {
+            return Err(Status::invalid_argument(format!(
+                "{} has {} values but open_time_ms has {}", name, values.len(), len
+            )));
+        }
+    }
+
+    let mut candles = CandleData::new(String::new(), String::new());
+    candles.open_time = series.open_time_ms.iter()
+        .map(|millis| from_millis("open_time_ms", *millis))
+        .collect::<Result<_, _>>()?;
+    candles.close_time = candles.open_time.clone();
+    candles.open = series.open;
+    candles.high = series.high;
+    candles.low = series.low;
+    candles.close = series.close;
+    candles.volume = series.volume;
+    Ok(candles)
+}
+
+fn proto_value(output: IndicatorOutput) -> ProtoValue {
+    match output {
+        IndicatorOutput::Scalar(value) => ProtoValue::Scalar(value.0),
+        IndicatorOutput::Macd(v) => ProtoValue::Macd(proto::Macd { macd: v.macd, signal: v.signal, histogram: v.histogram }),
+        IndicatorOutput::Bbands(v) => ProtoValue::Bbands(proto::Bbands {
+            upper: v.upper,
+            middle: v.middle,
+            lower: v.lower,
+            width: v.width,
+        }),
+        IndicatorOutput::Stoch(v) => ProtoValue::Stoch(proto::Stoch { k: v.k, d: v.d }),
+        IndicatorOutput::Pattern(hit) => ProtoValue::Pattern(proto::Pattern {
+            pattern: hit.pattern,
+            bullish: hit.direction == PatternDirection::Bullish,
+            neutral: hit.direction == PatternDirection::Neutral,
+            strength: hit.strength,
+        }),
+        IndicatorOutput::Values(values) => ProtoValue::Named(proto::NamedValues { values: values.into_iter().collect() }),
+    }
+}
+
+fn proto_performance(p: &StrategyPerformance) -> proto::Performance {
+    proto::Performance {
+        total_trades: p.total_trades,
+        winning_trades: p.winning_trades,
+        losing_trades: p.losing_trades,
+        win_rate: p.win_rate,
+        max_drawdown: p.max_drawdown,
+        profit_factor: p.profit_factor,
+        sharpe_ratio: p.sharpe_ratio,
+        total_return: p.total_return,
+        annualized_return: p.annualized_return,
+        max_consecutive_wins: p.max_consecutive_wins,
+        max_consecutive_losses: p.max_consecutive_losses,
+        avg_profit_per_win: p.avg_profit_per_win,
+        avg_loss_per_loss: p.avg_loss_per_loss,
+        avg_win_holding_period: p.avg_win_holding_period,
+        avg_loss_holding_period: p.avg_loss_holding_period,
+        expectancy: p.expectancy,
+        benchmark_return: p.benchmark_return,
+        excess_return: p.excess_return,
+        alpha: p.alpha,
+        beta: p.beta,
+        sortino_ratio: p.sortino_ratio,
+        calmar_ratio: p.calmar_ratio,
+        value_at_risk: p.value_at_risk,
+        expected_shortfall: p.expected_shortfall,
+        exposure_percent: p.exposure_percent,
+        turnover: p.turnover,
+        unfilled_orders: p.unfilled_orders,
+    }
+}
+
+/// Implementation of the Backtester service
+pub struct BacktesterService {
+    repository: StrategyRepository,
+    ledger: Arc<SignalLedger>,
+}
+
+#[tonic::async_trait]
+impl Backtester for BacktesterService {
+    async fn calculate_indicator(
+        &self,
+        request: Request<proto::CalculateIndicatorRequest>,
+    ) -> Result<Response<proto::CalculateIndicatorResponse>, Status> {
+        let request = request.into_inner();
+
+        let parameters: Value = if request.parameters_json.trim().is_empty() {
+            Value::Object(Default::default())
+        } else {
+            serde_json::from_str(&request.parameters_json)
+                .map_err(|e| Status::invalid_argument(format!("Invalid parameters_json: {}", e)))?
+        };
+
+        let candles = match request.candles {
+            Some(Candles::Series(series)) => candles_from_series(series)?,
+            Some(Candles::Query(query)) => {
+                let start = optional_millis("start_ms", query.start_ms)?;
+                let end = optional_millis("end_ms", query.end_ms)?;
+                self.repository.get_db_connection()
+                    .get_candles(&query.symbol, &query.interval, start, end).await?
+            },
+            None => return Err(Status::invalid_argument("Either query or series is required")),
+        };
+
+        let indicator_name = request.indicator_name;
+        let results = tokio::task::spawn_blocking(move || {
+            IndicatorCalculator::calculate_indicator(&candles, &indicator_name, &parameters)
+        })
+        .await
+        .map_err(|e| Status::internal(format!("Calculation task failed: {}", e)))??;
+
+        let values = results.into_iter()
+            .map(|(time, output)| proto::IndicatorValue {
+                open_time_ms: time.timestamp_millis(),
+                value: Some(proto_value(output)),
+            })
+            .collect();
+
+        Ok(Response::new(proto::CalculateIndicatorResponse { values }))
+    }
+
+    async fn run_backtest(
+        &self,
+        request: Request<proto::RunBacktestRequest>,
+    ) -> Result<Response<proto::RunBacktestResponse>, Status> {
+        let request = request.into_inner();
+
+        let strategy: Strategy = match request.strategy {
+            Some(StrategySource::StrategyId(id)) => self.repository.get_strategy(&id).await?,
+            Some(StrategySource::StrategyJson(json)) => {
+                let strategy = serde_json::from_str(&json)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid strategy_json: {}", e)))?;
+                script::validate_scripts(&strategy)?;
+                strategy
+            },
+            None => return Err(Status::invalid_argument("Either strategy_id or strategy_json is required")),
+        };
+
+        let start_date = optional_millis("start_ms", request.start_ms)?;
+        let end_date = optional_millis("end_ms", request.end_ms)?;
+        let initial_capital = request.initial_capital.unwrap_or(10000.0);
+        let engine_profile = request.engine_profile.as_deref().unwrap_or("default");
+
+        info!("gRPC backtest of strategy {} on {}:{}", strategy.id, request.symbol, request.interval);
+
+        let evaluator = self.repository
+            .evaluator_for(&request.symbol, engine_profile, initial_capital, start_date, end_date).await?
+            .with_indicator_source(if request.recalculate { IndicatorSource::Calculate } else { IndicatorSource::Precomputed })
+            .with_seed(request.seed.unwrap_or_default());
+
+        let performance = evaluator
+            .backtest(&strategy, &request.symbol, &request.interval, start_date, end_date).await?;
+
+        let backtest_id = if request.save {
+            let backtest_id = self.repository.save_backtest_result(
+                &strategy.id, &request.symbol, &request.interval, start_date, end_date, initial_capital, &performance,
+            ).await?;
+            self.repository.record_backtest_engine_settings(backtest_id, &evaluator).await?;
+            Some(backtest_id)
+        } else {
+            None
+        };
+
+        Ok(Response::new(proto::RunBacktestResponse {
+            performance: Some(proto_performance(&performance)),
+            backtest_id,
+        }))
+    }
+
+    type StreamSignalsStream = ReceiverStream<Result<proto::Signal, Status>>;
+
+    async fn stream_signals(
+        &self,
+        request: Request<proto::StreamSignalsRequest>,
+    ) -> Result<Response<Self::StreamSignalsStream>, Status> {
+        let request = request.into_inner();
+        let mut cursor = optional_millis("since_ms", request.since_ms)?.unwrap_or_else(Utc::now);
+        let ledger = self.ledger.clone();
+        let (tx, rx) = mpsc::channel(SIGNAL_BUFFER);
+
+        tokio::spawn(async move {
+            loop {
+                let rows = match ledger.sent_after(cursor).await {
+                    Ok(rows) => rows,
+                    Err(e) => {
+                        error!("Failed to read signals for a gRPC stream: {:#}", e);
+                        let _ = tx.send(Err(Status::internal("Failed to read signals"))).await;
+                        return;
+                    },
+                };
+
+                for (value, sent_at) in rows {
+                    cursor = cursor.max(sent_at);
+
+                    let signal: crate::signals::Signal = match serde_json::from_value(value) {
+                        Ok(signal) => signal,
+                        Err(e) => {
+                            error!("Skipping unreadable signal sent at {}: {}", sent_at, e);
+                            continue;
+                        },
+                    };
+
+                    let matches = (request.strategy_id.is_empty() || signal.strategy_id == request.strategy_id)
+                        && (request.symbol.is_empty() || signal.symbol == request.symbol);
+                    if !matches {
+                        continue;
+                    }
+
+                    let message = proto::Signal {
+                        action_json: serde_json::to_string(&signal.action).unwrap_or_default(),
+                        strategy_id: signal.strategy_id,
+                        symbol: signal.symbol,
+                        interval: signal.interval,
+                        bar_time_ms: signal.bar_time.timestamp_millis(),
+                        rule_id: signal.rule_id,
+                        price: signal.price,
+                        sent_at_ms: sent_at.timestamp_millis(),
+                    };
+
+                    if tx.send(Ok(message)).await.is_err() {
+                        debug!("gRPC signal stream closed by the client");
+                        return;
+                    }
+                }
+
+                tokio::select! {
+                    _ = tokio::time::sleep(SIGNAL_POLL_INTERVAL) => {},
+                    _ = tx.closed() => {
+                        debug!("gRPC signal stream closed by the client");
+                        return;
+                    },
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+/// Serve the Backtester service on `listen` until Ctrl+C
+pub async fn serve(config: &AppConfig, listen: &str) -> anyhow::Result<()> {
+    let addr: SocketAddr = listen.parse()
+        .with_context(|| format!("Invalid listen address: {}", listen))?;
+    events::set_source("grpc");
+
+    let pg = config.database.shared().await?;
+    pg.init_engine_settings_table().await?;
+    let ledger = SignalLedger::from_config(pg.clone(), &config.notifications);
+    ledger.init_table().await?;
+    if config.notifications.signal_poll_seconds == 0 {
+        warn!("Signal generation is disabled in this configuration; StreamSignals only streams signals \
+               delivered by a worker with notifications.signal_poll_seconds > 0");
+    }
+
+    let service = BacktesterService {
+        repository: StrategyRepository::new(pg),
+        ledger: Arc::new(ledger),
+    };
+
+    info!("gRPC server listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(BacktesterServer::new(service))
+        .serve_with_shutdown(addr, async {
+            tokio::signal::ctrl_c().await.ok();
+            info!("Received SIGINT, stopping gRPC server");
+        })
+        .await?;
+
+    Ok(())
+}
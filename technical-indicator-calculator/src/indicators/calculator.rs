@@ -1,9 +1,14 @@
 use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::chunked::call_function_chunked;
+use crate::indicators::cross_symbol;
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin;
+use crate::indicators::transform::Transform;
 use crate::talib_bindings::TaLibAbstract;
-use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
-use tracing::{debug, warn};
+use tracing::{debug, instrument, warn};
 
 pub struct IndicatorCalculator;
 
@@ -18,38 +23,64 @@ fn extract_parameters(params: &Value) -> Vec<(String, Value)> {
     vec![]
 }
 
+// Apply the normalization configured in parameters.transform, if any
+fn apply_transform(parameters: &Value, results: Vec<(usize, IndicatorOutput)>) -> Result<Vec<(usize, IndicatorOutput)>> {
+    match Transform::from_parameters(parameters)? {
+        Some(transform) => transform.apply(results),
+        None => Ok(results),
+    }
+}
+
 impl IndicatorCalculator {
     // Generic function to calculate any indicator using TA-Lib abstract API
+    #[instrument(name = "talib.calculate", skip(candle_data), fields(symbol = %candle_data.symbol, candles = candle_data.close.len()))]
     pub fn calculate_indicator(
         candle_data: &CandleData,
         indicator_name: &str,
         parameters: &Value,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         if candle_data.close.is_empty() {
-            return Err(anyhow::anyhow!("No candle data available"));
+            return Err(Error::validation("No candle data available"));
+        }
+
+        if cross_symbol::is_cross_symbol(indicator_name) {
+            return Err(Error::validation(format!(
+                "{} needs the candles of its other_symbol; use calculate_cross_symbol", indicator_name
+            )));
+        }
+
+        // Registered user-defined indicators take the place of a TA-Lib function
+        let results = if let Some(indicator) = plugin::get_indicator(indicator_name) {
+            debug!("Calculating user-defined indicator '{}' with parameters: {}", indicator_name, parameters);
+            plugin::calculate(indicator.as_ref(), candle_data, parameters)
+        } else {
+            // Extract parameters for TA-Lib
+            let params = extract_parameters(parameters);
+
+            // Get TA-Lib function name
+            let func_name = TaLibAbstract::get_function_name(indicator_name);
+
+            debug!("Calculating indicator '{}' with function '{}' and parameters: {:?}", 
+                   indicator_name, func_name, params);
+
+            // Call TA-Lib function (long window-based series are split into parallel chunks)
+            call_function_chunked(
+                &func_name,
+                Some(&candle_data.open),
+                Some(&candle_data.high),
+                Some(&candle_data.low),
+                Some(&candle_data.close),
+                Some(&candle_data.volume),
+                &params,
+            )
         }
+        .map_err(|e| {
+            warn!("Failed to calculate indicator {}: {}", indicator_name, e);
+            e
+        })?;
+        let results = apply_transform(parameters, results)?;
 
-        // Extract parameters for TA-Lib
-        let params = extract_parameters(parameters);
-        
-        // Get TA-Lib function name
-        let func_name = TaLibAbstract::get_function_name(indicator_name);
-
-        debug!("Calculating indicator '{}' with function '{}' and parameters: {:?}", 
-               indicator_name, func_name, params);
-
-        // Call TA-Lib function
-        let results = TaLibAbstract::call_function(
-            &func_name,
-            Some(&candle_data.open),
-            Some(&candle_data.high),
-            Some(&candle_data.low),
-            Some(&candle_data.close),
-            Some(&candle_data.volume),
-            &params,
-        ).context(format!("Failed to calculate indicator {}", indicator_name))?;
-
-        // Convert results to (DateTime, Value) pairs
+        // Convert results to (DateTime, output) pairs
         let value_results = results
             .into_iter()
             .map(|(idx, value)| {
@@ -69,6 +100,29 @@ impl IndicatorCalculator {
         Ok(value_results)
     }
 
+    // Calculate an indicator between two symbols (spread, ratio, relative strength);
+    // `other` holds the candles of the symbol named in parameters.other_symbol
+    pub fn calculate_cross_symbol(
+        candle_data: &CandleData,
+        other: &CandleData,
+        indicator_name: &str,
+        parameters: &Value,
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
+        if candle_data.close.is_empty() {
+            return Err(Error::validation("No candle data available"));
+        }
+
+        debug!("Calculating cross-symbol indicator '{}' against {} with parameters: {}",
+               indicator_name, other.symbol, parameters);
+
+        let results = cross_symbol::calculate(indicator_name, candle_data, other, parameters)?;
+        let results = apply_transform(parameters, results)?;
+        Ok(results
+            .into_iter()
+            .map(|(idx, value)| (candle_data.open_time[idx], value))
+            .collect())
+    }
+
     // For specific indicator types with multiple outputs, we would need specialized functions
     // Example for MACD which returns three values (MACD, Signal, Histogram)
     pub fn calculate_macd(
@@ -76,7 +130,7 @@ impl IndicatorCalculator {
         fast_period: usize,
         slow_period: usize,
         signal_period: usize,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Parameters for MACD
         let parameters = json!({
             "fast_period": fast_period,
@@ -95,7 +149,7 @@ impl IndicatorCalculator {
         period: usize,
         deviation_up: f64,
         deviation_down: f64,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Parameters for Bollinger Bands
         let parameters = json!({
             "period": period,
@@ -115,7 +169,7 @@ impl IndicatorCalculator {
         k_period: usize,
         slowing: usize,
         d_period: usize,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Parameters for Stochastic
         let parameters = json!({
             "k_period": k_period,
@@ -133,7 +187,7 @@ impl IndicatorCalculator {
     pub fn calculate_rsi(
         candle_data: &CandleData,
         period: usize,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Parameters for RSI
         let parameters = json!({
             "period": period
@@ -148,7 +202,7 @@ impl IndicatorCalculator {
     pub fn calculate_atr(
         candle_data: &CandleData,
         period: usize,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Parameters for ATR
         let parameters = json!({
             "period": period
@@ -162,7 +216,7 @@ impl IndicatorCalculator {
     #[allow(dead_code)]
     pub fn calculate_obv(
         candle_data: &CandleData,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // OBV doesn't need any parameters
         let parameters = json!({});
 
@@ -175,7 +229,7 @@ impl IndicatorCalculator {
     pub fn calculate_adx(
         candle_data: &CandleData,
         period: usize,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Parameters for ADX
         let parameters = json!({
             "period": period
@@ -189,7 +243,7 @@ impl IndicatorCalculator {
     #[allow(dead_code)]
     pub fn calculate_engulfing(
         candle_data: &CandleData,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // No parameters needed for candlestick patterns
         let parameters = json!({});
 
@@ -201,7 +255,7 @@ impl IndicatorCalculator {
     #[allow(dead_code)]
     pub fn calculate_hammer(
         candle_data: &CandleData,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // No parameters needed for candlestick patterns
         let parameters = json!({});
 
@@ -214,7 +268,7 @@ impl IndicatorCalculator {
     pub fn calculate_morning_star(
         candle_data: &CandleData,
         penetration: f64,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Penetration parameter for morning star pattern
         let parameters = json!({
             "penetration": penetration
@@ -233,12 +287,14 @@ impl IndicatorCalculator {
     #[allow(dead_code)]
     pub fn is_indicator_available(indicator_name: &str) -> bool {
         TaLibAbstract::is_function_available(&TaLibAbstract::get_function_name(indicator_name))
+            || plugin::get_indicator(indicator_name).is_some()
+            || cross_symbol::is_cross_symbol(indicator_name)
     }
 
     // Get a list of all supported indicators
     #[allow(dead_code)]
     pub fn get_supported_indicators() -> Vec<String> {
-        vec![
+        let mut indicators = vec![
             "RSI".to_string(),
             "SMA".to_string(),
             "EMA".to_string(),
@@ -251,6 +307,29 @@ impl IndicatorCalculator {
             "CDLENGULFING".to_string(),
             "CDLHAMMER".to_string(),
             "CDLMORNINGSTAR".to_string(),
-        ]
+            "CDLDOJI".to_string(),
+            "CDLDRAGONFLYDOJI".to_string(),
+            "CDLGRAVESTONEDOJI".to_string(),
+            "CDLLONGLEGGEDDOJI".to_string(),
+            "CDLSPINNINGTOP".to_string(),
+            "CDLMARUBOZU".to_string(),
+            "CDLSHOOTINGSTAR".to_string(),
+            "CDLINVERTEDHAMMER".to_string(),
+            "CDLHANGINGMAN".to_string(),
+            "PATTERN_CONFLUENCE".to_string(),
+            "CHART_PATTERNS".to_string(),
+            "ZIGZAG".to_string(),
+            "LINEARREG".to_string(),
+            "ZSCORE".to_string(),
+            "SPREAD".to_string(),
+            "RATIO".to_string(),
+            "RELATIVE_STRENGTH".to_string(),
+            "VOLUME_PROFILE".to_string(),
+            "VOLUME_DELTA".to_string(),
+            "CUMULATIVE_DELTA".to_string(),
+            "ANCHORED_VWAP".to_string(),
+        ];
+        indicators.extend(plugin::registered_indicators());
+        indicators
     }
 }
@@ -0,0 +1,320 @@
+// Catalog of supported indicators
+//
+// What `indicators list` and `indicators describe` print: every indicator that
+// indicator_config rows and strategies can name, with the candle series it reads, its
+// parameters (type, default, valid range) and the output properties strategies reference
+// as indicator.property. The descriptions mirror the parameter handling in
+// talib_bindings and the Rust indicators; registered plugin indicators are listed with
+// the inputs and properties they declare, as their parameters are not introspectable.
+use crate::indicators::chart_patterns::CHART_PATTERNS;
+use crate::indicators::cross_symbol::{RATIO, RELATIVE_STRENGTH, SPREAD};
+use crate::indicators::order_flow::{CUMULATIVE_DELTA, VOLUME_DELTA};
+use crate::indicators::plugin::{self, InputSeries};
+use crate::indicators::statistics::{LINEARREG, ZSCORE};
+use crate::indicators::volume_profile::VOLUME_PROFILE;
+use crate::indicators::vwap::ANCHORED_VWAP;
+use crate::indicators::zigzag::ZIGZAG;
+use crate::talib_bindings::TaLibAbstract;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// A parameter accepted in an indicator's parameters object
+#[derive(Debug, Clone, Serialize)]
+pub struct ParameterInfo {
+    pub name: &'static str,
+    /// "integer", "number", "string" or "list"
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    /// Value used when the parameter is omitted; null when it is required or has no default
+    pub default: Value,
+    /// Accepted values, e.g. ">= 2" or "0..1"; None when any value of the type is accepted
+    pub range: Option<&'static str>,
+    pub description: &'static str,
+}
+
+/// A supported indicator
+#[derive(Debug, Clone, Serialize)]
+pub struct IndicatorInfo {
+    pub name: String,
+    /// Other names resolving to this indicator
+    pub aliases: Vec<&'static str>,
+    /// momentum, overlap, volatility, volume, pattern, statistics, cross_symbol,
+    /// order_flow or plugin
+    pub category: &'static str,
+    pub description: &'static str,
+    /// Candle series read by the calculation
+    pub inputs: Vec<&'static str>,
+    pub parameters: Vec<ParameterInfo>,
+    /// Properties of multi-valued outputs; empty when the indicator produces a single value
+    pub outputs: Vec<&'static str>,
+}
+
+fn param(name: &'static str, kind: &'static str, default: Value, range: Option<&'static str>, description: &'static str) -> ParameterInfo {
+    ParameterInfo { name, kind, default, range, description }
+}
+
+fn period(default: u64, min: &'static str) -> ParameterInfo {
+    param("period", "integer", json!(default), Some(min), "Number of candles in the window")
+}
+
+fn ma_type() -> ParameterInfo {
+    param("ma_type", "integer", json!(0), Some("0..8"),
+          "TA-Lib moving average type (0 SMA, 1 EMA, 2 WMA, 3 DEMA, 4 TEMA, 5 TRIMA, 6 KAMA, 7 MAMA, 8 T3)")
+}
+
+// Filters shared by the candlestick patterns
+fn pattern_parameters() -> Vec<ParameterInfo> {
+    vec![
+        param("trend_window", "integer", json!(0), Some(">= 0"),
+              "Candles of prior trend a reversal pattern needs (0 disables the trend filter)"),
+        param("trend_threshold", "number", json!(0.0), Some(">= 0"),
+              "Minimum relative close change over trend_window, e.g. 0.02 for 2%"),
+        param("min_body_ratio", "number", json!(0.0), Some("0..1"),
+              "Minimum body of the pattern's last candle relative to its high-low range"),
+        param("max_body_ratio", "number", json!(1.0), Some("0..1"),
+              "Maximum body of the pattern's last candle relative to its high-low range"),
+    ]
+}
+
+fn reversal_parameters() -> Vec<ParameterInfo> {
+    vec![
+        param("reversal_percent", "number", Value::Null, Some("> 0"),
+              "Price move in percent confirming a swing; excludes atr_multiplier"),
+        param("atr_multiplier", "number", Value::Null, Some("> 0"),
+              "Multiple of the ATR confirming a swing; excludes reversal_percent"),
+        param("atr_period", "integer", json!(14), Some(">= 1"), "ATR period used with atr_multiplier"),
+    ]
+}
+
+fn other_symbol() -> ParameterInfo {
+    param("other_symbol", "string", Value::Null, None, "Symbol compared against (required)")
+}
+
+fn session() -> ParameterInfo {
+    param("session", "string", Value::Null, Some("day"), "\"day\" to reset at the start of each trading day of the symbol's session (UTC day for crypto)")
+}
+
+fn delta_source() -> ParameterInfo {
+    param("source", "string", json!("auto"), Some("auto, taker, candle"),
+          "Taker buy volume, the candle-shape approximation, or taker volume where stored")
+}
+
+const OHLC: [&str; 4] = ["open", "high", "low", "close"];
+const HLC: [&str; 3] = ["high", "low", "close"];
+
+const CANDLESTICK_PATTERNS: [(&str, &str, &str); 12] = [
+    ("CDLENGULFING", "ENGULFING", "Bullish or bearish engulfing"),
+    ("CDLHAMMER", "HAMMER", "Hammer"),
+    ("CDLMORNINGSTAR", "MORNINGSTAR", "Morning star"),
+    ("CDLDOJI", "DOJI", "Doji"),
+    ("CDLDRAGONFLYDOJI", "DRAGONFLYDOJI", "Dragonfly doji"),
+    ("CDLGRAVESTONEDOJI", "GRAVESTONEDOJI", "Gravestone doji"),
+    ("CDLLONGLEGGEDDOJI", "LONGLEGGEDDOJI", "Long-legged doji"),
+    ("CDLSPINNINGTOP", "SPINNINGTOP", "Spinning top"),
+    ("CDLMARUBOZU", "MARUBOZU", "Marubozu"),
+    ("CDLSHOOTINGSTAR", "SHOOTINGSTAR", "Shooting star"),
+    ("CDLINVERTEDHAMMER", "INVERTEDHAMMER", "Inverted hammer"),
+    ("CDLHANGINGMAN", "HANGINGMAN", "Hanging man"),
+];
+
+fn info(
+    name: &str,
+    aliases: &[&'static str],
+    category: &'static str,
+    description: &'static str,
+    inputs: &[&'static str],
+    parameters: Vec<ParameterInfo>,
+    outputs: &[&'static str],
+) -> IndicatorInfo {
+    IndicatorInfo {
+        name: name.to_string(),
+        aliases: aliases.to_vec(),
+        category,
+        description,
+        inputs: inputs.to_vec(),
+        parameters,
+        outputs: outputs.to_vec(),
+    }
+}
+
+// Built-in indicators in the order they are listed
+fn builtin_indicators() -> Vec<IndicatorInfo> {
+    let mut indicators = vec![
+        info("RSI", &[], "momentum", "Relative Strength Index", &["close"],
+             vec![period(14, ">= 2")], &[]),
+        info("MACD", &[], "momentum", "Moving Average Convergence/Divergence", &["close"],
+             vec![
+                 param("fast_period", "integer", json!(12), Some(">= 2"), "Fast EMA period"),
+                 param("slow_period", "integer", json!(26), Some(">= 2"), "Slow EMA period"),
+                 param("signal_period", "integer", json!(9), Some(">= 1"), "Signal line EMA period"),
+             ],
+             &["macd", "signal", "histogram"]),
+        info("STOCH", &[], "momentum", "Stochastic oscillator", &HLC,
+             vec![
+                 param("k_period", "integer", json!(14), Some(">= 1"), "Fast %K period"),
+                 param("slowing", "integer", json!(3), Some(">= 1"), "Smoothing of %K into slow %K"),
+                 param("d_period", "integer", json!(3), Some(">= 1"), "%D period"),
+                 ma_type(),
+             ],
+             &["k", "d"]),
+        info("STOCHRSI", &[], "momentum", "Stochastic RSI", &["close"],
+             vec![
+                 period(14, ">= 2"),
+                 param("k_period", "integer", json!(5), Some(">= 1"), "Fast %K period"),
+                 param("d_period", "integer", json!(3), Some(">= 1"), "Fast %D period"),
+                 ma_type(),
+             ],
+             &["k", "d"]),
+        info("CCI", &[], "momentum", "Commodity Channel Index", &HLC,
+             vec![period(14, ">= 2")], &[]),
+        info("MOM", &[], "momentum", "Momentum: close minus the close `period` candles ago", &["close"],
+             vec![period(10, ">= 1")], &[]),
+        info("MFI", &[], "volume", "Money Flow Index", &["high", "low", "close", "volume"],
+             vec![period(14, ">= 2")], &[]),
+        info("SMA", &[], "overlap", "Simple moving average", &["close"],
+             vec![period(14, ">= 2")], &[]),
+        info("EMA", &[], "overlap", "Exponential moving average", &["close"],
+             vec![period(9, ">= 2")], &[]),
+        info("BBANDS", &[], "overlap", "Bollinger Bands", &["close"],
+             vec![
+                 period(20, ">= 2"),
+                 param("deviation_up", "number", json!(2.0), None, "Standard deviations of the upper band"),
+                 param("deviation_down", "number", json!(2.0), None, "Standard deviations of the lower band"),
+                 ma_type(),
+             ],
+             &["upper", "middle", "lower", "width"]),
+        info("ATR", &[], "volatility", "Average True Range", &HLC,
+             vec![period(14, ">= 1")], &[]),
+        info("ADX", &[], "volatility", "Average Directional Movement Index", &HLC,
+             vec![period(14, ">= 2")], &[]),
+        info("OBV", &[], "volume", "On-Balance Volume", &["close", "volume"],
+             vec![], &[]),
+    ];
+
+    for (name, alias, description) in CANDLESTICK_PATTERNS {
+        let mut parameters = pattern_parameters();
+        if name == "CDLMORNINGSTAR" {
+            parameters.push(param("penetration", "number", json!(0.3), Some(">= 0"),
+                                  "How far the third candle must close into the first candle's body"));
+        }
+        indicators.push(info(name, &[alias], "pattern", description, &OHLC, parameters, &["pattern", "type", "strength"]));
+    }
+
+    let mut confluence = vec![
+        param("patterns", "list", Value::Null, None,
+              "Candlestick patterns to combine, by name or alias (default: all)"),
+        param("window", "integer", json!(3), Some(">= 1"), "Candles a pattern keeps counting after it fired"),
+        param("decay", "number", json!(0.5), Some("0..1"), "Weight lost per candle since the pattern fired"),
+    ];
+    confluence.extend(pattern_parameters());
+
+    indicators.extend([
+        info("PATTERN_CONFLUENCE", &["CONFLUENCE"], "pattern",
+             "Decaying sum of bullish and bearish candlestick patterns", &OHLC, confluence,
+             &["bullish", "bearish", "score"]),
+        info(CHART_PATTERNS, &[], "pattern",
+             "Double tops and bottoms, head and shoulders, triangles and flags over swing pivots", &HLC,
+             [
+                 vec![
+                     param("pivot_window", "integer", json!(5), Some(">= 1"),
+                           "Candles on each side of a window-extreme pivot (without a reversal threshold)"),
+                     param("tolerance", "number", json!(0.02), Some("0..1"),
+                           "Relative price difference under which pivots count as equal"),
+                     param("min_pole", "number", json!(0.05), Some("0..1"),
+                           "Minimum relative move of a flag's pole"),
+                 ],
+                 reversal_parameters(),
+             ].concat(),
+             &[
+                 "double_top", "double_bottom", "head_and_shoulders", "inverse_head_and_shoulders",
+                 "ascending_triangle", "descending_triangle", "bull_flag", "bear_flag",
+                 "direction", "breakout", "target",
+             ]),
+        info(ZIGZAG, &[], "pattern", "Confirmed swing highs and lows", &HLC,
+             reversal_parameters(), &["swing", "price", "bars_ago"]),
+        info(LINEARREG, &[], "statistics", "Least-squares line over a rolling window", &["close"],
+             vec![
+                 period(14, ">= 2"),
+                 param("source", "string", json!("close"), Some("open, high, low, close, volume"), "Candle series regressed"),
+             ],
+             &["slope", "intercept", "value", "forecast"]),
+        info(ZSCORE, &[], "statistics", "Distance from the rolling mean in standard deviations", &["close"],
+             vec![
+                 period(20, ">= 2"),
+                 param("source", "string", json!("close"), Some("open, high, low, close, volume"), "Candle series scored"),
+             ],
+             &[]),
+        info(SPREAD, &[], "cross_symbol", "close - hedge_ratio * other close", &["close"],
+             vec![
+                 other_symbol(),
+                 param("hedge_ratio", "number", json!(1.0), None, "Multiplier of the other symbol's close"),
+             ],
+             &[]),
+        info(RATIO, &[], "cross_symbol", "close / other close", &["close"],
+             vec![other_symbol()], &[]),
+        info(RELATIVE_STRENGTH, &[], "cross_symbol",
+             "Percentage change of close / other close over `period` candles", &["close"],
+             vec![other_symbol(), period(14, ">= 1")], &[]),
+        info(VOLUME_PROFILE, &[], "volume", "Point of control and value area of volume per price level",
+             &["high", "low", "volume"],
+             vec![
+                 period(24, ">= 1"),
+                 session(),
+                 param("bins", "integer", json!(24), Some(">= 2"), "Price levels the window's range is split into"),
+                 param("value_area", "number", json!(0.7), Some("0..1"), "Share of the volume in the value area"),
+             ],
+             &["poc", "vah", "val"]),
+        info(VOLUME_DELTA, &[], "order_flow", "Taker buy minus sell volume per candle", &["open", "high", "low", "close", "volume"],
+             vec![delta_source()], &["delta", "buy", "sell"]),
+        info(CUMULATIVE_DELTA, &[], "order_flow", "Running sum of the volume delta", &["open", "high", "low", "close", "volume"],
+             vec![delta_source(), session()], &[]),
+        info(ANCHORED_VWAP, &[], "volume", "Volume-weighted average typical price since the session start or first candle",
+             &["high", "low", "close", "volume"],
+             vec![
+                 param("anchor", "string", json!("session"), Some("session, start"),
+                       "Reset at the start of each trading day of the symbol's session, or never"),
+                 param("session_only", "boolean", json!(false), None, "Leave out candles outside the trading hours"),
+             ],
+             &[]),
+    ]);
+
+    indicators
+}
+
+fn input_name(input: InputSeries) -> &'static str {
+    match input {
+        InputSeries::Open => "open",
+        InputSeries::High => "high",
+        InputSeries::Low => "low",
+        InputSeries::Close => "close",
+        InputSeries::Volume => "volume",
+    }
+}
+
+/// All supported indicators: the built-in ones followed by registered plugins
+pub fn all_indicators() -> Vec<IndicatorInfo> {
+    let mut indicators = builtin_indicators();
+    for name in plugin::registered_indicators() {
+        if let Some(indicator) = plugin::get_indicator(&name) {
+            indicators.push(IndicatorInfo {
+                name,
+                aliases: vec![],
+                category: "plugin",
+                description: "User-defined indicator",
+                inputs: indicator.inputs().iter().map(|input| input_name(*input)).collect(),
+                parameters: vec![],
+                outputs: indicator.properties().to_vec(),
+            });
+        }
+    }
+    indicators
+}
+
+/// Indicator of the given name or alias, matched case-insensitively
+pub fn find_indicator(name: &str) -> Option<IndicatorInfo> {
+    let name = name.trim().to_uppercase();
+    let function_name = TaLibAbstract::get_function_name(&name);
+    all_indicators()
+        .into_iter()
+        .find(|indicator| indicator.name == function_name || indicator.aliases.contains(&name.as_str()))
+}
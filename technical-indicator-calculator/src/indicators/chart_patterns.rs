@@ -0,0 +1,361 @@
+// Chart patterns over swing pivots
+//
+// Multi-bar geometric patterns (double tops and bottoms, head and shoulders, ascending and
+// descending triangles, flags) are matched against the sequence of swing highs and lows.
+// Pivots are either window extremes, confirmed `pivot_window` candles after the extreme,
+// or ZigZag swings when a reversal threshold is given (see indicators::zigzag). Either
+// way a pattern is reported on the candle where its last pivot becomes known and never
+// looks ahead.
+//
+// The detector is available to indicator_config and strategies as the built-in
+// CHART_PATTERNS indicator. Each event carries the pattern as a one-hot property (e.g.
+// double_bottom = 1), its direction (+1 bullish, -1 bearish), the breakout level (neckline
+// or triangle side) and the measured-move target, so rules can wait for the breakout:
+// "chart.double_bottom == 1" and "close > chart.breakout".
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin::{Indicator, InputSeries};
+use crate::indicators::zigzag::{self, ReversalThreshold, SwingKind};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Name of the chart pattern indicator
+pub const CHART_PATTERNS: &str = "CHART_PATTERNS";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartPattern {
+    DoubleTop,
+    DoubleBottom,
+    HeadAndShoulders,
+    InverseHeadAndShoulders,
+    AscendingTriangle,
+    DescendingTriangle,
+    BullFlag,
+    BearFlag,
+}
+
+impl ChartPattern {
+    pub const ALL: [ChartPattern; 8] = [
+        ChartPattern::DoubleTop,
+        ChartPattern::DoubleBottom,
+        ChartPattern::HeadAndShoulders,
+        ChartPattern::InverseHeadAndShoulders,
+        ChartPattern::AscendingTriangle,
+        ChartPattern::DescendingTriangle,
+        ChartPattern::BullFlag,
+        ChartPattern::BearFlag,
+    ];
+
+    /// Property name in the indicator output
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartPattern::DoubleTop => "double_top",
+            ChartPattern::DoubleBottom => "double_bottom",
+            ChartPattern::HeadAndShoulders => "head_and_shoulders",
+            ChartPattern::InverseHeadAndShoulders => "inverse_head_and_shoulders",
+            ChartPattern::AscendingTriangle => "ascending_triangle",
+            ChartPattern::DescendingTriangle => "descending_triangle",
+            ChartPattern::BullFlag => "bull_flag",
+            ChartPattern::BearFlag => "bear_flag",
+        }
+    }
+
+    pub fn is_bullish(self) -> bool {
+        matches!(
+            self,
+            ChartPattern::DoubleBottom
+                | ChartPattern::InverseHeadAndShoulders
+                | ChartPattern::AscendingTriangle
+                | ChartPattern::BullFlag
+        )
+    }
+}
+
+/// A pattern completed at candle `index`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartPatternEvent {
+    pub index: usize,
+    pub pattern: ChartPattern,
+    /// Price whose break confirms the pattern (neckline, triangle side or flag edge)
+    pub breakout: f64,
+    /// Measured-move target after the breakout
+    pub target: f64,
+}
+
+impl ChartPatternEvent {
+    fn to_output(&self) -> IndicatorOutput {
+        let mut values: BTreeMap<String, f64> = ChartPattern::ALL
+            .iter()
+            .map(|pattern| (pattern.name().to_string(), if *pattern == self.pattern { 1.0 } else { 0.0 }))
+            .collect();
+        values.insert("direction".to_string(), if self.pattern.is_bullish() { 1.0 } else { -1.0 });
+        values.insert("breakout".to_string(), self.breakout);
+        values.insert("target".to_string(), self.target);
+        IndicatorOutput::Values(values)
+    }
+}
+
+/// Detection settings, from the indicator parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartPatternSettings {
+    /// Candles on each side a swing high or low must exceed
+    pub pivot_window: usize,
+    /// Relative difference under which two pivots count as level, e.g. 0.02 for 2%
+    pub tolerance: f64,
+    /// Minimum relative move of a flag pole
+    pub min_pole: f64,
+    /// Use ZigZag swings with this threshold instead of window pivots
+    pub reversal: Option<ReversalThreshold>,
+}
+
+impl Default for ChartPatternSettings {
+    fn default() -> Self {
+        Self {
+            pivot_window: 5,
+            tolerance: 0.02,
+            min_pole: 0.05,
+            reversal: None,
+        }
+    }
+}
+
+impl ChartPatternSettings {
+    pub fn from_parameters(parameters: &Value) -> Result<Self> {
+        let defaults = Self::default();
+
+        let pivot_window = match parameters.get("pivot_window") {
+            None => defaults.pivot_window,
+            Some(value) => value
+                .as_u64()
+                .filter(|window| *window >= 1)
+                .ok_or_else(|| Error::validation(format!("pivot_window must be a positive integer, got {}", value)))?
+                as usize,
+        };
+
+        let fraction = |name: &str, default: f64| -> Result<f64> {
+            match parameters.get(name) {
+                None => Ok(default),
+                Some(value) => value
+                    .as_f64()
+                    .filter(|v| *v > 0.0 && *v < 1.0)
+                    .ok_or_else(|| Error::validation(format!("{} must be between 0 and 1, got {}", name, value))),
+            }
+        };
+
+        Ok(Self {
+            pivot_window,
+            tolerance: fraction("tolerance", defaults.tolerance)?,
+            min_pole: fraction("min_pole", defaults.min_pole)?,
+            reversal: ReversalThreshold::from_parameters(parameters)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PivotKind {
+    High,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pivot {
+    kind: PivotKind,
+    price: f64,
+}
+
+// Whether two prices are level within the tolerance
+fn level(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance * a.abs().max(b.abs())
+}
+
+// Window extremes as (confirming candle, pivot): the extreme at `idx` is confirmed once
+// the k candles after it are known
+fn window_pivots(high: &[f64], low: &[f64], k: usize) -> Vec<(usize, Pivot)> {
+    let len = high.len().min(low.len());
+    let mut pivots = Vec::new();
+
+    for confirmed in (2 * k)..len {
+        let idx = confirmed - k;
+        let before = idx - k..idx;
+        let after = idx + 1..=idx + k;
+
+        if before.clone().all(|i| high[idx] > high[i]) && after.clone().all(|i| high[idx] >= high[i]) {
+            pivots.push((confirmed, Pivot { kind: PivotKind::High, price: high[idx] }));
+        }
+        if before.all(|i| low[idx] < low[i]) && after.all(|i| low[idx] <= low[i]) {
+            pivots.push((confirmed, Pivot { kind: PivotKind::Low, price: low[idx] }));
+        }
+    }
+
+    pivots
+}
+
+/// Detect chart patterns; events are in ascending candle order, at most one per candle
+pub fn detect(candles: &CandleData, settings: &ChartPatternSettings) -> Vec<ChartPatternEvent> {
+    let confirmed_pivots = match settings.reversal {
+        Some(threshold) => zigzag::swing_pivots(candles, threshold)
+            .into_iter()
+            .map(|swing| {
+                let kind = match swing.kind {
+                    SwingKind::High => PivotKind::High,
+                    SwingKind::Low => PivotKind::Low,
+                };
+                (swing.confirmed, Pivot { kind, price: swing.price })
+            })
+            .collect(),
+        None => window_pivots(&candles.high, &candles.low, settings.pivot_window),
+    };
+
+    let mut pivots: Vec<Pivot> = Vec::new();
+    let mut events: Vec<ChartPatternEvent> = Vec::new();
+
+    for (confirmed, pivot) in confirmed_pivots {
+        if !push_pivot(&mut pivots, pivot) {
+            continue;
+        }
+        // A later pivot on the same candle replaces that candle's event
+        if events.last().is_some_and(|event| event.index == confirmed) {
+            events.pop();
+        }
+        if let Some((pattern, breakout, target)) = match_pattern(&pivots, settings) {
+            events.push(ChartPatternEvent { index: confirmed, pattern, breakout, target });
+        }
+    }
+
+    events
+}
+
+// Add a pivot, keeping highs and lows alternating: of two consecutive pivots of the same
+// kind only the more extreme one is kept. Returns whether the pivot sequence changed.
+fn push_pivot(pivots: &mut Vec<Pivot>, pivot: Pivot) -> bool {
+    match pivots.last_mut() {
+        Some(last) if last.kind == pivot.kind => {
+            let more_extreme = match pivot.kind {
+                PivotKind::High => pivot.price > last.price,
+                PivotKind::Low => pivot.price < last.price,
+            };
+            if more_extreme {
+                *last = pivot;
+            }
+            more_extreme
+        },
+        _ => {
+            pivots.push(pivot);
+            true
+        },
+    }
+}
+
+// Pattern ending at the last pivot as (pattern, breakout, target), checked from the
+// widest formation to the narrowest
+fn match_pattern(pivots: &[Pivot], settings: &ChartPatternSettings) -> Option<(ChartPattern, f64, f64)> {
+    let tol = settings.tolerance;
+    let last = |n: usize| -> Option<Vec<f64>> {
+        pivots.len().checked_sub(n).map(|start| pivots[start..].iter().map(|p| p.price).collect())
+    };
+    let ends_high = pivots.last()?.kind == PivotKind::High;
+
+    // Head and shoulders: three peaks, the middle one highest, shoulders level
+    if let Some(p) = last(5) {
+        let (s1, t1, head, t2, s2) = (p[0], p[1], p[2], p[3], p[4]);
+        if ends_high && head > s1.max(s2) * (1.0 + tol) && level(s1, s2, tol) {
+            let neckline = (t1 + t2) / 2.0;
+            return Some((ChartPattern::HeadAndShoulders, neckline, neckline - (head - neckline)));
+        }
+        if !ends_high && head < s1.min(s2) * (1.0 - tol) && level(s1, s2, tol) {
+            let neckline = (t1 + t2) / 2.0;
+            return Some((ChartPattern::InverseHeadAndShoulders, neckline, neckline + (neckline - head)));
+        }
+    }
+
+    // Double top and bottom: two level extremes around a clear trough or peak
+    if let Some(p) = last(3) {
+        let (first, middle, second) = (p[0], p[1], p[2]);
+        if ends_high && level(first, second, tol) && middle < first.min(second) * (1.0 - tol) {
+            let top = first.max(second);
+            return Some((ChartPattern::DoubleTop, middle, middle - (top - middle)));
+        }
+        if !ends_high && level(first, second, tol) && middle > first.max(second) * (1.0 + tol) {
+            let bottom = first.min(second);
+            return Some((ChartPattern::DoubleBottom, middle, middle + (middle - bottom)));
+        }
+    }
+
+    if let Some(p) = last(4) {
+        // Earlier and later high and low of the last four pivots
+        let (highs, lows) = if ends_high { ((p[1], p[3]), (p[0], p[2])) } else { ((p[0], p[2]), (p[1], p[3])) };
+
+        // Triangles: one flat side and one converging side
+        if level(highs.0, highs.1, tol) && lows.1 > lows.0 * (1.0 + tol) {
+            let resistance = highs.0.max(highs.1);
+            return Some((ChartPattern::AscendingTriangle, resistance, resistance + (resistance - lows.0)));
+        }
+        if level(lows.0, lows.1, tol) && highs.1 < highs.0 * (1.0 - tol) {
+            let support = lows.0.min(lows.1);
+            return Some((ChartPattern::DescendingTriangle, support, support - (highs.0 - support)));
+        }
+
+        // Flags: a strong pole followed by a shallow counter-trend channel
+        if ends_high {
+            let (base, pole_top, flag_low, flag_high) = (p[0], p[1], p[2], p[3]);
+            let pole = pole_top - base;
+            if base > 0.0 && pole / base >= settings.min_pole && flag_low > pole_top - pole / 2.0 && flag_high < pole_top {
+                return Some((ChartPattern::BullFlag, flag_high, flag_high + pole));
+            }
+        } else {
+            let (base, pole_bottom, flag_high, flag_low) = (p[0], p[1], p[2], p[3]);
+            let pole = base - pole_bottom;
+            if base > 0.0 && pole / base >= settings.min_pole && flag_high < pole_bottom + pole / 2.0 && flag_low > pole_bottom {
+                return Some((ChartPattern::BearFlag, flag_low, flag_low - pole));
+            }
+        }
+    }
+
+    None
+}
+
+// Output properties: one per pattern plus the event's levels
+const PROPERTIES: [&str; 11] = [
+    "double_top",
+    "double_bottom",
+    "head_and_shoulders",
+    "inverse_head_and_shoulders",
+    "ascending_triangle",
+    "descending_triangle",
+    "bull_flag",
+    "bear_flag",
+    "direction",
+    "breakout",
+    "target",
+];
+
+/// The CHART_PATTERNS indicator
+pub struct ChartPatterns;
+
+impl Indicator for ChartPatterns {
+    fn name(&self) -> &str {
+        CHART_PATTERNS
+    }
+
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::High, InputSeries::Low, InputSeries::Close]
+    }
+
+    // Pivots form an alternating sequence over the entire history
+    fn lookback(&self, _parameters: &Value) -> Option<usize> {
+        None
+    }
+
+    fn properties(&self) -> &'static [&'static str] {
+        &PROPERTIES
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let settings = ChartPatternSettings::from_parameters(parameters)?;
+        Ok(detect(candles, &settings)
+            .iter()
+            .map(|event| (event.index, event.to_output()))
+            .collect())
+    }
+}
@@ -0,0 +1,143 @@
+// Chunk-level parallel calculation for window-based indicators
+use crate::error::Result;
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin;
+use crate::indicators::transform::Transform;
+use crate::talib_bindings::TaLibAbstract;
+use rayon::prelude::*;
+use serde_json::Value;
+use tracing::debug;
+
+// Series shorter than this are always calculated serially
+const MIN_PARALLEL_LEN: usize = 50_000;
+
+// Minimum number of output candles per chunk
+const MIN_CHUNK_LEN: usize = 10_000;
+
+// Overlap for candlestick patterns: TA-Lib's default candle settings average over
+// at most 10 prior candles, plus up to 3 candles for the pattern itself
+const PATTERN_LOOKBACK: usize = 16;
+
+// Number of candles a window-based function needs before its first output, including
+// the window of a normalization transform.
+// Returns None for recursive indicators (EMA, RSI, MACD, ...) whose values depend
+// on the entire history and therefore cannot be split into independent chunks.
+pub fn window_lookback(function_name: &str, parameters: &[(String, Value)]) -> Option<usize> {
+    let lookback = indicator_lookback(function_name, parameters)?;
+    let parameters: serde_json::Map<String, Value> = parameters.iter().cloned().collect();
+    match Transform::from_parameters(&Value::Object(parameters)).ok()? {
+        Some(transform) => Some(lookback + transform.lookback()),
+        None => Some(lookback),
+    }
+}
+
+fn indicator_lookback(function_name: &str, parameters: &[(String, Value)]) -> Option<usize> {
+    match function_name.to_uppercase().as_str() {
+        "SMA" => {
+            let period = TaLibAbstract::get_integer_param(parameters, "period", 14).ok()?;
+            Some(period.max(1) as usize - 1)
+        },
+        "BBANDS" => {
+            // Only the SMA-based bands are purely window-based
+            let ma_type = TaLibAbstract::get_integer_param(parameters, "ma_type", 0).ok()?;
+            if ma_type != 0 {
+                return None;
+            }
+            let period = TaLibAbstract::get_integer_param(parameters, "period", 20).ok()?;
+            Some(period.max(1) as usize - 1)
+        },
+        "CDLENGULFING" | "CDLHAMMER" | "CDLMORNINGSTAR" | "CDLDOJI" | "CDLDRAGONFLYDOJI" |
+        "CDLGRAVESTONEDOJI" | "CDLLONGLEGGEDDOJI" | "CDLSPINNINGTOP" | "CDLMARUBOZU" |
+        "CDLSHOOTINGSTAR" | "CDLINVERTEDHAMMER" | "CDLHANGINGMAN" => {
+            // The optional trend check looks further back than the pattern itself
+            let trend_window = TaLibAbstract::get_integer_param(parameters, "trend_window", 0).ok()?;
+            Some(PATTERN_LOOKBACK + trend_window.max(0) as usize)
+        },
+        "PATTERN_CONFLUENCE" => {
+            // Hits keep counting for `window` candles after the pattern
+            let trend_window = TaLibAbstract::get_integer_param(parameters, "trend_window", 0).ok()?;
+            let window = TaLibAbstract::get_integer_param(parameters, "window", 3).ok()?;
+            Some(PATTERN_LOOKBACK + trend_window.max(0) as usize + window.max(1) as usize - 1)
+        },
+        _ => {
+            // User-defined indicators declare their own lookback
+            let indicator = plugin::get_indicator(function_name)?;
+            let parameters: serde_json::Map<String, Value> = parameters.iter().cloned().collect();
+            indicator.lookback(&Value::Object(parameters))
+        },
+    }
+}
+
+// Slice an optional input series to the chunk window
+fn window(series: Option<&[f64]>, from: usize, end: usize) -> Option<&[f64]> {
+    series.map(|s| &s[from.min(s.len())..end.min(s.len())])
+}
+
+// Call a TA-Lib function, splitting long series of window-based indicators into
+// overlapping chunks that are calculated in parallel with rayon.
+//
+// Each chunk is extended backwards by the indicator lookback so that its first
+// output is computed from a full window. The stitched result equals the serial
+// calculation up to floating-point rounding: TA-Lib keeps running sums, which
+// accumulate differently from a chunk's start than from the series start. Chunks
+// are concatenated in order, so the output is deterministic regardless of thread
+// scheduling.
+pub fn call_function_chunked(
+    function_name: &str,
+    open: Option<&[f64]>,
+    high: Option<&[f64]>,
+    low: Option<&[f64]>,
+    close: Option<&[f64]>,
+    volume: Option<&[f64]>,
+    parameters: &[(String, Value)],
+) -> Result<Vec<(usize, IndicatorOutput)>> {
+    let len = close.map(|c| c.len()).unwrap_or(0);
+
+    let lookback = match window_lookback(function_name, parameters) {
+        Some(lookback) if len >= MIN_PARALLEL_LEN => lookback,
+        _ => return TaLibAbstract::call_function(function_name, open, high, low, close, volume, parameters),
+    };
+
+    let threads = rayon::current_num_threads().max(1);
+    let chunk_len = (len / threads).max(MIN_CHUNK_LEN).max(lookback + 1);
+    let starts: Vec<usize> = (0..len).step_by(chunk_len).collect();
+
+    if starts.len() < 2 {
+        return TaLibAbstract::call_function(function_name, open, high, low, close, volume, parameters);
+    }
+
+    debug!("Calculating {} over {} candles in {} chunks of {} (lookback {})",
+           function_name, len, starts.len(), chunk_len, lookback);
+
+    let chunks: Vec<Result<Vec<(usize, IndicatorOutput)>>> = starts
+        .par_iter()
+        .map(|&start| {
+            let end = (start + chunk_len).min(len);
+            let from = start.saturating_sub(lookback);
+
+            let results = TaLibAbstract::call_function(
+                function_name,
+                window(open, from, end),
+                window(high, from, end),
+                window(low, from, end),
+                window(close, from, end),
+                window(volume, from, end),
+                parameters,
+            )?;
+
+            // Shift back to series indices and drop outputs owned by the previous chunk
+            Ok(results
+                .into_iter()
+                .map(|(idx, value)| (idx + from, value))
+                .filter(|(idx, _)| *idx >= start)
+                .collect())
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(len);
+    for chunk in chunks {
+        results.extend(chunk?);
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,101 @@
+// Indicators between two symbols
+//
+// SPREAD, RATIO and RELATIVE_STRENGTH are configured on one symbol and name the other in
+// the `other_symbol` parameter, e.g. RATIO of ETHUSDT with {"other_symbol": "BTCUSDT"}
+// for a synthetic ETHBTC. Closes are paired by open time; candles the other symbol
+// doesn't have produce no value. Values are stored under the configured symbol like any
+// other indicator, so strategies on that symbol reference them as usual.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use serde_json::Value;
+
+/// close - hedge_ratio * other close (hedge_ratio defaults to 1)
+pub const SPREAD: &str = "SPREAD";
+
+/// close / other close
+pub const RATIO: &str = "RATIO";
+
+/// Percentage change of the ratio over `period` candles (default 14): how much the
+/// symbol out- or underperformed the other one
+pub const RELATIVE_STRENGTH: &str = "RELATIVE_STRENGTH";
+
+/// Whether an indicator needs the candles of a second symbol
+pub fn is_cross_symbol(indicator_name: &str) -> bool {
+    matches!(indicator_name.to_uppercase().as_str(), SPREAD | RATIO | RELATIVE_STRENGTH)
+}
+
+/// The second symbol of a cross-symbol indicator
+pub fn other_symbol(parameters: &Value) -> Result<&str> {
+    parameters.get("other_symbol")
+        .and_then(Value::as_str)
+        .filter(|symbol| !symbol.is_empty())
+        .ok_or_else(|| Error::validation("Cross-symbol indicators need an 'other_symbol' parameter"))
+}
+
+/// Closes of `other` paired with each candle by open time
+pub fn aligned_closes(candles: &CandleData, other: &CandleData) -> Vec<Option<f64>> {
+    let mut aligned = vec![None; candles.len()];
+    let mut j = 0;
+
+    for (i, open_time) in candles.open_time.iter().enumerate() {
+        while j < other.open_time.len() && other.open_time[j] < *open_time {
+            j += 1;
+        }
+        if j < other.open_time.len() && other.open_time[j] == *open_time {
+            aligned[i] = Some(other.close[j]);
+        }
+    }
+
+    aligned
+}
+
+/// Calculate a cross-symbol indicator as (candle index, value) pairs
+pub fn calculate(
+    indicator_name: &str,
+    candles: &CandleData,
+    other: &CandleData,
+    parameters: &Value,
+) -> Result<Vec<(usize, IndicatorOutput)>> {
+    let other_closes = aligned_closes(candles, other);
+    let ratios: Vec<Option<f64>> = candles.close.iter()
+        .zip(&other_closes)
+        .map(|(close, other)| other.filter(|other| *other != 0.0).map(|other| close / other))
+        .collect();
+
+    let values: Vec<(usize, f64)> = match indicator_name.to_uppercase().as_str() {
+        SPREAD => {
+            let hedge_ratio = parameters.get("hedge_ratio").and_then(Value::as_f64).unwrap_or(1.0);
+            candles.close.iter()
+                .zip(&other_closes)
+                .enumerate()
+                .filter_map(|(idx, (close, other))| Some((idx, close - hedge_ratio * (*other)?)))
+                .collect()
+        },
+        RATIO => ratios.iter()
+            .enumerate()
+            .filter_map(|(idx, ratio)| Some((idx, (*ratio)?)))
+            .collect(),
+        RELATIVE_STRENGTH => {
+            let period = match parameters.get("period") {
+                None => 14,
+                Some(value) => value.as_u64()
+                    .filter(|period| *period >= 1)
+                    .ok_or_else(|| Error::validation(format!("period must be a positive integer, got {}", value)))?
+                    as usize,
+            };
+            (period..ratios.len())
+                .filter_map(|idx| {
+                    let (start, end) = (ratios[idx - period]?, ratios[idx]?);
+                    Some((idx, (end / start - 1.0) * 100.0))
+                })
+                .collect()
+        },
+        _ => return Err(Error::not_found(format!("cross-symbol indicator {}", indicator_name))),
+    };
+
+    Ok(values.into_iter()
+        .filter(|(_, value)| value.is_finite())
+        .map(|(idx, value)| (idx, IndicatorOutput::scalar(value)))
+        .collect())
+}
@@ -0,0 +1,276 @@
+// Streaming export of calculated indicator series
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::TryStreamExt;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tracing::info;
+
+// Flush the writer after this many rows so memory stays flat for any series length
+const FLUSH_EVERY_ROWS: u64 = 10_000;
+
+/// Output format of an indicator export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Comma-separated values; object values are flattened into one column per property
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Format of command output printed to the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PrintFormat {
+    /// Aligned columns for reading
+    Table,
+    /// Comma-separated values with a header row
+    Csv,
+    /// JSON, for scripts
+    Json,
+}
+
+/// Selection of the indicator series to export
+#[derive(Debug, Clone)]
+pub struct ExportQuery {
+    pub symbol: String,
+    pub interval: String,
+    pub indicator_name: String,
+    pub parameters: Value,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+// Writes rows in the requested format, deriving the CSV header from the first row
+struct RowWriter<W: Write> {
+    out: W,
+    format: ExportFormat,
+    columns: Option<Vec<String>>,
+}
+
+impl<W: Write> RowWriter<W> {
+    fn new(out: W, format: ExportFormat) -> Self {
+        Self { out, format, columns: None }
+    }
+
+    fn write_row(&mut self, time: DateTime<Utc>, value: &Value) -> Result<()> {
+        match self.format {
+            ExportFormat::Jsonl => {
+                serde_json::to_writer(&mut self.out, &serde_json::json!({
+                    "time": time,
+                    "value": value,
+                }))?;
+                self.out.write_all(b"\n")?;
+            },
+            ExportFormat::Csv => {
+                if self.columns.is_none() {
+                    let columns = match value {
+                        Value::Object(map) => map.keys().cloned().collect(),
+                        _ => vec!["value".to_string()],
+                    };
+                    writeln!(self.out, "time,{}", columns.join(","))?;
+                    self.columns = Some(columns);
+                }
+
+                let columns = self.columns.as_ref().unwrap();
+                write!(self.out, "{}", time.to_rfc3339())?;
+                for column in columns {
+                    let cell = match value {
+                        Value::Object(map) => map.get(column).cloned().unwrap_or(Value::Null),
+                        other => other.clone(),
+                    };
+                    write!(self.out, ",{}", csv_cell(&cell))?;
+                }
+                self.out.write_all(b"\n")?;
+            },
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+// Format a JSON value as a CSV cell, quoting strings when needed
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) if s.contains(',') || s.contains('"') || s.contains('\n') => {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        },
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Print calculated values in the given format
+pub fn print_series<W: Write>(out: W, format: PrintFormat, rows: &[(DateTime<Utc>, Value)]) -> Result<()> {
+    match format {
+        PrintFormat::Csv => {
+            let mut writer = RowWriter::new(out, ExportFormat::Csv);
+            for (time, value) in rows {
+                writer.write_row(*time, value)?;
+            }
+            writer.flush()?;
+        },
+        PrintFormat::Json => {
+            let rows: Vec<Value> = rows.iter()
+                .map(|(time, value)| serde_json::json!({ "time": time, "value": value }))
+                .collect();
+            let mut out = out;
+            serde_json::to_writer_pretty(&mut out, &rows)?;
+            out.write_all(b"\n")?;
+        },
+        PrintFormat::Table => print_table(out, rows)?,
+    }
+
+    Ok(())
+}
+
+/// Print records as a JSON array or as CSV with the first record's keys as header
+///
+/// Tables differ per command and are printed by the caller; PrintFormat::Table is written
+/// as CSV here. Nested values are written to CSV cells as JSON.
+pub fn print_records<W: Write>(mut out: W, format: PrintFormat, records: &[Value]) -> Result<()> {
+    if format == PrintFormat::Json {
+        serde_json::to_writer_pretty(&mut out, records)?;
+        out.write_all(b"\n")?;
+        out.flush()?;
+        return Ok(());
+    }
+
+    let columns: Vec<String> = match records.first() {
+        Some(Value::Object(map)) => map.keys().cloned().collect(),
+        _ => vec!["value".to_string()],
+    };
+    writeln!(out, "{}", columns.join(","))?;
+    for record in records {
+        let cells: Vec<String> = columns.iter()
+            .map(|column| {
+                let cell = match record {
+                    Value::Object(map) => map.get(column).cloned().unwrap_or(Value::Null),
+                    other => other.clone(),
+                };
+                match cell {
+                    Value::Array(_) | Value::Object(_) => csv_cell(&Value::String(cell.to_string())),
+                    other => csv_cell(&other),
+                }
+            })
+            .collect();
+        writeln!(out, "{}", cells.join(","))?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+// Columns are taken from the first row like the CSV header
+fn print_table<W: Write>(mut out: W, rows: &[(DateTime<Utc>, Value)]) -> Result<()> {
+    let columns: Vec<String> = match rows.first() {
+        Some((_, Value::Object(map))) => map.keys().cloned().collect(),
+        _ => vec!["value".to_string()],
+    };
+
+    write!(out, "{:<20}", "Time")?;
+    for column in &columns {
+        write!(out, " | {:>12}", column)?;
+    }
+    writeln!(out)?;
+    write!(out, "{:-<20}", "")?;
+    for _ in &columns {
+        write!(out, "-+-{:-<12}", "")?;
+    }
+    writeln!(out)?;
+
+    for (time, value) in rows {
+        write!(out, "{:<20}", time.format("%Y-%m-%d %H:%M"))?;
+        for column in &columns {
+            let cell = match value {
+                Value::Object(map) => map.get(column).cloned().unwrap_or(Value::Null),
+                other => other.clone(),
+            };
+            let cell = match cell {
+                Value::Number(n) => n.as_f64().map_or(n.to_string(), |v| format!("{:.4}", v)),
+                Value::Null => "-".to_string(),
+                other => csv_cell(&other),
+            };
+            write!(out, " | {:>12}", cell)?;
+        }
+        writeln!(out)?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// Export an indicator series to a file, streaming rows from the database
+///
+/// Rows are written as they arrive, so memory use does not depend on the length
+/// of the series. Output is gzip-compressed when `compress` is set.
+pub async fn export_indicator_series(
+    pg: &PostgresManager,
+    query: &ExportQuery,
+    format: ExportFormat,
+    output: &Path,
+    compress: bool,
+) -> Result<u64> {
+    let file = File::create(output)
+        .map_err(Error::file("create", output))?;
+
+    let rows = if compress {
+        let writer = RowWriter::new(GzEncoder::new(BufWriter::new(file), Compression::default()), format);
+        let (rows, writer) = write_series(pg, query, writer).await?;
+        writer.into_inner().finish()?.flush()?;
+        rows
+    } else {
+        let writer = RowWriter::new(BufWriter::new(file), format);
+        let (rows, writer) = write_series(pg, query, writer).await?;
+        writer.into_inner().flush()?;
+        rows
+    };
+
+    info!("Exported {} rows of {} {}:{} to {}",
+          rows, query.indicator_name, query.symbol, query.interval, output.display());
+
+    Ok(rows)
+}
+
+// Copy the streamed series into the writer
+async fn write_series<W: Write>(
+    pg: &PostgresManager,
+    query: &ExportQuery,
+    mut writer: RowWriter<W>,
+) -> Result<(u64, RowWriter<W>)> {
+    let mut stream = pg.stream_calculated_indicators(
+        &query.symbol,
+        &query.interval,
+        &query.indicator_name,
+        &query.parameters,
+        query.start,
+        query.end,
+    );
+
+    let mut rows = 0u64;
+    while let Some((time, value)) = stream.try_next().await? {
+        writer.write_row(time, &value)?;
+        rows += 1;
+
+        if rows % FLUSH_EVERY_ROWS == 0 {
+            writer.flush()?;
+        }
+    }
+
+    Ok((rows, writer))
+}
@@ -0,0 +1,210 @@
+// Golden-file regression checks of the built-in indicators
+//
+// `verify indicators` calculates every built-in indicator on a reference dataset bundled
+// with the source (golden/*.csv, hourly candles of two synthetic symbols) and compares the
+// values with the outputs stored in golden/indicators.json, so a change to the TA-Lib
+// bindings, the FFI layer or a Rust indicator that alters results is caught before it
+// reaches calculated_indicators. The stored outputs of the TA-Lib indicators are TA-Lib's
+// values; after an intended change `verify indicators --update` rewrites the file from the
+// current build, and the diff shows what moved.
+use crate::data_source::csv::parse_candles;
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::calculator::IndicatorCalculator;
+use crate::indicators::catalog::all_indicators;
+use crate::indicators::cross_symbol;
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::zigzag::ZIGZAG;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Symbol the indicators are calculated on
+pub const REFERENCE_SYMBOL: &str = "REFAUSDT";
+/// Symbol the cross-symbol indicators compare against
+pub const OTHER_SYMBOL: &str = "REFBUSDT";
+/// Interval of the reference candles
+pub const REFERENCE_INTERVAL: &str = "1h";
+
+/// Golden outputs file in the source tree, written by `verify indicators --update`
+pub const GOLDEN_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden/indicators.json");
+
+/// Relative difference (absolute below 1) tolerated between a value and its golden output
+pub const DEFAULT_TOLERANCE: f64 = 1e-8;
+
+const REFERENCE_CANDLES: &str = include_str!("../../golden/REFAUSDT-1h.csv");
+const OTHER_CANDLES: &str = include_str!("../../golden/REFBUSDT-1h.csv");
+const GOLDEN_OUTPUTS: &str = include_str!("../../golden/indicators.json");
+
+/// Stored outputs of one indicator and parameter set on the reference candles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldenCase {
+    pub indicator: String,
+    pub parameters: Value,
+    /// Tolerance of this case when it differs from the default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<f64>,
+    /// Output JSON by RFC 3339 open time
+    pub values: BTreeMap<String, Value>,
+}
+
+/// Outcome of checking one indicator
+#[derive(Debug, Clone)]
+pub enum CaseStatus {
+    Passed,
+    /// The differences found, first ones first
+    Failed(Vec<String>),
+    /// The indicator has no stored outputs yet
+    Missing,
+    /// The calculation returned an error
+    Error(String),
+}
+
+/// An indicator checked against its golden outputs
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub indicator: String,
+    pub parameters: Value,
+    /// Values calculated
+    pub values: usize,
+    pub status: CaseStatus,
+}
+
+/// The bundled reference candles and the candles of the other symbol
+pub fn reference_candles() -> Result<(CandleData, CandleData)> {
+    let candles = |content: &str, symbol: &str| -> Result<CandleData> {
+        Ok(CandleData::from_candles(parse_candles(content, symbol, REFERENCE_INTERVAL)?))
+    };
+
+    Ok((candles(REFERENCE_CANDLES, REFERENCE_SYMBOL)?, candles(OTHER_CANDLES, OTHER_SYMBOL)?))
+}
+
+/// Golden outputs bundled at build time
+pub fn bundled_cases() -> Result<Vec<GoldenCase>> {
+    Ok(serde_json::from_str(GOLDEN_OUTPUTS)?)
+}
+
+/// Every built-in indicator with the parameters it is checked with: its defaults, plus
+/// the parameters it cannot do without
+pub fn reference_parameters() -> Vec<(String, Value)> {
+    all_indicators()
+        .into_iter()
+        .filter(|indicator| indicator.category != "plugin")
+        .map(|indicator| {
+            let parameters = if cross_symbol::is_cross_symbol(&indicator.name) {
+                json!({"other_symbol": OTHER_SYMBOL})
+            } else if indicator.name == ZIGZAG {
+                json!({"reversal_percent": 2.0})
+            } else {
+                json!({})
+            };
+            (indicator.name, parameters)
+        })
+        .collect()
+}
+
+/// Calculate an indicator on the reference candles
+pub fn calculate(
+    candles: &CandleData,
+    other: &CandleData,
+    indicator: &str,
+    parameters: &Value,
+) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
+    if cross_symbol::is_cross_symbol(indicator) {
+        IndicatorCalculator::calculate_cross_symbol(candles, other, indicator, parameters)
+    } else {
+        IndicatorCalculator::calculate_indicator(candles, indicator, parameters)
+    }
+}
+
+/// Golden outputs of the indicators from the current build
+pub fn generate(indicators: &[(String, Value)]) -> Result<Vec<GoldenCase>> {
+    let (candles, other) = reference_candles()?;
+
+    indicators.iter()
+        .map(|(indicator, parameters)| {
+            let values = calculate(&candles, &other, indicator, parameters)
+                .map_err(|e| Error::validation(format!("Failed to calculate {}: {}", indicator, e)))?
+                .into_iter()
+                .map(|(time, value)| (time_key(time), value.to_value()))
+                .collect();
+            Ok(GoldenCase { indicator: indicator.clone(), parameters: parameters.clone(), tolerance: None, values })
+        })
+        .collect()
+}
+
+/// Check the indicators against golden outputs; `tolerance` overrides the tolerance of every case
+pub fn verify(indicators: &[(String, Value)], golden: &[GoldenCase], tolerance: Option<f64>) -> Result<Vec<CaseResult>> {
+    let (candles, other) = reference_candles()?;
+
+    Ok(indicators.iter()
+        .map(|(indicator, parameters)| {
+            let case = golden.iter().find(|case| &case.indicator == indicator && &case.parameters == parameters);
+            let result = calculate(&candles, &other, indicator, parameters);
+            let values = result.as_ref().map_or(0, Vec::len);
+
+            let status = match (case, result) {
+                (_, Err(e)) => CaseStatus::Error(e.to_string()),
+                (None, Ok(_)) => CaseStatus::Missing,
+                (Some(case), Ok(actual)) => {
+                    let differences = compare(case, &actual, tolerance.or(case.tolerance).unwrap_or(DEFAULT_TOLERANCE));
+                    if differences.is_empty() { CaseStatus::Passed } else { CaseStatus::Failed(differences) }
+                },
+            };
+
+            CaseResult { indicator: indicator.clone(), parameters: parameters.clone(), values, status }
+        })
+        .collect())
+}
+
+/// Differences between calculated values and a case's golden outputs
+pub fn compare(case: &GoldenCase, actual: &[(DateTime<Utc>, IndicatorOutput)], tolerance: f64) -> Vec<String> {
+    let actual: BTreeMap<String, Value> = actual.iter()
+        .map(|(time, value)| (time_key(*time), value.to_value()))
+        .collect();
+    let mut differences = Vec::new();
+
+    for (time, expected) in &case.values {
+        match actual.get(time) {
+            None => differences.push(format!("{}: missing, expected {}", time, expected)),
+            Some(value) => {
+                if let Some(difference) = compare_values(expected, value, tolerance, "") {
+                    differences.push(format!("{}: {}", time, difference));
+                }
+            },
+        }
+    }
+    for (time, value) in &actual {
+        if !case.values.contains_key(time) {
+            differences.push(format!("{}: unexpected value {}", time, value));
+        }
+    }
+
+    differences
+}
+
+// How a value differs from its golden output, if it does; numbers may differ within the tolerance
+fn compare_values(expected: &Value, actual: &Value, tolerance: f64, path: &str) -> Option<String> {
+    match (expected, actual) {
+        (Value::Number(e), Value::Number(a)) => {
+            let (e, a) = (e.as_f64()?, a.as_f64()?);
+            ((a - e).abs() > tolerance * e.abs().max(1.0))
+                .then(|| format!("{}{} != {} (golden)", path, a, e))
+        },
+        (Value::Object(e), Value::Object(a)) => {
+            e.iter()
+                .find_map(|(key, value)| match a.get(key) {
+                    Some(actual) => compare_values(value, actual, tolerance, &format!("{}{}: ", path, key)),
+                    None => Some(format!("{}{} missing", path, key)),
+                })
+                .or_else(|| a.keys().find(|key| !e.contains_key(*key)).map(|key| format!("{}unexpected {}", path, key)))
+        },
+        _ => (expected != actual).then(|| format!("{}{} != {} (golden)", path, actual, expected)),
+    }
+}
+
+// Open time as stored in the golden file
+fn time_key(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
@@ -1,3 +1,55 @@
 // Main indicators calculator module using TA-Lib abstract interface
+#[cfg(feature = "native")]
 pub mod calculator;
 
+// Typed indicator values
+pub mod output;
+
+// User-defined indicators
+pub mod plugin;
+
+// Catalog of supported indicators
+#[cfg(feature = "native")]
+pub mod catalog;
+
+// ZigZag swing pivots
+pub mod zigzag;
+
+// Rolling statistics
+pub mod statistics;
+
+// Normalization transforms over any indicator's output
+#[cfg(feature = "native")]
+pub mod transform;
+
+// Indicators between two symbols
+#[cfg(feature = "native")]
+pub mod cross_symbol;
+
+// Volume profile
+pub mod volume_profile;
+
+// Order flow from candle data
+pub mod order_flow;
+
+// Session-anchored VWAP
+pub mod vwap;
+
+// Chart patterns over swing pivots
+pub mod chart_patterns;
+
+// Indicators calculated from other indicators' output
+#[cfg(feature = "native")]
+pub mod pipeline;
+
+// Chunk-level parallelism for window-based indicators
+#[cfg(feature = "native")]
+pub mod chunked;
+
+// Streaming export of calculated series
+#[cfg(feature = "native")]
+pub mod export;
+
+// Golden-file regression checks
+#[cfg(feature = "native")]
+pub mod golden;
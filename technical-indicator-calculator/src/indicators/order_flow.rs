@@ -0,0 +1,169 @@
+// Order flow from candle data
+//
+// Volume delta is the volume bought by takers minus the volume they sold. Binance reports
+// the taker buy volume of each candle, so where it was stored the delta is exact:
+// buy = taker buy volume, sell = volume - buy. Elsewhere (CSV imports without the column,
+// other data sources) it is approximated from the candle's shape: the share of the range
+// the close moved from the open, (close - open) / (high - low), times the volume.
+//
+// The `source` parameter picks "taker" (only candles with taker volume), "candle" (always
+// the approximation) or "auto" (the default: taker volume where present).
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin::{Indicator, InputSeries};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Name of the per-candle volume delta indicator
+pub const VOLUME_DELTA: &str = "VOLUME_DELTA";
+
+/// Name of the cumulative volume delta indicator
+pub const CUMULATIVE_DELTA: &str = "CUMULATIVE_DELTA";
+
+/// Where buy and sell volume come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaSource {
+    /// Taker buy volume, falling back to the candle approximation
+    Auto,
+    /// Taker buy volume only
+    Taker,
+    /// Candle approximation only
+    Candle,
+}
+
+impl DeltaSource {
+    pub fn from_parameters(parameters: &Value) -> Result<Self> {
+        match parameters.get("source").and_then(Value::as_str) {
+            None | Some("auto") => Ok(DeltaSource::Auto),
+            Some("taker") => Ok(DeltaSource::Taker),
+            Some("candle") => Ok(DeltaSource::Candle),
+            Some(other) => Err(Error::validation(format!(
+                "source must be one of auto, taker, candle, got {}", other
+            ))),
+        }
+    }
+}
+
+/// Buy and sell volume of one candle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeSplit {
+    pub buy: f64,
+    pub sell: f64,
+}
+
+impl VolumeSplit {
+    pub fn delta(self) -> f64 {
+        self.buy - self.sell
+    }
+}
+
+/// Buy and sell volume of candle `idx`; None when `source` can't provide it
+pub fn volume_split(candles: &CandleData, idx: usize, source: DeltaSource) -> Option<VolumeSplit> {
+    let volume = candles.volume[idx];
+    if !volume.is_finite() {
+        return None;
+    }
+
+    if source != DeltaSource::Candle {
+        if let Some(buy) = candles.taker_buy(idx) {
+            let buy = buy.clamp(0.0, volume.max(0.0));
+            return Some(VolumeSplit { buy, sell: volume - buy });
+        }
+        if source == DeltaSource::Taker {
+            return None;
+        }
+    }
+
+    // A candle without range is split evenly
+    let range = candles.high[idx] - candles.low[idx];
+    let pressure = if range > 0.0 {
+        ((candles.close[idx] - candles.open[idx]) / range).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    };
+    pressure.is_finite().then(|| VolumeSplit {
+        buy: volume * (1.0 + pressure) / 2.0,
+        sell: volume * (1.0 - pressure) / 2.0,
+    })
+}
+
+/// The VOLUME_DELTA indicator: delta, buy and sell volume of each candle
+pub struct VolumeDelta;
+
+impl Indicator for VolumeDelta {
+    fn name(&self) -> &str {
+        VOLUME_DELTA
+    }
+
+    // Taker buy volume is read as well where the candles have it
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::Open, InputSeries::High, InputSeries::Low, InputSeries::Close, InputSeries::Volume]
+    }
+
+    fn lookback(&self, _parameters: &Value) -> Option<usize> {
+        Some(0)
+    }
+
+    fn properties(&self) -> &'static [&'static str] {
+        &["delta", "buy", "sell"]
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let source = DeltaSource::from_parameters(parameters)?;
+        Ok((0..candles.len())
+            .filter_map(|idx| {
+                let split = volume_split(candles, idx, source)?;
+                let values = BTreeMap::from([
+                    ("delta".to_string(), split.delta()),
+                    ("buy".to_string(), split.buy),
+                    ("sell".to_string(), split.sell),
+                ]);
+                Some((idx, IndicatorOutput::Values(values)))
+            })
+            .collect())
+    }
+}
+
+/// The CUMULATIVE_DELTA indicator
+///
+/// Running sum of the volume delta since the first candle or, with "session": "day",
+/// since the start of each trading day of the symbol's session calendar. Candles without a delta leave the sum unchanged and
+/// produce no value.
+pub struct CumulativeDelta;
+
+impl Indicator for CumulativeDelta {
+    fn name(&self) -> &str {
+        CUMULATIVE_DELTA
+    }
+
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::Open, InputSeries::High, InputSeries::Low, InputSeries::Close, InputSeries::Volume]
+    }
+
+    // The sum runs over the entire history (or the day so far)
+    fn lookback(&self, _parameters: &Value) -> Option<usize> {
+        None
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let source = DeltaSource::from_parameters(parameters)?;
+        let daily = match parameters.get("session").and_then(Value::as_str) {
+            None => false,
+            Some("day") => true,
+            Some(other) => return Err(Error::validation(format!("session must be \"day\", got {}", other))),
+        };
+
+        let mut total = 0.0;
+        Ok((0..candles.len())
+            .filter_map(|idx| {
+                let session = &candles.session;
+                if daily && idx > 0 && session.trading_date(candles.open_time[idx]) != session.trading_date(candles.open_time[idx - 1]) {
+                    total = 0.0;
+                }
+                total += volume_split(candles, idx, source)?.delta();
+                Some((idx, IndicatorOutput::scalar(total)))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,176 @@
+// Typed values produced by indicator calculations
+//
+// Calculations return IndicatorOutput; the JSON stored in calculated_indicators.value is
+// produced only when values are written (or put into a JSON-backed series) and keeps the
+// exact shape of the previous raw values, so stored data reads back unchanged.
+// User-defined indicators produce scalars or named values.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Single-valued indicators (RSI, SMA, EMA, ATR, ADX, OBV, CCI, MOM, MFI)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScalarValue(pub f64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BbandsValue {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+    /// (upper - lower) / middle, 0 when the middle band is 0
+    pub width: f64,
+}
+
+/// %K and %D of STOCH and STOCHRSI
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StochValue {
+    pub k: f64,
+    pub d: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternDirection {
+    Bullish,
+    Bearish,
+    /// Indecision patterns such as doji, which TA-Lib reports as +100 either way
+    Neutral,
+}
+
+/// A detected candlestick pattern; candles without a pattern produce no output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternHit {
+    pub pattern: String,
+    #[serde(rename = "type")]
+    pub direction: PatternDirection,
+    /// TA-Lib pattern output scaled to 0..1 (100 -> 1.0)
+    pub strength: f64,
+}
+
+impl PatternHit {
+    /// Convert a non-zero TA-Lib pattern output (+/-100, +/-200)
+    ///
+    /// `direction` is the pattern's own direction when it has one regardless of the sign
+    /// (e.g. neutral for doji); None takes it from the sign of the output.
+    pub fn from_talib(pattern: &str, output: i32, direction: Option<PatternDirection>) -> Self {
+        let from_sign = if output > 0 { PatternDirection::Bullish } else { PatternDirection::Bearish };
+        Self {
+            pattern: pattern.to_string(),
+            direction: direction.unwrap_or(from_sign),
+            strength: output.unsigned_abs() as f64 / 100.0,
+        }
+    }
+}
+
+/// One calculated value of any supported or user-defined indicator
+///
+/// Serialized untagged, so each variant has the same JSON shape as its inner value.
+/// Named values come after the built-in shapes so stored values read back as before.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IndicatorOutput {
+    Macd(MacdValue),
+    Bbands(BbandsValue),
+    Stoch(StochValue),
+    Pattern(PatternHit),
+    /// Named values of a user-defined indicator (see indicators::plugin)
+    Values(BTreeMap<String, f64>),
+    Scalar(ScalarValue),
+}
+
+impl IndicatorOutput {
+    pub fn scalar(value: f64) -> Self {
+        IndicatorOutput::Scalar(ScalarValue(value))
+    }
+
+    /// Numeric value of the output, or of one of its properties (e.g. "signal", "upper", "k")
+    pub fn numeric(&self, property: Option<&str>) -> Option<f64> {
+        match (self, property) {
+            (IndicatorOutput::Scalar(ScalarValue(value)), None) => Some(*value),
+            (IndicatorOutput::Macd(v), Some("macd")) => Some(v.macd),
+            (IndicatorOutput::Macd(v), Some("signal")) => Some(v.signal),
+            (IndicatorOutput::Macd(v), Some("histogram")) => Some(v.histogram),
+            (IndicatorOutput::Bbands(v), Some("upper")) => Some(v.upper),
+            (IndicatorOutput::Bbands(v), Some("middle")) => Some(v.middle),
+            (IndicatorOutput::Bbands(v), Some("lower")) => Some(v.lower),
+            (IndicatorOutput::Bbands(v), Some("width")) => Some(v.width),
+            (IndicatorOutput::Stoch(v), Some("k")) => Some(v.k),
+            (IndicatorOutput::Stoch(v), Some("d")) => Some(v.d),
+            (IndicatorOutput::Pattern(v), Some("strength")) => Some(v.strength),
+            (IndicatorOutput::Values(values), Some(property)) => values.get(property).copied(),
+            _ => None,
+        }
+    }
+
+    /// Every numeric value of the output by property; the property is None for scalars
+    pub fn numeric_values(&self) -> Vec<(Option<&str>, f64)> {
+        match self {
+            IndicatorOutput::Scalar(ScalarValue(value)) => vec![(None, *value)],
+            IndicatorOutput::Macd(v) => vec![
+                (Some("macd"), v.macd),
+                (Some("signal"), v.signal),
+                (Some("histogram"), v.histogram),
+            ],
+            IndicatorOutput::Bbands(v) => vec![
+                (Some("upper"), v.upper),
+                (Some("middle"), v.middle),
+                (Some("lower"), v.lower),
+                (Some("width"), v.width),
+            ],
+            IndicatorOutput::Stoch(v) => vec![(Some("k"), v.k), (Some("d"), v.d)],
+            IndicatorOutput::Pattern(v) => vec![(Some("strength"), v.strength)],
+            IndicatorOutput::Values(values) => values.iter().map(|(name, value)| (Some(name.as_str()), *value)).collect(),
+        }
+    }
+
+    /// Read a stored value back; None when it matches none of the output shapes
+    pub fn from_value(value: &Value) -> Option<Self> {
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// JSON as stored in calculated_indicators.value; non-finite numbers become null
+    pub fn to_value(&self) -> Value {
+        match self {
+            IndicatorOutput::Scalar(ScalarValue(value)) => Value::from(*value),
+            IndicatorOutput::Macd(v) => json!({
+                "macd": v.macd,
+                "signal": v.signal,
+                "histogram": v.histogram,
+            }),
+            IndicatorOutput::Bbands(v) => json!({
+                "upper": v.upper,
+                "middle": v.middle,
+                "lower": v.lower,
+                "width": v.width,
+            }),
+            IndicatorOutput::Stoch(v) => json!({
+                "k": v.k,
+                "d": v.d,
+            }),
+            IndicatorOutput::Pattern(v) => json!({
+                "pattern": v.pattern,
+                "type": v.direction,
+                "strength": v.strength,
+            }),
+            IndicatorOutput::Values(values) => values
+                .iter()
+                .map(|(name, value)| (name.clone(), Value::from(*value)))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        }
+    }
+}
+
+impl From<IndicatorOutput> for Value {
+    fn from(output: IndicatorOutput) -> Self {
+        output.to_value()
+    }
+}
@@ -0,0 +1,229 @@
+// Indicator pipelines
+//
+// An indicator configuration can take the output of another configuration on the same
+// symbol and interval as its input instead of the candles, e.g. an EMA of an RSI. The
+// upstream configuration gets a "label" parameter and the downstream one refers to it:
+//
+//   RSI  {"period": 14, "label": "rsi14"}
+//   EMA  {"period": 9, "input": {"label": "rsi14"}}
+//   SMA  {"period": 5, "input": {"label": "stoch", "property": "k"}}
+//
+// Multi-valued inputs need a `property`. The input series replaces open, high, low and
+// close; volume stays that of the candles, so e.g. OBV of a series works as expected.
+// Pipelines can be chained; each job calculates its whole chain from the candles, so
+// jobs don't depend on the order in which other jobs finish. Cycles and unknown labels
+// make the configurations involved invalid.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::calculator::IndicatorCalculator;
+use crate::indicators::cross_symbol;
+use crate::indicators::output::IndicatorOutput;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Another configuration's output used as input
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineInput {
+    pub label: String,
+    pub property: Option<String>,
+}
+
+/// Label a configuration can be referred to by
+pub fn label(parameters: &Value) -> Option<&str> {
+    parameters.get("label").and_then(Value::as_str)
+}
+
+/// The input configured in `parameters`, if any
+pub fn input(parameters: &Value) -> Result<Option<PipelineInput>> {
+    let block = match parameters.get("input") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(block) => block,
+    };
+
+    let label = block.get("label")
+        .and_then(Value::as_str)
+        .filter(|label| !label.is_empty())
+        .ok_or_else(|| Error::validation(format!("input must be an object with a 'label', got {}", block)))?;
+    let property = match block.get("property") {
+        None => None,
+        Some(value) => Some(value.as_str()
+            .ok_or_else(|| Error::validation(format!("input property must be a string, got {}", value)))?
+            .to_string()),
+    };
+
+    Ok(Some(PipelineInput { label: label.to_string(), property }))
+}
+
+/// One upstream calculation of a pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStage {
+    pub indicator_name: String,
+    pub parameters: Value,
+    /// Property of the output passed on to the next stage
+    pub property: Option<String>,
+}
+
+/// A configuration taking part in dependency resolution
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineNode<'a> {
+    pub symbol: &'a str,
+    pub interval: &'a str,
+    pub indicator_name: &'a str,
+    pub parameters: &'a Value,
+}
+
+impl PipelineNode<'_> {
+    fn describe(&self) -> String {
+        match label(self.parameters) {
+            Some(label) => format!("{} '{}'", self.indicator_name, label),
+            None => format!("{} {}", self.indicator_name, self.parameters),
+        }
+    }
+}
+
+struct Resolver<'a, 'n> {
+    nodes: &'a [PipelineNode<'n>],
+    inputs: Vec<std::result::Result<Option<(usize, Option<String>)>, String>>,
+    resolved: Vec<Option<std::result::Result<Vec<PipelineStage>, String>>>,
+    stack: Vec<usize>,
+    order: Vec<usize>,
+}
+
+impl Resolver<'_, '_> {
+    fn visit(&mut self, idx: usize) -> std::result::Result<Vec<PipelineStage>, String> {
+        if let Some(resolved) = &self.resolved[idx] {
+            return resolved.clone();
+        }
+
+        if let Some(start) = self.stack.iter().position(|visiting| *visiting == idx) {
+            let cycle: Vec<String> = self.stack[start..].iter()
+                .chain(std::iter::once(&idx))
+                .map(|i| self.nodes[*i].describe())
+                .collect();
+            return Err(format!("input cycle {}", cycle.join(" -> ")));
+        }
+
+        self.stack.push(idx);
+        let result = match self.inputs[idx].clone() {
+            Err(e) => Err(e),
+            Ok(None) => Ok(Vec::new()),
+            Ok(Some((upstream, property))) => self.visit(upstream).map(|mut stages| {
+                let node = &self.nodes[upstream];
+                stages.push(PipelineStage {
+                    indicator_name: node.indicator_name.to_string(),
+                    parameters: node.parameters.clone(),
+                    property,
+                });
+                stages
+            }),
+        };
+        self.stack.pop();
+
+        self.resolved[idx] = Some(result.clone());
+        self.order.push(idx);
+        result
+    }
+}
+
+/// Resolve the inputs of configurations
+///
+/// Returns every node's index with the stages calculated before it, or why it can't be
+/// calculated, with each node after the nodes it depends on.
+pub fn resolve(nodes: &[PipelineNode]) -> Vec<(usize, Result<Vec<PipelineStage>>)> {
+    let mut labels: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for (idx, node) in nodes.iter().enumerate() {
+        if let Some(label) = label(node.parameters) {
+            if labels.insert((node.symbol, node.interval, label), idx).is_some() {
+                duplicates.push(label);
+            }
+        }
+    }
+
+    let inputs = nodes.iter()
+        .map(|node| {
+            let input = match input(node.parameters).map_err(|e| e.to_string())? {
+                Some(input) => input,
+                None => return Ok(None),
+            };
+            if duplicates.contains(&input.label.as_str()) {
+                return Err(format!("label '{}' is used by more than one configuration", input.label));
+            }
+            let upstream = *labels.get(&(node.symbol, node.interval, input.label.as_str()))
+                .ok_or_else(|| format!("no enabled configuration on {}:{} has label '{}'", node.symbol, node.interval, input.label))?;
+            if cross_symbol::is_cross_symbol(node.indicator_name) || cross_symbol::is_cross_symbol(nodes[upstream].indicator_name) {
+                return Err("cross-symbol indicators can't be part of a pipeline".to_string());
+            }
+            Ok(Some((upstream, input.property)))
+        })
+        .collect();
+
+    let mut resolver = Resolver {
+        nodes,
+        inputs,
+        resolved: vec![None; nodes.len()],
+        stack: Vec::new(),
+        order: Vec::new(),
+    };
+    for idx in 0..nodes.len() {
+        resolver.visit(idx).ok();
+    }
+
+    let Resolver { mut resolved, order, .. } = resolver;
+    order.into_iter()
+        .map(|idx| {
+            let result = resolved[idx].take().unwrap_or(Ok(Vec::new()));
+            (idx, result.map_err(Error::validation))
+        })
+        .collect()
+}
+
+// Output of one stage as a candle series for the next
+fn stage_series(source: &CandleData, results: Vec<(DateTime<Utc>, IndicatorOutput)>, property: Option<&str>) -> Result<CandleData> {
+    let mut series = CandleData::new(source.symbol.clone(), source.interval.clone()).with_session(source.session);
+    let mut idx = 0;
+
+    for (time, output) in results {
+        let value = output.numeric(property).ok_or_else(|| match property {
+            Some(property) => Error::validation(format!("input property '{}' is not a numeric output", property)),
+            None => Error::validation("input needs a 'property' for multi-valued outputs"),
+        })?;
+        while idx < source.open_time.len() && source.open_time[idx] < time {
+            idx += 1;
+        }
+        if idx == source.open_time.len() {
+            break;
+        }
+        if !value.is_finite() {
+            continue;
+        }
+
+        series.open_time.push(time);
+        series.open.push(value);
+        series.high.push(value);
+        series.low.push(value);
+        series.close.push(value);
+        series.volume.push(source.volume[idx]);
+        series.close_time.push(source.close_time[idx]);
+    }
+
+    Ok(series)
+}
+
+/// Calculate an indicator over the output of its upstream stages
+pub fn calculate(
+    candles: &CandleData,
+    stages: &[PipelineStage],
+    indicator_name: &str,
+    parameters: &Value,
+) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
+    let mut series = candles.clone();
+    for stage in stages {
+        let results = IndicatorCalculator::calculate_indicator(&series, &stage.indicator_name, &stage.parameters)?;
+        series = stage_series(&series, results, stage.property.as_deref())?;
+    }
+
+    IndicatorCalculator::calculate_indicator(&series, indicator_name, parameters)
+}
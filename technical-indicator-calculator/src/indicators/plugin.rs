@@ -0,0 +1,162 @@
+// User-defined indicators
+//
+// Indicators implementing the Indicator trait are registered once per process, usually at
+// startup by a downstream crate or a plugin loader. Registered indicators are looked up by
+// name wherever a TA-Lib function would be: indicator_config rows, worker jobs, strategy
+// indicators and backtests all go through IndicatorCalculator, which checks the registry
+// before calling TA-Lib. Indicators implemented in Rust in this crate (ZigZag, chart
+// patterns, statistics, volume profile, order flow) are looked up the same way.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::chart_patterns::{ChartPatterns, CHART_PATTERNS};
+use crate::indicators::order_flow::{CumulativeDelta, VolumeDelta, CUMULATIVE_DELTA, VOLUME_DELTA};
+use crate::indicators::statistics::{LinearRegression, ZScore, LINEARREG, ZSCORE};
+use crate::indicators::volume_profile::{VolumeProfile, VOLUME_PROFILE};
+use crate::indicators::vwap::{AnchoredVwap, ANCHORED_VWAP};
+use crate::indicators::zigzag::{ZigZag, ZIGZAG};
+use crate::indicators::output::IndicatorOutput;
+#[cfg(feature = "native")]
+use crate::talib_bindings::TaLibAbstract;
+use parking_lot::{const_rwlock, RwLock};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Candle series an indicator reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSeries {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+}
+
+impl InputSeries {
+    fn series(self, candles: &CandleData) -> &[f64] {
+        match self {
+            InputSeries::Open => &candles.open,
+            InputSeries::High => &candles.high,
+            InputSeries::Low => &candles.low,
+            InputSeries::Close => &candles.close,
+            InputSeries::Volume => &candles.volume,
+        }
+    }
+}
+
+/// An indicator calculated outside TA-Lib
+pub trait Indicator: Send + Sync {
+    /// Name used in indicator_config and strategies, matched case-insensitively
+    fn name(&self) -> &str;
+
+    /// Candle series the calculation reads; they are checked to be present and complete
+    fn inputs(&self) -> &[InputSeries];
+
+    /// Number of candles needed before the first output, or None when every value
+    /// depends on the entire history (e.g. recursive smoothing)
+    fn lookback(&self, parameters: &Value) -> Option<usize>;
+
+    /// Properties of multi-valued outputs (IndicatorOutput::Values) that strategies may
+    /// reference; empty for indicators producing a single value
+    fn properties(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Calculate the indicator; returns (candle index, value) pairs in ascending index order
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>>;
+}
+
+// Registered indicators by upper-case name
+static REGISTRY: RwLock<BTreeMap<String, Arc<dyn Indicator>>> = const_rwlock(BTreeMap::new());
+
+/// Register a user-defined indicator
+///
+/// Fails when the name is empty, is already registered, or is one of the built-in TA-Lib
+/// functions (or their aliases) or Rust indicators, which cannot be replaced.
+pub fn register_indicator(indicator: impl Indicator + 'static) -> Result<()> {
+    let name = indicator.name().trim().to_uppercase();
+    if name.is_empty() {
+        return Err(Error::validation("Indicator name must not be empty"));
+    }
+
+    if is_talib_function(&name) || builtin_indicator(&name).is_some() {
+        return Err(Error::validation(format!("{} is a built-in indicator and cannot be replaced", name)));
+    }
+
+    let mut registry = REGISTRY.write();
+    if registry.contains_key(&name) {
+        return Err(Error::validation(format!("Indicator {} is already registered", name)));
+    }
+
+    registry.insert(name, Arc::new(indicator));
+    Ok(())
+}
+
+/// Registered indicator of the given name, or one of the built-in Rust indicators
+pub fn get_indicator(name: &str) -> Option<Arc<dyn Indicator>> {
+    let name = name.trim().to_uppercase();
+    builtin_indicator(&name).or_else(|| REGISTRY.read().get(&name).cloned())
+}
+
+// Indicators implemented in this crate, by upper-case name
+fn builtin_indicator(name: &str) -> Option<Arc<dyn Indicator>> {
+    match name {
+        CHART_PATTERNS => Some(Arc::new(ChartPatterns)),
+        ZIGZAG => Some(Arc::new(ZigZag)),
+        LINEARREG => Some(Arc::new(LinearRegression)),
+        ZSCORE => Some(Arc::new(ZScore)),
+        VOLUME_PROFILE => Some(Arc::new(VolumeProfile)),
+        VOLUME_DELTA => Some(Arc::new(VolumeDelta)),
+        CUMULATIVE_DELTA => Some(Arc::new(CumulativeDelta)),
+        ANCHORED_VWAP => Some(Arc::new(AnchoredVwap)),
+        _ => None,
+    }
+}
+
+// Whether the name is a TA-Lib function or alias; there are none without the native feature
+#[cfg(feature = "native")]
+fn is_talib_function(name: &str) -> bool {
+    TaLibAbstract::is_function_available(&TaLibAbstract::get_function_name(name))
+}
+
+#[cfg(not(feature = "native"))]
+fn is_talib_function(_name: &str) -> bool {
+    false
+}
+
+/// Names of all registered indicators
+pub fn registered_indicators() -> Vec<String> {
+    REGISTRY.read().keys().cloned().collect()
+}
+
+/// Run a registered indicator after checking its inputs and outputs
+pub(crate) fn calculate(
+    indicator: &dyn Indicator,
+    candles: &CandleData,
+    parameters: &Value,
+) -> Result<Vec<(usize, IndicatorOutput)>> {
+    let len = candles.open_time.len();
+    for input in indicator.inputs() {
+        if input.series(candles).len() != len {
+            return Err(Error::validation(format!(
+                "Indicator {} needs {:?} for all {} candles",
+                indicator.name(),
+                input,
+                len
+            )));
+        }
+    }
+
+    let results = indicator.calculate(candles, parameters)?;
+
+    if let Some((index, _)) = results.iter().find(|(index, _)| *index >= len) {
+        return Err(Error::validation(format!(
+            "Indicator {} returned output for candle {} of {}",
+            indicator.name(),
+            index,
+            len
+        )));
+    }
+
+    Ok(results)
+}
@@ -0,0 +1,237 @@
+// Rolling statistics
+//
+// Linear regression, z-score, correlation and beta over a rolling window, computed in
+// Rust. LINEARREG and ZSCORE are built-in indicators over a candle series (parameter
+// `source`, default close). Strategies can also apply them to another indicator, and
+// correlate with or regress on another symbol (CORREL, BETA); see strategy::statistics.
+// Windows with a missing value produce no output.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin::{Indicator, InputSeries};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Name of the linear regression indicator
+pub const LINEARREG: &str = "LINEARREG";
+
+/// Name of the z-score indicator
+pub const ZSCORE: &str = "ZSCORE";
+
+/// Least-squares line over a window, with x counted in candles from the window's start
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    /// Change per candle
+    pub slope: f64,
+    /// Line value at the first candle of the window
+    pub intercept: f64,
+    /// Line value at the last candle of the window
+    pub value: f64,
+    /// Line value one candle after the window
+    pub forecast: f64,
+}
+
+impl Regression {
+    pub fn to_output(self) -> IndicatorOutput {
+        IndicatorOutput::Values(BTreeMap::from([
+            ("slope".to_string(), self.slope),
+            ("intercept".to_string(), self.intercept),
+            ("value".to_string(), self.value),
+            ("forecast".to_string(), self.forecast),
+        ]))
+    }
+}
+
+// Complete windows of `period` values, by index of their last value
+fn windows(values: &[Option<f64>], period: usize) -> impl Iterator<Item = (usize, Vec<f64>)> + '_ {
+    values
+        .windows(period)
+        .enumerate()
+        .filter_map(move |(start, window)| Some((start + period - 1, window.iter().copied().collect::<Option<Vec<f64>>>()?)))
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Rolling linear regression
+pub fn linear_regression(values: &[Option<f64>], period: usize) -> Vec<(usize, Regression)> {
+    if period < 2 {
+        return Vec::new();
+    }
+
+    let n = period as f64;
+    let x_mean = (n - 1.0) / 2.0;
+    let x_var: f64 = (0..period).map(|x| (x as f64 - x_mean).powi(2)).sum();
+
+    windows(values, period)
+        .map(|(idx, window)| {
+            let y_mean = mean(&window);
+            let covariance: f64 = window.iter().enumerate().map(|(x, y)| (x as f64 - x_mean) * (y - y_mean)).sum();
+            let slope = covariance / x_var;
+            let intercept = y_mean - slope * x_mean;
+            (idx, Regression {
+                slope,
+                intercept,
+                value: intercept + slope * (n - 1.0),
+                forecast: intercept + slope * n,
+            })
+        })
+        .collect()
+}
+
+/// Rolling z-score of the last value against its window; none where the window is flat
+pub fn zscore(values: &[Option<f64>], period: usize) -> Vec<(usize, f64)> {
+    if period < 2 {
+        return Vec::new();
+    }
+
+    windows(values, period)
+        .filter_map(|(idx, window)| {
+            let mean = mean(&window);
+            let std_dev = (window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window.len() as f64).sqrt();
+            (std_dev > 0.0).then(|| (idx, (window[window.len() - 1] - mean) / std_dev))
+        })
+        .collect()
+}
+
+/// Candle-to-candle simple returns, aligned with the input (the first is None)
+pub fn returns(values: &[Option<f64>]) -> Vec<Option<f64>> {
+    let mut returns = vec![None; values.len()];
+    for i in 1..values.len() {
+        if let (Some(previous), Some(current)) = (values[i - 1], values[i]) {
+            if previous != 0.0 {
+                returns[i] = Some(current / previous - 1.0);
+            }
+        }
+    }
+    returns
+}
+
+// Covariance and the variances of both series over paired windows
+fn rolling_moments(x: &[Option<f64>], y: &[Option<f64>], period: usize) -> Vec<(usize, f64, f64, f64)> {
+    let pairs: Vec<Option<(f64, f64)>> = x.iter().zip(y).map(|(x, y)| Some(((*x)?, (*y)?))).collect();
+    if period < 2 {
+        return Vec::new();
+    }
+
+    pairs
+        .windows(period)
+        .enumerate()
+        .filter_map(|(start, window)| {
+            let window: Vec<(f64, f64)> = window.iter().copied().collect::<Option<_>>()?;
+            let n = window.len() as f64;
+            let x_mean = window.iter().map(|(x, _)| x).sum::<f64>() / n;
+            let y_mean = window.iter().map(|(_, y)| y).sum::<f64>() / n;
+            let covariance = window.iter().map(|(x, y)| (x - x_mean) * (y - y_mean)).sum::<f64>() / n;
+            let x_var = window.iter().map(|(x, _)| (x - x_mean).powi(2)).sum::<f64>() / n;
+            let y_var = window.iter().map(|(_, y)| (y - y_mean).powi(2)).sum::<f64>() / n;
+            Some((start + period - 1, covariance, x_var, y_var))
+        })
+        .collect()
+}
+
+/// Rolling Pearson correlation of two aligned series
+pub fn correlation(x: &[Option<f64>], y: &[Option<f64>], period: usize) -> Vec<(usize, f64)> {
+    rolling_moments(x, y, period)
+        .into_iter()
+        .filter(|(_, _, x_var, y_var)| *x_var > 0.0 && *y_var > 0.0)
+        .map(|(idx, covariance, x_var, y_var)| (idx, covariance / (x_var * y_var).sqrt()))
+        .collect()
+}
+
+/// Rolling beta of `asset` on `benchmark`: cov(asset, benchmark) / var(benchmark)
+pub fn beta(asset: &[Option<f64>], benchmark: &[Option<f64>], period: usize) -> Vec<(usize, f64)> {
+    rolling_moments(asset, benchmark, period)
+        .into_iter()
+        .filter(|(_, _, _, benchmark_var)| *benchmark_var > 0.0)
+        .map(|(idx, covariance, _, benchmark_var)| (idx, covariance / benchmark_var))
+        .collect()
+}
+
+/// `period` parameter of at least 2
+pub fn period_param(parameters: &Value, default: usize) -> Result<usize> {
+    match parameters.get("period") {
+        None => Ok(default),
+        Some(value) => value
+            .as_u64()
+            .filter(|period| *period >= 2)
+            .map(|period| period as usize)
+            .ok_or_else(|| Error::validation(format!("period must be an integer of at least 2, got {}", value))),
+    }
+}
+
+// Candle series named by the `source` parameter
+fn source_series<'a>(candles: &'a CandleData, parameters: &Value) -> Result<&'a [f64]> {
+    match parameters.get("source").and_then(Value::as_str).unwrap_or("close") {
+        "open" => Ok(&candles.open),
+        "high" => Ok(&candles.high),
+        "low" => Ok(&candles.low),
+        "close" => Ok(&candles.close),
+        "volume" => Ok(&candles.volume),
+        other => Err(Error::validation(format!(
+            "source must be one of open, high, low, close, volume, got {}", other
+        ))),
+    }
+}
+
+fn present(values: &[f64]) -> Vec<Option<f64>> {
+    values.iter().map(|v| v.is_finite().then_some(*v)).collect()
+}
+
+/// The LINEARREG indicator: slope, intercept, value and forecast
+pub struct LinearRegression;
+
+impl Indicator for LinearRegression {
+    fn name(&self) -> &str {
+        LINEARREG
+    }
+
+    // The series actually read depends on `source`; all of them are loaded with the candles
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::Close]
+    }
+
+    fn lookback(&self, parameters: &Value) -> Option<usize> {
+        period_param(parameters, 14).ok().map(|period| period - 1)
+    }
+
+    fn properties(&self) -> &'static [&'static str] {
+        &["slope", "intercept", "value", "forecast"]
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let period = period_param(parameters, 14)?;
+        let values = present(source_series(candles, parameters)?);
+        Ok(linear_regression(&values, period)
+            .into_iter()
+            .map(|(idx, regression)| (idx, regression.to_output()))
+            .collect())
+    }
+}
+
+/// The ZSCORE indicator
+pub struct ZScore;
+
+impl Indicator for ZScore {
+    fn name(&self) -> &str {
+        ZSCORE
+    }
+
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::Close]
+    }
+
+    fn lookback(&self, parameters: &Value) -> Option<usize> {
+        period_param(parameters, 20).ok().map(|period| period - 1)
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let period = period_param(parameters, 20)?;
+        let values = present(source_series(candles, parameters)?);
+        Ok(zscore(&values, period)
+            .into_iter()
+            .map(|(idx, value)| (idx, IndicatorOutput::scalar(value)))
+            .collect())
+    }
+}
@@ -0,0 +1,125 @@
+// Normalization transforms
+//
+// Any indicator can be put on a common scale by adding a "transform" block to its
+// parameters, e.g. {"period": 14, "transform": {"type": "zscore", "period": 50}}:
+//
+//   zscore      (value - mean) / standard deviation of the window
+//   percentile  percentage of the window's values at or below the value (0..100)
+//   minmax      (value - min) / (max - min) of the window (0..1)
+//
+// The window is the last `period` candles (default 20). Multi-valued outputs need a
+// `property` to transform ({"type": "minmax", "property": "histogram"}). A transformed
+// indicator produces a single value; windows with a missing value or no spread produce
+// none. Transforms run after the calculation, so the worker stores and strategies read
+// the transformed series.
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::statistics;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformKind {
+    ZScore,
+    Percentile,
+    MinMax,
+}
+
+/// A rolling normalization applied to an indicator's output
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub kind: TransformKind,
+    pub period: usize,
+    /// Property of a multi-valued output to transform
+    pub property: Option<String>,
+}
+
+impl Transform {
+    /// The transform configured in `parameters`, if any
+    pub fn from_parameters(parameters: &Value) -> Result<Option<Self>> {
+        let block = match parameters.get("transform") {
+            None | Some(Value::Null) => return Ok(None),
+            Some(block) if block.is_object() => block,
+            Some(other) => return Err(Error::validation(format!("transform must be an object, got {}", other))),
+        };
+
+        let kind = match block.get("type").and_then(Value::as_str) {
+            Some("zscore") => TransformKind::ZScore,
+            Some("percentile") => TransformKind::Percentile,
+            Some("minmax") => TransformKind::MinMax,
+            _ => return Err(Error::validation(format!(
+                "transform type must be one of zscore, percentile, minmax, got {}",
+                block.get("type").unwrap_or(&Value::Null)
+            ))),
+        };
+
+        let property = match block.get("property") {
+            None => None,
+            Some(value) => Some(value.as_str()
+                .ok_or_else(|| Error::validation(format!("transform property must be a string, got {}", value)))?
+                .to_string()),
+        };
+
+        Ok(Some(Self {
+            kind,
+            period: statistics::period_param(block, 20)?,
+            property,
+        }))
+    }
+
+    /// Candles needed before the first transformed value, on top of the indicator's own
+    pub fn lookback(&self) -> usize {
+        self.period - 1
+    }
+
+    /// Transform (candle index, output) pairs in ascending index order
+    pub fn apply(&self, results: Vec<(usize, IndicatorOutput)>) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let (first, last) = match (results.first(), results.last()) {
+            (Some((first, _)), Some((last, _))) => (*first, *last),
+            _ => return Ok(Vec::new()),
+        };
+
+        // Values by candle, so gaps in the output break the window
+        let mut values = vec![None; last - first + 1];
+        for (idx, output) in &results {
+            let value = output.numeric(self.property.as_deref()).ok_or_else(|| match &self.property {
+                Some(property) => Error::validation(format!("transform property '{}' is not a numeric output", property)),
+                None => Error::validation("transform needs a 'property' for multi-valued outputs"),
+            })?;
+            values[idx - first] = value.is_finite().then_some(value);
+        }
+
+        let transformed = match self.kind {
+            TransformKind::ZScore => statistics::zscore(&values, self.period),
+            TransformKind::Percentile | TransformKind::MinMax => values
+                .windows(self.period)
+                .enumerate()
+                .filter_map(|(start, window)| {
+                    let window: Vec<f64> = window.iter().copied().collect::<Option<_>>()?;
+                    let current = window[window.len() - 1];
+                    let value = if self.kind == TransformKind::Percentile {
+                        let at_or_below = window.iter().filter(|v| **v <= current).count();
+                        at_or_below as f64 / window.len() as f64 * 100.0
+                    } else {
+                        let min = window.iter().copied().fold(f64::INFINITY, f64::min);
+                        let max = window.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                        if max <= min {
+                            return None;
+                        }
+                        (current - min) / (max - min)
+                    };
+                    Some((start + self.period - 1, value))
+                })
+                .collect(),
+        };
+
+        Ok(transformed
+            .into_iter()
+            .map(|(offset, value)| (first + offset, IndicatorOutput::scalar(value)))
+            .collect())
+    }
+}
+
+/// Whether an indicator's parameters include a transform
+pub fn has_transform(parameters: &Value) -> bool {
+    parameters.get("transform").is_some_and(|block| !block.is_null())
+}
@@ -0,0 +1,199 @@
+// Volume profile
+//
+// Volume traded per price level over a window of candles, summarized as the point of
+// control (the level with the most volume) and the value area around it that holds
+// `value_area` (default 70%) of the volume. Each candle's volume is spread evenly over
+// its high-low range, split into `bins` levels (default 24) between the window's lowest
+// low and highest high.
+//
+// The window is either the last `period` candles (default 24) or, with "session": "day",
+// the candles of the current trading day up to and including each candle. Trading days
+// follow the symbol's session calendar; crypto uses the UTC day.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin::{Indicator, InputSeries};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Name of the volume profile indicator
+pub const VOLUME_PROFILE: &str = "VOLUME_PROFILE";
+
+/// Window the profile is built over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileWindow {
+    /// The last n candles
+    Rolling(usize),
+    /// Candles since the start of the trading day
+    Day,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileSettings {
+    pub window: ProfileWindow,
+    pub bins: usize,
+    pub value_area: f64,
+}
+
+impl ProfileSettings {
+    pub fn from_parameters(parameters: &Value) -> Result<Self> {
+        let count = |name: &str, default: u64, min: u64| -> Result<usize> {
+            match parameters.get(name) {
+                None => Ok(default as usize),
+                Some(value) => value.as_u64()
+                    .filter(|v| *v >= min)
+                    .map(|v| v as usize)
+                    .ok_or_else(|| Error::validation(format!("{} must be an integer of at least {}, got {}", name, min, value))),
+            }
+        };
+
+        let window = match parameters.get("session").and_then(Value::as_str) {
+            None => ProfileWindow::Rolling(count("period", 24, 1)?),
+            Some("day") => ProfileWindow::Day,
+            Some(other) => return Err(Error::validation(format!("session must be \"day\", got {}", other))),
+        };
+
+        let value_area = match parameters.get("value_area") {
+            None => 0.7,
+            Some(value) => value.as_f64()
+                .filter(|v| *v > 0.0 && *v <= 1.0)
+                .ok_or_else(|| Error::validation(format!("value_area must be above 0 and at most 1, got {}", value)))?,
+        };
+
+        Ok(Self {
+            window,
+            bins: count("bins", 24, 2)?,
+            value_area,
+        })
+    }
+}
+
+/// Point of control and value area of one window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    pub poc: f64,
+    pub value_area_high: f64,
+    pub value_area_low: f64,
+}
+
+impl Profile {
+    fn to_output(self) -> IndicatorOutput {
+        IndicatorOutput::Values(BTreeMap::from([
+            ("poc".to_string(), self.poc),
+            ("vah".to_string(), self.value_area_high),
+            ("val".to_string(), self.value_area_low),
+        ]))
+    }
+}
+
+/// Profile of candles `from..=to`; None without volume or price range
+pub fn profile(candles: &CandleData, from: usize, to: usize, bins: usize, value_area: f64) -> Option<Profile> {
+    let range = from..=to;
+    let low = range.clone().map(|i| candles.low[i]).fold(f64::INFINITY, f64::min);
+    let high = range.clone().map(|i| candles.high[i]).fold(f64::NEG_INFINITY, f64::max);
+    if !(high > low) {
+        return None;
+    }
+
+    let bin_size = (high - low) / bins as f64;
+    let bin_of = |price: f64| (((price - low) / bin_size) as usize).min(bins - 1);
+
+    let mut volume = vec![0.0; bins];
+    for i in range {
+        let (candle_low, candle_high) = (candles.low[i], candles.high[i]);
+        if !(candles.volume[i] > 0.0) || !candle_low.is_finite() || !candle_high.is_finite() {
+            continue;
+        }
+
+        let (first, last) = (bin_of(candle_low), bin_of(candle_high));
+        let share = candles.volume[i] / (last - first + 1) as f64;
+        for bin in &mut volume[first..=last] {
+            *bin += share;
+        }
+    }
+
+    let total: f64 = volume.iter().sum();
+    if !(total > 0.0) {
+        return None;
+    }
+
+    // The first of equally large bins is the point of control
+    let poc = (0..bins).fold(0, |best, bin| if volume[bin] > volume[best] { bin } else { best });
+
+    // Grow the value area from the point of control towards the larger neighbour
+    let (mut lower, mut upper) = (poc, poc);
+    let mut covered = volume[poc];
+    while covered < total * value_area && (lower > 0 || upper < bins - 1) {
+        let below = if lower > 0 { volume[lower - 1] } else { f64::NEG_INFINITY };
+        let above = if upper < bins - 1 { volume[upper + 1] } else { f64::NEG_INFINITY };
+        if above >= below {
+            upper += 1;
+            covered += above;
+        } else {
+            lower -= 1;
+            covered += below;
+        }
+    }
+
+    Some(Profile {
+        poc: low + (poc as f64 + 0.5) * bin_size,
+        value_area_high: low + (upper + 1) as f64 * bin_size,
+        value_area_low: low + lower as f64 * bin_size,
+    })
+}
+
+/// Volume profile at every candle with a complete window
+pub fn volume_profile(candles: &CandleData, settings: &ProfileSettings) -> Vec<(usize, Profile)> {
+    let len = candles.close.len();
+    let day = |time: &DateTime<Utc>| candles.session.trading_date(*time);
+    let mut session_start = 0;
+
+    (0..len)
+        .filter_map(|idx| {
+            let from = match settings.window {
+                ProfileWindow::Rolling(period) => idx.checked_sub(period - 1)?,
+                ProfileWindow::Day => {
+                    if idx > 0 && day(&candles.open_time[idx]) != day(&candles.open_time[idx - 1]) {
+                        session_start = idx;
+                    }
+                    session_start
+                },
+            };
+            Some((idx, profile(candles, from, idx, settings.bins, settings.value_area)?))
+        })
+        .collect()
+}
+
+/// The VOLUME_PROFILE indicator: poc, vah (value area high) and val (value area low)
+pub struct VolumeProfile;
+
+impl Indicator for VolumeProfile {
+    fn name(&self) -> &str {
+        VOLUME_PROFILE
+    }
+
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::High, InputSeries::Low, InputSeries::Volume]
+    }
+
+    fn lookback(&self, parameters: &Value) -> Option<usize> {
+        match ProfileSettings::from_parameters(parameters).ok()?.window {
+            ProfileWindow::Rolling(period) => Some(period - 1),
+            // Sessions start wherever the day changes
+            ProfileWindow::Day => None,
+        }
+    }
+
+    fn properties(&self) -> &'static [&'static str] {
+        &["poc", "vah", "val"]
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let settings = ProfileSettings::from_parameters(parameters)?;
+        Ok(volume_profile(candles, &settings)
+            .into_iter()
+            .map(|(idx, profile)| (idx, profile.to_output()))
+            .collect())
+    }
+}
@@ -0,0 +1,78 @@
+// Anchored VWAP
+//
+// Volume-weighted average of the typical price ((high + low + close) / 3) since an
+// anchor: the start of each trading day ("anchor": "session", the default) or the first
+// candle ("anchor": "start"). Trading days follow the symbol's session calendar, so an
+// equity VWAP resets at the exchange's open rather than at midnight UTC. With
+// "session_only": true candles outside the trading hours (pre- and post-market) are left
+// out of the average and produce no value.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin::{Indicator, InputSeries};
+use serde_json::Value;
+
+/// Name of the anchored VWAP indicator
+pub const ANCHORED_VWAP: &str = "ANCHORED_VWAP";
+
+/// Where the average starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VwapAnchor {
+    /// The start of each trading day
+    Session,
+    /// The first candle
+    Start,
+}
+
+/// The ANCHORED_VWAP indicator
+pub struct AnchoredVwap;
+
+impl Indicator for AnchoredVwap {
+    fn name(&self) -> &str {
+        ANCHORED_VWAP
+    }
+
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::High, InputSeries::Low, InputSeries::Close, InputSeries::Volume]
+    }
+
+    // The average runs over the entire history (or the trading day so far)
+    fn lookback(&self, _parameters: &Value) -> Option<usize> {
+        None
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let anchor = match parameters.get("anchor").and_then(Value::as_str) {
+            None | Some("session") => VwapAnchor::Session,
+            Some("start") => VwapAnchor::Start,
+            Some(other) => return Err(Error::validation(format!("anchor must be \"session\" or \"start\", got {}", other))),
+        };
+        let session_only = match parameters.get("session_only") {
+            None => false,
+            Some(value) => value.as_bool()
+                .ok_or_else(|| Error::validation(format!("session_only must be true or false, got {}", value)))?,
+        };
+
+        let session = &candles.session;
+        let typical = candles.typical_price();
+        let (mut price_volume, mut volume) = (0.0, 0.0);
+
+        Ok((0..candles.len())
+            .filter_map(|idx| {
+                let time = candles.open_time[idx];
+                if anchor == VwapAnchor::Session && idx > 0
+                    && session.trading_date(time) != session.trading_date(candles.open_time[idx - 1]) {
+                    price_volume = 0.0;
+                    volume = 0.0;
+                }
+                if session_only && !session.is_open(time) {
+                    return None;
+                }
+
+                price_volume += typical[idx] * candles.volume[idx];
+                volume += candles.volume[idx];
+                (volume > 0.0).then(|| (idx, IndicatorOutput::scalar(price_volume / volume)))
+            })
+            .collect())
+    }
+}
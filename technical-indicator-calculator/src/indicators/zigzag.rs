@@ -0,0 +1,205 @@
+// ZigZag swing pivots
+//
+// A swing high is confirmed once price has fallen from it by the reversal threshold, a
+// swing low once price has risen from it by the threshold; highs and lows alternate. The
+// threshold is either a percentage of the extreme or a multiple of the ATR at the
+// confirming candle. Pivots are reported on the candle that confirms them, together with
+// how many candles back the extreme was, so consumers never see a pivot early.
+//
+// Available as the built-in ZIGZAG indicator and used by chart pattern detection.
+use crate::database::models::CandleData;
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::plugin::{Indicator, InputSeries};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Name of the ZigZag indicator
+pub const ZIGZAG: &str = "ZIGZAG";
+
+/// Price move that confirms a reversal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReversalThreshold {
+    /// Fraction of the extreme, e.g. 0.05 for 5%
+    Percent(f64),
+    /// Multiple of the Wilder ATR over `period` candles
+    Atr { period: usize, multiplier: f64 },
+}
+
+impl ReversalThreshold {
+    /// Threshold from `reversal_percent` (in percent) or `atr_multiplier` and `atr_period`
+    ///
+    /// Returns None when neither is given.
+    pub fn from_parameters(parameters: &Value) -> Result<Option<Self>> {
+        let positive = |name: &str| -> Result<Option<f64>> {
+            match parameters.get(name) {
+                None => Ok(None),
+                Some(value) => value
+                    .as_f64()
+                    .filter(|v| *v > 0.0)
+                    .map(Some)
+                    .ok_or_else(|| Error::validation(format!("{} must be a positive number, got {}", name, value))),
+            }
+        };
+
+        match (positive("reversal_percent")?, positive("atr_multiplier")?) {
+            (Some(_), Some(_)) => Err(Error::validation(
+                "Give either reversal_percent or atr_multiplier, not both",
+            )),
+            (Some(percent), None) => Ok(Some(ReversalThreshold::Percent(percent / 100.0))),
+            (None, Some(multiplier)) => {
+                let period = match parameters.get("atr_period") {
+                    None => 14,
+                    Some(value) => value
+                        .as_u64()
+                        .filter(|period| *period >= 1)
+                        .ok_or_else(|| Error::validation(format!("atr_period must be a positive integer, got {}", value)))?
+                        as usize,
+                };
+                Ok(Some(ReversalThreshold::Atr { period, multiplier }))
+            },
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// A confirmed swing high or low
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwingPivot {
+    pub kind: SwingKind,
+    /// Candle of the extreme
+    pub index: usize,
+    /// Candle on which the reversal confirmed the pivot
+    pub confirmed: usize,
+    pub price: f64,
+}
+
+/// Wilder ATR as TA-Lib calculates it from `CandleData::true_range`; None during the
+/// first `period` candles
+///
+/// Like TA-Lib, the first candle's true range (which has no previous close) is skipped.
+pub fn wilder_atr(true_range: &[f64], period: usize) -> Vec<Option<f64>> {
+    let len = true_range.len();
+    let mut atr = vec![None; len];
+    if len <= period {
+        return atr;
+    }
+
+    let mut value = true_range[1..=period].iter().sum::<f64>() / period as f64;
+    atr[period] = Some(value);
+    for (i, range) in true_range.iter().enumerate().skip(period + 1) {
+        value = (value * (period - 1) as f64 + range) / period as f64;
+        atr[i] = Some(value);
+    }
+    atr
+}
+
+/// Confirmed swing pivots in the order they were confirmed
+pub fn swing_pivots(candles: &CandleData, threshold: ReversalThreshold) -> Vec<SwingPivot> {
+    let (high, low) = (&candles.high, &candles.low);
+    let len = high.len().min(low.len()).min(candles.close.len());
+
+    let atr = match threshold {
+        ReversalThreshold::Atr { period, .. } => wilder_atr(&candles.true_range()[..len], period),
+        ReversalThreshold::Percent(_) => Vec::new(),
+    };
+    let reversal = |i: usize, extreme: f64| -> Option<f64> {
+        match threshold {
+            ReversalThreshold::Percent(fraction) => Some(extreme.abs() * fraction),
+            ReversalThreshold::Atr { multiplier, .. } => atr[i].map(|atr| atr * multiplier),
+        }
+    };
+
+    let mut pivots = Vec::new();
+    if len == 0 {
+        return pivots;
+    }
+
+    // Direction of the swing in progress; None until the first reversal
+    let mut direction: Option<SwingKind> = None;
+    let (mut high_idx, mut low_idx) = (0, 0);
+
+    for i in 1..len {
+        if high[i] > high[high_idx] {
+            high_idx = i;
+        }
+        if low[i] < low[low_idx] {
+            low_idx = i;
+        }
+
+        // Rising swings look for a reversal from the high, falling ones from the low
+        let check_high = direction != Some(SwingKind::Low);
+        let check_low = direction != Some(SwingKind::High);
+
+        if check_high && high_idx < i {
+            if let Some(threshold) = reversal(i, high[high_idx]) {
+                if high[high_idx] - low[i] >= threshold {
+                    pivots.push(SwingPivot { kind: SwingKind::High, index: high_idx, confirmed: i, price: high[high_idx] });
+                    direction = Some(SwingKind::Low);
+                    low_idx = (high_idx + 1..=i).min_by(|a, b| low[*a].total_cmp(&low[*b])).unwrap_or(i);
+                    continue;
+                }
+            }
+        }
+
+        if check_low && low_idx < i {
+            if let Some(threshold) = reversal(i, low[low_idx]) {
+                if high[i] - low[low_idx] >= threshold {
+                    pivots.push(SwingPivot { kind: SwingKind::Low, index: low_idx, confirmed: i, price: low[low_idx] });
+                    direction = Some(SwingKind::High);
+                    high_idx = (low_idx + 1..=i).max_by(|a, b| high[*a].total_cmp(&high[*b])).unwrap_or(i);
+                }
+            }
+        }
+    }
+
+    pivots
+}
+
+/// The ZIGZAG indicator
+///
+/// Emits swing (+1 for a high, -1 for a low), price and bars_ago (candles between the
+/// extreme and the confirming candle) on every confirming candle.
+pub struct ZigZag;
+
+impl Indicator for ZigZag {
+    fn name(&self) -> &str {
+        ZIGZAG
+    }
+
+    fn inputs(&self) -> &[InputSeries] {
+        &[InputSeries::High, InputSeries::Low, InputSeries::Close]
+    }
+
+    // Each pivot depends on the swing in progress, which can span any number of candles
+    fn lookback(&self, _parameters: &Value) -> Option<usize> {
+        None
+    }
+
+    fn properties(&self) -> &'static [&'static str] {
+        &["swing", "price", "bars_ago"]
+    }
+
+    fn calculate(&self, candles: &CandleData, parameters: &Value) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let threshold = ReversalThreshold::from_parameters(parameters)?
+            .unwrap_or(ReversalThreshold::Percent(0.05));
+
+        Ok(swing_pivots(candles, threshold)
+            .into_iter()
+            .map(|pivot| {
+                let values = BTreeMap::from([
+                    ("swing".to_string(), if pivot.kind == SwingKind::High { 1.0 } else { -1.0 }),
+                    ("price".to_string(), pivot.price),
+                    ("bars_ago".to_string(), (pivot.confirmed - pivot.index) as f64),
+                ]);
+                (pivot.confirmed, IndicatorOutput::Values(values))
+            })
+            .collect())
+    }
+}
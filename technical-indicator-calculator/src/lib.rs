@@ -1,14 +1,42 @@
 // Export all necessary modules
+//
+// Without the default native feature only the pure-Rust indicators and the candle model they
+// read are built, so the library compiles for wasm32-unknown-unknown.
+#[cfg(feature = "native")]
 pub mod strategy;
+#[cfg(feature = "native")]
 pub mod cli;
+#[cfg(feature = "native")]
+pub mod cli_help;
+#[cfg(feature = "native")]
 pub mod daemon;
+#[cfg(feature = "native")]
 pub mod worker;
 
 // Let's make sure the lib.rs exports other modules that might be needed
 pub mod database;
+#[cfg(feature = "native")]
+pub mod data_source;
+#[cfg(feature = "native")]
 pub mod cache;
 pub mod indicators;
+#[cfg(feature = "native")]
 pub mod processor;
+#[cfg(feature = "native")]
 pub mod talib_bindings;
 pub mod utils;
+#[cfg(feature = "native")]
 pub mod config;
+pub mod error;
+#[cfg(feature = "native")]
+pub mod signals;
+#[cfg(feature = "native")]
+pub mod execution;
+#[cfg(feature = "native")]
+pub mod events;
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
@@ -1,7 +1,12 @@
 // src/main.rs
-use technical_indicator_calculator::cli::{Cli, Commands};
+use technical_indicator_calculator::cli::{Cli, Commands, StatusCommands};
+use technical_indicator_calculator::config::AppConfig;
 use technical_indicator_calculator::strategy::cli_handler::execute_command;
-use technical_indicator_calculator::daemon::{start_daemon, stop_daemon, check_daemon_status};
+use technical_indicator_calculator::daemon::{
+    start_daemon, stop_daemon, check_daemon_status, check_cluster_status, check_coverage_status, 
+    check_timings_status, CoverageFilter,
+};
+use technical_indicator_calculator::utils::logging::init_tracing;
 use technical_indicator_calculator::worker::start_worker;
 use clap::Parser;
 use anyhow::Result;
@@ -11,31 +16,52 @@ async fn main() -> Result<()> {
     // Initialize environment
     dotenv::dotenv().ok();
     
-    // Initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-    
     // Parse command line arguments
     let cli = Cli::parse();
     
+    // --namespace applies to every settings load, including a detached worker's
+    if let Some(namespace) = &cli.namespace {
+        std::env::set_var("DB_NAMESPACE", namespace);
+    }
+    
+    // Load settings from config.toml, with environment variables taking precedence
+    let config = AppConfig::load()?;
+    
+    // Initialize logging and telemetry; the guard flushes buffered output on exit
+    let _tracing_guard = init_tracing(&config.logging, &config.telemetry)?;
+    
     // Execute command
     match cli.command {
         Commands::Start { concurrency, detached } => {
             if detached {
                 start_daemon(concurrency).await?;
             } else {
-                start_worker(concurrency).await?;
+                start_worker(&config, concurrency).await?;
             }
         },
         Commands::Stop => {
             stop_daemon().await?;
         },
-        Commands::Status => {
-            check_daemon_status().await?;
+        #[cfg(feature = "grpc")]
+        Commands::Serve { listen } => {
+            technical_indicator_calculator::grpc::serve(&config, &listen).await?;
+        },
+        Commands::Status { command: Some(StatusCommands::Coverage { symbol, interval, indicator, incomplete, json }), .. } => {
+            let filter = CoverageFilter { symbol, interval, indicator, incomplete_only: incomplete };
+            check_coverage_status(&config, &filter, json).await?;
+        },
+        Commands::Status { command: Some(StatusCommands::Timings { limit, prometheus }), .. } => {
+            check_timings_status(limit, prometheus).await?;
+        },
+        Commands::Status { cluster, .. } => {
+            if cluster {
+                check_cluster_status(&config).await?;
+            } else {
+                check_daemon_status().await?;
+            }
         },
         _ => {
-            execute_command(cli.command).await?;
+            execute_command(cli.command, cli.output).await?;
         }
     }
     
@@ -0,0 +1,124 @@
+// Sanity checks on calculated indicator values
+//
+// Some values point at corrupt candles or a broken calculation (a TA-Lib FFI bug, a
+// faulty user-defined indicator) rather than at the market: NaN/Inf, a value many times
+// larger than anything in the values before it, or the same value repeated for a long
+// run while the close price kept moving. The worker checks the new values of each job
+// and records what it finds in indicator_anomalies.
+use crate::database::models::CandleData;
+use crate::indicators::output::IndicatorOutput;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Values before a value that a jump is measured against
+const JUMP_WINDOW: usize = 20;
+
+/// Thresholds of the anomaly checks
+#[derive(Debug, Clone, Copy)]
+pub struct AnomalySettings {
+    /// A value jumps when its magnitude exceeds this multiple of the largest magnitude
+    /// of the values before it; 0 disables the check
+    pub jump_factor: f64,
+    /// Identical consecutive values flagged while the close price changes; 0 disables the check
+    pub constant_run: usize,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            jump_factor: 10.0,
+            constant_run: 200,
+        }
+    }
+}
+
+/// A suspicious calculated value
+#[derive(Debug, Clone)]
+pub struct IndicatorAnomaly {
+    /// Name of the failed check: "non_finite", "jump" or "constant_run"
+    pub check: &'static str,
+    pub time: DateTime<Utc>,
+    /// Property of a multi-valued output (e.g. "signal"); None for scalars
+    pub property: Option<String>,
+    pub value: f64,
+    pub detail: String,
+}
+
+/// Check the values an indicator calculated from `candles`, returning anomalies of
+/// values after `after`; earlier values only serve as history for the checks
+pub fn detect_anomalies(
+    values: &[(DateTime<Utc>, IndicatorOutput)],
+    candles: &CandleData,
+    after: Option<DateTime<Utc>>,
+    settings: &AnomalySettings,
+) -> Vec<IndicatorAnomaly> {
+    // One series per numeric property, in time order
+    let mut series: BTreeMap<Option<&str>, Vec<(DateTime<Utc>, f64)>> = BTreeMap::new();
+    for (time, output) in values {
+        for (property, value) in output.numeric_values() {
+            series.entry(property).or_default().push((*time, value));
+        }
+    }
+
+    let is_new = |time: DateTime<Utc>| after.map_or(true, |after| time > after);
+    let mut anomalies = Vec::new();
+
+    for (property, points) in series {
+        let anomaly = |check, time, value, detail| IndicatorAnomaly {
+            check,
+            time,
+            property: property.map(str::to_string),
+            value,
+            detail,
+        };
+        let mut run_start = 0;
+
+        for (idx, &(time, value)) in points.iter().enumerate() {
+            if idx > 0 && points[idx - 1].1 != value {
+                run_start = idx;
+            }
+            if !is_new(time) {
+                continue;
+            }
+
+            if !value.is_finite() {
+                anomalies.push(anomaly("non_finite", time, value, format!("{} value", value)));
+                continue;
+            }
+
+            // Reported once, when the run reaches its threshold
+            let run = idx - run_start + 1;
+            if settings.constant_run > 0 && run == settings.constant_run
+                && close_changed(candles, points[run_start].0, time) {
+                anomalies.push(anomaly("constant_run", time, value, format!(
+                    "{} repeated {} times since {} while the close price changed",
+                    value, run, points[run_start].0.to_rfc3339()
+                )));
+            }
+
+            if settings.jump_factor > 0.0 && idx >= JUMP_WINDOW {
+                let largest = points[idx - JUMP_WINDOW..idx].iter()
+                    .map(|(_, v)| v.abs())
+                    .filter(|v| v.is_finite())
+                    .fold(0.0, f64::max);
+                if largest > 0.0 && value.abs() > largest * settings.jump_factor {
+                    anomalies.push(anomaly("jump", time, value, format!(
+                        "{} is {:.1}x the largest magnitude of the previous {} values ({})",
+                        value, value.abs() / largest, JUMP_WINDOW, largest
+                    )));
+                }
+            }
+        }
+    }
+
+    anomalies.sort_by_key(|anomaly| anomaly.time);
+    anomalies
+}
+
+// Whether the close price of the candles between two times is not constant
+fn close_changed(candles: &CandleData, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+    let start = candles.open_time.partition_point(|time| *time < from);
+    let end = candles.open_time.partition_point(|time| *time <= to);
+    candles.close.get(start..end)
+        .map_or(false, |closes| closes.windows(2).any(|w| w[0] != w[1]))
+}
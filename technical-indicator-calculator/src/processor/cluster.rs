@@ -0,0 +1,386 @@
+// Shared job queue for several worker instances, coordinated through Redis
+//
+// Queued jobs live in one sorted set per worker pool, scored by priority. A worker
+// claims a job by moving it into the lease hash with an expiry; heartbeats extend
+// the leases of running jobs, and leases that expire (because their worker crashed)
+// are put back into their queue by whichever worker reaps them first. Queue entries
+// leave out the job ID so the same job is only queued once; the ID of the first
+// enqueue is kept in a separate hash until the job completes.
+use crate::cache::redis::RedisManager;
+use crate::processor::job::CalculationJob;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+const QUEUE_PREFIX: &str = "cluster:queue:";
+const LEASES_KEY: &str = "cluster:leases";
+const LEASE_EXPIRY_KEY: &str = "cluster:lease_expiry";
+const WORKERS_KEY: &str = "cluster:workers";
+const JOB_IDS_KEY: &str = "cluster:job_ids";
+
+// Workers silent for this many heartbeats are removed from the registry
+const WORKER_EXPIRY_HEARTBEATS: i64 = 10;
+
+// Queue a job unless it is already queued or leased
+const ENQUEUE_SCRIPT: &str = r"
+if redis.call('HEXISTS', KEYS[2], ARGV[1]) == 1 then
+    return 0
+end
+local added = redis.call('ZADD', KEYS[1], 'NX', ARGV[2], ARGV[1])
+if added == 1 then
+    redis.call('HSETNX', KEYS[3], ARGV[1], ARGV[3])
+end
+return added
+";
+
+// Take the highest-priority job and lease it to a worker, returning it with its ID
+const CLAIM_SCRIPT: &str = r"
+local popped = redis.call('ZPOPMAX', KEYS[1])
+if #popped == 0 then
+    return false
+end
+local payload = popped[1]
+local lease = cjson.encode({worker = ARGV[1], queue = KEYS[1], priority = tonumber(popped[2])})
+redis.call('HSET', KEYS[2], payload, lease)
+redis.call('ZADD', KEYS[3], ARGV[2], payload)
+return {payload, redis.call('HGET', KEYS[4], payload) or ''}
+";
+
+// Extend a lease if it is still held by the worker
+const RENEW_SCRIPT: &str = r"
+local lease = redis.call('HGET', KEYS[1], ARGV[1])
+if not lease or cjson.decode(lease).worker ~= ARGV[2] then
+    return 0
+end
+redis.call('ZADD', KEYS[2], 'XX', ARGV[3], ARGV[1])
+return 1
+";
+
+// Put jobs with expired leases back into their queue
+const REAP_SCRIPT: &str = r"
+local expired = redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', ARGV[1])
+for _, payload in ipairs(expired) do
+    local lease = redis.call('HGET', KEYS[1], payload)
+    redis.call('ZREM', KEYS[2], payload)
+    redis.call('HDEL', KEYS[1], payload)
+    if lease then
+        local info = cjson.decode(lease)
+        redis.call('ZADD', info.queue, 'NX', info.priority, payload)
+    end
+end
+return #expired
+";
+
+/// A worker instance as registered in Redis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterWorkerInfo {
+    pub id: String,
+    pub hostname: String,
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    pub last_heartbeat: DateTime<Utc>,
+    pub jobs_completed: u64,
+    pub jobs_failed: u64,
+    pub active_jobs: usize,
+}
+
+impl ClusterWorkerInfo {
+    /// Completed jobs per minute since the worker started
+    pub fn throughput(&self) -> f64 {
+        let minutes = (self.last_heartbeat - self.started_at).num_seconds() as f64 / 60.0;
+        if minutes > 0.0 {
+            self.jobs_completed as f64 / minutes
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Membership of this worker instance in the cluster
+pub struct ClusterCoordinator {
+    redis: Arc<RedisManager>,
+    worker_id: String,
+    hostname: String,
+    started_at: DateTime<Utc>,
+    lease: Duration,
+    // Payloads of the jobs this worker currently holds leases for
+    held: Mutex<HashSet<String>>,
+    completed: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl ClusterCoordinator {
+    pub fn new(redis: Arc<RedisManager>, lease: Duration) -> Self {
+        let hostname = hostname();
+        let worker_id = format!("{}-{}-{}", hostname, std::process::id(), &Uuid::new_v4().simple().to_string()[..8]);
+
+        Self {
+            redis,
+            worker_id,
+            hostname,
+            started_at: Utc::now(),
+            lease,
+            held: Mutex::new(HashSet::new()),
+            completed: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+        }
+    }
+
+    fn queue_key(pool: &str) -> String {
+        format!("{}{}", QUEUE_PREFIX, pool)
+    }
+
+    fn lease_expiry(&self) -> i64 {
+        Utc::now().timestamp() + self.lease.as_secs() as i64
+    }
+
+    // Queue entry of a job: the job without its ID
+    fn payload(job: &CalculationJob) -> Result<String> {
+        let mut value = serde_json::to_value(job)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("id");
+        }
+        Ok(value.to_string())
+    }
+
+    /// Add a job to the shared queue of a pool. Returns false if it is already queued or running.
+    pub async fn enqueue(&self, pool: &str, job: &CalculationJob, priority: i32) -> Result<bool> {
+        let payload = Self::payload(job)?;
+        let mut conn = self.redis.connection().await?;
+
+        let added: i64 = redis::Script::new(ENQUEUE_SCRIPT)
+            .key(Self::queue_key(pool))
+            .key(LEASES_KEY)
+            .key(JOB_IDS_KEY)
+            .arg(payload)
+            .arg(priority)
+            .arg(job.id.to_string())
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(added > 0)
+    }
+
+    /// Lease the most urgent job of a pool, if there is one
+    pub async fn claim(&self, pool: &str) -> Result<Option<CalculationJob>> {
+        let mut conn = self.redis.connection().await?;
+
+        let claimed: Option<(String, String)> = redis::Script::new(CLAIM_SCRIPT)
+            .key(Self::queue_key(pool))
+            .key(LEASES_KEY)
+            .key(LEASE_EXPIRY_KEY)
+            .key(JOB_IDS_KEY)
+            .arg(&self.worker_id)
+            .arg(self.lease_expiry())
+            .invoke_async(&mut conn)
+            .await?;
+
+        let (payload, id) = match claimed {
+            Some(claimed) => claimed,
+            None => return Ok(None),
+        };
+
+        // Entries queued without an ID keep the fresh one from deserializing
+        let mut job: CalculationJob = serde_json::from_str(&payload)?;
+        if let Ok(id) = Uuid::parse_str(&id) {
+            job.id = id;
+        }
+        self.held.lock().insert(payload);
+        Ok(Some(job))
+    }
+
+    /// Wait until a job of the pool can be claimed, polling the shared queue
+    pub async fn claim_next(&self, pool: &str) -> CalculationJob {
+        loop {
+            match self.claim(pool).await {
+                Ok(Some(job)) => return job,
+                Ok(None) => {},
+                Err(e) => warn!("Failed to claim job from cluster queue '{}': {}", pool, e),
+            }
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Release the lease of a finished job
+    pub async fn complete(&self, job: &CalculationJob, success: bool) -> Result<()> {
+        if success {
+            self.completed.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let payload = Self::payload(job)?;
+        self.held.lock().remove(&payload);
+
+        let mut conn = self.redis.connection().await?;
+        redis::pipe()
+            .hdel(LEASES_KEY, &payload).ignore()
+            .zrem(LEASE_EXPIRY_KEY, &payload).ignore()
+            .hdel(JOB_IDS_KEY, &payload).ignore()
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    // Publish this worker's state, extend its leases and re-queue expired ones
+    async fn heartbeat(&self) -> Result<()> {
+        let held: Vec<String> = self.held.lock().iter().cloned().collect();
+        let now = Utc::now();
+
+        let info = ClusterWorkerInfo {
+            id: self.worker_id.clone(),
+            hostname: self.hostname.clone(),
+            pid: std::process::id(),
+            started_at: self.started_at,
+            last_heartbeat: now,
+            jobs_completed: self.completed.load(Ordering::Relaxed),
+            jobs_failed: self.failed.load(Ordering::Relaxed),
+            active_jobs: held.len(),
+        };
+
+        let mut conn = self.redis.connection().await?;
+        redis::cmd("HSET")
+            .arg(WORKERS_KEY)
+            .arg(&self.worker_id)
+            .arg(serde_json::to_string(&info)?)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        let renew = redis::Script::new(RENEW_SCRIPT);
+        let expiry = self.lease_expiry();
+        for payload in &held {
+            let renewed: i64 = renew
+                .key(LEASES_KEY)
+                .key(LEASE_EXPIRY_KEY)
+                .arg(payload)
+                .arg(&self.worker_id)
+                .arg(expiry)
+                .invoke_async(&mut conn)
+                .await?;
+
+            if renewed == 0 {
+                warn!("Lost lease on job {}, it may run twice", payload);
+            }
+        }
+
+        let requeued: i64 = redis::Script::new(REAP_SCRIPT)
+            .key(LEASES_KEY)
+            .key(LEASE_EXPIRY_KEY)
+            .arg(now.timestamp())
+            .invoke_async(&mut conn)
+            .await?;
+
+        if requeued > 0 {
+            info!("Re-queued {} jobs with expired leases", requeued);
+        }
+
+        // Forget workers that stopped sending heartbeats long ago
+        let cutoff = now - chrono::Duration::seconds(self.heartbeat_interval().as_secs() as i64 * WORKER_EXPIRY_HEARTBEATS);
+        for worker in list_workers(&self.redis).await? {
+            if worker.last_heartbeat < cutoff {
+                debug!("Removing expired worker {} from the registry", worker.id);
+                redis::cmd("HDEL")
+                    .arg(WORKERS_KEY)
+                    .arg(&worker.id)
+                    .query_async::<_, ()>(&mut conn)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Time between heartbeats; a lease survives two missed heartbeats
+    pub fn heartbeat_interval(&self) -> Duration {
+        (self.lease / 3).max(Duration::from_secs(1))
+    }
+
+    /// Send heartbeats until the worker stops
+    pub async fn run_heartbeat(self: Arc<Self>) {
+        info!("Joined worker cluster as {}", self.worker_id);
+
+        loop {
+            if let Err(e) = self.heartbeat().await {
+                warn!("Cluster heartbeat failed: {}", e);
+            }
+
+            tokio::time::sleep(self.heartbeat_interval()).await;
+        }
+    }
+
+    /// Remove this worker from the registry
+    pub async fn leave(&self) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        redis::cmd("HDEL")
+            .arg(WORKERS_KEY)
+            .arg(&self.worker_id)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        info!("Left worker cluster as {}", self.worker_id);
+        Ok(())
+    }
+}
+
+/// All workers registered in the cluster, most recently seen first
+pub async fn list_workers(redis: &RedisManager) -> Result<Vec<ClusterWorkerInfo>> {
+    let mut conn = redis.connection().await?;
+    let entries: Vec<String> = redis::cmd("HVALS")
+        .arg(WORKERS_KEY)
+        .query_async(&mut conn)
+        .await?;
+
+    let mut workers: Vec<ClusterWorkerInfo> = entries
+        .iter()
+        .filter_map(|entry| serde_json::from_str(entry).ok())
+        .collect();
+    workers.sort_by(|a, b| b.last_heartbeat.cmp(&a.last_heartbeat));
+
+    Ok(workers)
+}
+
+/// Number of queued and leased jobs across all pools
+pub async fn queue_depth(redis: &RedisManager) -> Result<(u64, u64)> {
+    let mut conn = redis.connection().await?;
+
+    let queues: Vec<String> = redis::cmd("KEYS")
+        .arg(format!("{}*", QUEUE_PREFIX))
+        .query_async(&mut conn)
+        .await?;
+
+    let mut queued = 0;
+    for queue in queues {
+        let len: u64 = redis::cmd("ZCARD").arg(&queue).query_async(&mut conn).await?;
+        queued += len;
+    }
+
+    let leased: u64 = redis::cmd("HLEN").arg(LEASES_KEY).query_async(&mut conn).await?;
+
+    Ok((queued, leased))
+}
+
+// Host name of this machine, for worker IDs
+fn hostname() -> String {
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr() as *mut libc::c_char, buffer.len()) };
+
+    if result == 0 {
+        let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+        if let Ok(name) = std::str::from_utf8(&buffer[..len]) {
+            if !name.is_empty() {
+                return name.to_string();
+            }
+        }
+    }
+
+    "localhost".to_string()
+}
@@ -1,5 +1,9 @@
+use crate::database::models::IndicatorConfig;
+use crate::indicators::pipeline::{self, PipelineNode, PipelineStage};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 use std::fmt;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum IndicatorType {
@@ -37,11 +41,20 @@ impl From<&str> for IndicatorType {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CalculationJob {
+    /// Correlation ID following one run of the job through the queue, Redis, logs and job records
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
     pub symbol: String,
     pub interval: String,
     pub indicator_type: IndicatorType,
     pub indicator_name: String,
     pub parameters: serde_json::Value,
+    /// Further parameter sets of the same indicator, calculated from the same candles in one run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<serde_json::Value>,
+    /// Upstream calculations whose output is this job's input (see indicators::pipeline)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<PipelineStage>,
 }
 
 impl CalculationJob {
@@ -53,14 +66,68 @@ impl CalculationJob {
         parameters: serde_json::Value,
     ) -> Self {
         Self {
+            id: Uuid::new_v4(),
             symbol,
             interval,
             indicator_type,
             indicator_name,
             parameters,
+            variants: Vec::new(),
+            inputs: Vec::new(),
         }
     }
 
+    /// Jobs for configurations with their pipeline inputs resolved, each after the jobs
+    /// it takes input from; configurations with unresolvable inputs are logged and skipped
+    pub fn for_configs(configs: Vec<IndicatorConfig>) -> Vec<(CalculationJob, i32)> {
+        let nodes: Vec<PipelineNode> = configs.iter()
+            .map(|config| PipelineNode {
+                symbol: &config.symbol,
+                interval: &config.interval,
+                indicator_name: &config.indicator_name,
+                parameters: &config.parameters,
+            })
+            .collect();
+        let resolved = pipeline::resolve(&nodes);
+
+        let mut configs: Vec<Option<IndicatorConfig>> = configs.into_iter().map(Some).collect();
+        resolved.into_iter()
+            .filter_map(|(idx, inputs)| {
+                let config = configs[idx].take()?;
+                let inputs = match inputs {
+                    Ok(inputs) => inputs,
+                    Err(e) => {
+                        warn!("Skipping indicator configuration {} ({}:{}:{}): {}",
+                              config.id, config.symbol, config.interval, config.indicator_name, e);
+                        return None;
+                    },
+                };
+
+                let mut job = CalculationJob::new(
+                    config.symbol,
+                    config.interval,
+                    IndicatorType::from(config.indicator_type.as_str()),
+                    config.indicator_name,
+                    config.parameters,
+                );
+                job.inputs = inputs;
+                Some((job, config.priority))
+            })
+            .collect()
+    }
+
+    /// This job and one single-parameter job per variant, all sharing the job's ID
+    pub fn expand_variants(&self) -> Vec<CalculationJob> {
+        std::iter::once(&self.parameters)
+            .chain(&self.variants)
+            .map(|parameters| CalculationJob {
+                parameters: parameters.clone(),
+                variants: Vec::new(),
+                ..self.clone()
+            })
+            .collect()
+    }
+
     pub fn cache_key(&self) -> String {
         format!(
             "job:{}:{}:{}:{}:{}",
@@ -1,2 +1,8 @@
+pub mod anomaly;
+pub mod cluster;
 pub mod job;
+pub mod quality;
+pub mod queue;
+pub mod status;
+pub mod timings;
 pub mod worker;
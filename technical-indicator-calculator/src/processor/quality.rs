@@ -0,0 +1,72 @@
+// Sanity checks on candle data before indicators are calculated from it
+use crate::database::models::CandleData;
+use crate::utils::utils::interval_to_seconds;
+
+/// A problem found in a candle dataset
+#[derive(Debug, Clone)]
+pub struct QualityIssue {
+    /// Name of the failed check (e.g. "non_monotonic_time")
+    pub check: &'static str,
+    pub detail: String,
+}
+
+impl QualityIssue {
+    fn new(check: &'static str, detail: String) -> Self {
+        Self { check, detail }
+    }
+}
+
+/// Run all checks against a candle dataset, returning every issue found
+pub fn check_candle_quality(data: &CandleData, max_gap_ratio: f64) -> Vec<QualityIssue> {
+    let mut issues = Vec::new();
+
+    // Open times must be strictly increasing
+    if let Some(index) = data.open_time.windows(2).position(|w| w[1] <= w[0]) {
+        issues.push(QualityIssue::new(
+            "non_monotonic_time",
+            format!("Open time {} follows {}", data.open_time[index + 1], data.open_time[index]),
+        ));
+    }
+
+    // Prices must be finite and positive, volume finite and not negative
+    let columns: [(&str, &[f64]); 4] = [
+        ("open", &data.open),
+        ("high", &data.high),
+        ("low", &data.low),
+        ("close", &data.close),
+    ];
+    for (name, values) in columns {
+        if let Some(index) = values.iter().position(|v| !v.is_finite() || *v <= 0.0) {
+            issues.push(QualityIssue::new(
+                "invalid_price",
+                format!("{} price {} at {}", name, values[index], data.open_time[index]),
+            ));
+        }
+    }
+
+    if let Some(index) = data.volume.iter().position(|v| !v.is_finite() || *v < 0.0) {
+        issues.push(QualityIssue::new(
+            "invalid_volume",
+            format!("Volume {} at {}", data.volume[index], data.open_time[index]),
+        ));
+    }
+
+    // Too many missing candles make indicator values meaningless
+    if let (Some(step), Some(first), Some(last)) = (interval_to_seconds(&data.interval), data.open_time.first(), data.open_time.last()) {
+        let expected = (*last - *first).num_seconds() / step + 1;
+        let missing = expected - data.open_time.len() as i64;
+
+        if expected > 0 && missing > 0 {
+            let ratio = missing as f64 / expected as f64;
+            if ratio > max_gap_ratio {
+                issues.push(QualityIssue::new(
+                    "gap_ratio",
+                    format!("{} of {} candles missing ({:.2}% > {:.2}%)", 
+                            missing, expected, ratio * 100.0, max_gap_ratio * 100.0),
+                ));
+            }
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,158 @@
+// Priority queue of calculation jobs feeding a worker pool
+use crate::processor::job::CalculationJob;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use tokio::sync::Notify;
+
+/// Order in which jobs of equal priority are handed to the workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulingPolicy {
+    /// Alternate between symbol/interval pairs so no pair monopolizes the pool
+    RoundRobin,
+    /// Jobs whose indicator values lag furthest behind run first
+    StalenessFirst,
+}
+
+impl SchedulingPolicy {
+    /// Parse a policy name as used in SCHEDULING_POLICY
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().replace('_', "-").as_str() {
+            "round-robin" => Some(SchedulingPolicy::RoundRobin),
+            "staleness-first" => Some(SchedulingPolicy::StalenessFirst),
+            _ => None,
+        }
+    }
+}
+
+// A queued job with its scheduling key
+struct QueuedJob {
+    priority: i32,
+    // Jobs without any calculated values yet (full history backfills)
+    backfill: bool,
+    // Policy-specific rank, lower runs first
+    rank: i64,
+    // Insertion order, breaks remaining ties first-in first-out
+    seq: u64,
+    job: CalculationJob,
+}
+
+impl QueuedJob {
+    // Greater keys are popped first
+    fn key(&self) -> (i32, bool, i64, u64) {
+        (self.priority, !self.backfill, -self.rank, u64::MAX - self.seq)
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    heap: BinaryHeap<QueuedJob>,
+    // Jobs queued so far per symbol/interval, used for round-robin ranks
+    rounds: HashMap<String, i64>,
+    seq: u64,
+}
+
+/// Bounded job queue ordered by config priority, then incremental updates before
+/// backfills, then the scheduling policy
+pub struct JobQueue {
+    policy: SchedulingPolicy,
+    capacity: usize,
+    state: Mutex<QueueState>,
+    notify: Notify,
+}
+
+impl JobQueue {
+    pub fn new(policy: SchedulingPolicy, capacity: usize) -> Self {
+        Self {
+            policy,
+            capacity,
+            state: Mutex::new(QueueState::default()),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Queue a job, handing it back when the queue is full.
+    /// `last_calculated` is the time of the newest stored value, None if there is none yet.
+    pub fn try_push(
+        &self,
+        job: CalculationJob,
+        priority: i32,
+        last_calculated: Option<DateTime<Utc>>,
+    ) -> Result<(), CalculationJob> {
+        let mut state = self.state.lock();
+
+        if state.heap.len() >= self.capacity {
+            return Err(job);
+        }
+
+        let rank = match self.policy {
+            SchedulingPolicy::RoundRobin => {
+                let round = state.rounds.entry(format!("{}:{}", job.symbol, job.interval)).or_insert(0);
+                *round += 1;
+                *round
+            },
+            SchedulingPolicy::StalenessFirst => last_calculated.map_or(0, |t| t.timestamp()),
+        };
+
+        state.seq += 1;
+        let seq = state.seq;
+        state.heap.push(QueuedJob {
+            priority,
+            backfill: last_calculated.is_none(),
+            rank,
+            seq,
+            job,
+        });
+        drop(state);
+
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// Wait for the most urgent job
+    pub async fn pop(&self) -> CalculationJob {
+        loop {
+            let notified = self.notify.notified();
+
+            if let Some(entry) = self.state.lock().heap.pop() {
+                return entry.job;
+            }
+
+            notified.await;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove and return every queued job
+    pub fn drain(&self) -> Vec<CalculationJob> {
+        self.state.lock().heap.drain().map(|entry| entry.job).collect()
+    }
+}
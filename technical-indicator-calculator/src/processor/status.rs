@@ -0,0 +1,189 @@
+// Structured status of a running worker, published as a JSON file
+//
+// The worker rewrites the file every few seconds; `status` reads it instead of
+// inspecting the process, so it works the same on every platform.
+use crate::data_source::CandleCacheStats;
+use crate::processor::timings::{IndicatorTimings, TimingStats};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the running worker rewrites its status file
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Location of the status file (STATUS_FILE, or indicator-calculator.status.json in the temp dir)
+pub fn status_file_path() -> PathBuf {
+    match std::env::var("STATUS_FILE") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => std::env::temp_dir().join("indicator-calculator.status.json"),
+    }
+}
+
+/// Most recent job failure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastError {
+    pub message: String,
+    pub job: String,
+    /// Correlation ID of the failed job run
+    #[serde(default)]
+    pub job_id: Option<Uuid>,
+    pub at: DateTime<Utc>,
+}
+
+/// Snapshot of a worker as written to the status file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    pub pid: u32,
+    /// "running", "draining" or "stopped"
+    pub state: String,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+    /// Enabled indicator configurations seen in the last scheduling cycle
+    pub configs_enabled: u64,
+    pub jobs_completed: u64,
+    pub jobs_skipped: u64,
+    pub jobs_failed: u64,
+    /// Queued jobs per worker pool
+    pub queue_depth: BTreeMap<String, usize>,
+    pub last_error: Option<LastError>,
+    /// Calculation and insert timings per indicator and symbol, the most expensive first
+    #[serde(default)]
+    pub timings: Vec<IndicatorTimings>,
+    /// Shared candle cache counters; None when the cache is disabled
+    #[serde(default)]
+    pub candle_cache: Option<CandleCacheStats>,
+}
+
+impl WorkerStatus {
+    /// Whether the worker has updated the file recently enough to be considered alive
+    pub fn is_fresh(&self) -> bool {
+        let max_age = chrono::Duration::from_std(STATUS_INTERVAL * 3).unwrap_or_else(|_| chrono::Duration::seconds(15));
+        Utc::now() - self.updated_at <= max_age
+    }
+}
+
+/// Collects worker counters and writes them to the status file
+pub struct StatusReporter {
+    path: PathBuf,
+    started_at: DateTime<Utc>,
+    state: Mutex<&'static str>,
+    configs_enabled: AtomicU64,
+    completed: AtomicU64,
+    skipped: AtomicU64,
+    failed: AtomicU64,
+    last_error: Mutex<Option<LastError>>,
+    timings: Arc<TimingStats>,
+    candle_cache: Mutex<Option<CandleCacheStats>>,
+}
+
+impl StatusReporter {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            started_at: Utc::now(),
+            state: Mutex::new("running"),
+            configs_enabled: AtomicU64::new(0),
+            completed: AtomicU64::new(0),
+            skipped: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            timings: Arc::new(TimingStats::new()),
+            candle_cache: Mutex::new(None),
+        }
+    }
+
+    /// Collector for per-indicator calculation and insert timings
+    pub fn timings(&self) -> &Arc<TimingStats> {
+        &self.timings
+    }
+
+    // A stopped worker never reports itself as running again
+    pub fn set_state(&self, state: &'static str) {
+        let mut current = self.state.lock();
+        if *current != "stopped" {
+            *current = state;
+        }
+    }
+
+    pub fn set_configs_enabled(&self, count: usize) {
+        self.configs_enabled.store(count as u64, Ordering::Relaxed);
+    }
+
+    pub fn set_candle_cache(&self, stats: CandleCacheStats) {
+        *self.candle_cache.lock() = Some(stats);
+    }
+
+    pub fn job_completed(&self) {
+        self.completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn job_skipped(&self) {
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn job_failed(&self, job: &str, job_id: Uuid, message: &str) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock() = Some(LastError {
+            message: message.to_string(),
+            job: job.to_string(),
+            job_id: Some(job_id),
+            at: Utc::now(),
+        });
+    }
+
+    /// Take a snapshot of the current counters
+    pub fn snapshot(&self, queue_depth: BTreeMap<String, usize>) -> WorkerStatus {
+        let now = Utc::now();
+
+        WorkerStatus {
+            pid: std::process::id(),
+            state: self.state.lock().to_string(),
+            started_at: self.started_at,
+            updated_at: now,
+            uptime_seconds: (now - self.started_at).num_seconds(),
+            configs_enabled: self.configs_enabled.load(Ordering::Relaxed),
+            jobs_completed: self.completed.load(Ordering::Relaxed),
+            jobs_skipped: self.skipped.load(Ordering::Relaxed),
+            jobs_failed: self.failed.load(Ordering::Relaxed),
+            queue_depth,
+            last_error: self.last_error.lock().clone(),
+            timings: self.timings.snapshot(),
+            candle_cache: self.candle_cache.lock().clone(),
+        }
+    }
+
+    /// Write a snapshot, replacing the file atomically so readers never see partial JSON
+    pub fn write(&self, queue_depth: BTreeMap<String, usize>) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.snapshot(queue_depth))?;
+        let tmp_path = self.path.with_extension("json.tmp");
+
+        std::fs::write(&tmp_path, json)
+            .context(format!("Failed to write status file: {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &self.path)
+            .context(format!("Failed to replace status file: {}", self.path.display()))?;
+
+        Ok(())
+    }
+}
+
+/// Read the status file, returning None if no worker has written one
+pub fn read_status(path: &Path) -> Result<Option<WorkerStatus>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = std::fs::read_to_string(path)
+        .context(format!("Failed to read status file: {}", path.display()))?;
+    let status = serde_json::from_str(&json)
+        .context(format!("Invalid status file: {}", path.display()))?;
+
+    Ok(Some(status))
+}
@@ -0,0 +1,189 @@
+// Wall-time of indicator calculations and database inserts, aggregated per indicator and symbol
+//
+// The worker records every calculation and insert; the aggregates are published in the status
+// file for `status timings` and as Prometheus histograms on the metrics endpoint.
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// Upper bounds of the histogram buckets in seconds
+pub const TIMING_BUCKETS: [f64; 11] = [0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+/// Distribution of durations with Prometheus-style buckets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimingHistogram {
+    pub count: u64,
+    pub sum_seconds: f64,
+    pub max_seconds: f64,
+    /// Observations per bucket of TIMING_BUCKETS (not cumulative); the last entry counts overflows
+    pub buckets: Vec<u64>,
+}
+
+impl TimingHistogram {
+    pub fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if self.buckets.len() != TIMING_BUCKETS.len() + 1 {
+            self.buckets = vec![0; TIMING_BUCKETS.len() + 1];
+        }
+
+        let bucket = TIMING_BUCKETS.iter().position(|&bound| seconds <= bound).unwrap_or(TIMING_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_seconds += seconds;
+        self.max_seconds = self.max_seconds.max(seconds);
+    }
+
+    pub fn mean_seconds(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum_seconds / self.count as f64
+        }
+    }
+}
+
+/// Calculation and insert timings of one indicator on one symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorTimings {
+    pub indicator_name: String,
+    pub symbol: String,
+    pub calculation: TimingHistogram,
+    pub insert: TimingHistogram,
+    pub rows_inserted: u64,
+}
+
+impl IndicatorTimings {
+    /// Total wall-time spent on this indicator and symbol
+    pub fn total_seconds(&self) -> f64 {
+        self.calculation.sum_seconds + self.insert.sum_seconds
+    }
+}
+
+/// Collects timings from all worker pools
+#[derive(Default)]
+pub struct TimingStats {
+    entries: Mutex<HashMap<(String, String), IndicatorTimings>>,
+}
+
+impl TimingStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_calculation(&self, indicator_name: &str, symbol: &str, duration: Duration) {
+        self.with_entry(indicator_name, symbol, |entry| entry.calculation.observe(duration));
+    }
+
+    pub fn record_insert(&self, indicator_name: &str, symbol: &str, duration: Duration, rows: usize) {
+        self.with_entry(indicator_name, symbol, |entry| {
+            entry.insert.observe(duration);
+            entry.rows_inserted += rows as u64;
+        });
+    }
+
+    fn with_entry(&self, indicator_name: &str, symbol: &str, update: impl FnOnce(&mut IndicatorTimings)) {
+        let mut entries = self.entries.lock();
+        let entry = entries
+            .entry((indicator_name.to_string(), symbol.to_string()))
+            .or_insert_with(|| IndicatorTimings {
+                indicator_name: indicator_name.to_string(),
+                symbol: symbol.to_string(),
+                calculation: TimingHistogram::default(),
+                insert: TimingHistogram::default(),
+                rows_inserted: 0,
+            });
+        update(entry);
+    }
+
+    /// Current timings, the most expensive indicator first
+    pub fn snapshot(&self) -> Vec<IndicatorTimings> {
+        let mut timings: Vec<IndicatorTimings> = self.entries.lock().values().cloned().collect();
+        timings.sort_by(|a, b| b.total_seconds().total_cmp(&a.total_seconds()));
+        timings
+    }
+}
+
+/// Render timings in the Prometheus text exposition format
+pub fn render_prometheus(timings: &[IndicatorTimings]) -> String {
+    let mut out = String::new();
+
+    write_histogram(&mut out, "indicator_calculation_seconds", "Wall-time of indicator calculations",
+                    timings, |t| &t.calculation);
+    write_histogram(&mut out, "indicator_insert_seconds", "Wall-time of calculated indicator inserts",
+                    timings, |t| &t.insert);
+
+    let _ = writeln!(out, "# HELP indicator_rows_inserted_total Calculated indicator values written");
+    let _ = writeln!(out, "# TYPE indicator_rows_inserted_total counter");
+    for timing in timings {
+        let _ = writeln!(out, "indicator_rows_inserted_total{{{}}} {}", labels(timing), timing.rows_inserted);
+    }
+
+    out
+}
+
+fn write_histogram(
+    out: &mut String,
+    metric: &str,
+    help: &str,
+    timings: &[IndicatorTimings],
+    select: impl Fn(&IndicatorTimings) -> &TimingHistogram,
+) {
+    let _ = writeln!(out, "# HELP {} {}", metric, help);
+    let _ = writeln!(out, "# TYPE {} histogram", metric);
+
+    for timing in timings {
+        let histogram = select(timing);
+        let labels = labels(timing);
+
+        let mut cumulative = 0;
+        for (i, bound) in TIMING_BUCKETS.iter().enumerate() {
+            cumulative += histogram.buckets.get(i).copied().unwrap_or(0);
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", metric, labels, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{{},le=\"+Inf\"}} {}", metric, labels, histogram.count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", metric, labels, histogram.sum_seconds);
+        let _ = writeln!(out, "{}_count{{{}}} {}", metric, labels, histogram.count);
+    }
+}
+
+fn labels(timing: &IndicatorTimings) -> String {
+    format!("indicator=\"{}\",symbol=\"{}\"", timing.indicator_name, timing.symbol)
+}
+
+/// Serve the output of `render` as Prometheus metrics over plain HTTP on every path
+pub async fn serve_metrics<F>(addr: String, render: F) -> Result<()>
+where
+    F: Fn() -> String + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(&addr).await?;
+    info!("Serving Prometheus metrics on http://{}/metrics", addr);
+
+    let render = Arc::new(render);
+    loop {
+        let (mut socket, _) = listener.accept().await?;
+        let render = render.clone();
+
+        tokio::spawn(async move {
+            // The request itself is not inspected; read it so the client sees a clean response
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+
+            let body = render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(), body
+            );
+
+            if let Err(e) = socket.write_all(response.as_bytes()).await {
+                warn!("Failed to write metrics response: {}", e);
+            }
+        });
+    }
+}
@@ -1,28 +1,150 @@
 use crate::cache::completeness::{CompletenessCache, CompletenessInfo, SharedCompletenessCache};
 use crate::cache::completeness_controller::CompletenessController;
 use crate::cache::redis::RedisManager;
-use crate::database::models::{CalculatedIndicatorBatch, CandleData};
+use crate::data_source::{BinanceSource, CachedSource, DataSource, DatabaseSource};
+use crate::database::models::{BinanceCandle, CalculatedIndicatorBatch, CandleData, InvalidatedIndicatorConfig};
 use crate::database::postgres::PostgresManager;
+use crate::config::{NotificationSettings, ReevaluationSettings};
+use crate::database::retention::{apply_retention, RetentionPolicy};
+use crate::events::{self, EventBus, EventKind};
 use crate::indicators::calculator::IndicatorCalculator;
+use crate::indicators::chunked::window_lookback;
+use crate::indicators::{cross_symbol, pipeline};
+use crate::indicators::output::IndicatorOutput;
+use crate::processor::anomaly::{detect_anomalies, AnomalySettings, IndicatorAnomaly};
+use crate::processor::cluster::ClusterCoordinator;
 use crate::processor::job::{CalculationJob, IndicatorType};
-use crate::utils::log_utils::log_to_file;
+use crate::processor::quality::check_candle_quality;
+use crate::processor::queue::{JobQueue, SchedulingPolicy};
+use crate::processor::status::{status_file_path, StatusReporter, STATUS_INTERVAL};
+use crate::processor::timings::{render_prometheus, serve_metrics};
+use crate::signals::generator::SignalGenerator;
+use crate::signals::webhook::WebhookNotifier;
+use crate::strategy::reevaluation::Reevaluator;
+use crate::strategy::repository::StrategyRepository;
+use crate::utils::resources::ResourceTracker;
+use crate::utils::telemetry;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use rayon::prelude::*;
 use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::{mpsc, watch, Semaphore};
 use tokio::time::Instant;
 use tracing::{debug, error, info, instrument, warn};
 
+// Anomalies recorded per parameter set and job
+const MAX_RECORDED_ANOMALIES: usize = 100;
+
+// Concurrency settings for a group of intervals served by a dedicated pool
+#[derive(Debug, Clone)]
+pub struct IntervalPoolConfig {
+    pub name: String,
+    /// Intervals handled by this pool; an empty list catches every interval
+    pub intervals: Vec<String>,
+    pub concurrency: usize,
+}
+
+impl IntervalPoolConfig {
+    pub fn new(name: &str, intervals: &[&str], concurrency: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            intervals: intervals.iter().map(|i| i.to_string()).collect(),
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    // Check whether a job interval belongs to this pool
+    pub fn matches(&self, interval: &str) -> bool {
+        self.intervals.is_empty() || self.intervals.iter().any(|i| i == interval)
+    }
+
+    /// Parse a pool list such as `fast:1m,5m,15m:4;slow:1d,1w:1`
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>> {
+        let mut pools = Vec::new();
+
+        for entry in spec.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+            if parts.len() != 3 {
+                return Err(anyhow::anyhow!(
+                    "Invalid worker pool definition '{}', expected name:intervals:concurrency", entry
+                ));
+            }
+
+            let intervals: Vec<&str> = parts[1].split(',')
+                .map(str::trim)
+                .filter(|i| !i.is_empty())
+                .collect();
+            let concurrency = parts[2].parse::<usize>()
+                .map_err(|_| anyhow::anyhow!("Invalid concurrency for worker pool '{}': {}", parts[0], parts[2]))?;
+
+            pools.push(Self::new(parts[0], &intervals, concurrency));
+        }
+
+        Ok(pools)
+    }
+}
+
 // Worker configuration
 #[derive(Debug, Clone)]
 pub struct WorkerConfig {
     pub cache_ttl_seconds: u64,
     pub completeness_cache_minutes: i64,
     pub batch_size: usize,
+    /// Retries after a failed attempt before the job is recorded in failed_jobs
     pub retry_max: usize,
+    /// Delay before the first retry, doubled for every further retry
     pub retry_delay_ms: u64,
+    /// Dedicated pools per interval group; jobs not matched by any pool use the default pool
+    pub interval_pools: Vec<IntervalPoolConfig>,
+    /// Binance WebSocket base URL; when set, closed candles are streamed in and processed immediately
+    pub stream_url: Option<String>,
+    /// Largest share of missing candles a dataset may have before its jobs are skipped
+    pub max_gap_ratio: f64,
+    /// Candles loaded per chunk for window-based indicators; 0 loads the whole history at once
+    pub candle_chunk_size: usize,
+    /// Order of queued jobs with equal priority
+    pub scheduling_policy: SchedulingPolicy,
+    /// Job lease length when sharing the queue with other instances through Redis; None runs standalone
+    pub cluster_lease_seconds: Option<u64>,
+    /// Expiry and downsampling of calculated indicator values
+    pub retention_policies: Vec<RetentionPolicy>,
+    /// Time between retention runs
+    pub retention_check_hours: u64,
+    /// Days recorded events are kept; 0 keeps them forever
+    pub event_keep_days: u64,
+    /// Periodic backtests of enabled strategies; None disables them
+    pub reevaluation: Option<ReevaluationSettings>,
+    /// Webhook notified when a re-evaluated strategy degrades or recovers
+    pub notify_webhook_url: Option<String>,
+    /// Live signal generation every `signal_poll_seconds`; None disables it
+    pub signal_generation: Option<NotificationSettings>,
+    /// Address to serve Prometheus metrics on (e.g. "0.0.0.0:9184"); None disables the endpoint
+    pub metrics_addr: Option<String>,
+    /// Calculate all parameter sets of an indicator on a symbol/interval in one job
+    pub batch_variants: bool,
+    /// Candles held in memory and shared between jobs on the same symbol/interval; 0 disables the cache
+    pub candle_cache_max_candles: u64,
+    /// Thresholds of the checks on calculated values
+    pub anomaly: AnomalySettings,
+    /// Post suspicious calculated values to `notify_webhook_url`
+    pub notify_anomalies: bool,
+}
+
+impl WorkerConfig {
+    // Resolve the pools to run, appending a catch-all pool when none is configured
+    pub fn pool_layout(&self, default_concurrency: usize) -> Vec<IntervalPoolConfig> {
+        let mut pools = self.interval_pools.clone();
+
+        if !pools.iter().any(|p| p.intervals.is_empty()) {
+            pools.push(IntervalPoolConfig::new("default", &[], default_concurrency));
+        }
+
+        pools
+    }
 }
 
 impl Default for WorkerConfig {
@@ -33,6 +155,23 @@ impl Default for WorkerConfig {
             batch_size: 1000,                  // Number of indicators to batch insert
             retry_max: 3,                      // Maximum retries
             retry_delay_ms: 500,               // Delay between retries
+            interval_pools: Vec::new(),        // Single shared pool
+            stream_url: None,                  // Polling only
+            max_gap_ratio: 0.05,               // Up to 5% missing candles
+            candle_chunk_size: 100_000,        // Candles per chunk
+            scheduling_policy: SchedulingPolicy::StalenessFirst,
+            cluster_lease_seconds: None,       // Standalone
+            retention_policies: Vec::new(),    // Keep everything
+            retention_check_hours: 24,         // Daily retention runs
+            event_keep_days: 90,               // Three months of event history
+            reevaluation: None,                // No strategy re-evaluation
+            notify_webhook_url: None,          // Log only
+            signal_generation: None,           // No live signals
+            metrics_addr: None,                // No metrics endpoint
+            batch_variants: true,              // One candle load per indicator
+            candle_cache_max_candles: 0,       // Every job reads its own candles
+            anomaly: AnomalySettings::default(),
+            notify_anomalies: false,           // Log and record only
         }
     }
 }
@@ -42,8 +181,17 @@ impl Default for WorkerConfig {
 pub struct Worker {
     pg: Arc<PostgresManager>,
     redis: Arc<RedisManager>,
+    candles: Arc<dyn DataSource>,
+    // Shared candle histories, also read through `candles`; None when disabled
+    candle_cache: Option<Arc<CachedSource>>,
+    cluster: Option<Arc<ClusterCoordinator>>,
+    // Set to true once SIGTERM/SIGINT is received
+    shutdown_tx: Arc<watch::Sender<bool>>,
+    shutdown: watch::Receiver<bool>,
+    status: Arc<StatusReporter>,
     completeness_cache: SharedCompletenessCache,
     completeness_controller: CompletenessController,
+    events: EventBus,
     config: WorkerConfig,
     concurrency_limit: usize,
 }
@@ -64,11 +212,33 @@ impl Worker {
             pg.clone(),
         );
         
+        // Historical candles are read back from the database, through the shared cache when enabled
+        let candle_cache = (config.candle_cache_max_candles > 0)
+            .then(|| Arc::new(CachedSource::new(pg.clone(), config.candle_cache_max_candles)));
+        let candles: Arc<dyn DataSource> = match &candle_cache {
+            Some(cache) => cache.clone(),
+            None => Arc::new(DatabaseSource::new(pg.clone())),
+        };
+        
+        // Share the job queue with other instances when running in a cluster
+        let cluster = config.cluster_lease_seconds
+            .map(|secs| Arc::new(ClusterCoordinator::new(redis.clone(), Duration::from_secs(secs))));
+        
+        let (shutdown_tx, shutdown) = watch::channel(false);
+        let events = EventBus::new(pg.clone());
+        
         Self {
             pg,
             redis,
+            candles,
+            candle_cache,
+            cluster,
+            shutdown_tx: Arc::new(shutdown_tx),
+            shutdown,
+            status: Arc::new(StatusReporter::new(status_file_path())),
             completeness_cache,
             completeness_controller,
+            events,
             config,
             concurrency_limit,
         }
@@ -83,26 +253,289 @@ impl Worker {
             error!("Failed to initialize completeness cache: {}", e);
         }
         
-        // Set up channels
-        let (job_tx, job_rx) = mpsc::channel(1000);
+        if let Some(cluster) = &self.cluster {
+            tokio::spawn(cluster.clone().run_heartbeat());
+        }
+        
+        // Stop taking new work on SIGTERM/SIGINT
+        tokio::spawn(Self::listen_for_shutdown(self.shutdown_tx.clone()));
+        
+        // Set up one job queue and consumer per interval pool
+        let mut pool_senders = Vec::new();
+        let mut consumers = Vec::new();
+        
+        for pool in self.config.pool_layout(self.concurrency_limit) {
+            info!("Starting worker pool '{}' for intervals {:?} with concurrency {}", 
+                  pool.name, pool.intervals, pool.concurrency);
+            
+            let queue = Arc::new(JobQueue::new(self.config.scheduling_policy, 1000));
+            
+            // Each pool has its own semaphore so heavy intervals cannot starve fast ones
+            let semaphore = Arc::new(Semaphore::new(pool.concurrency));
+            
+            consumers.push(tokio::spawn(self.clone().job_consumer(pool.name.clone(), pool.concurrency, queue.clone(), semaphore)));
+            pool_senders.push((pool, queue));
+        }
+        
+        // Stream closed candles and enqueue their jobs right away
+        if let Some(stream_url) = self.config.stream_url.clone() {
+            let (closed_tx, closed_rx) = mpsc::channel(1000);
+            
+            let pairs = self.pg.get_unique_symbol_intervals().await?;
+            let source = BinanceSource::new("https://api.binance.com", &stream_url);
+            tokio::spawn(async move {
+                if let Err(e) = source.subscribe(pairs, closed_tx).await {
+                    error!("Kline streaming stopped: {}", e);
+                }
+            });
+            
+            tokio::spawn(self.clone().stream_job_producer(closed_rx, pool_senders.clone()));
+        }
+        
+        // Publish structured status for the `status` command
+        tokio::spawn(self.clone().status_writer(pool_senders.clone()));
+        
+        // Expose calculation timings and candle cache counters to Prometheus
+        if let Some(addr) = self.config.metrics_addr.clone() {
+            let timings = self.status.timings().clone();
+            let candle_cache = self.candle_cache.clone();
+            let render = move || {
+                let mut metrics = render_prometheus(&timings.snapshot());
+                if let Some(cache) = &candle_cache {
+                    metrics.push_str(&cache.stats().render_prometheus());
+                }
+                metrics
+            };
+            
+            tokio::spawn(async move {
+                if let Err(e) = serve_metrics(addr, render).await {
+                    error!("Metrics endpoint stopped: {}", e);
+                }
+            });
+        }
+        
+        let pools: Vec<Value> = self.config.pool_layout(self.concurrency_limit).iter()
+            .map(|pool| json!({"name": pool.name, "intervals": pool.intervals, "concurrency": pool.concurrency}))
+            .collect();
+        self.events.emit(
+            EventKind::WorkerStarted,
+            None,
+            &format!("Worker started with {} pools", pools.len()),
+            json!({"pools": pools, "cluster": self.cluster.is_some()}),
+        ).await;
+        
+        // Apply indicator retention policies and prune old events periodically
+        if !self.config.retention_policies.is_empty() || self.config.event_keep_days > 0 {
+            tokio::spawn(self.clone().retention_task());
+        }
+        
+        // Re-run backtests of enabled strategies periodically
+        if let Some(settings) = self.config.reevaluation.clone() {
+            tokio::spawn(self.clone().reevaluation_task(settings));
+        }
+        
+        // Evaluate enabled strategies on their last closed candle and deliver new signals
+        if let Some(settings) = self.config.signal_generation.clone() {
+            tokio::spawn(self.clone().signal_task(settings));
+        }
         
         // Spawn job producer
-        tokio::spawn(self.clone().job_producer(job_tx));
+        tokio::spawn(self.clone().job_producer(pool_senders));
         
-        // Create a semaphore to limit concurrent processing
-        let semaphore = Arc::new(Semaphore::new(self.concurrency_limit));
+        // Wait for the consumers (they run until shutdown and then drain their pools)
+        for consumer in consumers {
+            consumer.await??;
+        }
+        
+        if let Some(cluster) = &self.cluster {
+            if let Err(e) = cluster.leave().await {
+                warn!("Failed to leave worker cluster: {}", e);
+            }
+        }
         
-        // Process jobs in the main thread
-        self.job_consumer(0, job_rx, semaphore).await?;
+        self.status.set_state("stopped");
+        if let Err(e) = self.status.write(Default::default()) {
+            warn!("Failed to write status file: {}", e);
+        }
         
         info!("Technical Indicator Calculator shutting down");
+        self.events.emit(EventKind::WorkerStopped, None, "Worker shut down cleanly", json!({})).await;
         Ok(())
     }
     
-    #[instrument(skip(self, job_tx))]
-    async fn job_producer(self, job_tx: mpsc::Sender<CalculationJob>) -> Result<()> {
+    // Wait for SIGTERM or SIGINT and signal every task to stop
+    async fn listen_for_shutdown(shutdown_tx: Arc<watch::Sender<bool>>) {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            
+            let mut sigterm = match signal(SignalKind::terminate()) {
+                Ok(sigterm) => sigterm,
+                Err(e) => {
+                    error!("Failed to install SIGTERM handler: {}", e);
+                    return;
+                }
+            };
+            
+            tokio::select! {
+                _ = sigterm.recv() => info!("Received SIGTERM"),
+                _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+            }
+        }
+        
+        #[cfg(not(unix))]
+        {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                info!("Received Ctrl-C");
+            }
+        }
+        
+        info!("Shutting down: no new jobs will be started, waiting for running jobs to finish");
+        let _ = shutdown_tx.send(true);
+    }
+    
+    // Rewrite the status file periodically until the worker stops
+    async fn status_writer(self, pool_senders: Vec<(IntervalPoolConfig, Arc<JobQueue>)>) {
+        loop {
+            if self.is_shutting_down() {
+                self.status.set_state("draining");
+            }
+            
+            if let Some(cache) = &self.candle_cache {
+                self.status.set_candle_cache(cache.stats());
+            }
+            
+            let queue_depth = pool_senders.iter()
+                .map(|(pool, queue)| (pool.name.clone(), queue.len()))
+                .collect();
+            
+            if let Err(e) = self.status.write(queue_depth) {
+                warn!("Failed to write status file: {}", e);
+            }
+            
+            tokio::time::sleep(STATUS_INTERVAL).await;
+        }
+    }
+    
+    // Drop and downsample expired indicator values and events until the worker stops
+    async fn retention_task(self) {
+        let period = Duration::from_secs(self.config.retention_check_hours.max(1) * 3600);
+        
+        loop {
+            if !self.config.retention_policies.is_empty() {
+                match apply_retention(&self.pg, &self.config.retention_policies, false).await {
+                    Ok(outcomes) => {
+                        let deleted: u64 = outcomes.iter().map(|o| o.deleted).sum();
+                        self.events.emit(
+                            EventKind::Maintenance,
+                            Some("retention"),
+                            &format!("Retention removed {} expired indicator values", deleted),
+                            json!({"deleted": deleted}),
+                        ).await;
+                    },
+                    Err(e) => {
+                        error!("Failed to apply retention policies: {}", e);
+                        self.events.emit(
+                            EventKind::Maintenance,
+                            Some("retention"),
+                            &format!("Failed to apply retention policies: {}", e),
+                            json!({"error": e.to_string()}),
+                        ).await;
+                    }
+                }
+            }
+            
+            if self.config.event_keep_days > 0 {
+                match events::prune_events(&self.pg, self.config.event_keep_days).await {
+                    Ok(deleted) => debug!("Pruned {} events older than {} days", deleted, self.config.event_keep_days),
+                    Err(e) => warn!("Failed to prune events: {}", e),
+                }
+            }
+            
+            tokio::select! {
+                _ = tokio::time::sleep(period) => {},
+                _ = self.shutdown_requested() => break,
+            }
+        }
+    }
+    
+    // Backtest enabled strategies over a rolling window until the worker stops
+    async fn reevaluation_task(self, settings: ReevaluationSettings) {
+        let period = Duration::from_secs(settings.interval_hours.max(1) * 3600);
+        let repository = StrategyRepository::new(self.pg.clone());
+        let notifier = self.config.notify_webhook_url.as_deref()
+            .filter(|url| !url.is_empty())
+            .map(WebhookNotifier::new);
+        let reevaluator = Reevaluator::new(&repository, settings, notifier);
+        
+        loop {
+            match reevaluator.run_all().await {
+                Ok(results) => {
+                    let degraded = results.iter().filter(|r| r.degraded).count();
+                    self.events.emit(
+                        EventKind::Maintenance,
+                        Some("reevaluation"),
+                        &format!("Re-evaluated {} strategy windows, {} degraded", results.len(), degraded),
+                        json!({"windows": results.len(), "degraded": degraded}),
+                    ).await;
+                },
+                Err(e) => {
+                    error!("Failed to re-evaluate strategies: {}", e);
+                    self.events.emit(
+                        EventKind::Maintenance,
+                        Some("reevaluation"),
+                        &format!("Failed to re-evaluate strategies: {}", e),
+                        json!({"error": e.to_string()}),
+                    ).await;
+                }
+            }
+            
+            tokio::select! {
+                _ = tokio::time::sleep(period) => {},
+                _ = self.shutdown_requested() => break,
+            }
+        }
+    }
+    
+    // Deliver signals of enabled strategies every signal_poll_seconds until the worker stops
+    async fn signal_task(self, settings: NotificationSettings) {
+        let period = Duration::from_secs(settings.signal_poll_seconds.max(1));
+        let generator = SignalGenerator::from_config(StrategyRepository::new(self.pg.clone()), &settings);
+        if let Err(e) = generator.ledger().init_table().await {
+            error!("Failed to create the signal ledger, signal generation disabled: {}", e);
+            return;
+        }
+        
+        loop {
+            if let Err(e) = generator.run_all().await {
+                error!("Failed to generate signals: {}", e);
+            }
+            match generator.ledger().prune().await {
+                Ok(deleted) => debug!("Pruned {} signals outside the dedup window", deleted),
+                Err(e) => warn!("Failed to prune the signal ledger: {}", e),
+            }
+            
+            tokio::select! {
+                _ = tokio::time::sleep(period) => {},
+                _ = self.shutdown_requested() => break,
+            }
+        }
+    }
+    
+    // Whether shutdown has been requested
+    fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+    
+    // Resolves once shutdown has been requested
+    async fn shutdown_requested(&self) {
+        let mut shutdown = self.shutdown.clone();
+        let _ = shutdown.wait_for(|stop| *stop).await;
+    }
+    
+    #[instrument(skip(self, pool_senders))]
+    async fn job_producer(self, pool_senders: Vec<(IntervalPoolConfig, Arc<JobQueue>)>) -> Result<()> {
         info!("Started job producer");
-        let _ = log_to_file("Started job producer").await;
         
         // Track when we last initialized the completeness cache
         let mut last_cache_refresh = Instant::now();
@@ -119,6 +552,16 @@ impl Worker {
                 last_cache_refresh = Instant::now();
             }
             
+            // Values calculated before a configuration's parameters were edited are dropped
+            match self.pg.invalidate_changed_indicator_configs().await {
+                Ok(invalidated) => {
+                    for stale in invalidated {
+                        self.forget_invalidated_config(stale).await;
+                    }
+                },
+                Err(e) => error!("Failed to check indicator configurations for parameter changes: {}", e),
+            }
+            
             // Get all enabled indicator configurations
             let configs = match self.pg.get_enabled_indicator_configs().await {
                 Ok(configs) => configs,
@@ -129,20 +572,25 @@ impl Worker {
                 }
             };
             
+            // Dead-lettered jobs stay out of the queue until requeued
+            let failed_keys = match self.pg.get_failed_job_keys().await {
+                Ok(keys) => keys,
+                Err(e) => {
+                    error!("Failed to get failed jobs: {}", e);
+                    HashSet::new()
+                }
+            };
+            
             info!("Found {} enabled indicator configurations", configs.len());
-            let _ = log_to_file(&format!("Found {} enabled indicator configurations", configs.len())).await;
+            self.status.set_configs_enabled(configs.len());
             
-            // Process each configuration
-            for config in configs {
-                let indicator_type = IndicatorType::from(config.indicator_type.as_str());
-                
-                let job = CalculationJob::new(
-                    config.symbol,
-                    config.interval,
-                    indicator_type,
-                    config.indicator_name,
-                    config.parameters,
-                );
+            // Process each configuration, after the configurations it takes input from
+            let mut pending = Vec::new();
+            for (job, priority) in CalculationJob::for_configs(configs) {
+                if failed_keys.contains(&job.cache_key()) {
+                    debug!("Skipping failed job: {}", job.cache_key());
+                    continue;
+                }
                 
                 // Check if job is already complete according to our cache
                 if self.completeness_controller.is_job_complete(&job) {
@@ -151,211 +599,819 @@ impl Worker {
                     continue;
                 }
                 
-                // Check if job is already in cache (being processed)
-                let job_key = job.cache_key();
-                if let Ok(exists) = self.redis.exists(&job_key).await {
-                    if exists {
-                        debug!("Job already in progress, skipping: {}", job_key);
-                        continue;
+                pending.push((job, priority));
+            }
+            
+            for (job, priority) in self.group_variants(pending) {
+                if self.is_shutting_down() {
+                    break;
+                }
+                
+                self.dispatch_job(&pool_senders, job, priority).await;
+            }
+            
+            // Sleep for a while before checking for new configurations
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(60)) => {},
+                _ = self.shutdown_requested() => {
+                    info!("Job producer stopped");
+                    return Ok(());
+                },
+            }
+        }
+    }
+    
+    // Update the completeness cache with the values stored for a job
+    async fn refresh_completeness(&self, job: &CalculationJob) {
+        if let Ok((last_time, count)) = self.pg.get_indicator_completeness(
+            &job.symbol,
+            &job.interval,
+            &job.indicator_name,
+            &job.parameters,
+        ).await {
+            // Get candle data range
+            if let Ok((first_candle, last_candle)) = self.pg.get_candle_data_range(
+                &job.symbol,
+                &job.interval,
+            ).await {
+                // Create updated completeness info
+                let mut info = CompletenessInfo::from_job(job);
+                info.last_calculated_time = last_time;
+                info.first_candle_time = Some(first_candle);
+                info.last_candle_time = Some(last_candle);
+                info.data_count = count;
+                
+                // Calculate coverage percentage
+                if let Some(last_calc) = last_time {
+                    let candle_span = last_candle.signed_duration_since(first_candle).num_seconds();
+                    if candle_span > 0 {
+                        let calc_span = last_calc.signed_duration_since(first_candle).num_seconds();
+                        let coverage = (calc_span as f64 / candle_span as f64) * 100.0;
+                        info.coverage_percent = coverage.min(100.0) as i32;
+                        
+                        // Determine if complete
+                        let freshness = last_candle.signed_duration_since(last_calc).num_hours();
+                        info.is_complete = freshness <= 24 && info.coverage_percent >= 95;
                     }
                 }
                 
-                // Send job to workers
-                if let Err(e) = job_tx.send(job.clone()).await {
-                    error!("Failed to send job to workers: {}", e);
+                // Update cache
+                self.completeness_cache.update(info);
+            }
+        }
+    }
+    
+    // Merge jobs for different parameter sets of the same indicator on the same symbol/interval
+    // into one job that loads the candles once; the merged job runs at the highest priority
+    fn group_variants(&self, jobs: Vec<(CalculationJob, i32)>) -> Vec<(CalculationJob, i32)> {
+        if !self.config.batch_variants {
+            return jobs;
+        }
+        
+        let mut groups: Vec<(CalculationJob, i32, Vec<serde_json::Value>)> = Vec::new();
+        for (job, priority) in jobs {
+            // Pipeline jobs have their own inputs and are calculated alone
+            let group = groups.iter_mut().find(|(lead, _, _)| {
+                lead.symbol == job.symbol && lead.interval == job.interval && lead.indicator_name == job.indicator_name
+                    && lead.inputs.is_empty() && job.inputs.is_empty()
+            });
+            
+            match group {
+                Some((_, group_priority, parameters)) => {
+                    *group_priority = (*group_priority).max(priority);
+                    parameters.push(job.parameters);
+                },
+                None => {
+                    let parameters = vec![job.parameters.clone()];
+                    groups.push((job, priority, parameters));
+                },
+            }
+        }
+        
+        groups.into_iter()
+            .map(|(mut job, priority, mut parameters)| {
+                // A stable lead keeps the job key, and with it the in-progress marker, the same every cycle
+                parameters.sort_by_key(|p| p.to_string());
+                job.parameters = parameters.remove(0);
+                job.variants = parameters;
+                (job, priority)
+            })
+            .collect()
+    }
+    
+    // Drop cached state for an edited configuration so its new parameters are calculated from scratch
+    async fn forget_invalidated_config(&self, stale: InvalidatedIndicatorConfig) {
+        let config = stale.config;
+        info!("Parameters of {}:{}:{} changed from {} to {}, deleted {} stale values", 
+              config.symbol, config.interval, config.indicator_name, 
+              stale.previous_parameters, config.parameters, stale.deleted_rows);
+        self.events.emit(
+            EventKind::ConfigChanged,
+            Some(&format!("{}:{}:{}", config.symbol, config.interval, config.indicator_name)),
+            &format!("Parameters changed from {} to {}, deleted {} stale values", 
+                     stale.previous_parameters, config.parameters, stale.deleted_rows),
+            json!({
+                "config_id": config.id,
+                "previous_parameters": stale.previous_parameters,
+                "parameters": config.parameters,
+                "deleted_rows": stale.deleted_rows,
+            }),
+        ).await;
+        
+        let indicator_type = IndicatorType::from(config.indicator_type.as_str());
+        let previous = CalculationJob::new(
+            config.symbol.clone(),
+            config.interval.clone(),
+            indicator_type.clone(),
+            config.indicator_name.clone(),
+            stale.previous_parameters,
+        );
+        let current = CalculationJob::new(
+            config.symbol,
+            config.interval,
+            indicator_type,
+            config.indicator_name,
+            config.parameters,
+        );
+        
+        self.completeness_cache.remove(&previous);
+        self.completeness_cache.remove(&current);
+        
+        if let Err(e) = self.redis.delete(&previous.cache_key()).await {
+            warn!("Failed to remove stale job from cache: {}", e);
+        }
+    }
+    
+    // Route a job to the queue of the pool serving its interval and mark it as in progress.
+    // Returns false when the job was skipped.
+    #[instrument(parent = None, skip_all, fields(job_id = %job.id))]
+    async fn dispatch_job(&self, pool_senders: &[(IntervalPoolConfig, Arc<JobQueue>)], job: CalculationJob, priority: i32) -> bool {
+        // Check if job is already in cache (being processed)
+        let job_key = job.cache_key();
+        let job_id = job.id;
+        if let Ok(exists) = self.redis.exists(&job_key).await {
+            if exists {
+                debug!("Job already in progress, skipping: {}", job_key);
+                return false;
+            }
+        }
+        
+        // Route the job to the pool serving its interval
+        let (pool, queue) = match pool_senders.iter().find(|(pool, _)| pool.matches(&job.interval)) {
+            Some(entry) => entry,
+            None => {
+                warn!("No worker pool configured for interval {}, skipping job", job.interval);
+                return false;
+            }
+        };
+        
+        // Jobs that already have values are incremental updates and run before backfills
+        let last_calculated = self.completeness_cache.get(&job)
+            .and_then(|info| info.last_calculated_time);
+        
+        if let Some(cluster) = &self.cluster {
+            // The shared queue skips jobs that are already queued or leased by any instance
+            match cluster.enqueue(&pool.name, &job, priority).await {
+                Ok(true) => {},
+                Ok(false) => {
+                    debug!("Job already queued in the cluster, skipping: {}", job_key);
+                    return false;
+                },
+                Err(e) => {
+                    error!("Failed to queue job in the cluster: {}", e);
+                    return false;
+                }
+            }
+        } else if queue.try_push(job, priority, last_calculated).is_err() {
+            // Never block on a busy pool; the job is picked up again on the next cycle
+            debug!("Worker pool '{}' queue is full, deferring job: {}", pool.name, job_key);
+            return false;
+        }
+        
+        // Add job to cache to prevent duplicate processing
+        if let Err(e) = self.redis
+            .set(
+                &job_key,
+                &json!({"status": "processing", "job_id": job_id, "pool": pool.name, "queued_at": Utc::now()}),
+                Some(Duration::from_secs(600)), // 10 minute TTL
+            )
+            .await
+        {
+            warn!("Failed to cache job status: {}", e);
+        }
+        
+        debug!("Queued job {} in pool '{}': {}", job_id, pool.name, job_key);
+        true
+    }
+    
+    // Enqueue jobs for every enabled indicator of a symbol/interval as soon as a candle closes
+    #[instrument(skip(self, closed_rx, pool_senders))]
+    async fn stream_job_producer(
+        self,
+        mut closed_rx: mpsc::Receiver<BinanceCandle>,
+        pool_senders: Vec<(IntervalPoolConfig, Arc<JobQueue>)>,
+    ) -> Result<()> {
+        info!("Started streaming job producer");
+        
+        loop {
+            let candle = tokio::select! {
+                candle = closed_rx.recv() => match candle {
+                    Some(candle) => candle,
+                    None => break,
+                },
+                _ = self.shutdown_requested() => {
+                    info!("Streaming job producer stopped");
+                    break;
+                },
+            };
+            
+            // Persist the closed candle before anything reads it back
+            if let Err(e) = self.pg.upsert_candle(&candle).await {
+                error!("Failed to store streamed candle {}:{}: {}", candle.symbol, candle.interval, e);
+                continue;
+            }
+            
+            let configs = match self.pg.get_enabled_indicator_configs().await {
+                Ok(configs) => configs,
+                Err(e) => {
+                    error!("Failed to get indicator configurations: {}", e);
+                    continue;
+                }
+            };
+            
+            let failed_keys = self.pg.get_failed_job_keys().await.unwrap_or_default();
+            
+            // Pipeline inputs are on the same symbol and interval
+            let configs: Vec<_> = configs.into_iter()
+                .filter(|config| config.symbol == candle.symbol && config.interval == candle.interval)
+                .collect();
+            
+            let mut pending = Vec::new();
+            // New data makes the job incomplete regardless of the completeness cache
+            for (job, priority) in CalculationJob::for_configs(configs) {
+                if failed_keys.contains(&job.cache_key()) {
+                    continue;
                 }
                 
-                // Add job to cache to prevent duplicate processing
-                if let Err(e) = self.redis
-                    .set(
-                        &job_key,
-                        &json!({"status": "processing", "queued_at": Utc::now()}),
-                        Some(Duration::from_secs(600)), // 10 minute TTL
-                    )
-                    .await
-                {
-                    warn!("Failed to cache job status: {}", e);
+                pending.push((job, priority));
+            }
+            
+            let mut queued = 0;
+            for (job, priority) in self.group_variants(pending) {
+                if self.dispatch_job(&pool_senders, job, priority).await {
+                    queued += 1;
                 }
             }
             
-            // Sleep for a while before checking for new configurations
-            tokio::time::sleep(Duration::from_secs(60)).await;
+            debug!("Candle {}:{} closed at {}, queued {} jobs", 
+                   candle.symbol, candle.interval, candle.open_time, queued);
         }
+        
+        Ok(())
     }
     
-    #[instrument(skip(self, job_rx, semaphore), fields(worker_id = %worker_id))]
+    #[instrument(skip(self, queue, semaphore), fields(pool = %pool_name))]
     async fn job_consumer(
         self,
-        worker_id: usize,
-        mut job_rx: mpsc::Receiver<CalculationJob>,
+        pool_name: String,
+        concurrency: usize,
+        queue: Arc<JobQueue>,
         semaphore: Arc<Semaphore>,
     ) -> Result<()> {
-        info!("Started worker {}", worker_id);
-        let _ = log_to_file(&format!("Started worker {}", worker_id)).await;
-        
-        while let Some(job) = job_rx.recv().await {
-            // Acquire permit from semaphore
-            let _permit = semaphore.acquire().await?;
-            
-            info!("Worker {} processing job: {}:{}:{}", 
-                  worker_id, job.symbol, job.interval, job.indicator_name);
-            let _ = log_to_file(&format!("Worker {} processing job: {}:{}:{} with parameters: {:?}", 
-                worker_id, job.symbol, job.interval, job.indicator_name, job.parameters)).await;
-            
-            // Process the job
-            match self.process_job(&job).await {
-                Ok(success) => {
-                    if success {
-                        // Update the completeness cache with new information
-                        if let Ok((last_time, count)) = self.pg.get_indicator_completeness(
-                            &job.symbol,
-                            &job.interval,
-                            &job.indicator_name,
-                            &job.parameters,
-                        ).await {
-                            // Get candle data range
-                            if let Ok((first_candle, last_candle)) = self.pg.get_candle_data_range(
-                                &job.symbol,
-                                &job.interval,
-                            ).await {
-                                // Create updated completeness info
-                                let mut info = CompletenessInfo::from_job(&job);
-                                info.last_calculated_time = last_time;
-                                info.first_candle_time = Some(first_candle);
-                                info.last_candle_time = Some(last_candle);
-                                info.data_count = count;
-                                
-                                // Calculate coverage percentage
-                                if let Some(last_calc) = last_time {
-                                    let candle_span = last_candle.signed_duration_since(first_candle).num_seconds();
-                                    if candle_span > 0 {
-                                        let calc_span = last_calc.signed_duration_since(first_candle).num_seconds();
-                                        let coverage = (calc_span as f64 / candle_span as f64) * 100.0;
-                                        info.coverage_percent = coverage.min(100.0) as i32;
-                                        
-                                        // Determine if complete
-                                        let freshness = last_candle.signed_duration_since(last_calc).num_hours();
-                                        info.is_complete = freshness <= 24 && info.coverage_percent >= 95;
-                                    }
-                                }
-                                
-                                // Update cache
-                                self.completeness_cache.update(info);
-                            }
-                        }
+        info!("Started worker pool {}", pool_name);
+        
+        loop {
+            // Acquire permit from the pool semaphore before taking a job, so the
+            // most urgent job is chosen only once a worker is free
+            let next = async {
+                let permit = semaphore.clone().acquire_owned().await?;
+                let job = match &self.cluster {
+                    Some(cluster) => cluster.claim_next(&pool_name).await,
+                    None => queue.pop().await,
+                };
+                Ok::<_, anyhow::Error>((permit, job))
+            };
+            
+            let (permit, job) = tokio::select! {
+                next = next => next?,
+                _ = self.shutdown_requested() => break,
+            };
+            
+            let worker = self.clone();
+            let pool_name = pool_name.clone();
+            tokio::spawn(async move {
+                worker.handle_job(&pool_name, job).await;
+                drop(permit);
+            });
+        }
+        
+        // Release jobs that were queued but never started so other runs can pick them up
+        for job in queue.drain() {
+            if let Err(e) = self.redis.delete(&job.cache_key()).await {
+                warn!("Failed to release queued job {}: {}", job.cache_key(), e);
+            }
+        }
+        
+        // Wait for the running jobs by taking every permit of the pool
+        info!("Worker pool {} waiting for running jobs", pool_name);
+        let _permits = semaphore.acquire_many(concurrency as u32).await?;
+        
+        info!("Worker pool {} stopped", pool_name);
+        Ok(())
+    }
+    
+    /// Process a single job taken from the queue of `pool_name`, record its outcome and
+    /// remove its in-progress key from Redis
+    ///
+    /// Every job run is the root of its own trace, found again through its job_id.
+    #[instrument(parent = None, skip_all, fields(pool = %pool_name, job_id = %job.id))]
+    pub async fn handle_job(&self, pool_name: &str, job: CalculationJob) {
+        info!("Pool {} processing job: {}:{}:{}", 
+              pool_name, job.symbol, job.interval, job.indicator_name);
+        let subject = format!("{}:{}:{}", job.symbol, job.interval, job.indicator_name);
+        self.events.emit(
+            EventKind::JobStarted,
+            Some(&subject),
+            &format!("Pool {} started job", pool_name),
+            json!({"job_id": job.id, "pool": pool_name, "parameters": job.parameters, "variants": job.variants.len()}),
+        ).await;
+        
+        // Process the job, tracking its resource usage
+        let started_at = Utc::now();
+        let tracker = ResourceTracker::start();
+        let (result, attempts) = self.process_job_with_retry(&job, &tracker).await;
+        let usage = tracker.finish();
+        
+        let (status, error) = match &result {
+            Ok(true) => ("success", None),
+            Ok(false) => ("skipped", None),
+            Err(e) => ("failed", Some(e.to_string())),
+        };
+        
+        match &result {
+            Ok(true) => self.status.job_completed(),
+            Ok(false) => self.status.job_skipped(),
+            Err(e) => self.status.job_failed(&job.cache_key(), job.id, &e.to_string()),
+        }
+        
+        info!("Pool {} finished job {}:{}:{} ({}): {}", 
+              pool_name, job.symbol, job.interval, job.indicator_name, status, usage.summary());
+        telemetry::record_job(pool_name, &job.indicator_name, status, usage.wall_time_ms, usage.rows_written);
+        
+        let (kind, message) = match &error {
+            None => (EventKind::JobFinished, format!("Job {} after {} attempts: {}", status, attempts, usage.summary())),
+            Some(e) => (EventKind::JobFailed, format!("Job failed after {} attempts: {}", attempts, e)),
+        };
+        self.events.emit(
+            kind,
+            Some(&subject),
+            &message,
+            json!({
+                "job_id": job.id,
+                "pool": pool_name,
+                "parameters": job.parameters,
+                "status": status,
+                "attempts": attempts,
+                "error": error,
+                "usage": usage,
+            }),
+        ).await;
+        
+        if let Err(e) = self.pg.insert_job_history(
+            job.id,
+            pool_name,
+            &job.symbol,
+            &job.interval,
+            &job.indicator_name,
+            &job.parameters,
+            status,
+            error.as_deref(),
+            started_at,
+            &usage,
+        ).await {
+            warn!("Failed to record job history: {}", e);
+        }
+        
+        // Release the lease so the job can be queued again
+        if let Some(cluster) = &self.cluster {
+            if let Err(e) = cluster.complete(&job, result.is_ok()).await {
+                warn!("Failed to release cluster lease: {}", e);
+            }
+        }
+        
+        // Whatever the outcome, the job is no longer in progress; without this a skipped or
+        // interrupted job would be held back until the in-progress marker expires
+        if let Err(e) = self.redis.delete(&job.cache_key()).await {
+            warn!("Failed to remove finished job from cache: {}", e);
+        }
+        
+        match result {
+            Ok(success) => {
+                if success {
+                    // Every variant of a batched job now has fresh values
+                    for variant in job.expand_variants() {
+                        self.refresh_completeness(&variant).await;
                     }
-                },
-                Err(e) => {
-                    error!("Failed to process job: {}", e);
-                    let _ = log_to_file(&format!("Failed to process job: {}", e)).await;
-                    
-                    // Release the job from cache so it can be retried
-                    let job_key = job.cache_key();
-                    if let Err(e) = self.redis.delete(&job_key).await {
-                        warn!("Failed to remove failed job from cache: {}", e);
+                }
+            },
+            Err(e) => {
+                error!("Failed to process job after {} attempts: {}", attempts, e);
+                
+                // Dead-letter the job and its variants; producers skip them until they are requeued
+                for variant in job.expand_variants() {
+                    if let Err(e) = self.pg.insert_failed_job(pool_name, &variant, attempts, &e.to_string()).await {
+                        warn!("Failed to record failed job: {}", e);
                     }
                 }
             }
         }
-        
-        Ok(())
     }
     
-    #[instrument(skip(self))]
-    async fn process_job(&self, job: &CalculationJob) -> Result<bool> {
-        // Get candle data
-        let data = self.pg.get_candle_data(&job.symbol, &job.interval).await?;
+    // Run a job, retrying failures with exponential backoff.
+    // Returns the final result and the number of attempts made.
+    async fn process_job_with_retry(&self, job: &CalculationJob, tracker: &ResourceTracker) -> (Result<bool>, u32) {
+        let mut attempts = 0;
         
-        if data.close.is_empty() {
-            warn!("No candle data available for {}:{}", job.symbol, job.interval);
-            let _ = log_to_file(&format!("No candle data available for {}:{}", job.symbol, job.interval)).await;
-            return Ok(false);
+        loop {
+            attempts += 1;
+            
+            match self.process_job(job, tracker).await {
+                Err(e) if (attempts as usize) <= self.config.retry_max && !self.is_shutting_down() => {
+                    let delay = self.config.retry_delay_ms.saturating_mul(1 << (attempts - 1).min(16));
+                    
+                    warn!("Job {}:{}:{} failed (attempt {} of {}), retrying in {} ms: {}", 
+                          job.symbol, job.interval, job.indicator_name, 
+                          attempts, self.config.retry_max + 1, delay, e);
+                    self.events.emit(
+                        EventKind::JobFailed,
+                        Some(&format!("{}:{}:{}", job.symbol, job.interval, job.indicator_name)),
+                        &format!("Attempt {} failed, retrying in {} ms: {}", attempts, delay, e),
+                        json!({
+                            "job_id": job.id,
+                            "parameters": job.parameters,
+                            "attempts": attempts,
+                            "retry_in_ms": delay,
+                            "error": e.to_string(),
+                        }),
+                    ).await;
+                    
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                },
+                result => return (result, attempts),
+            }
         }
+    }
+    
+    #[instrument(skip(self, tracker))]
+    async fn process_job(&self, job: &CalculationJob, tracker: &ResourceTracker) -> Result<bool> {
+        // Batched jobs calculate every parameter variant from the same candles
+        let jobs = job.expand_variants();
         
-        // Log data information
-        let data_info = format!(
-            "Found {} candle data points for {}:{} from {} to {}",
-            data.close.len(),
-            job.symbol, job.interval,
-            data.open_time.first().unwrap().to_rfc3339(),
-            data.open_time.last().unwrap().to_rfc3339()
-        );
-        debug!("{}", data_info);
-        let _ = log_to_file(&data_info).await;
-        
-        // Log sample data (last 5 points)
-        if data.close.len() >= 5 {
-            let sample_idx = data.close.len() - 5;
-            let sample_data = format!(
-                "Sample data (last 5 points) for {}:{}: Open: {:?}, High: {:?}, Low: {:?}, Close: {:?}, Volume: {:?}",
+        // Window-based indicators can be calculated chunk by chunk; recursive ones need the whole history
+        let ta_function_name = IndicatorCalculator::get_ta_function_name(&job.indicator_name);
+        let lookback = jobs.iter()
+            .map(|variant| {
+                // Pipeline inputs may be recursive; calculate them over the whole history
+                if !variant.inputs.is_empty() {
+                    return None;
+                }
+                let parameters: Vec<(String, Value)> = variant.parameters.as_object()
+                    .map(|map| map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+                    .unwrap_or_default();
+                window_lookback(&ta_function_name, &parameters)
+            })
+            .collect::<Option<Vec<usize>>>()
+            .and_then(|lookbacks| lookbacks.into_iter().max());
+        
+        let mut chunks = match lookback {
+            Some(lookback) if self.config.candle_chunk_size > 0 => {
+                debug!("Loading {}:{} in chunks of {} candles (overlap {})", 
+                       job.symbol, job.interval, self.config.candle_chunk_size, lookback);
+                self.candles.stream_candles(&job.symbol, &job.interval, self.config.candle_chunk_size, lookback)
+            },
+            _ => self.candles.stream_candles(&job.symbol, &job.interval, usize::MAX, 0),
+        };
+        
+        // Cross-symbol indicators also need the other symbol's candles
+        let others = self.load_other_symbols(&jobs, None).await?;
+        
+        let mut batch = Vec::with_capacity(self.config.batch_size);
+        let mut candle_count = 0;
+        let mut results_len = 0;
+        let mut last_time: Option<DateTime<Utc>> = None;
+        let mut anomalies: Vec<Vec<IndicatorAnomaly>> = jobs.iter().map(|_| Vec::new()).collect();
+        
+        while let Some(data) = chunks.next().await {
+            // On shutdown, keep what earlier chunks produced; the rest is calculated on the next run
+            if self.is_shutting_down() && last_time.is_some() {
+                if !batch.is_empty() {
+                    tracker.add_rows_written(batch.len() as u64);
+                    self.insert_batch(job, batch).await?;
+                }
+                self.report_anomalies(&jobs, anomalies).await?;
+                
+                info!("Checkpointed {}:{}:{} at {} for shutdown", 
+                      job.symbol, job.interval, job.indicator_name, last_time.unwrap().to_rfc3339());
+                return Ok(false);
+            }
+            
+            let data = data?;
+            tracker.add_rows_read(data.len() as u64);
+            
+            if data.close.is_empty() {
+                continue;
+            }
+            candle_count += data.len();
+            
+            // Refuse to calculate indicators from broken data
+            let issues = check_candle_quality(&data, self.config.max_gap_ratio);
+            if !issues.is_empty() {
+                for issue in &issues {
+                    warn!("Data quality issue for {}:{} ({}): {}", job.symbol, job.interval, issue.check, issue.detail);
+                    self.events.emit(
+                        EventKind::DataQuality,
+                        Some(&format!("{}:{}", job.symbol, job.interval)),
+                        &format!("{}: {}", issue.check, issue.detail),
+                        json!({"job_id": job.id, "check": issue.check, "detail": issue.detail, "indicator": job.indicator_name}),
+                    ).await;
+                }
+                
+                self.pg.record_data_quality_issues(&job.symbol, &job.interval, &issues).await?;
+                return Ok(false);
+            }
+            
+            // Log data information
+            let data_info = format!(
+                "Found {} candle data points for {}:{} from {} to {}",
+                data.close.len(),
                 job.symbol, job.interval,
-                &data.open[sample_idx..],
-                &data.high[sample_idx..],
-                &data.low[sample_idx..],
-                &data.close[sample_idx..],
-                &data.volume[sample_idx..]
+                data.open_time.first().unwrap().to_rfc3339(),
+                data.open_time.last().unwrap().to_rfc3339()
             );
-            debug!("{}", sample_data);
-            let _ = log_to_file(&sample_data).await;
+            debug!("{}", data_info);
+            
+            // Log sample data (last 5 points)
+            if data.close.len() >= 5 {
+                let sample_idx = data.close.len() - 5;
+                let sample_data = format!(
+                    "Sample data (last 5 points) for {}:{}: Open: {:?}, High: {:?}, Low: {:?}, Close: {:?}, Volume: {:?}",
+                    job.symbol, job.interval,
+                    &data.open[sample_idx..],
+                    &data.high[sample_idx..],
+                    &data.low[sample_idx..],
+                    &data.close[sample_idx..],
+                    &data.volume[sample_idx..]
+                );
+                debug!("{}", sample_data);
+            }
+            
+            // Calculate the indicator using the TA-Lib abstract interface
+            debug!("Calculating indicator {}:{}:{} using TA-Lib abstract interface", 
+                   job.symbol, job.interval, job.indicator_name);
+            let variant_results = if jobs.len() == 1 {
+                let started = Instant::now();
+                let results = self.calculate_indicator(job, &data, &others).await?;
+                self.status.timings().record_calculation(&job.indicator_name, &job.symbol, started.elapsed());
+                vec![results]
+            } else {
+                debug!("Calculating {} parameter sets of {}:{}:{} in parallel", 
+                       jobs.len(), job.symbol, job.interval, job.indicator_name);
+                self.calculate_variants(&jobs, &data, &others)?
+            };
+            
+            for ((variant, results), found) in jobs.iter().zip(variant_results).zip(anomalies.iter_mut()) {
+                found.extend(self.check_values(variant, &results, &data, last_time));
+                
+                for (time, value) in results {
+                    // Values in the overlap were already produced by the previous chunk
+                    if last_time.map_or(false, |last| time <= last) {
+                        continue;
+                    }
+                    
+                    results_len += 1;
+                    batch.push(CalculatedIndicatorBatch {
+                        symbol: variant.symbol.clone(),
+                        interval: variant.interval.clone(),
+                        indicator_type: variant.indicator_type.to_string(),
+                        indicator_name: variant.indicator_name.clone(),
+                        parameters: variant.parameters.clone(),
+                        time,
+                        value: value.into(),
+                    });
+                    
+                    // Insert in batches
+                    if batch.len() >= self.config.batch_size {
+                        tracker.add_rows_written(batch.len() as u64);
+                        self.insert_batch(job, batch.clone()).await?;
+                        batch.clear();
+                    }
+                }
+            }
+            
+            last_time = data.open_time.last().copied();
         }
         
-        // Calculate the indicator using the TA-Lib abstract interface
-        debug!("Calculating indicator {}:{}:{} using TA-Lib abstract interface", 
-               job.symbol, job.interval, job.indicator_name);
-        let results = self.calculate_indicator(job, &data).await?;
-        let results_len = results.len(); // Store length before moving
+        if candle_count == 0 {
+            warn!("No candle data available for {}:{}", job.symbol, job.interval);
+            return Ok(false);
+        }
         
-        if results.is_empty() {
+        // Insert any remaining indicators
+        if !batch.is_empty() {
+            tracker.add_rows_written(batch.len() as u64);
+            self.insert_batch(job, batch).await?;
+        }
+        self.report_anomalies(&jobs, anomalies).await?;
+        
+        if results_len == 0 {
             info!("No new indicator values calculated for {}:{}:{}", 
                  job.symbol, job.interval, job.indicator_name);
             return Ok(false);
         }
         
-        // Prepare batch for database insertion
-        let mut batch = Vec::with_capacity(results.len());
+        info!("Successfully processed indicator {}:{}:{}", 
+             job.symbol, job.interval, job.indicator_name);
+        
+        Ok(true)
+    }
+    
+    /// Recalculate a job's values between `from` and `to`, overwriting stored values and
+    /// ignoring completeness; returns the number of values written
+    pub async fn backfill(
+        &self,
+        job: &CalculationJob,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<usize> {
+        // Load everything up to `to` so recursive indicators warm up exactly as in a full run
+        let data = self.pg.get_candle_data_between(&job.symbol, &job.interval, None, to).await?;
+        if data.close.is_empty() {
+            warn!("No candle data available for {}:{}", job.symbol, job.interval);
+            return Ok(0);
+        }
+        
+        let issues = check_candle_quality(&data, self.config.max_gap_ratio);
+        if !issues.is_empty() {
+            self.pg.record_data_quality_issues(&job.symbol, &job.interval, &issues).await?;
+            let details: Vec<String> = issues.iter().map(|i| format!("{}: {}", i.check, i.detail)).collect();
+            return Err(anyhow::anyhow!("Data quality issues for {}:{}: {}", 
+                                       job.symbol, job.interval, details.join("; ")));
+        }
+        
+        let others = self.load_other_symbols(std::slice::from_ref(job), to).await?;
+        let started = Instant::now();
+        let results = self.calculate_indicator(job, &data, &others).await?;
+        self.status.timings().record_calculation(&job.indicator_name, &job.symbol, started.elapsed());
         
-        for (time, value) in results {
-            batch.push(CalculatedIndicatorBatch {
+        let anomalies = self.check_values(job, &results, &data, None);
+        self.report_anomalies(std::slice::from_ref(job), vec![anomalies]).await?;
+        
+        let batch: Vec<CalculatedIndicatorBatch> = results.into_iter()
+            .filter(|(time, _)| from.map_or(true, |from| *time >= from))
+            .map(|(time, value)| CalculatedIndicatorBatch {
                 symbol: job.symbol.clone(),
                 interval: job.interval.clone(),
                 indicator_type: job.indicator_type.to_string(),
                 indicator_name: job.indicator_name.clone(),
                 parameters: job.parameters.clone(),
                 time,
-                value,
-            });
+                value: value.into(),
+            })
+            .collect();
+        
+        let written = batch.len();
+        for chunk in batch.chunks(self.config.batch_size.max(1)) {
+            self.insert_batch(job, chunk.to_vec()).await?;
+        }
+        
+        info!("Backfilled {} values for {}:{}:{}", written, job.symbol, job.interval, job.indicator_name);
+        
+        Ok(written)
+    }
+    
+    // Suspicious values among the results of a variant calculated after `after`
+    fn check_values(
+        &self,
+        variant: &CalculationJob,
+        results: &[(DateTime<Utc>, IndicatorOutput)],
+        data: &CandleData,
+        after: Option<DateTime<Utc>>,
+    ) -> Vec<IndicatorAnomaly> {
+        // Candlestick patterns repeat the same strength by design
+        let settings = match variant.indicator_type {
+            IndicatorType::Pattern => AnomalySettings { constant_run: 0, ..self.config.anomaly },
+            _ => self.config.anomaly,
+        };
+        
+        detect_anomalies(results, data, after, &settings)
+    }
+    
+    // Record the suspicious values found by a job, one list per variant, and announce
+    // those no earlier run found
+    async fn report_anomalies(&self, jobs: &[CalculationJob], anomalies: Vec<Vec<IndicatorAnomaly>>) -> Result<()> {
+        for (variant, mut found) in jobs.iter().zip(anomalies) {
+            if found.is_empty() {
+                continue;
+            }
+            
+            // A broken calculation can flag every value; later runs record the rest
+            found.truncate(MAX_RECORDED_ANOMALIES);
+            let found = self.pg.record_indicator_anomalies(variant, found).await?;
+            let Some(first) = found.first() else {
+                continue;
+            };
+            
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for anomaly in &found {
+                *counts.entry(anomaly.check).or_default() += 1;
+            }
+            let summary: Vec<String> = counts.iter().map(|(check, count)| format!("{} {}", count, check)).collect();
+            
+            let subject = format!("{}:{}:{}", variant.symbol, variant.interval, variant.indicator_name);
+            let message = format!("Suspicious values of {} {}: {}; first at {}: {}", 
+                                  subject, variant.parameters, summary.join(", "), first.time.to_rfc3339(), first.detail);
+            warn!("{}", message);
+            self.events.emit(
+                EventKind::IndicatorAnomaly,
+                Some(&subject),
+                &message,
+                json!({
+                    "job_id": variant.id,
+                    "parameters": variant.parameters,
+                    "counts": counts,
+                    "first": {
+                        "check": first.check,
+                        "time": first.time,
+                        "property": first.property,
+                        "value": first.value,
+                        "detail": first.detail,
+                    },
+                }),
+            ).await;
             
-            // Insert in batches
-            if batch.len() >= self.config.batch_size {
-                self.pg.insert_calculated_indicators_batch(batch.clone()).await?;
-                batch.clear();
+            if self.config.notify_anomalies {
+                let notifier = self.config.notify_webhook_url.as_deref()
+                    .filter(|url| !url.is_empty())
+                    .map(WebhookNotifier::new);
+                if let Some(notifier) = notifier {
+                    if let Err(e) = notifier.send(&message).await {
+                        warn!("Failed to send indicator anomaly notification: {}", e);
+                    }
+                }
             }
         }
         
-        // Insert any remaining indicators
-        if !batch.is_empty() {
-            self.pg.insert_calculated_indicators_batch(batch).await?;
-        }
+        Ok(())
+    }
+    
+    // Write calculated values, recording how long the insert took
+    async fn insert_batch(&self, job: &CalculationJob, batch: Vec<CalculatedIndicatorBatch>) -> Result<()> {
+        let rows = batch.len();
+        let started = Instant::now();
+        self.pg.insert_calculated_indicators_batch(batch).await?;
+        self.status.timings().record_insert(&job.indicator_name, &job.symbol, started.elapsed(), rows);
         
-        // Remove job from cache
-        let job_key = job.cache_key();
-        if let Err(e) = self.redis.delete(&job_key).await {
-            warn!("Failed to remove completed job from cache: {}", e);
+        Ok(())
+    }
+    
+    // Candles of the other symbols referenced by cross-symbol jobs, up to `to`
+    async fn load_other_symbols(&self, jobs: &[CalculationJob], to: Option<DateTime<Utc>>) -> Result<HashMap<String, CandleData>> {
+        let mut others = HashMap::new();
+        for job in jobs.iter().filter(|job| cross_symbol::is_cross_symbol(&job.indicator_name)) {
+            let other_symbol = cross_symbol::other_symbol(&job.parameters)?;
+            if !others.contains_key(other_symbol) {
+                let candles = self.pg.get_candle_data_between(other_symbol, &job.interval, None, to).await?;
+                others.insert(other_symbol.to_string(), candles);
+            }
         }
+        Ok(others)
+    }
+    
+    // Calculate the parameter variants of a batched job from the same candles in parallel
+    fn calculate_variants(&self, jobs: &[CalculationJob], candle_data: &CandleData, others: &HashMap<String, CandleData>) -> Result<Vec<Vec<(DateTime<Utc>, IndicatorOutput)>>> {
+        let timings = self.status.timings();
         
-        info!("Successfully processed indicator {}:{}:{}", 
-             job.symbol, job.interval, job.indicator_name);
-        let _ = log_to_file(&format!("Successfully processed indicator {}:{}:{} - Generated {} data points", 
-             job.symbol, job.interval, job.indicator_name, results_len));
-        
-        Ok(true)
+        // TA-Lib runs on the rayon pool; keep the async runtime responsive meanwhile
+        tokio::task::block_in_place(|| {
+            jobs.par_iter()
+                .map(|variant| {
+                    let started = Instant::now();
+                    let results = compute_indicator(variant, candle_data, others);
+                    timings.record_calculation(&variant.indicator_name, &variant.symbol, started.elapsed());
+                    
+                    results.map_err(|e| anyhow::anyhow!("Failed to calculate {} with parameters {}: {}", 
+                                                        variant.indicator_name, variant.parameters, e))
+                })
+                .collect()
+        })
     }
     
     async fn calculate_indicator(
         &self,
         job: &CalculationJob,
         candle_data: &CandleData,
-    ) -> Result<Vec<(DateTime<Utc>, Value)>> {
+        others: &HashMap<String, CandleData>,
+    ) -> Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
         // Get the TA-Lib function name for this indicator
         let ta_function_name = IndicatorCalculator::get_ta_function_name(&job.indicator_name);
         
@@ -365,87 +1421,8 @@ impl Worker {
             job.indicator_name, ta_function_name, job.parameters
         );
         debug!("{}", params_info);
-        let _ = log_to_file(&params_info).await;
-        
-        // Special handling for multi-output indicators that need extra processing
-        let result = match job.indicator_name.as_str() {
-            "MACD" => {
-                let fast_period = job.parameters["fast_period"].as_u64().unwrap_or(12) as usize;
-                let slow_period = job.parameters["slow_period"].as_u64().unwrap_or(26) as usize;
-                let signal_period = job.parameters["signal_period"].as_u64().unwrap_or(9) as usize;
-                
-                let _ = log_to_file(&format!(
-                    "MACD configuration: fast_period={}, slow_period={}, signal_period={}",
-                    fast_period, slow_period, signal_period
-                )).await;
-                
-                IndicatorCalculator::calculate_macd(
-                    candle_data,
-                    fast_period,
-                    slow_period,
-                    signal_period,
-                )
-            },
-            "BBANDS" => {
-                // Use the generic calculator for Bollinger Bands but process the results
-                // to provide upper/middle/lower bands in the expected format
-                let period = job.parameters["period"].as_u64().unwrap_or(20) as usize;
-                let dev_up = job.parameters["deviation_up"].as_f64().unwrap_or(2.0);
-                let dev_down = job.parameters["deviation_down"].as_f64().unwrap_or(2.0);
-                
-                let _ = log_to_file(&format!(
-                    "BBANDS configuration: period={}, dev_up={}, dev_down={}",
-                    period, dev_up, dev_down
-                )).await;
-                
-                // Customize the parameters for TA-Lib
-                let params = json!({
-                    "optInTimePeriod": period,
-                    "optInNbDevUp": dev_up,
-                    "optInNbDevDown": dev_down,
-                    "optInMAType": 0  // Simple Moving Average
-                });
-                
-                IndicatorCalculator::calculate_indicator(
-                    candle_data,
-                    "BBANDS",
-                    &params,
-                )
-            },
-            "STOCH" => {
-                let k_period = job.parameters["k_period"].as_u64().unwrap_or(14) as usize;
-                let d_period = job.parameters["d_period"].as_u64().unwrap_or(3) as usize;
-                let slowing = job.parameters["slowing"].as_u64().unwrap_or(3) as usize;
-                
-                let _ = log_to_file(&format!(
-                    "STOCH configuration: k_period={}, d_period={}, slowing={}",
-                    k_period, d_period, slowing
-                )).await;
-                
-                let params = json!({
-                    "optInFastK_Period": k_period,
-                    "optInSlowK_Period": slowing,
-                    "optInSlowK_MAType": 0,
-                    "optInSlowD_Period": d_period,
-                    "optInSlowD_MAType": 0
-                });
-                
-                IndicatorCalculator::calculate_indicator(
-                    candle_data,
-                    "STOCH",
-                    &params,
-                )
-            },
-            // Add other special cases as needed
-            _ => {
-                // Use the generic calculator for most indicators
-                IndicatorCalculator::calculate_indicator(
-                    candle_data,
-                    &ta_function_name,
-                    &job.parameters,
-                )
-            }
-        };
+        
+        let result = compute_indicator(job, candle_data, others);
         
         // Log result
         match &result {
@@ -455,7 +1432,6 @@ impl Worker {
                     job.indicator_name, values.len()
                 );
                 debug!("{}", success_msg);
-                let _ = log_to_file(&success_msg).await;
                 
                 // Log sample of output values
                 if !values.is_empty() && values.len() > 3 {
@@ -465,16 +1441,115 @@ impl Worker {
                         &values[sample_idx..]
                     );
                     debug!("{}", sample_values);
-                    let _ = log_to_file(&sample_values).await;
                 }
             },
             Err(e) => {
                 let error_msg = format!("Failed to calculate {}: {}", job.indicator_name, e);
                 error!("{}", error_msg);
-                let _ = log_to_file(&error_msg).await;
             },
         }
         
-        result
+        Ok(result?)
+    }
+}
+
+// Calculate one indicator job from candle data; safe to call from rayon threads.
+// `others` holds the candles of the second symbol of cross-symbol jobs.
+fn compute_indicator(
+    job: &CalculationJob,
+    candle_data: &CandleData,
+    others: &HashMap<String, CandleData>,
+) -> crate::error::Result<Vec<(DateTime<Utc>, IndicatorOutput)>> {
+    let ta_function_name = IndicatorCalculator::get_ta_function_name(&job.indicator_name);
+    
+    if cross_symbol::is_cross_symbol(&job.indicator_name) {
+        let other_symbol = cross_symbol::other_symbol(&job.parameters)?;
+        let other = others.get(other_symbol)
+            .ok_or_else(|| crate::error::Error::not_found(format!("candles of {}:{}", other_symbol, job.interval)))?;
+        return IndicatorCalculator::calculate_cross_symbol(candle_data, other, &job.indicator_name, &job.parameters);
+    }
+    
+    if !job.inputs.is_empty() {
+        return pipeline::calculate(candle_data, &job.inputs, &job.indicator_name, &job.parameters);
+    }
+    
+    // Special handling for multi-output indicators that need extra processing
+    match job.indicator_name.as_str() {
+        "MACD" => {
+            let fast_period = job.parameters["fast_period"].as_u64().unwrap_or(12) as usize;
+            let slow_period = job.parameters["slow_period"].as_u64().unwrap_or(26) as usize;
+            let signal_period = job.parameters["signal_period"].as_u64().unwrap_or(9) as usize;
+            
+            debug!(
+                "MACD configuration: fast_period={}, slow_period={}, signal_period={}",
+                fast_period, slow_period, signal_period
+            );
+            
+            IndicatorCalculator::calculate_macd(
+                candle_data,
+                fast_period,
+                slow_period,
+                signal_period,
+            )
+        },
+        "BBANDS" => {
+            // Use the generic calculator for Bollinger Bands but process the results
+            // to provide upper/middle/lower bands in the expected format
+            let period = job.parameters["period"].as_u64().unwrap_or(20) as usize;
+            let dev_up = job.parameters["deviation_up"].as_f64().unwrap_or(2.0);
+            let dev_down = job.parameters["deviation_down"].as_f64().unwrap_or(2.0);
+            
+            debug!(
+                "BBANDS configuration: period={}, dev_up={}, dev_down={}",
+                period, dev_up, dev_down
+            );
+            
+            // Customize the parameters for TA-Lib
+            let params = json!({
+                "optInTimePeriod": period,
+                "optInNbDevUp": dev_up,
+                "optInNbDevDown": dev_down,
+                "optInMAType": 0  // Simple Moving Average
+            });
+            
+            IndicatorCalculator::calculate_indicator(
+                candle_data,
+                "BBANDS",
+                &params,
+            )
+        },
+        "STOCH" => {
+            let k_period = job.parameters["k_period"].as_u64().unwrap_or(14) as usize;
+            let d_period = job.parameters["d_period"].as_u64().unwrap_or(3) as usize;
+            let slowing = job.parameters["slowing"].as_u64().unwrap_or(3) as usize;
+            
+            debug!(
+                "STOCH configuration: k_period={}, d_period={}, slowing={}",
+                k_period, d_period, slowing
+            );
+            
+            let params = json!({
+                "optInFastK_Period": k_period,
+                "optInSlowK_Period": slowing,
+                "optInSlowK_MAType": 0,
+                "optInSlowD_Period": d_period,
+                "optInSlowD_MAType": 0
+            });
+            
+            IndicatorCalculator::calculate_indicator(
+                candle_data,
+                "STOCH",
+                &params,
+            )
+        },
+        // Add other special cases as needed
+        _ => {
+            // Use the generic calculator for most indicators
+            IndicatorCalculator::calculate_indicator(
+                candle_data,
+                &ta_function_name,
+                &job.parameters,
+            )
+        }
     }
 }
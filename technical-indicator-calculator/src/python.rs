@@ -0,0 +1,314 @@
+// Python bindings (feature "python")
+//
+// Built as an extension module with maturin (see pyproject.toml). Indicators are calculated
+// directly on numpy arrays without touching the database; strategies and backtests use the
+// database configured by config.toml and the environment, like the CLI.
+//
+//     import technical_indicator_calculator as tic
+//     rsi = tic.calculate_indicator("RSI", close, parameters={"period": 14})
+//     db = tic.Database()
+//     performance = db.backtest(strategy_id, "BTCUSDT", "1h", start="2024-01-01T00:00:00Z")
+use crate::config::AppConfig;
+use crate::database::models::CandleData;
+use crate::error::Error;
+use crate::events;
+use crate::indicators::calculator::IndicatorCalculator;
+use crate::indicators::output::{IndicatorOutput, PatternDirection};
+use crate::strategy::cli_handler::parse_date;
+use crate::strategy::evaluator::IndicatorSource;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::Strategy;
+use crate::strategy::script;
+use chrono::{DateTime, Utc};
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::{PyLookupError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Validation(_) | Error::Json(_) => PyValueError::new_err(error.to_string()),
+            Error::NotFound(_) => PyLookupError::new_err(error.to_string()),
+            _ => PyRuntimeError::new_err(error.to_string()),
+        }
+    }
+}
+
+fn runtime_error(error: anyhow::Error) -> PyErr {
+    PyRuntimeError::new_err(format!("{:#}", error))
+}
+
+// Values cross the boundary as JSON so Python sees plain dicts, lists and numbers
+fn to_python<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(Error::from)?;
+    py.import_bound("json")?.call_method1("loads", (json,))
+}
+
+fn from_python<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = value.py().import_bound("json")?.call_method1("dumps", (value,))?.extract()?;
+    Ok(serde_json::from_str(&json).map_err(Error::from)?)
+}
+
+fn parse_optional_date(date: Option<&str>) -> PyResult<Option<DateTime<Utc>>> {
+    date.map(parse_date).transpose().map_err(|e| PyValueError::new_err(format!("{:#}", e)))
+}
+
+// Candles are addressed by position; the timestamps only carry the index through the calculator
+fn index_time(idx: usize) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp(idx as i64, 0).unwrap_or_default()
+}
+
+/// Calculate an indicator on numpy arrays
+///
+/// Returns an array aligned with the input, or a dict of arrays for multi-valued indicators
+/// (e.g. MACD's macd/signal/histogram). Candles without a value (warmup, or no pattern
+/// detected) are NaN; pattern strength is negative for bearish patterns and 0 for neutral
+/// ones such as doji. Series that aren't given are NaN, so indicators reading them
+/// produce NaN.
+#[pyfunction]
+#[pyo3(signature = (name, close, open=None, high=None, low=None, volume=None, parameters=None))]
+#[allow(clippy::too_many_arguments)]
+fn calculate_indicator<'py>(
+    py: Python<'py>,
+    name: &str,
+    close: PyReadonlyArray1<'py, f64>,
+    open: Option<PyReadonlyArray1<'py, f64>>,
+    high: Option<PyReadonlyArray1<'py, f64>>,
+    low: Option<PyReadonlyArray1<'py, f64>>,
+    volume: Option<PyReadonlyArray1<'py, f64>>,
+    parameters: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let close = close.as_array().to_vec();
+    let len = close.len();
+
+    let series = |values: Option<PyReadonlyArray1<'py, f64>>, label: &str| -> PyResult<Vec<f64>> {
+        let Some(values) = values else {
+            return Ok(vec![f64::NAN; len]);
+        };
+        let values = values.as_array().to_vec();
+        if values.len() != len {
+            return Err(PyValueError::new_err(format!(
+                "{} has {} values but close has {}", label, values.len(), len
+            )));
+        }
+        Ok(values)
+    };
+
+    let parameters: Value = match parameters {
+        Some(parameters) => from_python(parameters)?,
+        None => json!({}),
+    };
+
+    let mut candles = CandleData::new(String::new(), String::new());
+    candles.open_time = (0..len).map(index_time).collect();
+    candles.close_time = candles.open_time.clone();
+    candles.open = series(open, "open")?;
+    candles.high = series(high, "high")?;
+    candles.low = series(low, "low")?;
+    candles.volume = series(volume, "volume")?;
+    candles.close = close;
+
+    let results = py.allow_threads(|| IndicatorCalculator::calculate_indicator(&candles, name, &parameters))?;
+
+    // One column per output property, NaN where the indicator has no value
+    let mut columns: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    let mut set = |property: &str, idx: usize, value: f64| {
+        columns.entry(property.to_string()).or_insert_with(|| vec![f64::NAN; len])[idx] = value;
+    };
+
+    for (time, output) in results {
+        let idx = time.timestamp() as usize;
+        match output {
+            IndicatorOutput::Scalar(value) => set("value", idx, value.0),
+            IndicatorOutput::Pattern(hit) => match hit.direction {
+                PatternDirection::Bullish => set("value", idx, hit.strength),
+                PatternDirection::Bearish => set("value", idx, -hit.strength),
+                PatternDirection::Neutral => set("value", idx, 0.0),
+            },
+            output => {
+                if let Value::Object(properties) = output.to_value() {
+                    for (property, value) in properties {
+                        set(&property, idx, value.as_f64().unwrap_or(f64::NAN));
+                    }
+                }
+            },
+        }
+    }
+
+    if columns.is_empty() || (columns.len() == 1 && columns.contains_key("value")) {
+        let values = columns.remove("value").unwrap_or_else(|| vec![f64::NAN; len]);
+        return Ok(PyArray1::from_vec_bound(py, values).into_any());
+    }
+
+    let dict = PyDict::new_bound(py);
+    for (property, values) in columns {
+        dict.set_item(property, PyArray1::from_vec_bound(py, values))?;
+    }
+    Ok(dict.into_any())
+}
+
+/// Names of the indicators calculate_indicator supports, including registered plugins
+#[pyfunction]
+fn supported_indicators() -> Vec<String> {
+    IndicatorCalculator::get_supported_indicators()
+}
+
+/// Strategies and backtests in the configured database
+#[pyclass]
+struct Database {
+    runtime: Runtime,
+    repository: StrategyRepository,
+}
+
+impl Database {
+    async fn load_strategy(&self, strategy: StrategyRef) -> crate::error::Result<Strategy> {
+        match strategy {
+            StrategyRef::Id(id) => self.repository.get_strategy(&id).await,
+            StrategyRef::Definition(strategy) => {
+                script::validate_scripts(&strategy)?;
+                Ok(*strategy)
+            },
+        }
+    }
+}
+
+/// A stored strategy by id, or a definition passed in from Python
+enum StrategyRef {
+    Id(String),
+    Definition(Box<Strategy>),
+}
+
+impl StrategyRef {
+    fn extract(strategy: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match strategy.extract::<String>() {
+            Ok(id) => Ok(StrategyRef::Id(id)),
+            Err(_) => Ok(StrategyRef::Definition(Box::new(from_python(strategy)?))),
+        }
+    }
+}
+
+#[pymethods]
+impl Database {
+    /// Connect with the settings from config.toml and the environment
+    #[new]
+    fn new() -> PyResult<Self> {
+        events::set_source("python");
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+
+        let repository = runtime
+            .block_on(async {
+                let config = AppConfig::load()?;
+                // Each Database has its own runtime and a pool can't outlive the runtime it
+                // was opened on, so the process-wide shared pool isn't used here
+                let pg = config.database.connect(config.database.max_connections).await?;
+                pg.init_engine_settings_table().await?;
+                Ok::<_, anyhow::Error>(StrategyRepository::new(Arc::new(pg)))
+            })
+            .map_err(runtime_error)?;
+
+        Ok(Self { runtime, repository })
+    }
+
+    /// All strategies as dicts
+    #[pyo3(signature = (enabled_only=false))]
+    fn list_strategies<'py>(&self, py: Python<'py>, enabled_only: bool) -> PyResult<Bound<'py, PyAny>> {
+        let strategies = py.allow_threads(|| self.runtime.block_on(self.repository.list_strategies(enabled_only)))?;
+        to_python(py, &strategies)
+    }
+
+    /// A strategy as dict; raises LookupError if it doesn't exist
+    fn get_strategy<'py>(&self, py: Python<'py>, strategy_id: &str) -> PyResult<Bound<'py, PyAny>> {
+        let strategy = py.allow_threads(|| self.runtime.block_on(self.repository.get_strategy(strategy_id)))?;
+        to_python(py, &strategy)
+    }
+
+    /// Store a strategy given as dict, after compiling its expressions
+    fn save_strategy(&self, py: Python<'_>, strategy: &Bound<'_, PyAny>) -> PyResult<()> {
+        let strategy: Strategy = from_python(strategy)?;
+        script::validate_scripts(&strategy)?;
+        py.allow_threads(|| self.runtime.block_on(self.repository.save_strategy(&strategy)))?;
+        Ok(())
+    }
+
+    /// Backtest a stored strategy (by id) or a strategy dict and return its performance
+    ///
+    /// With save=True the result is stored like a CLI backtest and its id is returned as
+    /// "backtest_id"; dates are ISO 8601 strings. The same seed reproduces the same result.
+    #[pyo3(signature = (strategy, symbol, interval, start=None, end=None, initial_capital=10000.0, engine_profile="default", recalculate=false, save=false, seed=0))]
+    #[allow(clippy::too_many_arguments)]
+    fn backtest<'py>(
+        &self,
+        py: Python<'py>,
+        strategy: &Bound<'py, PyAny>,
+        symbol: &str,
+        interval: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+        initial_capital: f64,
+        engine_profile: &str,
+        recalculate: bool,
+        save: bool,
+        seed: u64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let strategy = StrategyRef::extract(strategy)?;
+        let start_date = parse_optional_date(start)?;
+        let end_date = parse_optional_date(end)?;
+
+        let result = py.allow_threads(|| self.runtime.block_on(async {
+            let strategy = self.load_strategy(strategy).await?;
+            let evaluator = self.repository
+                .evaluator_for(symbol, engine_profile, initial_capital, start_date, end_date).await?
+                .with_indicator_source(if recalculate { IndicatorSource::Calculate } else { IndicatorSource::Precomputed })
+                .with_seed(seed);
+
+            let performance = evaluator.backtest(&strategy, symbol, interval, start_date, end_date).await?;
+            let mut result = serde_json::to_value(&performance)?;
+
+            if save {
+                let backtest_id = self.repository.save_backtest_result(
+                    &strategy.id, symbol, interval, start_date, end_date, initial_capital, &performance,
+                ).await?;
+                self.repository.record_backtest_engine_settings(backtest_id, &evaluator).await?;
+                result["backtest_id"] = json!(backtest_id);
+            }
+
+            Ok::<_, Error>(result)
+        }))?;
+
+        to_python(py, &result)
+    }
+
+    /// Most recent stored backtests of a strategy as dicts with id, symbol, interval and metrics
+    #[pyo3(signature = (strategy_id, limit=10))]
+    fn backtest_results<'py>(&self, py: Python<'py>, strategy_id: &str, limit: i64) -> PyResult<Bound<'py, PyAny>> {
+        let results = py.allow_threads(|| {
+            self.runtime.block_on(self.repository.get_recent_backtest_results(strategy_id, limit))
+        })?;
+
+        let results: Vec<Value> = results
+            .into_iter()
+            .map(|(id, symbol, interval, performance)| json!({
+                "backtest_id": id,
+                "symbol": symbol,
+                "interval": interval,
+                "performance": performance,
+            }))
+            .collect();
+        to_python(py, &results)
+    }
+}
+
+#[pymodule]
+fn technical_indicator_calculator(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(calculate_indicator, m)?)?;
+    m.add_function(wrap_pyfunction!(supported_indicators, m)?)?;
+    m.add_class::<Database>()?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+// src/signals/generator.rs
+//
+// Live signal generation
+//
+// The worker periodically evaluates the rules of every enabled strategy on the last closed
+// candle of each of its assets and timeframes. Every signal is claimed in the ledger before
+// it is sent, so a bar evaluated again by the next poll or after a restart is never sent
+// twice. The claimed signals are what `paper run` executes and gRPC StreamSignals streams.
+use crate::config::NotificationSettings;
+use crate::signals::ledger::SignalLedger;
+use crate::signals::webhook::WebhookNotifier;
+use crate::signals::Signal;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::Strategy;
+use crate::strategy::watchlist;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+// Capital the rules are simulated with; signals don't depend on it
+const SIMULATION_CAPITAL: f64 = 10_000.0;
+
+/// Evaluates enabled strategies and delivers their new signals
+pub struct SignalGenerator {
+    repository: StrategyRepository,
+    ledger: SignalLedger,
+    notifier: Option<WebhookNotifier>,
+    /// Candles before the last closed one that are simulated to establish the position
+    lookback: Duration,
+}
+
+impl SignalGenerator {
+    pub fn new(repository: StrategyRepository, ledger: SignalLedger, notifier: Option<WebhookNotifier>,
+               lookback: Duration) -> Self {
+        Self { repository, ledger, notifier, lookback }
+    }
+
+    /// Create a generator using the configured ledger, webhook and lookback
+    pub fn from_config(repository: StrategyRepository, settings: &NotificationSettings) -> Self {
+        let ledger = SignalLedger::from_config(repository.get_db_connection(), settings);
+        Self::new(
+            repository,
+            ledger,
+            WebhookNotifier::from_config(settings),
+            Duration::days(settings.signal_lookback_days as i64),
+        )
+    }
+
+    /// The ledger signals are claimed in
+    pub fn ledger(&self) -> &SignalLedger {
+        &self.ledger
+    }
+
+    /// Evaluate every asset and timeframe of all enabled strategies, returning the signals delivered
+    pub async fn run_all(&self) -> Result<Vec<Signal>> {
+        let strategies = self.repository.list_strategies(true).await?;
+        let mut delivered = Vec::new();
+
+        for strategy in &strategies {
+            delivered.extend(self.run(strategy).await?);
+        }
+
+        info!("Evaluated {} strategies for signals, {} delivered", strategies.len(), delivered.len());
+        Ok(delivered)
+    }
+
+    /// Evaluate every asset and timeframe of one strategy, returning the signals delivered
+    pub async fn run(&self, strategy: &Strategy) -> Result<Vec<Signal>> {
+        let pg = self.repository.get_db_connection();
+        let now = Utc::now();
+        let start_date = now - self.lookback;
+        let mut delivered = Vec::new();
+
+        for symbol in &watchlist::resolve_assets(&pg, &strategy.assets).await? {
+            for interval in &strategy.timeframes {
+                let evaluator = self.repository
+                    .evaluator_for(symbol, "default", SIMULATION_CAPITAL, Some(start_date), Some(now))
+                    .await?;
+                let signals = match evaluator.latest_signals(strategy, symbol, interval, start_date, now).await {
+                    Ok(signals) => signals,
+                    Err(e) => {
+                        warn!("Signal evaluation of {} on {}:{} failed: {}", strategy.id, symbol, interval, e);
+                        continue;
+                    }
+                };
+
+                for signal in signals {
+                    match self.deliver(&signal).await {
+                        Ok(true) => delivered.push(signal),
+                        Ok(false) => {},
+                        Err(e) => warn!("Failed to deliver signal {}: {}", signal.idempotency_key(), e),
+                    }
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// Claim a signal and send it to the webhook
+    ///
+    /// Returns false without sending when the signal was already delivered within the dedup
+    /// window. A failed send releases the claim, so the next poll retries the signal.
+    pub async fn deliver(&self, signal: &Signal) -> Result<bool> {
+        if !self.ledger.claim(signal).await? {
+            return Ok(false);
+        }
+
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.send(&message(signal)).await {
+                if let Err(release_error) = self.ledger.release(signal).await {
+                    warn!("Failed to release signal {}: {}", signal.idempotency_key(), release_error);
+                }
+                return Err(e);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+// Webhook text of a signal
+fn message(signal: &Signal) -> String {
+    let action = serde_json::to_value(&signal.action).ok()
+        .and_then(|action| action["type"].as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    format!("{} {}:{} at {} (rule {}, strategy {})",
+            action, signal.symbol, signal.interval, signal.price, signal.rule_id, signal.strategy_id)
+}
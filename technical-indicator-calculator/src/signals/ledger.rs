@@ -0,0 +1,140 @@
+// src/signals/ledger.rs
+use crate::config::NotificationSettings;
+use crate::database::postgres::PostgresManager;
+use crate::events::{record_event, EventKind};
+use crate::signals::Signal;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Ledger of signals already delivered to notification sinks
+///
+/// Sinks must claim a signal before sending it. A claim only succeeds once per
+/// idempotency key within the dedup window, so restarts and cache refreshes that
+/// re-evaluate the same bar never deliver the same signal twice.
+pub struct SignalLedger {
+    pg: Arc<PostgresManager>,
+    dedup_window: Duration,
+}
+
+impl SignalLedger {
+    /// Create a new ledger with the given dedup window
+    pub fn new(pg: Arc<PostgresManager>, dedup_window: Duration) -> Self {
+        Self { pg, dedup_window }
+    }
+
+    /// Create a ledger using the configured deduplication window (default 7 days)
+    pub fn from_config(pg: Arc<PostgresManager>, settings: &NotificationSettings) -> Self {
+        Self::new(pg, Duration::from_secs(settings.signal_dedup_window_seconds))
+    }
+
+    /// Create the sent_signals table if it doesn't exist
+    pub async fn init_table(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS sent_signals (
+                idempotency_key VARCHAR PRIMARY KEY,
+                strategy_id VARCHAR NOT NULL,
+                symbol VARCHAR NOT NULL,
+                interval VARCHAR NOT NULL,
+                bar_time TIMESTAMPTZ NOT NULL,
+                rule_id VARCHAR NOT NULL,
+                signal JSONB NOT NULL,
+                sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_sent_signals_sent_at ON sent_signals(sent_at DESC)"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Atomically claim a signal for delivery
+    ///
+    /// Returns true if the signal has not been sent within the dedup window and
+    /// should be delivered now, false if it is a duplicate.
+    pub async fn claim(&self, signal: &Signal) -> Result<bool> {
+        let key = signal.idempotency_key();
+
+        // Insert the signal, or refresh an existing entry only if it fell out of the window
+        let row = sqlx::query(
+            "INSERT INTO sent_signals
+             (idempotency_key, strategy_id, symbol, interval, bar_time, rule_id, signal, sent_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+             ON CONFLICT (idempotency_key) DO UPDATE
+             SET sent_at = EXCLUDED.sent_at, signal = EXCLUDED.signal
+             WHERE sent_signals.sent_at < NOW() - make_interval(secs => $8)
+             RETURNING idempotency_key"
+        )
+        .bind(&key)
+        .bind(&signal.strategy_id)
+        .bind(&signal.symbol)
+        .bind(&signal.interval)
+        .bind(signal.bar_time)
+        .bind(&signal.rule_id)
+        .bind(serde_json::to_value(signal)?)
+        .bind(self.dedup_window.as_secs_f64())
+        .fetch_optional(&self.pg.pool)
+        .await?;
+
+        let claimed = row.is_some();
+        if claimed {
+            let data = serde_json::to_value(signal)?;
+            let action = data["action"]["type"].as_str().unwrap_or_default().to_string();
+            record_event(
+                &self.pg,
+                EventKind::SignalGenerated,
+                Some(&signal.strategy_id),
+                &format!("{} {}:{} at {} (rule {})", action, signal.symbol, signal.interval, signal.price, signal.rule_id),
+                data,
+            ).await;
+        } else {
+            debug!("Suppressing duplicate signal: {}", key);
+        }
+
+        Ok(claimed)
+    }
+
+    /// Release a claim after a failed delivery so the signal can be retried
+    pub async fn release(&self, signal: &Signal) -> Result<()> {
+        sqlx::query("DELETE FROM sent_signals WHERE idempotency_key = $1")
+            .bind(signal.idempotency_key())
+            .execute(&self.pg.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Signals sent after `cursor` as stored, oldest first, with the time they were sent
+    pub async fn sent_after(&self, cursor: DateTime<Utc>) -> Result<Vec<(serde_json::Value, DateTime<Utc>)>> {
+        let rows = sqlx::query_as(
+            "SELECT signal, sent_at FROM sent_signals
+             WHERE sent_at > $1
+             ORDER BY sent_at, bar_time"
+        )
+        .bind(cursor)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Remove ledger entries older than the dedup window
+    pub async fn prune(&self) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM sent_signals WHERE sent_at < NOW() - make_interval(secs => $1)"
+        )
+        .bind(self.dedup_window.as_secs_f64())
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,41 @@
+// src/signals/mod.rs
+pub mod generator;
+pub mod ledger;
+pub mod webhook;
+
+use crate::strategy::schema::RuleAction;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A trading signal produced when a strategy rule fires on a closed bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signal {
+    /// Strategy that produced the signal
+    pub strategy_id: String,
+    /// Symbol the signal applies to (e.g., "BTCUSDT")
+    pub symbol: String,
+    /// Interval of the bar (e.g., "1h")
+    pub interval: String,
+    /// Open time of the bar that triggered the rule
+    pub bar_time: DateTime<Utc>,
+    /// Rule that fired
+    pub rule_id: String,
+    /// Action requested by the rule
+    pub action: RuleAction,
+    /// Close price of the triggering bar
+    pub price: f64,
+}
+
+impl Signal {
+    /// Idempotency key identifying this signal across restarts and re-evaluations
+    pub fn idempotency_key(&self) -> String {
+        format!(
+            "signal:{}:{}:{}:{}:{}",
+            self.strategy_id,
+            self.symbol,
+            self.interval,
+            self.bar_time.timestamp(),
+            self.rule_id,
+        )
+    }
+}
@@ -0,0 +1,39 @@
+// src/signals/webhook.rs
+use crate::config::NotificationSettings;
+use anyhow::{Context, Result};
+use serde_json::json;
+
+/// Posts notifications as `{"text": "..."}` to a webhook (Slack and Mattermost compatible)
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Create a notifier for the configured webhook, if there is one
+    pub fn from_config(settings: &NotificationSettings) -> Option<Self> {
+        settings.webhook_url.as_deref()
+            .filter(|url| !url.is_empty())
+            .map(Self::new)
+    }
+
+    /// Send a plain text message
+    pub async fn send(&self, text: &str) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .json(&json!({ "text": text }))
+            .send()
+            .await?
+            .error_for_status()
+            .context("Notification webhook rejected the message")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+// src/strategy/accounting.rs
+use serde::{Deserialize, Serialize};
+
+/// How a contract's profit and loss is denominated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContractType {
+    /// Spot and USDT-margined contracts: PnL in the quote currency
+    #[serde(rename = "linear")]
+    Linear,
+    /// Coin-margined contracts quoted in USD: PnL in the base currency
+    #[serde(rename = "inverse")]
+    Inverse,
+    /// PnL in a third settlement currency at a fixed conversion rate
+    #[serde(rename = "quanto")]
+    Quanto,
+}
+
+impl ContractType {
+    /// Parse a contract type as stored in the symbols table
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "linear" | "spot" => Some(ContractType::Linear),
+            "inverse" => Some(ContractType::Inverse),
+            "quanto" => Some(ContractType::Quanto),
+            _ => None,
+        }
+    }
+}
+
+/// Contract specification of a symbol, used for PnL accounting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractSpec {
+    pub symbol: String,
+    pub contract_type: ContractType,
+    /// Value of one contract: quote units per contract for linear, USD face value for
+    /// inverse, settlement units per price point for quanto
+    pub multiplier: f64,
+    /// Fixed settlement currency units per quote unit (quanto only)
+    #[serde(default)]
+    pub quanto_rate: Option<f64>,
+    /// Currency PnL is paid out in
+    pub settlement_asset: Option<String>,
+    /// Charge stored funding rates to open positions (perpetual futures)
+    #[serde(default)]
+    pub apply_funding: bool,
+    /// Annual cost of borrowing for short positions, in percent
+    #[serde(default)]
+    pub short_borrow_rate: Option<f64>,
+}
+
+impl ContractSpec {
+    /// Linear contract with a multiplier of 1, used when a symbol has no metadata
+    pub fn linear(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            contract_type: ContractType::Linear,
+            multiplier: 1.0,
+            quanto_rate: None,
+            settlement_asset: None,
+            apply_funding: false,
+            short_borrow_rate: None,
+        }
+    }
+
+    /// Profit or loss in the settlement currency for `quantity` contracts
+    pub fn pnl(&self, quantity: f64, entry_price: f64, exit_price: f64, is_long: bool) -> f64 {
+        let direction = if is_long { 1.0 } else { -1.0 };
+
+        let pnl = match self.contract_type {
+            ContractType::Linear => (exit_price - entry_price) * quantity * self.multiplier,
+            ContractType::Inverse => (1.0 / entry_price - 1.0 / exit_price) * quantity * self.multiplier,
+            ContractType::Quanto => {
+                (exit_price - entry_price) * quantity * self.multiplier * self.quanto_rate.unwrap_or(1.0)
+            },
+        };
+
+        pnl * direction
+    }
+
+    /// Value of `quantity` contracts at `price` in the settlement currency
+    pub fn notional(&self, quantity: f64, price: f64) -> f64 {
+        match self.contract_type {
+            ContractType::Linear => quantity * self.multiplier * price,
+            ContractType::Inverse => quantity * self.multiplier / price,
+            ContractType::Quanto => quantity * self.multiplier * price * self.quanto_rate.unwrap_or(1.0),
+        }
+    }
+
+    /// Return of a trade as a percentage of the position's notional value at entry
+    ///
+    /// For inverse contracts this is measured in the base currency, so a long
+    /// position earns less than the price change on the way up and loses more on
+    /// the way down compared to a linear contract.
+    pub fn return_percent(&self, entry_price: f64, exit_price: f64, is_long: bool) -> f64 {
+        let notional = self.notional(1.0, entry_price);
+        if notional == 0.0 {
+            return 0.0;
+        }
+
+        self.pnl(1.0, entry_price, exit_price, is_long) / notional * 100.0
+    }
+}
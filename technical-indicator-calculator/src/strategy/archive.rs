@@ -0,0 +1,217 @@
+// src/strategy/archive.rs
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use bytes::Bytes;
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::env;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// Which backtest results are moved to object storage
+#[derive(Debug, Clone)]
+pub struct ArchivePolicy {
+    /// Archive results created more than this many days ago
+    pub older_than_days: i64,
+    /// Maximum number of results archived in one run
+    pub limit: i64,
+}
+
+/// Manifest written next to each archive file, describing its contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub run_id: String,
+    pub created_at: DateTime<Utc>,
+    pub cutoff: DateTime<Utc>,
+    /// Object key of the gzip-compressed JSON lines file with the full result rows and trades
+    pub object_key: String,
+    pub backtest_ids: Vec<i32>,
+    pub compressed_bytes: usize,
+}
+
+/// Moves old backtest results and their trades to S3-compatible object storage,
+/// keeping only summaries in Postgres
+pub struct BacktestArchiver {
+    pg: Arc<PostgresManager>,
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+}
+
+impl BacktestArchiver {
+    /// Create an archiver for the given object store
+    pub fn new(pg: Arc<PostgresManager>, store: Arc<dyn ObjectStore>, prefix: &str) -> Self {
+        Self {
+            pg,
+            store,
+            prefix: prefix.trim_matches('/').to_string(),
+        }
+    }
+
+    /// Create an archiver from the environment
+    ///
+    /// Uses ARCHIVE_BUCKET with the standard AWS_* variables (AWS_ENDPOINT for
+    /// S3-compatible stores) when set, otherwise ARCHIVE_LOCAL_DIR (default ./archive).
+    pub fn from_env(pg: Arc<PostgresManager>) -> Result<Self> {
+        let prefix = env::var("ARCHIVE_PREFIX").unwrap_or_else(|_| "backtests".to_string());
+
+        let store: Arc<dyn ObjectStore> = match env::var("ARCHIVE_BUCKET") {
+            Ok(bucket) => Arc::new(
+                AmazonS3Builder::from_env()
+                    .with_bucket_name(bucket)
+                    .build()?
+            ),
+            Err(_) => {
+                let dir = env::var("ARCHIVE_LOCAL_DIR").unwrap_or_else(|_| "./archive".to_string());
+                std::fs::create_dir_all(&dir)
+                    .map_err(Error::file("create archive directory", std::path::Path::new(&dir)))?;
+                Arc::new(LocalFileSystem::new_with_prefix(&dir)?)
+            }
+        };
+
+        Ok(Self::new(pg, store, &prefix))
+    }
+
+    /// Add the archive columns to strategy_backtest_results, and the engine settings
+    /// snapshot that archiving clears if no backtest has recorded one yet
+    pub async fn init_tables(&self) -> Result<()> {
+        sqlx::query(
+            "ALTER TABLE strategy_backtest_results 
+             ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ,
+             ADD COLUMN IF NOT EXISTS archive_key VARCHAR,
+             ADD COLUMN IF NOT EXISTS engine_settings JSONB"
+        )
+        .execute(&self.pg.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Archive results matching the policy
+    ///
+    /// Returns None when no results matched. With `dry_run` the matching results are
+    /// reported in the manifest but nothing is uploaded or modified.
+    pub async fn archive(&self, policy: &ArchivePolicy, dry_run: bool) -> Result<Option<ArchiveManifest>> {
+        let cutoff = Utc::now() - Duration::days(policy.older_than_days);
+
+        let rows = sqlx::query(
+            "SELECT r.id, to_jsonb(r) || jsonb_build_object('trades', COALESCE(
+                 (SELECT jsonb_agg(to_jsonb(t) ORDER BY t.entry_time) 
+                  FROM strategy_backtest_trades t 
+                  WHERE t.backtest_id = r.id), 
+                 '[]'::jsonb)) 
+             FROM strategy_backtest_results r 
+             WHERE r.created_at < $1 AND r.archived_at IS NULL 
+             ORDER BY r.created_at ASC 
+             LIMIT $2"
+        )
+        .bind(cutoff)
+        .bind(policy.limit)
+        .fetch_all(&self.pg.pool)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        // Serialize the full rows as gzip-compressed JSON lines
+        let mut backtest_ids = Vec::with_capacity(rows.len());
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for row in &rows {
+            let id: i32 = row.get(0);
+            let value: serde_json::Value = row.get(1);
+            serde_json::to_writer(&mut encoder, &value)?;
+            encoder.write_all(b"\n")?;
+            backtest_ids.push(id);
+        }
+        let compressed = encoder.finish()?;
+
+        let run_id = Uuid::new_v4().to_string();
+        let created_at = Utc::now();
+        let run_dir = format!("{}/{}/{}", self.prefix, created_at.format("%Y/%m/%d"), run_id);
+
+        let manifest = ArchiveManifest {
+            run_id,
+            created_at,
+            cutoff,
+            object_key: format!("{}/results.jsonl.gz", run_dir),
+            backtest_ids,
+            compressed_bytes: compressed.len(),
+        };
+
+        if dry_run {
+            return Ok(Some(manifest));
+        }
+
+        // Upload the data before the manifest, and both before touching Postgres
+        self.store.put(&ObjectPath::from(manifest.object_key.as_str()), Bytes::from(compressed)).await?;
+        self.store.put(
+            &ObjectPath::from(format!("{}/manifest.json", run_dir)),
+            Bytes::from(serde_json::to_vec_pretty(&manifest)?),
+        ).await?;
+
+        // Drop the trade history and heavy snapshots, keeping the summary columns
+        let mut tx = self.pg.pool.begin().await?;
+        
+        sqlx::query("DELETE FROM strategy_backtest_trades WHERE backtest_id = ANY($1)")
+            .bind(&manifest.backtest_ids)
+            .execute(&mut *tx)
+            .await?;
+        
+        sqlx::query(
+            "UPDATE strategy_backtest_results 
+             SET parameters_snapshot = '{}'::jsonb, engine_settings = NULL, 
+                 archived_at = $1, archive_key = $2 
+             WHERE id = ANY($3)"
+        )
+        .bind(manifest.created_at)
+        .bind(&manifest.object_key)
+        .bind(&manifest.backtest_ids)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+
+        info!("Archived {} backtest results to {}", manifest.backtest_ids.len(), manifest.object_key);
+
+        Ok(Some(manifest))
+    }
+
+    /// Retrieve the full archived row of a backtest result
+    pub async fn fetch(&self, backtest_id: i32) -> Result<serde_json::Value> {
+        let row = sqlx::query("SELECT archive_key FROM strategy_backtest_results WHERE id = $1")
+            .bind(backtest_id)
+            .fetch_optional(&self.pg.pool)
+            .await?
+            .ok_or_else(|| Error::not_found(format!("Backtest result {}", backtest_id)))?;
+
+        let archive_key: Option<String> = row.get(0);
+        let archive_key = archive_key
+            .ok_or_else(|| Error::not_found(format!("Archive of backtest result {}", backtest_id)))?;
+
+        let compressed = self.store.get(&ObjectPath::from(archive_key.as_str())).await?
+            .bytes()
+            .await?;
+
+        let mut content = String::new();
+        GzDecoder::new(&compressed[..]).read_to_string(&mut content)?;
+
+        for line in content.lines() {
+            let value: serde_json::Value = serde_json::from_str(line)?;
+            if value.get("id").and_then(|id| id.as_i64()) == Some(backtest_id as i64) {
+                return Ok(value);
+            }
+        }
+
+        Err(Error::not_found(format!("Backtest result {} in archive {}", backtest_id, archive_key)))
+    }
+}
@@ -0,0 +1,157 @@
+// Batch backtests over sets of strategies
+//
+// `backtest batch` runs every selected strategy on every symbol and interval, a few
+// backtests at a time, and stores each result like a single backtest run. A failed
+// backtest is reported with the others instead of stopping the batch. Results are
+// ranked by one of the metric names `list` accepts.
+use crate::database::postgres::PostgresManager;
+use crate::error::Result;
+use crate::strategy::listing::metric_column;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::{Strategy, StrategyPerformance};
+use crate::strategy::watchlist;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use std::cmp::Ordering;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Period, capital and engine of every backtest in a batch
+#[derive(Debug, Clone)]
+pub struct BatchSettings {
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub initial_capital: f64,
+    pub engine_profile: String,
+    /// Backtests running at the same time
+    pub concurrency: usize,
+}
+
+/// Outcome of one strategy on one symbol and interval
+#[derive(Debug, Clone)]
+pub struct BatchResult {
+    pub strategy_id: String,
+    pub strategy_name: String,
+    pub symbol: String,
+    pub interval: String,
+    /// Stored backtest ID and performance, or why the backtest failed
+    pub outcome: std::result::Result<(i32, StrategyPerformance), String>,
+}
+
+impl BatchResult {
+    /// Value of a metric column of the performance; None for failed backtests
+    pub fn metric(&self, column: &str) -> Option<f64> {
+        let (_, performance) = self.outcome.as_ref().ok()?;
+        serde_json::to_value(performance).ok()?.get(column)?.as_f64()
+    }
+}
+
+/// Every strategy on every symbol and interval; strategies use their own assets or
+/// timeframes where `symbols` or `intervals` is empty. Watchlists are resolved to their
+/// current symbols.
+pub async fn batch_jobs(
+    pg: &PostgresManager,
+    strategies: &[Strategy],
+    symbols: &[String],
+    intervals: &[String],
+) -> Result<Vec<(Arc<Strategy>, String, String)>> {
+    let mut jobs = Vec::new();
+    let symbols = watchlist::resolve_assets(pg, symbols).await?;
+
+    for strategy in strategies {
+        let strategy = Arc::new(strategy.clone());
+        let symbols = if symbols.is_empty() { watchlist::resolve_assets(pg, &strategy.assets).await? } else { symbols.clone() };
+        let intervals = if intervals.is_empty() { &strategy.timeframes[..] } else { intervals };
+
+        for symbol in &symbols {
+            for interval in intervals {
+                jobs.push((strategy.clone(), symbol.clone(), interval.clone()));
+            }
+        }
+    }
+
+    Ok(jobs)
+}
+
+/// Run backtests with at most `settings.concurrency` at a time, in completion order
+pub async fn run_batch(
+    pg: Arc<PostgresManager>,
+    jobs: Vec<(Arc<Strategy>, String, String)>,
+    settings: &BatchSettings,
+) -> Vec<BatchResult> {
+    info!("Running {} backtests, {} at a time", jobs.len(), settings.concurrency);
+
+    // Tasks are spawned as the stream is polled, so only `concurrency` exist at once
+    let tasks = jobs.into_iter().map(|(strategy, symbol, interval)| {
+        let pg = pg.clone();
+        let settings = settings.clone();
+        tokio::spawn(async move {
+            let outcome = backtest(pg, &strategy, &symbol, &interval, &settings).await
+                .map_err(|e| {
+                    warn!("Batch backtest of {} on {}:{} failed: {}", strategy.id, symbol, interval, e);
+                    e.to_string()
+                });
+
+            BatchResult {
+                strategy_id: strategy.id.clone(),
+                strategy_name: strategy.name.clone(),
+                symbol,
+                interval,
+                outcome,
+            }
+        })
+    });
+
+    stream::iter(tasks)
+        .buffer_unordered(settings.concurrency.max(1))
+        .filter_map(|task| async move {
+            task.map_err(|e| warn!("Batch backtest task panicked: {}", e)).ok()
+        })
+        .collect()
+        .await
+}
+
+/// Order results best first by a metric name, e.g. "sharpe"; the drawdown ranks lowest
+/// first. Failed backtests come last.
+pub fn rank_results(results: &mut [BatchResult], metric: &str) -> Result<&'static str> {
+    let column = metric_column(metric)?;
+    let lower_is_better = column == "max_drawdown";
+
+    results.sort_by(|a, b| match (a.metric(column), b.metric(column)) {
+        (Some(a), Some(b)) if lower_is_better => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    Ok(column)
+}
+
+// Backtest and store one strategy on a symbol and interval
+async fn backtest(
+    pg: Arc<PostgresManager>,
+    strategy: &Strategy,
+    symbol: &str,
+    interval: &str,
+    settings: &BatchSettings,
+) -> Result<(i32, StrategyPerformance)> {
+    let repository = StrategyRepository::new(pg);
+    let evaluator = repository
+        .evaluator_for(symbol, &settings.engine_profile, settings.initial_capital, settings.start_date, settings.end_date)
+        .await?;
+    let performance = evaluator.backtest(strategy, symbol, interval, settings.start_date, settings.end_date).await?;
+
+    let backtest_id = repository.save_backtest_result(
+        &strategy.id,
+        symbol,
+        interval,
+        settings.start_date,
+        settings.end_date,
+        settings.initial_capital,
+        &performance,
+    ).await?;
+    repository.record_backtest_engine_settings(backtest_id, &evaluator).await?;
+
+    Ok((backtest_id, performance))
+}
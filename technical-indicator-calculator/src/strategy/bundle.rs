@@ -0,0 +1,353 @@
+// src/strategy/bundle.rs
+//
+// Strategy bundles: a single .tar.gz holding everything needed to move a strategy to
+// another installation or share it:
+//
+//   manifest.json           what the bundle contains
+//   strategy.json           the strategy definition
+//   indicator_configs.json  configurations the strategy's indicators are calculated from,
+//                           including the pipeline inputs they refer to by label
+//   backtests.jsonl         recent backtest results as full rows with their trades
+//   equity/<id>.csv         equity curve of each backtest (time,equity), from its trades
+//
+// Importing saves the strategy, creates or enables the configurations and stores the
+// backtests under new IDs; backtests already imported before are skipped.
+use crate::database::models::IndicatorConfig;
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::events::{record_event, EventKind};
+use crate::indicators::pipeline;
+use crate::strategy::import_export::{provision_indicator_configs, RequiredConfig, StrategyFormat};
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::Strategy;
+use crate::strategy::validator::validate_strategy;
+use crate::strategy::{script, statistics, watchlist};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Row;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tracing::info;
+
+/// Version of the bundle layout written by this build
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Contents of manifest.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub strategy_id: String,
+    pub strategy_name: String,
+    /// IDs of the bundled backtests in the exporting database
+    pub backtest_ids: Vec<i32>,
+    pub indicator_configs: usize,
+}
+
+/// Outcome of importing a bundle
+#[derive(Debug, Clone)]
+pub struct BundleImport {
+    pub manifest: BundleManifest,
+    pub strategy: Strategy,
+    /// Configurations that were created or enabled
+    pub provisioned: Vec<RequiredConfig>,
+    pub backtests_imported: usize,
+    /// Backtests that were already in the database
+    pub backtests_skipped: usize,
+}
+
+/// Configurations the strategy's stored indicators are calculated from on `symbols`, the
+/// strategy's assets with watchlists resolved
+///
+/// Adds the configurations they take their input from, recursively, so imported
+/// pipelines resolve.
+fn strategy_configs(strategy: &Strategy, symbols: &[String], configs: &[IndicatorConfig]) -> Vec<IndicatorConfig> {
+    let mut bundled: Vec<IndicatorConfig> = configs.iter()
+        .filter(|config| symbols.contains(&config.symbol) && strategy.timeframes.contains(&config.interval))
+        .filter(|config| strategy.indicators.iter().any(|indicator| {
+            !script::is_script_indicator(indicator)
+                && !statistics::needs_frame(indicator)
+                && indicator.indicator_name == config.indicator_name
+                && indicator.parameters == config.parameters
+        }))
+        .cloned()
+        .collect();
+
+    let mut next = 0;
+    while next < bundled.len() {
+        let config = bundled[next].clone();
+        next += 1;
+
+        let label = match pipeline::input(&config.parameters) {
+            Ok(Some(input)) => input.label,
+            _ => continue,
+        };
+        let upstream = configs.iter().find(|candidate| {
+            candidate.symbol == config.symbol
+                && candidate.interval == config.interval
+                && pipeline::label(&candidate.parameters) == Some(label.as_str())
+        });
+        if let Some(upstream) = upstream {
+            if !bundled.iter().any(|b| b.id == upstream.id) {
+                bundled.push(upstream.clone());
+            }
+        }
+    }
+
+    bundled
+}
+
+/// Equity after each trade of a backtest row, starting with the initial capital
+fn equity_curve(backtest: &Value) -> String {
+    let number = |value: Option<&Value>| value.and_then(Value::as_f64).unwrap_or(0.0);
+
+    let mut equity = number(backtest.get("initial_capital"));
+    let mut csv = String::from("time,equity\n");
+    if let Some(start) = backtest.get("start_date").and_then(Value::as_str) {
+        csv.push_str(&format!("{},{}\n", start, equity));
+    }
+
+    let mut trades: Vec<&Value> = backtest.get("trades")
+        .and_then(Value::as_array)
+        .map(|trades| trades.iter().collect())
+        .unwrap_or_default();
+    trades.sort_by_key(|trade| trade.get("exit_time").and_then(Value::as_str).unwrap_or_default().to_string());
+
+    for trade in trades {
+        equity += number(trade.get("profit_loss_amount"));
+        let time = trade.get("exit_time").and_then(Value::as_str).unwrap_or_default();
+        csv.push_str(&format!("{},{}\n", time, equity));
+    }
+
+    csv
+}
+
+fn append<W: std::io::Write>(builder: &mut tar::Builder<W>, name: &str, content: &[u8], mtime: u64) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    builder.append_data(&mut header, name, content)?;
+    Ok(())
+}
+
+/// Write a bundle of a strategy with its `backtests` most recent results
+pub async fn export_bundle(
+    repository: &StrategyRepository,
+    strategy_id: &str,
+    backtests: i64,
+    path: &Path,
+) -> Result<BundleManifest> {
+    let strategy = repository.get_strategy(strategy_id).await?;
+    let pg = repository.get_db_connection();
+
+    let symbols = watchlist::resolve_assets(&pg, &strategy.assets).await?;
+    let configs = strategy_configs(&strategy, &symbols, &pg.get_all_indicator_configs().await?);
+
+    let rows = sqlx::query(
+        "SELECT r.id, to_jsonb(r) || jsonb_build_object('trades', COALESCE(
+             (SELECT jsonb_agg(to_jsonb(t) ORDER BY t.entry_time)
+              FROM strategy_backtest_trades t
+              WHERE t.backtest_id = r.id),
+             '[]'::jsonb))
+         FROM strategy_backtest_results r
+         WHERE r.strategy_id = $1::uuid
+         ORDER BY r.created_at DESC
+         LIMIT $2"
+    )
+    .bind(&strategy.id)
+    .bind(backtests)
+    .fetch_all(&pg.pool)
+    .await?;
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        created_at: Utc::now(),
+        strategy_id: strategy.id.clone(),
+        strategy_name: strategy.name.clone(),
+        backtest_ids: rows.iter().map(|row| row.get(0)).collect(),
+        indicator_configs: configs.len(),
+    };
+    let mtime = manifest.created_at.timestamp().max(0) as u64;
+
+    let file = File::create(path).map_err(Error::file("create", path))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    append(&mut builder, "manifest.json", &serde_json::to_vec_pretty(&manifest)?, mtime)?;
+    append(&mut builder, "strategy.json", StrategyFormat::Json.serialize(&strategy)?.as_bytes(), mtime)?;
+    append(&mut builder, "indicator_configs.json", &serde_json::to_vec_pretty(&configs)?, mtime)?;
+
+    let mut backtest_lines = Vec::new();
+    for row in &rows {
+        let value: Value = row.get(1);
+        serde_json::to_writer(&mut backtest_lines, &value)?;
+        backtest_lines.push(b'\n');
+    }
+    append(&mut builder, "backtests.jsonl", &backtest_lines, mtime)?;
+
+    for row in &rows {
+        let id: i32 = row.get(0);
+        let value: Value = row.get(1);
+        append(&mut builder, &format!("equity/{}.csv", id), equity_curve(&value).as_bytes(), mtime)?;
+    }
+
+    builder.into_inner()?.finish().map_err(Error::file("write", path))?;
+
+    info!("Exported strategy {} with {} backtests and {} indicator configurations to {}",
+          strategy.id, manifest.backtest_ids.len(), configs.len(), path.display());
+    Ok(manifest)
+}
+
+// Entries of a bundle by path
+fn read_entries(path: &Path) -> Result<HashMap<String, Vec<u8>>> {
+    let file = File::open(path).map_err(Error::file("open", path))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut entries = HashMap::new();
+    for entry in archive.entries().map_err(Error::file("read", path))? {
+        let mut entry = entry.map_err(Error::file("read", path))?;
+        let name = entry.path().map_err(Error::file("read", path))?.to_string_lossy().into_owned();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(Error::file("read", path))?;
+        entries.insert(name, content);
+    }
+
+    Ok(entries)
+}
+
+// Store one bundled backtest row for `strategy_id`; None when it was imported before
+async fn insert_backtest(pg: &PostgresManager, strategy_id: &str, mut backtest: Value) -> Result<Option<i32>> {
+    let trades = backtest.as_object_mut()
+        .and_then(|row| row.remove("trades"))
+        .unwrap_or_else(|| Value::Array(Vec::new()));
+    let row = backtest.as_object_mut()
+        .ok_or_else(|| Error::validation("bundled backtest is not a JSON object"))?;
+    row.insert("strategy_id".to_string(), Value::from(strategy_id));
+    row.insert("namespace".to_string(), Value::from(pg.namespace()));
+    // The archive file of an archived result stays with the exporting installation
+    row.remove("archived_at");
+    row.remove("archive_key");
+
+    let mut tx = pg.pool.begin().await?;
+
+    let id: Option<i32> = sqlx::query(
+        "INSERT INTO strategy_backtest_results
+         SELECT (s.r).* FROM (
+             SELECT jsonb_populate_record(NULL::strategy_backtest_results, $1::jsonb
+                 || jsonb_build_object('id', nextval(pg_get_serial_sequence('strategy_backtest_results', 'id')))) AS r
+         ) s
+         WHERE NOT EXISTS (
+             SELECT 1 FROM strategy_backtest_results e
+             WHERE e.strategy_id = (s.r).strategy_id AND e.symbol = (s.r).symbol
+               AND e.interval = (s.r).interval AND e.created_at = (s.r).created_at
+         )
+         RETURNING id"
+    )
+    .bind(&backtest)
+    .fetch_optional(&mut *tx)
+    .await?
+    .map(|row| row.get(0));
+
+    if let Some(id) = id {
+        sqlx::query(
+            "INSERT INTO strategy_backtest_trades
+             (backtest_id, is_long, entry_price, exit_price, size_percent, entry_time, exit_time,
+              exit_reason, profit_loss_percent, profit_loss_amount)
+             SELECT $1, is_long, entry_price, exit_price, size_percent, entry_time, exit_time,
+                    exit_reason, profit_loss_percent, profit_loss_amount
+             FROM jsonb_populate_recordset(NULL::strategy_backtest_trades, $2::jsonb)"
+        )
+        .bind(id)
+        .bind(&trades)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(id)
+}
+
+/// Import a bundle written by `export_bundle`
+pub async fn import_bundle(repository: &StrategyRepository, path: &Path) -> Result<BundleImport> {
+    let entries = read_entries(path)?;
+    let entry = |name: &str| entries.get(name)
+        .ok_or_else(|| Error::validation(format!("{} is not a strategy bundle: {} is missing", path.display(), name)));
+
+    let manifest: BundleManifest = serde_json::from_slice(entry("manifest.json")?)?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(Error::validation(format!(
+            "bundle format version {} is newer than the supported version {}",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        )));
+    }
+
+    let content = String::from_utf8_lossy(entry("strategy.json")?);
+    let mut strategy = StrategyFormat::Json.parse(&content)?;
+    validate_strategy(&strategy)?.to_result()?;
+    repository.assign_free_id(&mut strategy).await?;
+    repository.save_strategy(&strategy).await?;
+
+    // Configurations that are missing or disabled here
+    let pg = repository.get_db_connection();
+    let bundled: Vec<IndicatorConfig> = serde_json::from_slice(entry("indicator_configs.json")?)?;
+    let existing = pg.get_all_indicator_configs().await?;
+    let mut provisioned: Vec<RequiredConfig> = Vec::new();
+    for config in bundled {
+        let current = existing.iter().find(|e| {
+            e.symbol == config.symbol && e.interval == config.interval
+                && e.indicator_name == config.indicator_name && e.parameters == config.parameters
+        });
+        if current.is_some_and(|current| current.enabled) {
+            continue;
+        }
+        provisioned.push(RequiredConfig {
+            symbol: config.symbol,
+            interval: config.interval,
+            indicator_type: config.indicator_type,
+            indicator_name: config.indicator_name,
+            parameters: config.parameters,
+            disabled_id: current.map(|current| current.id),
+        });
+    }
+    provision_indicator_configs(&pg, &provisioned).await?;
+
+    let (mut backtests_imported, mut backtests_skipped) = (0, 0);
+    let lines = String::from_utf8_lossy(entry("backtests.jsonl")?).into_owned();
+    for line in lines.lines().filter(|line| !line.trim().is_empty()) {
+        match insert_backtest(&pg, &strategy.id, serde_json::from_str(line)?).await? {
+            Some(_) => backtests_imported += 1,
+            None => backtests_skipped += 1,
+        }
+    }
+
+    info!("Imported strategy {} from {}: {} backtests, {} indicator configurations provisioned",
+          strategy.id, path.display(), backtests_imported, provisioned.len());
+    record_event(
+        &pg,
+        EventKind::StrategyImported,
+        Some(&strategy.id),
+        &format!("Imported strategy '{}' version {} from bundle {}", strategy.name, strategy.version, path.display()),
+        serde_json::json!({
+            "name": strategy.name,
+            "version": strategy.version,
+            "file": path.display().to_string(),
+            "backtests_imported": backtests_imported,
+            "indicator_configs_provisioned": provisioned.len(),
+        }),
+    ).await;
+
+    Ok(BundleImport {
+        manifest,
+        strategy,
+        provisioned,
+        backtests_imported,
+        backtests_skipped,
+    })
+}
@@ -1,41 +1,88 @@
 // src/strategy/cli_handler.rs
-use crate::cli::Commands;
-use crate::database::postgres::PostgresManager;
-use crate::strategy::evaluator::StrategyEvaluator;
+use crate::cli::{
+    AdjustmentCommands, ArchiveCommands, BacktestCommands, CalcCommands, Cli, Commands, ConfigCommands, DataCommands, DbCommands,
+    EngineCommands, EventsCommands, IndicatorsCommands, JobsCommands, PaperCommands, ReevaluateCommands, RetentionCommands, RiskCommands, StrategyCommands,
+    SymbolsCommands, TagCommands, TuneCommands, VerifyCommands
+};
+use crate::cache::redis::RedisManager;
+use crate::cache::backtests::BacktestCache;
+use crate::cache::results::IndicatorResultCache;
+use crate::config::{apply_manifest, AppConfig, IndicatorManifest};
+use crate::data_source::{BinanceFundingSource, BinanceSource, CsvSource};
+use crate::database::adjustments::validate_adjustment;
+use crate::database::audit::{audit_candles, backfill_gaps};
+use crate::database::retention::apply_retention;
+use crate::events::{self, Event, EventKind};
+use crate::execution::journal::OrderJournal;
+use crate::execution::paper::PaperExecutor;
+use crate::execution::{OrderRouter, RiskGuard, RiskStatus};
+use crate::signals::ledger::SignalLedger;
+use crate::signals::webhook::WebhookNotifier;
+use crate::indicators::catalog::{self, IndicatorInfo};
+use crate::indicators::golden::{self, CaseStatus, GoldenCase};
+use crate::database::models::{CandleData, SymbolRecord};
+use crate::indicators::calculator::IndicatorCalculator;
+use crate::indicators::output::IndicatorOutput;
+use crate::indicators::cross_symbol;
+use crate::indicators::export::{export_indicator_series, print_records, print_series, ExportQuery, PrintFormat};
+use crate::processor::anomaly::AnomalySettings;
+use crate::processor::job::CalculationJob;
+use crate::processor::worker::{Worker, WorkerConfig};
+use crate::strategy::evaluator::{IndicatorSource, StrategyEvaluator};
+use crate::strategy::listing::{self, MetricFilter, StrategyFilter, StrategySort};
 use crate::strategy::repository::StrategyRepository;
-use crate::strategy::import_export::{import_strategy_from_file, export_strategy_to_file};
-use anyhow::{Result, Context};
-use chrono::{DateTime, Utc};
+use crate::strategy::import_export::{
+    import_strategy_from_file, export_strategy_to_file, provision_indicator_configs, read_strategy_file,
+    required_indicator_configs, write_strategy_file, StrategyFormat
+};
+use crate::strategy::archive::{ArchivePolicy, BacktestArchiver};
+use crate::strategy::bundle::{export_bundle, import_bundle};
+use crate::strategy::comparison::BacktestComparison;
+use crate::strategy::batch;
+use crate::strategy::editor;
+use crate::strategy::genetic::{get_run_candidates, init_tables, GeneticOptimizer, GeneticSettings, SearchTarget};
+use crate::strategy::optimizer::{LlmClient, OptimizationSettings, StrategyOptimizer};
+use crate::strategy::reevaluation::{self, recent_reevaluations, Reevaluation, Reevaluator};
+use crate::strategy::engine_settings::EngineSettings;
+use crate::strategy::schema::{Strategy, StrategyIndicator, StrategyPerformance, StrategyRule};
+use crate::strategy::templates::build_strategy_from_template;
+use crate::utils::chart::{self, ChartSeries};
+use crate::utils::resources::{ResourceTracker, ResourceUsage};
+use crate::strategy::watchlist::{self, Watchlist};
+use crate::utils::session::SessionCalendar;
+use crate::strategy::validator::{
+    validate_strategy, validate_data_coverage, validate_data_coverage_for, ValidationResult
+};
+use anyhow::{anyhow, Result, Context};
+use clap::CommandFactory;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
 use std::env;
 use std::path::Path;
 use std::sync::Arc;
-use std::process::Command;
 use tracing::{info, warn, error};
 
-/// Create a database connection and repository
+/// Create a repository on the connection pool shared by the commands of this process
 pub async fn create_repository() -> Result<StrategyRepository> {
-    // Get database configuration from environment
-    let db_host = env::var("DB_HOST").unwrap_or_else(|_| "localhost".to_string());
-    let db_port = env::var("DB_PORT").unwrap_or_else(|_| "5432".to_string()).parse::<u16>()?;
-    let db_user = env::var("DB_USER").unwrap_or_else(|_| "binanceuser".to_string());
-    let db_password = env::var("DB_PASSWORD").unwrap_or_else(|_| "binancepass".to_string());
-    let db_name = env::var("DB_NAME").unwrap_or_else(|_| "binancedb".to_string());
-    
-    // Create PostgreSQL connection
-    let pg = PostgresManager::new(
-        &db_host,
-        db_port,
-        &db_user,
-        &db_password,
-        &db_name,
-        10, // Max connections
-    )
-    .await?;
-    
-    // Create the repository
-    let repository = StrategyRepository::new(Arc::new(pg));
-    
-    Ok(repository)
+    // Get database configuration from config.toml and the environment
+    let config = AppConfig::load()?;
+    let pg = config.database.shared().await?;
+    pg.init_engine_settings_table().await?;
+    
+    Ok(StrategyRepository::new(pg))
+}
+
+/// Connect to Redis for the indicator result and backtest caches, or None when it is unavailable
+async fn connect_cache_redis() -> Option<Arc<RedisManager>> {
+    let config = AppConfig::load().ok()?;
+    
+    match RedisManager::new(&config.redis.url, config.redis.cache_ttl_seconds, 2).await {
+        Ok(redis) => Some(Arc::new(redis)),
+        Err(e) => {
+            info!("Indicator result and backtest caches disabled, Redis unavailable: {}", e);
+            None
+        }
+    }
 }
 
 /// Parse an ISO date string to DateTime<Utc>
@@ -58,20 +105,54 @@ fn format_strategy_for_display(strategy_id: &str, name: &str, version: &str, ena
 }
 
 /// Execute a command from the CLI
-pub async fn execute_command(command: Commands) -> Result<()> {
+pub async fn execute_command(command: Commands, output: PrintFormat) -> Result<()> {
     match command {
-        Commands::Start { .. } | Commands::Stop | Commands::Status => {
+        Commands::Start { .. } | Commands::Stop | Commands::Status { .. } => {
             // These commands are handled in main.rs
             unreachable!("Start/Stop/Status commands should be handled in main.rs");
         },
+        #[cfg(feature = "grpc")]
+        Commands::Serve { .. } => {
+            unreachable!("Serve is handled in main.rs");
+        },
         
-        Commands::List { enabled_only } => {
+        Commands::List { enabled_only, asset, timeframe, author, tag, metric, search, sort, desc } => {
+            let filter = StrategyFilter {
+                enabled_only,
+                asset,
+                timeframe,
+                author,
+                search,
+                tags: tag.iter().map(|t| editor::normalize_tag(t)).collect::<Result<_, _>>()?,
+                metrics: metric.iter().map(|m| MetricFilter::parse(m)).collect::<Result<_, _>>()?,
+                sort: StrategySort::parse(&sort)?,
+                descending: desc,
+            };
+            
             // Create repository
             let repository = create_repository().await?;
             
-            let strategies = repository.list_strategies(enabled_only).await?;
+            let strategies = repository.find_strategies(&filter).await?;
+            
+            if output != PrintFormat::Table {
+                let records: Vec<serde_json::Value> = strategies.iter()
+                    .map(|strategy| serde_json::json!({
+                        "id": strategy.id,
+                        "name": strategy.name,
+                        "version": strategy.version,
+                        "enabled": strategy.enabled,
+                        "author": strategy.author,
+                        "assets": strategy.assets,
+                        "timeframes": strategy.timeframes,
+                        "tags": strategy.tags,
+                        "updated_at": strategy.updated_at,
+                    }))
+                    .collect();
+                print_records(std::io::stdout().lock(), output, &records)?;
+                return Ok(());
+            }
             
-            println!("Found {} strategies:", strategies.len());
+            println!("Found {} strategies in namespace {}:", strategies.len(), repository.namespace());
             println!("{:<8} | {:<30} | {:<10} | {:<10}", "ID", "Name", "Version", "Status");
             println!("{:-<8}-+-{:-<30}-+-{:-<10}-+-{:-<10}", "", "", "", "");
             
@@ -99,6 +180,44 @@ pub async fn execute_command(command: Commands) -> Result<()> {
                 return Ok(());
             }
             
+            // JSON is the strategy as stored; CSV lists its indicators, rules and parameters
+            match output {
+                PrintFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&strategy)?);
+                    return Ok(());
+                },
+                PrintFormat::Csv => {
+                    let mut records = Vec::new();
+                    for indicator in &strategy.indicators {
+                        records.push(serde_json::json!({
+                            "section": "indicator",
+                            "id": indicator.id,
+                            "name": indicator.indicator_name,
+                            "value": indicator.parameters,
+                        }));
+                    }
+                    for rule in &strategy.rules {
+                        records.push(serde_json::json!({
+                            "section": "rule",
+                            "id": rule.id,
+                            "name": rule.name,
+                            "value": rule.action,
+                        }));
+                    }
+                    for (name, param) in &strategy.parameters {
+                        records.push(serde_json::json!({
+                            "section": "parameter",
+                            "id": name,
+                            "name": name,
+                            "value": param,
+                        }));
+                    }
+                    print_records(std::io::stdout().lock(), output, &records)?;
+                    return Ok(());
+                },
+                PrintFormat::Table => {},
+            }
+            
             // Otherwise, display the strategy details
             println!("\n=== STRATEGY DETAILS ===");
             println!("ID: {}", strategy.id);
@@ -111,6 +230,9 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             println!("Status: {}", if strategy.enabled { "Enabled" } else { "Disabled" });
             println!("Assets: {}", strategy.assets.join(", "));
             println!("Timeframes: {}", strategy.timeframes.join(", "));
+            if !strategy.tags.is_empty() {
+                println!("Tags: {}", strategy.tags.join(", "));
+            }
             
             println!("\n=== INDICATORS ({}) ===", strategy.indicators.len());
             for indicator in &strategy.indicators {
@@ -141,6 +263,12 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             println!("Default Position Size: {}%", strategy.risk_management.default_position_size);
             println!("Default Stop Loss: {:?}%", strategy.risk_management.default_stop_loss);
             println!("Default Take Profit: {:?}%", strategy.risk_management.default_take_profit);
+            if let Some(level) = &strategy.risk_management.stop_loss_level {
+                println!("Stop Loss Level: {}", serde_json::to_string(level)?);
+            }
+            if let Some(level) = &strategy.risk_management.take_profit_level {
+                println!("Take Profit Level: {}", serde_json::to_string(level)?);
+            }
             println!("Use Trailing Stop: {}", strategy.risk_management.use_trailing_stop);
             if strategy.risk_management.use_trailing_stop {
                 println!("  Trailing Stop Activation: {:?}%", strategy.risk_management.trailing_stop_activation);
@@ -174,9 +302,11 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             }
         },
         
-        Commands::Import { file } => {
+        Commands::Import { file, provision } => {
             // Create repository
-            let repository = create_repository().await?;
+            let config = AppConfig::load()?;
+            let pg = config.database.shared().await?;
+            let repository = StrategyRepository::new(pg.clone());
             
             // Import the strategy
             let strategy = import_strategy_from_file(&repository, &file).await?;
@@ -189,17 +319,47 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             println!("Timeframes: {}", strategy.timeframes.join(", "));
             println!("Indicators: {}", strategy.indicators.len());
             println!("Rules: {}", strategy.rules.len());
+            
+            // Indicator configurations the worker needs to calculate for this strategy
+            let required = required_indicator_configs(&strategy, &pg).await?;
+            if required.is_empty() {
+                println!("\nAll indicators are configured for every asset and timeframe");
+            } else if provision {
+                provision_indicator_configs(&pg, &required).await?;
+                println!("\nProvisioned {} indicator configurations:", required.len());
+            } else {
+                println!("\nMissing indicator configurations (import with --provision to create them):");
+            }
+            
+            for config in &required {
+                let action = if config.disabled_id.is_some() { "enable" } else { "create" };
+                println!("  {:<6} {}:{} {} {}", action, config.symbol, config.interval, 
+                         config.indicator_name, config.parameters);
+            }
+        },
+        
+        Commands::Backtest { command: Some(command), .. } => {
+            execute_backtest_command(command, output).await?;
         },
         
         Commands::Backtest { 
-            strategy_id, 
-            symbol, 
-            interval, 
+            command: None,
+            strategy_id: Some(strategy_id), 
+            symbol: Some(symbol), 
+            interval: Some(interval), 
             start_date, 
             end_date, 
             initial_capital, 
-            export 
+            export,
+            engine_profile,
+            recalculate,
+            no_cache,
+            seed
         } => {
+            if watchlist::is_watchlist(&symbol) {
+                return Err(anyhow!("{} is a watchlist; use 'backtest batch --symbols {}' to backtest its symbols", symbol, symbol));
+            }
+            
             // Create repository
             let repository = create_repository().await?;
             
@@ -210,11 +370,53 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             let start_date = start_date.map(|d| parse_date(&d)).transpose()?;
             let end_date = end_date.map(|d| parse_date(&d)).transpose()?;
             
+            // Warn about indicators that have no configuration or calculated data
+            let mut coverage = ValidationResult::new();
+            validate_data_coverage_for(&strategy, &repository.get_db_connection(), &symbol, &interval, &mut coverage).await?;
+            // Progress and warnings go to stderr when stdout is JSON or CSV
+            let table = output == PrintFormat::Table;
+            if coverage.has_warnings() {
+                if table { println!("{}", coverage.summary()) } else { eprintln!("{}", coverage.summary()) }
+            }
+            
+            // Load the engine settings profile
+            let engine_settings = repository.get_engine_settings(&engine_profile).await?;
+            
+            // Load the contract specification for PnL accounting
+            let contract = repository.get_contract_spec(&symbol).await?;
+            
+            // Perpetual futures pay or receive funding while a position is open
+            let funding_rates = if contract.apply_funding {
+                let rates = repository.get_funding_rates(&symbol, start_date, end_date).await?;
+                if rates.is_empty() {
+                    eprintln!("Warning: no funding rates stored for {}; run 'data funding --symbol {}' first", symbol, symbol);
+                }
+                rates
+            } else {
+                Vec::new()
+            };
+            
             // Create evaluator
-            let evaluator = StrategyEvaluator::new(repository.get_db_connection(), initial_capital);
+            let mut evaluator = StrategyEvaluator::new(repository.get_db_connection(), initial_capital)
+                .with_engine_settings(engine_settings.clone())
+                .with_contract_spec(contract)
+                .with_funding_rates(funding_rates)
+                .with_indicator_source(if recalculate { IndicatorSource::Calculate } else { IndicatorSource::Precomputed })
+                .with_seed(seed);
+            
+            // Redis is optional; without it every calculated indicator is recalculated
+            if let Some(redis) = connect_cache_redis().await {
+                evaluator = evaluator.with_result_cache(Arc::new(IndicatorResultCache::new(redis.clone())));
+                if !no_cache {
+                    evaluator = evaluator.with_backtest_cache(Arc::new(BacktestCache::new(redis)));
+                }
+            }
             
             // Run backtest
-            println!("Running backtest for strategy {} on {}:{}", strategy.name, symbol, interval);
+            let running = format!("Running backtest for strategy {} on {}:{} (engine profile: {})", 
+                                  strategy.name, symbol, interval, engine_settings.name);
+            if table { println!("{}", running) } else { eprintln!("{}", running) }
+            let tracker = ResourceTracker::start();
             let performance = evaluator.backtest(&strategy, &symbol, &interval, start_date, end_date).await?;
             
             // Save results to database
@@ -228,8 +430,25 @@ pub async fn execute_command(command: Commands) -> Result<()> {
                 &performance
             ).await?;
             
+            // Record the engine settings and seed so results stay reproducible
+            repository.record_backtest_engine_settings(backtest_id, &evaluator).await?;
+            
+            // Record the resources used by this run
+            tracker.add_rows_written(1);
+            let usage = tracker.finish();
+            repository.record_backtest_resource_usage(backtest_id, &usage).await?;
+            
+            if !table {
+                print_backtest_result(output, backtest_id, seed, &performance, &usage)?;
+                if let Some(export_path) = export {
+                    std::fs::write(&export_path, serde_json::to_string_pretty(&performance)?)?;
+                    eprintln!("Results exported to: {}", export_path.display());
+                }
+                return Ok(());
+            }
+            
             // Display results
-            println!("\nBacktest Results (ID: {}):", backtest_id);
+            println!("\nBacktest Results (ID: {}, seed {}):", backtest_id, seed);
             println!("Total Trades: {}", performance.total_trades);
             println!("Win Rate: {:.2}%", performance.win_rate);
             println!("Total Return: {:.2}%", performance.total_return);
@@ -250,6 +469,27 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             println!("Avg Loss Holding Period: {:.2} hours", performance.avg_loss_holding_period);
             println!("Annualized Return: {:.2}%", performance.annualized_return);
             
+            println!("\nBenchmark (Buy and Hold):");
+            println!("Benchmark Return: {:.2}%", performance.benchmark_return);
+            println!("Excess Return: {:.2}%", performance.excess_return);
+            println!("Alpha (annualized): {:.2}%", performance.alpha);
+            println!("Beta: {:.2}", performance.beta);
+            
+            println!("\nRisk:");
+            println!("Sortino Ratio: {:.2}", performance.sortino_ratio);
+            println!("Calmar Ratio: {:.2}", performance.calmar_ratio);
+            println!("Value at Risk (95%): {:.2}%", performance.value_at_risk);
+            println!("Expected Shortfall (95%): {:.2}%", performance.expected_shortfall);
+            println!("Exposure: {:.1}%", performance.exposure_percent);
+            println!("Turnover: {:.2}x", performance.turnover);
+            println!("Unfilled Orders: {}", performance.unfilled_orders);
+            
+            println!("\nResource Usage:");
+            println!("Wall Time: {:.2}s", usage.wall_time_ms as f64 / 1000.0);
+            println!("CPU Time: {:.2}s", usage.cpu_time_ms as f64 / 1000.0);
+            println!("Peak Memory: {:.1} MB", usage.peak_memory_bytes as f64 / (1024.0 * 1024.0));
+            println!("Rows Read/Written: {}/{}", usage.rows_read, usage.rows_written);
+            
             // Export if requested
             if let Some(export_path) = export {
                 let json = serde_json::to_string_pretty(&performance)?;
@@ -258,6 +498,10 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             }
         },
         
+        Commands::Backtest { .. } => {
+            return Err(anyhow!("--strategy-id, --symbol and --interval are required to run a backtest"));
+        },
+        
         Commands::Optimize { 
             strategy_id, 
             symbol, 
@@ -266,49 +510,2045 @@ pub async fn execute_command(command: Commands) -> Result<()> {
             end_date, 
             initial_capital, 
             max_iterations, 
-            output 
+            engine_profile, 
+            output, 
+            dry_run 
         } => {
-            // Since the optimizer is in Python, call it as a separate process
-            let mut command = Command::new("python3");
+            let config = AppConfig::load()?;
+            let repository = create_repository().await?;
+            let strategy = repository.get_strategy(&strategy_id).await?;
+            
+            let settings = OptimizationSettings {
+                symbol,
+                interval,
+                start_date: start_date.map(|d| parse_date(&d)).transpose()?,
+                end_date: end_date.map(|d| parse_date(&d)).transpose()?,
+                initial_capital,
+                engine_profile,
+                max_iterations,
+            };
+            
+            println!("Optimizing strategy {} with {} at {} ({} iterations)...", 
+                     strategy.name, config.optimizer.model, config.optimizer.endpoint, max_iterations);
+            let optimizer = StrategyOptimizer::new(&repository, LlmClient::new(&config.optimizer)?, settings);
+            let report = optimizer.run(&strategy).await?;
+            
+            std::fs::write(&output, report.to_markdown())
+                .context(format!("Failed to write report: {}", output.display()))?;
+            println!("Optimization report written to: {}", output.display());
+            
+            if !report.improved() {
+                println!("No configuration beat the original strategy.");
+            } else if dry_run {
+                println!("Iteration {} performed best; not saved (--dry-run).", report.best_iteration);
+            } else {
+                save_validated_strategy(&repository, &report.best).await?;
+                println!("Iteration {} performed best; strategy {} updated.", report.best_iteration, strategy.id);
+            }
+        },
+        
+        Commands::Tune { command } => {
+            execute_tune_command(command).await?;
+        },
+        
+        Commands::Reevaluate { command } => {
+            execute_reevaluate_command(command).await?;
+        },
+        
+        Commands::Events { command } => {
+            execute_events_command(command).await?;
+        },
+        
+        Commands::Indicators { command } => {
+            execute_indicators_command(command)?;
+        },
+        
+        Commands::Verify { command } => {
+            execute_verify_command(command)?;
+        },
+        
+        Commands::Strategy { command } => {
+            execute_strategy_command(command).await?;
+        },
+        
+        Commands::Engine { command } => {
+            execute_engine_command(command).await?;
+        },
+        
+        Commands::Archive { command } => {
+            execute_archive_command(command).await?;
+        },
+        
+        Commands::Export { symbol, interval, indicator, parameters, start_date, end_date, format, output } => {
+            let parameters: serde_json::Value = serde_json::from_str(&parameters)
+                .context("Failed to parse indicator parameters JSON")?;
+            
+            let query = ExportQuery {
+                symbol,
+                interval,
+                indicator_name: indicator,
+                parameters,
+                start: start_date.map(|d| parse_date(&d)).transpose()?,
+                end: end_date.map(|d| parse_date(&d)).transpose()?,
+            };
+            
+            let compress = output.extension().map_or(false, |ext| ext == "gz");
+            
+            let repository = create_repository().await?;
+            let rows = export_indicator_series(&repository.get_db_connection(), &query, format, &output, compress).await?;
+            
+            println!("Exported {} rows to {}", rows, output.display());
+        },
+        
+        Commands::Data { command } => {
+            execute_data_command(command).await?;
+        },
+        
+        Commands::Symbols { command } => {
+            execute_symbols_command(command, output).await?;
+        },
+        
+        Commands::Jobs { command } => {
+            execute_jobs_command(command).await?;
+        },
+        
+        Commands::Db { command } => {
+            execute_db_command(command).await?;
+        },
+        
+        Commands::Calc { command } => {
+            execute_calc_command(command).await?;
+        },
+        
+        Commands::Config { command } => {
+            execute_config_command(command).await?;
+        },
+        
+        Commands::Paper { command } => {
+            execute_paper_command(command).await?;
+        },
+        
+        Commands::Risk { command } => {
+            execute_risk_command(command).await?;
+        },
+        
+        Commands::Completions { shell } => {
+            let mut command = Cli::command();
+            let name = command.get_name().to_string();
+            clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        },
+    }
+    
+    Ok(())
+}
+
+/// Print batch results in rank order, followed by the backtests that failed
+fn print_batch_results(output: PrintFormat, results: &[batch::BatchResult], metric: &str, column: &str) -> Result<()> {
+    if output != PrintFormat::Table {
+        let records: Vec<serde_json::Value> = results.iter()
+            .enumerate()
+            .map(|(i, result)| {
+                let (backtest_id, performance, error) = match &result.outcome {
+                    Ok((id, performance)) => (Some(*id), Some(performance), None),
+                    Err(e) => (None, None, Some(e)),
+                };
+                serde_json::json!({
+                    "rank": backtest_id.map(|_| i + 1),
+                    "strategy_id": result.strategy_id,
+                    "strategy_name": result.strategy_name,
+                    "symbol": result.symbol,
+                    "interval": result.interval,
+                    "backtest_id": backtest_id,
+                    metric: result.metric(column),
+                    "total_return": performance.map(|p| p.total_return),
+                    "sharpe_ratio": performance.map(|p| p.sharpe_ratio),
+                    "max_drawdown": performance.map(|p| p.max_drawdown),
+                    "total_trades": performance.map(|p| p.total_trades),
+                    "error": error,
+                })
+            })
+            .collect();
+        print_records(std::io::stdout().lock(), output, &records)?;
+        return Ok(());
+    }
+    
+    println!("\n{:<4} | {:<24} | {:<10} | {:<8} | {:<10} | {:<9} | {:<7} | {:<8} | {:<6} | {}", 
+             "Rank", "Strategy", "Symbol", "Interval", metric, "Return", "Sharpe", "Max DD", "Trades", "ID");
+    println!("{:-<4}-+-{:-<24}-+-{:-<10}-+-{:-<8}-+-{:-<10}-+-{:-<9}-+-{:-<7}-+-{:-<8}-+-{:-<6}-+-{:-<6}", 
+             "", "", "", "", "", "", "", "", "", "");
+    
+    let mut failed = Vec::new();
+    for (i, result) in results.iter().enumerate() {
+        let (backtest_id, performance) = match &result.outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                failed.push((result, e));
+                continue;
+            }
+        };
+        let name: String = result.strategy_name.chars().take(24).collect();
+        println!("{:<4} | {:<24} | {:<10} | {:<8} | {:<10.2} | {:<9} | {:<7.2} | {:<8} | {:<6} | {}", 
+                 i + 1, name, result.symbol, result.interval, result.metric(column).unwrap_or_default(),
+                 format!("{:.2}%", performance.total_return), performance.sharpe_ratio,
+                 format!("{:.2}%", performance.max_drawdown), performance.total_trades, backtest_id);
+    }
+    
+    if !failed.is_empty() {
+        println!("\n{} backtests failed:", failed.len());
+        for (result, error) in failed {
+            println!("  {} on {}:{}: {}", result.strategy_name, result.symbol, result.interval, error);
+        }
+    }
+    
+    Ok(())
+}
+
+// Metrics shown by `backtest view`: column, label and unit
+const BACKTEST_METRICS: [(&str, &str, &str); 28] = [
+    ("total_trades", "Total Trades", ""),
+    ("winning_trades", "Winning Trades", ""),
+    ("losing_trades", "Losing Trades", ""),
+    ("win_rate", "Win Rate", "%"),
+    ("total_return", "Total Return", "%"),
+    ("annualized_return", "Annualized Return", "%"),
+    ("max_drawdown", "Max Drawdown", "%"),
+    ("sharpe_ratio", "Sharpe Ratio", ""),
+    ("sortino_ratio", "Sortino Ratio", ""),
+    ("calmar_ratio", "Calmar Ratio", ""),
+    ("profit_factor", "Profit Factor", ""),
+    ("expectancy", "Expectancy", ""),
+    ("avg_profit_per_win", "Avg Profit per Win", "%"),
+    ("avg_loss_per_loss", "Avg Loss per Loss", "%"),
+    ("max_consecutive_wins", "Max Consecutive Wins", ""),
+    ("max_consecutive_losses", "Max Consecutive Losses", ""),
+    ("avg_win_holding_period", "Avg Win Holding Period", " h"),
+    ("avg_loss_holding_period", "Avg Loss Holding Period", " h"),
+    ("benchmark_return", "Benchmark Return", "%"),
+    ("excess_return", "Excess Return", "%"),
+    ("alpha", "Alpha (annualized)", "%"),
+    ("beta", "Beta", ""),
+    ("value_at_risk", "Value at Risk (95%)", "%"),
+    ("expected_shortfall", "Expected Shortfall (95%)", "%"),
+    ("exposure_percent", "Exposure", "%"),
+    ("turnover", "Turnover", "x"),
+    ("unfilled_orders", "Unfilled Orders", ""),
+    ("final_capital", "Final Capital", ""),
+];
+
+/// Print the stored row of a backtest result as a metric table
+fn print_backtest_view(id: i32, result: &serde_json::Value) {
+    let text = |key: &str| match result.get(key) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Null) | None => "-".to_string(),
+        Some(other) => other.to_string(),
+    };
+    
+    println!("\n=== BACKTEST {} ===", id);
+    println!("Strategy: {} ({})", text("strategy_name"), text("strategy_id"));
+    println!("Symbol: {} {}", text("symbol"), text("interval"));
+    println!("Period: {} to {}", text("start_date"), text("end_date"));
+    println!("Initial Capital: {}", text("initial_capital"));
+    println!("Engine Profile: {} (seed {})", text("engine_profile"), text("seed"));
+    println!("Created: {}", text("created_at"));
+    if result.get("archived_at").is_some_and(|archived| !archived.is_null()) {
+        println!("Archived: {} (trades moved to {})", text("archived_at"), text("archive_key"));
+    }
+    
+    println!("\n{:<26} | {}", "Metric", "Value");
+    println!("{:-<26}-+-{:-<16}", "", "");
+    for (column, label, unit) in BACKTEST_METRICS {
+        let value = match result.get(column).and_then(serde_json::Value::as_f64) {
+            Some(value) if value.fract() == 0.0 && unit.is_empty() => format!("{}", value),
+            Some(value) => format!("{:.2}{}", value, unit),
+            None => "-".to_string(),
+        };
+        println!("{:<26} | {}", label, value);
+    }
+}
+
+/// Print a finished backtest as JSON, or as CSV with one metric per row
+fn print_backtest_result(output: PrintFormat, backtest_id: i32, seed: u64, 
+                         performance: &StrategyPerformance, usage: &ResourceUsage) -> Result<()> {
+    if output == PrintFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "backtest_id": backtest_id,
+            "seed": seed,
+            "performance": performance,
+            "resource_usage": usage,
+        }))?);
+        return Ok(());
+    }
+    
+    // Nested values such as the trade list don't fit a metric row and are left out
+    let mut metrics = vec![
+        serde_json::json!({ "metric": "backtest_id", "value": backtest_id }),
+        serde_json::json!({ "metric": "seed", "value": seed }),
+    ];
+    for (prefix, value) in [("", serde_json::to_value(performance)?), ("usage.", serde_json::to_value(usage)?)] {
+        if let serde_json::Value::Object(map) = value {
+            for (name, value) in map.into_iter().filter(|(_, value)| !value.is_array() && !value.is_object()) {
+                metrics.push(serde_json::json!({ "metric": format!("{}{}", prefix, name), "value": value }));
+            }
+        }
+    }
+    
+    print_records(std::io::stdout().lock(), output, &metrics)?;
+    Ok(())
+}
+
+/// Validate a strategy and save it, refusing to store strategies with errors
+async fn save_validated_strategy(repository: &StrategyRepository, strategy: &Strategy) -> Result<()> {
+    let validation = validate_strategy(strategy)?;
+    
+    if validation.has_errors() || validation.has_warnings() {
+        println!("{}", validation.summary());
+    }
+    validation.to_result()?;
+    
+    Ok(repository.save_strategy(strategy).await?)
+}
+
+/// Validate a strategy file and return the process exit code
+///
+/// 0 = valid, 1 = validation errors (or warnings with `strict`), 2 = unreadable or invalid JSON
+async fn validate_strategy_file(file: &Path, strict: bool, offline: bool) -> i32 {
+    // Reading and schema deserialization, in the format of the file extension
+    let strategy: Strategy = match read_strategy_file(file) {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            eprintln!("Failed to load strategy {}: {}", file.display(), e);
+            return 2;
+        }
+    };
+    
+    let mut validation = match validate_strategy(&strategy) {
+        Ok(validation) => validation,
+        Err(e) => {
+            eprintln!("Failed to validate strategy {}: {}", file.display(), e);
+            return 1;
+        }
+    };
+    
+    // Database coverage checks
+    if !offline {
+        let coverage = async {
+            let repository = create_repository().await?;
+            validate_data_coverage(&strategy, &repository.get_db_connection(), &mut validation).await
+        };
+        
+        if let Err(e) = coverage.await {
+            validation.add_warning(format!("Skipped database coverage checks: {}", e));
+        }
+    }
+    
+    println!("{}: {} ({})", file.display(), strategy.name, strategy.id);
+    println!("{}", validation.summary());
+    
+    if validation.has_errors() || (strict && validation.has_warnings()) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Execute a strategy authoring command
+async fn execute_strategy_command(command: StrategyCommands) -> Result<()> {
+    match command {
+        StrategyCommands::New { template, name, output, import } => {
+            // Generate the strategy from the template
+            let strategy = build_strategy_from_template(template, name.as_deref());
+            
+            // Templates must always produce valid strategies
+            let validation = validate_strategy(&strategy)?;
+            if validation.has_errors() || validation.has_warnings() {
+                println!("{}", validation.summary());
+            }
+            validation.to_result()?;
+            
+            if let Some(output_path) = &output {
+                write_strategy_file(&strategy, output_path)?;
+                println!("Strategy template written to: {}", output_path.display());
+            }
+            
+            if import {
+                let repository = create_repository().await?;
+                repository.save_strategy(&strategy).await?;
+                println!("Strategy imported successfully with ID: {}", strategy.id);
+            }
+            
+            // Print the strategy when it is neither saved to a file nor imported
+            if output.is_none() && !import {
+                println!("{}", StrategyFormat::Json.serialize(&strategy)?);
+            }
+        },
+        
+        StrategyCommands::Validate { file, strict, offline } => {
+            let code = validate_strategy_file(&file, strict, offline).await;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        },
+        
+        StrategyCommands::Convert { input, output } => {
+            let strategy = read_strategy_file(&input)?;
+            write_strategy_file(&strategy, &output)?;
+            println!("Strategy {} converted from {:?} to {:?}: {}", strategy.name, 
+                     StrategyFormat::from_path(&input), StrategyFormat::from_path(&output), output.display());
+        },
+        
+        StrategyCommands::AddIndicator { id, indicator_id, indicator_type, indicator_name, parameters, description } => {
+            let repository = create_repository().await?;
+            let mut strategy = repository.get_strategy(&id).await?;
+            
+            let parameters: serde_json::Value = serde_json::from_str(&parameters)
+                .context("Failed to parse indicator parameters JSON")?;
+            
+            editor::add_indicator(&mut strategy, StrategyIndicator {
+                id: indicator_id.clone(),
+                indicator_type,
+                indicator_name,
+                parameters,
+                description,
+            })?;
+            
+            save_validated_strategy(&repository, &strategy).await?;
+            println!("Indicator {} added to strategy {}", indicator_id, strategy.name);
+        },
+        
+        StrategyCommands::RemoveIndicator { id, indicator_id } => {
+            let repository = create_repository().await?;
+            let mut strategy = repository.get_strategy(&id).await?;
+            
+            editor::remove_indicator(&mut strategy, &indicator_id)?;
+            
+            save_validated_strategy(&repository, &strategy).await?;
+            println!("Indicator {} removed from strategy {}", indicator_id, strategy.name);
+        },
+        
+        StrategyCommands::AddRule { id, file } => {
+            let repository = create_repository().await?;
+            let mut strategy = repository.get_strategy(&id).await?;
+            
+            let json = std::fs::read_to_string(&file)
+                .context(format!("Failed to read file: {}", file.display()))?;
+            let rule: StrategyRule = serde_json::from_str(&json)
+                .context("Failed to parse rule JSON")?;
+            let rule_id = rule.id.clone();
+            
+            editor::add_rule(&mut strategy, rule)?;
+            
+            save_validated_strategy(&repository, &strategy).await?;
+            println!("Rule {} added to strategy {}", rule_id, strategy.name);
+        },
+        
+        StrategyCommands::RemoveRule { id, rule_id } => {
+            let repository = create_repository().await?;
+            let mut strategy = repository.get_strategy(&id).await?;
+            
+            editor::remove_rule(&mut strategy, &rule_id)?;
+            
+            save_validated_strategy(&repository, &strategy).await?;
+            println!("Rule {} removed from strategy {}", rule_id, strategy.name);
+        },
+        
+        StrategyCommands::CloneStrategy { id, name, set } => {
+            let repository = create_repository().await?;
+            let original = repository.get_strategy(&id).await?;
+            
+            let mut strategy = editor::clone_strategy(&original, name.as_deref());
+            editor::apply_parameter_overrides(&mut strategy, &set)?;
+            
+            save_validated_strategy(&repository, &strategy).await?;
+            println!("Strategy {} cloned as {} with ID: {}", original.name, strategy.name, strategy.id);
+            for entry in &set {
+                println!("  Override: {}", entry);
+            }
+        },
+        
+        StrategyCommands::SetParam { id, name, value } => {
+            let repository = create_repository().await?;
+            let mut strategy = repository.get_strategy(&id).await?;
+            
+            editor::set_parameter(&mut strategy, &name, &value)?;
+            
+            save_validated_strategy(&repository, &strategy).await?;
+            println!("Parameter {} set to {} in strategy {}", name, value, strategy.name);
+        },
+        
+        StrategyCommands::ExportBundle { id, out, backtests } => {
+            let repository = create_repository().await?;
+            let manifest = export_bundle(&repository, &id, backtests, &out).await?;
+            
+            println!("Strategy {} exported to {}", manifest.strategy_name, out.display());
+            println!("Backtest results: {}", manifest.backtest_ids.len());
+            println!("Indicator configurations: {}", manifest.indicator_configs);
+        },
+        
+        StrategyCommands::ImportBundle { file } => {
+            let repository = create_repository().await?;
+            let import = import_bundle(&repository, &file).await?;
+            
+            println!("Strategy imported from bundle created {}:", import.manifest.created_at.format("%Y-%m-%d %H:%M"));
+            println!("ID: {}", import.strategy.id);
+            println!("Name: {}", import.strategy.name);
+            println!("Backtest results: {} imported, {} already present", 
+                     import.backtests_imported, import.backtests_skipped);
             
-            command.arg("llm_optimizer.py")
-                   .arg("--strategy_id").arg(strategy_id)
-                   .arg("--symbol").arg(symbol)
-                   .arg("--interval").arg(interval)
-                   .arg("--initial_capital").arg(initial_capital.to_string())
-                   .arg("--max_iterations").arg(max_iterations.to_string())
-                   .arg("--output").arg(output.to_string_lossy().to_string());
-                   
-            if let Some(start) = start_date {
-                command.arg("--start_date").arg(start);
+            if import.provisioned.is_empty() {
+                println!("All indicator configurations were already enabled");
+            } else {
+                println!("Provisioned {} indicator configurations:", import.provisioned.len());
+                for config in &import.provisioned {
+                    let action = if config.disabled_id.is_some() { "enable" } else { "create" };
+                    println!("  {:<6} {}:{} {} {}", action, config.symbol, config.interval, 
+                             config.indicator_name, config.parameters);
+                }
             }
+        },
+        
+        StrategyCommands::Tag { command } => {
+            let repository = create_repository().await?;
             
-            if let Some(end) = end_date {
-                command.arg("--end_date").arg(end);
+            // Tags don't affect validation, so strategies are saved without re-validating
+            match command {
+                TagCommands::Add { id, tags } => {
+                    let mut strategy = repository.get_strategy(&id).await?;
+                    let added = editor::add_tags(&mut strategy, &tags)?;
+                    repository.save_strategy(&strategy).await?;
+                    
+                    if added.is_empty() {
+                        println!("Strategy {} already has these tags", strategy.name);
+                    } else {
+                        println!("Tagged strategy {} with: {}", strategy.name, added.join(", "));
+                    }
+                    println!("Tags: {}", strategy.tags.join(", "));
+                },
+                
+                TagCommands::Remove { id, tags } => {
+                    let mut strategy = repository.get_strategy(&id).await?;
+                    let removed = editor::remove_tags(&mut strategy, &tags);
+                    if removed.is_empty() {
+                        return Err(anyhow!("Strategy {} has none of the tags: {}", strategy.name, tags.join(", ")));
+                    }
+                    repository.save_strategy(&strategy).await?;
+                    
+                    println!("Removed from strategy {}: {}", strategy.name, removed.join(", "));
+                    println!("Tags: {}", if strategy.tags.is_empty() { "(none)".to_string() } else { strategy.tags.join(", ") });
+                },
+                
+                TagCommands::List => {
+                    let tags = repository.list_tags().await?;
+                    if tags.is_empty() {
+                        println!("No strategies in namespace {} have tags", repository.namespace());
+                        return Ok(());
+                    }
+                    
+                    println!("{:<30} | {}", "Tag", "Strategies");
+                    println!("{:-<30}-+-{:-<10}", "", "");
+                    for (tag, count) in tags {
+                        println!("{:<30} | {}", tag, count);
+                    }
+                },
             }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute an engine settings command
+async fn execute_engine_command(command: EngineCommands) -> Result<()> {
+    let repository = create_repository().await?;
+    
+    match command {
+        EngineCommands::List => {
+            let profiles = repository.list_engine_settings().await?;
             
-            println!("Starting LLM optimization process...");
+            println!("Found {} engine profiles:", profiles.len());
+            println!("{:<15} | {:<12} | {:<10} | {:<14} | {:<8} | {}", 
+                     "Name", "Commission", "Slippage", "Intrabar", "Latency", "Description");
+            println!("{:-<15}-+-{:-<12}-+-{:-<10}-+-{:-<14}-+-{:-<8}-+-{:-<20}", "", "", "", "", "", "");
             
-            let output = command.output().context("Failed to run LLM optimizer")?;
+            for profile in profiles {
+                println!("{:<15} | {:<11.3}% | {:<9.3}% | {:<14} | {:<8} | {}", 
+                         profile.name,
+                         profile.commission_percent,
+                         profile.slippage_percent,
+                         serde_json::to_value(profile.intrabar_assumption)?.as_str().unwrap_or(""),
+                         profile.execution_latency_bars,
+                         profile.description);
+            }
+        },
+        
+        EngineCommands::Show { name } => {
+            let profile = repository.get_engine_settings(&name).await?;
+            println!("{}", serde_json::to_string_pretty(&profile)?);
+        },
+        
+        EngineCommands::Save { file } => {
+            let json = std::fs::read_to_string(&file)
+                .context(format!("Failed to read file: {}", file.display()))?;
+            let profile: EngineSettings = serde_json::from_str(&json)
+                .context("Failed to parse engine settings JSON")?;
             
-            // Print any stdout/stderr from the process
-            if !output.stdout.is_empty() {
-                println!("{}", String::from_utf8_lossy(&output.stdout));
+            if profile.name.is_empty() {
+                return Err(anyhow::anyhow!("Engine settings profile name cannot be empty"));
             }
             
-            if !output.stderr.is_empty() {
-                eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+            repository.save_engine_settings(&profile).await?;
+            println!("Engine settings profile '{}' saved.", profile.name);
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a backtest archival command
+async fn execute_archive_command(command: ArchiveCommands) -> Result<()> {
+    let repository = create_repository().await?;
+    let archiver = BacktestArchiver::from_env(repository.get_db_connection())?;
+    archiver.init_tables().await?;
+    
+    match command {
+        ArchiveCommands::Run { older_than_days, limit, dry_run } => {
+            let policy = ArchivePolicy { older_than_days, limit };
+            
+            match archiver.archive(&policy, dry_run).await? {
+                Some(manifest) => {
+                    let verb = if dry_run { "Would archive" } else { "Archived" };
+                    println!("{} {} backtest results created before {}", 
+                             verb, manifest.backtest_ids.len(), manifest.cutoff.format("%Y-%m-%d"));
+                    println!("Archive: {} ({} bytes compressed)", manifest.object_key, manifest.compressed_bytes);
+                },
+                None => println!("No backtest results older than {} days to archive", older_than_days),
             }
+        },
+        
+        ArchiveCommands::Fetch { id, output } => {
+            let result = archiver.fetch(id).await?;
+            let json = serde_json::to_string_pretty(&result)?;
             
-            // Check exit status
-            if output.status.success() {
-                println!("Optimization completed successfully.");
+            if let Some(output_path) = output {
+                std::fs::write(&output_path, json)
+                    .context(format!("Failed to write file: {}", output_path.display()))?;
+                println!("Archived backtest result written to: {}", output_path.display());
             } else {
-                eprintln!("Optimization failed with exit code: {:?}", output.status.code());
-                return Err(anyhow::anyhow!("Optimization failed"));
+                println!("{}", json);
             }
         },
     }
     
     Ok(())
 }
+
+/// Execute a parameter search command
+async fn execute_tune_command(command: TuneCommands) -> Result<()> {
+    let repository = create_repository().await?;
+    
+    match command {
+        TuneCommands::Run { 
+            strategy_id, symbol, interval, start_date, end_date, initial_capital, engine_profile, objective, 
+            population, generations, mutation_rate, crossover_rate, elite, patience, min_trades, seed, 
+            holdout, stability_top, max_degradation, min_stability, apply 
+        } => {
+            let strategy = repository.get_strategy(&strategy_id).await?;
+            
+            let target = SearchTarget {
+                symbol,
+                interval,
+                start_date: start_date.map(|d| parse_date(&d)).transpose()?,
+                end_date: end_date.map(|d| parse_date(&d)).transpose()?,
+                initial_capital,
+                engine_profile,
+            };
+            let settings = GeneticSettings {
+                population,
+                generations,
+                mutation_rate,
+                crossover_rate,
+                elite,
+                patience,
+                min_trades,
+                objective,
+                seed,
+                holdout,
+                stability_top,
+                max_degradation,
+                min_stability,
+                ..GeneticSettings::default()
+            };
+            
+            println!("Searching parameters of strategy {} on {}:{} (population {}, up to {} generations)...", 
+                     strategy.name, target.symbol, target.interval, population, generations);
+            let outcome = GeneticOptimizer::new(&repository, target, settings).run(&strategy).await?;
+            
+            println!("\nRun {} (seed {}): {} candidates backtested over {} generations{}", 
+                     outcome.run_id, outcome.seed, outcome.evaluated, outcome.generations,
+                     if outcome.stopped_early { " (stopped early, no improvement)" } else { "" });
+            let format_date = |date: Option<DateTime<Utc>>| date.map_or("-".to_string(), |d| d.format("%Y-%m-%d").to_string());
+            println!("In-sample: {} to {}", format_date(outcome.in_sample.0), format_date(outcome.in_sample.1));
+            if let Some((start, end)) = outcome.out_of_sample {
+                println!("Out-of-sample: {} to {}", format_date(start), format_date(end));
+            }
+            
+            let format_value = |value: Option<f64>, precision: usize| value.map_or("-".to_string(), |v| format!("{:.*}", precision, v));
+            println!("\n{:<8} | {:<12} | {:<12} | {:<12} | {:<9} | {:<7} | {}", 
+                     "ID", "In-sample", "Out-sample", "Degradation", "Stability", "Overfit", "Parameters");
+            println!("{:-<8}-+-{:-<12}-+-{:-<12}-+-{:-<12}-+-{:-<9}-+-{:-<7}-+-{:-<30}", "", "", "", "", "", "", "");
+            for evaluation in &outcome.top {
+                println!("{:<8} | {:<12} | {:<12} | {:<12} | {:<9} | {:<7} | {}", 
+                         evaluation.candidate_id,
+                         format_value(evaluation.fitness, 4),
+                         format_value(evaluation.oos_fitness, 4),
+                         evaluation.degradation.map_or("-".to_string(), |d| format!("{:.1}%", d)),
+                         format_value(evaluation.stability, 2),
+                         if evaluation.overfit { "yes" } else { "no" },
+                         serde_json::to_string(&evaluation.candidate)?);
+            }
+            
+            match outcome.best.fitness {
+                Some(fitness) => println!("\nBest {:?}: {:.4} (candidate {}, backtest {})", objective, fitness, 
+                                          outcome.best.candidate_id,
+                                          outcome.best.backtest_id.map_or("-".to_string(), |id| id.to_string())),
+                None => println!("\nNo candidate reached {} trades", min_trades),
+            }
+            if outcome.best.overfit {
+                println!("Warning: every checked candidate is flagged as likely overfit.");
+            }
+            for warning in &outcome.warnings {
+                println!("Warning: {}.", warning);
+            }
+            
+            if apply && outcome.best.fitness.is_some() && !outcome.best.overfit {
+                save_validated_strategy(&repository, &outcome.best_strategy).await?;
+                println!("Strategy {} updated with the best parameters.", strategy.id);
+            } else if apply {
+                println!("Strategy not updated: the best candidate has no fitness or is flagged as overfit.");
+            } else {
+                println!("Inspect with 'tune show {}'; rerun with --apply to save the best parameters.", outcome.run_id);
+            }
+        },
+        
+        TuneCommands::Show { run_id, limit } => {
+            let pg = repository.get_db_connection();
+            init_tables(&pg).await?;
+            let candidates = get_run_candidates(&pg, run_id, limit).await?;
+            if candidates.is_empty() {
+                println!("No candidates stored for run {}", run_id);
+                return Ok(());
+            }
+            
+            println!("{:<8} | {:<10} | {:<12} | {:<10} | {:<12} | {:<12} | {:<9} | {:<7} | {}", 
+                     "ID", "Generation", "Fitness", "Backtest", "Out-sample", "Degradation", "Stability", "Overfit", "Parameters");
+            println!("{:-<8}-+-{:-<10}-+-{:-<12}-+-{:-<10}-+-{:-<12}-+-{:-<12}-+-{:-<9}-+-{:-<7}-+-{:-<30}", 
+                     "", "", "", "", "", "", "", "", "");
+            for candidate in candidates {
+                let fitness = match (candidate.fitness, &candidate.error) {
+                    (Some(fitness), _) => format!("{:.4}", fitness),
+                    (None, Some(_)) => "failed".to_string(),
+                    (None, None) => "-".to_string(),
+                };
+                let overfit = match candidate.overfit {
+                    Some(true) => "yes",
+                    Some(false) => "no",
+                    None => "-",
+                };
+                println!("{:<8} | {:<10} | {:<12} | {:<10} | {:<12} | {:<12} | {:<9} | {:<7} | {}", 
+                         candidate.id, candidate.generation, fitness,
+                         candidate.backtest_id.map_or("-".to_string(), |id| id.to_string()),
+                         candidate.oos_fitness.map_or("-".to_string(), |f| format!("{:.4}", f)),
+                         candidate.degradation_percent.map_or("-".to_string(), |d| format!("{:.1}%", d)),
+                         candidate.stability.map_or("-".to_string(), |s| format!("{:.2}", s)),
+                         overfit,
+                         candidate.parameters);
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a strategy re-evaluation command
+async fn execute_reevaluate_command(command: ReevaluateCommands) -> Result<()> {
+    let config = AppConfig::load()?;
+    let repository = create_repository().await?;
+    
+    let results = match command {
+        ReevaluateCommands::Run { strategy_id, window_days } => {
+            let mut settings = config.reevaluation.clone();
+            if let Some(days) = window_days {
+                settings.window_days = days;
+            }
+            let reevaluator = Reevaluator::new(&repository, settings, WebhookNotifier::from_config(&config.notifications));
+            
+            match strategy_id {
+                Some(id) => reevaluator.run(&repository.get_strategy(&id).await?).await?,
+                None => reevaluator.run_all().await?,
+            }
+        },
+        
+        ReevaluateCommands::Show { strategy_id, degraded, limit } => {
+            let pg = repository.get_db_connection();
+            reevaluation::init_table(&pg).await?;
+            recent_reevaluations(&pg, strategy_id.as_deref(), limit).await?
+                .into_iter()
+                .filter(|r| !degraded || r.degraded)
+                .collect()
+        },
+    };
+    
+    print_reevaluations(&results);
+    Ok(())
+}
+
+/// Execute an event log command
+async fn execute_events_command(command: EventsCommands) -> Result<()> {
+    match command {
+        EventsCommands::Tail { follow, kind, subject, limit, poll_seconds, json } => {
+            let kinds = kind.iter()
+                .map(|name| EventKind::parse(name))
+                .collect::<Result<Vec<_>>>()?;
+            
+            let repository = create_repository().await?;
+            let pg = repository.get_db_connection();
+            events::init_table(&pg).await?;
+            
+            let mut recent = events::recent_events(&pg, &kinds, subject.as_deref(), limit).await?;
+            recent.reverse();
+            for event in &recent {
+                print_event(event, json)?;
+            }
+            
+            if !follow {
+                if recent.is_empty() && !json {
+                    println!("No events.");
+                }
+                return Ok(());
+            }
+            
+            let mut last_id = recent.last().map(|e| e.id).unwrap_or(0);
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(std::time::Duration::from_secs(poll_seconds.max(1))) => {},
+                    _ = tokio::signal::ctrl_c() => break,
+                }
+                
+                for event in events::events_after(&pg, Some(last_id), &kinds, subject.as_deref(), 1000).await? {
+                    print_event(&event, json)?;
+                    last_id = event.id;
+                }
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+fn print_event(event: &Event, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(event)?);
+    } else {
+        println!("{} {:>6} {:<17} {:<30} {}", 
+                 event.created_at.format("%Y-%m-%d %H:%M:%S"), event.source, event.kind,
+                 event.subject.as_deref().unwrap_or("-"), event.message);
+    }
+    Ok(())
+}
+
+/// Execute an indicator catalog command
+fn execute_indicators_command(command: IndicatorsCommands) -> Result<()> {
+    match command {
+        IndicatorsCommands::List { category, json } => {
+            let indicators: Vec<IndicatorInfo> = catalog::all_indicators()
+                .into_iter()
+                .filter(|indicator| category.as_deref().map_or(true, |c| indicator.category.eq_ignore_ascii_case(c)))
+                .collect();
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&indicators)?);
+                return Ok(());
+            }
+            
+            if indicators.is_empty() {
+                println!("No indicators.");
+                return Ok(());
+            }
+            
+            println!("{:<20} | {:<12} | {:<30} | {}", "Name", "Category", "Inputs", "Outputs");
+            println!("{:-<20}-+-{:-<12}-+-{:-<30}-+-{:-<30}", "", "", "", "");
+            for indicator in &indicators {
+                let outputs = if indicator.outputs.is_empty() {
+                    "value".to_string()
+                } else {
+                    indicator.outputs.join(", ")
+                };
+                println!("{:<20} | {:<12} | {:<30} | {}", 
+                         indicator.name, indicator.category, indicator.inputs.join(", "), outputs);
+            }
+            println!("\nUse `indicators describe <name>` for parameters.");
+        },
+        
+        IndicatorsCommands::Describe { name, json } => {
+            let indicator = catalog::find_indicator(&name)
+                .ok_or_else(|| anyhow!("Unknown indicator '{}'. Run `indicators list` for supported indicators", name))?;
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&indicator)?);
+                return Ok(());
+            }
+            
+            println!("{} ({})", indicator.name, indicator.category);
+            println!("{}", indicator.description);
+            if !indicator.aliases.is_empty() {
+                println!("\nAliases: {}", indicator.aliases.join(", "));
+            }
+            println!("\nInputs: {}", indicator.inputs.join(", "));
+            
+            println!("\nParameters:");
+            if indicator.parameters.is_empty() {
+                println!("  none");
+            }
+            for parameter in &indicator.parameters {
+                let default = if parameter.default.is_null() {
+                    "-".to_string()
+                } else {
+                    parameter.default.to_string()
+                };
+                println!("  {:<18} {:<8} default {:<8} {:<22} {}", 
+                         parameter.name, parameter.kind, default,
+                         parameter.range.map_or(String::new(), |r| format!("range {}", r)), parameter.description);
+            }
+            
+            println!("\nOutputs:");
+            if indicator.outputs.is_empty() {
+                println!("  single value");
+            }
+            for output in &indicator.outputs {
+                println!("  {}", output);
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a verification command
+fn execute_verify_command(command: VerifyCommands) -> Result<()> {
+    match command {
+        VerifyCommands::Indicators { indicator, tolerance, update } => {
+            let name = indicator
+                .map(|name| catalog::find_indicator(&name)
+                    .map(|indicator| indicator.name)
+                    .ok_or_else(|| anyhow!("Unknown indicator '{}'. Run `indicators list` for supported indicators", name)))
+                .transpose()?;
+            
+            // The source tree's file when updating, so earlier updates are kept
+            let stored: Vec<GoldenCase> = if update && Path::new(golden::GOLDEN_PATH).exists() {
+                serde_json::from_str(&std::fs::read_to_string(golden::GOLDEN_PATH)?)
+                    .context(format!("Invalid golden outputs in {}", golden::GOLDEN_PATH))?
+            } else {
+                golden::bundled_cases()?
+            };
+            
+            // Built-in indicators with their reference parameters, and any further stored cases
+            let mut indicators = golden::reference_parameters();
+            for case in &stored {
+                if !indicators.iter().any(|(name, parameters)| *name == case.indicator && *parameters == case.parameters) {
+                    indicators.push((case.indicator.clone(), case.parameters.clone()));
+                }
+            }
+            indicators.retain(|(indicator, _)| name.as_ref().map_or(true, |name| indicator == name));
+            
+            if update {
+                let mut generated = golden::generate(&indicators)?;
+                let mut cases = Vec::new();
+                for case in stored {
+                    match generated.iter_mut().find(|g| g.indicator == case.indicator && g.parameters == case.parameters) {
+                        // A case keeps its own tolerance across updates
+                        Some(update) => update.tolerance = case.tolerance,
+                        None => cases.push(case),
+                    }
+                }
+                cases.extend(generated);
+                cases.sort_by(|a, b| a.indicator.cmp(&b.indicator).then_with(|| a.parameters.to_string().cmp(&b.parameters.to_string())));
+                
+                std::fs::write(golden::GOLDEN_PATH, format!("{}\n", serde_json::to_string_pretty(&cases)?))
+                    .context(format!("Failed to write {}", golden::GOLDEN_PATH))?;
+                println!("Wrote golden outputs of {} indicators to {}", indicators.len(), golden::GOLDEN_PATH);
+                return Ok(());
+            }
+            
+            let results = golden::verify(&indicators, &stored, tolerance)?;
+            
+            println!("{:<20} | {:<30} | {:<6} | {}", "Indicator", "Parameters", "Values", "Result");
+            println!("{:-<20}-+-{:-<30}-+-{:-<6}-+-{:-<10}", "", "", "", "");
+            for result in &results {
+                let status = match &result.status {
+                    CaseStatus::Passed => "ok".to_string(),
+                    CaseStatus::Failed(differences) => format!("FAILED ({} differences)", differences.len()),
+                    CaseStatus::Missing => "missing".to_string(),
+                    CaseStatus::Error(e) => format!("ERROR: {}", e),
+                };
+                println!("{:<20} | {:<30} | {:<6} | {}", result.indicator, result.parameters.to_string(), result.values, status);
+            }
+            
+            for result in &results {
+                if let CaseStatus::Failed(differences) = &result.status {
+                    println!("\n{} {}:", result.indicator, result.parameters);
+                    for difference in differences.iter().take(5) {
+                        println!("  {}", difference);
+                    }
+                    if differences.len() > 5 {
+                        println!("  ... and {} more", differences.len() - 5);
+                    }
+                }
+            }
+            
+            let count = |matches: fn(&CaseStatus) -> bool| results.iter().filter(|result| matches(&result.status)).count();
+            let passed = count(|status| matches!(status, CaseStatus::Passed));
+            let missing = count(|status| matches!(status, CaseStatus::Missing));
+            let failed = results.len() - passed - missing;
+            
+            println!("\n{} passed, {} failed, {} without golden outputs", passed, failed, missing);
+            if missing > 0 {
+                println!("Record the missing outputs with `verify indicators --update`.");
+            }
+            if failed > 0 {
+                return Err(anyhow!("{} of {} indicators differ from their golden outputs", failed, results.len()));
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+fn print_reevaluations(results: &[Reevaluation]) {
+    if results.is_empty() {
+        println!("No re-evaluations.");
+        return;
+    }
+    
+    println!("{:<36} | {:<10} | {:<8} | {:<16} | {:<6} | {:<9} | {:<9} | {:<8} | {:<6} | {}", 
+             "Strategy", "Symbol", "Interval", "Run", "Trades", "Return", "Drawdown", "Win Rate", "PF", "Status");
+    println!("{:-<36}-+-{:-<10}-+-{:-<8}-+-{:-<16}-+-{:-<6}-+-{:-<9}-+-{:-<9}-+-{:-<8}-+-{:-<6}-+-{:-<30}", 
+             "", "", "", "", "", "", "", "", "", "");
+    
+    let percent = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.2}%", v));
+    for result in results {
+        let status = match (&result.error, &result.reasons) {
+            (Some(error), _) => format!("failed: {}", error),
+            (None, Some(reasons)) => format!("degraded: {}", reasons),
+            (None, None) => "ok".to_string(),
+        };
+        println!("{:<36} | {:<10} | {:<8} | {:<16} | {:<6} | {:<9} | {:<9} | {:<8} | {:<6} | {}", 
+                 result.strategy_id, result.symbol, result.interval,
+                 result.created_at.format("%Y-%m-%d %H:%M"),
+                 result.total_trades.map_or("-".to_string(), |t| t.to_string()),
+                 percent(result.total_return), percent(result.max_drawdown), percent(result.win_rate),
+                 result.profit_factor.map_or("-".to_string(), |pf| format!("{:.2}", pf)),
+                 status);
+    }
+    
+    let degraded = results.iter().filter(|r| r.degraded).count();
+    println!("\n{} windows, {} degraded", results.len(), degraded);
+}
+
+/// Execute an indicator configuration command
+async fn execute_config_command(command: ConfigCommands) -> Result<()> {
+    match command {
+        ConfigCommands::Apply { file, dry_run } => {
+            let manifest = IndicatorManifest::from_file(&file)?;
+            
+            let repository = create_repository().await?;
+            let plan = apply_manifest(&repository.get_db_connection(), &manifest, dry_run).await?;
+            
+            if plan.is_empty() {
+                println!("indicator_config already matches {} ({} configurations)", file.display(), plan.unchanged);
+                return Ok(());
+            }
+            
+            println!("{:<8} | {:<12} | {:<8} | {:<15} | {}", "Action", "Symbol", "Interval", "Indicator", "Parameters");
+            println!("{:-<8}-+-{:-<12}-+-{:-<8}-+-{:-<15}-+-{:-<30}", "", "", "", "", "");
+            
+            for config in &plan.create {
+                println!("{:<8} | {:<12} | {:<8} | {:<15} | {}", 
+                         "create", config.symbol, config.interval, config.indicator_name, config.parameters);
+            }
+            for config in &plan.enable {
+                println!("{:<8} | {:<12} | {:<8} | {:<15} | {}", 
+                         "enable", config.symbol, config.interval, config.indicator_name, config.parameters);
+            }
+            for config in &plan.disable {
+                println!("{:<8} | {:<12} | {:<8} | {:<15} | {}", 
+                         "disable", config.symbol, config.interval, config.indicator_name, config.parameters);
+            }
+            
+            let verb = if dry_run { "Would apply" } else { "Applied" };
+            println!("\n{}: {} created, {} enabled, {} disabled, {} unchanged", 
+                     verb, plan.create.len(), plan.enable.len(), plan.disable.len(), plan.unchanged);
+        },
+        ConfigCommands::Show => {
+            let config = AppConfig::load()?;
+            
+            match AppConfig::config_file_path() {
+                Some(path) => println!("# Settings from {} with environment overrides", path.display()),
+                None => println!("# No config file found, using defaults with environment overrides"),
+            }
+            println!();
+            print!("{}", config.to_masked_toml()?);
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a candle data command
+async fn execute_data_command(command: DataCommands) -> Result<()> {
+    match command {
+        DataCommands::Import { file, symbol, interval } => {
+            let config = AppConfig::load()?;
+            let storage = config.database.connect_storage().await?;
+            storage.init().await?;
+            
+            let candles = CsvSource::new(&file, &symbol, &interval).read_candles()?;
+            if candles.is_empty() {
+                println!("No candles found in {}", file.display());
+                return Ok(());
+            }
+            
+            storage.upsert_candles(&candles).await?;
+            
+            let first = candles.iter().map(|c| c.open_time).min().unwrap_or_default();
+            let last = candles.iter().map(|c| c.open_time).max().unwrap_or_default();
+            info!("Imported {} candles for {}:{} into {}", candles.len(), symbol, interval, storage.backend());
+            println!("Imported {} candles for {}:{} ({} to {}) into {}", 
+                     candles.len(), symbol, interval, 
+                     first.format("%Y-%m-%d %H:%M"), last.format("%Y-%m-%d %H:%M"), storage.backend());
+        },
+        DataCommands::Funding { symbol, start_date, end_date } => {
+            let repository = create_repository().await?;
+            let pg = repository.get_db_connection();
+            pg.init_funding_rates_table().await?;
+            
+            // Continue after the latest stored rate unless a start date is given
+            let start = match start_date {
+                Some(date) => Some(parse_date(&date)?),
+                None => pg.get_last_funding_time(&symbol).await?
+                    .map(|time| time + Duration::milliseconds(1)),
+            };
+            let end = end_date.map(|d| parse_date(&d)).transpose()?;
+            
+            let rest_url = env::var("BINANCE_FUTURES_API_URL").unwrap_or_else(|_| "https://fapi.binance.com".to_string());
+            let rates = BinanceFundingSource::new(&rest_url).get_funding_rates(&symbol, start, end).await?;
+            if rates.is_empty() {
+                println!("No new funding rates for {}", symbol);
+                return Ok(());
+            }
+            
+            pg.upsert_funding_rates(&rates).await?;
+            
+            let mean_rate = rates.iter().map(|r| r.funding_rate).sum::<f64>() / rates.len() as f64;
+            info!("Stored {} funding rates for {}", rates.len(), symbol);
+            println!("Stored {} funding rates for {} ({} to {}), mean rate {:.4}%", 
+                     rates.len(), symbol,
+                     rates[0].funding_time.format("%Y-%m-%d %H:%M"),
+                     rates[rates.len() - 1].funding_time.format("%Y-%m-%d %H:%M"),
+                     mean_rate * 100.0);
+        },
+        DataCommands::Adjustments { command } => {
+            execute_adjustment_command(command).await?;
+        },
+        DataCommands::Audit { symbol, interval, backfill } => {
+            let repository = create_repository().await?;
+            let pg = repository.get_db_connection();
+            
+            let pairs: Vec<(String, String)> = pg.get_candle_symbol_intervals().await?
+                .into_iter()
+                .filter(|(s, _)| symbol.as_ref().map_or(true, |symbol| s == symbol))
+                .filter(|(_, i)| interval.as_ref().map_or(true, |interval| i == interval))
+                .collect();
+            
+            if pairs.is_empty() {
+                println!("No candle data found");
+                return Ok(());
+            }
+            
+            let source = backfill.then(|| {
+                let rest_url = env::var("BINANCE_API_URL").unwrap_or_else(|_| "https://api.binance.com".to_string());
+                BinanceSource::new(&rest_url, "wss://stream.binance.com:9443")
+            });
+            
+            println!("{:<12} | {:<8} | {:<6} | {:<8} | {:<10} | {:<11} | {:<10}", 
+                     "Symbol", "Interval", "Gaps", "Missing", "Duplicates", "Zero Volume", "Backfilled");
+            println!("{:-<12}-+-{:-<8}-+-{:-<6}-+-{:-<8}-+-{:-<10}-+-{:-<11}-+-{:-<10}", "", "", "", "", "", "", "");
+            
+            let mut clean = 0;
+            for (symbol, interval) in &pairs {
+                let mut audit = audit_candles(&pg, symbol, interval).await?;
+                
+                if let Some(source) = &source {
+                    if let Err(e) = backfill_gaps(&pg, source, &mut audit).await {
+                        warn!("Backfill failed for {}:{}: {}", symbol, interval, e);
+                    }
+                }
+                
+                if audit.is_clean() {
+                    clean += 1;
+                }
+                
+                println!("{:<12} | {:<8} | {:<6} | {:<8} | {:<10} | {:<11} | {:<10}", 
+                         audit.symbol, audit.interval, audit.gaps.len(), audit.missing_candles(),
+                         audit.duplicates, audit.zero_volume, audit.backfilled);
+            }
+            
+            println!("\n{} of {} symbol/intervals clean", clean, pairs.len());
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a price adjustment command
+async fn execute_adjustment_command(command: AdjustmentCommands) -> Result<()> {
+    let repository = create_repository().await?;
+    let pg = repository.get_db_connection();
+    
+    match command {
+        AdjustmentCommands::Add { symbol, effective_time, ratio, kind, note } => {
+            validate_adjustment(&kind, ratio)?;
+            let effective_time = parse_date(&effective_time)?;
+            
+            let (id, deleted) = pg.insert_price_adjustment(&symbol, effective_time, ratio, &kind, note.as_deref()).await?;
+            
+            info!("Recorded {} of {} at {} (ratio {})", kind, symbol, effective_time, ratio);
+            println!("Recorded adjustment {}: {} of {} at {} (ratio {})", 
+                     id, kind, symbol, effective_time.format("%Y-%m-%d %H:%M"), ratio);
+            println!("Deleted {} calculated indicator values of {} for recalculation", deleted, symbol);
+        },
+        AdjustmentCommands::List { symbol } => {
+            let adjustments = pg.get_price_adjustments(symbol.as_deref()).await?;
+            if adjustments.is_empty() {
+                println!("No price adjustments found");
+                return Ok(());
+            }
+            
+            println!("{:<6} | {:<12} | {:<16} | {:<14} | {:<12} | {:<12} | {}", 
+                     "ID", "Symbol", "Effective", "Kind", "Ratio", "Factor", "Note");
+            println!("{:-<6}-+-{:-<12}-+-{:-<16}-+-{:-<14}-+-{:-<12}-+-{:-<12}-+-{:-<10}", "", "", "", "", "", "", "");
+            
+            for adjustment in &adjustments {
+                // Prices just before this event are divided by it and every later ratio
+                let factor: f64 = adjustments.iter()
+                    .filter(|a| a.symbol == adjustment.symbol && a.effective_time >= adjustment.effective_time)
+                    .map(|a| a.ratio)
+                    .product();
+                
+                println!("{:<6} | {:<12} | {:<16} | {:<14} | {:<12} | {:<12} | {}", 
+                         adjustment.id, adjustment.symbol, adjustment.effective_time.format("%Y-%m-%d %H:%M"),
+                         adjustment.kind, adjustment.ratio, factor, adjustment.note.as_deref().unwrap_or(""));
+            }
+        },
+        AdjustmentCommands::Remove { id } => {
+            let (adjustment, deleted) = pg.delete_price_adjustment(id).await?;
+            
+            info!("Deleted adjustment {} of {}", id, adjustment.symbol);
+            println!("Deleted adjustment {}: {} of {} at {}", 
+                     id, adjustment.kind, adjustment.symbol, adjustment.effective_time.format("%Y-%m-%d %H:%M"));
+            println!("Deleted {} calculated indicator values of {} for recalculation", deleted, adjustment.symbol);
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a symbol metadata command
+async fn execute_symbols_command(command: SymbolsCommands, output: PrintFormat) -> Result<()> {
+    let repository = create_repository().await?;
+    let pg = repository.get_db_connection();
+    pg.init_symbols_table().await?;
+    
+    match command {
+        SymbolsCommands::Add { symbol, base, quote, tick_size, listing_date, session, inactive } => {
+            // The calendar is stored as given, so preset names stay readable
+            let session = session.map(|s| s.trim().to_string());
+            if let Some(session) = &session {
+                SessionCalendar::parse(session)?;
+            }
+            
+            let record = SymbolRecord {
+                symbol: symbol.to_uppercase(),
+                base_asset: base.map(|b| b.to_uppercase()),
+                quote_asset: quote.map(|q| q.to_uppercase()),
+                tick_size,
+                listing_date: listing_date.map(|d| parse_date(&d)).transpose()?,
+                active: !inactive,
+                quote_volume: None,
+                session,
+                updated_at: Utc::now(),
+            };
+            pg.upsert_symbols(std::slice::from_ref(&record)).await?;
+            
+            println!("Saved symbol {} ({})", record.symbol, if record.active { "active" } else { "inactive" });
+            if record.session.is_some() {
+                let deleted = pg.delete_session_indicator_values(&record.symbol).await?;
+                println!("Deleted {} session-anchored indicator values of {} for recalculation", deleted, record.symbol);
+            }
+        },
+        SymbolsCommands::List { quote, all, watchlist } => {
+            let symbols = match watchlist {
+                Some(name) => {
+                    let names = Watchlist::parse(&name)?.symbols(&pg).await?;
+                    let stored = pg.list_symbols(true, None).await?;
+                    names.iter()
+                        .filter_map(|name| stored.iter().find(|s| &s.symbol == name).cloned())
+                        .collect()
+                },
+                None => pg.list_symbols(!all, quote.as_deref()).await?,
+            };
+            
+            if output != PrintFormat::Table {
+                let records: Vec<serde_json::Value> = symbols.iter()
+                    .map(serde_json::to_value)
+                    .collect::<Result<_, _>>()?;
+                print_records(std::io::stdout().lock(), output, &records)?;
+                return Ok(());
+            }
+            
+            if symbols.is_empty() {
+                println!("No symbols found; run 'symbols import-from-exchange' or 'symbols add' first");
+                return Ok(());
+            }
+            
+            println!("{:<14} | {:<8} | {:<8} | {:<12} | {:<10} | {:<6} | {:<18} | {}", 
+                     "Symbol", "Base", "Quote", "Tick Size", "Listed", "Active", "24h Quote Volume", "Session");
+            println!("{:-<14}-+-{:-<8}-+-{:-<8}-+-{:-<12}-+-{:-<10}-+-{:-<6}-+-{:-<18}-+-{:-<10}", "", "", "", "", "", "", "", "");
+            
+            for symbol in &symbols {
+                println!("{:<14} | {:<8} | {:<8} | {:<12} | {:<10} | {:<6} | {:<18} | {}", 
+                         symbol.symbol,
+                         symbol.base_asset.as_deref().unwrap_or("-"),
+                         symbol.quote_asset.as_deref().unwrap_or("-"),
+                         symbol.tick_size.map_or("-".to_string(), |t| t.to_string()),
+                         symbol.listing_date.map_or("-".to_string(), |d| d.format("%Y-%m-%d").to_string()),
+                         if symbol.active { "yes" } else { "no" },
+                         symbol.quote_volume.map_or("-".to_string(), |v| format!("{:.0}", v)),
+                         symbol.session.as_deref().unwrap_or("crypto"));
+            }
+            
+            println!("\n{} symbols", symbols.len());
+        },
+        SymbolsCommands::ImportFromExchange { quote } => {
+            let rest_url = env::var("BINANCE_API_URL").unwrap_or_else(|_| "https://api.binance.com".to_string());
+            let symbols: Vec<SymbolRecord> = BinanceSource::new(&rest_url, "wss://stream.binance.com:9443")
+                .get_symbol_metadata()
+                .await?
+                .into_iter()
+                .filter(|s| quote.as_ref().map_or(true, |quote| {
+                    s.quote_asset.as_ref().is_some_and(|q| q.eq_ignore_ascii_case(quote))
+                }))
+                .collect();
+            
+            if symbols.is_empty() {
+                println!("No symbols returned by {}", rest_url);
+                return Ok(());
+            }
+            
+            pg.upsert_symbols(&symbols).await?;
+            
+            let active = symbols.iter().filter(|s| s.active).count();
+            info!("Imported metadata of {} symbols from Binance", symbols.len());
+            println!("Imported {} symbols from Binance ({} active, {} inactive)", 
+                     symbols.len(), active, symbols.len() - active);
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a worker job command
+async fn execute_jobs_command(command: JobsCommands) -> Result<()> {
+    let repository = create_repository().await?;
+    let pg = repository.get_db_connection();
+    
+    match command {
+        JobsCommands::Failed { limit } => {
+            let jobs = pg.get_failed_jobs(limit).await?;
+            
+            if jobs.is_empty() {
+                println!("No failed jobs");
+                return Ok(());
+            }
+            
+            println!("{:<6} | {:<12} | {:<8} | {:<15} | {:<8} | {:<19} | {}", 
+                     "ID", "Symbol", "Interval", "Indicator", "Failures", "Last Failed", "Error");
+            println!("{:-<6}-+-{:-<12}-+-{:-<8}-+-{:-<15}-+-{:-<8}-+-{:-<19}-+-{:-<40}", "", "", "", "", "", "", "");
+            
+            for job in &jobs {
+                println!("{:<6} | {:<12} | {:<8} | {:<15} | {:<8} | {:<19} | {}", 
+                         job.id, job.symbol, job.interval, job.indicator_name, job.failure_count,
+                         job.last_failed_at.format("%Y-%m-%d %H:%M:%S"), job.error.lines().next().unwrap_or(""));
+            }
+        },
+        
+        JobsCommands::Requeue { ids, all } => {
+            let requeued = if all {
+                pg.delete_failed_jobs(None).await?
+            } else {
+                pg.delete_failed_jobs(Some(&ids)).await?
+            };
+            
+            println!("Requeued {} failed jobs", requeued);
+        },
+        
+        JobsCommands::Anomalies { symbol, indicator, limit } => {
+            pg.init_indicator_anomalies_table().await?;
+            let anomalies = pg.get_indicator_anomalies(symbol.as_deref(), indicator.as_deref(), limit).await?;
+            
+            if anomalies.is_empty() {
+                println!("No indicator anomalies");
+                return Ok(());
+            }
+            
+            println!("{:<12} | {:<8} | {:<15} | {:<20} | {:<19} | {:<12} | {}", 
+                     "Symbol", "Interval", "Indicator", "Parameters", "Time", "Check", "Detail");
+            println!("{:-<12}-+-{:-<8}-+-{:-<15}-+-{:-<20}-+-{:-<19}-+-{:-<12}-+-{:-<40}", "", "", "", "", "", "", "");
+            
+            for anomaly in &anomalies {
+                let detail = match &anomaly.property {
+                    Some(property) => format!("{}: {}", property, anomaly.detail),
+                    None => anomaly.detail.clone(),
+                };
+                println!("{:<12} | {:<8} | {:<15} | {:<20} | {:<19} | {:<12} | {}", 
+                         anomaly.symbol, anomaly.interval, anomaly.indicator_name, anomaly.parameters.to_string(),
+                         anomaly.time.format("%Y-%m-%d %H:%M:%S"), anomaly.check_name, detail);
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute an indicator recalculation command
+async fn execute_calc_command(command: CalcCommands) -> Result<()> {
+    match command {
+        CalcCommands::Run { symbol, interval, indicator, params, last, format, chart, height } => {
+            let parameters = parse_indicator_params(&params)?;
+            
+            let config = AppConfig::load()?;
+            crate::worker::apply_nan_policy(&config.worker.nan_policy)?;
+            let pg = config.database.shared().await?;
+            
+            let candles = pg.get_candle_data(&symbol, &interval).await?;
+            if candles.close.is_empty() {
+                return Err(anyhow!("No candles stored for {}:{}", symbol, interval));
+            }
+            
+            let results = if cross_symbol::is_cross_symbol(&indicator) {
+                let other_symbol = cross_symbol::other_symbol(&parameters)?;
+                let other = pg.get_candle_data(other_symbol, &interval).await?;
+                if other.close.is_empty() {
+                    return Err(anyhow!("No candles stored for {}:{}", other_symbol, interval));
+                }
+                IndicatorCalculator::calculate_cross_symbol(&candles, &other, &indicator, &parameters)?
+            } else {
+                IndicatorCalculator::calculate_indicator(&candles, &indicator, &parameters)?
+            };
+            
+            if chart {
+                print_indicator_chart(&candles, &indicator, &results, last, height);
+                return Ok(());
+            }
+            
+            let skip = results.len().saturating_sub(last);
+            let rows: Vec<(DateTime<Utc>, serde_json::Value)> = results.into_iter()
+                .skip(skip)
+                .map(|(time, value)| (time, value.into()))
+                .collect();
+            
+            print_series(std::io::stdout().lock(), format, &rows)?;
+        },
+        
+        CalcCommands::Backfill { symbol, interval, indicator, parameters, from, to } => {
+            let from = from.map(|d| parse_date(&d)).transpose()?;
+            let to = to.map(|d| parse_date(&d)).transpose()?;
+            if let (Some(from), Some(to)) = (from, to) {
+                if from > to {
+                    return Err(anyhow!("--from must not be after --to"));
+                }
+            }
+            let parameters: Option<serde_json::Value> = parameters
+                .map(|p| serde_json::from_str(&p).context("Invalid --parameters JSON"))
+                .transpose()?;
+            
+            let config = AppConfig::load()?;
+            crate::worker::apply_nan_policy(&config.worker.nan_policy)?;
+            let pg = config.database.shared().await?;
+            pg.init_indicator_anomalies_table().await?;
+            
+            // Pipeline inputs are resolved against all configurations of the symbol and interval
+            let jobs: Vec<CalculationJob> = CalculationJob::for_configs(pg.get_enabled_indicator_configs().await?
+                .into_iter()
+                .filter(|c| c.symbol == symbol && c.interval == interval)
+                .collect())
+                .into_iter()
+                .map(|(job, _)| job)
+                .filter(|job| indicator.as_ref().map_or(true, |name| job.indicator_name.eq_ignore_ascii_case(name)))
+                .filter(|job| parameters.as_ref().map_or(true, |params| &job.parameters == params))
+                .collect();
+            
+            if jobs.is_empty() {
+                println!("No enabled indicator configurations match {}:{}{}", symbol, interval,
+                         indicator.map(|name| format!(":{}", name)).unwrap_or_default());
+                return Ok(());
+            }
+            
+            let redis = Arc::new(RedisManager::new(&config.redis.url, config.redis.cache_ttl_seconds, 2).await?);
+            let worker_config = WorkerConfig {
+                cache_ttl_seconds: config.redis.cache_ttl_seconds,
+                completeness_cache_minutes: config.worker.completeness_cache_minutes,
+                batch_size: config.worker.batch_size,
+                max_gap_ratio: config.worker.max_gap_ratio,
+                anomaly: AnomalySettings {
+                    jump_factor: config.worker.anomaly_jump_factor,
+                    constant_run: config.worker.anomaly_constant_run,
+                },
+                ..WorkerConfig::default()
+            };
+            let worker = Worker::new(pg, redis, worker_config, 1);
+            
+            println!("{:<15} | {:<30} | {:<10}", "Indicator", "Parameters", "Values");
+            println!("{:-<15}-+-{:-<30}-+-{:-<10}", "", "", "");
+            
+            let mut failed = 0;
+            for job in &jobs {
+                let written = match worker.backfill(job, from, to).await {
+                    Ok(written) => written.to_string(),
+                    Err(e) => {
+                        failed += 1;
+                        error!("Backfill failed for {}:{}:{}: {}", job.symbol, job.interval, job.indicator_name, e);
+                        "failed".to_string()
+                    }
+                };
+                
+                println!("{:<15} | {:<30} | {:<10}", job.indicator_name, job.parameters.to_string(), written);
+            }
+            
+            if failed > 0 {
+                return Err(anyhow!("{} of {} backfills failed", failed, jobs.len()));
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Draw the closes of the last `last` candles with an indicator's values
+///
+/// Indicators on the price scale (the overlap category, or values within the range of the
+/// closes) are drawn over the prices, others in a panel below. Each numeric property of
+/// multi-valued outputs is a series of its own.
+fn print_indicator_chart(candles: &CandleData, indicator: &str, results: &[(DateTime<Utc>, IndicatorOutput)], 
+                         last: usize, height: usize) {
+    let start = candles.close.len().saturating_sub(last.max(2));
+    let times = &candles.open_time[start..];
+    let closes: Vec<Option<f64>> = candles.close[start..].iter().map(|close| Some(*close)).collect();
+    
+    let values: HashMap<DateTime<Utc>, serde_json::Value> = results.iter()
+        .map(|(time, value)| (*time, value.to_value()))
+        .collect();
+    let properties: Vec<Option<String>> = match results.last().map(|(_, value)| value.to_value()) {
+        Some(serde_json::Value::Object(map)) => map.iter()
+            .filter(|(_, value)| value.is_number())
+            .map(|(name, _)| Some(name.clone()))
+            .collect(),
+        _ => vec![None],
+    };
+    
+    let series: Vec<(String, Vec<Option<f64>>)> = properties.iter()
+        .map(|property| {
+            let label = match property {
+                Some(property) => format!("{}.{}", indicator, property),
+                None => indicator.to_string(),
+            };
+            let points = times.iter()
+                .map(|time| values.get(time)
+                    .and_then(|value| match property {
+                        Some(property) => value.get(property),
+                        None => Some(value),
+                    })
+                    .and_then(serde_json::Value::as_f64))
+                .collect();
+            (label, points)
+        })
+        .collect();
+    
+    let on_price_scale = catalog::find_indicator(indicator).is_some_and(|info| info.category == "overlap");
+    let (low, high) = closes.iter().flatten().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), close| (low.min(*close), high.max(*close)));
+    
+    let mut price = vec![ChartSeries { label: "close", marker: '*', values: &closes }];
+    let mut panel = Vec::new();
+    for (i, (label, points)) in series.iter().enumerate() {
+        let chart_series = ChartSeries { label, marker: chart::OVERLAY_MARKERS[i % chart::OVERLAY_MARKERS.len()], values: points };
+        if on_price_scale || points.iter().flatten().all(|value| (low..=high).contains(value)) {
+            price.push(chart_series);
+        } else {
+            panel.push(chart_series);
+        }
+    }
+    
+    let first = times.first().map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default();
+    let end = times.last().map(|t| t.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default();
+    let width = chart::terminal_width();
+    
+    println!("{} {} with {} ({} candles)\n", candles.symbol, candles.interval, indicator, times.len());
+    print!("{}", chart::line_chart(&price, width, height, Some((&first, &end))));
+    if !panel.is_empty() {
+        let drawn = chart::line_chart(&panel, width, (height / 2).max(5), Some((&first, &end)));
+        if drawn.is_empty() {
+            println!("\nNo {} values in this window", indicator);
+        } else {
+            println!();
+            print!("{}", drawn);
+        }
+    }
+}
+
+/// Build an indicator parameters object from name=value arguments
+///
+/// Values are read as JSON when they parse, so numbers and lists keep their type, and as
+/// strings otherwise.
+fn parse_indicator_params(params: &[String]) -> Result<serde_json::Value> {
+    let mut parameters = serde_json::Map::new();
+    for entry in params {
+        let (name, value) = entry.split_once('=')
+            .ok_or_else(|| anyhow!("Invalid parameter '{}', expected name=value", entry))?;
+        let value = value.trim();
+        let value = serde_json::from_str(value)
+            .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+        parameters.insert(name.trim().to_string(), value);
+    }
+    Ok(serde_json::Value::Object(parameters))
+}
+
+/// Execute a database maintenance command
+async fn execute_db_command(command: DbCommands) -> Result<()> {
+    match command {
+        DbCommands::Retention { command: RetentionCommands::Apply { dry_run } } => {
+            let config = AppConfig::load()?;
+            let policies = &config.retention.policies;
+            
+            if policies.is_empty() {
+                println!("No retention policies configured");
+                return Ok(());
+            }
+            
+            let repository = create_repository().await?;
+            let pg = repository.get_db_connection();
+            pg.init_tables().await?;
+            
+            let outcomes = apply_retention(&pg, policies, dry_run).await?;
+            
+            println!("{:<8} | {:<10} | {:<10} | {:<12} | {:<12} | {:<10}", 
+                     "Interval", "Keep Days", "Cutoff", "Expired", "Downsampled", "Deleted");
+            println!("{:-<8}-+-{:-<10}-+-{:-<10}-+-{:-<12}-+-{:-<12}-+-{:-<10}", "", "", "", "", "", "");
+            
+            for outcome in &outcomes {
+                let deleted = if outcome.timescale_job {
+                    "timescale".to_string()
+                } else {
+                    outcome.deleted.to_string()
+                };
+                
+                println!("{:<8} | {:<10} | {:<10} | {:<12} | {:<12} | {:<10}", 
+                         outcome.policy.interval, outcome.policy.keep_days, 
+                         outcome.cutoff.format("%Y-%m-%d"), outcome.expired, 
+                         outcome.downsampled, deleted);
+            }
+            
+            if dry_run {
+                println!("\nDry run: nothing was changed");
+            } else if outcomes.iter().any(|o| o.timescale_job) {
+                println!("\nA TimescaleDB retention job drops chunks for the '*' policy in the background");
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a saved backtest result command
+async fn execute_backtest_command(command: BacktestCommands, output: PrintFormat) -> Result<()> {
+    match command {
+        BacktestCommands::Batch { 
+            tag, strategy, symbols, intervals, start_date, end_date, initial_capital, engine_profile, concurrency, rank_by 
+        } => {
+            if tag.is_empty() && strategy.is_empty() {
+                return Err(anyhow!("Select the strategies to backtest with --tag or --strategy"));
+            }
+            // Reject an unknown metric before running anything
+            listing::metric_column(&rank_by)?;
+            
+            let repository = create_repository().await?;
+            
+            // Strategies with all the tags, then the ones given by ID
+            let mut strategies = if tag.is_empty() {
+                Vec::new()
+            } else {
+                let filter = StrategyFilter {
+                    tags: tag.iter().map(|t| editor::normalize_tag(t)).collect::<Result<_, _>>()?,
+                    ..StrategyFilter::default()
+                };
+                repository.find_strategies(&filter).await?
+            };
+            for id in &strategy {
+                if !strategies.iter().any(|s| &s.id == id) {
+                    strategies.push(repository.get_strategy(id).await?);
+                }
+            }
+            
+            let jobs = batch::batch_jobs(&repository.get_db_connection(), &strategies, &symbols, &intervals).await?;
+            if jobs.is_empty() {
+                return Err(anyhow!("No strategies match the selection, or they have no assets or timeframes"));
+            }
+            
+            let settings = batch::BatchSettings {
+                start_date: start_date.map(|d| parse_date(&d)).transpose()?,
+                end_date: end_date.map(|d| parse_date(&d)).transpose()?,
+                initial_capital,
+                engine_profile,
+                concurrency,
+            };
+            
+            // Progress goes to stderr when stdout is JSON or CSV
+            let running = format!("Running {} backtests of {} strategies, {} at a time (engine profile: {})", 
+                                  jobs.len(), strategies.len(), concurrency.max(1), settings.engine_profile);
+            if output == PrintFormat::Table { println!("{}", running) } else { eprintln!("{}", running) }
+            
+            let mut results = batch::run_batch(repository.get_db_connection(), jobs, &settings).await;
+            let column = batch::rank_results(&mut results, &rank_by)?;
+            print_batch_results(output, &results, &rank_by, column)?;
+        },
+        
+        BacktestCommands::List { strategy, symbol, interval, since, limit } => {
+            let since = since.map(|d| parse_date(&d)).transpose()?;
+            
+            let repository = create_repository().await?;
+            let pg = repository.get_db_connection();
+            pg.init_backtest_metric_columns().await?;
+            let records = pg.list_backtest_records(
+                strategy.as_deref(), symbol.as_deref(), interval.as_deref(), since, limit,
+            ).await?;
+            
+            if output != PrintFormat::Table {
+                let records = records.iter()
+                    .map(serde_json::to_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                print_records(std::io::stdout().lock(), output, &records)?;
+                return Ok(());
+            }
+            
+            if records.is_empty() {
+                println!("No backtest results.");
+                return Ok(());
+            }
+            
+            println!("{:<6} | {:<24} | {:<10} | {:<8} | {:<23} | {:<6} | {:<9} | {:<7} | {:<8} | {:<8} | {:<6}", 
+                     "ID", "Strategy", "Symbol", "Interval", "Period", "Trades", "Return", "Sharpe", "Max DD", "Win Rate", "PF");
+            println!("{:-<6}-+-{:-<24}-+-{:-<10}-+-{:-<8}-+-{:-<23}-+-{:-<6}-+-{:-<9}-+-{:-<7}-+-{:-<8}-+-{:-<8}-+-{:-<6}", 
+                     "", "", "", "", "", "", "", "", "", "", "");
+            
+            let percent = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.2}%", v));
+            let ratio = |value: Option<f64>| value.map_or("-".to_string(), |v| format!("{:.2}", v));
+            for record in &records {
+                let name = record.strategy_name.clone().unwrap_or_else(|| record.strategy_id.clone());
+                let name: String = name.chars().take(24).collect();
+                println!("{:<6} | {:<24} | {:<10} | {:<8} | {} - {} | {:<6} | {:<9} | {:<7} | {:<8} | {:<8} | {:<6}", 
+                         record.id, name, record.symbol, record.interval,
+                         record.start_date.format("%Y-%m-%d"), record.end_date.format("%Y-%m-%d"),
+                         record.total_trades, percent(record.total_return), ratio(record.sharpe_ratio),
+                         percent(record.max_drawdown), percent(record.win_rate), ratio(record.profit_factor));
+            }
+            
+            if records.len() as i64 == limit {
+                println!("\nShowing the {} most recent results; use --limit or filters to see others", limit);
+            }
+        },
+        
+        BacktestCommands::View { id } => {
+            let repository = create_repository().await?;
+            let result = repository.get_db_connection().get_backtest_result_json(id).await?
+                .ok_or_else(|| anyhow!("Backtest result {} not found", id))?;
+            
+            match output {
+                PrintFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+                PrintFormat::Csv => {
+                    let metrics: Vec<serde_json::Value> = BACKTEST_METRICS.iter()
+                        .map(|(column, _, _)| serde_json::json!({ "metric": column, "value": result.get(*column) }))
+                        .collect();
+                    print_records(std::io::stdout().lock(), output, &metrics)?;
+                },
+                PrintFormat::Table => print_backtest_view(id, &result),
+            }
+        },
+        
+        BacktestCommands::Compare { ids, output } => {
+            let repository = create_repository().await?;
+            let comparison = BacktestComparison::load(&repository.get_db_connection(), &ids).await?;
+            
+            println!("{:<8} | {:<12} | {:<8} | {:<6} | {:<8} | {:<9} | {:<8} | {:<7} | {:<7} | {:<10} | {}", 
+                     "ID", "Symbol", "Interval", "Trades", "Win Rate", "Return", "Max DD", "Sharpe", "PF", "Mean Diff", "p-value");
+            println!("{:-<8}-+-{:-<12}-+-{:-<8}-+-{:-<6}-+-{:-<8}-+-{:-<9}-+-{:-<8}-+-{:-<7}-+-{:-<7}-+-{:-<10}-+-{:-<8}", 
+                     "", "", "", "", "", "", "", "", "", "", "");
+            
+            for result in &comparison.results {
+                let performance = &result.performance;
+                let (mean_diff, p_value) = if result.id == comparison.baseline_id {
+                    ("baseline".to_string(), String::new())
+                } else {
+                    match comparison.difference(result.id) {
+                        Some(difference) => (format!("{:+.3}%", difference.mean_difference), 
+                                             format!("{:.4}", difference.p_value)),
+                        None => ("-".to_string(), "-".to_string()),
+                    }
+                };
+                
+                println!("{:<8} | {:<12} | {:<8} | {:<6} | {:<8} | {:<9} | {:<8} | {:<7.2} | {:<7.2} | {:<10} | {}", 
+                         result.id, result.symbol, result.interval, performance.total_trades,
+                         format!("{:.2}%", performance.win_rate), format!("{:.2}%", performance.total_return),
+                         format!("{:.2}%", performance.max_drawdown), performance.sharpe_ratio,
+                         performance.profit_factor, mean_diff, p_value);
+            }
+            
+            let untested = comparison.results.len() - 1 - comparison.differences.len();
+            if untested > 0 {
+                println!("\n{} results were not tested: fewer than two stored trades on either side or no variance", untested);
+            }
+            println!("\nMean Diff is the difference in mean trade return to the baseline; p-values below 0.05 suggest the difference is not chance");
+            
+            if let Some(output_path) = output {
+                comparison.write_report(&output_path)?;
+                println!("Comparison written to: {}", output_path.display());
+            }
+        },
+        
+        BacktestCommands::ShowEquity { id, chart, height } => {
+            let repository = create_repository().await?;
+            let curve = repository.get_db_connection().get_backtest_equity_curve(id).await?;
+            let equity: Vec<f64> = curve.iter().map(|(_, equity)| *equity).collect();
+            let initial = equity[0];
+            let percent = |value: f64| if initial != 0.0 { (value / initial - 1.0) * 100.0 } else { 0.0 };
+            
+            if chart {
+                let values: Vec<Option<f64>> = equity.iter().map(|e| Some(*e)).collect();
+                let first = curve[0].0.format("%Y-%m-%d").to_string();
+                let end = curve[curve.len() - 1].0.format("%Y-%m-%d").to_string();
+                print!("{}", chart::line_chart(
+                    &[ChartSeries { label: "equity", marker: '*', values: &values }],
+                    chart::terminal_width(), height, Some((&first, &end)),
+                ));
+            } else {
+                println!("{:<20} | {:>14} | {:>9}", "Time", "Equity", "Return");
+                println!("{:-<20}-+-{:-<14}-+-{:-<9}", "", "", "");
+                for (time, value) in &curve {
+                    println!("{:<20} | {:>14.2} | {:>8.2}%", time.format("%Y-%m-%d %H:%M"), value, percent(*value));
+                }
+                println!("\n{}", chart::sparkline(&equity, chart::terminal_width()));
+            }
+            
+            let last = equity[equity.len() - 1];
+            println!("\nBacktest {}: {} trades, equity {:.2} -> {:.2} ({:+.2}%)", 
+                     id, curve.len() - 1, initial, last, percent(last));
+            if curve.len() == 1 {
+                println!("No trades stored; archived results keep only their summary");
+            }
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a paper trading command
+async fn execute_paper_command(command: PaperCommands) -> Result<()> {
+    let config = AppConfig::load()?;
+    let repository = create_repository().await?;
+    let pg = repository.get_db_connection();
+    
+    match command {
+        PaperCommands::Run { account, since, route_orders } => {
+            SignalLedger::from_config(pg.clone(), &config.notifications).init_table().await?;
+            let guard = RiskGuard::new(pg.clone(), config.risk.clone(), WebhookNotifier::from_config(&config.notifications));
+            guard.init_table().await?;
+            
+            let mut executor = PaperExecutor::new(pg.clone(), &account, config.paper.initial_capital)
+                .with_risk_guard(guard);
+            executor.init_tables().await?;
+            
+            if route_orders {
+                if !config.execution.enabled {
+                    return Err(anyhow!("Order routing is disabled; set enabled = true in [execution] or EXECUTION_ENABLED=true"));
+                }
+                
+                executor = executor.with_order_router(OrderRouter::from_config(pg, &config.execution).await?);
+                println!("Routing fills to {} (max order value {:.2}, max {} orders per day)", 
+                         config.execution.rest_url, config.execution.max_order_notional, 
+                         config.execution.max_orders_per_day);
+            }
+            
+            let since = since.map(|d| parse_date(&d)).transpose()?.unwrap_or_else(Utc::now);
+            println!("Paper trading account '{}' from signals sent after {} (Ctrl+C to stop)", 
+                     account, since.format("%Y-%m-%d %H:%M:%S"));
+            executor.run(since, std::time::Duration::from_secs(config.paper.poll_interval_seconds)).await?;
+        },
+        
+        PaperCommands::Positions { account } => {
+            let executor = PaperExecutor::new(pg, &account, config.paper.initial_capital);
+            executor.init_tables().await?;
+            let positions = executor.positions().await?;
+            
+            if positions.is_empty() {
+                println!("No open paper positions in account '{}'", account);
+                return Ok(());
+            }
+            
+            println!("{:<36} | {:<12} | {:<5} | {:<14} | {:<12} | {:<12} | {:<12} | {:<16}", 
+                     "Strategy", "Symbol", "Side", "Quantity", "Entry", "Price", "Unrealized", "Opened");
+            println!("{:-<36}-+-{:-<12}-+-{:-<5}-+-{:-<14}-+-{:-<12}-+-{:-<12}-+-{:-<12}-+-{:-<16}", 
+                     "", "", "", "", "", "", "", "");
+            
+            for position in &positions {
+                let price = executor.latest_price(&position.symbol).await?;
+                let unrealized = price.map(|p| format!("{:.2}", position.pnl(position.quantity, p)))
+                    .unwrap_or_else(|| "N/A".to_string());
+                
+                println!("{:<36} | {:<12} | {:<5} | {:<14.6} | {:<12.2} | {:<12} | {:<12} | {:<16}", 
+                         position.strategy_id, position.symbol, 
+                         if position.is_long { "long" } else { "short" },
+                         position.quantity, position.entry_price,
+                         price.map(|p| format!("{:.2}", p)).unwrap_or_else(|| "N/A".to_string()),
+                         unrealized, position.opened_at.format("%Y-%m-%d %H:%M"));
+            }
+        },
+        
+        PaperCommands::Trades { account, limit } => {
+            let executor = PaperExecutor::new(pg, &account, config.paper.initial_capital);
+            executor.init_tables().await?;
+            let trades = executor.trades(limit).await?;
+            
+            if trades.is_empty() {
+                println!("No paper trades in account '{}'", account);
+                return Ok(());
+            }
+            
+            println!("{:<19} | {:<36} | {:<12} | {:<4} | {:<14} | {:<12} | {:<12} | {}", 
+                     "Time", "Strategy", "Symbol", "Side", "Quantity", "Price", "Realized", "Rule");
+            println!("{:-<19}-+-{:-<36}-+-{:-<12}-+-{:-<4}-+-{:-<14}-+-{:-<12}-+-{:-<12}-+-{:-<12}", 
+                     "", "", "", "", "", "", "", "");
+            
+            for trade in &trades {
+                println!("{:<19} | {:<36} | {:<12} | {:<4} | {:<14.6} | {:<12.2} | {:<12.2} | {}", 
+                         trade.executed_at.format("%Y-%m-%d %H:%M:%S"), trade.strategy_id, trade.symbol,
+                         trade.side, trade.quantity, trade.price, trade.realized_pnl, trade.rule_id);
+            }
+        },
+        
+        PaperCommands::Orders { limit } => {
+            let journal = OrderJournal::new(pg);
+            journal.init_table().await?;
+            let orders = journal.recent(limit).await?;
+            
+            if orders.is_empty() {
+                println!("No orders journaled");
+                return Ok(());
+            }
+            
+            println!("{:<19} | {:<12} | {:<4} | {:<14} | {:<16} | {:<14} | {:<12} | {}", 
+                     "Time", "Symbol", "Side", "Quantity", "Status", "Filled", "Avg Price", "Error");
+            println!("{:-<19}-+-{:-<12}-+-{:-<4}-+-{:-<14}-+-{:-<16}-+-{:-<14}-+-{:-<12}-+-{:-<20}", 
+                     "", "", "", "", "", "", "", "");
+            
+            for order in &orders {
+                println!("{:<19} | {:<12} | {:<4} | {:<14.6} | {:<16} | {:<14.6} | {:<12} | {}", 
+                         order.created_at.format("%Y-%m-%d %H:%M:%S"), order.symbol, order.side, 
+                         order.quantity, order.status, order.executed_quantity,
+                         order.average_price.map(|p| format!("{:.2}", p)).unwrap_or_else(|| "N/A".to_string()),
+                         order.error.as_deref().unwrap_or(""));
+            }
+        },
+        
+        PaperCommands::Equity { account, limit } => {
+            let executor = PaperExecutor::new(pg, &account, config.paper.initial_capital);
+            executor.init_tables().await?;
+            let history = executor.equity_history(limit).await?;
+            
+            println!("{:<19} | {:<14} | {:<14} | {:<14} | {:<9}", 
+                     "Time", "Balance", "Unrealized", "Equity", "Positions");
+            println!("{:-<19}-+-{:-<14}-+-{:-<14}-+-{:-<14}-+-{:-<9}", "", "", "", "", "");
+            
+            for snapshot in &history {
+                println!("{:<19} | {:<14.2} | {:<14.2} | {:<14.2} | {:<9}", 
+                         snapshot.time.format("%Y-%m-%d %H:%M:%S"), snapshot.balance,
+                         snapshot.unrealized_pnl, snapshot.equity, snapshot.open_positions);
+            }
+            
+            let current = executor.snapshot().await?;
+            let return_percent = (current.equity / config.paper.initial_capital - 1.0) * 100.0;
+            println!("\nCurrent equity: {:.2} ({:+.2}% since start, {} open positions)", 
+                     current.equity, return_percent, current.open_positions);
+        },
+    }
+    
+    Ok(())
+}
+
+/// Execute a risk guard command
+async fn execute_risk_command(command: RiskCommands) -> Result<()> {
+    let config = AppConfig::load()?;
+    let repository = create_repository().await?;
+    let pg = repository.get_db_connection();
+    
+    let guard = RiskGuard::new(pg.clone(), config.risk.clone(), WebhookNotifier::from_config(&config.notifications));
+    guard.init_table().await?;
+    
+    let (account, status) = match command {
+        RiskCommands::Status { account } => {
+            let executor = PaperExecutor::new(pg, &account, config.paper.initial_capital);
+            executor.init_tables().await?;
+            let status = guard.status(&executor.snapshot().await?).await?;
+            (account, status)
+        },
+        RiskCommands::Reset { account } => {
+            let executor = PaperExecutor::new(pg, &account, config.paper.initial_capital);
+            executor.init_tables().await?;
+            let status = guard.reset(&executor.snapshot().await?).await?;
+            println!("Risk guard reset for account '{}'", account);
+            (account, status)
+        },
+    };
+    
+    print_risk_status(&account, &status, &guard);
+    Ok(())
+}
+
+/// Print the limit usage of an account
+fn print_risk_status(account: &str, status: &RiskStatus, guard: &RiskGuard) {
+    let limits = guard.limits();
+    
+    println!("Account: {}", account);
+    if status.state.halted {
+        println!("Trading: HALTED since {} ({})", 
+                 status.state.halted_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default(),
+                 status.state.reason.as_deref().unwrap_or("unknown reason"));
+    } else {
+        println!("Trading: active");
+    }
+    println!("Equity: {:.2} (day start {:.2}, peak {:.2})", 
+             status.equity, status.state.day_start_equity, status.state.peak_equity);
+    
+    println!("\n{:<16} | {:<10} | {:<10}", "Limit", "Current", "Max");
+    println!("{:-<16}-+-{:-<10}-+-{:-<10}", "", "", "");
+    println!("{:<16} | {:<10} | {:<10}", "Daily loss", 
+             format!("{:.2}%", status.daily_loss_percent), format!("{:.2}%", limits.max_daily_loss_percent));
+    println!("{:<16} | {:<10} | {:<10}", "Drawdown", 
+             format!("{:.2}%", status.drawdown_percent), format!("{:.2}%", limits.max_drawdown_percent));
+    println!("{:<16} | {:<10} | {:<10}", "Open positions", status.open_positions, limits.max_open_positions);
+}
@@ -0,0 +1,282 @@
+// src/strategy/comparison.rs
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::strategy::repository_helpers::performance_from_record;
+use crate::strategy::schema::StrategyPerformance;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// A saved backtest result taking part in a comparison
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedBacktest {
+    pub id: i32,
+    pub symbol: String,
+    pub interval: String,
+    pub performance: StrategyPerformance,
+    /// Trades found in strategy_backtest_trades (none once a result is archived)
+    pub trades_loaded: usize,
+    /// Mean return per trade in percent
+    pub mean_trade_return: Option<f64>,
+    #[serde(skip)]
+    trade_returns: Vec<f64>,
+}
+
+/// Welch's t-test of the per-trade returns of a result against the baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct ReturnDifference {
+    pub baseline_id: i32,
+    pub id: i32,
+    /// Mean trade return of the result minus that of the baseline, in percent
+    pub mean_difference: f64,
+    pub t_statistic: f64,
+    pub degrees_of_freedom: f64,
+    /// Two-sided p-value; small values mean the difference is unlikely to be chance
+    pub p_value: f64,
+}
+
+/// Side-by-side comparison of saved backtest results; the first result is the baseline
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestComparison {
+    pub generated_at: DateTime<Utc>,
+    pub baseline_id: i32,
+    pub results: Vec<ComparedBacktest>,
+    /// One entry per result with enough trades on both sides to test
+    pub differences: Vec<ReturnDifference>,
+}
+
+impl BacktestComparison {
+    /// Load the results in the given order and test each against the first one
+    pub async fn load(pg: &PostgresManager, ids: &[i32]) -> Result<Self> {
+        if ids.len() < 2 {
+            return Err(Error::validation("At least two backtest results are needed for a comparison"));
+        }
+
+        pg.init_backtest_metric_columns().await?;
+        let records = pg.get_backtest_records(ids).await?;
+        let mut results = Vec::with_capacity(ids.len());
+
+        for id in ids {
+            let record = records.iter()
+                .find(|record| record.id == *id)
+                .ok_or_else(|| Error::not_found(format!("Backtest result {}", id)))?;
+            let trade_returns = pg.get_backtest_trade_returns(*id).await?;
+
+            results.push(ComparedBacktest {
+                id: record.id,
+                symbol: record.symbol.clone(),
+                interval: record.interval.clone(),
+                performance: performance_from_record(record),
+                trades_loaded: trade_returns.len(),
+                mean_trade_return: mean(&trade_returns),
+                trade_returns,
+            });
+        }
+
+        let baseline = &results[0];
+        let differences = results[1..].iter()
+            .filter_map(|result| welch_t_test(&baseline.trade_returns, &result.trade_returns)
+                .map(|(mean_difference, t_statistic, degrees_of_freedom, p_value)| ReturnDifference {
+                    baseline_id: baseline.id,
+                    id: result.id,
+                    mean_difference,
+                    t_statistic,
+                    degrees_of_freedom,
+                    p_value,
+                }))
+            .collect();
+
+        Ok(Self {
+            generated_at: Utc::now(),
+            baseline_id: baseline.id,
+            results,
+            differences,
+        })
+    }
+
+    /// Get the significance test of a result against the baseline, if it could be run
+    pub fn difference(&self, id: i32) -> Option<&ReturnDifference> {
+        self.differences.iter().find(|difference| difference.id == id)
+    }
+
+    /// One row per result with its key metrics and the test against the baseline
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "id,symbol,interval,total_trades,win_rate,total_return,max_drawdown,sharpe_ratio,\
+             profit_factor,expectancy,benchmark_return,excess_return,alpha,beta,\
+             sortino_ratio,calmar_ratio,value_at_risk,expected_shortfall,exposure_percent,turnover,\
+             mean_trade_return,mean_difference,t_statistic,p_value\n"
+        );
+
+        for result in &self.results {
+            let performance = &result.performance;
+            let difference = self.difference(result.id);
+            let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+
+            let _ = writeln!(
+                csv, "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                result.id, result.symbol, result.interval, performance.total_trades,
+                performance.win_rate, performance.total_return, performance.max_drawdown,
+                performance.sharpe_ratio, performance.profit_factor, performance.expectancy,
+                performance.benchmark_return, performance.excess_return, performance.alpha, performance.beta,
+                performance.sortino_ratio, performance.calmar_ratio, performance.value_at_risk,
+                performance.expected_shortfall, performance.exposure_percent, performance.turnover,
+                optional(result.mean_trade_return),
+                optional(difference.map(|d| d.mean_difference)),
+                optional(difference.map(|d| d.t_statistic)),
+                optional(difference.map(|d| d.p_value)),
+            );
+        }
+
+        csv
+    }
+
+    /// Write the comparison as JSON or CSV, chosen by the file extension
+    pub fn write_report(&self, path: &Path) -> Result<()> {
+        let content = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::to_string_pretty(self)?,
+            Some("csv") => self.to_csv(),
+            _ => return Err(Error::validation(format!("Unsupported report format: {} (expected .json or .csv)", path.display()))),
+        };
+
+        std::fs::write(path, content).map_err(Error::file("write", path))
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn sample_variance(values: &[f64], mean: f64) -> f64 {
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+/// Welch's t-test for a difference in means without assuming equal variances
+///
+/// Returns (mean difference, t, degrees of freedom, two-sided p-value), or None when
+/// either side has fewer than two values or both sides have no variance.
+fn welch_t_test(baseline: &[f64], other: &[f64]) -> Option<(f64, f64, f64, f64)> {
+    if baseline.len() < 2 || other.len() < 2 {
+        return None;
+    }
+
+    let (mean_a, mean_b) = (mean(baseline)?, mean(other)?);
+    let (n_a, n_b) = (baseline.len() as f64, other.len() as f64);
+    let se_a = sample_variance(baseline, mean_a) / n_a;
+    let se_b = sample_variance(other, mean_b) / n_b;
+    let se = se_a + se_b;
+    if se <= 0.0 {
+        return None;
+    }
+
+    let t = (mean_b - mean_a) / se.sqrt();
+    let df = se.powi(2) / (se_a.powi(2) / (n_a - 1.0) + se_b.powi(2) / (n_b - 1.0));
+    let p_value = regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5).clamp(0.0, 1.0);
+
+    Some((mean_b - mean_a, t, df, p_value))
+}
+
+/// Natural log of the gamma function (Lanczos approximation, g = 7)
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        return std::f64::consts::PI.ln() - (std::f64::consts::PI * x).sin().ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let mut sum = COEFFICIENTS[0];
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    // The continued fraction converges quickly only on this side of the mean
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - front * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz's method)
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+
+        // Even step
+        let numerator = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 + numerator * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + numerator / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        // Odd step
+        let numerator = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 + numerator * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + numerator / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
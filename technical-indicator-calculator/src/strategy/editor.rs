@@ -0,0 +1,156 @@
+// src/strategy/editor.rs
+use crate::error::{Error, Result};
+use crate::strategy::schema::{Strategy, StrategyIndicator, StrategyParameter, StrategyRule};
+use chrono::Utc;
+use uuid::Uuid;
+
+/// Add an indicator to a strategy, rejecting duplicate IDs
+pub fn add_indicator(strategy: &mut Strategy, indicator: StrategyIndicator) -> Result<()> {
+    if strategy.indicators.iter().any(|i| i.id == indicator.id) {
+        return Err(Error::validation(format!("Strategy already has an indicator with ID: {}", indicator.id)));
+    }
+
+    strategy.indicators.push(indicator);
+    Ok(())
+}
+
+/// Remove an indicator from a strategy by ID
+pub fn remove_indicator(strategy: &mut Strategy, indicator_id: &str) -> Result<StrategyIndicator> {
+    let position = strategy.indicators.iter()
+        .position(|i| i.id == indicator_id)
+        .ok_or_else(|| Error::not_found(format!("Indicator {} in strategy", indicator_id)))?;
+
+    Ok(strategy.indicators.remove(position))
+}
+
+/// Add a rule to a strategy, rejecting duplicate IDs
+pub fn add_rule(strategy: &mut Strategy, rule: StrategyRule) -> Result<()> {
+    if strategy.rules.iter().any(|r| r.id == rule.id) {
+        return Err(Error::validation(format!("Strategy already has a rule with ID: {}", rule.id)));
+    }
+
+    strategy.rules.push(rule);
+    strategy.rules.sort_by_key(|r| r.priority);
+    Ok(())
+}
+
+/// Remove a rule from a strategy by ID
+pub fn remove_rule(strategy: &mut Strategy, rule_id: &str) -> Result<StrategyRule> {
+    let position = strategy.rules.iter()
+        .position(|r| r.id == rule_id)
+        .ok_or_else(|| Error::not_found(format!("Rule {} in strategy", rule_id)))?;
+
+    Ok(strategy.rules.remove(position))
+}
+
+/// Set the value of an existing strategy parameter from its string representation
+pub fn set_parameter(strategy: &mut Strategy, parameter_id: &str, value: &str) -> Result<()> {
+    let parameter = strategy.parameters.get_mut(parameter_id)
+        .ok_or_else(|| Error::not_found(format!("Parameter {} in strategy", parameter_id)))?;
+
+    set_parameter_value(parameter, parameter_id, value)
+}
+
+/// Parse a string value according to the parameter type and check its bounds
+pub fn set_parameter_value(parameter: &mut StrategyParameter, parameter_id: &str, value: &str) -> Result<()> {
+    match parameter {
+        StrategyParameter::Integer { value: current, min, max, .. } => {
+            let parsed = value.parse::<i64>()
+                .map_err(|_| Error::validation(format!("Parameter {} expects an integer, got: {}", parameter_id, value)))?;
+
+            if parsed < *min || parsed > *max {
+                return Err(Error::validation(format!("Parameter {} must be between {} and {}, got: {}", parameter_id, min, max, parsed)));
+            }
+
+            *current = parsed;
+        },
+        StrategyParameter::Float { value: current, min, max, .. } => {
+            let parsed = value.parse::<f64>()
+                .map_err(|_| Error::validation(format!("Parameter {} expects a number, got: {}", parameter_id, value)))?;
+
+            if parsed < *min || parsed > *max {
+                return Err(Error::validation(format!("Parameter {} must be between {} and {}, got: {}", parameter_id, min, max, parsed)));
+            }
+
+            *current = parsed;
+        },
+        StrategyParameter::Boolean { value: current, .. } => {
+            *current = value.parse::<bool>()
+                .map_err(|_| Error::validation(format!("Parameter {} expects true or false, got: {}", parameter_id, value)))?;
+        },
+        StrategyParameter::String { value: current, options, .. } => {
+            if let Some(options) = options {
+                if !options.iter().any(|o| o == value) {
+                    return Err(Error::validation(format!("Parameter {} must be one of {:?}, got: {}", parameter_id, options, value)));
+                }
+            }
+
+            *current = value.to_string();
+        },
+    }
+
+    Ok(())
+}
+
+/// Normalize a tag to lowercase, rejecting empty tags and tags with whitespace or commas
+pub fn normalize_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() || tag.chars().any(|c| c.is_whitespace() || c == ',') {
+        return Err(Error::validation(format!("Invalid tag '{}', expected a single word such as momentum", tag)));
+    }
+
+    Ok(tag)
+}
+
+/// Add tags to a strategy, returning those it didn't have yet
+pub fn add_tags(strategy: &mut Strategy, tags: &[String]) -> Result<Vec<String>> {
+    let mut added = Vec::new();
+    for tag in tags {
+        let tag = normalize_tag(tag)?;
+        if !strategy.tags.contains(&tag) {
+            strategy.tags.push(tag.clone());
+            added.push(tag);
+        }
+    }
+
+    strategy.tags.sort();
+    Ok(added)
+}
+
+/// Remove tags from a strategy, returning those it had
+pub fn remove_tags(strategy: &mut Strategy, tags: &[String]) -> Vec<String> {
+    let tags: Vec<String> = tags.iter().map(|tag| tag.trim().to_lowercase()).collect();
+    let removed = strategy.tags.iter().filter(|tag| tags.contains(tag)).cloned().collect();
+    strategy.tags.retain(|tag| !tags.contains(tag));
+
+    removed
+}
+
+/// Deep-copy a strategy under a new UUID, recording where it was cloned from
+pub fn clone_strategy(strategy: &Strategy, name: Option<&str>) -> Strategy {
+    let mut clone = strategy.clone();
+
+    clone.id = Uuid::new_v4().to_string();
+    clone.name = match name {
+        Some(name) => name.to_string(),
+        None => format!("{} (copy)", strategy.name),
+    };
+    clone.created_at = Utc::now();
+    clone.updated_at = Utc::now();
+    clone.performance = None;
+    clone.metadata.insert("cloned_from".to_string(), serde_json::Value::from(strategy.id.clone()));
+
+    clone
+}
+
+/// Apply `param=value` overrides to a strategy
+pub fn apply_parameter_overrides(strategy: &mut Strategy, overrides: &[String]) -> Result<()> {
+    for entry in overrides {
+        let (parameter_id, value) = entry.split_once('=')
+            .ok_or_else(|| Error::validation(format!("Invalid parameter override '{}', expected param=value", entry)))?;
+
+        set_parameter(strategy, parameter_id.trim(), value.trim())?;
+    }
+
+    Ok(())
+}
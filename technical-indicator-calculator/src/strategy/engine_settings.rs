@@ -0,0 +1,103 @@
+// src/strategy/engine_settings.rs
+use serde::{Deserialize, Serialize};
+
+/// Which exit is assumed to fill first when a candle touches both stop loss and take profit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntrabarAssumption {
+    /// Assume the stop loss is hit before the take profit (pessimistic)
+    #[serde(rename = "stop_first")]
+    StopFirst,
+    /// Assume the take profit is hit before the stop loss (optimistic)
+    #[serde(rename = "target_first")]
+    TargetFirst,
+    /// Only evaluate exits against the candle close
+    #[serde(rename = "close_only")]
+    CloseOnly,
+}
+
+/// Named backtest engine configuration, stored in the database and recorded with each backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineSettings {
+    /// Unique profile name (e.g. "conservative")
+    pub name: String,
+    /// Human-readable description of the profile
+    #[serde(default)]
+    pub description: String,
+    /// Commission charged per fill as a percentage of the traded value
+    pub commission_percent: f64,
+    /// Slippage applied per fill as a percentage of the fill price
+    pub slippage_percent: f64,
+    /// Random variation of each fill's slippage, up to this many percentage points either way
+    #[serde(default)]
+    pub slippage_jitter_percent: f64,
+    /// How to resolve candles that touch both exit levels
+    pub intrabar_assumption: IntrabarAssumption,
+    /// Number of candles between a signal and its execution; delayed actions execute at
+    /// the open of that candle, undelayed ones at the signal candle's close
+    #[serde(default)]
+    pub execution_latency_bars: u32,
+    /// Largest entry fill as a percentage of the value traded in its candle (volume times
+    /// price); larger entries and scale-ins are reduced to it
+    #[serde(default)]
+    pub max_volume_participation: Option<f64>,
+}
+
+impl EngineSettings {
+    /// Pessimistic fills and costs
+    pub fn conservative() -> Self {
+        Self {
+            name: "conservative".to_string(),
+            description: "Stop loss fills first, higher costs and one candle of execution latency".to_string(),
+            commission_percent: 0.1,
+            slippage_percent: 0.05,
+            slippage_jitter_percent: 0.0,
+            intrabar_assumption: IntrabarAssumption::StopFirst,
+            execution_latency_bars: 1,
+            max_volume_participation: Some(1.0),
+        }
+    }
+
+    /// Favourable fills and costs, useful as an upper bound
+    pub fn optimistic() -> Self {
+        Self {
+            name: "optimistic".to_string(),
+            description: "Take profit fills first, low costs and immediate execution".to_string(),
+            commission_percent: 0.02,
+            slippage_percent: 0.0,
+            slippage_jitter_percent: 0.0,
+            intrabar_assumption: IntrabarAssumption::TargetFirst,
+            execution_latency_bars: 0,
+            max_volume_participation: None,
+        }
+    }
+
+    /// Profiles that are always available, even when not stored in the database
+    pub fn builtin_profiles() -> Vec<Self> {
+        vec![Self::default(), Self::conservative(), Self::optimistic()]
+    }
+
+    /// Find a built-in profile by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        Self::builtin_profiles().into_iter().find(|p| p.name == name)
+    }
+
+    /// Total cost of a round trip (entry and exit) in percent
+    pub fn round_trip_cost_percent(&self) -> f64 {
+        2.0 * (self.commission_percent + self.slippage_percent)
+    }
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            description: "Typical exchange costs with stop loss filling first".to_string(),
+            commission_percent: 0.075,
+            slippage_percent: 0.02,
+            slippage_jitter_percent: 0.0,
+            intrabar_assumption: IntrabarAssumption::StopFirst,
+            execution_latency_bars: 0,
+            max_volume_participation: None,
+        }
+    }
+}
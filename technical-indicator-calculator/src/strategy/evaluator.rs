@@ -1,32 +1,236 @@
 // src/strategy/evaluator.rs
-use crate::database::models::CandleData;
-use crate::database::postgres::PostgresManager;
+use crate::cache::backtests::{fingerprint, BacktestCache};
+use crate::cache::results::IndicatorResultCache;
+use crate::database::models::{AlignedFrame, CandleData, FrameIndicator, IndicatorSeries};
+use crate::database::storage::Storage;
+use crate::error::{Error, Result};
 use crate::indicators::calculator::IndicatorCalculator;
-use crate::strategy::schema::{Strategy, StrategyPerformance};
-use anyhow::Result;
-use chrono::{DateTime, Utc};
+use crate::indicators::cross_symbol;
+use crate::signals::Signal;
+use crate::strategy::accounting::ContractSpec;
+use crate::strategy::engine_settings::{EngineSettings, IntrabarAssumption};
+use crate::strategy::rules::RuleSet;
+use crate::strategy::script;
+use crate::strategy::statistics;
+use crate::strategy::schema::{OrderType, RuleAction, StopLevel, Strategy, StrategyPerformance};
+use crate::utils::utils::interval_to_seconds;
+use chrono::{DateTime, Datelike, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-/// Represents a position in the market
-#[derive(Debug, Clone)]
-struct Position {
-    /// Whether the position is long or short
-    is_long: bool,
+/// Part of a position opened by a single entry or scale-in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Tranche {
     /// Entry price
     entry_price: f64,
-    /// Size of the position as percentage of capital
+    /// Size of the tranche as percentage of capital
     size_percent: f64,
     /// Entry time
     entry_time: DateTime<Utc>,
+    /// Funding and borrow costs accrued while open, in percent of the tranche
+    carry_percent: f64,
+}
+
+/// Represents a position in the market, built from one or more tranches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Position {
+    /// Whether the position is long or short
+    is_long: bool,
+    /// Open tranches, oldest first
+    tranches: Vec<Tranche>,
     /// Stop loss price, if any
     stop_loss: Option<f64>,
     /// Take profit price, if any
     take_profit: Option<f64>,
 }
 
+impl Position {
+    fn open(is_long: bool, entry_price: f64, size_percent: f64, entry_time: DateTime<Utc>) -> Self {
+        Self {
+            is_long,
+            tranches: vec![Tranche { entry_price, size_percent, entry_time, carry_percent: 0.0 }],
+            stop_loss: None,
+            take_profit: None,
+        }
+    }
+    
+    /// Total size of the open tranches as percentage of capital
+    fn size_percent(&self) -> f64 {
+        self.tranches.iter().map(|t| t.size_percent).sum()
+    }
+    
+    /// Size-weighted average entry price of the open tranches
+    fn average_entry_price(&self) -> f64 {
+        let size = self.size_percent();
+        if size <= 0.0 {
+            return 0.0;
+        }
+        
+        self.tranches.iter().map(|t| t.entry_price * t.size_percent).sum::<f64>() / size
+    }
+    
+    /// Add a tranche without growing the position beyond `max_size_percent`; returns the size added
+    fn scale_in(&mut self, entry_price: f64, size_percent: f64, entry_time: DateTime<Utc>, max_size_percent: f64) -> f64 {
+        let size = size_percent.min(max_size_percent - self.size_percent());
+        if size <= 0.0 {
+            return 0.0;
+        }
+        
+        self.tranches.push(Tranche { entry_price, size_percent: size, entry_time, carry_percent: 0.0 });
+        size
+    }
+    
+    /// Remove `percent` of the position, oldest tranches first, splitting a tranche if needed
+    fn scale_out(&mut self, percent: f64) -> Vec<Tranche> {
+        let mut remaining = self.size_percent() * percent.clamp(0.0, 100.0) / 100.0;
+        let mut closed = Vec::new();
+        
+        while remaining > 0.0 && !self.tranches.is_empty() {
+            let tranche = &mut self.tranches[0];
+            if tranche.size_percent <= remaining {
+                remaining -= tranche.size_percent;
+                closed.push(self.tranches.remove(0));
+            } else {
+                tranche.size_percent -= remaining;
+                closed.push(Tranche { size_percent: remaining, ..tranche.clone() });
+                remaining = 0.0;
+            }
+        }
+        
+        closed
+    }
+}
+
+/// Entry order waiting for the price to reach its level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingOrder {
+    is_long: bool,
+    order_type: OrderType,
+    /// Limit price of limit orders, stop price of stop and stop-limit orders
+    price: f64,
+    /// Limit price of a stop-limit order
+    limit_price: Option<f64>,
+    size_percent: f64,
+    placed_idx: usize,
+    placed_time: DateTime<Utc>,
+    /// Candle after which the order is cancelled; None keeps it until filled
+    expires_idx: Option<usize>,
+    /// Whether the stop of a stop-limit order has been hit
+    triggered: bool,
+}
+
+impl PendingOrder {
+    /// Fill price of the order on a candle, or None if the price didn't reach the order
+    ///
+    /// Gaps through the order price fill at the open: better than the limit for limit
+    /// orders and worse than the stop for stop orders. A stop-limit order triggered on
+    /// this candle only fills here if the trigger price is within its limit.
+    fn fill_price(&mut self, open: f64, high: f64, low: f64) -> Option<f64> {
+        match self.order_type {
+            OrderType::Limit => limit_fill(self.is_long, self.price, open, high, low),
+            OrderType::Stop => stop_fill(self.is_long, self.price, open, high, low),
+            OrderType::StopLimit => {
+                let limit = self.limit_price.unwrap_or(self.price);
+                
+                if self.triggered {
+                    return limit_fill(self.is_long, limit, open, high, low);
+                }
+                
+                let trigger = stop_fill(self.is_long, self.price, open, high, low)?;
+                self.triggered = true;
+                
+                let within_limit = if self.is_long { trigger <= limit } else { trigger >= limit };
+                within_limit.then_some(trigger)
+            },
+        }
+    }
+    
+    fn cancel(&self, cancelled_time: DateTime<Utc>, reason: &str) -> UnfilledOrder {
+        UnfilledOrder {
+            is_long: self.is_long,
+            order_type: self.order_type,
+            price: self.price,
+            size_percent: self.size_percent,
+            placed_time: self.placed_time,
+            cancelled_time,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Entry order that was cancelled before it filled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UnfilledOrder {
+    is_long: bool,
+    order_type: OrderType,
+    price: f64,
+    size_percent: f64,
+    placed_time: DateTime<Utc>,
+    cancelled_time: DateTime<Utc>,
+    /// Why the order was cancelled ("Expired", "No Volume" or "End of Simulation")
+    reason: String,
+}
+
+/// Action of a rule waiting out the engine's execution latency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedAction {
+    /// Candle at whose open the action executes
+    execute_idx: usize,
+    action: RuleAction,
+}
+
+/// Trades and cancelled orders of a simulation run
+#[derive(Debug, Clone, Default)]
+struct SimulationResult {
+    trades: Vec<TradeResult>,
+    unfilled_orders: Vec<UnfilledOrder>,
+    /// Equity after every position was closed
+    equity: f64,
+    /// Equity marked to market at the close of every simulated candle
+    equity_curve: Vec<f64>,
+}
+
+/// Progress of a simulation, checkpointed after every month of candles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SimulationState {
+    /// Next candle to simulate
+    next_idx: usize,
+    position: Option<Position>,
+    pending_order: Option<PendingOrder>,
+    equity: f64,
+    max_equity: f64,
+    trades: Vec<TradeResult>,
+    unfilled_orders: Vec<UnfilledOrder>,
+    /// Equity marked to market at the close of every simulated candle, after the initial capital
+    #[serde(default)]
+    equity_curve: Vec<f64>,
+    /// Triggered actions that execute on a later candle
+    #[serde(default)]
+    queued_actions: Vec<QueuedAction>,
+}
+
+impl SimulationState {
+    fn new(first_idx: usize, initial_capital: f64) -> Self {
+        Self {
+            next_idx: first_idx,
+            position: None,
+            pending_order: None,
+            equity: initial_capital,
+            max_equity: initial_capital,
+            trades: Vec::new(),
+            unfilled_orders: Vec::new(),
+            equity_curve: vec![initial_capital],
+            queued_actions: Vec::new(),
+        }
+    }
+}
+
 /// Result of a completed trade
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct TradeResult {
     /// Whether the trade was long or short
     is_long: bool,
@@ -46,51 +250,384 @@ struct TradeResult {
     pl_percent: f64,
 }
 
+/// Buy-and-hold comparison of a backtest
+#[derive(Debug, Clone, Default)]
+struct Benchmark {
+    /// Buy-and-hold return over the tradeable period (percentage)
+    total_return: f64,
+    /// Annualized return not explained by exposure to the benchmark (percentage)
+    alpha: f64,
+    /// Sensitivity of the strategy's returns to the benchmark's
+    beta: f64,
+}
+
+/// Win/loss statistics of the closed trades of a backtest
+#[derive(Debug, Clone, Default)]
+struct TradeStatistics {
+    winning_trades: i32,
+    losing_trades: i32,
+    /// Gross profit over gross loss, weighted by position size
+    profit_factor: f64,
+    max_consecutive_wins: i32,
+    max_consecutive_losses: i32,
+    /// Average return of winning and losing trades (percentage)
+    avg_profit_per_win: f64,
+    avg_loss_per_loss: f64,
+    /// Average holding period of winning and losing trades (hours)
+    avg_win_holding_period: f64,
+    avg_loss_holding_period: f64,
+    /// Average return per trade (percentage)
+    expectancy: f64,
+}
+
+/// Downside and activity metrics of a backtest
+#[derive(Debug, Clone, Default)]
+struct RiskMetrics {
+    sharpe_ratio: f64,
+    sortino_ratio: f64,
+    /// Loss of the worst 5% of trades (percentage, positive for losses)
+    value_at_risk: f64,
+    /// Average loss of the trades beyond the value at risk (percentage)
+    expected_shortfall: f64,
+    /// Share of the tradeable period with an open position (percentage)
+    exposure_percent: f64,
+    /// Traded notional, entries and exits, as a multiple of capital
+    turnover: f64,
+}
+
+/// Where the evaluator takes indicator values from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndicatorSource {
+    /// Load values stored by the worker, calculating only indicators that are missing or incomplete
+    #[default]
+    Precomputed,
+    /// Calculate every indicator from the candles
+    Calculate,
+}
+
 /// Evaluator for backtesting strategies
 pub struct StrategyEvaluator {
-    pg: Arc<PostgresManager>,
+    storage: Arc<dyn Storage>,
     initial_capital: f64,
+    engine_settings: EngineSettings,
+    contract: ContractSpec,
+    indicator_source: IndicatorSource,
+    /// Funding times and rates of the traded symbol in ascending order
+    funding_rates: Vec<(DateTime<Utc>, f64)>,
+    result_cache: Option<Arc<IndicatorResultCache>>,
+    backtest_cache: Option<Arc<BacktestCache>>,
+    /// Seed of every random draw, so a backtest can be reproduced exactly
+    seed: u64,
 }
 
 impl StrategyEvaluator {
-    /// Create a new strategy evaluator
-    pub fn new(pg: Arc<PostgresManager>, initial_capital: f64) -> Self {
+    /// Create a new strategy evaluator reading candles from any storage backend
+    pub fn new(storage: Arc<dyn Storage>, initial_capital: f64) -> Self {
         Self {
-            pg,
+            storage,
             initial_capital,
+            engine_settings: EngineSettings::default(),
+            contract: ContractSpec::linear(""),
+            indicator_source: IndicatorSource::default(),
+            funding_rates: Vec::new(),
+            result_cache: None,
+            backtest_cache: None,
+            seed: 0,
         }
     }
+    
+    /// Use a specific engine settings profile for fills and costs
+    pub fn with_engine_settings(mut self, engine_settings: EngineSettings) -> Self {
+        self.engine_settings = engine_settings;
+        self
+    }
+    
+    /// Get the engine settings used by this evaluator
+    pub fn engine_settings(&self) -> &EngineSettings {
+        &self.engine_settings
+    }
+    
+    /// Use the contract specification of the traded symbol for PnL calculation
+    pub fn with_contract_spec(mut self, contract: ContractSpec) -> Self {
+        self.contract = contract;
+        self
+    }
+    
+    /// Choose between stored and freshly calculated indicator values
+    pub fn with_indicator_source(mut self, indicator_source: IndicatorSource) -> Self {
+        self.indicator_source = indicator_source;
+        self
+    }
+    
+    /// Reuse indicator series calculated earlier from identical candles
+    pub fn with_result_cache(mut self, cache: Arc<IndicatorResultCache>) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+    
+    /// Seed the random components of the simulation, such as slippage jitter
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    
+    /// Get the seed of the random components
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    
+    /// Reuse frames and results of identical backtests and resume interrupted ones
+    ///
+    /// Only backtests with an end date are cached, as the data of an open range keeps growing.
+    pub fn with_backtest_cache(mut self, cache: Arc<BacktestCache>) -> Self {
+        self.backtest_cache = Some(cache);
+        self
+    }
+    
+    /// Charge these funding rates to open positions; only used when the contract applies funding
+    pub fn with_funding_rates(mut self, mut funding_rates: Vec<(DateTime<Utc>, f64)>) -> Self {
+        funding_rates.sort_by_key(|(time, _)| *time);
+        self.funding_rates = funding_rates;
+        self
+    }
 
     /// Backtest a strategy on a symbol and interval
     pub async fn backtest(&self, strategy: &Strategy, symbol: &str, interval: &str, 
-                         _start_date: Option<DateTime<Utc>>, _end_date: Option<DateTime<Utc>>) -> Result<StrategyPerformance> {
-        info!("Starting backtest for strategy {} on {}:{}", strategy.name, symbol, interval);
-        
-        // For now we'll just return a placeholder result
-        info!("(Note: This is a placeholder implementation)");
-        
-        let performance = StrategyPerformance {
-            total_trades: 0,
-            winning_trades: 0,
-            losing_trades: 0,
-            win_rate: 0.0,
-            max_drawdown: 0.0,
-            profit_factor: 0.0,
-            sharpe_ratio: 0.0,
-            total_return: 0.0,
-            annualized_return: 0.0,
-            max_consecutive_wins: 0,
-            max_consecutive_losses: 0,
-            avg_profit_per_win: 0.0,
-            avg_loss_per_loss: 0.0,
-            avg_win_holding_period: 0.0,
-            avg_loss_holding_period: 0.0,
-            expectancy: 0.0,
+                         start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>) -> Result<StrategyPerformance> {
+        info!("Starting backtest for strategy {} on {}:{} with engine profile '{}'", 
+              strategy.name, symbol, interval, self.engine_settings.name);
+        
+        let cache = self.backtest_cache.as_ref().filter(|_| end_date.is_some());
+        let frame_key = self.frame_key(strategy, symbol, interval, start_date, end_date);
+        let run_key = self.run_key(strategy, &frame_key);
+        
+        // A cache failure only costs a recalculation
+        if let Some(cache) = cache {
+            match cache.get_result(&run_key).await {
+                Ok(Some(performance)) => {
+                    info!("Reusing the cached result of an identical backtest");
+                    return Ok(performance);
+                },
+                Ok(None) => {},
+                Err(e) => warn!("Failed to read cached backtest result: {}", e),
+            }
+        }
+        
+        let frame = self.cached_indicator_frame(cache, &frame_key, strategy, symbol, interval, start_date, end_date).await?;
+        info!("Loaded {} candles with {} indicators", frame.len(), frame.indicators.len());
+        
+        let first_tradeable_idx = frame.first_complete_index().unwrap_or(frame.len());
+        let mut state = match cache {
+            Some(cache) => match cache.get_checkpoint::<SimulationState>(&run_key).await {
+                Ok(Some(state)) if state.next_idx >= first_tradeable_idx && state.next_idx <= frame.len() => {
+                    info!("Resuming interrupted backtest at candle {} of {} with {} trades", 
+                          state.next_idx, frame.len(), state.trades.len());
+                    Some(state)
+                },
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("Failed to read backtest checkpoint: {}", e);
+                    None
+                },
+            },
+            None => None,
+        }
+        .unwrap_or_else(|| SimulationState::new(first_tradeable_idx, self.initial_capital));
+        
+        // Simulate month by month, checkpointing after each completed month
+        for end_idx in month_ends(&frame.candles, state.next_idx) {
+            self.simulate_trades(strategy, &frame, &mut state, end_idx)?;
+            if let Some(cache) = cache {
+                if let Err(e) = cache.put_checkpoint(&run_key, &state).await {
+                    warn!("Failed to write backtest checkpoint: {}", e);
+                }
+            }
+        }
+        
+        let simulation = self.finish_simulation(&frame, state);
+        let trades = simulation.trades;
+        let benchmark = self.benchmark(&frame.candles, first_tradeable_idx, &trades);
+        let risk = risk_metrics(&frame.candles, first_tradeable_idx, &trades);
+        let statistics = trade_statistics(&trades);
+        
+        let total_return = (simulation.equity / self.initial_capital - 1.0) * 100.0;
+        let total_trades = trades.len() as i32;
+        
+        let mut performance = StrategyPerformance {
+            total_trades,
+            winning_trades: statistics.winning_trades,
+            losing_trades: statistics.losing_trades,
+            win_rate: if total_trades > 0 { statistics.winning_trades as f64 / total_trades as f64 * 100.0 } else { 0.0 },
+            max_drawdown: max_drawdown(&simulation.equity_curve),
+            profit_factor: statistics.profit_factor,
+            sharpe_ratio: risk.sharpe_ratio,
+            total_return,
+            annualized_return: annualized_return(total_return, &frame.candles, first_tradeable_idx),
+            max_consecutive_wins: statistics.max_consecutive_wins,
+            max_consecutive_losses: statistics.max_consecutive_losses,
+            avg_profit_per_win: statistics.avg_profit_per_win,
+            avg_loss_per_loss: statistics.avg_loss_per_loss,
+            avg_win_holding_period: statistics.avg_win_holding_period,
+            avg_loss_holding_period: statistics.avg_loss_holding_period,
+            expectancy: statistics.expectancy,
+            benchmark_return: benchmark.total_return,
+            excess_return: 0.0,
+            alpha: benchmark.alpha,
+            beta: benchmark.beta,
+            sortino_ratio: risk.sortino_ratio,
+            calmar_ratio: 0.0,
+            value_at_risk: risk.value_at_risk,
+            expected_shortfall: risk.expected_shortfall,
+            exposure_percent: risk.exposure_percent,
+            turnover: risk.turnover,
+            unfilled_orders: simulation.unfilled_orders.len() as i32,
         };
+        performance.excess_return = performance.total_return - performance.benchmark_return;
+        if performance.max_drawdown > 0.0 {
+            performance.calmar_ratio = performance.annualized_return / performance.max_drawdown;
+        }
+        
+        info!("{} trades returned {:.2}% ({:.2}% annualized)", 
+              performance.total_trades, performance.total_return, performance.annualized_return);
+        info!("Buy-and-hold return {:.2}%, alpha {:.2}%, beta {:.2}", 
+              benchmark.total_return, benchmark.alpha, benchmark.beta);
+        
+        if let Some(cache) = cache {
+            if let Err(e) = cache.put_result(&run_key, &performance).await {
+                warn!("Failed to cache backtest result: {}", e);
+            }
+        }
         
         Ok(performance)
     }
     
+    /// Signals of the rules that trigger on the last candle closed by `now`
+    ///
+    /// The strategy is simulated from `start_date` up to that candle, so position-dependent
+    /// operands like bars_since_entry see the position a backtest would hold.
+    pub async fn latest_signals(&self, strategy: &Strategy, symbol: &str, interval: &str,
+                                start_date: DateTime<Utc>, now: DateTime<Utc>) -> Result<Vec<Signal>> {
+        let frame = self.load_indicator_frame(strategy, symbol, interval, Some(start_date), Some(now)).await?;
+        let candle_data = &frame.candles;
+        
+        let closed = candle_data.close_time.partition_point(|close_time| *close_time <= now);
+        let Some(last_idx) = closed.checked_sub(1) else {
+            return Ok(Vec::new());
+        };
+        let Some(first_tradeable_idx) = frame.first_complete_index().filter(|idx| *idx <= last_idx) else {
+            return Ok(Vec::new());
+        };
+        
+        let mut state = SimulationState::new(first_tradeable_idx, self.initial_capital);
+        self.simulate_trades(strategy, &frame, &mut state, last_idx)?;
+        
+        let entry_time = state.position.as_ref()
+            .and_then(|position| position.tranches.first())
+            .map(|tranche| tranche.entry_time);
+        let signals = RuleSet::new(strategy)?
+            .triggered(&frame, last_idx, entry_time)
+            .into_iter()
+            .map(|rule| Signal {
+                strategy_id: strategy.id.clone(),
+                symbol: symbol.to_string(),
+                interval: interval.to_string(),
+                bar_time: candle_data.open_time[last_idx],
+                rule_id: rule.id.clone(),
+                action: rule.action.clone(),
+                price: candle_data.close[last_idx],
+            })
+            .collect();
+        
+        Ok(signals)
+    }
+    
+    /// Key of the indicator frame: strategy version and indicators, data range and indicator source
+    fn frame_key(&self, strategy: &Strategy, symbol: &str, interval: &str,
+                 start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>) -> String {
+        fingerprint(&json!({
+            "strategy_id": strategy.id,
+            "version": strategy.version,
+            "indicators": strategy.indicators,
+            "symbol": symbol,
+            "interval": interval,
+            "start_date": start_date,
+            "end_date": end_date,
+            "indicator_source": format!("{:?}", self.indicator_source),
+        }))
+    }
+    
+    /// Key of a backtest run: its frame and everything else the simulation depends on
+    fn run_key(&self, strategy: &Strategy, frame_key: &str) -> String {
+        fingerprint(&json!({
+            "frame": frame_key,
+            "strategy": strategy,
+            "engine_settings": self.engine_settings,
+            "contract": self.contract,
+            "initial_capital": self.initial_capital,
+            "funding_rates": self.funding_rates,
+            "seed": self.seed,
+        }))
+    }
+    
+    /// Load the indicator frame, reusing one cached for the same key
+    #[allow(clippy::too_many_arguments)]
+    async fn cached_indicator_frame(&self, cache: Option<&Arc<BacktestCache>>, frame_key: &str, strategy: &Strategy,
+                                    symbol: &str, interval: &str, start_date: Option<DateTime<Utc>>,
+                                    end_date: Option<DateTime<Utc>>) -> Result<AlignedFrame> {
+        let Some(cache) = cache else {
+            return self.load_indicator_frame(strategy, symbol, interval, start_date, end_date).await;
+        };
+        
+        match cache.get_frame(frame_key).await {
+            Ok(Some(frame)) => return Ok(frame),
+            Ok(None) => {},
+            Err(e) => warn!("Failed to read cached indicator frame: {}", e),
+        }
+        
+        let frame = self.load_indicator_frame(strategy, symbol, interval, start_date, end_date).await?;
+        if let Err(e) = cache.put_frame(frame_key, &frame).await {
+            warn!("Failed to cache indicator frame: {}", e);
+        }
+        Ok(frame)
+    }
+    
+    /// Compare the strategy with buying and holding the symbol from candle `start_idx` to the end
+    ///
+    /// Trade returns are booked on their exit candle and weighted by position size,
+    /// then regressed on the candle-to-candle returns of the benchmark.
+    fn benchmark(&self, candle_data: &CandleData, start_idx: usize, trades: &[TradeResult]) -> Benchmark {
+        let len = candle_data.close.len();
+        if start_idx + 1 >= len {
+            return Benchmark::default();
+        }
+        
+        let total_return = self.contract.return_percent(candle_data.close[start_idx], candle_data.close[len - 1], true);
+        
+        let benchmark_returns: Vec<f64> = (start_idx + 1..len)
+            .map(|i| self.contract.return_percent(candle_data.close[i - 1], candle_data.close[i], true))
+            .collect();
+        
+        let strategy_returns = period_returns(candle_data, start_idx, trades);
+        
+        let n = benchmark_returns.len() as f64;
+        let mean_benchmark = benchmark_returns.iter().sum::<f64>() / n;
+        let mean_strategy = strategy_returns.iter().sum::<f64>() / n;
+        
+        let covariance = benchmark_returns.iter().zip(&strategy_returns)
+            .map(|(b, s)| (b - mean_benchmark) * (s - mean_strategy))
+            .sum::<f64>() / n;
+        let variance = benchmark_returns.iter()
+            .map(|b| (b - mean_benchmark).powi(2))
+            .sum::<f64>() / n;
+        
+        let beta = if variance > 0.0 { covariance / variance } else { 0.0 };
+        
+        // Jensen's alpha per candle, annualized by the number of candles in a year
+        let alpha = (mean_strategy - beta * mean_benchmark) * periods_per_year(&candle_data.interval);
+        
+        Benchmark { total_return, alpha, beta }
+    }
+    
     /// Filter candle data based on date range
     #[allow(dead_code)]
     fn filter_candle_data(&self, candle_data: &CandleData, start_date: Option<DateTime<Utc>>, 
@@ -112,7 +649,7 @@ impl StrategyEvaluator {
         };
         
         if start_idx >= end_idx {
-            return Err(anyhow::anyhow!("Invalid date range: start_date must be before end_date"));
+            return Err(Error::validation("Invalid date range: start_date must be before end_date"));
         }
         
         // Copy the data within the range
@@ -123,100 +660,450 @@ impl StrategyEvaluator {
         filtered.close = candle_data.close[start_idx..end_idx].to_vec();
         filtered.volume = candle_data.volume[start_idx..end_idx].to_vec();
         filtered.close_time = candle_data.close_time[start_idx..end_idx].to_vec();
+        filtered.taker_buy_volume = candle_data.taker_buy_volume.get(start_idx..end_idx)
+            .map(<[f64]>::to_vec)
+            .unwrap_or_default();
         
         Ok((filtered, start_idx, end_idx))
     }
     
-    /// Calculate all indicators for the strategy
-    #[allow(dead_code)]
-    async fn calculate_indicators(&self, strategy: &Strategy, candle_data: &CandleData) 
-        -> Result<std::collections::HashMap<String, Vec<serde_json::Value>>> {
-        let mut indicators_map = std::collections::HashMap::new();
+    /// Load candles with the strategy's indicators aligned to them by timestamp
+    ///
+    /// With `IndicatorSource::Precomputed`, values already stored in calculated_indicators
+    /// are used as they are; only indicators the worker hasn't fully calculated for this
+    /// range are computed here.
+    async fn load_indicator_frame(&self, strategy: &Strategy, symbol: &str, interval: &str,
+                                  start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>) -> Result<AlignedFrame> {
+        let requested: Vec<FrameIndicator> = match self.indicator_source {
+            IndicatorSource::Precomputed => strategy.indicators.iter()
+                .filter(|indicator| is_candle_indicator(indicator))
+                .map(|indicator| FrameIndicator {
+                    id: indicator.id.clone(),
+                    indicator_name: indicator.indicator_name.clone(),
+                    parameters: indicator.parameters.clone(),
+                })
+                .collect(),
+            IndicatorSource::Calculate => Vec::new(),
+        };
+        
+        let mut frame = self.storage.get_aligned_frame(symbol, interval, &requested, start_date, end_date).await?;
         
-        for indicator in &strategy.indicators {
-            let indicator_values = self.calculate_indicator(indicator, candle_data).await?;
-            indicators_map.insert(indicator.id.clone(), indicator_values);
+        let mut reused = 0;
+        for indicator in strategy.indicators.iter().filter(|indicator| is_candle_indicator(indicator)) {
+            let complete = frame.indicators.get(&indicator.id)
+                .map_or(false, |values| is_complete(values));
+            
+            if complete {
+                reused += 1;
+                continue;
+            }
+            
+            if self.indicator_source == IndicatorSource::Precomputed {
+                info!("Stored values for indicator {} ({}) on {}:{} are missing or incomplete, calculating",
+                      indicator.id, indicator.indicator_name, symbol, interval);
+            }
+            let series = if cross_symbol::is_cross_symbol(&indicator.indicator_name) {
+                let other_symbol = cross_symbol::other_symbol(&indicator.parameters)?;
+                let other = self.storage.get_aligned_frame(other_symbol, interval, &[], start_date, end_date).await?;
+                self.cross_symbol_indicator(indicator, &frame.candles, &other.candles)?
+            } else {
+                self.cached_indicator(indicator, &frame.candles).await?
+            };
+            frame.insert_series(&indicator.id, &series);
         }
         
-        Ok(indicators_map)
+        // Script indicators and frame statistics are calculated from the candles and the
+        // indicators listed before them
+        for (position, indicator) in strategy.indicators.iter().enumerate() {
+            if script::is_script_indicator(indicator) {
+                let series = script::indicator_series(strategy, position, &frame)?;
+                frame.insert_series(&indicator.id, &series);
+            } else if statistics::needs_frame(indicator) {
+                let benchmark = match statistics::benchmark_symbol(indicator)? {
+                    Some(benchmark) => Some(self.storage
+                        .get_aligned_frame(benchmark, interval, &[], start_date, end_date).await?
+                        .candles),
+                    None => None,
+                };
+                let series = statistics::indicator_series(strategy, position, &frame, benchmark.as_ref())?;
+                frame.insert_series(&indicator.id, &series);
+            }
+        }
+        
+        info!("Loaded {} of {} indicators from storage for {}:{}",
+              reused, strategy.indicators.len(), symbol, interval);
+        
+        Ok(frame)
     }
     
-    /// Calculate a single indicator
-    #[allow(dead_code)]
-    async fn calculate_indicator(&self, indicator: &crate::strategy::schema::StrategyIndicator, candle_data: &CandleData) 
-        -> Result<Vec<serde_json::Value>> {
+    /// Calculate an indicator, reusing a series cached for identical candles
+    async fn cached_indicator(&self, indicator: &crate::strategy::schema::StrategyIndicator, candle_data: &CandleData)
+        -> Result<IndicatorSeries> {
+        let cache = match &self.result_cache {
+            Some(cache) => cache,
+            None => return self.calculate_indicator(indicator, candle_data),
+        };
+        
+        // A cache failure only costs a recalculation
+        match cache.get(candle_data, &indicator.indicator_name, &indicator.parameters).await {
+            Ok(Some(series)) => return Ok(series),
+            Ok(None) => {},
+            Err(e) => warn!("Failed to read cached {} series: {}", indicator.indicator_name, e),
+        }
+        
+        let series = self.calculate_indicator(indicator, candle_data)?;
+        if let Err(e) = cache.put(candle_data, &series).await {
+            warn!("Failed to cache {} series: {}", indicator.indicator_name, e);
+        }
+        
+        Ok(series)
+    }
+    
+    /// Calculate a single indicator over the given candles
+    fn calculate_indicator(&self, indicator: &crate::strategy::schema::StrategyIndicator, candle_data: &CandleData) 
+        -> Result<IndicatorSeries> {
         let results = IndicatorCalculator::calculate_indicator(
             candle_data,
             &indicator.indicator_name,
             &indicator.parameters,
         )?;
         
-        // Convert from (DateTime, Value) to just Value
-        let values = results.into_iter()
-            .map(|(_, value)| value)
-            .collect();
+        let mut series = IndicatorSeries::new(
+            &candle_data.symbol, &candle_data.interval, &indicator.indicator_name, &indicator.parameters,
+        );
+        // The frame holds stored JSON values, so typed outputs are converted here
+        for (time, value) in results {
+            series.push(time, value.into());
+        }
         
-        Ok(values)
+        Ok(series)
     }
     
-    /// Simulate trading based on strategy rules
-    #[allow(dead_code)]
-    fn simulate_trades(&self, _strategy: &Strategy, candle_data: &CandleData, 
-                     indicators_map: &std::collections::HashMap<String, Vec<serde_json::Value>>, _start_idx: usize, _end_idx: usize) 
-        -> Result<Vec<TradeResult>> {
-        let mut trade_results = Vec::new();
-        let mut current_position: Option<Position> = None;
-        let mut equity = self.initial_capital;
+    /// Calculate an indicator between the strategy's symbol and the candles of another
+    fn cross_symbol_indicator(&self, indicator: &crate::strategy::schema::StrategyIndicator,
+                              candle_data: &CandleData, other: &CandleData) -> Result<IndicatorSeries> {
+        let results = IndicatorCalculator::calculate_cross_symbol(
+            candle_data, other, &indicator.indicator_name, &indicator.parameters,
+        )?;
         
-        // We need a warmup period to have all indicators ready
-        // Find the longest indicator length to determine warmup
-        let warmup = indicators_map.values()
-            .map(|values| candle_data.close.len().saturating_sub(values.len()))
-            .max()
-            .unwrap_or(0);
+        let mut series = IndicatorSeries::new(
+            &candle_data.symbol, &candle_data.interval, &indicator.indicator_name, &indicator.parameters,
+        );
+        for (time, value) in results {
+            series.push(time, value.into());
+        }
         
-        let first_tradeable_idx = warmup;
+        Ok(series)
+    }
+    
+    /// Resolve stop loss and take profit prices for a position opened at candle `idx`
+    fn resolve_exit_levels(&self, strategy: &Strategy, frame: &AlignedFrame,
+                           idx: usize, entry_price: f64, is_long: bool) -> (Option<f64>, Option<f64>) {
+        let risk = &strategy.risk_management;
+        
+        // Explicit levels take precedence over the default percentages
+        let stop_loss = match &risk.stop_loss_level {
+            Some(level) => resolve_stop_level(level, frame, idx, entry_price, is_long, true),
+            None => risk.default_stop_loss
+                .map(|p| percent_level(entry_price, p, is_long, true)),
+        };
+        
+        let take_profit = match &risk.take_profit_level {
+            Some(level) => resolve_stop_level(level, frame, idx, entry_price, is_long, false),
+            None => risk.default_take_profit
+                .map(|p| percent_level(entry_price, p, is_long, false)),
+        };
+        
+        (stop_loss, take_profit)
+    }
+    
+    /// Record closed tranches as trades, each with its own entry and P/L
+    fn close_tranches(&self, is_long: bool, tranches: Vec<Tranche>, exit_price: f64,
+                      exit_time: DateTime<Utc>, reason: &str) -> Vec<TradeResult> {
+        tranches.into_iter()
+            .map(|tranche| TradeResult {
+                is_long,
+                entry_price: tranche.entry_price,
+                exit_price,
+                size_percent: tranche.size_percent,
+                entry_time: tranche.entry_time,
+                exit_time,
+                exit_reason: reason.to_string(),
+                pl_percent: self.contract.return_percent(tranche.entry_price, exit_price, is_long)
+                    - self.round_trip_cost_percent(tranche.entry_time, exit_time)
+                    - tranche.carry_percent,
+            })
+            .collect()
+    }
+    
+    /// Commission and slippage of a round trip in percent, with jittered slippage per fill
+    ///
+    /// Each fill draws its jitter from a generator seeded with the evaluator's seed and the
+    /// fill time, so the draws don't depend on the order of the simulation and a resumed
+    /// backtest draws the same values as an uninterrupted one.
+    fn round_trip_cost_percent(&self, entry_time: DateTime<Utc>, exit_time: DateTime<Utc>) -> f64 {
+        let settings = &self.engine_settings;
+        let jitter = settings.slippage_jitter_percent;
+        if jitter <= 0.0 {
+            return settings.round_trip_cost_percent();
+        }
+        
+        let fill_slippage = |time: DateTime<Utc>, side: u64| {
+            let stream = (time.timestamp_millis() as u64).wrapping_mul(2).wrapping_add(side);
+            let mut rng = StdRng::seed_from_u64(self.seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            (settings.slippage_percent + rng.gen_range(-jitter..=jitter)).max(0.0)
+        };
+        
+        2.0 * settings.commission_percent + fill_slippage(entry_time, 0) + fill_slippage(exit_time, 1)
+    }
+    
+    /// Carry cost in percent of position size for holding through candle `idx`
+    ///
+    /// Funding events inside the candle are paid by longs and received by shorts when
+    /// the rate is positive, and the other way round when it's negative. Shorts also
+    /// pay the symbol's annual borrow rate pro rata. Costs are relative to the entry
+    /// notional rather than the current mark price.
+    fn carry_cost_percent(&self, is_long: bool, candles: &CandleData, idx: usize) -> f64 {
+        let start = candles.open_time[idx];
+        let end = candles.close_time[idx];
+        let mut cost = 0.0;
+        
+        if self.contract.apply_funding {
+            let first = self.funding_rates.partition_point(|(time, _)| *time < start);
+            let last = self.funding_rates.partition_point(|(time, _)| *time <= end);
+            let funding: f64 = self.funding_rates[first..last.max(first)].iter()
+                .map(|(_, rate)| rate * 100.0)
+                .sum();
+            cost += if is_long { funding } else { -funding };
+        }
+        
+        if let (false, Some(borrow_rate)) = (is_long, self.contract.short_borrow_rate) {
+            let held_seconds = (end - start).num_seconds().max(0) as f64;
+            cost += borrow_rate * held_seconds / (365.0 * 86_400.0);
+        }
+        
+        cost
+    }
+    
+    /// Apply the action of a triggered rule at `price` on candle `idx`
+    ///
+    /// Returns the trades closed by the action. Entries are ignored while a position
+    /// is open; scale_in adds tranches to it and exits and scale_out close tranches
+    /// oldest first. Stop and target levels follow the average entry price. Entries and
+    /// scale-ins are reduced to the engine's volume participation limit.
+    #[allow(clippy::too_many_arguments)]
+    fn apply_rule_action(&self, strategy: &Strategy, frame: &AlignedFrame, idx: usize, price: f64, action: &RuleAction,
+                         equity: f64, position: &mut Option<Position>, pending_order: &mut Option<PendingOrder>)
+        -> Vec<TradeResult> {
+        let risk = &strategy.risk_management;
+        let time = frame.candles.open_time[idx];
+        let max_fill_size = self.max_fill_size_percent(&frame.candles, idx, price, equity);
         
-        // Track max equity for drawdown calculation
-        let mut max_equity = equity;
+        match action {
+            RuleAction::EnterLong { size_percent, order } | RuleAction::EnterShort { size_percent, order } => {
+                if position.is_some() || pending_order.is_some() {
+                    return Vec::new();
+                }
+                
+                let is_long = matches!(action, RuleAction::EnterLong { .. });
+                let size = size_percent.unwrap_or(risk.default_position_size);
+                
+                match order {
+                    Some(order) => {
+                        let offset_price = |offset: f64| price * (1.0 + offset / 100.0);
+                        *pending_order = Some(PendingOrder {
+                            is_long,
+                            order_type: order.order_type,
+                            price: offset_price(order.price_offset_percent),
+                            limit_price: order.limit_offset_percent.map(offset_price),
+                            size_percent: size,
+                            placed_idx: idx,
+                            placed_time: time,
+                            expires_idx: order.expires_after_bars.map(|bars| idx + bars as usize),
+                            triggered: false,
+                        });
+                    },
+                    None => {
+                        let size = size.min(max_fill_size);
+                        if size > 0.0 {
+                            *position = Some(self.open_position(strategy, frame, idx, is_long, price, size));
+                        }
+                    },
+                }
+                Vec::new()
+            },
+            RuleAction::ExitLong { size_percent } | RuleAction::ExitShort { size_percent } => {
+                let is_long = matches!(action, RuleAction::ExitLong { .. });
+                if position.as_ref().map_or(false, |open| open.is_long == is_long) {
+                    self.scale_out_position(position, size_percent.unwrap_or(100.0), price, time, "Exit Rule")
+                } else {
+                    Vec::new()
+                }
+            },
+            RuleAction::ScaleIn { size_percent, max_position_percent } => {
+                if let Some(open) = position {
+                    let max_size = max_position_percent.unwrap_or(risk.max_total_risk);
+                    if open.scale_in(price, size_percent.min(max_fill_size), time, max_size) > 0.0 {
+                        let average = open.average_entry_price();
+                        (open.stop_loss, open.take_profit) = self.resolve_exit_levels(strategy, frame, idx, average, open.is_long);
+                    }
+                }
+                Vec::new()
+            },
+            RuleAction::ScaleOut { percent } => {
+                self.scale_out_position(position, *percent, price, time, "Scale Out")
+            },
+            RuleAction::SetStopLoss { percent, price: level } => {
+                if let Some(open) = position {
+                    let average = open.average_entry_price();
+                    open.stop_loss = level.or_else(|| percent.map(|p| percent_level(average, p, open.is_long, true)));
+                }
+                Vec::new()
+            },
+            RuleAction::SetTakeProfit { percent, price: level } => {
+                if let Some(open) = position {
+                    let average = open.average_entry_price();
+                    open.take_profit = level.or_else(|| percent.map(|p| percent_level(average, p, open.is_long, false)));
+                }
+                Vec::new()
+            },
+        }
+    }
+    
+    /// Largest fill on candle `idx` in percent of capital the volume participation limit allows
+    fn max_fill_size_percent(&self, candles: &CandleData, idx: usize, price: f64, equity: f64) -> f64 {
+        match self.engine_settings.max_volume_participation {
+            // Fill value equity * size / 100 at most participation / 100 of the candle's value
+            Some(participation) if equity > 0.0 => participation * candles.volume[idx] * price / equity,
+            _ => f64::INFINITY,
+        }
+    }
+    
+    /// Open a position at `price` on candle `idx` with the strategy's exit levels
+    fn open_position(&self, strategy: &Strategy, frame: &AlignedFrame, idx: usize,
+                     is_long: bool, price: f64, size_percent: f64) -> Position {
+        let mut position = Position::open(is_long, price, size_percent, frame.candles.open_time[idx]);
+        (position.stop_loss, position.take_profit) = self.resolve_exit_levels(strategy, frame, idx, price, is_long);
+        position
+    }
+    
+    /// Close `percent` of the open position, clearing it once no tranches remain
+    fn scale_out_position(&self, position: &mut Option<Position>, percent: f64, price: f64,
+                          time: DateTime<Utc>, reason: &str) -> Vec<TradeResult> {
+        let Some(open) = position else {
+            return Vec::new();
+        };
+        
+        let is_long = open.is_long;
+        let closed = open.scale_out(percent);
+        if open.tranches.is_empty() {
+            *position = None;
+        }
+        
+        self.close_tranches(is_long, closed, price, time, reason)
+    }
+    
+    /// Simulate trading based on strategy rules from `state.next_idx` up to candle `end_idx`
+    fn simulate_trades(&self, strategy: &Strategy, frame: &AlignedFrame, state: &mut SimulationState, end_idx: usize) 
+        -> Result<()> {
+        let candle_data = &frame.candles;
+        let rules = RuleSet::new(strategy)?;
+        let start_idx = state.next_idx;
+        let SimulationState {
+            position: current_position,
+            pending_order,
+            equity,
+            max_equity,
+            trades: trade_results,
+            unfilled_orders,
+            equity_curve,
+            queued_actions,
+            ..
+        } = state;
+        let latency = self.engine_settings.execution_latency_bars as usize;
         
         // Simulate candle by candle
-        for i in first_tradeable_idx..candle_data.close.len() {
+        for i in start_idx..end_idx {
             let candle_time = candle_data.open_time[i];
-            let _open_price = candle_data.open[i];
+            let open_price = candle_data.open[i];
             let high_price = candle_data.high[i];
             let low_price = candle_data.low[i];
             let close_price = candle_data.close[i];
-            let _volume = candle_data.volume[i];
+            
+            // Actions of rules that triggered `latency` candles ago execute at this candle's open
+            let (due, waiting): (Vec<QueuedAction>, Vec<QueuedAction>) = std::mem::take(queued_actions)
+                .into_iter()
+                .partition(|queued| queued.execute_idx <= i);
+            *queued_actions = waiting;
+            for queued in due {
+                let closed = self.apply_rule_action(strategy, frame, i, open_price, &queued.action, *equity,
+                                                    current_position, pending_order);
+                book_trades(equity, max_equity, trade_results, closed);
+            }
+            
+            // Positions held into this candle accrue funding and borrow costs
+            if let Some(position) = current_position.as_mut() {
+                let carry = self.carry_cost_percent(position.is_long, candle_data, i);
+                for tranche in &mut position.tranches {
+                    tranche.carry_percent += carry;
+                }
+            }
+            
+            // Orders can fill from the candle after the one they were placed on
+            if let Some(mut order) = pending_order.take() {
+                if i > order.placed_idx {
+                    if let Some(fill_price) = order.fill_price(open_price, high_price, low_price) {
+                        let size = order.size_percent.min(self.max_fill_size_percent(candle_data, i, fill_price, *equity));
+                        if size > 0.0 {
+                            *current_position = Some(self.open_position(strategy, frame, i, order.is_long, 
+                                                                        fill_price, size));
+                        } else {
+                            unfilled_orders.push(order.cancel(candle_time, "No Volume"));
+                        }
+                    } else if order.expires_idx.map_or(false, |expires_idx| i >= expires_idx) {
+                        unfilled_orders.push(order.cancel(candle_time, "Expired"));
+                    } else {
+                        *pending_order = Some(order);
+                    }
+                } else {
+                    *pending_order = Some(order);
+                }
+            }
             
             // Check if we need to close position due to stop loss or take profit
-            if let Some(position) = &current_position {
+            if let Some(position) = current_position.as_ref() {
                 let mut exit_reason = None;
                 
-                // Check stop loss
-                if let Some(stop_loss) = position.stop_loss {
-                    // For long positions, stop loss is triggered if price goes below stop level
-                    if position.is_long && low_price <= stop_loss {
-                        exit_reason = Some("Stop Loss".to_string());
-                    } 
-                    // For short positions, stop loss is triggered if price goes above stop level
-                    else if !position.is_long && high_price >= stop_loss {
-                        exit_reason = Some("Stop Loss".to_string());
-                    }
-                }
+                // Stop loss is triggered below the level for longs and above it for shorts
+                let stop_hit = position.stop_loss.map_or(false, |stop_loss| match self.engine_settings.intrabar_assumption {
+                    IntrabarAssumption::CloseOnly => {
+                        if position.is_long { close_price <= stop_loss } else { close_price >= stop_loss }
+                    },
+                    _ => {
+                        if position.is_long { low_price <= stop_loss } else { high_price >= stop_loss }
+                    },
+                });
                 
-                // Check take profit
-                if exit_reason.is_none() {
-                    if let Some(take_profit) = position.take_profit {
-                        // For long positions, take profit is triggered if price goes above take profit level
-                        if position.is_long && high_price >= take_profit {
-                            exit_reason = Some("Take Profit".to_string());
-                        } 
-                        // For short positions, take profit is triggered if price goes below take profit level
-                        else if !position.is_long && low_price <= take_profit {
-                            exit_reason = Some("Take Profit".to_string());
-                        }
-                    }
+                // Take profit is triggered above the level for longs and below it for shorts
+                let target_hit = position.take_profit.map_or(false, |take_profit| match self.engine_settings.intrabar_assumption {
+                    IntrabarAssumption::CloseOnly => {
+                        if position.is_long { close_price >= take_profit } else { close_price <= take_profit }
+                    },
+                    _ => {
+                        if position.is_long { high_price >= take_profit } else { low_price <= take_profit }
+                    },
+                });
+                
+                // When a candle touches both levels, the engine profile decides which fills first
+                if stop_hit && target_hit {
+                    exit_reason = match self.engine_settings.intrabar_assumption {
+                        IntrabarAssumption::TargetFirst => Some("Take Profit".to_string()),
+                        _ => Some("Stop Loss".to_string()),
+                    };
+                } else if stop_hit {
+                    exit_reason = Some("Stop Loss".to_string());
+                } else if target_hit {
+                    exit_reason = Some("Take Profit".to_string());
                 }
                 
                 // Exit position if needed
@@ -230,64 +1117,345 @@ impl StrategyEvaluator {
                         close_price
                     };
                     
-                    let pl_percent = if position.is_long {
-                        (exit_price - position.entry_price) / position.entry_price * 100.0
-                    } else {
-                        (position.entry_price - exit_price) / position.entry_price * 100.0
-                    };
-                    
-                    // Record the trade
-                    trade_results.push(TradeResult {
-                        is_long: position.is_long,
-                        entry_price: position.entry_price,
-                        exit_price,
-                        size_percent: position.size_percent,
-                        entry_time: position.entry_time,
-                        exit_time: candle_time,
-                        exit_reason: reason,
-                        pl_percent,
-                    });
-                    
-                    // Update equity
-                    let position_value = equity * (position.size_percent / 100.0);
-                    let profit_loss = position_value * (pl_percent / 100.0);
-                    equity += profit_loss;
-                    
-                    // Update max equity
-                    max_equity = max_equity.max(equity);
+                    // Stops and targets close every tranche; each is recorded as its own trade
+                    let closed = self.close_tranches(position.is_long, position.tranches.clone(), 
+                                                     exit_price, candle_time, &reason);
+                    book_trades(equity, max_equity, trade_results, closed);
                     
                     // Clear position
-                    current_position = None;
+                    *current_position = None;
                 }
             }
             
-            // More implementation details omitted for brevity
+            // Rules see the candle once it has closed and act in priority order, at its close
+            // or at the open of the candle the engine's execution latency delays them to
+            let entry_time = current_position.as_ref()
+                .and_then(|position| position.tranches.first())
+                .map(|tranche| tranche.entry_time);
+            for rule in rules.triggered(frame, i, entry_time) {
+                if latency > 0 {
+                    queued_actions.push(QueuedAction { execute_idx: i + latency, action: rule.action.clone() });
+                    continue;
+                }
+                
+                let closed = self.apply_rule_action(strategy, frame, i, close_price, &rule.action, *equity,
+                                                    current_position, pending_order);
+                book_trades(equity, max_equity, trade_results, closed);
+            }
+            
+            equity_curve.push(self.marked_equity(*equity, current_position.as_ref(), close_price));
+        }
+        
+        state.next_idx = end_idx;
+        Ok(())
+    }
+    
+    /// Equity including the unrealized P/L and accrued carry of the open position at `price`
+    fn marked_equity(&self, equity: f64, position: Option<&Position>, price: f64) -> f64 {
+        let Some(position) = position else {
+            return equity;
+        };
+        
+        position.tranches.iter()
+            .map(|tranche| {
+                let pl_percent = self.contract.return_percent(tranche.entry_price, price, position.is_long) - tranche.carry_percent;
+                equity * (tranche.size_percent / 100.0) * (pl_percent / 100.0)
+            })
+            .fold(equity, |marked, pl| marked + pl)
+    }
+    
+    /// Cancel the pending order and close the open position at the end of the simulation
+    fn finish_simulation(&self, frame: &AlignedFrame, state: SimulationState) -> SimulationResult {
+        let candle_data = &frame.candles;
+        let SimulationState {
+            position, pending_order, mut equity, trades: mut trade_results, mut unfilled_orders, mut equity_curve, ..
+        } = state;
+        
+        // Orders still waiting at the end never filled
+        if let (Some(order), Some(last_time)) = (&pending_order, candle_data.open_time.last()) {
+            unfilled_orders.push(order.cancel(*last_time, "End of Simulation"));
         }
         
         // Close any open positions at the end of the simulation
-        if let Some(position) = &current_position {
+        if let Some(position) = position {
             let last_idx = candle_data.close.len() - 1;
             let close_price = candle_data.close[last_idx];
             let candle_time = candle_data.open_time[last_idx];
             
-            let pl_percent = if position.is_long {
-                (close_price - position.entry_price) / position.entry_price * 100.0
-            } else {
-                (position.entry_price - close_price) / position.entry_price * 100.0
-            };
+            let closed = self.close_tranches(position.is_long, position.tranches, 
+                                             close_price, candle_time, "End of Simulation");
+            equity = apply_trades_to_equity(equity, &closed);
+            trade_results.extend(closed);
+            
+            // The closing costs are only known now
+            if let Some(last) = equity_curve.last_mut() {
+                *last = equity;
+            }
+        }
+        
+        for order in &unfilled_orders {
+            info!("Unfilled {} {:?} order at {:.2} ({}% of capital) placed {} cancelled {}: {}",
+                  if order.is_long { "long" } else { "short" }, order.order_type, order.price,
+                  order.size_percent, order.placed_time, order.cancelled_time, order.reason);
+        }
+        
+        SimulationResult { trades: trade_results, unfilled_orders, equity, equity_curve }
+    }
+}
+
+/// End indices of the months of candles from `from` on, by trading day of the symbol's
+/// session; the last one is the candle count
+fn month_ends(candles: &CandleData, from: usize) -> Vec<usize> {
+    let len = candles.open_time.len();
+    let month = |i: usize| {
+        let date = candles.session.trading_date(candles.open_time[i]);
+        (date.year(), date.month())
+    };
+    let mut ends: Vec<usize> = (from + 1..len)
+        .filter(|&i| month(i - 1) != month(i))
+        .collect();
+    if from < len {
+        ends.push(len);
+    }
+    ends
+}
+
+/// Whether an indicator is calculated from the candles alone, and may be stored
+fn is_candle_indicator(indicator: &crate::strategy::schema::StrategyIndicator) -> bool {
+    !script::is_script_indicator(indicator) && !statistics::needs_frame(indicator)
+}
+
+/// Whether a stored series covers the frame from its first value through the last candle
+///
+/// Leading gaps are the indicator's warmup; a gap after that means the worker
+/// hasn't caught up or skipped candles, so the stored values can't be trusted.
+fn is_complete(values: &[Option<serde_json::Value>]) -> bool {
+    match values.iter().position(Option::is_some) {
+        Some(first) => values[first..].iter().all(Option::is_some),
+        None => false,
+    }
+}
+
+/// Fill price of a limit order, which fills at its price or better
+fn limit_fill(is_long: bool, limit: f64, open: f64, high: f64, low: f64) -> Option<f64> {
+    if is_long {
+        (low <= limit).then(|| open.min(limit))
+    } else {
+        (high >= limit).then(|| open.max(limit))
+    }
+}
+
+/// Fill price of a stop order, which fills at its price or worse once traded through
+fn stop_fill(is_long: bool, stop: f64, open: f64, high: f64, low: f64) -> Option<f64> {
+    if is_long {
+        (high >= stop).then(|| open.max(stop))
+    } else {
+        (low <= stop).then(|| open.min(stop))
+    }
+}
+
+/// Book closed trades on the equity of a simulation and its peak
+fn book_trades(equity: &mut f64, max_equity: &mut f64, trades: &mut Vec<TradeResult>, closed: Vec<TradeResult>) {
+    if closed.is_empty() {
+        return;
+    }
+    
+    *equity = apply_trades_to_equity(*equity, &closed);
+    *max_equity = max_equity.max(*equity);
+    trades.extend(closed);
+}
+
+/// Book the P/L of closed trades on the equity, each weighted by its position size
+fn apply_trades_to_equity(equity: f64, trades: &[TradeResult]) -> f64 {
+    trades.iter()
+        .fold(equity, |equity, trade| equity + equity * (trade.size_percent / 100.0) * (trade.pl_percent / 100.0))
+}
+
+/// Number of candles of an interval in a year, or zero for unknown intervals
+fn periods_per_year(interval: &str) -> f64 {
+    interval_to_seconds(interval).map_or(0.0, |seconds| 365.0 * 86400.0 / seconds as f64)
+}
+
+/// Strategy return of each candle after `start_idx`, booking trades on their exit candle
+/// weighted by position size
+fn period_returns(candle_data: &CandleData, start_idx: usize, trades: &[TradeResult]) -> Vec<f64> {
+    let mut returns = vec![0.0; candle_data.close.len().saturating_sub(start_idx + 1)];
+    
+    for trade in trades {
+        if let Ok(idx) = candle_data.open_time.binary_search(&trade.exit_time) {
+            if idx > start_idx {
+                returns[idx - start_idx - 1] += trade.pl_percent * trade.size_percent / 100.0;
+            }
+        }
+    }
+    
+    returns
+}
+
+/// Largest fall of an equity curve from its running peak, in percent of the peak
+pub fn max_drawdown(equity_curve: &[f64]) -> f64 {
+    let mut peak = f64::NEG_INFINITY;
+    let mut drawdown: f64 = 0.0;
+    
+    for &equity in equity_curve {
+        peak = peak.max(equity);
+        if peak > 0.0 {
+            drawdown = drawdown.max((peak - equity) / peak * 100.0);
+        }
+    }
+    
+    drawdown
+}
+
+/// Return compounded to a yearly rate over the tradeable period from candle `start_idx`
+fn annualized_return(total_return: f64, candle_data: &CandleData, start_idx: usize) -> f64 {
+    let (Some(start), Some(end)) = (candle_data.open_time.get(start_idx), candle_data.close_time.last()) else {
+        return 0.0;
+    };
+    
+    let years = (*end - *start).num_seconds() as f64 / (365.0 * 86_400.0);
+    let growth = 1.0 + total_return / 100.0;
+    if years <= 0.0 || growth <= 0.0 {
+        return 0.0;
+    }
+    
+    (growth.powf(1.0 / years) - 1.0) * 100.0
+}
+
+/// Count wins and losses and average their returns and holding periods
+///
+/// Every trade with a positive return is a win, every other trade a loss.
+fn trade_statistics(trades: &[TradeResult]) -> TradeStatistics {
+    let mut statistics = TradeStatistics::default();
+    if trades.is_empty() {
+        return statistics;
+    }
+    
+    let (wins, losses): (Vec<&TradeResult>, Vec<&TradeResult>) = trades.iter().partition(|trade| trade.pl_percent > 0.0);
+    statistics.winning_trades = wins.len() as i32;
+    statistics.losing_trades = losses.len() as i32;
+    
+    let average = |trades: &[&TradeResult], value: fn(&TradeResult) -> f64| {
+        if trades.is_empty() { 0.0 } else { trades.iter().map(|trade| value(trade)).sum::<f64>() / trades.len() as f64 }
+    };
+    let holding_hours = |trade: &TradeResult| (trade.exit_time - trade.entry_time).num_seconds() as f64 / 3600.0;
+    statistics.avg_profit_per_win = average(&wins, |trade| trade.pl_percent);
+    statistics.avg_loss_per_loss = average(&losses, |trade| trade.pl_percent);
+    statistics.avg_win_holding_period = average(&wins, holding_hours);
+    statistics.avg_loss_holding_period = average(&losses, holding_hours);
+    statistics.expectancy = trades.iter().map(|trade| trade.pl_percent).sum::<f64>() / trades.len() as f64;
+    
+    let weighted = |trade: &&TradeResult| trade.pl_percent * trade.size_percent / 100.0;
+    let gross_profit: f64 = wins.iter().map(weighted).sum();
+    let gross_loss: f64 = -losses.iter().map(weighted).sum::<f64>();
+    // Without losses the factor is unbounded; f64::MAX stands in for infinity, which JSON can't hold
+    statistics.profit_factor = if gross_loss > 0.0 {
+        gross_profit / gross_loss
+    } else if gross_profit > 0.0 {
+        f64::MAX
+    } else {
+        0.0
+    };
+    
+    // Streaks follow the order the trades closed in
+    let mut streak = 0i32;
+    for trade in trades {
+        if trade.pl_percent > 0.0 {
+            streak = streak.max(0) + 1;
+            statistics.max_consecutive_wins = statistics.max_consecutive_wins.max(streak);
+        } else {
+            streak = streak.min(0) - 1;
+            statistics.max_consecutive_losses = statistics.max_consecutive_losses.max(-streak);
+        }
+    }
+    
+    statistics
+}
+
+/// Calculate Sharpe and Sortino ratios, tail risk of trade returns, exposure and turnover
+fn risk_metrics(candle_data: &CandleData, start_idx: usize, trades: &[TradeResult]) -> RiskMetrics {
+    let mut metrics = RiskMetrics::default();
+    let returns = period_returns(candle_data, start_idx, trades);
+    
+    if !returns.is_empty() {
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let annualization = periods_per_year(&candle_data.interval).sqrt();
+        
+        let deviation = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n).sqrt();
+        if deviation > 0.0 {
+            metrics.sharpe_ratio = mean / deviation * annualization;
+        }
+        
+        // Downside deviation only penalizes candles that lost money
+        let downside = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+        if downside > 0.0 {
+            metrics.sortino_ratio = mean / downside * annualization;
+        }
+        
+        // Exposure is measured in time so partially filled candles count proportionally
+        let period_start = candle_data.open_time[start_idx];
+        let period_end = candle_data.close_time[candle_data.close_time.len() - 1];
+        let period_seconds = (period_end - period_start).num_seconds();
+        if period_seconds > 0 {
+            let held_seconds: i64 = trades.iter()
+                .map(|trade| (trade.exit_time - trade.entry_time).num_seconds().max(0))
+                .sum();
+            metrics.exposure_percent = (held_seconds as f64 / period_seconds as f64 * 100.0).min(100.0);
+        }
+    }
+    
+    // Historical 95% value at risk and expected shortfall of the trade returns
+    if !trades.is_empty() {
+        let mut trade_returns: Vec<f64> = trades.iter().map(|trade| trade.pl_percent).collect();
+        trade_returns.sort_by(|a, b| a.total_cmp(b));
+        
+        let tail = ((trade_returns.len() as f64 * 0.05).ceil() as usize).max(1);
+        metrics.value_at_risk = (-trade_returns[tail - 1]).max(0.0);
+        metrics.expected_shortfall = (-trade_returns[..tail].iter().sum::<f64>() / tail as f64).max(0.0);
+    }
+    
+    metrics.turnover = trades.iter().map(|trade| 2.0 * trade.size_percent / 100.0).sum();
+    
+    metrics
+}
+
+/// Price at a percentage distance from the entry, on the losing side for stops
+fn percent_level(entry_price: f64, percent: f64, is_long: bool, is_stop: bool) -> f64 {
+    distance_level(entry_price, entry_price * percent / 100.0, is_long, is_stop)
+}
+
+/// Price at an absolute distance from the entry, on the losing side for stops
+fn distance_level(entry_price: f64, distance: f64, is_long: bool, is_stop: bool) -> f64 {
+    // Stops sit below longs and above shorts, targets the other way round
+    if is_long == is_stop {
+        entry_price - distance
+    } else {
+        entry_price + distance
+    }
+}
+
+/// Resolve a stop level to a price using the indicator values at candle `idx`
+fn resolve_stop_level(level: &StopLevel, frame: &AlignedFrame,
+                      idx: usize, entry_price: f64, is_long: bool, is_stop: bool) -> Option<f64> {
+    match level {
+        StopLevel::Percent { value } => Some(percent_level(entry_price, *value, is_long, is_stop)),
+        StopLevel::AtrMultiple { indicator_id, multiple } => {
+            let atr = frame.numeric_at(indicator_id, None, idx)?;
+            Some(distance_level(entry_price, atr * multiple, is_long, is_stop))
+        },
+        StopLevel::Indicator { indicator_id, property } => {
+            let price = frame.numeric_at(indicator_id, property.as_deref(), idx)?;
+            
+            // A level on the wrong side of the entry would trigger immediately
+            let valid = if is_long == is_stop { price < entry_price } else { price > entry_price };
+            if !valid {
+                warn!("Indicator level {} ({:?}) = {} is on the wrong side of entry {}, ignoring",
+                      indicator_id, property, price, entry_price);
+                return None;
+            }
             
-            trade_results.push(TradeResult {
-                is_long: position.is_long,
-                entry_price: position.entry_price,
-                exit_price: close_price,
-                size_percent: position.size_percent,
-                entry_time: position.entry_time,
-                exit_time: candle_time,
-                exit_reason: "End of Simulation".to_string(),
-                pl_percent,
-            });
-        }
-        
-        Ok(trade_results)
+            Some(price)
+        },
     }
 }
@@ -0,0 +1,811 @@
+// src/strategy/genetic.rs
+//
+// Genetic search over strategy parameters
+//
+// A candidate assigns a value to each tunable parameter: integers and floats within
+// min..max (floats snapped to their step), booleans, and strings with options; strings
+// without options keep their value. The first generation holds the strategy's own values
+// and random candidates. Each following generation keeps the `elite` best candidates and
+// fills up with children of tournament-selected parents: uniform crossover, then every
+// gene mutates with `mutation_rate`, numbers moving by up to a tenth of their range. The
+// search ends after `generations`, or early when the best fitness hasn't improved for
+// `patience` generations. A search whose first generation scores the same for every distinct
+// candidate is refused: the parameters don't reach the backtest, or no candidate trades
+// enough, and ranking the candidates would be meaningless.
+//
+// Every evaluated candidate is stored in optimization_candidates with the ID of its saved
+// backtest result; a candidate seen before is not backtested again.
+//
+// Overfitting guards: the last `holdout` share of the period is kept out of sample. The
+// search only sees in-sample fitness; each candidate is also backtested out of sample and
+// its degradation, (in-sample - out-of-sample) / |in-sample| in percent, is stored. The
+// best `stability_top` candidates additionally get a stability score, 1 / (1 + mean
+// relative change of the in-sample metric) over their ±1 step neighbours, so 1 means
+// the metric doesn't move at all. Candidates degrading more than `max_degradation` or less
+// stable than `min_stability` are flagged as likely overfit, and the best candidate that
+// isn't flagged is reported as the result. When every candidate has the same out-of-sample
+// fitness, or no neighbour of any checked candidate moves the metric, the guards can't tell
+// candidates apart; the outcome carries a warning instead of silently passing them.
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::strategy::editor::set_parameter_value;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::{Strategy, StrategyParameter, StrategyPerformance};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Row;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
+
+/// Metric a search maximizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    Expectancy,
+    SharpeRatio,
+    SortinoRatio,
+    TotalReturn,
+    ProfitFactor,
+    CalmarRatio,
+}
+
+impl Objective {
+    pub fn score(self, performance: &StrategyPerformance) -> f64 {
+        match self {
+            Objective::Expectancy => performance.expectancy,
+            Objective::SharpeRatio => performance.sharpe_ratio,
+            Objective::SortinoRatio => performance.sortino_ratio,
+            Objective::TotalReturn => performance.total_return,
+            Objective::ProfitFactor => performance.profit_factor,
+            Objective::CalmarRatio => performance.calmar_ratio,
+        }
+    }
+}
+
+/// Settings of a genetic search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneticSettings {
+    pub population: usize,
+    /// Maximum number of generations, including the first
+    pub generations: usize,
+    /// Probability that a gene of a child mutates
+    pub mutation_rate: f64,
+    /// Probability that a child mixes two parents instead of copying one
+    pub crossover_rate: f64,
+    /// Best candidates carried over unchanged into the next generation
+    pub elite: usize,
+    /// Candidates competing for each parent slot
+    pub tournament: usize,
+    /// Stop after this many generations without a better candidate; 0 never stops early
+    pub patience: usize,
+    /// Candidates with fewer trades get no fitness
+    pub min_trades: i32,
+    pub objective: Objective,
+    /// Seed of the random generator, for reproducible searches
+    pub seed: Option<u64>,
+    /// Share of the period held out of sample; 0 disables the out-of-sample check
+    pub holdout: f64,
+    /// Best candidates whose parameter stability is checked
+    pub stability_top: usize,
+    /// Flag candidates whose out-of-sample metric is this many percent below in-sample
+    pub max_degradation: f64,
+    /// Flag candidates with a lower stability score
+    pub min_stability: f64,
+}
+
+impl Default for GeneticSettings {
+    fn default() -> Self {
+        Self {
+            population: 20,
+            generations: 10,
+            mutation_rate: 0.2,
+            crossover_rate: 0.7,
+            elite: 2,
+            tournament: 3,
+            patience: 3,
+            min_trades: 5,
+            objective: Objective::Expectancy,
+            seed: None,
+            holdout: 0.25,
+            stability_top: 3,
+            max_degradation: 50.0,
+            min_stability: 0.5,
+        }
+    }
+}
+
+impl GeneticSettings {
+    pub fn validate(&self) -> Result<()> {
+        if self.population < 2 {
+            return Err(Error::validation("population must be at least 2"));
+        }
+        if self.generations == 0 {
+            return Err(Error::validation("generations must be at least 1"));
+        }
+        if !(0.0..=1.0).contains(&self.mutation_rate) || !(0.0..=1.0).contains(&self.crossover_rate) {
+            return Err(Error::validation("mutation and crossover rates must be between 0 and 1"));
+        }
+        if self.elite >= self.population {
+            return Err(Error::validation("elite must be smaller than the population"));
+        }
+        if self.tournament == 0 {
+            return Err(Error::validation("tournament size must be at least 1"));
+        }
+        if !(0.0..0.9).contains(&self.holdout) {
+            return Err(Error::validation("holdout must be at least 0 and below 0.9"));
+        }
+        Ok(())
+    }
+}
+
+/// Market and period every candidate is backtested on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchTarget {
+    pub symbol: String,
+    pub interval: String,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub initial_capital: f64,
+    pub engine_profile: String,
+}
+
+/// Parameter values by parameter ID
+pub type Candidate = BTreeMap<String, Value>;
+
+/// Backtest period; open ends use all stored candles
+pub type Period = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+// Whether the search varies a parameter
+fn tunable(parameter: &StrategyParameter) -> bool {
+    match parameter {
+        StrategyParameter::Integer { min, max, .. } => min < max,
+        StrategyParameter::Float { min, max, .. } => min < max,
+        StrategyParameter::Boolean { .. } => true,
+        StrategyParameter::String { options, .. } => options.as_ref().is_some_and(|options| options.len() > 1),
+    }
+}
+
+fn current_value(parameter: &StrategyParameter) -> Value {
+    match parameter {
+        StrategyParameter::Integer { value, .. } => Value::from(*value),
+        StrategyParameter::Float { value, .. } => Value::from(*value),
+        StrategyParameter::Boolean { value, .. } => Value::from(*value),
+        StrategyParameter::String { value, .. } => Value::from(value.as_str()),
+    }
+}
+
+// Clamp a float into its range and onto its step grid
+fn snap(value: f64, min: f64, max: f64, step: Option<f64>) -> f64 {
+    let value = match step.filter(|step| *step > 0.0) {
+        Some(step) => min + ((value - min) / step).round() * step,
+        None => value,
+    };
+    // Limit the digits so stored candidates compare equal
+    (value.clamp(min, max) * 1e8).round() / 1e8
+}
+
+fn random_value(parameter: &StrategyParameter, rng: &mut StdRng) -> Value {
+    match parameter {
+        StrategyParameter::Integer { min, max, .. } => Value::from(rng.gen_range(*min..=*max)),
+        StrategyParameter::Float { min, max, step, .. } => Value::from(snap(rng.gen_range(*min..=*max), *min, *max, *step)),
+        StrategyParameter::Boolean { .. } => Value::from(rng.gen_bool(0.5)),
+        StrategyParameter::String { value, options, .. } => options.as_ref()
+            .and_then(|options| options.choose(rng))
+            .map(|option| Value::from(option.as_str()))
+            .unwrap_or_else(|| Value::from(value.as_str())),
+    }
+}
+
+fn mutate_value(parameter: &StrategyParameter, value: &Value, rng: &mut StdRng) -> Value {
+    match parameter {
+        StrategyParameter::Integer { min, max, .. } => {
+            let reach = ((max - min) / 10).max(1);
+            let current = value.as_i64().unwrap_or(*min);
+            Value::from((current + rng.gen_range(-reach..=reach)).clamp(*min, *max))
+        },
+        StrategyParameter::Float { min, max, step, .. } => {
+            let reach = (max - min) / 10.0;
+            let current = value.as_f64().unwrap_or(*min);
+            Value::from(snap(current + rng.gen_range(-reach..=reach), *min, *max, *step))
+        },
+        StrategyParameter::Boolean { .. } => Value::from(!value.as_bool().unwrap_or(false)),
+        StrategyParameter::String { .. } => random_value(parameter, rng),
+    }
+}
+
+// Candidates one step away from `candidate` in each parameter
+fn neighbours(parameters: &HashMap<&String, &StrategyParameter>, candidate: &Candidate) -> Vec<Candidate> {
+    let mut neighbours = Vec::new();
+    for (id, value) in candidate {
+        let moved: Vec<Value> = match parameters.get(id) {
+            Some(StrategyParameter::Integer { min, max, .. }) => {
+                let current = value.as_i64().unwrap_or(*min);
+                [current - 1, current + 1].into_iter()
+                    .filter(|v| (*min..=*max).contains(v))
+                    .map(Value::from)
+                    .collect()
+            },
+            Some(StrategyParameter::Float { min, max, step, .. }) => {
+                let step = step.filter(|step| *step > 0.0).unwrap_or((max - min) / 100.0);
+                let current = value.as_f64().unwrap_or(*min);
+                [current - step, current + step].into_iter()
+                    .filter(|v| *v >= *min && *v <= *max)
+                    .map(|v| Value::from(snap(v, *min, *max, None)))
+                    .collect()
+            },
+            Some(StrategyParameter::Boolean { .. }) => vec![Value::from(!value.as_bool().unwrap_or(false))],
+            _ => Vec::new(),
+        };
+
+        for value in moved {
+            let mut neighbour = candidate.clone();
+            neighbour.insert(id.clone(), value);
+            neighbours.push(neighbour);
+        }
+    }
+    neighbours
+}
+
+/// The strategy with a candidate's parameter values
+pub fn apply_candidate(strategy: &Strategy, candidate: &Candidate) -> Result<Strategy> {
+    let mut strategy = strategy.clone();
+    for (id, value) in candidate {
+        let parameter = strategy.parameters.get_mut(id)
+            .ok_or_else(|| Error::not_found(format!("Parameter {} in strategy", id)))?;
+        let text = match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        set_parameter_value(parameter, id, &text)?;
+    }
+    Ok(strategy)
+}
+
+/// An evaluated candidate
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub candidate: Candidate,
+    /// In-sample fitness; None when the backtest failed or had too few trades
+    pub fitness: Option<f64>,
+    pub candidate_id: i32,
+    pub backtest_id: Option<i32>,
+    pub oos_fitness: Option<f64>,
+    pub oos_backtest_id: Option<i32>,
+    /// Percent by which the out-of-sample metric falls short of the in-sample one
+    pub degradation: Option<f64>,
+    /// Only checked for the best candidates
+    pub stability: Option<f64>,
+    pub overfit: bool,
+}
+
+impl Evaluation {
+    fn rank(&self) -> f64 {
+        self.fitness.unwrap_or(f64::NEG_INFINITY)
+    }
+}
+
+/// Whether every evaluation has the same fitness, including none at all
+pub fn uniform_fitness(evaluations: &[Evaluation]) -> bool {
+    evaluations.windows(2).all(|pair| pair[0].fitness == pair[1].fitness)
+}
+
+/// Percent by which `out_of_sample` falls short of `in_sample`
+pub fn degradation(in_sample: f64, out_of_sample: f64) -> Option<f64> {
+    (in_sample != 0.0).then(|| (in_sample - out_of_sample) / in_sample.abs() * 100.0)
+}
+
+/// Stability score of a fitness given its neighbours' fitness (None counts as a full change)
+pub fn stability(fitness: f64, neighbours: &[Option<f64>]) -> Option<f64> {
+    if neighbours.is_empty() {
+        return None;
+    }
+    let scale = fitness.abs().max(f64::EPSILON);
+    let change: f64 = neighbours.iter()
+        .map(|neighbour| neighbour.map_or(1.0, |n| ((n - fitness).abs() / scale).min(1.0)))
+        .sum::<f64>() / neighbours.len() as f64;
+    Some(1.0 / (1.0 + change))
+}
+
+/// Outcome of a genetic search
+#[derive(Debug, Clone)]
+pub struct SearchOutcome {
+    pub run_id: i32,
+    /// Seed of the search and its backtests
+    pub seed: u64,
+    /// Best candidate not flagged as overfit, or the best overall if all are
+    pub best: Evaluation,
+    pub best_strategy: Strategy,
+    /// Candidates whose stability was checked, best first
+    pub top: Vec<Evaluation>,
+    pub in_sample: Period,
+    pub out_of_sample: Option<Period>,
+    /// Candidates backtested
+    pub evaluated: usize,
+    pub generations: usize,
+    pub stopped_early: bool,
+    /// Overfitting guards that could not tell the candidates apart
+    pub warnings: Vec<String>,
+}
+
+/// A stored candidate of a search run
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredCandidate {
+    pub id: i32,
+    pub generation: i32,
+    pub parameters: Value,
+    pub fitness: Option<f64>,
+    pub backtest_id: Option<i32>,
+    pub error: Option<String>,
+    pub oos_fitness: Option<f64>,
+    pub oos_backtest_id: Option<i32>,
+    pub degradation_percent: Option<f64>,
+    pub stability: Option<f64>,
+    pub overfit: Option<bool>,
+}
+
+/// Create the optimization_runs and optimization_candidates tables if they don't exist
+pub async fn init_tables(pg: &PostgresManager) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS optimization_runs (
+            id SERIAL PRIMARY KEY,
+            strategy_id VARCHAR NOT NULL,
+            method VARCHAR NOT NULL,
+            symbol VARCHAR NOT NULL,
+            interval VARCHAR NOT NULL,
+            objective VARCHAR NOT NULL,
+            settings JSONB NOT NULL,
+            best_candidate_id INTEGER,
+            stopped_early BOOLEAN,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+            finished_at TIMESTAMPTZ
+        )"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS optimization_candidates (
+            id SERIAL PRIMARY KEY,
+            run_id INTEGER NOT NULL REFERENCES optimization_runs(id) ON DELETE CASCADE,
+            generation INTEGER NOT NULL,
+            parameters JSONB NOT NULL,
+            fitness DOUBLE PRECISION,
+            backtest_id INTEGER,
+            error TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_optimization_candidates_run ON optimization_candidates(run_id, fitness DESC)"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    // Overfitting guards
+    sqlx::query(
+        "ALTER TABLE optimization_runs
+         ADD COLUMN IF NOT EXISTS in_sample_end TIMESTAMPTZ"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    sqlx::query(
+        "ALTER TABLE optimization_candidates
+         ADD COLUMN IF NOT EXISTS oos_fitness DOUBLE PRECISION,
+         ADD COLUMN IF NOT EXISTS oos_backtest_id INTEGER,
+         ADD COLUMN IF NOT EXISTS degradation_percent DOUBLE PRECISION,
+         ADD COLUMN IF NOT EXISTS stability DOUBLE PRECISION,
+         ADD COLUMN IF NOT EXISTS overfit BOOLEAN"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Candidates of a search run, best first
+pub async fn get_run_candidates(pg: &PostgresManager, run_id: i32, limit: i64) -> Result<Vec<StoredCandidate>> {
+    let rows = sqlx::query(
+        "SELECT id, generation, parameters, fitness, backtest_id, error,
+                oos_fitness, oos_backtest_id, degradation_percent, stability, overfit
+         FROM optimization_candidates
+         WHERE run_id = $1
+         ORDER BY fitness DESC NULLS LAST, id
+         LIMIT $2"
+    )
+    .bind(run_id)
+    .bind(limit)
+    .fetch_all(&pg.pool)
+    .await?;
+
+    Ok(rows.iter()
+        .map(|row| StoredCandidate {
+            id: row.get("id"),
+            generation: row.get("generation"),
+            parameters: row.get("parameters"),
+            fitness: row.get("fitness"),
+            backtest_id: row.get("backtest_id"),
+            error: row.get("error"),
+            oos_fitness: row.get("oos_fitness"),
+            oos_backtest_id: row.get("oos_backtest_id"),
+            degradation_percent: row.get("degradation_percent"),
+            stability: row.get("stability"),
+            overfit: row.get("overfit"),
+        })
+        .collect())
+}
+
+/// Searches strategy parameters with a genetic algorithm
+pub struct GeneticOptimizer<'a> {
+    repository: &'a StrategyRepository,
+    target: SearchTarget,
+    settings: GeneticSettings,
+}
+
+impl<'a> GeneticOptimizer<'a> {
+    /// Create a search; without a seed one is drawn, so every run is stored with the seed that reproduces it
+    pub fn new(repository: &'a StrategyRepository, target: SearchTarget, mut settings: GeneticSettings) -> Self {
+        settings.seed.get_or_insert_with(rand::random);
+        Self { repository, target, settings }
+    }
+
+    // Split the target period into in-sample and out-of-sample parts
+    async fn periods(&self) -> Result<(Period, Option<Period>)> {
+        let target = &self.target;
+        if self.settings.holdout <= 0.0 {
+            return Ok(((target.start_date, target.end_date), None));
+        }
+
+        let (first, last) = self.repository.get_db_connection()
+            .get_candle_data_range(&target.symbol, &target.interval).await?;
+        let start = target.start_date.unwrap_or(first).max(first);
+        let end = target.end_date.unwrap_or(last).min(last);
+        if start >= end {
+            return Err(Error::validation(format!("No candles of {}:{} in the search period", target.symbol, target.interval)));
+        }
+
+        let split = start + (end - start) * ((1.0 - self.settings.holdout) * 1000.0) as i32 / 1000;
+        Ok(((Some(start), Some(split)), Some((Some(split), Some(end)))))
+    }
+
+    // Backtest a strategy over a period, storing the result when `save` is set
+    async fn backtest(&self, strategy: &Strategy, period: Period, save: bool) -> Result<(StrategyPerformance, Option<i32>)> {
+        let target = &self.target;
+        let (start_date, end_date) = period;
+        let evaluator = self.repository
+            .evaluator_for(&target.symbol, &target.engine_profile, target.initial_capital, start_date, end_date)
+            .await?
+            .with_seed(self.settings.seed.unwrap_or_default());
+        let performance = evaluator
+            .backtest(strategy, &target.symbol, &target.interval, start_date, end_date)
+            .await?;
+        if !save {
+            return Ok((performance, None));
+        }
+
+        let backtest_id = self.repository.save_backtest_result(
+            &strategy.id,
+            &target.symbol,
+            &target.interval,
+            start_date,
+            end_date,
+            target.initial_capital,
+            &performance,
+        ).await?;
+        self.repository.record_backtest_engine_settings(backtest_id, &evaluator).await?;
+
+        Ok((performance, Some(backtest_id)))
+    }
+
+    // Fitness of a performance; None with too few trades
+    fn fitness(&self, performance: &StrategyPerformance, min_trades: i32) -> Option<f64> {
+        let score = self.settings.objective.score(performance);
+        (performance.total_trades >= min_trades && score.is_finite()).then_some(score)
+    }
+
+    async fn evaluate(
+        &self,
+        pg: &PostgresManager,
+        run_id: i32,
+        generation: usize,
+        strategy: &Strategy,
+        candidate: Candidate,
+        (in_sample, out_of_sample): (Period, Option<Period>),
+    ) -> Result<Evaluation> {
+        let mut evaluation = Evaluation {
+            candidate,
+            fitness: None,
+            candidate_id: 0,
+            backtest_id: None,
+            oos_fitness: None,
+            oos_backtest_id: None,
+            degradation: None,
+            stability: None,
+            overfit: false,
+        };
+
+        let result = async {
+            let variant = apply_candidate(strategy, &evaluation.candidate)?;
+            let (performance, backtest_id) = self.backtest(&variant, in_sample, true).await?;
+            evaluation.fitness = self.fitness(&performance, self.settings.min_trades);
+            evaluation.backtest_id = backtest_id;
+
+            // The hold-out period is shorter, so any trade count counts there
+            if let (Some(fitness), Some(period)) = (evaluation.fitness, out_of_sample) {
+                evaluation.overfit = true;
+                let (performance, backtest_id) = self.backtest(&variant, period, true).await?;
+                evaluation.oos_fitness = self.fitness(&performance, 0);
+                evaluation.oos_backtest_id = backtest_id;
+                evaluation.degradation = evaluation.oos_fitness.and_then(|oos| degradation(fitness, oos));
+                evaluation.overfit = !evaluation.degradation.is_some_and(|d| d <= self.settings.max_degradation);
+            }
+            Ok::<_, Error>(())
+        }.await;
+
+        let error = result.err().map(|e| {
+            warn!("Candidate {} failed: {}", serde_json::to_string(&evaluation.candidate).unwrap_or_default(), e);
+            e.to_string()
+        });
+
+        let row = sqlx::query(
+            "INSERT INTO optimization_candidates
+             (run_id, generation, parameters, fitness, backtest_id, error,
+              oos_fitness, oos_backtest_id, degradation_percent, overfit)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             RETURNING id"
+        )
+        .bind(run_id)
+        .bind(generation as i32)
+        .bind(serde_json::to_value(&evaluation.candidate)?)
+        .bind(evaluation.fitness)
+        .bind(evaluation.backtest_id)
+        .bind(error)
+        .bind(evaluation.oos_fitness)
+        .bind(evaluation.oos_backtest_id)
+        .bind(evaluation.degradation)
+        .bind(evaluation.overfit)
+        .fetch_one(&pg.pool)
+        .await?;
+
+        evaluation.candidate_id = row.get("id");
+        Ok(evaluation)
+    }
+
+    // Score how much the in-sample metric moves when each parameter moves one step
+    async fn check_stability(
+        &self,
+        pg: &PostgresManager,
+        parameters: &HashMap<&String, &StrategyParameter>,
+        strategy: &Strategy,
+        in_sample: Period,
+        evaluation: &mut Evaluation,
+    ) -> Result<()> {
+        let fitness = match evaluation.fitness {
+            Some(fitness) => fitness,
+            None => return Ok(()),
+        };
+
+        let mut scores = Vec::new();
+        for neighbour in neighbours(parameters, &evaluation.candidate) {
+            let score = match apply_candidate(strategy, &neighbour) {
+                Ok(variant) => match self.backtest(&variant, in_sample, false).await {
+                    Ok((performance, _)) => self.fitness(&performance, self.settings.min_trades),
+                    Err(e) => {
+                        warn!("Neighbour {} failed: {}", serde_json::to_string(&neighbour)?, e);
+                        None
+                    },
+                },
+                Err(_) => None,
+            };
+            scores.push(score);
+        }
+
+        evaluation.stability = stability(fitness, &scores);
+        if evaluation.stability.is_some_and(|s| s < self.settings.min_stability) {
+            evaluation.overfit = true;
+        }
+
+        sqlx::query("UPDATE optimization_candidates SET stability = $2, overfit = $3 WHERE id = $1")
+            .bind(evaluation.candidate_id)
+            .bind(evaluation.stability)
+            .bind(evaluation.overfit)
+            .execute(&pg.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // Pick the best of `tournament` random candidates
+    fn select<'e>(&self, ranked: &'e [Evaluation], rng: &mut StdRng) -> &'e Evaluation {
+        (0..self.settings.tournament)
+            .map(|_| &ranked[rng.gen_range(0..ranked.len())])
+            .max_by(|a, b| a.rank().total_cmp(&b.rank()))
+            .unwrap_or(&ranked[0])
+    }
+
+    fn child(&self, parameters: &HashMap<&String, &StrategyParameter>, ranked: &[Evaluation], rng: &mut StdRng) -> Candidate {
+        let first = self.select(ranked, rng);
+        let second = self.select(ranked, rng);
+        let crossover = rng.gen_bool(self.settings.crossover_rate);
+
+        first.candidate.iter()
+            .map(|(id, value)| {
+                let mut value = match second.candidate.get(id) {
+                    Some(other) if crossover && rng.gen_bool(0.5) => other.clone(),
+                    _ => value.clone(),
+                };
+                if rng.gen_bool(self.settings.mutation_rate) {
+                    value = mutate_value(parameters[id], &value, rng);
+                }
+                (id.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Run the search; the strategy itself is not modified
+    pub async fn run(&self, strategy: &Strategy) -> Result<SearchOutcome> {
+        self.settings.validate()?;
+        let parameters: HashMap<&String, &StrategyParameter> = strategy.parameters.iter()
+            .filter(|(_, parameter)| tunable(parameter))
+            .collect();
+        if parameters.is_empty() {
+            return Err(Error::validation(format!("Strategy {} has no tunable parameters", strategy.name)));
+        }
+
+        let (in_sample, out_of_sample) = self.periods().await?;
+
+        let pg = self.repository.get_db_connection();
+        init_tables(&pg).await?;
+        let run_id: i32 = sqlx::query(
+            "INSERT INTO optimization_runs (strategy_id, method, symbol, interval, objective, settings, in_sample_end)
+             VALUES ($1, 'genetic', $2, $3, $4, $5, $6)
+             RETURNING id"
+        )
+        .bind(&strategy.id)
+        .bind(&self.target.symbol)
+        .bind(&self.target.interval)
+        .bind(serde_json::to_value(self.settings.objective)?.as_str().unwrap_or_default())
+        .bind(serde_json::json!({"genetic": self.settings, "target": self.target}))
+        .bind(out_of_sample.and(in_sample.1))
+        .fetch_one(&pg.pool)
+        .await?
+        .get("id");
+        info!("Genetic search run {} for strategy {} over {} parameters", run_id, strategy.name, parameters.len());
+
+        let seed = self.settings.seed.unwrap_or_default();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // The strategy's own values, then random candidates
+        let mut offspring: Vec<Candidate> = vec![parameters.iter()
+            .map(|(id, parameter)| ((*id).clone(), current_value(parameter)))
+            .collect()];
+        while offspring.len() < self.settings.population {
+            offspring.push(parameters.iter()
+                .map(|(id, parameter)| ((*id).clone(), random_value(parameter, &mut rng)))
+                .collect());
+        }
+
+        let mut seen: HashMap<String, Evaluation> = HashMap::new();
+        let mut ranked: Vec<Evaluation> = Vec::new();
+        let mut best_rank = f64::NEG_INFINITY;
+        let (mut generation, mut stale, mut stopped_early) = (0, 0, false);
+
+        loop {
+            let mut population: Vec<Evaluation> = ranked.iter().take(self.settings.elite).cloned().collect();
+            for candidate in offspring.drain(..) {
+                let key = serde_json::to_string(&candidate)?;
+                let evaluation = match seen.get(&key) {
+                    Some(evaluation) => evaluation.clone(),
+                    None => {
+                        let evaluation = self.evaluate(&pg, run_id, generation, strategy, candidate, (in_sample, out_of_sample)).await?;
+                        seen.insert(key, evaluation.clone());
+                        evaluation
+                    },
+                };
+                population.push(evaluation);
+            }
+
+            population.sort_by(|a, b| b.rank().total_cmp(&a.rank()));
+            ranked = population;
+
+            // Nothing to select from when the distinct candidates are indistinguishable
+            if generation == 0 && seen.len() > 1 && uniform_fitness(&ranked) {
+                sqlx::query("UPDATE optimization_runs SET finished_at = NOW() WHERE id = $1")
+                    .bind(run_id)
+                    .execute(&pg.pool)
+                    .await?;
+                let score = ranked[0].fitness.map_or("no fitness".to_string(), |f| format!("fitness {:.4}", f));
+                return Err(Error::validation(format!(
+                    "All {} candidates of the first generation of run {} have {}; the tunable parameters \
+                     don't change the backtest or no candidate reached {} trades",
+                    seen.len(), run_id, score, self.settings.min_trades,
+                )));
+            }
+
+            let generation_best = ranked[0].rank();
+            info!("Generation {}: best fitness {}", generation, ranked[0].fitness.map_or("none".to_string(), |f| format!("{:.4}", f)));
+            if generation_best > best_rank {
+                best_rank = generation_best;
+                stale = 0;
+            } else {
+                stale += 1;
+            }
+
+            generation += 1;
+            if generation >= self.settings.generations {
+                break;
+            }
+            if self.settings.patience > 0 && stale >= self.settings.patience {
+                stopped_early = true;
+                break;
+            }
+
+            offspring = (self.settings.elite..self.settings.population)
+                .map(|_| self.child(&parameters, &ranked, &mut rng))
+                .collect();
+        }
+
+        // Stability of the best distinct candidates
+        let evaluated = seen.len();
+        let mut all: Vec<Evaluation> = seen.into_values().collect();
+        all.sort_by(|a, b| b.rank().total_cmp(&a.rank()));
+
+        let mut warnings = Vec::new();
+        let uniform_oos = all.windows(2).all(|pair| pair[0].oos_fitness == pair[1].oos_fitness);
+        if out_of_sample.is_some() && all.len() > 1 && uniform_oos {
+            warnings.push(format!("All {} candidates have the same out-of-sample fitness; \
+                                   degradation doesn't tell them apart", all.len()));
+        }
+
+        let mut top: Vec<Evaluation> = all.into_iter().take(self.settings.stability_top.max(1)).collect();
+        for evaluation in top.iter_mut().take(self.settings.stability_top) {
+            self.check_stability(&pg, &parameters, strategy, in_sample, evaluation).await?;
+        }
+
+        let checked: Vec<Option<f64>> = top.iter().take(self.settings.stability_top).map(|e| e.stability).collect();
+        if !checked.is_empty() && checked.iter().all(|stability| *stability == Some(1.0)) {
+            warnings.push("No one-step parameter change moved the metric of any checked candidate; \
+                           the stability scores can't detect overfitting".to_string());
+        }
+        for warning in &warnings {
+            warn!("Run {}: {}", run_id, warning);
+        }
+
+        let best = top.iter()
+            .find(|evaluation| evaluation.fitness.is_some() && !evaluation.overfit)
+            .unwrap_or(&top[0])
+            .clone();
+        sqlx::query(
+            "UPDATE optimization_runs
+             SET best_candidate_id = $2, stopped_early = $3, finished_at = NOW()
+             WHERE id = $1"
+        )
+        .bind(run_id)
+        .bind(best.candidate_id)
+        .bind(stopped_early)
+        .execute(&pg.pool)
+        .await?;
+
+        Ok(SearchOutcome {
+            run_id,
+            seed,
+            best_strategy: apply_candidate(strategy, &best.candidate)?,
+            best,
+            top,
+            in_sample,
+            out_of_sample,
+            evaluated,
+            generations: generation,
+            stopped_early,
+            warnings,
+        })
+    }
+}
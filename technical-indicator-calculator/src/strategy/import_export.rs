@@ -1,7 +1,10 @@
 // src/strategy/import_export.rs
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::events::{record_event, EventKind};
 use crate::strategy::schema::Strategy;
 use crate::strategy::repository::StrategyRepository;
-use anyhow::{Result, Context};
+use crate::strategy::{script, statistics, watchlist};
 use chrono::Utc;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
@@ -9,37 +12,105 @@ use std::path::Path;
 use tracing::info;
 use uuid::Uuid;
 
-/// Import a strategy from a JSON file
+/// File format of a strategy, detected from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl StrategyFormat {
+    /// Format of a file by its extension; .yaml/.yml and .toml, anything else is JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("yaml") | Some("yml") => StrategyFormat::Yaml,
+            Some("toml") => StrategyFormat::Toml,
+            _ => StrategyFormat::Json,
+        }
+    }
+
+    /// Parse a strategy
+    pub fn parse(self, content: &str) -> Result<Strategy> {
+        match self {
+            StrategyFormat::Json => Ok(serde_json::from_str(content)?),
+            StrategyFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| Error::validation(format!("Invalid YAML strategy: {}", e))),
+            StrategyFormat::Toml => toml::from_str(content)
+                .map_err(|e| Error::validation(format!("Invalid TOML strategy: {}", e))),
+        }
+    }
+
+    /// Serialize a strategy
+    ///
+    /// TOML has no null, so indicator parameters containing null can't be written as TOML.
+    pub fn serialize(self, strategy: &Strategy) -> Result<String> {
+        match self {
+            StrategyFormat::Json => Ok(serde_json::to_string_pretty(strategy)?),
+            StrategyFormat::Yaml => serde_yaml::to_string(strategy)
+                .map_err(|e| Error::validation(format!("Failed to write strategy as YAML: {}", e))),
+            StrategyFormat::Toml => toml::to_string_pretty(strategy)
+                .map_err(|e| Error::validation(format!("Failed to write strategy as TOML: {}", e))),
+        }
+    }
+}
+
+/// Read a strategy file in the format of its extension, without validating it
+pub fn read_strategy_file(file_path: &Path) -> Result<Strategy> {
+    // Open and read the file
+    let file = File::open(file_path)
+        .map_err(Error::file("open", file_path))?;
+    
+    let mut reader = BufReader::new(file);
+    let mut content = String::new();
+    reader.read_to_string(&mut content)
+        .map_err(Error::file("read", file_path))?;
+    
+    StrategyFormat::from_path(file_path).parse(&content)
+}
+
+/// Write a strategy file in the format of its extension
+pub fn write_strategy_file(strategy: &Strategy, file_path: &Path) -> Result<()> {
+    let content = StrategyFormat::from_path(file_path).serialize(strategy)?;
+    
+    let file = File::create(file_path)
+        .map_err(Error::file("create", file_path))?;
+    
+    let mut writer = BufWriter::new(file);
+    writer.write_all(content.as_bytes())
+        .map_err(Error::file("write", file_path))?;
+    writer.flush()
+        .map_err(Error::file("write", file_path))
+}
+
+/// Import a strategy from a JSON, YAML or TOML file
 pub async fn import_strategy_from_file(
     repository: &StrategyRepository, 
     file_path: &Path
 ) -> Result<Strategy> {
     info!("Importing strategy from file: {}", file_path.display());
     
-    // Open and read the file
-    let file = File::open(file_path)
-        .context(format!("Failed to open file: {}", file_path.display()))?;
-    
-    let mut reader = BufReader::new(file);
-    let mut json_str = String::new();
-    reader.read_to_string(&mut json_str)
-        .context(format!("Failed to read file: {}", file_path.display()))?;
-    
-    // Parse the JSON into a Strategy object
-    let mut strategy: Strategy = serde_json::from_str(&json_str)
-        .context("Failed to parse strategy JSON")?;
+    let mut strategy = read_strategy_file(file_path)?;
     
     // Validate the strategy
     validate_strategy(&mut strategy)?;
+    repository.assign_free_id(&mut strategy).await?;
     
     // Save the strategy to the database
     repository.save_strategy(&strategy).await?;
     
     info!("Strategy imported successfully: {} ({})", strategy.name, strategy.id);
+    record_event(
+        &repository.get_db_connection(),
+        EventKind::StrategyImported,
+        Some(&strategy.id),
+        &format!("Imported strategy '{}' version {} from {}", strategy.name, strategy.version, file_path.display()),
+        serde_json::json!({"name": strategy.name, "version": strategy.version, "file": file_path.display().to_string()}),
+    ).await;
     Ok(strategy)
 }
 
-/// Export a strategy to a JSON file
+/// Export a strategy to a JSON, YAML or TOML file
 pub async fn export_strategy_to_file(
     repository: &StrategyRepository, 
     strategy_id: &str,
@@ -50,22 +121,89 @@ pub async fn export_strategy_to_file(
     // Load the strategy
     let strategy = repository.get_strategy(strategy_id).await?;
     
-    // Convert to JSON
-    let json = serde_json::to_string_pretty(&strategy)
-        .context("Failed to serialize strategy to JSON")?;
-    
-    // Write to file
-    let file = File::create(file_path)
-        .context(format!("Failed to create file: {}", file_path.display()))?;
-    
-    let mut writer = BufWriter::new(file);
-    writer.write_all(json.as_bytes())
-        .context(format!("Failed to write to file: {}", file_path.display()))?;
+    write_strategy_file(&strategy, file_path)?;
     
     info!("Strategy exported successfully to: {}", file_path.display());
     Ok(())
 }
 
+/// An indicator configuration a strategy needs that isn't enabled yet
+#[derive(Debug, Clone)]
+pub struct RequiredConfig {
+    pub symbol: String,
+    pub interval: String,
+    pub indicator_type: String,
+    pub indicator_name: String,
+    pub parameters: serde_json::Value,
+    /// Existing disabled configuration; None when it has to be created
+    pub disabled_id: Option<i32>,
+}
+
+/// Indicator configurations for the strategy's assets × timeframes × indicators that are
+/// missing or disabled
+///
+/// Script indicators and frame statistics are calculated during the backtest and need none.
+pub async fn required_indicator_configs(strategy: &Strategy, pg: &PostgresManager) -> Result<Vec<RequiredConfig>> {
+    let existing = pg.get_all_indicator_configs().await?;
+    let mut required: Vec<RequiredConfig> = Vec::new();
+
+    for symbol in &watchlist::resolve_assets(pg, &strategy.assets).await? {
+        for interval in &strategy.timeframes {
+            for indicator in strategy.indicators.iter()
+                .filter(|indicator| !script::is_script_indicator(indicator) && !statistics::needs_frame(indicator)) {
+                let config = existing.iter().find(|config| {
+                    &config.symbol == symbol
+                        && &config.interval == interval
+                        && config.indicator_name == indicator.indicator_name
+                        && config.parameters == indicator.parameters
+                });
+                if config.is_some_and(|config| config.enabled) {
+                    continue;
+                }
+
+                // Indicators listed twice with the same parameters need one configuration
+                let duplicate = required.iter().any(|r| {
+                    &r.symbol == symbol && &r.interval == interval
+                        && r.indicator_name == indicator.indicator_name && r.parameters == indicator.parameters
+                });
+                if !duplicate {
+                    required.push(RequiredConfig {
+                        symbol: symbol.clone(),
+                        interval: interval.clone(),
+                        indicator_type: indicator.indicator_type.clone(),
+                        indicator_name: indicator.indicator_name.clone(),
+                        parameters: indicator.parameters.clone(),
+                        disabled_id: config.map(|config| config.id),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(required)
+}
+
+/// Create the missing configurations and enable the disabled ones
+pub async fn provision_indicator_configs(pg: &PostgresManager, configs: &[RequiredConfig]) -> Result<()> {
+    for config in configs {
+        match config.disabled_id {
+            Some(id) => pg.set_indicator_config_enabled(id, true).await?,
+            None => {
+                pg.insert_indicator_config(
+                    &config.symbol,
+                    &config.interval,
+                    &config.indicator_type,
+                    &config.indicator_name,
+                    &config.parameters,
+                ).await?;
+            },
+        }
+    }
+
+    info!("Provisioned {} indicator configurations", configs.len());
+    Ok(())
+}
+
 /// Validate a strategy and ensure it has a valid UUID
 fn validate_strategy(strategy: &mut Strategy) -> Result<()> {
     // Check if the strategy has a valid UUID
@@ -76,7 +214,7 @@ fn validate_strategy(strategy: &mut Strategy) -> Result<()> {
     } else {
         // Validate the existing UUID
         let _: Uuid = Uuid::parse_str(&strategy.id)
-            .context("Invalid UUID format in strategy")?;
+            .map_err(|e| Error::validation(format!("Invalid UUID format in strategy: {}", e)))?;
     }
     
     // Ensure created_at and updated_at are set
@@ -88,17 +226,20 @@ fn validate_strategy(strategy: &mut Strategy) -> Result<()> {
     // Validate indicators
     for indicator in &strategy.indicators {
         if indicator.id.is_empty() {
-            return Err(anyhow::anyhow!("Indicator ID cannot be empty"));
+            return Err(Error::validation("Indicator ID cannot be empty"));
         }
     }
     
     // Validate rules
     for rule in &strategy.rules {
         if rule.id.is_empty() {
-            return Err(anyhow::anyhow!("Rule ID cannot be empty"));
+            return Err(Error::validation("Rule ID cannot be empty"));
         }
     }
     
+    // Expressions must compile in the sandbox before the strategy is stored
+    script::validate_scripts(strategy)?;
+    
     Ok(())
 }
 
@@ -115,6 +256,7 @@ pub fn create_new_strategy(name: &str, description: &str) -> Strategy {
         enabled: true,
         assets: vec!["BTCUSDT".to_string()],
         timeframes: vec!["1h".to_string()],
+        tags: Vec::new(),
         indicators: Vec::new(),
         rules: Vec::new(),
         parameters: std::collections::HashMap::new(),
@@ -0,0 +1,122 @@
+// Filtering and sorting of the strategy list
+//
+// `list` narrows strategies by asset, timeframe, author, tags and free text over name
+// and description, and by metrics of each strategy's latest backtest result, e.g.
+// "sharpe>1.5". Filters are applied in SQL; metric names map to columns of
+// strategy_backtest_results. Strategies without a backtest never match a metric filter
+// and are sorted last by a metric.
+use crate::error::{Error, Result};
+
+/// Metric names accepted by --metric and --sort, with their strategy_backtest_results column
+pub const METRICS: [(&str, &str); 12] = [
+    ("sharpe", "sharpe_ratio"),
+    ("sortino", "sortino_ratio"),
+    ("calmar", "calmar_ratio"),
+    ("return", "total_return"),
+    ("annualized_return", "annualized_return"),
+    ("excess_return", "excess_return"),
+    ("drawdown", "max_drawdown"),
+    ("win_rate", "win_rate"),
+    ("profit_factor", "profit_factor"),
+    ("expectancy", "expectancy"),
+    ("trades", "total_trades"),
+    ("exposure", "exposure_percent"),
+];
+
+/// Column of strategy_backtest_results for a metric name
+pub fn metric_column(name: &str) -> Result<&'static str> {
+    METRICS.iter()
+        .find(|(metric, _)| *metric == name)
+        .map(|(_, column)| *column)
+        .ok_or_else(|| Error::validation(format!(
+            "Unknown metric '{}', expected one of: {}",
+            name,
+            METRICS.iter().map(|(metric, _)| *metric).collect::<Vec<_>>().join(", ")
+        )))
+}
+
+/// A comparison of a latest-backtest metric with a value, e.g. "sharpe>1.5"
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricFilter {
+    pub column: &'static str,
+    pub operator: &'static str,
+    pub value: f64,
+}
+
+impl MetricFilter {
+    pub fn parse(expression: &str) -> Result<Self> {
+        // Two-character operators first so ">=" isn't read as ">"
+        const OPERATORS: [&str; 6] = [">=", "<=", "!=", ">", "<", "="];
+
+        let (position, operator) = OPERATORS.iter()
+            .filter_map(|operator| expression.find(operator).map(|position| (position, *operator)))
+            .min_by_key(|(position, operator)| (*position, std::cmp::Reverse(operator.len())))
+            .ok_or_else(|| Error::validation(format!(
+                "Invalid metric filter '{}', expected e.g. sharpe>1.5", expression
+            )))?;
+
+        let column = metric_column(expression[..position].trim())?;
+        let value = expression[position + operator.len()..].trim();
+        let value = value.parse::<f64>()
+            .map_err(|_| Error::validation(format!("Invalid value '{}' in metric filter '{}'", value, expression)))?;
+
+        Ok(Self { column, operator: if operator == "!=" { "<>" } else { operator }, value })
+    }
+}
+
+/// Order of the strategy list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategySort {
+    Name,
+    Created,
+    Updated,
+    /// A metric of the latest backtest, by column
+    Metric(&'static str),
+}
+
+impl StrategySort {
+    /// "name", "created", "updated" or a metric name
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "name" => Ok(StrategySort::Name),
+            "created" => Ok(StrategySort::Created),
+            "updated" => Ok(StrategySort::Updated),
+            metric => metric_column(metric).map(StrategySort::Metric),
+        }
+    }
+}
+
+/// Which strategies to list and in what order
+#[derive(Debug, Clone)]
+pub struct StrategyFilter {
+    pub enabled_only: bool,
+    /// Strategies trading this symbol
+    pub asset: Option<String>,
+    /// Strategies on this interval
+    pub timeframe: Option<String>,
+    /// Author, matched case-insensitively
+    pub author: Option<String>,
+    /// Text contained in the name or description, matched case-insensitively
+    pub search: Option<String>,
+    /// Tags the strategies must all have
+    pub tags: Vec<String>,
+    pub metrics: Vec<MetricFilter>,
+    pub sort: StrategySort,
+    pub descending: bool,
+}
+
+impl Default for StrategyFilter {
+    fn default() -> Self {
+        Self {
+            enabled_only: false,
+            asset: None,
+            timeframe: None,
+            author: None,
+            search: None,
+            tags: Vec::new(),
+            metrics: Vec::new(),
+            sort: StrategySort::Name,
+            descending: false,
+        }
+    }
+}
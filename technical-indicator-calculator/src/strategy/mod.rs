@@ -2,7 +2,23 @@
 pub mod schema;
 pub mod evaluator;
 pub mod repository;
+pub mod listing;
 pub mod repository_helpers;
 pub mod import_export;
 pub mod validator;
 pub mod cli_handler;
+pub mod templates;
+pub mod editor;
+pub mod engine_settings;
+pub mod archive;
+pub mod bundle;
+pub mod comparison;
+pub mod batch;
+pub mod watchlist;
+pub mod optimizer;
+pub mod genetic;
+pub mod reevaluation;
+pub mod accounting;
+pub mod script;
+pub mod rules;
+pub mod statistics;
@@ -0,0 +1,479 @@
+// src/strategy/optimizer.rs
+//
+// LLM-guided strategy optimization
+//
+// Each iteration sends the strategy's indicators, parameters (with their bounds) and risk
+// management, the metrics of the best configuration so far and the history of earlier
+// attempts to an OpenAI-compatible chat completions endpoint, asking for changed values
+// as a JSON block. Proposed values are checked against the parameter types and min/max,
+// the changed strategy is validated and backtested, and it becomes the new best when its
+// expectancy is higher. Rejected changes are listed in the history the next prompt sees.
+use crate::config::OptimizerSettings;
+use crate::error::{Error, Result};
+use crate::strategy::editor::set_parameter_value;
+use crate::strategy::evaluator::IndicatorSource;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::{RiskManagement, Strategy, StrategyParameter, StrategyPerformance};
+use crate::strategy::validator::validate_strategy;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+const SYSTEM_PROMPT: &str = "You are a trading strategy optimizer for a cryptocurrency algorithmic \
+trading system. You suggest parameter changes based on backtest results and answer with a short \
+analysis followed by exactly one ```json block.";
+
+/// Client for an OpenAI-compatible chat completions endpoint
+pub struct LlmClient {
+    client: reqwest::Client,
+    endpoint: String,
+    model: String,
+    api_key: String,
+    temperature: f64,
+}
+
+impl LlmClient {
+    pub fn new(settings: &OptimizerSettings) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_seconds))
+            .build()
+            .map_err(|e| Error::Other(e.into()))?;
+
+        Ok(Self {
+            client,
+            endpoint: settings.endpoint.clone(),
+            model: settings.model.clone(),
+            api_key: settings.api_key.clone(),
+            temperature: settings.temperature,
+        })
+    }
+
+    /// Send one prompt and return the text of the first choice
+    pub async fn complete(&self, system: &str, prompt: &str) -> Result<String> {
+        let mut request = self.client
+            .post(&self.endpoint)
+            .json(&json!({
+                "model": self.model,
+                "temperature": self.temperature,
+                "messages": [
+                    {"role": "system", "content": system},
+                    {"role": "user", "content": prompt},
+                ],
+            }));
+        if !self.api_key.is_empty() {
+            request = request.bearer_auth(&self.api_key);
+        }
+
+        let response = request.send().await.map_err(|e| Error::Other(e.into()))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| Error::Other(e.into()))?;
+        if !status.is_success() {
+            return Err(Error::Other(anyhow::anyhow!("LLM endpoint {} returned {}: {}", self.endpoint, status, body)));
+        }
+
+        let body: Value = serde_json::from_str(&body)?;
+        body.pointer("/choices/0/message/content")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| Error::Other(anyhow::anyhow!("LLM response has no message content: {}", body)))
+    }
+}
+
+/// Changes proposed by the LLM
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ProposedChanges {
+    /// New values of strategy parameters by ID
+    pub parameters: HashMap<String, Value>,
+    /// New values of risk management settings by name
+    pub risk_management: Map<String, Value>,
+    pub indicators: Vec<IndicatorChange>,
+}
+
+/// New parameter values of one indicator
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndicatorChange {
+    pub id: String,
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+}
+
+/// Parse the JSON block of an LLM answer
+///
+/// Takes the first ```json (or plain ```) fenced block, or else everything from the first
+/// '{' to the last '}'.
+pub fn parse_response(text: &str) -> Result<ProposedChanges> {
+    let fenced = ["```json", "```"].iter().find_map(|fence| {
+        let start = text.find(fence)? + fence.len();
+        let end = text[start..].find("```")?;
+        Some(&text[start..start + end])
+    });
+    let block = match fenced {
+        Some(block) => block,
+        None => match (text.find('{'), text.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &text[start..=end],
+            _ => return Err(Error::validation("LLM answer contains no JSON block")),
+        },
+    };
+
+    serde_json::from_str(block.trim())
+        .map_err(|e| Error::validation(format!("LLM answer has an invalid JSON block: {}", e)))
+}
+
+// Proposed value as the text set_parameter_value parses; whole floats count as integers
+fn parameter_text(parameter: &StrategyParameter, value: &Value) -> String {
+    match (parameter, value) {
+        (_, Value::String(text)) => text.clone(),
+        (StrategyParameter::Integer { .. }, Value::Number(number)) => match number.as_f64() {
+            Some(float) if number.as_i64().is_none() && float.fract() == 0.0 => format!("{}", float as i64),
+            _ => number.to_string(),
+        },
+        (_, other) => other.to_string(),
+    }
+}
+
+// Whether a proposed indicator parameter keeps the type of the current one
+fn same_kind(current: &Value, proposed: &Value) -> bool {
+    match (current, proposed) {
+        (Value::Number(current), Value::Number(proposed)) => {
+            current.is_f64() || proposed.is_i64() || proposed.is_u64()
+        },
+        (Value::String(_), Value::String(_)) | (Value::Bool(_), Value::Bool(_)) => true,
+        _ => false,
+    }
+}
+
+/// A strategy with proposed changes applied
+#[derive(Debug, Clone)]
+pub struct AppliedChanges {
+    pub strategy: Strategy,
+    /// Changes that were applied, as "name = value"
+    pub applied: Vec<String>,
+    /// Changes that were rejected, with the reason
+    pub rejected: Vec<String>,
+}
+
+/// Apply the proposed changes that keep their types and bounds
+///
+/// Fails when the resulting strategy doesn't validate.
+pub fn apply_changes(strategy: &Strategy, changes: &ProposedChanges) -> Result<AppliedChanges> {
+    let mut candidate = strategy.clone();
+    let mut applied = Vec::new();
+    let mut rejected = Vec::new();
+
+    for (id, value) in &changes.parameters {
+        let parameter = match candidate.parameters.get_mut(id) {
+            Some(parameter) => parameter,
+            None => {
+                rejected.push(format!("{}: unknown parameter", id));
+                continue;
+            },
+        };
+        let text = parameter_text(parameter, value);
+        match set_parameter_value(parameter, id, &text) {
+            Ok(()) => applied.push(format!("{} = {}", id, value)),
+            Err(e) => rejected.push(e.to_string()),
+        }
+    }
+
+    let mut risk = serde_json::to_value(&candidate.risk_management)?;
+    for (name, value) in &changes.risk_management {
+        let current = match risk.get(name) {
+            Some(current) => current.clone(),
+            None => {
+                rejected.push(format!("risk_management.{}: unknown setting", name));
+                continue;
+            },
+        };
+        risk[name] = value.clone();
+        match serde_json::from_value::<RiskManagement>(risk.clone()) {
+            Ok(_) => applied.push(format!("risk_management.{} = {}", name, value)),
+            Err(e) => {
+                risk[name] = current;
+                rejected.push(format!("risk_management.{}: {}", name, e));
+            },
+        }
+    }
+    candidate.risk_management = serde_json::from_value(risk)?;
+
+    for change in &changes.indicators {
+        let indicator = match candidate.indicators.iter_mut().find(|indicator| indicator.id == change.id) {
+            Some(indicator) => indicator,
+            None => {
+                rejected.push(format!("{}: unknown indicator", change.id));
+                continue;
+            },
+        };
+        for (name, value) in &change.parameters {
+            match indicator.parameters.get(name) {
+                Some(current) if same_kind(current, value) => {
+                    indicator.parameters[name] = value.clone();
+                    applied.push(format!("{}.{} = {}", change.id, name, value));
+                },
+                Some(current) => rejected.push(format!("{}.{}: expected a value like {}, got {}", change.id, name, current, value)),
+                None => rejected.push(format!("{}.{}: unknown indicator parameter", change.id, name)),
+            }
+        }
+    }
+
+    validate_strategy(&candidate)?.to_result()?;
+
+    Ok(AppliedChanges { strategy: candidate, applied, rejected })
+}
+
+/// What an optimization run backtests on
+#[derive(Debug, Clone)]
+pub struct OptimizationSettings {
+    pub symbol: String,
+    pub interval: String,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub initial_capital: f64,
+    pub engine_profile: String,
+    pub max_iterations: usize,
+}
+
+/// One iteration of an optimization run; iteration 0 is the original strategy
+#[derive(Debug, Clone)]
+pub struct OptimizationIteration {
+    pub iteration: usize,
+    pub applied: Vec<String>,
+    pub rejected: Vec<String>,
+    pub performance: Option<StrategyPerformance>,
+    /// Why the iteration produced no backtest
+    pub error: Option<String>,
+    /// Whether this iteration became the best so far
+    pub improved: bool,
+}
+
+/// Outcome of an optimization run
+#[derive(Debug, Clone)]
+pub struct OptimizationReport {
+    pub settings: OptimizationSettings,
+    pub original: Strategy,
+    pub best: Strategy,
+    pub best_iteration: usize,
+    pub iterations: Vec<OptimizationIteration>,
+}
+
+impl OptimizationReport {
+    /// Whether a changed configuration beat the original
+    pub fn improved(&self) -> bool {
+        self.best_iteration > 0
+    }
+
+    /// Render the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut report = String::from("# Strategy Optimization Report\n\n");
+        report.push_str(&format!("Strategy: {} ({})\n", self.original.name, self.original.id));
+        report.push_str(&format!("Symbol: {}\n", self.settings.symbol));
+        report.push_str(&format!("Interval: {}\n", self.settings.interval));
+        report.push_str(&format!("Iterations: {}\n", self.iterations.len().saturating_sub(1)));
+        report.push_str(&format!("Best iteration: {}\n\n", self.best_iteration));
+
+        report.push_str("## Performance\n\n");
+        report.push_str("| Iteration | Trades | Win Rate | Return | Drawdown | Sharpe | Profit Factor | Expectancy |\n");
+        report.push_str("|-----------|--------|----------|--------|----------|--------|---------------|------------|\n");
+        for iteration in &self.iterations {
+            match &iteration.performance {
+                Some(p) => report.push_str(&format!(
+                    "| {}{} | {} | {:.2}% | {:.2}% | {:.2}% | {:.2} | {:.2} | {:.2} |\n",
+                    iteration.iteration, if iteration.improved { " *" } else { "" },
+                    p.total_trades, p.win_rate, p.total_return, p.max_drawdown,
+                    p.sharpe_ratio, p.profit_factor, p.expectancy,
+                )),
+                None => report.push_str(&format!(
+                    "| {} | - | - | - | - | - | - | - |\n", iteration.iteration,
+                )),
+            }
+        }
+
+        report.push_str("\n## Changes\n");
+        for iteration in self.iterations.iter().skip(1) {
+            report.push_str(&format!("\n### Iteration {}\n\n", iteration.iteration));
+            if let Some(error) = &iteration.error {
+                report.push_str(&format!("Failed: {}\n", error));
+            }
+            for change in &iteration.applied {
+                report.push_str(&format!("- {}\n", change));
+            }
+            for change in &iteration.rejected {
+                report.push_str(&format!("- rejected: {}\n", change));
+            }
+        }
+
+        report.push_str("\n## Best Configuration\n\n```json\n");
+        report.push_str(&serde_json::to_string_pretty(&configuration(&self.best)).unwrap_or_default());
+        report.push_str("\n```\n");
+        report
+    }
+}
+
+// The tunable part of a strategy as shown to the LLM
+fn configuration(strategy: &Strategy) -> Value {
+    json!({
+        "parameters": strategy.parameters,
+        "risk_management": strategy.risk_management,
+        "indicators": strategy.indicators.iter()
+            .map(|indicator| json!({
+                "id": indicator.id,
+                "name": indicator.indicator_name,
+                "parameters": indicator.parameters,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn metrics(performance: &StrategyPerformance) -> Value {
+    json!({
+        "total_trades": performance.total_trades,
+        "win_rate": performance.win_rate,
+        "total_return": performance.total_return,
+        "max_drawdown": performance.max_drawdown,
+        "sharpe_ratio": performance.sharpe_ratio,
+        "profit_factor": performance.profit_factor,
+        "expectancy": performance.expectancy,
+        "avg_profit_per_win": performance.avg_profit_per_win,
+        "avg_loss_per_loss": performance.avg_loss_per_loss,
+    })
+}
+
+fn build_prompt(strategy: &Strategy, performance: &StrategyPerformance, history: &[OptimizationIteration]) -> String {
+    let history: Vec<Value> = history.iter()
+        .map(|iteration| json!({
+            "iteration": iteration.iteration,
+            "applied": iteration.applied,
+            "rejected": iteration.rejected,
+            "error": iteration.error,
+            "performance": iteration.performance.as_ref().map(metrics),
+        }))
+        .collect();
+    let pretty = |value: &Value| serde_json::to_string_pretty(value).unwrap_or_default();
+
+    format!(
+        "## STRATEGY\nName: {}\nDescription: {}\n\n\
+         ## CURRENT CONFIGURATION\n{}\n\n\
+         ## PERFORMANCE OF THE CURRENT CONFIGURATION\n{}\n\n\
+         ## OPTIMIZATION HISTORY\n{}\n\n\
+         ## GOALS\n\
+         Increase expectancy, which decides whether a configuration is kept, while keeping the \
+         drawdown low and the win rate, Sharpe ratio and profit factor high.\n\n\
+         ## ANSWER FORMAT\n\
+         Analyze the weaknesses, then return only the values you change. Keep parameter values \
+         within their min and max (and options), and keep their types: integers stay integers.\n\n\
+         ```json\n\
+         {{\n  \"parameters\": {{\"<parameter id>\": <value>}},\n  \
+         \"risk_management\": {{\"<setting>\": <value>}},\n  \
+         \"indicators\": [{{\"id\": \"<indicator id>\", \"parameters\": {{\"<name>\": <value>}}}}]\n}}\n\
+         ```",
+        strategy.name,
+        strategy.description,
+        pretty(&configuration(strategy)),
+        pretty(&metrics(performance)),
+        pretty(&Value::from(history)),
+    )
+}
+
+/// Optimizes a strategy's parameters with an LLM and backtests
+pub struct StrategyOptimizer<'a> {
+    repository: &'a StrategyRepository,
+    llm: LlmClient,
+    settings: OptimizationSettings,
+}
+
+impl<'a> StrategyOptimizer<'a> {
+    pub fn new(repository: &'a StrategyRepository, llm: LlmClient, settings: OptimizationSettings) -> Self {
+        Self { repository, llm, settings }
+    }
+
+    // Indicators are calculated from candles, since changed parameters have no stored values
+    async fn backtest(&self, strategy: &Strategy) -> Result<StrategyPerformance> {
+        let settings = &self.settings;
+        self.repository
+            .evaluator_for(&settings.symbol, &settings.engine_profile, settings.initial_capital, settings.start_date, settings.end_date)
+            .await?
+            .with_indicator_source(IndicatorSource::Calculate)
+            .backtest(strategy, &settings.symbol, &settings.interval, settings.start_date, settings.end_date)
+            .await
+    }
+
+    // Ask for changes to the best configuration and backtest them
+    async fn iterate(
+        &self,
+        best: &Strategy,
+        best_performance: &StrategyPerformance,
+        history: &[OptimizationIteration],
+    ) -> Result<(AppliedChanges, StrategyPerformance)> {
+        let answer = self.llm.complete(SYSTEM_PROMPT, &build_prompt(best, best_performance, history)).await?;
+        let changes = apply_changes(best, &parse_response(&answer)?)?;
+        if changes.applied.is_empty() {
+            return Err(Error::validation(format!("no applicable changes ({})", changes.rejected.join("; "))));
+        }
+
+        let performance = self.backtest(&changes.strategy).await?;
+        Ok((changes, performance))
+    }
+
+    /// Run the optimization; the strategy itself is not modified
+    pub async fn run(&self, strategy: &Strategy) -> Result<OptimizationReport> {
+        let mut best = strategy.clone();
+        let mut best_performance = self.backtest(strategy).await?;
+        let mut best_iteration = 0;
+        info!("Baseline of {}: expectancy {:.2}, return {:.2}%",
+              strategy.name, best_performance.expectancy, best_performance.total_return);
+
+        let mut iterations = vec![OptimizationIteration {
+            iteration: 0,
+            applied: Vec::new(),
+            rejected: Vec::new(),
+            performance: Some(best_performance.clone()),
+            error: None,
+            improved: false,
+        }];
+
+        for iteration in 1..=self.settings.max_iterations {
+            let mut record = OptimizationIteration {
+                iteration,
+                applied: Vec::new(),
+                rejected: Vec::new(),
+                performance: None,
+                error: None,
+                improved: false,
+            };
+
+            match self.iterate(&best, &best_performance, &iterations).await {
+                Ok((changes, performance)) => {
+                    record.improved = performance.expectancy > best_performance.expectancy;
+                    info!("Iteration {}: expectancy {:.2}, return {:.2}%{}", iteration,
+                          performance.expectancy, performance.total_return,
+                          if record.improved { " (new best)" } else { "" });
+                    if record.improved {
+                        best = changes.strategy;
+                        best_performance = performance.clone();
+                        best_iteration = iteration;
+                    }
+                    record.applied = changes.applied;
+                    record.rejected = changes.rejected;
+                    record.performance = Some(performance);
+                },
+                Err(e) => {
+                    warn!("Iteration {} failed: {}", iteration, e);
+                    record.error = Some(e.to_string());
+                },
+            }
+
+            iterations.push(record);
+        }
+
+        Ok(OptimizationReport {
+            settings: self.settings.clone(),
+            original: strategy.clone(),
+            best,
+            best_iteration,
+            iterations,
+        })
+    }
+}
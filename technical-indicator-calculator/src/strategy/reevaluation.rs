@@ -0,0 +1,271 @@
+// src/strategy/reevaluation.rs
+//
+// Scheduled re-evaluation of enabled strategies
+//
+// The worker periodically backtests every enabled strategy on each of its assets and
+// timeframes over the last `window_days` days. Each backtest is saved like any other and
+// recorded in strategy_reevaluations together with the thresholds it breached. A window
+// with fewer than `min_trades` trades is recorded but not judged. The webhook is notified
+// when a strategy/symbol/interval becomes degraded and when it recovers, not on every run.
+use crate::config::ReevaluationSettings;
+use crate::database::postgres::PostgresManager;
+use crate::error::Result;
+use crate::signals::webhook::WebhookNotifier;
+use crate::strategy::repository::StrategyRepository;
+use crate::strategy::schema::{Strategy, StrategyPerformance};
+use crate::strategy::watchlist;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use tracing::{info, warn};
+
+/// One re-evaluation of a strategy on a symbol and interval
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Reevaluation {
+    pub id: i32,
+    pub strategy_id: String,
+    pub symbol: String,
+    pub interval: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub backtest_id: Option<i32>,
+    pub total_trades: Option<i32>,
+    pub total_return: Option<f64>,
+    pub max_drawdown: Option<f64>,
+    pub win_rate: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub degraded: bool,
+    /// Thresholds breached, separated by "; "
+    pub reasons: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create the strategy_reevaluations table if it doesn't exist
+pub async fn init_table(pg: &PostgresManager) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS strategy_reevaluations (
+            id SERIAL PRIMARY KEY,
+            strategy_id VARCHAR NOT NULL,
+            symbol VARCHAR NOT NULL,
+            interval VARCHAR NOT NULL,
+            window_start TIMESTAMPTZ NOT NULL,
+            window_end TIMESTAMPTZ NOT NULL,
+            backtest_id INTEGER,
+            total_trades INTEGER,
+            total_return DOUBLE PRECISION,
+            max_drawdown DOUBLE PRECISION,
+            win_rate DOUBLE PRECISION,
+            profit_factor DOUBLE PRECISION,
+            degraded BOOLEAN NOT NULL DEFAULT FALSE,
+            reasons TEXT,
+            error TEXT,
+            created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_strategy_reevaluations_target
+         ON strategy_reevaluations(strategy_id, symbol, interval, created_at DESC)"
+    )
+    .execute(&pg.pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Thresholds a window's performance breaches; empty when it is healthy or has too few trades
+pub fn breaches(settings: &ReevaluationSettings, performance: &StrategyPerformance) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if performance.total_trades < settings.min_trades {
+        return reasons;
+    }
+
+    if performance.total_return < settings.min_total_return_percent {
+        reasons.push(format!("return {:.2}% below {:.2}%", performance.total_return, settings.min_total_return_percent));
+    }
+    if performance.max_drawdown > settings.max_drawdown_percent {
+        reasons.push(format!("drawdown {:.2}% above {:.2}%", performance.max_drawdown, settings.max_drawdown_percent));
+    }
+    if performance.win_rate < settings.min_win_rate_percent {
+        reasons.push(format!("win rate {:.2}% below {:.2}%", performance.win_rate, settings.min_win_rate_percent));
+    }
+    if performance.profit_factor < settings.min_profit_factor {
+        reasons.push(format!("profit factor {:.2} below {:.2}", performance.profit_factor, settings.min_profit_factor));
+    }
+
+    reasons
+}
+
+/// Latest re-evaluations, newest first, optionally of one strategy
+pub async fn recent_reevaluations(pg: &PostgresManager, strategy_id: Option<&str>, limit: i64) -> Result<Vec<Reevaluation>> {
+    let rows = sqlx::query_as::<_, Reevaluation>(
+        "SELECT * FROM strategy_reevaluations
+         WHERE $1::VARCHAR IS NULL OR strategy_id = $1
+         ORDER BY created_at DESC, id DESC
+         LIMIT $2"
+    )
+    .bind(strategy_id)
+    .bind(limit)
+    .fetch_all(&pg.pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Re-runs backtests of enabled strategies over a rolling window
+pub struct Reevaluator<'a> {
+    repository: &'a StrategyRepository,
+    settings: ReevaluationSettings,
+    notifier: Option<WebhookNotifier>,
+}
+
+impl<'a> Reevaluator<'a> {
+    pub fn new(repository: &'a StrategyRepository, settings: ReevaluationSettings, notifier: Option<WebhookNotifier>) -> Self {
+        Self { repository, settings, notifier }
+    }
+
+    /// Re-evaluate every asset and timeframe of all enabled strategies
+    pub async fn run_all(&self) -> Result<Vec<Reevaluation>> {
+        let strategies = self.repository.list_strategies(true).await?;
+        let mut results = Vec::new();
+
+        for strategy in &strategies {
+            results.extend(self.run(strategy).await?);
+        }
+
+        let degraded = results.iter().filter(|r| r.degraded).count();
+        info!("Re-evaluated {} strategies on {} symbol/intervals, {} degraded", strategies.len(), results.len(), degraded);
+        Ok(results)
+    }
+
+    /// Re-evaluate every asset and timeframe of one strategy
+    pub async fn run(&self, strategy: &Strategy) -> Result<Vec<Reevaluation>> {
+        let pg = self.repository.get_db_connection();
+        init_table(&pg).await?;
+
+        let window_end = Utc::now();
+        let window_start = window_end - Duration::days(self.settings.window_days as i64);
+        let mut results = Vec::new();
+
+        // Watchlists are resolved on every run, so newly listed symbols are picked up
+        for symbol in &watchlist::resolve_assets(&pg, &strategy.assets).await? {
+            for interval in &strategy.timeframes {
+                let backtest = self.backtest(strategy, symbol, interval, window_start, window_end).await;
+                let (performance, backtest_id, error) = match backtest {
+                    Ok((performance, backtest_id)) => (Some(performance), Some(backtest_id), None),
+                    Err(e) => {
+                        warn!("Re-evaluation of {} on {}:{} failed: {}", strategy.id, symbol, interval, e);
+                        (None, None, Some(e.to_string()))
+                    }
+                };
+                let reasons = performance.as_ref()
+                    .map(|performance| breaches(&self.settings, performance))
+                    .unwrap_or_default();
+
+                let previous = self.previous_state(&pg, &strategy.id, symbol, interval).await?;
+                let result = sqlx::query_as::<_, Reevaluation>(
+                    "INSERT INTO strategy_reevaluations
+                        (strategy_id, symbol, interval, window_start, window_end, backtest_id, total_trades,
+                         total_return, max_drawdown, win_rate, profit_factor, degraded, reasons, error)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+                     RETURNING *"
+                )
+                .bind(&strategy.id)
+                .bind(symbol)
+                .bind(interval)
+                .bind(window_start)
+                .bind(window_end)
+                .bind(backtest_id)
+                .bind(performance.as_ref().map(|p| p.total_trades))
+                .bind(performance.as_ref().map(|p| p.total_return))
+                .bind(performance.as_ref().map(|p| p.max_drawdown))
+                .bind(performance.as_ref().map(|p| p.win_rate))
+                .bind(performance.as_ref().map(|p| p.profit_factor))
+                .bind(!reasons.is_empty())
+                .bind((!reasons.is_empty()).then(|| reasons.join("; ")))
+                .bind(error)
+                .fetch_one(&pg.pool)
+                .await?;
+
+                // Failed backtests keep the previous state
+                if result.error.is_none() {
+                    self.notify_change(strategy, &result, previous).await;
+                }
+                results.push(result);
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn backtest(
+        &self,
+        strategy: &Strategy,
+        symbol: &str,
+        interval: &str,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> Result<(StrategyPerformance, i32)> {
+        let (start_date, end_date) = (Some(start_date), Some(end_date));
+        let evaluator = self.repository
+            .evaluator_for(symbol, &self.settings.engine_profile, self.settings.initial_capital, start_date, end_date)
+            .await?;
+        let performance = evaluator.backtest(strategy, symbol, interval, start_date, end_date).await?;
+
+        let backtest_id = self.repository.save_backtest_result(
+            &strategy.id,
+            symbol,
+            interval,
+            start_date,
+            end_date,
+            self.settings.initial_capital,
+            &performance,
+        ).await?;
+        self.repository.record_backtest_engine_settings(backtest_id, &evaluator).await?;
+
+        Ok((performance, backtest_id))
+    }
+
+    // Whether the last successful re-evaluation was degraded; None before the first
+    async fn previous_state(&self, pg: &PostgresManager, strategy_id: &str, symbol: &str, interval: &str) -> Result<Option<bool>> {
+        let degraded = sqlx::query_scalar::<_, bool>(
+            "SELECT degraded FROM strategy_reevaluations
+             WHERE strategy_id = $1 AND symbol = $2 AND interval = $3 AND error IS NULL
+             ORDER BY created_at DESC, id DESC
+             LIMIT 1"
+        )
+        .bind(strategy_id)
+        .bind(symbol)
+        .bind(interval)
+        .fetch_optional(&pg.pool)
+        .await?;
+
+        Ok(degraded)
+    }
+
+    async fn notify_change(&self, strategy: &Strategy, result: &Reevaluation, previous: Option<bool>) {
+        let message = match (previous.unwrap_or(false), result.degraded) {
+            (false, true) => format!(
+                "Strategy '{}' ({}) degraded on {}:{} over the last {} days: {}",
+                strategy.name, strategy.id, result.symbol, result.interval, self.settings.window_days,
+                result.reasons.as_deref().unwrap_or_default()
+            ),
+            (true, false) => format!(
+                "Strategy '{}' ({}) recovered on {}:{} with a {:.2}% return over the last {} days",
+                strategy.name, strategy.id, result.symbol, result.interval,
+                result.total_return.unwrap_or_default(), self.settings.window_days
+            ),
+            _ => return,
+        };
+
+        warn!("{}", message);
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.send(&message).await {
+                warn!("Failed to send re-evaluation notification: {}", e);
+            }
+        }
+    }
+}
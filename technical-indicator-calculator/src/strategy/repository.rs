@@ -1,16 +1,23 @@
 // src/strategy/repository.rs
 use crate::database::postgres::PostgresManager;
+use crate::database::strategy_queries::NewBacktestResult;
+use crate::error::{Error, Result};
+use crate::events::{record_event, EventKind};
+use crate::strategy::accounting::{ContractSpec, ContractType};
+use crate::strategy::editor::normalize_tag;
+use crate::strategy::engine_settings::EngineSettings;
+use crate::strategy::evaluator::StrategyEvaluator;
+use crate::strategy::listing::StrategyFilter;
 use crate::strategy::schema::{Strategy, StrategyPerformance};
+use crate::utils::resources::ResourceUsage;
 use crate::strategy::repository_helpers::{
-    load_strategy_indicators, load_strategy_rules, save_strategy_indicators, 
-    save_strategy_rules, parse_strategy_json
+    indicator_from_record, indicator_to_record, performance_from_record, rule_from_record,
+    rule_to_record, strategy_from_record, strategy_to_record
 };
-use anyhow::{Result, Context};
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
 use tracing::info;
 use uuid::Uuid;
-use sqlx::Row; // Add this import
 
 /// Repository for storing and retrieving strategies from the database
 pub struct StrategyRepository {
@@ -28,65 +35,94 @@ impl StrategyRepository {
         self.pg.clone()
     }
     
+    /// Evaluator for backtests on `symbol` with the named engine profile, the symbol's
+    /// contract specification and, for contracts with funding, its stored funding rates
+    pub async fn evaluator_for(
+        &self,
+        symbol: &str,
+        engine_profile: &str,
+        initial_capital: f64,
+        start_date: Option<DateTime<Utc>>,
+        end_date: Option<DateTime<Utc>>,
+    ) -> Result<StrategyEvaluator> {
+        let engine_settings = self.get_engine_settings(engine_profile).await?;
+        let contract = self.get_contract_spec(symbol).await?;
+        let funding_rates = if contract.apply_funding {
+            self.get_funding_rates(symbol, start_date, end_date).await?
+        } else {
+            Vec::new()
+        };
+        
+        Ok(StrategyEvaluator::new(self.pg.clone(), initial_capital)
+            .with_engine_settings(engine_settings)
+            .with_contract_spec(contract)
+            .with_funding_rates(funding_rates))
+    }
+    
     /// List all strategies
     pub async fn list_strategies(&self, enabled_only: bool) -> Result<Vec<Strategy>> {
         info!("Listing strategies (enabled_only: {})", enabled_only);
         
-        // Note the use of id::text to explicitly cast the UUID to text for the query
-        let rows = if enabled_only {
-            self.pg.execute_query(
-                "SELECT id::text, name, description, version, author, created_at, updated_at, 
-                        enabled, assets, timeframes, parameters, risk_management, metadata
-                 FROM strategies
-                 WHERE enabled = true
-                 ORDER BY name"
-            ).await?
-        } else {
-            self.pg.execute_query(
-                "SELECT id::text, name, description, version, author, created_at, updated_at, 
-                        enabled, assets, timeframes, parameters, risk_management, metadata
-                 FROM strategies
-                 ORDER BY name"
-            ).await?
-        };
+        let ids = self.pg.list_strategy_ids(enabled_only).await?;
         
-        let mut strategies = Vec::with_capacity(rows.len());
+        let mut strategies = Vec::with_capacity(ids.len());
         
-        for row in rows {
-            let id_str: String = row.get("id");
-            let strategy = self.get_strategy(&id_str).await?;
+        for id in ids {
+            let strategy = self.get_strategy(&id).await?;
             strategies.push(strategy);
         }
         
         Ok(strategies)
     }
     
+    /// List the strategies matching a filter, in the filter's order
+    pub async fn find_strategies(&self, filter: &StrategyFilter) -> Result<Vec<Strategy>> {
+        let ids = self.pg.find_strategy_ids(filter).await?;
+        
+        let mut strategies = Vec::with_capacity(ids.len());
+        for id in ids {
+            strategies.push(self.get_strategy(&id).await?);
+        }
+        
+        Ok(strategies)
+    }
+    
+    /// List the strategies with a tag, ordered by name
+    pub async fn find_strategies_by_tag(&self, tag: &str) -> Result<Vec<Strategy>> {
+        let filter = StrategyFilter {
+            tags: vec![normalize_tag(tag)?],
+            ..StrategyFilter::default()
+        };
+        
+        self.find_strategies(&filter).await
+    }
+    
+    /// List the tags in use with the number of strategies having each
+    pub async fn list_tags(&self) -> Result<Vec<(String, i64)>> {
+        self.pg.list_strategy_tags().await
+    }
+    
     /// Get a strategy by ID
     pub async fn get_strategy(&self, id: &str) -> Result<Strategy> {
         info!("Getting strategy with ID: {}", id);
         
         // First, get the base strategy data
-        let strategy_row = self.pg.query_opt_by_string(
-            "SELECT id::text, name, description, version, author, created_at, updated_at, 
-                    enabled, assets, timeframes, parameters, risk_management, metadata
-             FROM strategies
-             WHERE id = $1::uuid",
-            id
-        ).await?;
-        
-        let strategy_row = match strategy_row {
-            Some(row) => row,
-            None => return Err(anyhow::anyhow!("Strategy not found with ID: {}", id)),
-        };
+        let record = self.pg.get_strategy_record(id).await?
+            .ok_or_else(|| Error::not_found(format!("Strategy with ID {}", id)))?;
         
-        // Parse the strategy data from the database row
-        let mut strategy = parse_strategy_json(strategy_row)?;
+        let mut strategy = strategy_from_record(record)?;
         
         // Get indicators for this strategy
-        strategy.indicators = load_strategy_indicators(&self.pg, id).await?;
+        strategy.indicators = self.pg.get_strategy_indicator_records(&strategy.id).await?
+            .into_iter()
+            .map(indicator_from_record)
+            .collect();
         
         // Get rules for this strategy
-        strategy.rules = load_strategy_rules(&self.pg, id).await?;
+        strategy.rules = self.pg.get_strategy_rule_records(&strategy.id).await?
+            .into_iter()
+            .map(rule_from_record)
+            .collect::<Result<Vec<_>>>()?;
         
         Ok(strategy)
     }
@@ -95,100 +131,38 @@ impl StrategyRepository {
     pub async fn save_strategy(&self, strategy: &Strategy) -> Result<()> {
         info!("Saving strategy: {} ({})", strategy.name, strategy.id);
         
-        // Start a transaction
-        let mut tx = self.pg.begin_transaction().await?;
-        
-        // Convert UUID string to Uuid object
-        let id = Uuid::parse_str(&strategy.id)
-            .context("Invalid UUID format for strategy ID")?;
-            
-        // Convert Uuid to String for database query
-        let id_str = id.to_string();
-        
-        // Serialize JSON fields
-        let assets_json = serde_json::to_value(&strategy.assets)?;
-        let timeframes_json = serde_json::to_value(&strategy.timeframes)?;
-        let parameters_json = serde_json::to_value(&strategy.parameters)?;
-        let risk_management_json = serde_json::to_value(&strategy.risk_management)?;
-        let metadata_json = serde_json::to_value(&strategy.metadata)?;
-        
-        // Check if strategy exists - using UUID type explicitly
-        let exists = self.pg.query_opt_by_string(
-            "SELECT 1 FROM strategies WHERE id = $1::uuid",
-            &id_str
-        ).await?.is_some();
-        
-        if exists {
-            // Update existing strategy - using UUID type for the id parameter
-            self.pg.execute_tx_insert_strategy(
-                &mut tx,
-                "UPDATE strategies 
-                 SET name = $2, description = $3, version = $4, author = $5, 
-                     updated_at = $7, enabled = $8, assets = $9, timeframes = $10, 
-                     parameters = $11, risk_management = $12, metadata = $13
-                 WHERE id = $1::uuid",
-                &id_str,
-                &strategy.name,
-                &strategy.description,
-                &strategy.version,
-                &strategy.author,
-                strategy.created_at,
-                Utc::now(),
-                strategy.enabled,
-                assets_json,
-                timeframes_json,
-                parameters_json,
-                risk_management_json,
-                metadata_json
-            ).await?;
-            
-            // Delete existing indicators and rules - using UUID type
-            self.pg.execute_tx_command_by_string(
-                &mut tx,
-                "DELETE FROM strategy_indicators WHERE strategy_id = $1::uuid",
-                &id_str
-            ).await?;
-                
-            self.pg.execute_tx_command_by_string(
-                &mut tx,
-                "DELETE FROM strategy_rules WHERE strategy_id = $1::uuid",
-                &id_str
-            ).await?;
-        } else {
-            // Insert new strategy - using UUID type for the id parameter
-            self.pg.execute_tx_insert_strategy(
-                &mut tx,
-                "INSERT INTO strategies 
-                 (id, name, description, version, author, created_at, updated_at, 
-                  enabled, assets, timeframes, parameters, risk_management, metadata)
-                 VALUES ($1::uuid, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
-                &id_str,
-                &strategy.name,
-                &strategy.description,
-                &strategy.version,
-                &strategy.author,
-                strategy.created_at,
-                Utc::now(),
-                strategy.enabled,
-                assets_json,
-                timeframes_json,
-                parameters_json,
-                risk_management_json,
-                metadata_json
-            ).await?;
-        }
+        let record = strategy_to_record(strategy)?;
+        let indicators: Vec<_> = strategy.indicators.iter().map(indicator_to_record).collect();
+        let rules = strategy.rules.iter()
+            .map(rule_to_record)
+            .collect::<Result<Vec<_>>>()?;
         
-        // Save indicators and rules
-        save_strategy_indicators(&mut tx, &self.pg, id, &strategy.indicators).await?;
-        save_strategy_rules(&mut tx, &self.pg, id, &strategy.rules).await?;
-        
-        // Commit the transaction
-        self.pg.commit_transaction(tx).await?;
+        // Strategy, indicators and rules are replaced in one transaction
+        self.pg.save_strategy_records(&record, &indicators, &rules).await?;
         
         info!("Strategy saved successfully");
         Ok(())
     }
     
+    /// Give a strategy a new ID when a strategy of another namespace already has its ID,
+    /// so the same file can be imported into several namespaces
+    pub async fn assign_free_id(&self, strategy: &mut Strategy) -> Result<()> {
+        if let Some(namespace) = self.pg.get_strategy_namespace(&strategy.id).await? {
+            if namespace != self.pg.namespace() {
+                let id = Uuid::new_v4().to_string();
+                info!("Strategy ID {} is used in namespace {}, importing as {}", strategy.id, namespace, id);
+                strategy.id = id;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// The namespace strategies and backtest results are read from and written to
+    pub fn namespace(&self) -> &str {
+        self.pg.namespace()
+    }
+    
     /// Save backtest results
     pub async fn save_backtest_result(
         &self, 
@@ -202,6 +176,8 @@ impl StrategyRepository {
     ) -> Result<i32> {
         info!("Saving backtest result for strategy {} on {}:{}", strategy_id, symbol, interval);
         
+        self.pg.init_backtest_metric_columns().await?;
+        
         // Calculate final capital
         let final_capital = initial_capital * (1.0 + performance.total_return / 100.0);
         
@@ -212,47 +188,36 @@ impl StrategyRepository {
         let start = start_date.unwrap_or_else(|| Utc::now() - chrono::Duration::days(30));
         let end = end_date.unwrap_or_else(|| Utc::now());
         
-        // Insert the backtest result - using UUID type for strategy_id
-        let backtest_id = self.pg.execute_save_backtest_result(
-            "INSERT INTO strategy_backtest_results
-             (strategy_id, symbol, interval, start_date, end_date, initial_capital, 
-              final_capital, total_trades, winning_trades, losing_trades, win_rate,
-              max_drawdown, profit_factor, sharpe_ratio, total_return, annualized_return,
-              max_consecutive_wins, max_consecutive_losses, avg_profit_per_win, 
-              avg_loss_per_loss, avg_win_holding_period, avg_loss_holding_period,
-              expectancy, parameters_snapshot, created_at)
-             VALUES
-             ($1::uuid, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, 
-              $18, $19, $20, $21, $22, $23, $24, $25)
-             RETURNING id",
+        let backtest_id = self.pg.insert_backtest_result(&NewBacktestResult {
             strategy_id,
             symbol,
             interval,
-            start,
-            end,
+            start_date: start,
+            end_date: end,
             initial_capital,
             final_capital,
-            performance.total_trades,
-            performance.winning_trades,
-            performance.losing_trades,
-            performance.win_rate as f32,
-            performance.max_drawdown as f32,
-            performance.profit_factor as f32,
-            performance.sharpe_ratio as f32,
-            performance.total_return as f32,
-            performance.annualized_return as f32,
-            performance.max_consecutive_wins,
-            performance.max_consecutive_losses,
-            performance.avg_profit_per_win as f32,
-            performance.avg_loss_per_loss as f32,
-            performance.avg_win_holding_period as f32,
-            performance.avg_loss_holding_period as f32,
-            performance.expectancy as f32,
-            parameters_json,
-            Utc::now()
-        ).await?;
+            performance,
+            parameters_snapshot: parameters_json,
+        }).await?;
         
         info!("Backtest result saved with ID: {}", backtest_id);
+        record_event(
+            &self.pg,
+            EventKind::BacktestRun,
+            Some(strategy_id),
+            &format!("Backtest {} on {}:{} returned {:.2}% over {} trades", 
+                     backtest_id, symbol, interval, performance.total_return, performance.total_trades),
+            serde_json::json!({
+                "backtest_id": backtest_id,
+                "symbol": symbol,
+                "interval": interval,
+                "start_date": start,
+                "end_date": end,
+                "initial_capital": initial_capital,
+                "total_return": performance.total_return,
+                "total_trades": performance.total_trades,
+            }),
+        ).await;
         
         Ok(backtest_id)
     }
@@ -265,52 +230,95 @@ impl StrategyRepository {
     ) -> Result<Vec<(i32, String, String, StrategyPerformance)>> {
         info!("Getting recent backtest results for strategy: {}", strategy_id);
         
-        // Query recent backtest results - using UUID type
-        let rows = self.pg.query_by_string_and_i64(
-            "SELECT id, symbol, interval, 
-                    total_trades, winning_trades, losing_trades, win_rate,
-                    max_drawdown, profit_factor, sharpe_ratio, total_return, 
-                    annualized_return, max_consecutive_wins, max_consecutive_losses, 
-                    avg_profit_per_win, avg_loss_per_loss, avg_win_holding_period, 
-                    avg_loss_holding_period, expectancy
-             FROM strategy_backtest_results
-             WHERE strategy_id = $1::uuid
-             ORDER BY created_at DESC
-             LIMIT $2",
-            strategy_id,
-            limit
-        ).await?;
-        
-        let mut results = Vec::with_capacity(rows.len());
-        
-        for row in rows {
-            let id: i32 = row.get("id");
-            let symbol: String = row.get("symbol");
-            let interval: String = row.get("interval");
-            
-            // Create StrategyPerformance from row data
-            let performance = StrategyPerformance {
-                total_trades: row.get("total_trades"),
-                winning_trades: row.get("winning_trades"),
-                losing_trades: row.get("losing_trades"),
-                win_rate: row.get::<f32, _>("win_rate") as f64,
-                max_drawdown: row.get::<f32, _>("max_drawdown") as f64,
-                profit_factor: row.get::<f32, _>("profit_factor") as f64,
-                sharpe_ratio: row.get::<f32, _>("sharpe_ratio") as f64,
-                total_return: row.get::<f32, _>("total_return") as f64,
-                annualized_return: row.get::<f32, _>("annualized_return") as f64,
-                max_consecutive_wins: row.get("max_consecutive_wins"),
-                max_consecutive_losses: row.get("max_consecutive_losses"),
-                avg_profit_per_win: row.get::<f32, _>("avg_profit_per_win") as f64,
-                avg_loss_per_loss: row.get::<f32, _>("avg_loss_per_loss") as f64,
-                avg_win_holding_period: row.get::<f32, _>("avg_win_holding_period") as f64,
-                avg_loss_holding_period: row.get::<f32, _>("avg_loss_holding_period") as f64,
-                expectancy: row.get::<f32, _>("expectancy") as f64,
-            };
-            
-            results.push((id, symbol, interval, performance));
-        }
+        self.pg.init_backtest_metric_columns().await?;
+        let records = self.pg.get_recent_backtest_records(strategy_id, limit).await?;
+        
+        let results = records.into_iter()
+            .map(|record| {
+                let performance = performance_from_record(&record);
+                (record.id, record.symbol, record.interval, performance)
+            })
+            .collect();
         
         Ok(results)
     }
+    
+    /// Get an engine settings profile by name, falling back to the built-in profiles
+    pub async fn get_engine_settings(&self, name: &str) -> Result<EngineSettings> {
+        if let Some(settings_json) = self.pg.get_engine_settings_json(name).await? {
+            return serde_json::from_value(settings_json)
+                .map_err(|e| Error::config(format!("Invalid engine settings stored for profile {}: {}", name, e)));
+        }
+        
+        EngineSettings::builtin(name)
+            .ok_or_else(|| Error::not_found(format!("Engine settings profile {}", name)))
+    }
+    
+    /// List stored engine settings profiles together with built-in profiles that are not overridden
+    pub async fn list_engine_settings(&self) -> Result<Vec<EngineSettings>> {
+        let mut profiles = self.pg.list_engine_settings_json().await?
+            .into_iter()
+            .map(serde_json::from_value::<EngineSettings>)
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        for builtin in EngineSettings::builtin_profiles() {
+            if !profiles.iter().any(|p| p.name == builtin.name) {
+                profiles.push(builtin);
+            }
+        }
+        
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(profiles)
+    }
+    
+    /// Save an engine settings profile, replacing any profile with the same name
+    pub async fn save_engine_settings(&self, settings: &EngineSettings) -> Result<()> {
+        info!("Saving engine settings profile: {}", settings.name);
+        
+        self.pg.upsert_engine_settings(&settings.name, &settings.description, &serde_json::to_value(settings)?).await
+    }
+    
+    /// Record the engine settings and seed of the evaluator that produced a backtest result
+    pub async fn record_backtest_engine_settings(&self, backtest_id: i32, evaluator: &StrategyEvaluator) -> Result<()> {
+        let settings = evaluator.engine_settings();
+        self.pg.update_backtest_engine_settings(backtest_id, &settings.name, &serde_json::to_value(settings)?, evaluator.seed()).await
+    }
+    
+    /// Get the contract specification of a symbol, defaulting to a linear contract
+    pub async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        self.pg.init_symbols_table().await?;
+        
+        let record = match self.pg.get_symbol_contract(symbol).await? {
+            Some(record) => record,
+            None => return Ok(ContractSpec::linear(symbol)),
+        };
+        
+        let contract_type = ContractType::parse(&record.contract_type)
+            .ok_or_else(|| Error::validation(format!("Unknown contract type for {}: {}", symbol, record.contract_type)))?;
+        
+        Ok(ContractSpec {
+            symbol: record.symbol,
+            contract_type,
+            multiplier: record.contract_multiplier,
+            quanto_rate: record.quanto_rate,
+            settlement_asset: record.settlement_asset,
+            apply_funding: record.apply_funding,
+            short_borrow_rate: record.short_borrow_rate,
+        })
+    }
+    
+    /// Get the stored funding rates of a symbol as (funding time, rate) pairs in ascending order
+    pub async fn get_funding_rates(&self, symbol: &str, start: Option<DateTime<Utc>>, 
+                                   end: Option<DateTime<Utc>>) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        self.pg.init_funding_rates_table().await?;
+        
+        let rates = self.pg.get_funding_rates(symbol, start, end).await?;
+        Ok(rates.into_iter().map(|rate| (rate.funding_time, rate.funding_rate)).collect())
+    }
+    
+    /// Record the resources used by a backtest
+    pub async fn record_backtest_resource_usage(&self, backtest_id: i32, usage: &ResourceUsage) -> Result<()> {
+        self.pg.init_backtest_resource_columns().await?;
+        self.pg.update_backtest_resource_usage(backtest_id, usage).await
+    }
 }
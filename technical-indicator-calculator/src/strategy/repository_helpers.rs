@@ -1,212 +1,154 @@
 // src/strategy/repository_helpers.rs
-use crate::database::postgres::PostgresManager;
+use crate::database::models::{
+    BacktestResultRecord, StrategyIndicatorRecord, StrategyRecord, StrategyRuleRecord,
+};
+use crate::error::{Error, Result};
 use crate::strategy::schema::{
-    Strategy, StrategyIndicator, StrategyRule, StrategyParameter,
+    Strategy, StrategyIndicator, StrategyRule, StrategyParameter, StrategyPerformance,
     RiskManagement, CompositeCondition, RuleAction
 };
-use anyhow::{Result, Context};
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use std::collections::HashMap;
-use sqlx::postgres::PgRow;
-use sqlx::Row; // Add this import
 use uuid::Uuid;
 
-/// Parse a strategy from a database row
-pub fn parse_strategy_json(row: PgRow) -> Result<Strategy> {
-    // Parse the strategy data
-    let uuid_str: String = row.get("id");
-    let uuid = Uuid::parse_str(&uuid_str)?;
-    
-    let name: String = row.get("name");
-    let description: String = row.get("description");
-    let version: String = row.get("version");
-    let author: String = row.get("author");
-    let created_at: DateTime<Utc> = row.get("created_at");
-    let updated_at: DateTime<Utc> = row.get("updated_at");
-    let enabled: bool = row.get("enabled");
-    
-    // Parse JSON fields
-    let assets_json: serde_json::Value = row.get("assets");
-    let timeframes_json: serde_json::Value = row.get("timeframes");
-    let parameters_json: serde_json::Value = row.get("parameters");
-    let risk_management_json: serde_json::Value = row.get("risk_management");
-    let metadata_json: Option<serde_json::Value> = row.try_get("metadata").unwrap_or(None);
-    
+/// Build a strategy from its stored header (indicators and rules are loaded separately)
+pub fn strategy_from_record(record: StrategyRecord) -> Result<Strategy> {
+    let uuid = Uuid::parse_str(&record.id)
+        .map_err(|e| Error::validation(format!("Invalid UUID format for strategy ID {}: {}", record.id, e)))?;
+
     // Convert JSON fields to proper types
-    let assets: Vec<String> = serde_json::from_value(assets_json)?;
-    let timeframes: Vec<String> = serde_json::from_value(timeframes_json)?;
-    let parameters: HashMap<String, StrategyParameter> = serde_json::from_value(parameters_json)?;
-    let risk_management: RiskManagement = serde_json::from_value(risk_management_json)?;
-    let metadata: HashMap<String, serde_json::Value> = match metadata_json {
+    let assets: Vec<String> = serde_json::from_value(record.assets)?;
+    let timeframes: Vec<String> = serde_json::from_value(record.timeframes)?;
+    let tags: Vec<String> = serde_json::from_value(record.tags)?;
+    let parameters: HashMap<String, StrategyParameter> = serde_json::from_value(record.parameters)?;
+    let risk_management: RiskManagement = serde_json::from_value(record.risk_management)?;
+    let metadata: HashMap<String, serde_json::Value> = match record.metadata {
         Some(json) => serde_json::from_value(json)?,
         None => HashMap::new(),
     };
-    
-    // Create the strategy object (without indicators and rules, which will be loaded separately)
-    let strategy = Strategy {
+
+    Ok(Strategy {
         id: uuid.to_string(),
-        name,
-        description,
-        version,
-        author,
-        created_at,
-        updated_at,
-        enabled,
+        name: record.name,
+        description: record.description.unwrap_or_default(),
+        version: record.version,
+        author: record.author.unwrap_or_default(),
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+        enabled: record.enabled,
         assets,
         timeframes,
+        tags,
         indicators: Vec::new(), // Will be filled separately
         rules: Vec::new(),      // Will be filled separately
         parameters,
         risk_management,
         performance: None,      // Will be filled separately if needed
         metadata,
-    };
-    
-    Ok(strategy)
+    })
 }
 
-/// Load indicators for a strategy
-pub async fn load_strategy_indicators(pg: &PostgresManager, strategy_id: &str) -> Result<Vec<StrategyIndicator>> {
-    let strategy_uuid = Uuid::parse_str(strategy_id)
-        .context("Invalid UUID format for strategy ID")?;
-    
-    // Convert Uuid to String for database query
-    let strategy_uuid_str = strategy_uuid.to_string();
-        
-    let rows = pg.query_by_string(
-        "SELECT indicator_id, indicator_type, indicator_name, parameters, description
-         FROM strategy_indicators
-         WHERE strategy_id = $1::uuid
-         ORDER BY indicator_id",
-        &strategy_uuid_str
-    ).await?;
-    
-    let mut indicators = Vec::with_capacity(rows.len());
-    
-    for row in rows {
-        let indicator_id: String = row.get("indicator_id");
-        let indicator_type: String = row.get("indicator_type");
-        let indicator_name: String = row.get("indicator_name");
-        let parameters_json: serde_json::Value = row.get("parameters");
-        let description: String = row.get("description");
-        
-        indicators.push(StrategyIndicator {
-            id: indicator_id,
-            indicator_type,
-            indicator_name,
-            parameters: parameters_json,
-            description,
-        });
-    }
-    
-    Ok(indicators)
+/// Convert a strategy header to its stored form, stamping the update time
+pub fn strategy_to_record(strategy: &Strategy) -> Result<StrategyRecord> {
+    let id = Uuid::parse_str(&strategy.id)
+        .map_err(|e| Error::validation(format!("Invalid UUID format for strategy ID {}: {}", strategy.id, e)))?;
+
+    Ok(StrategyRecord {
+        id: id.to_string(),
+        name: strategy.name.clone(),
+        description: Some(strategy.description.clone()),
+        version: strategy.version.clone(),
+        author: Some(strategy.author.clone()),
+        created_at: strategy.created_at,
+        updated_at: Utc::now(),
+        enabled: strategy.enabled,
+        assets: serde_json::to_value(&strategy.assets)?,
+        timeframes: serde_json::to_value(&strategy.timeframes)?,
+        tags: serde_json::to_value(&strategy.tags)?,
+        parameters: serde_json::to_value(&strategy.parameters)?,
+        risk_management: serde_json::to_value(&strategy.risk_management)?,
+        metadata: Some(serde_json::to_value(&strategy.metadata)?),
+    })
 }
 
-/// Load rules for a strategy
-pub async fn load_strategy_rules(pg: &PostgresManager, strategy_id: &str) -> Result<Vec<StrategyRule>> {
-    let strategy_uuid = Uuid::parse_str(strategy_id)
-        .context("Invalid UUID format for strategy ID")?;
-    
-    // Convert Uuid to String for database query
-    let strategy_uuid_str = strategy_uuid.to_string();
-        
-    let rows = pg.query_by_string(
-        "SELECT rule_id, name, condition, action, priority, description
-         FROM strategy_rules
-         WHERE strategy_id = $1::uuid
-         ORDER BY priority",
-        &strategy_uuid_str
-    ).await?;
-    
-    let mut rules = Vec::with_capacity(rows.len());
-    
-    for row in rows {
-        let rule_id: String = row.get("rule_id");
-        let name: String = row.get("name");
-        let condition_json: serde_json::Value = row.get("condition");
-        let action_json: serde_json::Value = row.get("action");
-        let priority: i32 = row.get("priority");
-        let description: String = row.get("description");
-        
-        // Parse JSON fields
-        let condition: CompositeCondition = serde_json::from_value(condition_json)?;
-        let action: RuleAction = serde_json::from_value(action_json)?;
-        
-        rules.push(StrategyRule {
-            id: rule_id,
-            name,
-            condition,
-            action,
-            priority,
-            description,
-        });
+/// Build a strategy indicator from its stored form
+pub fn indicator_from_record(record: StrategyIndicatorRecord) -> StrategyIndicator {
+    StrategyIndicator {
+        id: record.indicator_id,
+        indicator_type: record.indicator_type,
+        indicator_name: record.indicator_name,
+        parameters: record.parameters,
+        description: record.description.unwrap_or_default(),
     }
-    
-    Ok(rules)
 }
 
-/// Save indicators for a strategy
-pub async fn save_strategy_indicators<'a>(
-    tx: &mut sqlx::Transaction<'a, sqlx::Postgres>,
-    pg: &PostgresManager,
-    strategy_id: Uuid,
-    indicators: &[StrategyIndicator]
-) -> Result<()> {
-    // Convert Uuid to String for database query
-    let strategy_id_str = strategy_id.to_string();
-    
-    for indicator in indicators {
-        // Serialize parameters
-        let parameters_json = serde_json::to_value(&indicator.parameters)?;
-        
-        pg.execute_tx_insert_indicator(
-            tx,
-            "INSERT INTO strategy_indicators
-             (strategy_id, indicator_id, indicator_type, indicator_name, parameters, description, created_at)
-             VALUES ($1::uuid, $2, $3, $4, $5, $6, $7)",
-            &strategy_id_str,
-            &indicator.id,
-            &indicator.indicator_type,
-            &indicator.indicator_name,
-            parameters_json,
-            &indicator.description,
-            Utc::now()
-        ).await?;
+/// Convert a strategy indicator to its stored form
+pub fn indicator_to_record(indicator: &StrategyIndicator) -> StrategyIndicatorRecord {
+    StrategyIndicatorRecord {
+        indicator_id: indicator.id.clone(),
+        indicator_type: indicator.indicator_type.clone(),
+        indicator_name: indicator.indicator_name.clone(),
+        parameters: indicator.parameters.clone(),
+        description: Some(indicator.description.clone()),
     }
-    
-    Ok(())
 }
 
-/// Save rules for a strategy
-pub async fn save_strategy_rules<'a>(
-    tx: &mut sqlx::Transaction<'a, sqlx::Postgres>,
-    pg: &PostgresManager,
-    strategy_id: Uuid,
-    rules: &[StrategyRule]
-) -> Result<()> {
-    // Convert Uuid to String for database query
-    let strategy_id_str = strategy_id.to_string();
-    
-    for rule in rules {
-        // Serialize condition and action
-        let condition_json = serde_json::to_value(&rule.condition)?;
-        let action_json = serde_json::to_value(&rule.action)?;
-        
-        pg.execute_tx_insert_rule(
-            tx,
-            "INSERT INTO strategy_rules
-             (strategy_id, rule_id, name, condition, action, priority, description, created_at)
-             VALUES ($1::uuid, $2, $3, $4, $5, $6, $7, $8)",
-            &strategy_id_str,
-            &rule.id,
-            &rule.name,
-            condition_json,
-            action_json,
-            rule.priority,
-            &rule.description,
-            Utc::now()
-        ).await?;
+/// Build a strategy rule from its stored form
+pub fn rule_from_record(record: StrategyRuleRecord) -> Result<StrategyRule> {
+    let condition: CompositeCondition = serde_json::from_value(record.condition)?;
+    let action: RuleAction = serde_json::from_value(record.action)?;
+
+    Ok(StrategyRule {
+        id: record.rule_id,
+        name: record.name,
+        condition,
+        action,
+        priority: record.priority,
+        description: record.description.unwrap_or_default(),
+    })
+}
+
+/// Convert a strategy rule to its stored form
+pub fn rule_to_record(rule: &StrategyRule) -> Result<StrategyRuleRecord> {
+    Ok(StrategyRuleRecord {
+        rule_id: rule.id.clone(),
+        name: rule.name.clone(),
+        condition: serde_json::to_value(&rule.condition)?,
+        action: serde_json::to_value(&rule.action)?,
+        priority: rule.priority,
+        description: Some(rule.description.clone()),
+    })
+}
+
+/// Build backtest performance from a stored summary; missing metrics read as zero
+pub fn performance_from_record(record: &BacktestResultRecord) -> StrategyPerformance {
+    StrategyPerformance {
+        total_trades: record.total_trades,
+        winning_trades: record.winning_trades,
+        losing_trades: record.losing_trades,
+        win_rate: record.win_rate.unwrap_or_default(),
+        max_drawdown: record.max_drawdown.unwrap_or_default(),
+        profit_factor: record.profit_factor.unwrap_or_default(),
+        sharpe_ratio: record.sharpe_ratio.unwrap_or_default(),
+        total_return: record.total_return.unwrap_or_default(),
+        annualized_return: record.annualized_return.unwrap_or_default(),
+        max_consecutive_wins: record.max_consecutive_wins.unwrap_or_default(),
+        max_consecutive_losses: record.max_consecutive_losses.unwrap_or_default(),
+        avg_profit_per_win: record.avg_profit_per_win.unwrap_or_default(),
+        avg_loss_per_loss: record.avg_loss_per_loss.unwrap_or_default(),
+        avg_win_holding_period: record.avg_win_holding_period.unwrap_or_default(),
+        avg_loss_holding_period: record.avg_loss_holding_period.unwrap_or_default(),
+        expectancy: record.expectancy.unwrap_or_default(),
+        benchmark_return: record.benchmark_return.unwrap_or_default(),
+        excess_return: record.excess_return.unwrap_or_default(),
+        alpha: record.alpha.unwrap_or_default(),
+        beta: record.beta.unwrap_or_default(),
+        sortino_ratio: record.sortino_ratio.unwrap_or_default(),
+        calmar_ratio: record.calmar_ratio.unwrap_or_default(),
+        value_at_risk: record.value_at_risk.unwrap_or_default(),
+        expected_shortfall: record.expected_shortfall.unwrap_or_default(),
+        exposure_percent: record.exposure_percent.unwrap_or_default(),
+        turnover: record.turnover.unwrap_or_default(),
+        unfilled_orders: record.unfilled_orders.unwrap_or_default(),
     }
-    
-    Ok(())
 }
@@ -0,0 +1,205 @@
+// src/strategy/rules.rs
+//
+// Evaluation of strategy rule conditions
+//
+// Rules are evaluated at the close of a candle of an aligned frame, the same way in
+// backtests and for live signals. Operands are read at that candle, or `offset` candles
+// before it; crossovers also compare the values of the candle before. An operand without
+// a value (indicator warmup, bars_since_entry while flat) makes its condition false.
+use crate::database::models::AlignedFrame;
+use crate::error::{Error, Result};
+use crate::strategy::schema::{
+    ComparisonOperator, CompositeCondition, Condition, LogicalOperator, Strategy, StrategyParameter,
+    StrategyRule, ValueSource,
+};
+use crate::strategy::script::Script;
+use chrono::{DateTime, Utc};
+
+/// A condition with its expressions compiled
+enum PreparedCondition<'a> {
+    Simple(&'a Condition),
+    Compound(&'a LogicalOperator, Vec<PreparedCondition<'a>>),
+    Expression(Script),
+}
+
+/// The rules of a strategy ready for evaluation, in priority order
+pub struct RuleSet<'a> {
+    strategy: &'a Strategy,
+    rules: Vec<(&'a StrategyRule, PreparedCondition<'a>)>,
+}
+
+impl<'a> RuleSet<'a> {
+    /// Prepare the rules of a strategy, compiling their expressions once
+    pub fn new(strategy: &'a Strategy) -> Result<Self> {
+        let mut rules = strategy.rules.iter()
+            .map(|rule| {
+                let condition = prepare(strategy, &rule.condition)
+                    .map_err(|e| Error::validation(format!("Rule {}: {}", rule.id, e)))?;
+                Ok((rule, condition))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Lower numbers first; rules of equal priority keep their order in the strategy
+        rules.sort_by_key(|(rule, _)| rule.priority);
+
+        Ok(Self { strategy, rules })
+    }
+
+    /// Rules whose conditions hold at the close of candle `idx`, in priority order
+    ///
+    /// `entry_time` is when the open position was entered, None while flat.
+    pub fn triggered(&self, frame: &AlignedFrame, idx: usize, entry_time: Option<DateTime<Utc>>) -> Vec<&'a StrategyRule> {
+        let context = Context { strategy: self.strategy, frame, entry_time };
+
+        self.rules.iter()
+            .filter(|(_, condition)| context.holds(condition, idx))
+            .map(|(rule, _)| *rule)
+            .collect()
+    }
+}
+
+// Compile the expressions of a condition tree
+fn prepare<'a>(strategy: &Strategy, condition: &'a CompositeCondition) -> Result<PreparedCondition<'a>> {
+    Ok(match condition {
+        CompositeCondition::Simple { condition } => PreparedCondition::Simple(condition),
+        CompositeCondition::Compound { operator, conditions } => PreparedCondition::Compound(
+            operator,
+            conditions.iter().map(|condition| prepare(strategy, condition)).collect::<Result<_>>()?,
+        ),
+        CompositeCondition::Expression { expression } => {
+            PreparedCondition::Expression(Script::for_condition(strategy, expression)?)
+        },
+    })
+}
+
+// What conditions are evaluated against
+struct Context<'a> {
+    strategy: &'a Strategy,
+    frame: &'a AlignedFrame,
+    entry_time: Option<DateTime<Utc>>,
+}
+
+impl Context<'_> {
+    fn holds(&self, condition: &PreparedCondition, idx: usize) -> bool {
+        match condition {
+            PreparedCondition::Simple(condition) => self.compare(condition, idx),
+            PreparedCondition::Compound(LogicalOperator::And, conditions) => {
+                conditions.iter().all(|condition| self.holds(condition, idx))
+            },
+            PreparedCondition::Compound(LogicalOperator::Or, conditions) => {
+                conditions.iter().any(|condition| self.holds(condition, idx))
+            },
+            PreparedCondition::Expression(script) => {
+                script.condition_at(self.strategy, self.frame, idx).unwrap_or(false)
+            },
+        }
+    }
+
+    fn compare(&self, condition: &Condition, idx: usize) -> bool {
+        // Text operands (pattern names and types, string parameters) compare for equality only
+        if let (Some(left), Some(right)) = (self.text(&condition.left, idx), self.text(&condition.right, idx)) {
+            return match condition.operator {
+                ComparisonOperator::Equal => left == right,
+                ComparisonOperator::NotEqual => left != right,
+                _ => false,
+            };
+        }
+
+        let values = |idx: usize| Some((self.value(&condition.left, idx)?, self.value(&condition.right, idx)?));
+        let Some((left, right)) = values(idx) else {
+            return false;
+        };
+
+        match condition.operator {
+            ComparisonOperator::Equal => left == right,
+            ComparisonOperator::NotEqual => left != right,
+            ComparisonOperator::GreaterThan => left > right,
+            ComparisonOperator::GreaterThanOrEqual => left >= right,
+            ComparisonOperator::LessThan => left < right,
+            ComparisonOperator::LessThanOrEqual => left <= right,
+            ComparisonOperator::CrossesAbove | ComparisonOperator::CrossesBelow => {
+                let Some((previous_left, previous_right)) = idx.checked_sub(1).and_then(values) else {
+                    return false;
+                };
+
+                if matches!(condition.operator, ComparisonOperator::CrossesAbove) {
+                    previous_left <= previous_right && left > right
+                } else {
+                    previous_left >= previous_right && left < right
+                }
+            },
+        }
+    }
+
+    fn value(&self, source: &ValueSource, idx: usize) -> Option<f64> {
+        operand_value_at(source, self.strategy, self.frame, idx, self.entry_time)
+    }
+
+    // Text value of an operand at candle `idx`; None for numeric operands
+    fn text(&self, source: &ValueSource, idx: usize) -> Option<&str> {
+        match source {
+            ValueSource::Indicator { indicator_id, property, offset } => {
+                let idx = idx.checked_sub(usize::try_from(offset.unwrap_or(0)).ok()?)?;
+                self.frame.value_at(indicator_id, idx)?.get(property.as_deref()?)?.as_str()
+            },
+            ValueSource::Parameter { parameter_id } => match self.strategy.parameters.get(parameter_id)? {
+                StrategyParameter::String { value, .. } => Some(value),
+                _ => None,
+            },
+            ValueSource::Constant { value } => value.as_str(),
+            _ => None,
+        }
+    }
+}
+
+/// Numeric value of a rule operand at candle `idx`
+///
+/// Offsets count candles back from `idx`. Time sources use the candle's open time in the
+/// symbol's session timezone; bars_since_entry counts from `entry_time` and has no value
+/// while flat.
+pub fn operand_value_at(source: &ValueSource, strategy: &Strategy, frame: &AlignedFrame,
+                        idx: usize, entry_time: Option<DateTime<Utc>>) -> Option<f64> {
+    let candles = &frame.candles;
+    let offset_idx = |offset: Option<i32>| idx.checked_sub(usize::try_from(offset.unwrap_or(0)).ok()?);
+
+    match source {
+        ValueSource::Indicator { indicator_id, property, offset } => {
+            frame.numeric_at(indicator_id, property.as_deref(), offset_idx(*offset)?)
+        },
+        ValueSource::Price { property, offset } => {
+            let idx = offset_idx(*offset)?;
+            let series = match property.as_str() {
+                "open" => &candles.open,
+                "high" => &candles.high,
+                "low" => &candles.low,
+                "close" => &candles.close,
+                "volume" => &candles.volume,
+                _ => return None,
+            };
+            series.get(idx).copied()
+        },
+        ValueSource::Parameter { parameter_id } => match strategy.parameters.get(parameter_id)? {
+            StrategyParameter::Integer { value, .. } => Some(*value as f64),
+            StrategyParameter::Float { value, .. } => Some(*value),
+            _ => None,
+        },
+        ValueSource::Constant { value } => value.as_f64(),
+        ValueSource::TimeOfDay => {
+            let time = candles.open_time.get(idx)?;
+            Some(candles.session.time_of_day(*time))
+        },
+        ValueSource::DayOfWeek => {
+            let time = candles.open_time.get(idx)?;
+            Some(candles.session.day_of_week(*time))
+        },
+        ValueSource::InSession => {
+            let time = candles.open_time.get(idx)?;
+            Some(if candles.session.is_open(*time) { 1.0 } else { 0.0 })
+        },
+        ValueSource::BarsSinceEntry => {
+            let entry_time = entry_time?;
+            let entry_idx = candles.open_time.partition_point(|time| *time < entry_time);
+            idx.checked_sub(entry_idx).map(|bars| bars as f64)
+        },
+    }
+}
@@ -26,6 +26,9 @@ pub struct Strategy {
     pub assets: Vec<String>,
     /// Timeframes this strategy is designed for
     pub timeframes: Vec<String>,
+    /// Tags grouping the strategy into families, e.g. "momentum"
+    #[serde(default)]
+    pub tags: Vec<String>,
     /// List of indicator configurations used by this strategy
     pub indicators: Vec<StrategyIndicator>,
     /// Rules that define when to enter or exit positions
@@ -49,7 +52,8 @@ pub struct StrategyIndicator {
     pub id: String,
     /// Type of indicator (must match a valid indicator_type)
     pub indicator_type: String,
-    /// Name of the indicator (must match a valid indicator_name)
+    /// Name of the indicator (must match a valid indicator_name, or "SCRIPT" for a
+    /// formula given in parameters.expression)
     pub indicator_name: String,
     /// Parameters for the indicator
     pub parameters: serde_json::Value,
@@ -110,6 +114,19 @@ pub enum ValueSource {
     Constant {
         value: serde_json::Value,
     },
+    /// Time of the candle's open in the symbol's session timezone (UTC for crypto) as
+    /// decimal hours (8.5 = 08:30)
+    #[serde(rename = "time_of_day")]
+    TimeOfDay,
+    /// Weekday of the candle's open in the symbol's session timezone, 1 = Monday to 7 = Sunday
+    #[serde(rename = "day_of_week")]
+    DayOfWeek,
+    /// 1 when the candle opens within the symbol's trading session, 0 outside it
+    #[serde(rename = "in_session")]
+    InSession,
+    /// Candles since the open position was entered; no value while flat
+    #[serde(rename = "bars_since_entry")]
+    BarsSinceEntry,
 }
 
 /// Represents a condition in a rule
@@ -131,6 +148,10 @@ pub enum CompositeCondition {
         operator: LogicalOperator,
         conditions: Vec<CompositeCondition>,
     },
+    /// Boolean rhai expression over prices, indicators and parameters,
+    /// e.g. "(close - ema20) / atr14 > 1.5" (see strategy::script)
+    #[serde(rename = "expression")]
+    Expression { expression: String },
 }
 
 /// Possible actions for a strategy rule
@@ -141,11 +162,17 @@ pub enum RuleAction {
     EnterLong {
         #[serde(default)]
         size_percent: Option<f64>,
+        /// Pending order to enter with instead of filling at the candle close
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        order: Option<EntryOrder>,
     },
     #[serde(rename = "enter_short")]
     EnterShort {
         #[serde(default)]
         size_percent: Option<f64>,
+        /// Pending order to enter with instead of filling at the candle close
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        order: Option<EntryOrder>,
     },
     #[serde(rename = "exit_long")]
     ExitLong {
@@ -157,6 +184,20 @@ pub enum RuleAction {
         #[serde(default)]
         size_percent: Option<f64>,
     },
+    /// Add a tranche to the open position without exceeding a maximum total size
+    #[serde(rename = "scale_in")]
+    ScaleIn {
+        /// Size of the added tranche as percentage of capital
+        size_percent: f64,
+        /// Largest total position size as percentage of capital (defaults to max_total_risk)
+        #[serde(default)]
+        max_position_percent: Option<f64>,
+    },
+    /// Close a percentage of the open position, oldest tranches first
+    #[serde(rename = "scale_out")]
+    ScaleOut {
+        percent: f64,
+    },
     #[serde(rename = "set_stop_loss")]
     SetStopLoss {
         #[serde(default)]
@@ -173,6 +214,36 @@ pub enum RuleAction {
     },
 }
 
+/// Type of a pending entry order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderType {
+    /// Fill at the order price or better
+    #[serde(rename = "limit")]
+    Limit,
+    /// Fill at market once the price trades through the order price
+    #[serde(rename = "stop")]
+    Stop,
+    /// Become a limit order once the price trades through the stop price
+    #[serde(rename = "stop_limit")]
+    StopLimit,
+}
+
+/// Pending entry order placed by a rule; prices are relative to the price the rule executes
+/// at, the signal candle's close or, with execution latency, the open of a later candle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryOrder {
+    pub order_type: OrderType,
+    /// Limit price (limit orders) or stop price (stop and stop_limit orders)
+    /// as a signed percentage from the execution price, e.g. -1.0 for 1% below
+    pub price_offset_percent: f64,
+    /// Limit price of a stop_limit order as a signed percentage from the execution price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_offset_percent: Option<f64>,
+    /// Cancel the order if it hasn't filled within this many candles; unset keeps it until filled (GTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_after_bars: Option<u32>,
+}
+
 /// Represents a single rule within a strategy
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategyRule {
@@ -243,6 +314,35 @@ pub struct RiskManagement {
     pub trailing_stop_activation: Option<f64>,
     /// Trailing stop percentage
     pub trailing_stop_percent: Option<f64>,
+    /// Stop loss level resolved at entry time (overrides default_stop_loss)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_loss_level: Option<StopLevel>,
+    /// Take profit level resolved at entry time (overrides default_take_profit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub take_profit_level: Option<StopLevel>,
+}
+
+/// Defines how a stop loss or take profit price is derived when a position is opened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum StopLevel {
+    /// Fixed distance from the entry price in percent
+    #[serde(rename = "percent")]
+    Percent {
+        value: f64,
+    },
+    /// Distance from the entry price as a multiple of an ATR indicator value
+    #[serde(rename = "atr_multiple")]
+    AtrMultiple {
+        indicator_id: String,
+        multiple: f64,
+    },
+    /// Absolute price taken from an indicator value (e.g. the lower Bollinger Band)
+    #[serde(rename = "indicator")]
+    Indicator {
+        indicator_id: String,
+        property: Option<String>,
+    },
 }
 
 /// Performance metrics for a strategy during backtesting
@@ -280,6 +380,39 @@ pub struct StrategyPerformance {
     pub avg_loss_holding_period: f64,
     /// Expectancy (average profit/loss per trade)
     pub expectancy: f64,
+    /// Buy-and-hold return of the symbol over the same period (percentage)
+    #[serde(default)]
+    pub benchmark_return: f64,
+    /// Total return minus the buy-and-hold return (percentage points)
+    #[serde(default)]
+    pub excess_return: f64,
+    /// Annualized return not explained by exposure to buy-and-hold (percentage)
+    #[serde(default)]
+    pub alpha: f64,
+    /// Sensitivity of the strategy's returns to buy-and-hold returns
+    #[serde(default)]
+    pub beta: f64,
+    /// Annualized mean return over downside deviation
+    #[serde(default)]
+    pub sortino_ratio: f64,
+    /// Annualized return over maximum drawdown
+    #[serde(default)]
+    pub calmar_ratio: f64,
+    /// 95% value at risk of trade returns (percentage, positive for losses)
+    #[serde(default)]
+    pub value_at_risk: f64,
+    /// Average trade return beyond the value at risk (percentage, positive for losses)
+    #[serde(default)]
+    pub expected_shortfall: f64,
+    /// Share of the backtest period with an open position (percentage)
+    #[serde(default)]
+    pub exposure_percent: f64,
+    /// Traded notional, entries and exits, as a multiple of capital
+    #[serde(default)]
+    pub turnover: f64,
+    /// Pending entry orders that expired or were still open at the end
+    #[serde(default)]
+    pub unfilled_orders: i32,
 }
 
 /// Create a new strategy with default values
@@ -296,6 +429,7 @@ impl Default for Strategy {
             enabled: true,
             assets: Vec::new(),
             timeframes: Vec::new(),
+            tags: Vec::new(),
             indicators: Vec::new(),
             rules: Vec::new(),
             parameters: HashMap::new(),
@@ -317,6 +451,8 @@ impl Default for RiskManagement {
             use_trailing_stop: false,
             trailing_stop_activation: None,
             trailing_stop_percent: None,
+            stop_loss_level: None,
+            take_profit_level: None,
         }
     }
 }
@@ -0,0 +1,237 @@
+// src/strategy/script.rs
+//
+// Sandboxed rhai expressions for script indicators and rule conditions
+//
+// A strategy can compute an indicator from a formula (indicator_name "SCRIPT" with the
+// formula in parameters.expression) or use an expression as a rule condition, e.g.
+// "(close - ema20) / atr14 > 1.5". Expressions see the candle's prices, the values of the
+// strategy's indicators by id (multi-valued ones as maps, e.g. macd.signal) and numeric
+// strategy parameters. Only single expressions are accepted: no statements, loops,
+// function definitions or modules, and evaluation is bounded in operations and depth.
+use crate::database::models::{AlignedFrame, IndicatorSeries};
+use crate::error::{Error, Result};
+use crate::strategy::schema::{CompositeCondition, Strategy, StrategyIndicator, StrategyParameter};
+use rhai::packages::{ArithmeticPackage, BasicMathPackage, LogicPackage, Package};
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use serde_json::Value;
+use tracing::trace;
+
+/// indicator_name of indicators computed from an expression
+pub const SCRIPT_INDICATOR: &str = "SCRIPT";
+
+// Limits of the sandbox
+const MAX_SOURCE_LEN: usize = 1_000;
+const MAX_OPERATIONS: u64 = 10_000;
+const MAX_EXPR_DEPTH: usize = 32;
+const MAX_STRING_SIZE: usize = 256;
+
+const PRICE_NAMES: [&str; 5] = ["open", "high", "low", "close", "volume"];
+
+/// Whether an indicator is computed from an expression instead of TA-Lib
+pub fn is_script_indicator(indicator: &StrategyIndicator) -> bool {
+    indicator.indicator_name.eq_ignore_ascii_case(SCRIPT_INDICATOR)
+}
+
+/// Formula of a script indicator
+pub fn indicator_expression(indicator: &StrategyIndicator) -> Result<&str> {
+    indicator.parameters.get("expression")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::validation(format!(
+            "Script indicator {} needs a string 'expression' parameter", indicator.id
+        )))
+}
+
+/// Engine with arithmetic, comparison and math functions only
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new_raw();
+    engine.register_global_module(ArithmeticPackage::new().as_shared_module());
+    engine.register_global_module(LogicPackage::new().as_shared_module());
+    engine.register_global_module(BasicMathPackage::new().as_shared_module());
+
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_allow_looping(false);
+    engine.set_allow_anonymous_fn(false);
+    // Unknown names are rejected when compiling instead of failing on every candle
+    engine.set_strict_variables(true);
+    engine
+}
+
+/// Whether an id can be referenced by name in an expression
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names an expression may use: prices, the given indicators and numeric parameters
+fn names<'a>(strategy: &'a Strategy, indicators: &'a [StrategyIndicator]) -> Vec<&'a str> {
+    let parameters = strategy.parameters.iter()
+        .filter(|(_, parameter)| matches!(parameter, StrategyParameter::Integer { .. } | StrategyParameter::Float { .. }))
+        .map(|(id, _)| id.as_str());
+
+    PRICE_NAMES.into_iter()
+        .chain(indicators.iter().map(|indicator| indicator.id.as_str()))
+        .chain(parameters)
+        .filter(|name| is_identifier(name))
+        .collect()
+}
+
+/// A compiled expression
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+}
+
+impl Script {
+    /// Compile an expression that may only refer to `names`
+    pub fn compile(source: &str, names: &[&str]) -> Result<Self> {
+        if source.len() > MAX_SOURCE_LEN {
+            return Err(Error::validation(format!(
+                "Expression is {} characters long, at most {} are allowed", source.len(), MAX_SOURCE_LEN
+            )));
+        }
+
+        let engine = sandboxed_engine();
+        let mut scope = Scope::new();
+        for name in names {
+            scope.push_dynamic(*name, Dynamic::UNIT);
+        }
+
+        let ast = engine.compile_expression_with_scope(&scope, source)
+            .map_err(|e| Error::validation(format!("Invalid expression '{}': {}", source, e)))?;
+
+        Ok(Self { engine, ast })
+    }
+
+    /// Compile the formula of the script indicator at `position` in the strategy's indicator
+    /// list; it may refer to the indicators listed before it
+    pub fn for_indicator(strategy: &Strategy, position: usize) -> Result<Self> {
+        let indicator = &strategy.indicators[position];
+        Self::compile(indicator_expression(indicator)?, &names(strategy, &strategy.indicators[..position]))
+    }
+
+    /// Compile an expression used as a rule condition; it may refer to every indicator
+    pub fn for_condition(strategy: &Strategy, source: &str) -> Result<Self> {
+        Self::compile(source, &names(strategy, &strategy.indicators))
+    }
+
+    /// Evaluate at candle `idx`; None while a referenced value is missing or on runtime errors
+    fn eval_at(&self, strategy: &Strategy, frame: &AlignedFrame, idx: usize) -> Option<Dynamic> {
+        let mut scope = scope_at(strategy, frame, idx)?;
+        match self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                trace!("Expression has no value at candle {}: {}", idx, e);
+                None
+            },
+        }
+    }
+
+    /// Numeric result at candle `idx`
+    pub fn number_at(&self, strategy: &Strategy, frame: &AlignedFrame, idx: usize) -> Option<f64> {
+        let value = self.eval_at(strategy, frame, idx)?;
+        value.as_float().ok()
+            .or_else(|| value.as_int().ok().map(|v| v as f64))
+            .filter(|v| v.is_finite())
+    }
+
+    /// Boolean result at candle `idx`
+    pub fn condition_at(&self, strategy: &Strategy, frame: &AlignedFrame, idx: usize) -> Option<bool> {
+        self.eval_at(strategy, frame, idx)?.as_bool().ok()
+    }
+}
+
+/// Convert a stored indicator value; None for nulls and arrays
+fn to_dynamic(value: &Value) -> Option<Dynamic> {
+    match value {
+        Value::Number(number) => number.as_f64().map(Dynamic::from_float),
+        Value::Bool(value) => Some(Dynamic::from_bool(*value)),
+        Value::String(value) => Some(Dynamic::from(value.clone())),
+        Value::Object(object) => {
+            let map: Map = object.iter()
+                .filter_map(|(key, value)| Some((key.as_str().into(), to_dynamic(value)?)))
+                .collect();
+            Some(Dynamic::from_map(map))
+        },
+        Value::Null | Value::Array(_) => None,
+    }
+}
+
+/// Variables of candle `idx`; indicators without a value at this candle are left out
+fn scope_at(strategy: &Strategy, frame: &AlignedFrame, idx: usize) -> Option<Scope<'static>> {
+    let candles = &frame.candles;
+    let mut scope = Scope::new();
+
+    scope.push("open", *candles.open.get(idx)?);
+    scope.push("high", *candles.high.get(idx)?);
+    scope.push("low", *candles.low.get(idx)?);
+    scope.push("close", *candles.close.get(idx)?);
+    scope.push("volume", *candles.volume.get(idx)?);
+
+    for indicator in &strategy.indicators {
+        if let Some(value) = frame.value_at(&indicator.id, idx).and_then(to_dynamic) {
+            scope.push_dynamic(indicator.id.clone(), value);
+        }
+    }
+
+    for (id, parameter) in &strategy.parameters {
+        let value = match parameter {
+            StrategyParameter::Integer { value, .. } => *value as f64,
+            StrategyParameter::Float { value, .. } => *value,
+            _ => continue,
+        };
+        scope.push(id.clone(), value);
+    }
+
+    Some(scope)
+}
+
+/// Calculate the script indicator at `position` over the frame
+///
+/// Indicators listed before it must already be in the frame.
+pub fn indicator_series(strategy: &Strategy, position: usize, frame: &AlignedFrame) -> Result<IndicatorSeries> {
+    let indicator = &strategy.indicators[position];
+    let script = Script::for_indicator(strategy, position)?;
+    let candles = &frame.candles;
+
+    let mut series = IndicatorSeries::new(&candles.symbol, &candles.interval, &indicator.indicator_name, &indicator.parameters);
+    for idx in 0..frame.len() {
+        if let Some(value) = script.number_at(strategy, frame, idx) {
+            series.push(candles.open_time[idx], Value::from(value));
+        }
+    }
+
+    Ok(series)
+}
+
+/// Compile every expression of a strategy, failing on the first invalid one
+pub fn validate_scripts(strategy: &Strategy) -> Result<()> {
+    for (position, indicator) in strategy.indicators.iter().enumerate() {
+        if is_script_indicator(indicator) {
+            Script::for_indicator(strategy, position)
+                .map_err(|e| Error::validation(format!("Script indicator {}: {}", indicator.id, e)))?;
+        }
+    }
+
+    for rule in &strategy.rules {
+        for source in condition_expressions(&rule.condition) {
+            Script::for_condition(strategy, source)
+                .map_err(|e| Error::validation(format!("Rule {}: {}", rule.id, e)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Expressions used in a condition tree
+pub fn condition_expressions(condition: &CompositeCondition) -> Vec<&str> {
+    match condition {
+        CompositeCondition::Simple { .. } => Vec::new(),
+        CompositeCondition::Compound { operator: _, conditions } => {
+            conditions.iter().flat_map(condition_expressions).collect()
+        },
+        CompositeCondition::Expression { expression } => vec![expression.as_str()],
+    }
+}
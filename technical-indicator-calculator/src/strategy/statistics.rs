@@ -0,0 +1,134 @@
+// src/strategy/statistics.rs
+//
+// Statistics indicators that need more than the strategy's own candles
+//
+// ZSCORE and LINEARREG with an `indicator` parameter are applied to an indicator listed
+// before them (optionally one of its properties, e.g. {"indicator": "macd", "property":
+// "histogram"}). CORREL and BETA compare the strategy's closes with the closes of the
+// `benchmark` symbol on the same interval, both as candle-to-candle returns; CORREL uses
+// prices instead with "use_returns": false. These are calculated during the backtest,
+// after the indicators listed before them, and never stored.
+use crate::database::models::{AlignedFrame, CandleData, IndicatorSeries};
+use crate::error::{Error, Result};
+use crate::indicators::cross_symbol::aligned_closes;
+use crate::indicators::statistics::{self, LINEARREG, ZSCORE};
+use crate::strategy::schema::{Strategy, StrategyIndicator};
+use serde_json::Value;
+
+/// Rolling correlation with another symbol
+pub const CORREL: &str = "CORREL";
+
+/// Rolling beta on another symbol
+pub const BETA: &str = "BETA";
+
+/// Whether an indicator is calculated from the loaded frame instead of candles alone
+pub fn needs_frame(indicator: &StrategyIndicator) -> bool {
+    match indicator.indicator_name.to_uppercase().as_str() {
+        CORREL | BETA => true,
+        LINEARREG | ZSCORE => indicator.parameters.get("indicator").is_some(),
+        _ => false,
+    }
+}
+
+/// Symbol a CORREL or BETA indicator compares with; None for other indicators
+pub fn benchmark_symbol(indicator: &StrategyIndicator) -> Result<Option<&str>> {
+    match indicator.indicator_name.to_uppercase().as_str() {
+        CORREL | BETA => indicator.parameters.get("benchmark")
+            .and_then(Value::as_str)
+            .map(Some)
+            .ok_or_else(|| Error::validation(format!(
+                "{} indicator {} needs a string 'benchmark' symbol parameter",
+                indicator.indicator_name, indicator.id
+            ))),
+        _ => Ok(None),
+    }
+}
+
+/// Check the parameters of the frame statistic at `position`
+pub fn validate(strategy: &Strategy, position: usize) -> Result<()> {
+    let indicator = &strategy.indicators[position];
+    benchmark_symbol(indicator)?;
+
+    let default_period = if indicator.indicator_name.eq_ignore_ascii_case(LINEARREG) { 14 } else { 20 };
+    statistics::period_param(&indicator.parameters, default_period)?;
+
+    if let Some(source) = indicator.parameters.get("indicator") {
+        let source = source.as_str().ok_or_else(|| Error::validation(format!(
+            "Indicator {}: 'indicator' must be the id of an indicator", indicator.id
+        )))?;
+        if !strategy.indicators[..position].iter().any(|earlier| earlier.id == source) {
+            return Err(Error::validation(format!(
+                "Indicator {} refers to {}, which is not listed before it", indicator.id, source
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Calculate the frame statistic at `position`
+///
+/// Indicators listed before it must already be in the frame; CORREL and BETA need the
+/// candles of their benchmark symbol.
+pub fn indicator_series(
+    strategy: &Strategy,
+    position: usize,
+    frame: &AlignedFrame,
+    benchmark: Option<&CandleData>,
+) -> Result<IndicatorSeries> {
+    validate(strategy, position)?;
+    let indicator = &strategy.indicators[position];
+    let parameters = &indicator.parameters;
+    let candles = &frame.candles;
+    let closes: Vec<Option<f64>> = candles.close.iter().map(|close| Some(*close)).collect();
+
+    let name = indicator.indicator_name.to_uppercase();
+    let values: Vec<(usize, Value)> = match name.as_str() {
+        CORREL | BETA => {
+            let benchmark = benchmark.ok_or_else(|| Error::validation(format!(
+                "{} indicator {} needs the benchmark candles", name, indicator.id
+            )))?;
+            let benchmark_closes = aligned_closes(candles, benchmark);
+            let use_returns = name == BETA || parameters.get("use_returns").and_then(Value::as_bool).unwrap_or(true);
+            let (asset, benchmark) = if use_returns {
+                (statistics::returns(&closes), statistics::returns(&benchmark_closes))
+            } else {
+                (closes, benchmark_closes)
+            };
+
+            let period = statistics::period_param(parameters, 20)?;
+            let results = if name == BETA {
+                statistics::beta(&asset, &benchmark, period)
+            } else {
+                statistics::correlation(&asset, &benchmark, period)
+            };
+            results.into_iter().map(|(idx, value)| (idx, Value::from(value))).collect()
+        },
+        _ => {
+            let source = parameters.get("indicator").and_then(Value::as_str).unwrap_or_default();
+            let property = parameters.get("property").and_then(Value::as_str);
+            let values: Vec<Option<f64>> = (0..frame.len())
+                .map(|idx| frame.numeric_at(source, property, idx).filter(|v| v.is_finite()))
+                .collect();
+
+            if name == LINEARREG {
+                statistics::linear_regression(&values, statistics::period_param(parameters, 14)?)
+                    .into_iter()
+                    .map(|(idx, regression)| (idx, regression.to_output().to_value()))
+                    .collect()
+            } else {
+                statistics::zscore(&values, statistics::period_param(parameters, 20)?)
+                    .into_iter()
+                    .map(|(idx, value)| (idx, Value::from(value)))
+                    .collect()
+            }
+        },
+    };
+
+    let mut series = IndicatorSeries::new(&candles.symbol, &candles.interval, &indicator.indicator_name, parameters);
+    for (idx, value) in values {
+        series.push(candles.open_time[idx], value);
+    }
+
+    Ok(series)
+}
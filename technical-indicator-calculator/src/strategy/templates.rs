@@ -0,0 +1,222 @@
+// src/strategy/templates.rs
+use crate::strategy::import_export::create_new_strategy;
+use crate::strategy::schema::{
+    ComparisonOperator, CompositeCondition, Condition, LogicalOperator, RuleAction,
+    Strategy, StrategyIndicator, StrategyParameter, StrategyRule, ValueSource,
+};
+use clap::ValueEnum;
+use serde_json::json;
+
+/// Built-in strategy templates for scaffolding new strategies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StrategyTemplate {
+    /// Buy oversold RSI, sell overbought RSI
+    RsiReversal,
+    /// Follow MACD crossovers in the direction of the long-term EMA
+    MacdTrend,
+    /// Enter on a close above the upper Bollinger Band
+    BbandsBreakout,
+}
+
+impl StrategyTemplate {
+    /// Default strategy name for the template
+    pub fn default_name(&self) -> &'static str {
+        match self {
+            StrategyTemplate::RsiReversal => "RSI Reversal",
+            StrategyTemplate::MacdTrend => "MACD Trend",
+            StrategyTemplate::BbandsBreakout => "Bollinger Bands Breakout",
+        }
+    }
+}
+
+/// Generate a complete strategy from a template
+pub fn build_strategy_from_template(template: StrategyTemplate, name: Option<&str>) -> Strategy {
+    let name = name.unwrap_or(template.default_name());
+
+    match template {
+        StrategyTemplate::RsiReversal => rsi_reversal(name),
+        StrategyTemplate::MacdTrend => macd_trend(name),
+        StrategyTemplate::BbandsBreakout => bbands_breakout(name),
+    }
+}
+
+/// Create a simple condition wrapped as a composite condition
+fn simple(left: ValueSource, operator: ComparisonOperator, right: ValueSource) -> CompositeCondition {
+    CompositeCondition::Simple {
+        condition: Condition { left, operator, right },
+    }
+}
+
+/// Reference an indicator output
+fn indicator(indicator_id: &str, property: Option<&str>) -> ValueSource {
+    ValueSource::Indicator {
+        indicator_id: indicator_id.to_string(),
+        property: property.map(|p| p.to_string()),
+        offset: None,
+    }
+}
+
+/// Reference a price field
+fn price(property: &str) -> ValueSource {
+    ValueSource::Price {
+        property: property.to_string(),
+        offset: None,
+    }
+}
+
+/// Reference a strategy parameter
+fn parameter(parameter_id: &str) -> ValueSource {
+    ValueSource::Parameter {
+        parameter_id: parameter_id.to_string(),
+    }
+}
+
+/// Create a rule
+fn rule(id: &str, name: &str, condition: CompositeCondition, action: RuleAction, priority: i32, description: &str) -> StrategyRule {
+    StrategyRule {
+        id: id.to_string(),
+        name: name.to_string(),
+        condition,
+        action,
+        priority,
+        description: description.to_string(),
+    }
+}
+
+fn rsi_reversal(name: &str) -> Strategy {
+    let mut strategy = create_new_strategy(
+        name,
+        "Mean-reversion strategy that buys when RSI leaves oversold territory and sells when it leaves overbought territory",
+    );
+
+    strategy.indicators = vec![StrategyIndicator {
+        id: "rsi".to_string(),
+        indicator_type: "oscillator".to_string(),
+        indicator_name: "RSI".to_string(),
+        parameters: json!({"period": 14}),
+        description: "14-period RSI used to detect oversold and overbought conditions".to_string(),
+    }];
+
+    strategy.parameters.insert("oversold".to_string(), StrategyParameter::Float {
+        value: 30.0,
+        min: 10.0,
+        max: 40.0,
+        step: Some(1.0),
+        description: "RSI level considered oversold".to_string(),
+    });
+    strategy.parameters.insert("overbought".to_string(), StrategyParameter::Float {
+        value: 70.0,
+        min: 60.0,
+        max: 90.0,
+        step: Some(1.0),
+        description: "RSI level considered overbought".to_string(),
+    });
+
+    strategy.rules = vec![
+        rule(
+            "enter_long",
+            "RSI leaves oversold",
+            simple(indicator("rsi", None), ComparisonOperator::CrossesAbove, parameter("oversold")),
+            RuleAction::EnterLong { size_percent: None, order: None },
+            1,
+            "Enter long when RSI crosses back above the oversold level",
+        ),
+        rule(
+            "exit_long",
+            "RSI leaves overbought",
+            simple(indicator("rsi", None), ComparisonOperator::CrossesBelow, parameter("overbought")),
+            RuleAction::ExitLong { size_percent: None },
+            0,
+            "Exit long when RSI crosses back below the overbought level",
+        ),
+    ];
+
+    strategy
+}
+
+fn macd_trend(name: &str) -> Strategy {
+    let mut strategy = create_new_strategy(
+        name,
+        "Trend-following strategy that trades MACD signal-line crossovers in the direction of the 200 EMA",
+    );
+
+    strategy.indicators = vec![
+        StrategyIndicator {
+            id: "macd".to_string(),
+            indicator_type: "oscillator".to_string(),
+            indicator_name: "MACD".to_string(),
+            parameters: json!({"fast_period": 12, "slow_period": 26, "signal_period": 9}),
+            description: "Standard MACD used for entry and exit timing".to_string(),
+        },
+        StrategyIndicator {
+            id: "trend_ema".to_string(),
+            indicator_type: "overlap".to_string(),
+            indicator_name: "EMA".to_string(),
+            parameters: json!({"period": 200}),
+            description: "Long-term EMA used as a trend filter".to_string(),
+        },
+    ];
+
+    strategy.rules = vec![
+        rule(
+            "enter_long",
+            "Bullish MACD crossover in uptrend",
+            CompositeCondition::Compound {
+                operator: LogicalOperator::And,
+                conditions: vec![
+                    simple(indicator("macd", Some("macd")), ComparisonOperator::CrossesAbove, indicator("macd", Some("signal"))),
+                    simple(price("close"), ComparisonOperator::GreaterThan, indicator("trend_ema", None)),
+                ],
+            },
+            RuleAction::EnterLong { size_percent: None, order: None },
+            1,
+            "Enter long when MACD crosses above its signal line while price is above the 200 EMA",
+        ),
+        rule(
+            "exit_long",
+            "Bearish MACD crossover",
+            simple(indicator("macd", Some("macd")), ComparisonOperator::CrossesBelow, indicator("macd", Some("signal"))),
+            RuleAction::ExitLong { size_percent: None },
+            0,
+            "Exit long when MACD crosses below its signal line",
+        ),
+    ];
+
+    strategy
+}
+
+fn bbands_breakout(name: &str) -> Strategy {
+    let mut strategy = create_new_strategy(
+        name,
+        "Volatility breakout strategy that buys closes above the upper Bollinger Band and exits at the middle band",
+    );
+
+    strategy.indicators = vec![StrategyIndicator {
+        id: "bbands".to_string(),
+        indicator_type: "overlap".to_string(),
+        indicator_name: "BBANDS".to_string(),
+        parameters: json!({"period": 20, "deviation_up": 2.0, "deviation_down": 2.0}),
+        description: "20-period Bollinger Bands with 2 standard deviations".to_string(),
+    }];
+
+    strategy.rules = vec![
+        rule(
+            "enter_long",
+            "Close above upper band",
+            simple(price("close"), ComparisonOperator::CrossesAbove, indicator("bbands", Some("upper"))),
+            RuleAction::EnterLong { size_percent: None, order: None },
+            1,
+            "Enter long when the close breaks above the upper band",
+        ),
+        rule(
+            "exit_long",
+            "Close below middle band",
+            simple(price("close"), ComparisonOperator::CrossesBelow, indicator("bbands", Some("middle"))),
+            RuleAction::ExitLong { size_percent: None },
+            0,
+            "Exit long when the close falls back below the middle band",
+        ),
+    ];
+
+    strategy
+}
@@ -1,8 +1,14 @@
 // src/strategy/validator.rs
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+use crate::indicators::{plugin, transform::{self, Transform}};
+use crate::strategy::script::{self, Script};
+use crate::strategy::statistics;
+use crate::strategy::watchlist::{self, Watchlist};
 use crate::strategy::schema::{
-    Strategy, StrategyIndicator, RiskManagement, CompositeCondition, Condition, ValueSource
+    Strategy, StrategyIndicator, StrategyParameter, RiskManagement, CompositeCondition, Condition, 
+    ComparisonOperator, ValueSource, StopLevel, RuleAction
 };
-use anyhow::{Result, anyhow};
 use std::collections::HashSet;
 
 /// Validate a complete strategy to ensure it meets all requirements
@@ -21,10 +27,109 @@ pub fn validate_strategy(strategy: &Strategy) -> Result<ValidationResult> {
     // Validate risk management
     validate_risk_management(&strategy.risk_management, &mut result);
     
+    // Validate indicator-based stop levels
+    validate_stop_levels(strategy, &mut result);
+    
     // Return validation result
     Ok(result)
 }
 
+/// Validate a strategy and check that the database has data for all of its indicators
+pub async fn validate_strategy_with_coverage(strategy: &Strategy, pg: &PostgresManager) -> Result<ValidationResult> {
+    let mut result = validate_strategy(strategy)?;
+    
+    validate_data_coverage(strategy, pg, &mut result).await?;
+    
+    Ok(result)
+}
+
+/// Warn about indicators without an enabled indicator_config or calculated data
+/// for any of the strategy's assets and timeframes
+pub async fn validate_data_coverage(
+    strategy: &Strategy, 
+    pg: &PostgresManager, 
+    result: &mut ValidationResult
+) -> Result<()> {
+    // A watchlist without symbols has no data to check
+    let symbols = match watchlist::resolve_assets(pg, &strategy.assets).await {
+        Ok(symbols) => symbols,
+        Err(e) => {
+            result.add_warning(e.to_string());
+            strategy.assets.iter().filter(|asset| !watchlist::is_watchlist(asset)).cloned().collect()
+        }
+    };
+    
+    for symbol in &symbols {
+        for interval in &strategy.timeframes {
+            validate_data_coverage_for(strategy, pg, symbol, interval, result).await?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Warn about indicators without an enabled indicator_config or calculated data
+/// for a single symbol and interval
+pub async fn validate_data_coverage_for(
+    strategy: &Strategy, 
+    pg: &PostgresManager, 
+    symbol: &str, 
+    interval: &str, 
+    result: &mut ValidationResult
+) -> Result<()> {
+    // Script indicators and frame statistics are calculated during the backtest and never stored
+    for indicator in strategy.indicators.iter()
+        .filter(|indicator| !script::is_script_indicator(indicator) && !statistics::needs_frame(indicator)) {
+        let enabled = pg.get_indicator_config_enabled(
+            symbol, 
+            interval, 
+            &indicator.indicator_name, 
+            &indicator.parameters
+        ).await?;
+        
+        match enabled {
+            None => result.add_warning(format!(
+                "Indicator {} ({} {}) has no indicator_config for {}:{}", 
+                indicator.id, 
+                indicator.indicator_name, 
+                indicator.parameters, 
+                symbol, 
+                interval
+            )),
+            Some(false) => result.add_warning(format!(
+                "Indicator {} ({} {}) is disabled in indicator_config for {}:{}", 
+                indicator.id, 
+                indicator.indicator_name, 
+                indicator.parameters, 
+                symbol, 
+                interval
+            )),
+            Some(true) => {
+                // Configured, so check that the worker has calculated it
+                let (_, count) = pg.get_indicator_completeness(
+                    symbol, 
+                    interval, 
+                    &indicator.indicator_name, 
+                    &indicator.parameters
+                ).await?;
+                
+                if count == 0 {
+                    result.add_warning(format!(
+                        "Indicator {} ({} {}) has no calculated data for {}:{}", 
+                        indicator.id, 
+                        indicator.indicator_name, 
+                        indicator.parameters, 
+                        symbol, 
+                        interval
+                    ));
+                }
+            }
+        }
+    }
+    
+    Ok(())
+}
+
 /// Validate basic fields of a strategy
 fn validate_basic_fields(strategy: &Strategy, result: &mut ValidationResult) {
     // Check ID
@@ -51,6 +156,11 @@ fn validate_basic_fields(strategy: &Strategy, result: &mut ValidationResult) {
     if strategy.assets.is_empty() {
         result.add_error("Strategy has no assets defined");
     }
+    for asset in strategy.assets.iter().filter(|asset| watchlist::is_watchlist(asset)) {
+        if let Err(e) = Watchlist::parse(asset) {
+            result.add_error(e.to_string());
+        }
+    }
     
     // Check timeframes
     if strategy.timeframes.is_empty() {
@@ -72,7 +182,7 @@ fn validate_basic_fields(strategy: &Strategy, result: &mut ValidationResult) {
 fn validate_indicators(strategy: &Strategy, result: &mut ValidationResult) {
     let mut indicator_ids = HashSet::new();
     
-    for indicator in &strategy.indicators {
+    for (position, indicator) in strategy.indicators.iter().enumerate() {
         // Check ID
         if indicator.id.is_empty() {
             result.add_error("Indicator ID is empty");
@@ -90,7 +200,7 @@ fn validate_indicators(strategy: &Strategy, result: &mut ValidationResult) {
         } else {
             // Validate type against known types
             match indicator.indicator_type.as_str() {
-                "oscillator" | "overlap" | "volume" | "volatility" | "pattern" => {},
+                "oscillator" | "overlap" | "volume" | "volatility" | "pattern" | "script" | "statistics" => {},
                 _ => result.add_warning(format!(
                     "Indicator {} has unknown type: {}", 
                     indicator.id, 
@@ -106,6 +216,24 @@ fn validate_indicators(strategy: &Strategy, result: &mut ValidationResult) {
         
         // Check parameters based on indicator_name
         validate_indicator_parameters(indicator, result);
+        
+        if let Err(e) = Transform::from_parameters(&indicator.parameters) {
+            result.add_error(format!("Indicator {}: {}", indicator.id, e));
+        }
+        
+        // Script formulas must compile against the names available to them
+        if script::is_script_indicator(indicator) {
+            if let Err(e) = Script::for_indicator(strategy, position) {
+                result.add_error(format!("Script indicator {}: {}", indicator.id, e));
+            }
+        }
+        
+        // Statistics over another symbol or indicator need valid references
+        if statistics::needs_frame(indicator) {
+            if let Err(e) = statistics::validate(strategy, position) {
+                result.add_error(e.to_string());
+            }
+        }
     }
 }
 
@@ -180,6 +308,16 @@ fn validate_indicator_parameters(indicator: &StrategyIndicator, result: &mut Val
                 ));
             }
         },
+        "SPREAD" | "RATIO" | "RELATIVE_STRENGTH" => {
+            // The second symbol is required to calculate anything
+            if indicator.parameters.get("other_symbol").and_then(|v| v.as_str()).is_none() {
+                result.add_error(format!(
+                    "{} indicator {} missing 'other_symbol' parameter",
+                    indicator.indicator_name,
+                    indicator.id
+                ));
+            }
+        },
         // Add more indicators as needed
         _ => {}
     }
@@ -188,9 +326,6 @@ fn validate_indicator_parameters(indicator: &StrategyIndicator, result: &mut Val
 /// Validate rules in a strategy
 fn validate_rules(strategy: &Strategy, result: &mut ValidationResult) {
     let mut rule_ids = HashSet::new();
-    let indicator_ids: HashSet<&String> = strategy.indicators.iter()
-        .map(|i| &i.id)
-        .collect();
     
     for rule in &strategy.rules {
         // Check ID
@@ -210,23 +345,112 @@ fn validate_rules(strategy: &Strategy, result: &mut ValidationResult) {
         }
         
         // Validate condition
-        validate_condition(&rule.condition, &indicator_ids, result);
+        validate_condition(&rule.condition, strategy, result);
+        
+        // An entry rule is only evaluated while flat, when bars_since_entry has no value
+        if matches!(rule.action, RuleAction::EnterLong { .. } | RuleAction::EnterShort { .. })
+            && uses_bars_since_entry(&rule.condition) {
+            result.add_warning(format!(
+                "Rule {} enters a position but its condition uses bars_since_entry, which has no value while flat", 
+                rule.id
+            ));
+        }
+        
+        // Check pyramiding sizes
+        match &rule.action {
+            RuleAction::ScaleIn { size_percent, max_position_percent } => {
+                if *size_percent <= 0.0 || *size_percent > 100.0 {
+                    result.add_error(format!(
+                        "Rule {} scales in by {}%. Should be between 0 and 100", rule.id, size_percent
+                    ));
+                }
+                if let Some(max) = max_position_percent {
+                    if *max < *size_percent {
+                        result.add_warning(format!(
+                            "Rule {} can never scale in: max_position_percent {}% is below size_percent {}%", 
+                            rule.id, max, size_percent
+                        ));
+                    }
+                }
+            },
+            RuleAction::ScaleOut { percent } => {
+                if *percent <= 0.0 || *percent > 100.0 {
+                    result.add_error(format!(
+                        "Rule {} scales out {}% of the position. Should be between 0 and 100", rule.id, percent
+                    ));
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Type of the values produced by a value source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Numeric,
+    Text,
+    Boolean,
+    /// The type cannot be determined statically (e.g. an unknown indicator)
+    Unknown,
+}
+
+/// Output properties of an indicator with their value kinds
+/// Returns an empty list for single-value indicators and None for unknown indicators
+fn indicator_output_properties(indicator_name: &str) -> Option<&'static [(&'static str, ValueKind)]> {
+    match indicator_name.to_uppercase().as_str() {
+        "RSI" | "SMA" | "EMA" | "WMA" | "TEMA" | "ATR" | "NATR" | "ADX" | "OBV" | "CCI" | "MOM" | "MFI" | "SCRIPT" | "CORREL" | "BETA" |
+        "SPREAD" | "RATIO" | "RELATIVE_STRENGTH" => Some(&[]),
+        "MACD" => Some(&[
+            ("macd", ValueKind::Numeric),
+            ("signal", ValueKind::Numeric),
+            ("histogram", ValueKind::Numeric),
+        ]),
+        "BBANDS" => Some(&[
+            ("upper", ValueKind::Numeric),
+            ("middle", ValueKind::Numeric),
+            ("lower", ValueKind::Numeric),
+            ("width", ValueKind::Numeric),
+        ]),
+        "STOCH" | "STOCHRSI" => Some(&[
+            ("k", ValueKind::Numeric),
+            ("d", ValueKind::Numeric),
+        ]),
+        "ENGULFING" | "CDLENGULFING" | "HAMMER" | "CDLHAMMER" | "MORNINGSTAR" | "CDLMORNINGSTAR" |
+        "DOJI" | "CDLDOJI" | "DRAGONFLYDOJI" | "CDLDRAGONFLYDOJI" | "GRAVESTONEDOJI" | "CDLGRAVESTONEDOJI" |
+        "LONGLEGGEDDOJI" | "CDLLONGLEGGEDDOJI" | "SPINNINGTOP" | "CDLSPINNINGTOP" | "MARUBOZU" | "CDLMARUBOZU" |
+        "SHOOTINGSTAR" | "CDLSHOOTINGSTAR" | "INVERTEDHAMMER" | "CDLINVERTEDHAMMER" | "HANGINGMAN" | "CDLHANGINGMAN" => Some(&[
+            ("pattern", ValueKind::Text),
+            ("type", ValueKind::Text),
+            ("strength", ValueKind::Numeric),
+        ]),
+        "PATTERN_CONFLUENCE" | "CONFLUENCE" => Some(&[
+            ("bullish", ValueKind::Numeric),
+            ("bearish", ValueKind::Numeric),
+            ("score", ValueKind::Numeric),
+        ]),
+        _ => None,
     }
 }
 
 /// Validate a composite condition
 fn validate_condition(
     condition: &CompositeCondition, 
-    indicator_ids: &HashSet<&String>, 
+    strategy: &Strategy, 
     result: &mut ValidationResult
 ) {
     match condition {
         CompositeCondition::Simple { condition } => {
-            validate_simple_condition(condition, indicator_ids, result);
+            validate_simple_condition(condition, strategy, result);
         },
         CompositeCondition::Compound { operator: _, conditions } => {
             for cond in conditions {
-                validate_condition(cond, indicator_ids, result);
+                validate_condition(cond, strategy, result);
+            }
+        },
+        CompositeCondition::Expression { expression } => {
+            if let Err(e) = Script::for_condition(strategy, expression) {
+                result.add_error(format!("Condition expression: {}", e));
             }
         }
     }
@@ -235,30 +459,128 @@ fn validate_condition(
 /// Validate a simple condition
 fn validate_simple_condition(
     condition: &Condition, 
-    indicator_ids: &HashSet<&String>, 
+    strategy: &Strategy, 
     result: &mut ValidationResult
 ) {
     // Validate left value source
-    validate_value_source(&condition.left, indicator_ids, result);
+    let left = validate_value_source(&condition.left, strategy, result);
     
     // Validate right value source
-    validate_value_source(&condition.right, indicator_ids, result);
+    let right = validate_value_source(&condition.right, strategy, result);
+    
+    match condition.operator {
+        ComparisonOperator::Equal | ComparisonOperator::NotEqual => {
+            // Equality only makes sense between values of the same kind
+            if left != ValueKind::Unknown && right != ValueKind::Unknown && left != right {
+                result.add_warning(format!(
+                    "Condition compares {:?} value with {:?} value", 
+                    left, 
+                    right
+                ));
+            }
+        },
+        ComparisonOperator::CrossesAbove | ComparisonOperator::CrossesBelow => {
+            // Crossovers need numeric operands, at least one of which changes over time
+            check_numeric_operands(condition, left, right, result);
+            
+            let is_series = |source: &ValueSource| {
+                matches!(source, ValueSource::Indicator { .. } | ValueSource::Price { .. })
+            };
+            if !is_series(&condition.left) && !is_series(&condition.right) {
+                result.add_error(format!(
+                    "{:?} condition needs an indicator or price series as an operand", 
+                    condition.operator
+                ));
+            }
+        },
+        _ => check_numeric_operands(condition, left, right, result),
+    }
+    
+    check_time_constants(condition, result);
+    check_pattern_type_constants(condition, result);
+}
+
+/// Report operands of an ordering comparison that are not numeric
+fn check_numeric_operands(condition: &Condition, left: ValueKind, right: ValueKind, result: &mut ValidationResult) {
+    for (side, kind) in [("Left", left), ("Right", right)] {
+        if kind != ValueKind::Numeric && kind != ValueKind::Unknown {
+            result.add_error(format!(
+                "{} operand of {:?} condition is {:?}, expected a numeric value", 
+                side, 
+                condition.operator, 
+                kind
+            ));
+        }
+    }
 }
 
-/// Validate a value source
+/// Validate a value source and determine the kind of value it produces
 fn validate_value_source(
     source: &ValueSource, 
-    indicator_ids: &HashSet<&String>, 
+    strategy: &Strategy, 
     result: &mut ValidationResult
-) {
+) -> ValueKind {
     match source {
-        ValueSource::Indicator { indicator_id, property: _, offset: _ } => {
+        ValueSource::Indicator { indicator_id, property, offset: _ } => {
             // Check if the indicator ID exists
-            if !indicator_ids.contains(indicator_id) {
-                result.add_error(format!(
-                    "Rule references unknown indicator: {}", 
-                    indicator_id
-                ));
+            let indicator = match strategy.indicators.iter().find(|i| &i.id == indicator_id) {
+                Some(indicator) => indicator,
+                None => {
+                    result.add_error(format!(
+                        "Rule references unknown indicator: {}", 
+                        indicator_id
+                    ));
+                    return ValueKind::Unknown;
+                }
+            };
+            
+            // Check the property against the indicator's output shape
+            let properties: Vec<(&str, ValueKind)> = match indicator_output_properties(&indicator.indicator_name) {
+                // Transformed indicators produce a single value
+                _ if transform::has_transform(&indicator.parameters) => Vec::new(),
+                Some(properties) => properties.to_vec(),
+                None => match plugin::get_indicator(&indicator.indicator_name) {
+                    // User-defined indicators produce numeric values
+                    Some(plugin) => plugin.properties().iter().map(|name| (*name, ValueKind::Numeric)).collect(),
+                    None => return ValueKind::Unknown,
+                },
+            };
+            
+            match property {
+                Some(property) if properties.is_empty() => {
+                    result.add_error(format!(
+                        "Indicator {} ({}) has a single value and no property '{}'", 
+                        indicator_id, 
+                        indicator.indicator_name, 
+                        property
+                    ));
+                    ValueKind::Unknown
+                },
+                Some(property) => match properties.iter().find(|(name, _)| *name == property.as_str()) {
+                    Some((_, kind)) => *kind,
+                    None => {
+                        let names: Vec<&str> = properties.iter().map(|(name, _)| *name).collect();
+                        result.add_error(format!(
+                            "Indicator {} ({}) has no property '{}' (available: {})", 
+                            indicator_id, 
+                            indicator.indicator_name, 
+                            property, 
+                            names.join(", ")
+                        ));
+                        ValueKind::Unknown
+                    }
+                },
+                None if properties.is_empty() => ValueKind::Numeric,
+                None => {
+                    let names: Vec<&str> = properties.iter().map(|(name, _)| *name).collect();
+                    result.add_error(format!(
+                        "Indicator {} ({}) has multiple outputs, a property is required (one of: {})", 
+                        indicator_id, 
+                        indicator.indicator_name, 
+                        names.join(", ")
+                    ));
+                    ValueKind::Unknown
+                },
             }
         },
         ValueSource::Price { property, offset: _ } => {
@@ -270,17 +592,93 @@ fn validate_value_source(
                     property
                 )),
             }
+            ValueKind::Numeric
+        },
+        ValueSource::Parameter { parameter_id } => {
+            // Check that the parameter is declared by the strategy
+            match strategy.parameters.get(parameter_id) {
+                Some(StrategyParameter::Integer { .. }) | Some(StrategyParameter::Float { .. }) => ValueKind::Numeric,
+                Some(StrategyParameter::Boolean { .. }) => ValueKind::Boolean,
+                Some(StrategyParameter::String { .. }) => ValueKind::Text,
+                None => {
+                    result.add_error(format!(
+                        "Rule references unknown parameter: {}", 
+                        parameter_id
+                    ));
+                    ValueKind::Unknown
+                }
+            }
         },
-        ValueSource::Parameter { parameter_id: _ } => {
-            // Parameter validation would require checking against strategy.parameters
-            // This is left as a future enhancement
+        ValueSource::Constant { value } => {
+            match value {
+                serde_json::Value::Number(_) => ValueKind::Numeric,
+                serde_json::Value::String(_) => ValueKind::Text,
+                serde_json::Value::Bool(_) => ValueKind::Boolean,
+                _ => ValueKind::Unknown,
+            }
         },
-        ValueSource::Constant { value: _ } => {
-            // Constants don't need validation
+        ValueSource::TimeOfDay | ValueSource::DayOfWeek | ValueSource::InSession | ValueSource::BarsSinceEntry => ValueKind::Numeric,
+    }
+}
+
+/// Warn about constants outside the range of the time source they are compared with
+fn check_time_constants(condition: &Condition, result: &mut ValidationResult) {
+    for (source, other) in [(&condition.left, &condition.right), (&condition.right, &condition.left)] {
+        let value = match other {
+            ValueSource::Constant { value } => match value.as_f64() {
+                Some(value) => value,
+                None => continue,
+            },
+            _ => continue,
+        };
+        
+        let (name, min, max) = match source {
+            ValueSource::TimeOfDay => ("time_of_day", 0.0, 24.0),
+            ValueSource::DayOfWeek => ("day_of_week", 1.0, 7.0),
+            ValueSource::InSession => ("in_session", 0.0, 1.0),
+            _ => continue,
+        };
+        
+        if value < min || value > max {
+            result.add_warning(format!(
+                "Condition compares {} with {}, outside its range of {} to {}", 
+                name, value, min, max
+            ));
         }
     }
 }
 
+/// Warn about constants compared with a pattern's type that no pattern produces
+fn check_pattern_type_constants(condition: &Condition, result: &mut ValidationResult) {
+    for (source, other) in [(&condition.left, &condition.right), (&condition.right, &condition.left)] {
+        let (ValueSource::Indicator { property: Some(property), .. }, ValueSource::Constant { value }) = (source, other) else {
+            continue;
+        };
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        
+        if property == "type" && !["bullish", "bearish", "neutral"].contains(&value) {
+            result.add_warning(format!(
+                "Condition compares a pattern type with '{}'; patterns are bullish, bearish or neutral", 
+                value
+            ));
+        }
+    }
+}
+
+/// Whether a condition refers to the open position anywhere
+fn uses_bars_since_entry(condition: &CompositeCondition) -> bool {
+    match condition {
+        CompositeCondition::Simple { condition } => {
+            matches!(condition.left, ValueSource::BarsSinceEntry) || matches!(condition.right, ValueSource::BarsSinceEntry)
+        },
+        CompositeCondition::Compound { operator: _, conditions } => conditions.iter().any(uses_bars_since_entry),
+        // Expressions only see prices, indicators and parameters
+        CompositeCondition::Expression { .. } => false,
+    }
+}
+
 /// Validate risk management settings
 fn validate_risk_management(risk_management: &RiskManagement, result: &mut ValidationResult) {
     // Check max risk per trade
@@ -338,6 +736,61 @@ fn validate_risk_management(risk_management: &RiskManagement, result: &mut Valid
     }
 }
 
+/// Validate stop loss and take profit levels that reference indicators
+fn validate_stop_levels(strategy: &Strategy, result: &mut ValidationResult) {
+    let levels = [
+        ("stop_loss_level", &strategy.risk_management.stop_loss_level),
+        ("take_profit_level", &strategy.risk_management.take_profit_level),
+    ];
+    
+    for (name, level) in levels {
+        match level {
+            Some(StopLevel::Percent { value }) => {
+                if *value <= 0.0 || *value > 100.0 {
+                    result.add_warning(format!(
+                        "Invalid {} percent: {}%. Should be between 0 and 100", 
+                        name, 
+                        value
+                    ));
+                }
+            },
+            Some(StopLevel::AtrMultiple { indicator_id, multiple }) => {
+                match strategy.indicators.iter().find(|i| &i.id == indicator_id) {
+                    Some(indicator) => {
+                        if !matches!(indicator.indicator_name.as_str(), "ATR" | "NATR") {
+                            result.add_warning(format!(
+                                "{} uses indicator {} ({}) as ATR", 
+                                name, 
+                                indicator_id, 
+                                indicator.indicator_name
+                            ));
+                        }
+                    },
+                    None => result.add_error(format!(
+                        "{} references unknown indicator: {}", 
+                        name, 
+                        indicator_id
+                    )),
+                }
+                
+                if *multiple <= 0.0 {
+                    result.add_error(format!("{} ATR multiple must be positive, got {}", name, multiple));
+                }
+            },
+            Some(StopLevel::Indicator { indicator_id, property: _ }) => {
+                if !strategy.indicators.iter().any(|i| &i.id == indicator_id) {
+                    result.add_error(format!(
+                        "{} references unknown indicator: {}", 
+                        name, 
+                        indicator_id
+                    ));
+                }
+            },
+            None => {}
+        }
+    }
+}
+
 /// Result of strategy validation containing errors and warnings
 #[derive(Debug, Default)]
 pub struct ValidationResult {
@@ -374,10 +827,10 @@ impl ValidationResult {
         !self.warnings.is_empty()
     }
     
-    /// Convert validation result to anyhow::Result
+    /// Convert validation result to a Result, failing with Error::Validation
     pub fn to_result(self) -> Result<()> {
         if self.has_errors() {
-            Err(anyhow!("Strategy validation failed: {}", self.errors.join(", ")))
+            Err(Error::validation(format!("Strategy validation failed: {}", self.errors.join(", "))))
         } else {
             Ok(())
         }
@@ -0,0 +1,89 @@
+// Watchlists in strategy assets
+//
+// An asset starting with "@" names a watchlist instead of a symbol, e.g. "@top50_volume".
+// Watchlists are resolved against the symbols table each time a strategy is backtested,
+// re-evaluated or checked for data, so the symbols follow the latest `symbols
+// import-from-exchange`. Inactive symbols never match.
+use crate::database::postgres::PostgresManager;
+use crate::error::{Error, Result};
+
+/// Prefix of watchlist names in strategy assets
+pub const WATCHLIST_PREFIX: char = '@';
+
+/// A rule selecting symbols from the symbols table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Watchlist {
+    /// The N active symbols with the highest 24h quote volume: "top<N>_volume"
+    TopVolume(usize),
+    /// Every active symbol quoted in an asset: "quote_<asset>", e.g. "quote_usdt"
+    Quote(String),
+}
+
+impl Watchlist {
+    /// Parse a watchlist name, with or without the "@" prefix
+    pub fn parse(name: &str) -> Result<Self> {
+        let name = name.strip_prefix(WATCHLIST_PREFIX).unwrap_or(name);
+
+        if let Some(count) = name.strip_prefix("top").and_then(|rest| rest.strip_suffix("_volume")) {
+            return match count.parse::<usize>() {
+                Ok(count) if count > 0 => Ok(Watchlist::TopVolume(count)),
+                _ => Err(Error::validation(format!("Invalid watchlist '{}', expected e.g. top50_volume", name))),
+            };
+        }
+
+        match name.strip_prefix("quote_") {
+            Some(asset) if !asset.is_empty() => Ok(Watchlist::Quote(asset.to_uppercase())),
+            _ => Err(Error::validation(format!(
+                "Unknown watchlist '{}', expected top<N>_volume or quote_<asset>", name
+            ))),
+        }
+    }
+
+    /// Symbols of the watchlist, by descending 24h quote volume
+    pub async fn symbols(&self, pg: &PostgresManager) -> Result<Vec<String>> {
+        pg.init_symbols_table().await?;
+
+        let symbols = match self {
+            Watchlist::TopVolume(count) => pg.list_symbols(true, None).await?
+                .into_iter()
+                .filter(|symbol| symbol.quote_volume.is_some())
+                .take(*count)
+                .collect::<Vec<_>>(),
+            Watchlist::Quote(asset) => pg.list_symbols(true, Some(asset.as_str())).await?,
+        };
+
+        Ok(symbols.into_iter().map(|symbol| symbol.symbol).collect())
+    }
+}
+
+/// Whether a strategy asset names a watchlist
+pub fn is_watchlist(asset: &str) -> bool {
+    asset.starts_with(WATCHLIST_PREFIX)
+}
+
+/// Strategy assets with watchlists replaced by their symbols, without duplicates
+pub async fn resolve_assets(pg: &PostgresManager, assets: &[String]) -> Result<Vec<String>> {
+    let mut symbols: Vec<String> = Vec::new();
+
+    for asset in assets {
+        let resolved = if is_watchlist(asset) {
+            let resolved = Watchlist::parse(asset)?.symbols(pg).await?;
+            if resolved.is_empty() {
+                return Err(Error::validation(format!(
+                    "Watchlist {} has no symbols; run 'symbols import-from-exchange' first", asset
+                )));
+            }
+            resolved
+        } else {
+            vec![asset.clone()]
+        };
+
+        for symbol in resolved {
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
+            }
+        }
+    }
+
+    Ok(symbols)
+}
@@ -0,0 +1,166 @@
+// Checked results of TA-Lib calls: typed error codes, output range validation and NaN handling
+//
+// TA-Lib reports the produced range through out_beg_idx/out_nb_element. Those are validated
+// against the input and output buffers before any element is read, and non-finite values are
+// handled by the process-wide NaN policy before they reach the JSON results.
+use crate::indicators::output::IndicatorOutput;
+use std::os::raw::c_int;
+use std::sync::atomic::{AtomicU8, Ordering};
+use thiserror::Error;
+
+use super::ffi;
+
+/// Failure of a TA-Lib call
+#[derive(Debug, Error)]
+pub enum TaLibError {
+    #[error("{function}: TA-Lib is not initialized (TA_LIB_NOT_INITIALIZE)")]
+    NotInitialized { function: &'static str },
+    #[error("{function}: invalid parameter (TA_BAD_PARAM)")]
+    BadParam { function: &'static str },
+    #[error("{function}: memory allocation failed (TA_ALLOC_ERR)")]
+    AllocError { function: &'static str },
+    #[error("{function}: start index out of range (TA_OUT_OF_RANGE_START_INDEX)")]
+    StartIndexOutOfRange { function: &'static str },
+    #[error("{function}: end index out of range (TA_OUT_OF_RANGE_END_INDEX)")]
+    EndIndexOutOfRange { function: &'static str },
+    #[error("{function}: internal TA-Lib error (TA_INTERNAL_ERROR)")]
+    Internal { function: &'static str },
+    #[error("{function}: unknown TA-Lib error (TA_UNKNOWN_ERR)")]
+    Unknown { function: &'static str },
+    #[error("{function}: TA-Lib error code {code}")]
+    Other { function: &'static str, code: c_int },
+    #[error("{function}: output range {begin}+{count} does not fit {input_len} inputs / {buffer_len} buffered values")]
+    InvalidOutput {
+        function: &'static str,
+        begin: c_int,
+        count: c_int,
+        input_len: usize,
+        buffer_len: usize,
+    },
+    #[error("{function}: non-finite value {value} at index {index}")]
+    NonFinite { function: &'static str, index: usize, value: f64 },
+    #[error("Failed to initialize TA-Lib, TA_Initialize returned {code}")]
+    InitializeFailed { code: c_int },
+    #[error("TA-Lib has already been shut down")]
+    ShutDown,
+}
+
+impl TaLibError {
+    /// Map a TA_RetCode other than TA_SUCCESS
+    pub fn from_code(function: &'static str, code: c_int) -> Self {
+        match code {
+            1 => TaLibError::NotInitialized { function },
+            2 => TaLibError::BadParam { function },
+            3 => TaLibError::AllocError { function },
+            12 => TaLibError::StartIndexOutOfRange { function },
+            13 => TaLibError::EndIndexOutOfRange { function },
+            5000 => TaLibError::Internal { function },
+            0xFFFF => TaLibError::Unknown { function },
+            code => TaLibError::Other { function, code },
+        }
+    }
+}
+
+/// What to do with NaN and infinite values in TA-Lib outputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// Keep the row and store the value as JSON null
+    Null,
+    /// Drop rows containing a non-finite value
+    Skip,
+    /// Fail the calculation
+    Error,
+}
+
+impl NanPolicy {
+    /// Parse a policy name as used in INDICATOR_NAN_POLICY
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "null" => Some(NanPolicy::Null),
+            "skip" => Some(NanPolicy::Skip),
+            "error" => Some(NanPolicy::Error),
+            _ => None,
+        }
+    }
+}
+
+static NAN_POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Set the NaN policy for all following calculations in this process
+pub fn set_nan_policy(policy: NanPolicy) {
+    let value = match policy {
+        NanPolicy::Null => 0,
+        NanPolicy::Skip => 1,
+        NanPolicy::Error => 2,
+    };
+    NAN_POLICY.store(value, Ordering::Relaxed);
+}
+
+pub fn nan_policy() -> NanPolicy {
+    match NAN_POLICY.load(Ordering::Relaxed) {
+        1 => NanPolicy::Skip,
+        2 => NanPolicy::Error,
+        _ => NanPolicy::Null,
+    }
+}
+
+/// Check the return code and produced range of a call; returns (first input index, element count)
+pub(crate) fn check_output(
+    function: &'static str,
+    ret_code: c_int,
+    out_beg_idx: c_int,
+    out_nb_element: c_int,
+    input_len: usize,
+    buffer_len: usize,
+) -> Result<(usize, usize), TaLibError> {
+    if ret_code != ffi::TA_SUCCESS {
+        return Err(TaLibError::from_code(function, ret_code));
+    }
+
+    let invalid = || TaLibError::InvalidOutput {
+        function,
+        begin: out_beg_idx,
+        count: out_nb_element,
+        input_len,
+        buffer_len,
+    };
+
+    let begin = usize::try_from(out_beg_idx).map_err(|_| invalid())?;
+    let count = usize::try_from(out_nb_element).map_err(|_| invalid())?;
+
+    if count > buffer_len || begin.checked_add(count).map_or(true, |end| end > input_len) {
+        return Err(invalid());
+    }
+
+    Ok((begin, count))
+}
+
+/// Apply the NaN policy to one output row; false when the row is to be dropped
+pub(crate) fn keep_row(function: &'static str, index: usize, values: &[f64]) -> Result<bool, TaLibError> {
+    let Some(&value) = values.iter().find(|v| !v.is_finite()) else {
+        return Ok(true);
+    };
+
+    match nan_policy() {
+        // serde_json serializes non-finite floats as null
+        NanPolicy::Null => Ok(true),
+        NanPolicy::Skip => Ok(false),
+        NanPolicy::Error => Err(TaLibError::NonFinite { function, index, value }),
+    }
+}
+
+/// Results of a function with a single real output
+pub(crate) fn collect_real(
+    function: &'static str,
+    begin: usize,
+    count: usize,
+    out: &[f64],
+) -> Result<Vec<(usize, IndicatorOutput)>, TaLibError> {
+    let mut results = Vec::with_capacity(count);
+    for (i, &value) in out[..count].iter().enumerate() {
+        if keep_row(function, begin + i, &[value])? {
+            results.push((begin + i, IndicatorOutput::scalar(value)));
+        }
+    }
+    Ok(results)
+}
@@ -1,6 +1,7 @@
 // Common functionality and main interface for TA-Lib
-use crate::talib_bindings::ffi;
-use anyhow::{anyhow, Result};
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::talib_bindings::lifecycle::{with_talib, TaLibGuard};
 use serde_json::{Value};
 use std::os::raw::c_int;
 use tracing::{debug};
@@ -15,13 +16,9 @@ use super::volatility::VolatilityIndicators;
 pub struct TaLibAbstract;
 
 impl TaLibAbstract {
-    // Initialize TA-Lib
-    pub fn initialize() -> Result<()> {
-        let ret_code = unsafe { ffi::TA_Initialize() };
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to initialize TA-Lib"));
-        }
-        Ok(())
+    // Initialize TA-Lib once for the process; it is shut down when the returned guard is dropped
+    pub fn initialize() -> Result<TaLibGuard> {
+        TaLibGuard::acquire()
     }
 
     // Check if a function is available
@@ -29,6 +26,8 @@ impl TaLibAbstract {
         match function_name.to_uppercase().as_str() {
             "RSI" | "SMA" | "EMA" | "MACD" | "BBANDS" | "ATR" | "STOCH" | 
             "ADX" | "OBV" | "CDLENGULFING" | "CDLHAMMER" | "CDLMORNINGSTAR" |
+            "CDLDOJI" | "CDLDRAGONFLYDOJI" | "CDLGRAVESTONEDOJI" | "CDLLONGLEGGEDDOJI" |
+            "CDLSPINNINGTOP" | "CDLMARUBOZU" | "CDLSHOOTINGSTAR" | "CDLINVERTEDHAMMER" | "CDLHANGINGMAN" | "PATTERN_CONFLUENCE" |
             "CCI" | "STOCHRSI" | "MOM" | "MFI" => true,
             _ => false,
         }
@@ -50,6 +49,16 @@ impl TaLibAbstract {
             "ENGULFING" | "CDLENGULFING" => "CDLENGULFING".to_string(),
             "HAMMER" | "CDLHAMMER" => "CDLHAMMER".to_string(),
             "MORNINGSTAR" | "CDLMORNINGSTAR" => "CDLMORNINGSTAR".to_string(),
+            "DOJI" | "CDLDOJI" => "CDLDOJI".to_string(),
+            "DRAGONFLYDOJI" | "CDLDRAGONFLYDOJI" => "CDLDRAGONFLYDOJI".to_string(),
+            "GRAVESTONEDOJI" | "CDLGRAVESTONEDOJI" => "CDLGRAVESTONEDOJI".to_string(),
+            "LONGLEGGEDDOJI" | "CDLLONGLEGGEDDOJI" => "CDLLONGLEGGEDDOJI".to_string(),
+            "SPINNINGTOP" | "CDLSPINNINGTOP" => "CDLSPINNINGTOP".to_string(),
+            "MARUBOZU" | "CDLMARUBOZU" => "CDLMARUBOZU".to_string(),
+            "SHOOTINGSTAR" | "CDLSHOOTINGSTAR" => "CDLSHOOTINGSTAR".to_string(),
+            "INVERTEDHAMMER" | "CDLINVERTEDHAMMER" => "CDLINVERTEDHAMMER".to_string(),
+            "HANGINGMAN" | "CDLHANGINGMAN" => "CDLHANGINGMAN".to_string(),
+            "PATTERN_CONFLUENCE" | "CONFLUENCE" => "PATTERN_CONFLUENCE".to_string(),
             "CCI" => "CCI".to_string(),
             "STOCHRSI" => "STOCHRSI".to_string(),
             "MOM" => "MOM".to_string(),
@@ -67,8 +76,9 @@ impl TaLibAbstract {
         close: Option<&[f64]>,
         volume: Option<&[f64]>,
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
-        match function_name.to_uppercase().as_str() {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Every FFI call runs with TA-Lib initialized and not yet shut down
+        with_talib(|| match function_name.to_uppercase().as_str() {
             // Oscillator indicators
             "RSI" => OscillatorIndicators::calculate_rsi(close.unwrap_or(&[]), parameters),
             "MACD" => OscillatorIndicators::calculate_macd(close.unwrap_or(&[]), parameters),
@@ -94,9 +104,19 @@ impl TaLibAbstract {
             "CDLENGULFING" => PatternIndicators::calculate_cdl_engulfing(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
             "CDLHAMMER" => PatternIndicators::calculate_cdl_hammer(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
             "CDLMORNINGSTAR" => PatternIndicators::calculate_cdl_morning_star(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLDOJI" => PatternIndicators::calculate_cdl_doji(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLDRAGONFLYDOJI" => PatternIndicators::calculate_cdl_dragonfly_doji(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLGRAVESTONEDOJI" => PatternIndicators::calculate_cdl_gravestone_doji(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLLONGLEGGEDDOJI" => PatternIndicators::calculate_cdl_long_legged_doji(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLSPINNINGTOP" => PatternIndicators::calculate_cdl_spinning_top(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLMARUBOZU" => PatternIndicators::calculate_cdl_marubozu(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLSHOOTINGSTAR" => PatternIndicators::calculate_cdl_shooting_star(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLINVERTEDHAMMER" => PatternIndicators::calculate_cdl_inverted_hammer(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "CDLHANGINGMAN" => PatternIndicators::calculate_cdl_hanging_man(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
+            "PATTERN_CONFLUENCE" => PatternIndicators::calculate_pattern_confluence(open.unwrap_or(&[]), high.unwrap_or(&[]), low.unwrap_or(&[]), close.unwrap_or(&[]), parameters),
             
-            _ => Err(anyhow!("Unsupported function: {}", function_name)),
-        }
+            _ => Err(Error::not_found(format!("TA-Lib function {}", function_name))),
+        })
     }
 
     // Helper method to get an integer parameter
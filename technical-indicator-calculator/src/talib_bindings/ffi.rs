@@ -10,6 +10,9 @@ extern "C" {
     // Initialize TA-Lib
     pub fn TA_Initialize() -> c_int;
     
+    // Release TA-Lib resources
+    pub fn TA_Shutdown() -> c_int;
+    
     // RSI - Relative Strength Index
     pub fn TA_RSI(
         startIdx: c_int,
@@ -155,6 +158,123 @@ extern "C" {
         outInteger: *mut c_int,
     ) -> c_int;
     
+    // Patterns - Doji
+    pub fn TA_CDLDOJI(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Dragonfly Doji
+    pub fn TA_CDLDRAGONFLYDOJI(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Gravestone Doji
+    pub fn TA_CDLGRAVESTONEDOJI(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Long Legged Doji
+    pub fn TA_CDLLONGLEGGEDDOJI(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Spinning Top
+    pub fn TA_CDLSPINNINGTOP(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Marubozu
+    pub fn TA_CDLMARUBOZU(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Shooting Star
+    pub fn TA_CDLSHOOTINGSTAR(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Inverted Hammer
+    pub fn TA_CDLINVERTEDHAMMER(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
+    // Patterns - Hanging Man
+    pub fn TA_CDLHANGINGMAN(
+        startIdx: c_int,
+        endIdx: c_int,
+        inOpen: *const c_double,
+        inHigh: *const c_double,
+        inLow: *const c_double,
+        inClose: *const c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outInteger: *mut c_int,
+    ) -> c_int;
+    
     // Patterns - Morning Star
     pub fn TA_CDLMORNINGSTAR(
         startIdx: c_int,
@@ -221,4 +341,84 @@ extern "C" {
         outNbElement: *mut c_int,
         outReal: *mut c_double,
     ) -> c_int;
+    
+    // LINEARREG - Linear Regression
+    pub fn TA_LINEARREG(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal: *const c_double,
+        optInTimePeriod: c_int,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
+    
+    // LINEARREG_SLOPE - Linear Regression Slope
+    pub fn TA_LINEARREG_SLOPE(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal: *const c_double,
+        optInTimePeriod: c_int,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
+    
+    // LINEARREG_INTERCEPT - Linear Regression Intercept
+    pub fn TA_LINEARREG_INTERCEPT(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal: *const c_double,
+        optInTimePeriod: c_int,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
+    
+    // TSF - Time Series Forecast
+    pub fn TA_TSF(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal: *const c_double,
+        optInTimePeriod: c_int,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
+    
+    // STDDEV - Standard Deviation
+    pub fn TA_STDDEV(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal: *const c_double,
+        optInTimePeriod: c_int,
+        optInNbDev: c_double,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
+    
+    // CORREL - Pearson's Correlation Coefficient
+    pub fn TA_CORREL(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal0: *const c_double,
+        inReal1: *const c_double,
+        optInTimePeriod: c_int,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
+    
+    // BETA - Beta
+    pub fn TA_BETA(
+        startIdx: c_int,
+        endIdx: c_int,
+        inReal0: *const c_double,
+        inReal1: *const c_double,
+        optInTimePeriod: c_int,
+        outBegIdx: *mut c_int,
+        outNbElement: *mut c_int,
+        outReal: *mut c_double,
+    ) -> c_int;
 }
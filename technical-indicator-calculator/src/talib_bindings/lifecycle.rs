@@ -0,0 +1,81 @@
+// Process-wide TA-Lib lifecycle
+//
+// TA_Initialize must run exactly once before any other TA-Lib function and TA_Shutdown
+// must not run while a calculation is in flight. Every FFI call goes through `with_talib`,
+// which initializes the library on first use and holds a read lock for the duration of
+// the call; the shutdown guard takes the write lock, so it waits for running calls.
+use crate::error::Result;
+use crate::talib_bindings::checked::TaLibError;
+use crate::talib_bindings::ffi;
+use parking_lot::RwLock;
+use std::os::raw::c_int;
+use std::sync::OnceLock;
+use tracing::{debug, warn};
+
+// Return code of the one TA_Initialize call
+static INITIALIZED: OnceLock<c_int> = OnceLock::new();
+
+// True once TA_Shutdown has run; calls fail from then on
+static SHUT_DOWN: RwLock<bool> = parking_lot::const_rwlock(false);
+
+// Initialize TA-Lib unless that already happened, returning the original outcome
+fn ensure_initialized() -> Result<()> {
+    let ret_code = *INITIALIZED.get_or_init(|| {
+        let ret_code = unsafe { ffi::TA_Initialize() };
+        if ret_code == ffi::TA_SUCCESS {
+            debug!("TA-Lib initialized");
+        }
+        ret_code
+    });
+
+    if ret_code == ffi::TA_SUCCESS {
+        Ok(())
+    } else {
+        Err(TaLibError::InitializeFailed { code: ret_code }.into())
+    }
+}
+
+/// Run a calculation that calls into TA-Lib, initializing the library first if needed
+pub(crate) fn with_talib<T>(calculate: impl FnOnce() -> Result<T>) -> Result<T> {
+    let shut_down = SHUT_DOWN.read();
+    if *shut_down {
+        return Err(TaLibError::ShutDown.into());
+    }
+
+    ensure_initialized()?;
+    calculate()
+}
+
+/// Shuts TA-Lib down when dropped
+///
+/// Keep one guard alive for as long as the process calculates indicators; calls made
+/// after it is dropped fail instead of touching a shut down library.
+#[must_use = "TA-Lib is shut down as soon as the guard is dropped"]
+pub struct TaLibGuard {
+    _private: (),
+}
+
+impl TaLibGuard {
+    pub(crate) fn acquire() -> Result<Self> {
+        ensure_initialized()?;
+        Ok(Self { _private: () })
+    }
+}
+
+impl Drop for TaLibGuard {
+    fn drop(&mut self) {
+        // Waits for calculations still holding the read lock
+        let mut shut_down = SHUT_DOWN.write();
+        if *shut_down {
+            return;
+        }
+
+        let ret_code = unsafe { ffi::TA_Shutdown() };
+        if ret_code == ffi::TA_SUCCESS {
+            debug!("TA-Lib shut down");
+        } else {
+            warn!("TA_Shutdown returned {}", ret_code);
+        }
+        *shut_down = true;
+    }
+}
@@ -1,11 +1,17 @@
 // Main module file for TA-Lib bindings
 mod ffi;
+mod checked;
 mod common;
+mod lifecycle;
 mod oscillators;
 mod overlaps;
 mod patterns;
+mod statistics;
 mod volume;
 mod volatility;
 
 // Re-export the main interface
+pub use checked::{nan_policy, set_nan_policy, NanPolicy, TaLibError};
 pub use common::TaLibAbstract;
+pub use lifecycle::TaLibGuard;
+pub use statistics::StatisticFunctions;
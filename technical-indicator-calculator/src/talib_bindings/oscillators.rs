@@ -1,8 +1,10 @@
 // Oscillator indicators implementation
+use crate::error::{Error, Result};
+use crate::indicators::output::{IndicatorOutput, MacdValue, StochValue};
+use crate::talib_bindings::checked;
 use crate::talib_bindings::ffi;
 use crate::talib_bindings::common::TaLibAbstract;
-use anyhow::{anyhow, Result};
-use serde_json::{json, Value};
+use serde_json::Value;
 use std::os::raw::c_int;
 
 pub struct OscillatorIndicators;
@@ -12,7 +14,7 @@ impl OscillatorIndicators {
     pub fn calculate_rsi(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -37,25 +39,16 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_RSI, error code: {}", ret_code));
-        }
-        
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
+        let (begin, count) = checked::check_output("TA_RSI", ret_code, out_beg_idx, out_nb_element, close.len(), out_data.len())?;
         
-        Ok(results)
+        Ok(checked::collect_real("TA_RSI", begin, count, &out_data)?)
     }
 
     // Calculate MACD
     pub fn calculate_macd(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -88,19 +81,20 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_MACD, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_MACD", ret_code, out_beg_idx, out_nb_element, close.len(), out_macd.len())?;
         
         // Create result vector with all three output values
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
+        let mut results = Vec::with_capacity(count);
+        for i in 0..count {
+            let original_idx = begin + i;
+            if !checked::keep_row("TA_MACD", original_idx, &[out_macd[i], out_signal[i], out_hist[i]])? {
+                continue;
+            }
             
-            let macd_value = json!({
-                "macd": out_macd[i],
-                "signal": out_signal[i],
-                "histogram": out_hist[i],
+            let macd_value = IndicatorOutput::Macd(MacdValue {
+                macd: out_macd[i],
+                signal: out_signal[i],
+                histogram: out_hist[i],
             });
             
             results.push((original_idx, macd_value));
@@ -115,7 +109,7 @@ impl OscillatorIndicators {
         low: &[f64],
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
@@ -123,7 +117,7 @@ impl OscillatorIndicators {
         // Validate input lengths
         let data_len = high.len();
         if low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
 
         let k_period = TaLibAbstract::get_integer_param(parameters, "k_period", 14)?;
@@ -157,18 +151,19 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_STOCH, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_STOCH", ret_code, out_beg_idx, out_nb_element, data_len, out_k.len())?;
         
         // Create result vector with both K and D
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
+        let mut results = Vec::with_capacity(count);
+        for i in 0..count {
+            let original_idx = begin + i;
+            if !checked::keep_row("TA_STOCH", original_idx, &[out_k[i], out_d[i]])? {
+                continue;
+            }
             
-            let stoch_value = json!({
-                "k": out_k[i],
-                "d": out_d[i],
+            let stoch_value = IndicatorOutput::Stoch(StochValue {
+                k: out_k[i],
+                d: out_d[i],
             });
             
             results.push((original_idx, stoch_value));
@@ -183,7 +178,7 @@ impl OscillatorIndicators {
         low: &[f64],
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
@@ -191,7 +186,7 @@ impl OscillatorIndicators {
         // Validate input lengths
         let data_len = high.len();
         if low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
 
         let period = TaLibAbstract::get_integer_param(parameters, "period", 14)?;
@@ -216,25 +211,16 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_CCI, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_CCI", ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
         
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
-        
-        Ok(results)
+        Ok(checked::collect_real("TA_CCI", begin, count, &out_data)?)
     }
 
     // STOCHRSI - Stochastic RSI
     pub fn calculate_stoch_rsi(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -267,18 +253,19 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_STOCHRSI, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_STOCHRSI", ret_code, out_beg_idx, out_nb_element, close.len(), out_k.len())?;
         
         // Create result vector with both K and D
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
+        let mut results = Vec::with_capacity(count);
+        for i in 0..count {
+            let original_idx = begin + i;
+            if !checked::keep_row("TA_STOCHRSI", original_idx, &[out_k[i], out_d[i]])? {
+                continue;
+            }
             
-            let stochrsi_value = json!({
-                "k": out_k[i],
-                "d": out_d[i],
+            let stochrsi_value = IndicatorOutput::Stoch(StochValue {
+                k: out_k[i],
+                d: out_d[i],
             });
             
             results.push((original_idx, stochrsi_value));
@@ -291,7 +278,7 @@ impl OscillatorIndicators {
     pub fn calculate_momentum(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -316,18 +303,9 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_MOM, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_MOM", ret_code, out_beg_idx, out_nb_element, close.len(), out_data.len())?;
         
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
-        
-        Ok(results)
+        Ok(checked::collect_real("TA_MOM", begin, count, &out_data)?)
     }
 
     // MFI - Money Flow Index
@@ -337,7 +315,7 @@ impl OscillatorIndicators {
         close: &[f64],
         volume: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if high.is_empty() || low.is_empty() || close.is_empty() || volume.is_empty() {
             return Ok(vec![]);
         }
@@ -345,7 +323,7 @@ impl OscillatorIndicators {
         // Validate input lengths
         let data_len = high.len();
         if low.len() != data_len || close.len() != data_len || volume.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
 
         let period = TaLibAbstract::get_integer_param(parameters, "period", 14)?;
@@ -371,17 +349,8 @@ impl OscillatorIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_MFI, error code: {}", ret_code));
-        }
-        
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
+        let (begin, count) = checked::check_output("TA_MFI", ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
         
-        Ok(results)
+        Ok(checked::collect_real("TA_MFI", begin, count, &out_data)?)
     }
 }
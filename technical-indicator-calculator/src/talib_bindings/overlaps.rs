@@ -1,8 +1,10 @@
 // Overlap indicators implementation
+use crate::error::Result;
+use crate::indicators::output::{BbandsValue, IndicatorOutput};
+use crate::talib_bindings::checked;
 use crate::talib_bindings::ffi;
 use crate::talib_bindings::common::TaLibAbstract;
-use anyhow::{anyhow, Result};
-use serde_json::{json, Value};
+use serde_json::Value;
 use std::os::raw::c_int;
 
 pub struct OverlapIndicators;
@@ -12,7 +14,7 @@ impl OverlapIndicators {
     pub fn calculate_sma(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -37,25 +39,16 @@ impl OverlapIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_SMA, error code: {}", ret_code));
-        }
-        
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
+        let (begin, count) = checked::check_output("TA_SMA", ret_code, out_beg_idx, out_nb_element, close.len(), out_data.len())?;
         
-        Ok(results)
+        Ok(checked::collect_real("TA_SMA", begin, count, &out_data)?)
     }
 
     // Calculate EMA
     pub fn calculate_ema(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -80,25 +73,16 @@ impl OverlapIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_EMA, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_EMA", ret_code, out_beg_idx, out_nb_element, close.len(), out_data.len())?;
         
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
-        
-        Ok(results)
+        Ok(checked::collect_real("TA_EMA", begin, count, &out_data)?)
     }
 
     // Calculate Bollinger Bands
     pub fn calculate_bbands(
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() {
             return Ok(vec![]);
         }
@@ -133,14 +117,15 @@ impl OverlapIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_BBANDS, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_BBANDS", ret_code, out_beg_idx, out_nb_element, close.len(), out_upper.len())?;
         
         // Create result vector with all three bands
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
+        let mut results = Vec::with_capacity(count);
+        for i in 0..count {
+            let original_idx = begin + i;
+            if !checked::keep_row("TA_BBANDS", original_idx, &[out_upper[i], out_middle[i], out_lower[i]])? {
+                continue;
+            }
             
             // Calculate bandwidth
             let bandwidth = if out_middle[i] != 0.0 {
@@ -149,11 +134,11 @@ impl OverlapIndicators {
                 0.0
             };
             
-            let bbands_value = json!({
-                "upper": out_upper[i],
-                "middle": out_middle[i],
-                "lower": out_lower[i],
-                "width": bandwidth,
+            let bbands_value = IndicatorOutput::Bbands(BbandsValue {
+                upper: out_upper[i],
+                middle: out_middle[i],
+                lower: out_lower[i],
+                width: bandwidth,
             });
             
             results.push((original_idx, bbands_value));
@@ -1,9 +1,148 @@
 // Pattern indicators implementation
+use crate::database::models::candle_body_ratio;
+use crate::error::{Error, Result};
+use crate::indicators::output::{IndicatorOutput, PatternDirection, PatternHit};
+use crate::talib_bindings::checked;
 use crate::talib_bindings::ffi;
 use crate::talib_bindings::common::TaLibAbstract;
-use anyhow::{anyhow, Result};
-use serde_json::{json, Value};
-use std::os::raw::c_int;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::os::raw::{c_double, c_int};
+
+// Signature shared by TA-Lib pattern functions without optional inputs
+type CandlePatternFn = unsafe extern "C" fn(
+    c_int,
+    c_int,
+    *const c_double,
+    *const c_double,
+    *const c_double,
+    *const c_double,
+    *mut c_int,
+    *mut c_int,
+    *mut c_int,
+) -> c_int;
+
+// A TA-Lib pattern function without optional inputs
+struct CandlePattern {
+    function: &'static str,
+    pattern: &'static str,
+    ta_function: CandlePatternFn,
+    // Number of candles the pattern spans
+    candles: usize,
+    // Overrides the sign of TA-Lib's output for patterns that only occur one way or, like
+    // doji, have no direction
+    direction: Option<PatternDirection>,
+    // Reversal patterns are checked against the preceding trend
+    reversal: bool,
+}
+
+// Context a detected pattern must appear in, set through optional pattern parameters:
+//   trend_window      candles before the pattern whose closes define the trend; 0 (the
+//                     default) accepts patterns regardless of trend. Bullish reversals
+//                     need a downtrend, bearish reversals an uptrend.
+//   trend_threshold   minimum relative close change over the window, e.g. 0.02 for 2%
+//   min_body_ratio,   body of the pattern's last candle relative to its high-low range,
+//   max_body_ratio    between 0 and 1
+#[derive(Debug, Clone, Copy)]
+struct PatternContext {
+    trend_window: usize,
+    trend_threshold: f64,
+    min_body_ratio: f64,
+    max_body_ratio: f64,
+}
+
+impl PatternContext {
+    fn from_parameters(parameters: &[(String, Value)]) -> Result<Self> {
+        let trend_window = TaLibAbstract::get_integer_param(parameters, "trend_window", 0)?;
+        if trend_window < 0 {
+            return Err(Error::validation(format!("trend_window must not be negative, got {}", trend_window)));
+        }
+
+        let trend_threshold = TaLibAbstract::get_float_param(parameters, "trend_threshold", 0.0)?;
+        if trend_threshold.is_nan() || trend_threshold < 0.0 {
+            return Err(Error::validation(format!("trend_threshold must not be negative, got {}", trend_threshold)));
+        }
+
+        let min_body_ratio = TaLibAbstract::get_float_param(parameters, "min_body_ratio", 0.0)?;
+        let max_body_ratio = TaLibAbstract::get_float_param(parameters, "max_body_ratio", 1.0)?;
+        if !(0.0..=1.0).contains(&min_body_ratio) || !(0.0..=1.0).contains(&max_body_ratio) || min_body_ratio > max_body_ratio {
+            return Err(Error::validation(format!(
+                "Body ratios must satisfy 0 <= min_body_ratio <= max_body_ratio <= 1, got {} and {}",
+                min_body_ratio, max_body_ratio
+            )));
+        }
+
+        Ok(Self {
+            trend_window: trend_window as usize,
+            trend_threshold,
+            min_body_ratio,
+            max_body_ratio,
+        })
+    }
+
+    // Relative close change over the trend window ending right before `start`
+    fn trend(&self, close: &[f64], start: usize) -> Option<f64> {
+        let last = start.checked_sub(1)?;
+        let first = last.checked_sub(self.trend_window)?;
+        if close[first] == 0.0 {
+            return None;
+        }
+        Some((close[last] - close[first]) / close[first])
+    }
+
+    // Whether a pattern ending at `idx` appears in the configured context
+    #[allow(clippy::too_many_arguments)]
+    fn accepts(
+        &self,
+        hit: &PatternHit,
+        reversal: bool,
+        candles: usize,
+        idx: usize,
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+    ) -> bool {
+        let body_ratio = candle_body_ratio(open[idx], high[idx], low[idx], close[idx]);
+        if body_ratio < self.min_body_ratio || body_ratio > self.max_body_ratio {
+            return false;
+        }
+
+        if !reversal || self.trend_window == 0 {
+            return true;
+        }
+
+        // Without enough history the trend is unknown and the pattern is dropped
+        let Some(trend) = self.trend(close, (idx + 1).saturating_sub(candles)) else {
+            return false;
+        };
+        match hit.direction {
+            PatternDirection::Bullish => trend < -self.trend_threshold,
+            PatternDirection::Bearish => trend > self.trend_threshold,
+            // Nothing to reverse
+            PatternDirection::Neutral => true,
+        }
+    }
+}
+
+// Signature of the pattern calculations below
+type PatternFn = fn(&[f64], &[f64], &[f64], &[f64], &[(String, Value)]) -> Result<Vec<(usize, IndicatorOutput)>>;
+
+// Patterns combined by the confluence score, by TA-Lib function name
+const CONFLUENCE_PATTERNS: [(&str, PatternFn); 12] = [
+    ("CDLENGULFING", PatternIndicators::calculate_cdl_engulfing),
+    ("CDLHAMMER", PatternIndicators::calculate_cdl_hammer),
+    ("CDLMORNINGSTAR", PatternIndicators::calculate_cdl_morning_star),
+    ("CDLDOJI", PatternIndicators::calculate_cdl_doji),
+    ("CDLDRAGONFLYDOJI", PatternIndicators::calculate_cdl_dragonfly_doji),
+    ("CDLGRAVESTONEDOJI", PatternIndicators::calculate_cdl_gravestone_doji),
+    ("CDLLONGLEGGEDDOJI", PatternIndicators::calculate_cdl_long_legged_doji),
+    ("CDLSPINNINGTOP", PatternIndicators::calculate_cdl_spinning_top),
+    ("CDLMARUBOZU", PatternIndicators::calculate_cdl_marubozu),
+    ("CDLSHOOTINGSTAR", PatternIndicators::calculate_cdl_shooting_star),
+    ("CDLINVERTEDHAMMER", PatternIndicators::calculate_cdl_inverted_hammer),
+    ("CDLHANGINGMAN", PatternIndicators::calculate_cdl_hanging_man),
+];
 
 pub struct PatternIndicators;
 
@@ -14,8 +153,45 @@ impl PatternIndicators {
         high: &[f64],
         low: &[f64],
         close: &[f64],
-        _parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLENGULFING",
+            pattern: "ENGULFING",
+            ta_function: ffi::TA_CDLENGULFING,
+            candles: 2,
+            direction: None,
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Hammer Pattern
+    pub fn calculate_cdl_hammer(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Hammer is a bullish pattern
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLHAMMER",
+            pattern: "HAMMER",
+            ta_function: ffi::TA_CDLHAMMER,
+            candles: 1,
+            direction: Some(PatternDirection::Bullish),
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Morning Star Pattern
+    pub fn calculate_cdl_morning_star(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if open.is_empty() || high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
@@ -23,62 +199,236 @@ impl PatternIndicators {
         // Validate input lengths
         let data_len = open.len();
         if high.len() != data_len || low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
         
+        // Get the penetration parameter (usually between 0.0 and 1.0)
+        let penetration = TaLibAbstract::get_float_param(parameters, "penetration", 0.3)?;
+        let context = PatternContext::from_parameters(parameters)?;
+        
         // Prepare output arrays
         let mut out_beg_idx: c_int = 0;
         let mut out_nb_element: c_int = 0;
         let mut out_data = vec![0; data_len];
         
-        // Call TA-Lib CDLENGULFING function
+        // Call TA-Lib CDLMORNINGSTAR function
         let ret_code = unsafe {
-            ffi::TA_CDLENGULFING(
+            ffi::TA_CDLMORNINGSTAR(
                 0, // startIdx
                 (data_len - 1) as c_int, // endIdx
                 open.as_ptr(),
                 high.as_ptr(),
                 low.as_ptr(),
                 close.as_ptr(),
+                penetration,
                 &mut out_beg_idx,
                 &mut out_nb_element,
                 out_data.as_mut_ptr(),
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_CDLENGULFING, error code: {}", ret_code));
-        }
+        let (begin, count) = checked::check_output("TA_CDLMORNINGSTAR", ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
         
         // Create result vector - pattern recognition returns integers
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
+        let mut results = Vec::with_capacity(count);
+        for i in 0..count {
+            let original_idx = begin + i;
             
             // Non-zero values indicate pattern detected
-            // Convert to a meaningful JSON structure
             if out_data[i] != 0 {
-                let pattern_value = json!({
-                    "pattern": "ENGULFING",
-                    "type": if out_data[i] > 0 { "bullish" } else { "bearish" },
-                    "strength": out_data[i].abs() as f64 / 100.0,
-                });
+                // Morning Star is a bullish pattern
+                let hit = PatternHit::from_talib("MORNINGSTAR", out_data[i], Some(PatternDirection::Bullish));
                 
-                results.push((original_idx, pattern_value));
+                if context.accepts(&hit, true, 3, original_idx, open, high, low, close) {
+                    results.push((original_idx, IndicatorOutput::Pattern(hit)));
+                }
             }
         }
         
         Ok(results)
     }
 
-    // Calculate Hammer Pattern
-    pub fn calculate_cdl_hammer(
+    // Calculate Doji Pattern
+    pub fn calculate_cdl_doji(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // TA-Lib reports doji as +100; they only mark indecision, so they are neutral and
+        // strategies should combine them with trend context
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLDOJI",
+            pattern: "DOJI",
+            ta_function: ffi::TA_CDLDOJI,
+            candles: 1,
+            direction: Some(PatternDirection::Neutral),
+            reversal: false,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Dragonfly Doji Pattern
+    pub fn calculate_cdl_dragonfly_doji(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Dragonfly doji (long lower shadow) is a bullish reversal
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLDRAGONFLYDOJI",
+            pattern: "DRAGONFLYDOJI",
+            ta_function: ffi::TA_CDLDRAGONFLYDOJI,
+            candles: 1,
+            direction: Some(PatternDirection::Bullish),
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Gravestone Doji Pattern
+    pub fn calculate_cdl_gravestone_doji(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Gravestone doji (long upper shadow) is a bearish reversal; TA-Lib reports it as +100
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLGRAVESTONEDOJI",
+            pattern: "GRAVESTONEDOJI",
+            ta_function: ffi::TA_CDLGRAVESTONEDOJI,
+            candles: 1,
+            direction: Some(PatternDirection::Bearish),
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Long Legged Doji Pattern
+    pub fn calculate_cdl_long_legged_doji(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Like doji, long legged doji are reported as +100 and are neutral
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLLONGLEGGEDDOJI",
+            pattern: "LONGLEGGEDDOJI",
+            ta_function: ffi::TA_CDLLONGLEGGEDDOJI,
+            candles: 1,
+            direction: Some(PatternDirection::Neutral),
+            reversal: false,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Spinning Top Pattern
+    pub fn calculate_cdl_spinning_top(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Direction follows the candle colour
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLSPINNINGTOP",
+            pattern: "SPINNINGTOP",
+            ta_function: ffi::TA_CDLSPINNINGTOP,
+            candles: 1,
+            direction: None,
+            reversal: false,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Marubozu Pattern
+    pub fn calculate_cdl_marubozu(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Direction follows the candle colour
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLMARUBOZU",
+            pattern: "MARUBOZU",
+            ta_function: ffi::TA_CDLMARUBOZU,
+            candles: 1,
+            direction: None,
+            reversal: false,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Shooting Star Pattern
+    pub fn calculate_cdl_shooting_star(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Shooting Star is a bearish pattern
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLSHOOTINGSTAR",
+            pattern: "SHOOTINGSTAR",
+            ta_function: ffi::TA_CDLSHOOTINGSTAR,
+            candles: 1,
+            direction: Some(PatternDirection::Bearish),
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Inverted Hammer Pattern
+    pub fn calculate_cdl_inverted_hammer(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Inverted Hammer is a bullish pattern
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLINVERTEDHAMMER",
+            pattern: "INVERTEDHAMMER",
+            ta_function: ffi::TA_CDLINVERTEDHAMMER,
+            candles: 1,
+            direction: Some(PatternDirection::Bullish),
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Calculate Hanging Man Pattern
+    pub fn calculate_cdl_hanging_man(
+        open: &[f64],
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        // Hanging Man is a bearish pattern
+        Self::calculate_candle_pattern(&CandlePattern {
+            function: "TA_CDLHANGINGMAN",
+            pattern: "HANGINGMAN",
+            ta_function: ffi::TA_CDLHANGINGMAN,
+            candles: 1,
+            direction: Some(PatternDirection::Bearish),
+            reversal: true,
+        }, open, high, low, close, parameters)
+    }
+
+    // Run a pattern function without optional inputs and keep the hits in context
+    fn calculate_candle_pattern(
+        spec: &CandlePattern,
         open: &[f64],
         high: &[f64],
         low: &[f64],
         close: &[f64],
-        _parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+        parameters: &[(String, Value)],
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if open.is_empty() || high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
@@ -86,17 +436,18 @@ impl PatternIndicators {
         // Validate input lengths
         let data_len = open.len();
         if high.len() != data_len || low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
-        
+
+        let context = PatternContext::from_parameters(parameters)?;
+
         // Prepare output arrays
         let mut out_beg_idx: c_int = 0;
         let mut out_nb_element: c_int = 0;
         let mut out_data = vec![0; data_len];
-        
-        // Call TA-Lib CDLHAMMER function
+
         let ret_code = unsafe {
-            ffi::TA_CDLHAMMER(
+            (spec.ta_function)(
                 0, // startIdx
                 (data_len - 1) as c_int, // endIdx
                 open.as_ptr(),
@@ -108,96 +459,117 @@ impl PatternIndicators {
                 out_data.as_mut_ptr(),
             )
         };
-        
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_CDLHAMMER, error code: {}", ret_code));
-        }
-        
-        // Create result vector - pattern recognition returns integers
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            
-            // Non-zero values indicate pattern detected
-            // Convert to a meaningful JSON structure
-            if out_data[i] != 0 {
-                let pattern_value = json!({
-                    "pattern": "HAMMER",
-                    "type": "bullish", // Hammer is a bullish pattern
-                    "strength": out_data[i].abs() as f64 / 100.0,
-                });
-                
-                results.push((original_idx, pattern_value));
-            }
-        }
-        
+
+        let (begin, count) = checked::check_output(spec.function, ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
+
+        // Non-zero values indicate pattern detected
+        let results = out_data[..count]
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != 0)
+            .filter_map(|(i, value)| {
+                let hit = PatternHit::from_talib(spec.pattern, *value, spec.direction);
+                let idx = begin + i;
+                context.accepts(&hit, spec.reversal, spec.candles, idx, open, high, low, close)
+                    .then(|| (idx, IndicatorOutput::Pattern(hit)))
+            })
+            .collect();
+
         Ok(results)
     }
 
-    // Calculate Morning Star Pattern
-    pub fn calculate_cdl_morning_star(
+    // Calculate the pattern confluence score
+    //
+    // Runs the patterns listed in the "patterns" parameter (all by default, names as in
+    // indicator_config) and sums the strength of their hits over the last `window` candles,
+    // weighted by `decay` per candle of age. Emits bullish, bearish and score (bullish
+    // minus bearish) for every candle; neutral hits such as doji count for neither side.
+    // Other parameters (penetration, trend context) are passed to each pattern.
+    pub fn calculate_pattern_confluence(
         open: &[f64],
         high: &[f64],
         low: &[f64],
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if open.is_empty() || high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
 
-        // Validate input lengths
-        let data_len = open.len();
-        if high.len() != data_len || low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+        let window = TaLibAbstract::get_integer_param(parameters, "window", 3)?;
+        if window < 1 {
+            return Err(Error::validation(format!("window must be at least 1, got {}", window)));
         }
-        
-        // Get the penetration parameter (usually between 0.0 and 1.0)
-        let penetration = TaLibAbstract::get_float_param(parameters, "penetration", 0.3)?;
-        
-        // Prepare output arrays
-        let mut out_beg_idx: c_int = 0;
-        let mut out_nb_element: c_int = 0;
-        let mut out_data = vec![0; data_len];
-        
-        // Call TA-Lib CDLMORNINGSTAR function
-        let ret_code = unsafe {
-            ffi::TA_CDLMORNINGSTAR(
-                0, // startIdx
-                (data_len - 1) as c_int, // endIdx
-                open.as_ptr(),
-                high.as_ptr(),
-                low.as_ptr(),
-                close.as_ptr(),
-                penetration,
-                &mut out_beg_idx,
-                &mut out_nb_element,
-                out_data.as_mut_ptr(),
-            )
-        };
-        
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_CDLMORNINGSTAR, error code: {}", ret_code));
+        let window = window as usize;
+
+        let decay = TaLibAbstract::get_float_param(parameters, "decay", 0.5)?;
+        if !(0.0..=1.0).contains(&decay) {
+            return Err(Error::validation(format!("decay must be between 0 and 1, got {}", decay)));
         }
-        
-        // Create result vector - pattern recognition returns integers
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            
-            // Non-zero values indicate pattern detected
-            // Convert to a meaningful JSON structure
-            if out_data[i] != 0 {
-                let pattern_value = json!({
-                    "pattern": "MORNINGSTAR",
-                    "type": "bullish", // Morning Star is a bullish pattern
-                    "strength": out_data[i].abs() as f64 / 100.0,
-                });
-                
-                results.push((original_idx, pattern_value));
+
+        let data_len = close.len();
+        let mut bullish = vec![0.0; data_len];
+        let mut bearish = vec![0.0; data_len];
+
+        for calculate in Self::confluence_patterns(parameters)? {
+            for (idx, output) in calculate(open, high, low, close, parameters)? {
+                let IndicatorOutput::Pattern(hit) = output else {
+                    continue;
+                };
+                let scores = match hit.direction {
+                    PatternDirection::Bullish => &mut bullish,
+                    PatternDirection::Bearish => &mut bearish,
+                    PatternDirection::Neutral => continue,
+                };
+                // A hit counts on its own candle and fades over the following ones
+                let mut weight = hit.strength;
+                for score in scores.iter_mut().skip(idx).take(window) {
+                    *score += weight;
+                    weight *= decay;
+                }
             }
         }
-        
+
+        let results = bullish
+            .into_iter()
+            .zip(bearish)
+            .enumerate()
+            .map(|(idx, (bullish, bearish))| {
+                let values = BTreeMap::from([
+                    ("bullish".to_string(), bullish),
+                    ("bearish".to_string(), bearish),
+                    ("score".to_string(), bullish - bearish),
+                ]);
+                (idx, IndicatorOutput::Values(values))
+            })
+            .collect();
+
         Ok(results)
     }
+
+    // Pattern calculations selected by the "patterns" parameter
+    fn confluence_patterns(parameters: &[(String, Value)]) -> Result<Vec<PatternFn>> {
+        let Some((_, selected)) = parameters.iter().find(|(name, _)| name == "patterns") else {
+            return Ok(CONFLUENCE_PATTERNS.iter().map(|(_, calculate)| *calculate).collect());
+        };
+
+        let Value::Array(selected) = selected else {
+            return Err(Error::validation("patterns must be a list of pattern names"));
+        };
+
+        selected
+            .iter()
+            .map(|name| {
+                let name = name
+                    .as_str()
+                    .ok_or_else(|| Error::validation("patterns must be a list of pattern names"))?;
+                let function_name = TaLibAbstract::get_function_name(name);
+                CONFLUENCE_PATTERNS
+                    .iter()
+                    .find(|(pattern, _)| *pattern == function_name)
+                    .map(|(_, calculate)| *calculate)
+                    .ok_or_else(|| Error::validation(format!("{} is not a candlestick pattern", name)))
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,170 @@
+// Statistic functions implementation
+//
+// LINEARREG, ZSCORE, CORREL and BETA are calculated in Rust (indicators::statistics), so
+// these calls aren't dispatched by name; they give the differential tests TA-Lib's values
+// to check the Rust code against.
+use crate::error::Result;
+use crate::indicators::output::IndicatorOutput;
+use crate::talib_bindings::checked;
+use crate::talib_bindings::ffi;
+use crate::talib_bindings::lifecycle::with_talib;
+use std::os::raw::{c_double, c_int};
+
+// Signature shared by TA-Lib functions of one series and a period
+type RealFn = unsafe extern "C" fn(
+    c_int,
+    c_int,
+    *const c_double,
+    c_int,
+    *mut c_int,
+    *mut c_int,
+    *mut c_double,
+) -> c_int;
+
+// Signature shared by TA-Lib functions of two series and a period
+type PairFn = unsafe extern "C" fn(
+    c_int,
+    c_int,
+    *const c_double,
+    *const c_double,
+    c_int,
+    *mut c_int,
+    *mut c_int,
+    *mut c_double,
+) -> c_int;
+
+pub struct StatisticFunctions;
+
+impl StatisticFunctions {
+    /// LINEARREG: the regression line at the last value of each window
+    pub fn linearreg(real: &[f64], period: usize) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_real("TA_LINEARREG", ffi::TA_LINEARREG, real, period)
+    }
+
+    /// LINEARREG_SLOPE: change of the regression line per value
+    pub fn linearreg_slope(real: &[f64], period: usize) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_real("TA_LINEARREG_SLOPE", ffi::TA_LINEARREG_SLOPE, real, period)
+    }
+
+    /// LINEARREG_INTERCEPT: the regression line at the first value of each window
+    pub fn linearreg_intercept(real: &[f64], period: usize) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_real("TA_LINEARREG_INTERCEPT", ffi::TA_LINEARREG_INTERCEPT, real, period)
+    }
+
+    /// TSF: the regression line one value after each window
+    pub fn tsf(real: &[f64], period: usize) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_real("TA_TSF", ffi::TA_TSF, real, period)
+    }
+
+    /// STDDEV: population standard deviation of each window, times `deviations`
+    pub fn stddev(real: &[f64], period: usize, deviations: f64) -> Result<Vec<(usize, IndicatorOutput)>> {
+        if real.is_empty() {
+            return Ok(vec![]);
+        }
+
+        with_talib(|| {
+            let mut out_beg_idx: c_int = 0;
+            let mut out_nb_element: c_int = 0;
+            let mut out_data = vec![0.0; real.len()];
+
+            let ret_code = unsafe {
+                ffi::TA_STDDEV(
+                    0, // startIdx
+                    (real.len() - 1) as c_int, // endIdx
+                    real.as_ptr(),
+                    period as c_int,
+                    deviations,
+                    &mut out_beg_idx,
+                    &mut out_nb_element,
+                    out_data.as_mut_ptr(),
+                )
+            };
+
+            let (begin, count) = checked::check_output("TA_STDDEV", ret_code, out_beg_idx, out_nb_element, real.len(), out_data.len())?;
+
+            Ok(checked::collect_real("TA_STDDEV", begin, count, &out_data)?)
+        })
+    }
+
+    /// CORREL: Pearson correlation of two aligned series
+    pub fn correl(real0: &[f64], real1: &[f64], period: usize) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_pair("TA_CORREL", ffi::TA_CORREL, real0, real1, period)
+    }
+
+    /// BETA: beta of the returns of `real1` on those of `real0`, TA-Lib taking prices and
+    /// calculating the returns itself
+    pub fn beta(real0: &[f64], real1: &[f64], period: usize) -> Result<Vec<(usize, IndicatorOutput)>> {
+        Self::calculate_pair("TA_BETA", ffi::TA_BETA, real0, real1, period)
+    }
+
+    // Call a function of one series and a period over the whole series
+    fn calculate_real(
+        function: &'static str,
+        ta_function: RealFn,
+        real: &[f64],
+        period: usize,
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        if real.is_empty() {
+            return Ok(vec![]);
+        }
+
+        with_talib(|| {
+            let mut out_beg_idx: c_int = 0;
+            let mut out_nb_element: c_int = 0;
+            let mut out_data = vec![0.0; real.len()];
+
+            let ret_code = unsafe {
+                ta_function(
+                    0, // startIdx
+                    (real.len() - 1) as c_int, // endIdx
+                    real.as_ptr(),
+                    period as c_int,
+                    &mut out_beg_idx,
+                    &mut out_nb_element,
+                    out_data.as_mut_ptr(),
+                )
+            };
+
+            let (begin, count) = checked::check_output(function, ret_code, out_beg_idx, out_nb_element, real.len(), out_data.len())?;
+
+            Ok(checked::collect_real(function, begin, count, &out_data)?)
+        })
+    }
+
+    // Call a function of two series and a period over their common length
+    fn calculate_pair(
+        function: &'static str,
+        ta_function: PairFn,
+        real0: &[f64],
+        real1: &[f64],
+        period: usize,
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
+        let data_len = real0.len().min(real1.len());
+        if data_len == 0 {
+            return Ok(vec![]);
+        }
+
+        with_talib(|| {
+            let mut out_beg_idx: c_int = 0;
+            let mut out_nb_element: c_int = 0;
+            let mut out_data = vec![0.0; data_len];
+
+            let ret_code = unsafe {
+                ta_function(
+                    0, // startIdx
+                    (data_len - 1) as c_int, // endIdx
+                    real0.as_ptr(),
+                    real1.as_ptr(),
+                    period as c_int,
+                    &mut out_beg_idx,
+                    &mut out_nb_element,
+                    out_data.as_mut_ptr(),
+                )
+            };
+
+            let (begin, count) = checked::check_output(function, ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
+
+            Ok(checked::collect_real(function, begin, count, &out_data)?)
+        })
+    }
+}
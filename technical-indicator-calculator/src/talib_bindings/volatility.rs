@@ -1,7 +1,9 @@
 // Volatility indicators implementation
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::talib_bindings::checked;
 use crate::talib_bindings::ffi;
 use crate::talib_bindings::common::TaLibAbstract;
-use anyhow::{anyhow, Result};
 use serde_json::Value;
 use std::os::raw::c_int;
 
@@ -14,7 +16,7 @@ impl VolatilityIndicators {
         low: &[f64],
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
@@ -22,7 +24,7 @@ impl VolatilityIndicators {
         // Validate input lengths
         let data_len = high.len();
         if low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
 
         let period = TaLibAbstract::get_integer_param(parameters, "period", 14)?;
@@ -47,18 +49,9 @@ impl VolatilityIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_ATR, error code: {}", ret_code));
-        }
-        
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
+        let (begin, count) = checked::check_output("TA_ATR", ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
         
-        Ok(results)
+        Ok(checked::collect_real("TA_ATR", begin, count, &out_data)?)
     }
 
     // Calculate Average Directional Index (ADX)
@@ -67,7 +60,7 @@ impl VolatilityIndicators {
         low: &[f64],
         close: &[f64],
         parameters: &[(String, Value)],
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if high.is_empty() || low.is_empty() || close.is_empty() {
             return Ok(vec![]);
         }
@@ -75,7 +68,7 @@ impl VolatilityIndicators {
         // Validate input lengths
         let data_len = high.len();
         if low.len() != data_len || close.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
 
         let period = TaLibAbstract::get_integer_param(parameters, "period", 14)?;
@@ -100,17 +93,8 @@ impl VolatilityIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_ADX, error code: {}", ret_code));
-        }
-        
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
+        let (begin, count) = checked::check_output("TA_ADX", ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
         
-        Ok(results)
+        Ok(checked::collect_real("TA_ADX", begin, count, &out_data)?)
     }
 }
@@ -1,6 +1,8 @@
 // Volume indicators implementation
+use crate::error::{Error, Result};
+use crate::indicators::output::IndicatorOutput;
+use crate::talib_bindings::checked;
 use crate::talib_bindings::ffi;
-use anyhow::{anyhow, Result};
 use serde_json::Value;
 use std::os::raw::c_int;
 
@@ -12,7 +14,7 @@ impl VolumeIndicators {
         close: &[f64],
         volume: &[f64],
         _parameters: &[(String, Value)],  // Fixed parameter name
-    ) -> Result<Vec<(usize, Value)>> {
+    ) -> Result<Vec<(usize, IndicatorOutput)>> {
         if close.is_empty() || volume.is_empty() {
             return Ok(vec![]);
         }
@@ -20,7 +22,7 @@ impl VolumeIndicators {
         // Validate input lengths
         let data_len = close.len();
         if volume.len() != data_len {
-            return Err(anyhow!("Input arrays must have the same length"));
+            return Err(Error::validation("Input arrays must have the same length"));
         }
         
         // Prepare output arrays
@@ -41,17 +43,8 @@ impl VolumeIndicators {
             )
         };
         
-        if ret_code != ffi::TA_SUCCESS {
-            return Err(anyhow!("Failed to call TA_OBV, error code: {}", ret_code));
-        }
-        
-        // Create result vector
-        let mut results = Vec::with_capacity(out_nb_element as usize);
-        for i in 0..out_nb_element as usize {
-            let original_idx = out_beg_idx as usize + i;
-            results.push((original_idx, Value::from(out_data[i])));
-        }
+        let (begin, count) = checked::check_output("TA_OBV", ret_code, out_beg_idx, out_nb_element, data_len, out_data.len())?;
         
-        Ok(results)
+        Ok(checked::collect_real("TA_OBV", begin, count, &out_data)?)
     }
 }
@@ -0,0 +1,125 @@
+// Text charts for the terminal
+//
+// Line charts drawn with plain characters, so price, indicator and equity series can be
+// checked on a headless server without exporting them to a plotting tool. Series longer
+// than the chart is wide are resampled to one point per column (the last value in the
+// column's range of the series).
+use std::fmt::Write;
+
+// Width of the y-axis labels
+const LABEL_WIDTH: usize = 12;
+
+/// Markers for overlays, in the order series are added after the first one
+pub const OVERLAY_MARKERS: [char; 6] = ['o', '+', 'x', '#', '@', '%'];
+
+/// A series to draw; all series of a chart have one value per x position
+pub struct ChartSeries<'a> {
+    pub label: &'a str,
+    pub marker: char,
+    pub values: &'a [Option<f64>],
+}
+
+/// Chart width for the terminal: $COLUMNS minus the axis, 80 columns when unknown
+pub fn terminal_width() -> usize {
+    let columns = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(80 + LABEL_WIDTH + 2);
+    columns.saturating_sub(LABEL_WIDTH + 2).clamp(20, 400)
+}
+
+/// Draw series as a line chart `width` columns by `height` rows with a y-axis and legend
+///
+/// Later series are drawn over earlier ones. `x_labels` are printed under the first and
+/// last column. Returns an empty string when no series has a value.
+pub fn line_chart(series: &[ChartSeries], width: usize, height: usize, x_labels: Option<(&str, &str)>) -> String {
+    let len = series.iter().map(|s| s.values.len()).max().unwrap_or(0);
+    let (width, height) = (width.min(len).max(1), height.max(2));
+
+    let columns: Vec<Vec<Option<f64>>> = series.iter()
+        .map(|s| resample(s.values, width))
+        .collect();
+
+    let finite = columns.iter().flatten().flatten().copied().filter(|v| v.is_finite());
+    let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(v), max.max(v)));
+    if min > max {
+        return String::new();
+    }
+    // A flat series is drawn in the middle of the chart
+    let (min, max) = if min == max { (min - 1.0, max + 1.0) } else { (min, max) };
+
+    let mut grid = vec![vec![' '; width]; height];
+    for (s, values) in series.iter().zip(&columns) {
+        for (x, value) in values.iter().enumerate() {
+            if let Some(value) = value.filter(|v| v.is_finite()) {
+                let row = ((max - value) / (max - min) * (height - 1) as f64).round() as usize;
+                grid[row.min(height - 1)][x] = s.marker;
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (row, cells) in grid.iter().enumerate() {
+        // Label the top, middle and bottom rows
+        let label = if row == 0 || row == height - 1 || row == height / 2 {
+            format_value(max - (max - min) * row as f64 / (height - 1) as f64)
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "{:>width$} |{}", label, cells.iter().collect::<String>(), width = LABEL_WIDTH);
+    }
+    let _ = writeln!(out, "{:>width$} +{}", "", "-".repeat(width), width = LABEL_WIDTH);
+
+    if let Some((first, last)) = x_labels {
+        let gap = width.saturating_sub(first.len() + last.len()).max(1);
+        let _ = writeln!(out, "{:>width$}  {}{}{}", "", first, " ".repeat(gap), last, width = LABEL_WIDTH);
+    }
+
+    let legend: Vec<String> = series.iter().map(|s| format!("{} {}", s.marker, s.label)).collect();
+    let _ = writeln!(out, "{:>width$}  {}", "", legend.join("   "), width = LABEL_WIDTH);
+
+    out
+}
+
+/// One-line chart of a series using block characters, resampled to `width` characters
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let values: Vec<Option<f64>> = values.iter().map(|v| Some(*v).filter(|v| v.is_finite())).collect();
+    let values = resample(&values, width.min(values.len()).max(1));
+    let (min, max) = values.iter()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| (min.min(*v), max.max(*v)));
+
+    values.iter()
+        .map(|value| match value {
+            Some(v) if max > min => BLOCKS[(((v - min) / (max - min)) * 7.0).round() as usize],
+            Some(_) => BLOCKS[3],
+            None => ' ',
+        })
+        .collect()
+}
+
+// Last value in each of `width` equal ranges of the series
+fn resample(values: &[Option<f64>], width: usize) -> Vec<Option<f64>> {
+    if values.len() <= width {
+        return values.to_vec();
+    }
+
+    (0..width)
+        .map(|x| {
+            let (start, end) = (x * values.len() / width, (x + 1) * values.len() / width);
+            values[start..end].iter().rev().find_map(|v| *v)
+        })
+        .collect()
+}
+
+fn format_value(value: f64) -> String {
+    if value.abs() >= 100_000.0 {
+        format!("{:.0}", value)
+    } else if value.abs() >= 100.0 {
+        format!("{:.2}", value)
+    } else {
+        format!("{:.4}", value)
+    }
+}
@@ -0,0 +1,71 @@
+// Tracing subscriber set up from the [logging] and [telemetry] settings
+use crate::config::{LoggingSettings, TelemetrySettings};
+use crate::utils::telemetry;
+use anyhow::{anyhow, Result};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// Flushes log files and telemetry exporters when dropped
+pub struct TracingGuard {
+    _file: Option<WorkerGuard>,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        telemetry::shutdown();
+    }
+}
+
+/// Install the global tracing subscriber
+///
+/// Events are written to stdout and, with `directory` set, to rolling files in that
+/// directory; spans are also exported over OTLP when telemetry is configured. File
+/// lines and spans are written in the background; keep the returned guard alive until
+/// the process exits so buffered output is flushed.
+pub fn init_tracing(settings: &LoggingSettings, telemetry_settings: &TelemetrySettings) -> Result<TracingGuard> {
+    let json = match settings.format.as_str() {
+        "text" => false,
+        "json" => true,
+        other => return Err(anyhow!("Invalid log format '{}', expected text or json", other)),
+    };
+
+    let stdout = if json {
+        fmt::layer().json().boxed()
+    } else {
+        fmt::layer().boxed()
+    };
+
+    let (file, guard) = match &settings.directory {
+        Some(directory) => {
+            let appender = RollingFileAppender::new(parse_rotation(&settings.rotation)?, directory, &settings.file_prefix);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+
+            let layer = fmt::layer().with_writer(writer).with_ansi(false);
+            let layer = if json { layer.json().boxed() } else { layer.boxed() };
+            (Some(layer), Some(guard))
+        },
+        None => (None, None),
+    };
+
+    let registry = tracing_subscriber::registry()
+        .with(EnvFilter::new(&settings.level))
+        .with(stdout)
+        .with(file);
+    let otel = telemetry::layer(telemetry_settings)?;
+    registry.with(otel).try_init()?;
+
+    Ok(TracingGuard { _file: guard })
+}
+
+fn parse_rotation(rotation: &str) -> Result<Rotation> {
+    match rotation {
+        "minutely" => Ok(Rotation::MINUTELY),
+        "hourly" => Ok(Rotation::HOURLY),
+        "daily" => Ok(Rotation::DAILY),
+        "never" => Ok(Rotation::NEVER),
+        other => Err(anyhow!("Invalid log rotation '{}', expected minutely, hourly, daily or never", other)),
+    }
+}
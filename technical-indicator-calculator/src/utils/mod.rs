@@ -1,3 +1,10 @@
-// Expose log_utils module
-pub mod log_utils;
+#[cfg(feature = "native")]
+pub mod logging;
+#[cfg(feature = "native")]
 pub mod utils;
+#[cfg(feature = "native")]
+pub mod resources;
+#[cfg(feature = "native")]
+pub mod telemetry;
+pub mod chart;
+pub mod session;
@@ -0,0 +1,113 @@
+// Resource usage accounting for backtests and worker jobs
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Resources consumed by a single backtest or worker job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    /// Elapsed wall-clock time in milliseconds
+    pub wall_time_ms: u64,
+    /// Process CPU time (user + system) in milliseconds
+    pub cpu_time_ms: u64,
+    /// Peak resident memory of the process in bytes
+    pub peak_memory_bytes: u64,
+    /// Rows read from the database
+    pub rows_read: u64,
+    /// Rows written to the database
+    pub rows_written: u64,
+}
+
+impl ResourceUsage {
+    /// Format the usage for display
+    pub fn summary(&self) -> String {
+        format!(
+            "wall {:.2}s, cpu {:.2}s, peak memory {:.1} MB, {} rows read, {} rows written",
+            self.wall_time_ms as f64 / 1000.0,
+            self.cpu_time_ms as f64 / 1000.0,
+            self.peak_memory_bytes as f64 / (1024.0 * 1024.0),
+            self.rows_read,
+            self.rows_written,
+        )
+    }
+}
+
+/// Measures resource usage from creation until `finish` is called
+///
+/// CPU time and peak memory are process-wide, so runs that overlap with other
+/// jobs in the same process (e.g. concurrent worker jobs) include their share too.
+pub struct ResourceTracker {
+    started: Instant,
+    cpu_at_start: Duration,
+    rows_read: AtomicU64,
+    rows_written: AtomicU64,
+}
+
+impl ResourceTracker {
+    /// Start tracking
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            cpu_at_start: process_cpu_time(),
+            rows_read: AtomicU64::new(0),
+            rows_written: AtomicU64::new(0),
+        }
+    }
+
+    /// Record rows read from the database
+    pub fn add_rows_read(&self, rows: u64) {
+        self.rows_read.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    /// Record rows written to the database
+    pub fn add_rows_written(&self, rows: u64) {
+        self.rows_written.fetch_add(rows, Ordering::Relaxed);
+    }
+
+    /// Stop tracking and return the usage
+    pub fn finish(&self) -> ResourceUsage {
+        ResourceUsage {
+            wall_time_ms: self.started.elapsed().as_millis() as u64,
+            cpu_time_ms: process_cpu_time().saturating_sub(self.cpu_at_start).as_millis() as u64,
+            peak_memory_bytes: process_peak_memory(),
+            rows_read: self.rows_read.load(Ordering::Relaxed),
+            rows_written: self.rows_written.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// Get the rusage of the current process
+fn process_rusage() -> Option<libc::rusage> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    if result == 0 {
+        Some(usage)
+    } else {
+        None
+    }
+}
+
+// User and system CPU time consumed by the process so far
+fn process_cpu_time() -> Duration {
+    let to_duration = |tv: libc::timeval| {
+        Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+    };
+
+    process_rusage()
+        .map(|usage| to_duration(usage.ru_utime) + to_duration(usage.ru_stime))
+        .unwrap_or_default()
+}
+
+// Peak resident set size of the process in bytes
+fn process_peak_memory() -> u64 {
+    process_rusage()
+        .map(|usage| {
+            // Linux reports kilobytes, macOS reports bytes
+            if cfg!(target_os = "macos") {
+                usage.ru_maxrss as u64
+            } else {
+                usage.ru_maxrss as u64 * 1024
+            }
+        })
+        .unwrap_or(0)
+}
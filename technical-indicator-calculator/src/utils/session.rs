@@ -0,0 +1,185 @@
+// Trading session calendars
+//
+// A calendar gives a symbol's trading hours in its exchange's timezone, so daily
+// boundaries (session-anchored indicators, time-of-day rules) fall where the market's
+// day starts rather than at midnight UTC. Crypto trades around the clock and uses the
+// UTC day, the default for symbols without a calendar.
+//
+// A calendar is written as "<timezone> <open>-<close> <days>", e.g.
+// "America/New_York 09:30-16:00 mon-fri", or as one of the preset names. A session that
+// closes at or before it opens runs overnight and belongs to the trading day it closes
+// on: the "cme" session opening Sunday 17:00 is Monday's. Days are those trading days.
+use crate::error::{Error, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Named calendars accepted wherever a calendar is
+pub const SESSION_PRESETS: [(&str, &str); 5] = [
+    ("crypto", "UTC 00:00-00:00 mon-sun"),
+    ("nyse", "America/New_York 09:30-16:00 mon-fri"),
+    ("lse", "Europe/London 08:00-16:30 mon-fri"),
+    ("xetra", "Europe/Berlin 09:00-17:30 mon-fri"),
+    ("cme", "America/Chicago 17:00-16:00 mon-fri"),
+];
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Trading hours of a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SessionCalendar {
+    pub timezone: Tz,
+    /// Local time the session opens
+    pub open: NaiveTime,
+    /// Local time the session closes; equal to `open` for continuous trading
+    pub close: NaiveTime,
+    /// Trading days, Monday first
+    pub days: [bool; 7],
+}
+
+impl SessionCalendar {
+    /// Continuous trading on every UTC day
+    pub fn crypto() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            open: NaiveTime::MIN,
+            close: NaiveTime::MIN,
+            days: [true; 7],
+        }
+    }
+
+    /// Parse a preset name or "<timezone> <open>-<close> <days>"
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if let Some((_, preset)) = SESSION_PRESETS.iter().find(|(name, _)| name.eq_ignore_ascii_case(spec)) {
+            return Self::parse(preset);
+        }
+
+        let invalid = |reason: &str| Error::validation(format!(
+            "Invalid session '{}': {}; expected a preset ({}) or e.g. \"America/New_York 09:30-16:00 mon-fri\"",
+            spec, reason, SESSION_PRESETS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+        ));
+
+        let parts: Vec<&str> = spec.split_whitespace().collect();
+        let [timezone, hours, days] = parts[..] else {
+            return Err(invalid("expected a timezone, hours and days"));
+        };
+
+        let timezone = Tz::from_str(timezone).map_err(|_| invalid("unknown timezone"))?;
+
+        let (open, close) = hours.split_once('-').ok_or_else(|| invalid("hours must be open-close"))?;
+        let time = |t: &str| NaiveTime::parse_from_str(t, "%H:%M").map_err(|_| invalid("times must be HH:MM"));
+        let (open, close) = (time(open)?, time(close)?);
+
+        let day = |d: &str| WEEKDAYS.iter().position(|w| w.eq_ignore_ascii_case(d)).ok_or_else(|| invalid("unknown day"));
+        let mut trading_days = [false; 7];
+        for range in days.split(',') {
+            match range.split_once('-') {
+                Some((first, last)) => {
+                    let (first, last) = (day(first)?, day(last)?);
+                    // Ranges may wrap around the week, e.g. sun-thu
+                    let mut d = first;
+                    loop {
+                        trading_days[d] = true;
+                        if d == last {
+                            break;
+                        }
+                        d = (d + 1) % 7;
+                    }
+                },
+                None => trading_days[day(range)?] = true,
+            }
+        }
+
+        Ok(Self { timezone, open, close, days: trading_days })
+    }
+
+    /// Whether trading never pauses
+    pub fn is_continuous(&self) -> bool {
+        self.open == self.close && self.days.iter().all(|d| *d)
+    }
+
+    /// A time in the calendar's timezone
+    pub fn local(&self, time: DateTime<Utc>) -> DateTime<Tz> {
+        time.with_timezone(&self.timezone)
+    }
+
+    /// Trading day a time belongs to: the local date, or the next one from the open of
+    /// an overnight session
+    pub fn trading_date(&self, time: DateTime<Utc>) -> NaiveDate {
+        let local = self.local(time);
+        let overnight = self.close <= self.open && self.open != NaiveTime::MIN;
+
+        if overnight && local.time() >= self.open {
+            local.date_naive() + Duration::days(1)
+        } else {
+            local.date_naive()
+        }
+    }
+
+    /// Whether the market is open at a time
+    pub fn is_open(&self, time: DateTime<Utc>) -> bool {
+        let weekday = self.trading_date(time).weekday().num_days_from_monday() as usize;
+        if !self.days[weekday] {
+            return false;
+        }
+
+        let t = self.local(time).time();
+        if self.open < self.close {
+            self.open <= t && t < self.close
+        } else if self.open > self.close {
+            t >= self.open || t < self.close
+        } else {
+            true
+        }
+    }
+
+    /// Local time of day as decimal hours (8.5 = 08:30)
+    pub fn time_of_day(&self, time: DateTime<Utc>) -> f64 {
+        let local = self.local(time);
+        local.hour() as f64 + local.minute() as f64 / 60.0
+    }
+
+    /// Local weekday, 1 = Monday to 7 = Sunday
+    pub fn day_of_week(&self, time: DateTime<Utc>) -> f64 {
+        self.local(time).weekday().number_from_monday() as f64
+    }
+}
+
+impl Default for SessionCalendar {
+    fn default() -> Self {
+        Self::crypto()
+    }
+}
+
+impl fmt::Display for SessionCalendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days: Vec<&str> = (0..7).filter(|d| self.days[*d]).map(|d| WEEKDAYS[d]).collect();
+        // Runs of consecutive days starting on Monday print as a range
+        let first_gap = self.days.iter().position(|d| !d).unwrap_or(7);
+        let days = if days.len() > 1 && first_gap == days.len() {
+            format!("{}-{}", days[0], days[days.len() - 1])
+        } else {
+            days.join(",")
+        };
+
+        write!(f, "{} {}-{} {}", self.timezone.name(), self.open.format("%H:%M"), self.close.format("%H:%M"), days)
+    }
+}
+
+impl TryFrom<String> for SessionCalendar {
+    type Error = Error;
+
+    fn try_from(spec: String) -> Result<Self> {
+        Self::parse(&spec)
+    }
+}
+
+impl From<SessionCalendar> for String {
+    fn from(calendar: SessionCalendar) -> Self {
+        calendar.to_string()
+    }
+}
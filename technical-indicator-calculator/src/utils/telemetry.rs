@@ -0,0 +1,138 @@
+// OpenTelemetry export of tracing spans and worker job metrics over OTLP
+//
+// Built with the "otel" feature. Spans of the tracing subscriber (job processing,
+// database queries, TA-Lib calls) are exported to telemetry.otlp_endpoint and the
+// job counters below are pushed every metrics_interval_seconds. Without the feature
+// or without an endpoint everything here is a no-op.
+use crate::config::TelemetrySettings;
+use anyhow::Result;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use super::*;
+    use opentelemetry::metrics::{Counter, Histogram};
+    use opentelemetry::{global, KeyValue};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::trace::{self, Sampler};
+    use opentelemetry_sdk::{runtime, Resource};
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    const METER_NAME: &str = "technical-indicator-calculator";
+
+    static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+    static JOB_METRICS: OnceLock<JobMetrics> = OnceLock::new();
+
+    struct JobMetrics {
+        jobs: Counter<u64>,
+        duration: Histogram<f64>,
+        rows_written: Counter<u64>,
+    }
+
+    pub fn layer<S>(settings: &TelemetrySettings) -> Result<Option<Box<dyn Layer<S> + Send + Sync>>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        let endpoint = match settings.otlp_endpoint.as_deref().filter(|e| !e.is_empty()) {
+            Some(endpoint) => endpoint,
+            None => return Ok(None),
+        };
+        let resource = Resource::new(vec![KeyValue::new("service.name", settings.service_name.clone())]);
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_trace_config(
+                trace::config()
+                    .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(settings.sample_ratio))))
+                    .with_resource(resource.clone()),
+            )
+            .install_batch(runtime::Tokio)?;
+
+        let meter_provider = opentelemetry_otlp::new_pipeline()
+            .metrics(runtime::Tokio)
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+            .with_period(Duration::from_secs(settings.metrics_interval_seconds.max(1)))
+            .with_resource(resource)
+            .build()?;
+        global::set_meter_provider(meter_provider.clone());
+        let _ = METER_PROVIDER.set(meter_provider);
+
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer).boxed()))
+    }
+
+    pub fn record_job(pool: &str, indicator: &str, status: &str, wall_time_ms: u64, rows_written: u64) {
+        // Instruments are created on first use, after the meter provider is installed
+        let metrics = JOB_METRICS.get_or_init(|| {
+            let meter = global::meter(METER_NAME);
+            JobMetrics {
+                jobs: meter.u64_counter("worker.jobs")
+                    .with_description("Calculation jobs processed")
+                    .init(),
+                duration: meter.f64_histogram("worker.job.duration")
+                    .with_description("Wall time of calculation jobs")
+                    .with_unit(opentelemetry::metrics::Unit::new("s"))
+                    .init(),
+                rows_written: meter.u64_counter("worker.rows_written")
+                    .with_description("Indicator values written by calculation jobs")
+                    .init(),
+            }
+        });
+
+        let attributes = [
+            KeyValue::new("pool", pool.to_string()),
+            KeyValue::new("indicator", indicator.to_string()),
+            KeyValue::new("status", status.to_string()),
+        ];
+        metrics.jobs.add(1, &attributes);
+        metrics.duration.record(wall_time_ms as f64 / 1000.0, &attributes);
+        metrics.rows_written.add(rows_written, &attributes);
+    }
+
+    pub fn shutdown() {
+        global::shutdown_tracer_provider();
+        if let Some(provider) = METER_PROVIDER.get() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush OpenTelemetry metrics: {}", e);
+            }
+        }
+    }
+}
+
+/// Tracing layer exporting spans over OTLP; None when no endpoint is configured
+pub fn layer<S>(settings: &TelemetrySettings) -> Result<Option<Box<dyn Layer<S> + Send + Sync>>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    #[cfg(feature = "otel")]
+    {
+        otlp::layer(settings)
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        if settings.otlp_endpoint.as_deref().is_some_and(|e| !e.is_empty()) {
+            eprintln!("telemetry.otlp_endpoint is set but this build has no OpenTelemetry support (--features otel)");
+        }
+        Ok(None)
+    }
+}
+
+/// Count a processed calculation job
+pub fn record_job(pool: &str, indicator: &str, status: &str, wall_time_ms: u64, rows_written: u64) {
+    #[cfg(feature = "otel")]
+    otlp::record_job(pool, indicator, status, wall_time_ms, rows_written);
+
+    #[cfg(not(feature = "otel"))]
+    let _ = (pool, indicator, status, wall_time_ms, rows_written);
+}
+
+/// Flush and stop the exporters
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    otlp::shutdown();
+}
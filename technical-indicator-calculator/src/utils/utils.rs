@@ -42,6 +42,30 @@ pub fn format_timestamp(timestamp_ms: i64) -> String {
     format_time(&dt)
 }
 
+// Length of a Binance kline interval in seconds; monthly candles have no fixed length
+pub fn interval_to_seconds(interval: &str) -> Option<i64> {
+    let seconds = match interval {
+        "1s" => 1,
+        "1m" => 60,
+        "3m" => 3 * 60,
+        "5m" => 5 * 60,
+        "15m" => 15 * 60,
+        "30m" => 30 * 60,
+        "1h" => 60 * 60,
+        "2h" => 2 * 60 * 60,
+        "4h" => 4 * 60 * 60,
+        "6h" => 6 * 60 * 60,
+        "8h" => 8 * 60 * 60,
+        "12h" => 12 * 60 * 60,
+        "1d" => 24 * 60 * 60,
+        "3d" => 3 * 24 * 60 * 60,
+        "1w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    Some(seconds)
+}
+
 // Utility to truncate long strings for logging
 #[allow(dead_code)]
 pub fn truncate_string(s: &str, max_len: usize) -> String {
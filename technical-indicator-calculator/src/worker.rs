@@ -1,26 +1,34 @@
 // src/worker.rs
 use crate::cache::redis::RedisManager;
+use crate::config::AppConfig;
 use crate::database::postgres::PostgresManager;
-use crate::processor::worker::{Worker, WorkerConfig};
-use crate::talib_bindings::TaLibAbstract;
+use crate::events;
+use crate::processor::anomaly::AnomalySettings;
+use crate::processor::queue::SchedulingPolicy;
+use crate::processor::worker::{IntervalPoolConfig, Worker, WorkerConfig};
+use crate::talib_bindings::{set_nan_policy, NanPolicy, TaLibAbstract};
 use anyhow::Result;
 use num_cpus;
-use std::env;
 use std::sync::Arc;
 use tracing::{info, error};
 
 /// Start the worker process
-pub async fn start_worker(concurrency: Option<usize>) -> Result<()> {
+pub async fn start_worker(config: &AppConfig, concurrency: Option<usize>) -> Result<()> {
+    events::set_source("worker");
+    
     info!("Starting Technical Indicator Calculator with TA-Lib Direct Functions and Completeness Caching");
     
-    // Initialize TA-Lib
-    match TaLibAbstract::initialize() {
-        Ok(_) => info!("TA-Lib successfully initialized"),
+    // Initialize TA-Lib; the guard shuts it down once the worker has stopped
+    let _talib = match TaLibAbstract::initialize() {
+        Ok(guard) => {
+            info!("TA-Lib successfully initialized");
+            guard
+        },
         Err(e) => {
             error!("Failed to initialize TA-Lib: {}", e);
             return Err(anyhow::anyhow!("TA-Lib initialization failed"));
         }
-    }
+    };
     
     // Check if TA-Lib functions are available
     if !TaLibAbstract::is_function_available("RSI") {
@@ -31,73 +39,100 @@ pub async fn start_worker(concurrency: Option<usize>) -> Result<()> {
     
     info!("TA-Lib library found and initialized successfully");
     
-    // Get database configuration
-    let db_host = env::var("DB_HOST").unwrap_or_else(|_| "localhost".to_string());
-    let db_port = env::var("DB_PORT").unwrap_or_else(|_| "5432".to_string()).parse::<u16>()?;
-    let db_user = env::var("DB_USER").unwrap_or_else(|_| "binanceuser".to_string());
-    let db_password = env::var("DB_PASSWORD").unwrap_or_else(|_| "binancepass".to_string());
-    let db_name = env::var("DB_NAME").unwrap_or_else(|_| "binancedb".to_string());
+    let settings = &config.worker;
     
-    // Get Redis configuration
-    let redis_url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let nan_policy = apply_nan_policy(&settings.nan_policy)?;
+    info!("Using {:?} policy for NaN/Inf indicator values", nan_policy);
     
     // Determine concurrency
-    let concurrency = concurrency.unwrap_or_else(|| {
-        env::var("CONCURRENCY")
-            .ok()
-            .and_then(|s| s.parse::<usize>().ok())
-            .unwrap_or_else(|| num_cpus::get())
-    });
+    let concurrency = concurrency
+        .or(settings.concurrency)
+        .unwrap_or_else(num_cpus::get);
     
     info!("Using concurrency level: {}", concurrency);
     
-    // Create PostgreSQL connection
+    // Get per-interval worker pools (e.g. "fast:1m,5m,15m:4;slow:1d,1w:1")
+    let interval_pools = match &settings.pools {
+        Some(spec) => IntervalPoolConfig::parse_list(spec)?,
+        None => Vec::new(),
+    };
+    
+    for pool in &interval_pools {
+        info!("Configured worker pool '{}' for intervals {:?} with concurrency {}", 
+              pool.name, pool.intervals, pool.concurrency);
+    }
+    
+    // Live candle streaming from Binance
+    let stream_url = settings.streaming.then(|| settings.stream_url.clone());
+    
+    if let Some(url) = &stream_url {
+        info!("Live candle streaming enabled from {}", url);
+    }
+    
+    // Every pool runs its own jobs, so size connection pools for all of them
+    let total_concurrency = concurrency + interval_pools.iter().map(|p| p.concurrency).sum::<usize>();
+    
+    // Create PostgreSQL connection; not the shared pool of the CLI commands, which is sized by
+    // max_connections rather than by the concurrency of the worker pools
     let pg: Arc<PostgresManager> = Arc::new(
-        PostgresManager::new(
-            &db_host,
-            db_port,
-            &db_user,
-            &db_password,
-            &db_name,
-            concurrency * 2, // Max connections in the pool
-        )
-        .await?
+        config.database.connect(total_concurrency * 2).await?
     );
     
     // Initialize database tables
     pg.init_tables().await?;
     
-    // Create Redis connection
-    let cache_ttl = env::var("CACHE_TTL_SECONDS")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(3600); // Default 1 hour
-    
-    // Get completeness cache TTL
-    let completeness_ttl = env::var("COMPLETENESS_CACHE_MINUTES")
-        .ok()
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(30); // Default 30 minutes
-    
-    info!("Using completeness cache TTL: {} minutes", completeness_ttl);
+    info!("Using completeness cache TTL: {} minutes", settings.completeness_cache_minutes);
     
     // Add explicit type annotation for RedisManager
     let redis: Arc<RedisManager> = Arc::new(
         RedisManager::new(
-            &redis_url,
-            cache_ttl,
-            concurrency * 2, // Max connections in the pool
+            &config.redis.url,
+            config.redis.cache_ttl_seconds,
+            total_concurrency * 2, // Max connections in the pool
         )
         .await?
     );
     
+    // Order of queued jobs with equal priority
+    let scheduling_policy = SchedulingPolicy::parse(&settings.scheduling_policy)
+        .ok_or_else(|| anyhow::anyhow!("Invalid scheduling policy '{}', expected round-robin or staleness-first", 
+                                       settings.scheduling_policy))?;
+    info!("Using {:?} job scheduling", scheduling_policy);
+    
+    // Share the job queue with other instances through Redis
+    let cluster_lease_seconds = settings.cluster.then_some(settings.cluster_lease_seconds);
+    
+    if let Some(lease) = cluster_lease_seconds {
+        info!("Running in cluster mode with {} second job leases", lease);
+    }
+    
     // Create worker configuration
     let worker_config = WorkerConfig {
-        cache_ttl_seconds: cache_ttl,
-        completeness_cache_minutes: completeness_ttl,
-        batch_size: 1000,
-        retry_max: 3,
-        retry_delay_ms: 500,
+        cache_ttl_seconds: config.redis.cache_ttl_seconds,
+        completeness_cache_minutes: settings.completeness_cache_minutes,
+        batch_size: settings.batch_size,
+        retry_max: settings.retry_max,
+        retry_delay_ms: settings.retry_delay_ms,
+        interval_pools,
+        stream_url,
+        max_gap_ratio: settings.max_gap_ratio,
+        candle_chunk_size: settings.candle_chunk_size,
+        scheduling_policy,
+        cluster_lease_seconds,
+        retention_policies: config.retention.policies.clone(),
+        retention_check_hours: config.retention.check_interval_hours,
+        event_keep_days: config.retention.event_keep_days,
+        reevaluation: config.reevaluation.enabled.then(|| config.reevaluation.clone()),
+        notify_webhook_url: config.notifications.webhook_url.clone(),
+        signal_generation: (config.notifications.signal_poll_seconds > 0).then(|| config.notifications.clone()),
+        metrics_addr: settings.metrics_addr.clone(),
+        batch_variants: settings.batch_variants,
+        candle_cache_max_candles: settings.candle_cache_max_candles,
+        anomaly: AnomalySettings {
+            jump_factor: settings.anomaly_jump_factor,
+            constant_run: settings.anomaly_constant_run,
+        },
+        notify_anomalies: settings.anomaly_notify,
     };
     
     // Create and start worker
@@ -115,3 +150,11 @@ pub async fn start_worker(concurrency: Option<usize>) -> Result<()> {
     info!("Technical Indicator Calculator shutting down");
     Ok(())
 }
+
+/// Set the NaN/Inf policy of TA-Lib outputs from its name as used in INDICATOR_NAN_POLICY
+pub fn apply_nan_policy(name: &str) -> Result<NanPolicy> {
+    let policy = NanPolicy::parse(name)
+        .ok_or_else(|| anyhow::anyhow!("Invalid NaN policy '{}', expected null, skip or error", name))?;
+    set_nan_policy(policy);
+    Ok(policy)
+}
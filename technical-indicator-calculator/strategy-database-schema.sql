@@ -15,7 +15,9 @@ CREATE TABLE IF NOT EXISTS strategies (
     timeframes JSONB NOT NULL, -- Array of intervals
     parameters JSONB NOT NULL, -- Strategy parameters
     risk_management JSONB NOT NULL, -- Risk settings
-    metadata JSONB -- Optional metadata
+    metadata JSONB, -- Optional metadata
+    tags TEXT[] NOT NULL DEFAULT '{}', -- Lowercase tags grouping strategies into families
+    namespace VARCHAR NOT NULL DEFAULT 'default' -- Owner or environment sharing the database
 );
 
 -- Create strategy_indicators table
@@ -71,8 +73,39 @@ CREATE TABLE IF NOT EXISTS strategy_backtest_results (
     avg_win_holding_period NUMERIC, -- in hours
     avg_loss_holding_period NUMERIC, -- in hours
     expectancy NUMERIC,
+    benchmark_return NUMERIC, -- Buy-and-hold return over the same period
+    excess_return NUMERIC, -- total_return minus benchmark_return
+    alpha NUMERIC, -- Annualized Jensen's alpha versus buy-and-hold
+    beta NUMERIC, -- Beta versus buy-and-hold
+    sortino_ratio NUMERIC,
+    calmar_ratio NUMERIC,
+    value_at_risk NUMERIC, -- 95% historical VaR of trade returns, in percent
+    expected_shortfall NUMERIC, -- Average trade loss beyond the VaR, in percent
+    exposure_percent NUMERIC, -- Share of the period with an open position
+    turnover NUMERIC, -- Traded notional as a multiple of capital
+    unfilled_orders INTEGER, -- Pending entry orders that never filled
     parameters_snapshot JSONB, -- Parameters used in this backtest
-    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    archived_at TIMESTAMPTZ, -- Set when the full result was moved to object storage
+    archive_key VARCHAR, -- Object key of the archive file containing the full result
+    wall_time_ms BIGINT, -- Resource usage of the backtest run
+    cpu_time_ms BIGINT,
+    peak_memory_bytes BIGINT,
+    rows_read BIGINT,
+    rows_written BIGINT,
+    namespace VARCHAR NOT NULL DEFAULT 'default', -- Namespace of the strategy that ran
+    engine_profile VARCHAR, -- Engine settings profile the backtest ran with
+    engine_settings JSONB, -- Snapshot of that profile's settings
+    seed BIGINT -- Seed of the random components, to reproduce the run
+);
+
+-- Create engine settings profiles table; built-in profiles need no row
+CREATE TABLE IF NOT EXISTS engine_settings (
+    name VARCHAR PRIMARY KEY,
+    description VARCHAR NOT NULL DEFAULT '',
+    settings JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
 );
 
 -- Create trades table for detailed backtest trade history
@@ -92,6 +125,8 @@ CREATE TABLE IF NOT EXISTS strategy_backtest_trades (
 
 -- Create index for faster lookup
 CREATE INDEX IF NOT EXISTS idx_strategies_enabled ON strategies(enabled);
+CREATE INDEX IF NOT EXISTS idx_strategies_namespace ON strategies(namespace);
+CREATE INDEX IF NOT EXISTS idx_strategies_tags ON strategies USING GIN (tags);
 CREATE INDEX IF NOT EXISTS idx_strategy_backtest_results_strategy_id ON strategy_backtest_results(strategy_id);
 CREATE INDEX IF NOT EXISTS idx_strategy_backtest_trades_backtest_id ON strategy_backtest_trades(backtest_id);
 CREATE INDEX IF NOT EXISTS idx_strategy_indicators_strategy_id ON strategy_indicators(strategy_id);
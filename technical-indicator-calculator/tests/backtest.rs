@@ -0,0 +1,380 @@
+// Backtest simulation and performance metrics
+//
+// Backtests run against candles held in memory, with frictionless engine settings so
+// the expected returns can be worked out by hand.
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use technical_indicator_calculator::database::models::{
+    BinanceCandle, CalculatedIndicatorBatch, CandleData, IndicatorConfig, IndicatorSeries,
+    StrategyIndicatorRecord, StrategyRecord, StrategyRuleRecord,
+};
+use technical_indicator_calculator::database::storage::Storage;
+use technical_indicator_calculator::error::Result;
+use technical_indicator_calculator::strategy::engine_settings::EngineSettings;
+use technical_indicator_calculator::strategy::evaluator::{max_drawdown, StrategyEvaluator};
+use technical_indicator_calculator::strategy::schema::{Strategy, StrategyPerformance};
+
+const INITIAL_CAPITAL: f64 = 1000.0;
+
+// Storage serving one symbol's candles and nothing else
+struct MemoryStorage {
+    candles: CandleData,
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    fn backend(&self) -> &str {
+        "memory"
+    }
+
+    async fn init(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_candles(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        _start: Option<DateTime<Utc>>,
+        _end: Option<DateTime<Utc>>,
+    ) -> Result<CandleData> {
+        Ok(self.candles.clone())
+    }
+
+    async fn get_candle_symbol_intervals(&self) -> Result<Vec<(String, String)>> {
+        Ok(vec![(self.candles.symbol.clone(), self.candles.interval.clone())])
+    }
+
+    async fn upsert_candles(&self, _candles: &[BinanceCandle]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_enabled_indicator_configs(&self) -> Result<Vec<IndicatorConfig>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_last_calculated_time(
+        &self,
+        _symbol: &str,
+        _interval: &str,
+        _indicator_name: &str,
+        _parameters: &Value,
+    ) -> Result<Option<DateTime<Utc>>> {
+        Ok(None)
+    }
+
+    async fn insert_calculated_indicators_batch(&self, _batch: Vec<CalculatedIndicatorBatch>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_indicator_series(
+        &self,
+        symbol: &str,
+        interval: &str,
+        indicator_name: &str,
+        parameters: &Value,
+        _start: Option<DateTime<Utc>>,
+        _end: Option<DateTime<Utc>>,
+    ) -> Result<IndicatorSeries> {
+        Ok(IndicatorSeries::new(symbol, interval, indicator_name, parameters))
+    }
+
+    async fn list_strategy_ids(&self, _enabled_only: bool) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_strategy_record(&self, _id: &str) -> Result<Option<StrategyRecord>> {
+        Ok(None)
+    }
+
+    async fn get_strategy_indicator_records(&self, _strategy_id: &str) -> Result<Vec<StrategyIndicatorRecord>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_strategy_rule_records(&self, _strategy_id: &str) -> Result<Vec<StrategyRuleRecord>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_strategy_records(
+        &self,
+        _strategy: &StrategyRecord,
+        _indicators: &[StrategyIndicatorRecord],
+        _rules: &[StrategyRuleRecord],
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn first_open() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+}
+
+// Hourly candles from (open, high, low, close) prices, each with a volume of 1000
+fn candles(prices: &[(f64, f64, f64, f64)]) -> CandleData {
+    let mut candles = CandleData::new("TEST".to_string(), "1h".to_string());
+
+    for (i, (open, high, low, close)) in prices.iter().enumerate() {
+        let open_time = first_open() + Duration::hours(i as i64);
+        candles.open_time.push(open_time);
+        candles.close_time.push(open_time + Duration::hours(1) - Duration::milliseconds(1));
+        candles.open.push(*open);
+        candles.high.push(*high);
+        candles.low.push(*low);
+        candles.close.push(*close);
+        candles.volume.push(1000.0);
+    }
+
+    candles
+}
+
+// Candles that open, trade and close at one price
+fn flat_candles(closes: &[f64]) -> CandleData {
+    candles(&closes.iter().map(|close| (*close, *close, *close, *close)).collect::<Vec<_>>())
+}
+
+// A rule acting when the close equals `close`
+fn rule_at_close(id: &str, close: f64, action: Value) -> Value {
+    json!({
+        "id": id,
+        "name": id,
+        "condition": {
+            "type": "simple",
+            "condition": {
+                "left": {"type": "price", "property": "close", "offset": null},
+                "operator": "=",
+                "right": {"type": "constant", "value": close},
+            },
+        },
+        "action": action,
+    })
+}
+
+// A strategy without indicators, so every candle is tradeable
+fn strategy(rules: Vec<Value>, risk_management: Value) -> Strategy {
+    let mut risk = json!({
+        "max_risk_per_trade": 1.0,
+        "max_total_risk": 100.0,
+        "default_position_size": 10.0,
+        "default_stop_loss": null,
+        "default_take_profit": null,
+        "use_trailing_stop": false,
+        "trailing_stop_activation": null,
+        "trailing_stop_percent": null,
+    });
+    if let (Some(risk), Some(overrides)) = (risk.as_object_mut(), risk_management.as_object()) {
+        risk.extend(overrides.clone());
+    }
+
+    serde_json::from_value(json!({
+        "id": "test",
+        "name": "Test",
+        "description": "",
+        "version": "1.0.0",
+        "author": "test",
+        "created_at": first_open(),
+        "updated_at": first_open(),
+        "enabled": true,
+        "assets": ["TEST"],
+        "timeframes": ["1h"],
+        "indicators": [],
+        "rules": rules,
+        "parameters": {},
+        "risk_management": risk,
+    }))
+    .expect("invalid test strategy")
+}
+
+fn frictionless() -> EngineSettings {
+    EngineSettings {
+        name: "frictionless".to_string(),
+        commission_percent: 0.0,
+        slippage_percent: 0.0,
+        ..EngineSettings::default()
+    }
+}
+
+async fn backtest(strategy: &Strategy, candles: CandleData, settings: EngineSettings) -> StrategyPerformance {
+    StrategyEvaluator::new(Arc::new(MemoryStorage { candles }), INITIAL_CAPITAL)
+        .with_engine_settings(settings)
+        .backtest(strategy, "TEST", "1h", None, None)
+        .await
+        .expect("backtest failed")
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "expected {}, got {}", expected, actual);
+}
+
+#[tokio::test]
+async fn scaled_position_closes_each_tranche_at_its_own_entry() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 11.0, json!({"type": "enter_long", "size_percent": 10.0})),
+        rule_at_close("add", 12.0, json!({"type": "scale_in", "size_percent": 10.0, "max_position_percent": 50.0})),
+        rule_at_close("exit", 13.0, json!({"type": "exit_long"})),
+    ], json!({}));
+
+    let performance = backtest(&strategy, flat_candles(&[10.0, 10.0, 11.0, 12.0, 13.0, 13.0]), frictionless()).await;
+
+    // 10% of capital from 11 and another 10% from 12, both sold at 13
+    assert_eq!(performance.total_trades, 2);
+    assert_eq!(performance.winning_trades, 2);
+    let equity = (1.0 + 0.1 * (13.0 / 11.0 - 1.0)) * (1.0 + 0.1 * (13.0 / 12.0 - 1.0));
+    assert_close(performance.total_return, (equity - 1.0) * 100.0);
+    assert_close(performance.max_drawdown, 0.0);
+}
+
+#[tokio::test]
+async fn scale_in_stops_at_the_maximum_position_size() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 11.0, json!({"type": "enter_long", "size_percent": 10.0})),
+        rule_at_close("add", 12.0, json!({"type": "scale_in", "size_percent": 10.0, "max_position_percent": 15.0})),
+    ], json!({}));
+
+    // The tranche added at 12 is cut to 5%; both close at the end of the simulation
+    let performance = backtest(&strategy, flat_candles(&[10.0, 11.0, 12.0, 12.0, 12.0]), frictionless()).await;
+
+    assert_eq!(performance.total_trades, 2);
+    assert_close(performance.total_return, 0.1 * (12.0 / 11.0 - 1.0) * 100.0);
+}
+
+#[tokio::test]
+async fn limit_order_fills_once_a_later_low_reaches_it() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 100.0, json!({
+            "type": "enter_long",
+            "size_percent": 10.0,
+            "order": {"order_type": "limit", "price_offset_percent": -5.0},
+        })),
+        rule_at_close("exit", 105.0, json!({"type": "exit_long"})),
+    ], json!({}));
+
+    // The limit at 95 is missed by the low of 96, then filled below the open of 99
+    let prices = [
+        (100.0, 100.0, 100.0, 100.0),
+        (100.0, 101.0, 96.0, 99.0),
+        (99.0, 100.0, 94.0, 98.0),
+        (105.0, 105.0, 105.0, 105.0),
+    ];
+    let performance = backtest(&strategy, candles(&prices), frictionless()).await;
+
+    assert_eq!(performance.total_trades, 1);
+    assert_close(performance.total_return, 0.1 * (105.0 / 95.0 - 1.0) * 100.0);
+    assert_eq!(performance.unfilled_orders, 0);
+}
+
+#[tokio::test]
+async fn limit_order_expires_unfilled() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 100.0, json!({
+            "type": "enter_long",
+            "size_percent": 10.0,
+            "order": {"order_type": "limit", "price_offset_percent": -5.0, "expires_after_bars": 1},
+        })),
+    ], json!({}));
+
+    let prices = [
+        (100.0, 100.0, 100.0, 100.0),
+        (100.0, 101.0, 96.0, 99.0),
+        (99.0, 100.0, 94.0, 98.0),
+    ];
+    let performance = backtest(&strategy, candles(&prices), frictionless()).await;
+
+    assert_eq!(performance.total_trades, 0);
+    assert_eq!(performance.unfilled_orders, 1);
+}
+
+#[tokio::test]
+async fn stop_order_fills_at_the_open_when_the_price_gaps_through_it() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 100.0, json!({
+            "type": "enter_long",
+            "size_percent": 10.0,
+            "order": {"order_type": "stop", "price_offset_percent": 2.0},
+        })),
+        rule_at_close("exit", 110.0, json!({"type": "exit_long"})),
+    ], json!({}));
+
+    // The stop at 102 is missed by the high of 101.5, then gapped through at 103
+    let prices = [
+        (100.0, 100.0, 100.0, 100.0),
+        (100.0, 101.5, 99.0, 101.0),
+        (103.0, 104.0, 102.5, 103.5),
+        (110.0, 110.0, 110.0, 110.0),
+    ];
+    let performance = backtest(&strategy, candles(&prices), frictionless()).await;
+
+    assert_eq!(performance.total_trades, 1);
+    assert_close(performance.total_return, 0.1 * (110.0 / 103.0 - 1.0) * 100.0);
+}
+
+#[tokio::test]
+async fn stop_loss_level_exits_at_the_resolved_price() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 100.0, json!({"type": "enter_long", "size_percent": 10.0})),
+    ], json!({
+        "stop_loss_level": {"type": "percent", "value": 5.0},
+        "take_profit_level": {"type": "percent", "value": 10.0},
+    }));
+
+    // The stop at 95 is hit by the low of the third candle, before the target at 110
+    let prices = [
+        (100.0, 100.0, 100.0, 100.0),
+        (100.0, 104.0, 97.0, 98.0),
+        (98.0, 99.0, 94.0, 96.0),
+        (96.0, 111.0, 96.0, 110.0),
+    ];
+    let performance = backtest(&strategy, candles(&prices), frictionless()).await;
+
+    assert_eq!(performance.total_trades, 1);
+    assert_eq!(performance.losing_trades, 1);
+    assert_close(performance.total_return, 0.1 * (95.0 / 100.0 - 1.0) * 100.0);
+}
+
+#[tokio::test]
+async fn execution_latency_delays_actions_to_a_later_open() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 100.0, json!({"type": "enter_long", "size_percent": 10.0})),
+        rule_at_close("exit", 110.0, json!({"type": "exit_long"})),
+    ], json!({}));
+    let settings = EngineSettings { execution_latency_bars: 1, ..frictionless() };
+
+    // Bought at the next open of 101 and sold at the open of 108 after the exit signal
+    let prices = [
+        (100.0, 100.0, 100.0, 100.0),
+        (101.0, 106.0, 101.0, 105.0),
+        (105.0, 111.0, 105.0, 110.0),
+        (108.0, 109.0, 107.0, 108.0),
+    ];
+    let performance = backtest(&strategy, candles(&prices), settings).await;
+
+    assert_eq!(performance.total_trades, 1);
+    assert_close(performance.total_return, 0.1 * (108.0 / 101.0 - 1.0) * 100.0);
+}
+
+#[tokio::test]
+async fn volume_participation_limits_the_entry_size() {
+    let strategy = strategy(vec![
+        rule_at_close("enter", 100.0, json!({"type": "enter_long", "size_percent": 50.0})),
+        rule_at_close("exit", 110.0, json!({"type": "exit_long"})),
+    ], json!({}));
+    let settings = EngineSettings { max_volume_participation: Some(0.2), ..frictionless() };
+
+    // 0.2% of 1000 units at 100 is 200, or 20% of the capital of 1000
+    let performance = backtest(&strategy, flat_candles(&[100.0, 110.0]), settings).await;
+
+    assert_eq!(performance.total_trades, 1);
+    assert_close(performance.total_return, 0.2 * 10.0);
+}
+
+#[test]
+fn max_drawdown_is_the_largest_fall_from_a_running_peak() {
+    // Falls of 20% from 110 to 88 and of 25% from 120 to 90; the recovery to 105 stays below
+    // the first peak, so 88 is measured against 110
+    let curve = [100.0, 110.0, 88.0, 105.0, 120.0, 90.0, 130.0];
+    assert!((max_drawdown(&curve) - 25.0).abs() < 1e-9);
+
+    assert_eq!(max_drawdown(&[100.0, 101.0, 102.0]), 0.0);
+    assert_eq!(max_drawdown(&[]), 0.0);
+}
@@ -0,0 +1,31 @@
+// Derived columns of CandleData
+use chrono::{Duration, TimeZone, Utc};
+use technical_indicator_calculator::database::models::CandleData;
+
+// Push a candle opening an hour after the previous one
+fn push(candles: &mut CandleData, open: f64, high: f64, low: f64, close: f64) {
+    let open_time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::hours(candles.len() as i64);
+    candles.open_time.push(open_time);
+    candles.close_time.push(open_time + Duration::hours(1) - Duration::milliseconds(1));
+    candles.open.push(open);
+    candles.high.push(high);
+    candles.low.push(low);
+    candles.close.push(close);
+    candles.volume.push(1.0);
+}
+
+#[test]
+fn derived_columns_follow_appended_and_replaced_prices() {
+    let mut candles = CandleData::new("TEST".to_string(), "1h".to_string());
+    push(&mut candles, 10.0, 12.0, 9.0, 11.0);
+    assert_eq!(&*candles.true_range(), &[3.0]);
+
+    // The gap up from the previous close of 11 widens the second candle's range
+    push(&mut candles, 14.0, 15.0, 13.0, 14.0);
+    assert_eq!(&*candles.true_range(), &[3.0, 4.0]);
+    assert_eq!(&*candles.body_size(), &[1.0, 0.0]);
+
+    candles.close = vec![9.0, 15.0];
+    assert_eq!(&*candles.body_size(), &[1.0, 1.0]);
+    assert_eq!(&*candles.typical_price(), &[10.0, 43.0 / 3.0]);
+}
@@ -0,0 +1,78 @@
+// Parallel chunked indicator calculation against the serial TA-Lib call
+//
+// Window-based indicators (SMA) are split into chunks that each recompute their
+// lookback; recursive ones (EMA, RSI, ATR) must fall back to a single serial call.
+// Either way the result has to match the serial calculation up to the rounding of
+// TA-Lib's running sums. They call into the TA-Lib library, so they only build with
+// the talib-tests feature: `cargo test --features talib-tests`.
+#![cfg(feature = "talib-tests")]
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+use technical_indicator_calculator::indicators::chunked::call_function_chunked;
+use technical_indicator_calculator::indicators::output::IndicatorOutput;
+use technical_indicator_calculator::talib_bindings::TaLibAbstract;
+
+// Long enough to be chunked: with 8 threads, 8 chunks of 20,000 candles
+const LEN: usize = 160_000;
+const THREADS: usize = 8;
+
+// Relative difference (absolute below 1) tolerated between chunked and serial values
+const TOLERANCE: f64 = 1e-9;
+
+// A seeded random walk of high, low and close prices
+fn prices() -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let (mut high, mut low, mut close) = (Vec::with_capacity(LEN), Vec::with_capacity(LEN), Vec::with_capacity(LEN));
+    let mut price = 30_000.0;
+
+    for _ in 0..LEN {
+        price *= 1.0 + rng.gen_range(-0.01..0.01);
+        high.push(price * (1.0 + rng.gen_range(0.0..0.005)));
+        low.push(price * (1.0 - rng.gen_range(0.0..0.005)));
+        close.push(price);
+    }
+
+    (high, low, close)
+}
+
+fn numeric(results: Vec<(usize, IndicatorOutput)>) -> Vec<(usize, f64)> {
+    results.into_iter()
+        .map(|(idx, output)| (idx, output.numeric(None).expect("not a scalar indicator")))
+        .collect()
+}
+
+fn assert_matches_serial(function_name: &str, period: i64) {
+    let (high, low, close) = prices();
+    let parameters: Vec<(String, Value)> = vec![("period".to_string(), json!(period))];
+    let inputs = (Some(high.as_slice()), Some(low.as_slice()), Some(close.as_slice()));
+
+    let serial = TaLibAbstract::call_function(function_name, None, inputs.0, inputs.1, inputs.2, None, &parameters)
+        .expect("serial calculation failed");
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(THREADS).build().unwrap();
+    let chunked = pool
+        .install(|| call_function_chunked(function_name, None, inputs.0, inputs.1, inputs.2, None, &parameters))
+        .expect("chunked calculation failed");
+
+    let (serial, chunked) = (numeric(serial), numeric(chunked));
+    assert_eq!(serial.len(), chunked.len(), "{}({}) output lengths differ", function_name, period);
+    for ((serial_idx, expected), (chunked_idx, actual)) in serial.into_iter().zip(chunked) {
+        assert_eq!(serial_idx, chunked_idx, "{}({}) outputs are out of order", function_name, period);
+        assert!((actual - expected).abs() <= TOLERANCE * expected.abs().max(1.0),
+                "{}({}) at candle {}: chunked {}, serial {}", function_name, period, serial_idx, actual, expected);
+    }
+}
+
+#[test]
+fn sma_matches_serial_across_chunk_boundaries() {
+    assert_matches_serial("SMA", 20);
+    assert_matches_serial("SMA", 200);
+}
+
+#[test]
+fn recursive_indicators_match_serial() {
+    assert_matches_serial("EMA", 20);
+    assert_matches_serial("RSI", 14);
+    assert_matches_serial("ATR", 14);
+}
@@ -0,0 +1,32 @@
+// Built-in indicators against the golden outputs bundled with the source
+//
+// The same check as `verify indicators`: every built-in indicator is calculated on the
+// reference candles with its reference parameters and must match golden/indicators.json.
+// An indicator without stored outputs fails too; record them with
+// `verify indicators --update` and review the diff. They call into the TA-Lib library,
+// so they only build with the talib-tests feature: `cargo test --features talib-tests`.
+#![cfg(feature = "talib-tests")]
+
+use technical_indicator_calculator::indicators::golden::{self, CaseStatus};
+
+#[test]
+fn every_indicator_matches_its_golden_outputs() {
+    let cases = golden::bundled_cases().expect("failed to read the bundled golden outputs");
+    let results = golden::verify(&golden::reference_parameters(), &cases, None).expect("failed to verify indicators");
+    assert!(!results.is_empty(), "no indicators were checked");
+
+    let failures: Vec<String> = results.iter()
+        .filter_map(|result| {
+            let reason = match &result.status {
+                CaseStatus::Passed => return None,
+                CaseStatus::Failed(differences) => format!("{} differences, first {}", differences.len(), differences[0]),
+                CaseStatus::Missing => "no golden outputs".to_string(),
+                CaseStatus::Error(e) => format!("error: {}", e),
+            };
+            Some(format!("{} {}: {}", result.indicator, result.parameters, reason))
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "{} of {} indicators don't match their golden outputs:\n{}",
+            failures.len(), results.len(), failures.join("\n"));
+}
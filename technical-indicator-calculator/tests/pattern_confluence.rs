@@ -0,0 +1,57 @@
+// Candlestick pattern directions and the confluence score built from them
+//
+// Patterns are detected by TA-Lib, so these tests only build with the talib-tests feature:
+// `cargo test --features talib-tests`.
+#![cfg(feature = "talib-tests")]
+
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+use technical_indicator_calculator::database::models::CandleData;
+use technical_indicator_calculator::indicators::calculator::IndicatorCalculator;
+use technical_indicator_calculator::indicators::output::{IndicatorOutput, PatternDirection};
+
+// Hourly doji opening and closing at the same price with equal wicks
+fn doji(len: usize) -> CandleData {
+    let first_open = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut data = CandleData::new("TEST".to_string(), "1h".to_string());
+
+    for i in 0..len {
+        let open_time = first_open + Duration::hours(i as i64);
+        let price = 100.0 + (i % 5) as f64;
+        data.open_time.push(open_time);
+        data.close_time.push(open_time + Duration::hours(1) - Duration::milliseconds(1));
+        data.open.push(price);
+        data.high.push(price + 2.0);
+        data.low.push(price - 2.0);
+        data.close.push(price);
+        data.volume.push(1.0);
+    }
+
+    data
+}
+
+#[test]
+fn doji_are_neutral() {
+    let hits = IndicatorCalculator::calculate_indicator(&doji(30), "CDLDOJI", &json!({})).expect("CDLDOJI failed");
+    assert!(!hits.is_empty(), "no doji detected");
+
+    for (time, output) in hits {
+        let IndicatorOutput::Pattern(hit) = output else {
+            panic!("CDLDOJI produced a non-pattern value at {}", time);
+        };
+        assert_eq!(hit.direction, PatternDirection::Neutral, "doji at {}", time);
+    }
+}
+
+#[test]
+fn doji_series_has_no_confluence() {
+    let scores = IndicatorCalculator::calculate_indicator(&doji(30), "PATTERN_CONFLUENCE", &json!({}))
+        .expect("PATTERN_CONFLUENCE failed");
+    assert!(!scores.is_empty(), "no confluence scores");
+
+    for (time, output) in scores {
+        for property in ["bullish", "bearish", "score"] {
+            assert_eq!(output.numeric(Some(property)), Some(0.0), "{} at {}", property, time);
+        }
+    }
+}
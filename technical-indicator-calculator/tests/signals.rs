@@ -0,0 +1,140 @@
+// Delivery of live signals through the signal ledger and their paper execution
+//
+// These tests write to the PostgreSQL database configured in config.toml or the DB_*
+// variables, so they only build with the postgres-tests feature:
+// `cargo test --features postgres-tests`. Every test uses its own strategy id.
+#![cfg(feature = "postgres-tests")]
+
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+use std::sync::Arc;
+use technical_indicator_calculator::config::AppConfig;
+use technical_indicator_calculator::database::postgres::PostgresManager;
+use technical_indicator_calculator::execution::paper::PaperExecutor;
+use technical_indicator_calculator::signals::generator::SignalGenerator;
+use technical_indicator_calculator::signals::ledger::SignalLedger;
+use technical_indicator_calculator::signals::webhook::WebhookNotifier;
+use technical_indicator_calculator::signals::Signal;
+use technical_indicator_calculator::strategy::repository::StrategyRepository;
+use technical_indicator_calculator::strategy::schema::Strategy;
+
+async fn connect() -> Arc<PostgresManager> {
+    let config = AppConfig::load().expect("failed to load the configuration");
+    let pg = config.database.connect(2).await.expect("failed to connect to PostgreSQL");
+    Arc::new(pg)
+}
+
+// A generator with a one day dedup window, posting to `webhook_url` if given
+async fn generator(pg: &Arc<PostgresManager>, webhook_url: Option<&str>) -> SignalGenerator {
+    let ledger = SignalLedger::new(pg.clone(), std::time::Duration::from_secs(24 * 3600));
+    ledger.init_table().await.expect("failed to create the signal ledger");
+
+    SignalGenerator::new(
+        StrategyRepository::new(pg.clone()),
+        ledger,
+        webhook_url.map(WebhookNotifier::new),
+        Duration::days(30),
+    )
+}
+
+fn signal(strategy_id: &str) -> Signal {
+    Signal {
+        strategy_id: strategy_id.to_string(),
+        symbol: "BTCUSDT".to_string(),
+        interval: "1h".to_string(),
+        bar_time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        rule_id: "entry".to_string(),
+        action: serde_json::from_value(json!({"type": "enter_long", "size_percent": 10.0})).unwrap(),
+        price: 100.0,
+    }
+}
+
+fn unique_id() -> String {
+    format!("test-{}", uuid::Uuid::new_v4())
+}
+
+// A disabled strategy, so a running worker never evaluates it
+fn strategy(id: &str) -> Strategy {
+    let created_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+    serde_json::from_value(json!({
+        "id": id,
+        "name": "Paper test",
+        "description": "",
+        "version": "1.0.0",
+        "author": "test",
+        "created_at": created_at,
+        "updated_at": created_at,
+        "enabled": false,
+        "assets": ["BTCUSDT"],
+        "timeframes": ["1h"],
+        "indicators": [],
+        "rules": [],
+        "parameters": {},
+        "risk_management": {
+            "max_risk_per_trade": 1.0,
+            "max_total_risk": 100.0,
+            "default_position_size": 5.0,
+            "default_stop_loss": null,
+            "default_take_profit": null,
+            "use_trailing_stop": false,
+            "trailing_stop_activation": null,
+            "trailing_stop_percent": null,
+        },
+    }))
+    .expect("invalid test strategy")
+}
+
+#[tokio::test]
+async fn duplicate_signals_are_delivered_once() {
+    let pg = connect().await;
+    let generator = generator(&pg, None).await;
+    let signal = signal(&unique_id());
+
+    assert!(generator.deliver(&signal).await.unwrap());
+    assert!(!generator.deliver(&signal).await.unwrap(), "the same bar and rule was delivered twice");
+
+    generator.ledger().release(&signal).await.unwrap();
+}
+
+#[tokio::test]
+async fn failed_sends_release_the_claim() {
+    let pg = connect().await;
+    // Nothing listens on the discard port, so every send fails
+    let generator = generator(&pg, Some("http://127.0.0.1:9/")).await;
+    let signal = signal(&unique_id());
+
+    assert!(generator.deliver(&signal).await.is_err());
+    assert!(generator.ledger().claim(&signal).await.unwrap(), "a failed send kept the signal claimed");
+
+    generator.ledger().release(&signal).await.unwrap();
+}
+
+#[tokio::test]
+async fn delivered_signals_open_paper_positions() {
+    let pg = connect().await;
+    pg.init_engine_settings_table().await.unwrap();
+    let generator = generator(&pg, None).await;
+    let strategy_id = unique_id();
+    StrategyRepository::new(pg.clone()).save_strategy(&strategy(&strategy_id)).await.unwrap();
+
+    let mut executor = PaperExecutor::new(pg.clone(), &unique_id(), 10_000.0);
+    executor.init_tables().await.unwrap();
+    // Allow for clock skew between the test and the database, which stamps sent_at
+    let cursor = Utc::now() - Duration::minutes(5);
+
+    let signal = signal(&strategy_id);
+    assert!(generator.deliver(&signal).await.unwrap());
+    executor.poll(cursor).await.unwrap();
+
+    // 10% of 10,000 at 100
+    let positions = executor.positions().await.unwrap();
+    let position = positions.iter()
+        .find(|position| position.strategy_id == strategy_id)
+        .expect("the delivered signal opened no paper position");
+    assert!(position.is_long);
+    assert!((position.quantity - 10.0).abs() < 1e-9, "quantity {}", position.quantity);
+    assert_eq!(position.entry_price, 100.0);
+
+    generator.ledger().release(&signal).await.unwrap();
+}
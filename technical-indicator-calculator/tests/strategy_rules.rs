@@ -0,0 +1,175 @@
+// Rule condition evaluation on hand-built candle frames
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::{json, Value};
+use technical_indicator_calculator::database::models::{AlignedFrame, CandleData, IndicatorSeries};
+use technical_indicator_calculator::strategy::rules::RuleSet;
+use technical_indicator_calculator::strategy::schema::Strategy;
+use technical_indicator_calculator::utils::session::SessionCalendar;
+
+fn first_open() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+}
+
+// Hourly candles opening and closing at the given prices
+fn frame(closes: &[f64]) -> AlignedFrame {
+    let mut candles = CandleData::new("TEST".to_string(), "1h".to_string());
+
+    for (i, close) in closes.iter().enumerate() {
+        let open_time = first_open() + Duration::hours(i as i64);
+        candles.open_time.push(open_time);
+        candles.close_time.push(open_time + Duration::hours(1) - Duration::milliseconds(1));
+        candles.open.push(*close);
+        candles.high.push(*close);
+        candles.low.push(*close);
+        candles.close.push(*close);
+        candles.volume.push(1.0);
+    }
+
+    AlignedFrame::new(candles)
+}
+
+// A strategy with one rule per condition, named after its position
+fn strategy(conditions: Vec<Value>) -> Strategy {
+    let rules: Vec<Value> = conditions.into_iter().enumerate()
+        .map(|(i, condition)| json!({
+            "id": format!("rule{}", i),
+            "name": format!("Rule {}", i),
+            "condition": condition,
+            "action": {"type": "exit_long"},
+        }))
+        .collect();
+
+    serde_json::from_value(json!({
+        "id": "test",
+        "name": "Test",
+        "description": "",
+        "version": "1.0.0",
+        "author": "test",
+        "created_at": first_open(),
+        "updated_at": first_open(),
+        "enabled": true,
+        "assets": ["TEST"],
+        "timeframes": ["1h"],
+        "indicators": [],
+        "rules": rules,
+        "parameters": {},
+        "risk_management": {
+            "max_risk_per_trade": 1.0,
+            "max_total_risk": 100.0,
+            "default_position_size": 10.0,
+            "default_stop_loss": null,
+            "default_take_profit": null,
+            "use_trailing_stop": false,
+            "trailing_stop_activation": null,
+            "trailing_stop_percent": null,
+        },
+    }))
+    .expect("invalid test strategy")
+}
+
+fn simple(left: Value, operator: &str, right: Value) -> Value {
+    json!({"type": "simple", "condition": {"left": left, "operator": operator, "right": right}})
+}
+
+fn close() -> Value {
+    json!({"type": "price", "property": "close", "offset": null})
+}
+
+fn constant(value: f64) -> Value {
+    json!({"type": "constant", "value": value})
+}
+
+// Indices of the candles at which any rule of a strategy triggers
+fn triggers(strategy: &Strategy, frame: &AlignedFrame, entry_time: Option<DateTime<Utc>>) -> Vec<usize> {
+    let rules = RuleSet::new(strategy).expect("rules failed to compile");
+    (0..frame.len())
+        .filter(|&idx| !rules.triggered(frame, idx, entry_time).is_empty())
+        .collect()
+}
+
+#[test]
+fn crossovers_trigger_only_on_the_crossing_candle() {
+    let frame = frame(&[9.0, 11.0, 12.0, 10.0, 9.0, 11.0]);
+
+    let above = strategy(vec![simple(close(), "crosses_above", constant(10.0))]);
+    assert_eq!(triggers(&above, &frame, None), vec![1, 5]);
+
+    // Leaving the level counts as crossing it
+    let below = strategy(vec![simple(close(), "crosses_below", constant(10.0))]);
+    assert_eq!(triggers(&below, &frame, None), vec![4]);
+}
+
+#[test]
+fn bars_since_entry_counts_from_the_entry_candle() {
+    let frame = frame(&[1.0; 6]);
+    let bars_since_entry = json!({"type": "bars_since_entry"});
+    let strategy = strategy(vec![simple(bars_since_entry, ">=", constant(2.0))]);
+
+    assert_eq!(triggers(&strategy, &frame, Some(first_open() + Duration::hours(2))), vec![4, 5]);
+
+    // No value while flat, so the condition never holds
+    assert!(triggers(&strategy, &frame, None).is_empty());
+}
+
+#[test]
+fn session_operands_use_the_symbol_calendar() {
+    // Hourly candles from Friday 2024-01-05 13:00 UTC (08:00 in New York) to Saturday
+    let mut frame = frame(&[1.0; 30]);
+    let friday = Utc.with_ymd_and_hms(2024, 1, 5, 13, 0, 0).unwrap();
+    for (i, open_time) in frame.candles.open_time.iter_mut().enumerate() {
+        *open_time = friday + Duration::hours(i as i64);
+    }
+    frame.candles.session = SessionCalendar::parse("nyse").unwrap();
+
+    // Candles opening from 10:00 to 15:00 New York time; the 09:00 one opens before the 09:30 open
+    let in_session = strategy(vec![simple(json!({"type": "in_session"}), "=", constant(1.0))]);
+    assert_eq!(triggers(&in_session, &frame, None), vec![2, 3, 4, 5, 6, 7]);
+
+    // 15:00 to 23:00 on Friday
+    let afternoon = strategy(vec![simple(json!({"type": "time_of_day"}), ">=", constant(14.5))]);
+    assert_eq!(triggers(&afternoon, &frame, None), (7..16).collect::<Vec<_>>());
+
+    // Friday is 5; New York midnight is 05:00 UTC, 16 candles in
+    let weekend = strategy(vec![simple(json!({"type": "day_of_week"}), ">", constant(5.0))]);
+    assert_eq!(triggers(&weekend, &frame, None), (16..30).collect::<Vec<_>>());
+}
+
+#[test]
+fn pattern_types_compare_as_text() {
+    // A doji on the first candle, a hammer on the second and no pattern on the third
+    let mut frame = frame(&[1.0; 3]);
+    let mut series = IndicatorSeries::new("TEST", "1h", "CDLDOJI", &json!({}));
+    series.time = frame.candles.open_time[..2].to_vec();
+    series.value = vec![
+        json!({"pattern": "DOJI", "type": "neutral", "strength": 1.0}),
+        json!({"pattern": "HAMMER", "type": "bullish", "strength": 1.0}),
+    ];
+    frame.insert_series("pattern", &series);
+
+    let pattern_type = json!({"type": "indicator", "indicator_id": "pattern", "property": "type", "offset": null});
+    let text = |value: &str| json!({"type": "constant", "value": value});
+
+    let neutral = strategy(vec![simple(pattern_type.clone(), "=", text("neutral"))]);
+    assert_eq!(triggers(&neutral, &frame, None), vec![0]);
+
+    // A candle without a pattern has no type, so it isn't "not bullish" either
+    let not_bullish = strategy(vec![simple(pattern_type, "!=", text("bullish"))]);
+    assert_eq!(triggers(&not_bullish, &frame, None), vec![0]);
+}
+
+#[test]
+fn compound_conditions_and_priorities() {
+    let frame = frame(&[1.0, 2.0, 3.0, 4.0]);
+    let and = json!({
+        "type": "composite",
+        "operator": "and",
+        "conditions": [simple(close(), ">", constant(1.0)), simple(close(), "<", constant(4.0))],
+    });
+    assert_eq!(triggers(&strategy(vec![and]), &frame, None), vec![1, 2]);
+
+    let mut strategy = strategy(vec![simple(close(), ">", constant(0.0)), simple(close(), ">", constant(0.0))]);
+    strategy.rules[1].priority = -1;
+    let rules = RuleSet::new(&strategy).unwrap();
+    let ids: Vec<&str> = rules.triggered(&frame, 0, None).iter().map(|rule| rule.id.as_str()).collect();
+    assert_eq!(ids, vec!["rule1", "rule0"]);
+}
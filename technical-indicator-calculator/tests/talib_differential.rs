@@ -0,0 +1,208 @@
+// Differential tests of the Rust indicator code against TA-Lib
+//
+// Some calculations exist both as a TA-Lib call and in Rust: the ATR reversal threshold
+// of ZigZag, which chart pattern detection also finds its pivots with, is a Wilder ATR
+// over `CandleData::true_range`, and the rolling statistics (linear regression, z-score,
+// correlation and beta) have TA-Lib counterparts in LINEARREG*, TSF, STDDEV, CORREL and
+// BETA. These properties run both on random series, the way the indicators call them,
+// and fail when the two drift apart. They call into the TA-Lib library, so they only
+// build with the talib-tests feature: `cargo test --features talib-tests`.
+#![cfg(feature = "talib-tests")]
+
+use chrono::{Duration, TimeZone, Utc};
+use proptest::prelude::*;
+use serde_json::json;
+use technical_indicator_calculator::database::models::CandleData;
+use technical_indicator_calculator::indicators::calculator::IndicatorCalculator;
+use technical_indicator_calculator::indicators::output::IndicatorOutput;
+use technical_indicator_calculator::indicators::statistics::{
+    beta, correlation, linear_regression, returns, zscore, Regression,
+};
+use technical_indicator_calculator::indicators::zigzag::wilder_atr;
+use technical_indicator_calculator::talib_bindings::StatisticFunctions;
+
+// Relative difference (absolute below 1) tolerated between the two implementations
+const TOLERANCE: f64 = 1e-9;
+
+// TA-Lib keeps running sums for the rolling statistics, which lose more precision
+const STATISTICS_TOLERANCE: f64 = 1e-6;
+
+// Random walks of hourly candles: each candle opens at the previous close, its high and
+// low enclose the open and close, and prices stay positive
+fn ohlcv(len: std::ops::Range<usize>) -> impl Strategy<Value = CandleData> {
+    let candle = (-0.05f64..0.05, 0.0f64..0.02, 0.0f64..0.02, 0.0f64..1_000.0);
+
+    (0.01f64..100_000.0, prop::collection::vec(candle, len)).prop_map(|(start, candles)| {
+        let first_open = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut data = CandleData::new("PROPTEST".to_string(), "1h".to_string());
+        let mut price = start;
+
+        for (i, (change, upper_wick, lower_wick, volume)) in candles.into_iter().enumerate() {
+            let open_time = first_open + Duration::hours(i as i64);
+            let close = price * (1.0 + change);
+
+            data.open_time.push(open_time);
+            data.close_time.push(open_time + Duration::hours(1) - Duration::milliseconds(1));
+            data.open.push(price);
+            data.high.push(price.max(close) * (1.0 + upper_wick));
+            data.low.push(price.min(close) * (1.0 - lower_wick));
+            data.close.push(close);
+            data.volume.push(volume);
+            price = close;
+        }
+
+        data
+    })
+}
+
+// A period and candles with at least one value after the warm-up
+fn period_and_candles() -> impl Strategy<Value = (usize, CandleData)> {
+    (1usize..30).prop_flat_map(|period| (Just(period), ohlcv(period + 1..300)))
+}
+
+// TA-Lib's ATR by candle index
+fn talib_atr(candles: &CandleData, period: usize) -> Vec<(usize, f64)> {
+    IndicatorCalculator::calculate_indicator(candles, "ATR", &json!({"period": period}))
+        .expect("TA-Lib ATR failed")
+        .into_iter()
+        .map(|(time, output)| {
+            let index = candles.open_time.iter().position(|t| *t == time).expect("ATR value at an unknown time");
+            (index, output.numeric(None).expect("ATR is a scalar"))
+        })
+        .collect()
+}
+
+// Random values without the trend of a price series, so windows are never flat
+fn series(len: std::ops::Range<usize>) -> impl Strategy<Value = Vec<f64>> {
+    prop::collection::vec(-1_000.0f64..1_000.0, len)
+}
+
+// A period of at least 2 and values with at least one complete window
+fn period_and_series() -> impl Strategy<Value = (usize, Vec<f64>)> {
+    (2usize..30).prop_flat_map(|period| (Just(period), series(period..300)))
+}
+
+// A period and two series of the same length drawn from `values`, with at least one
+// complete window of returns
+fn period_and_pair(values: std::ops::Range<f64>) -> impl Strategy<Value = (usize, Vec<f64>, Vec<f64>)> {
+    (2usize..30, 0usize..270).prop_flat_map(move |(period, extra)| {
+        let len = period + 1 + extra;
+        (Just(period), prop::collection::vec(values.clone(), len), prop::collection::vec(values.clone(), len))
+    })
+}
+
+fn present(values: &[f64]) -> Vec<Option<f64>> {
+    values.iter().copied().map(Some).collect()
+}
+
+// Values of a TA-Lib function by index
+fn talib_values(outputs: Vec<(usize, IndicatorOutput)>) -> Vec<(usize, f64)> {
+    outputs
+        .into_iter()
+        .map(|(index, output)| (index, output.numeric(None).expect("TA-Lib statistics are scalars")))
+        .collect()
+}
+
+fn close(rust: f64, talib: f64) -> bool {
+    close_within(rust, talib, TOLERANCE)
+}
+
+fn close_within(rust: f64, talib: f64, tolerance: f64) -> bool {
+    (rust - talib).abs() <= tolerance * talib.abs().max(1.0)
+}
+
+proptest! {
+    #[test]
+    fn wilder_atr_matches_talib((period, candles) in period_and_candles()) {
+        let rust = wilder_atr(&candles.true_range(), period);
+        let talib = talib_atr(&candles, period);
+
+        prop_assert_eq!(rust.iter().filter(|value| value.is_some()).count(), talib.len());
+        for (index, expected) in talib {
+            let actual = rust[index];
+            prop_assert!(actual.map_or(false, |actual| close(actual, expected)),
+                         "ATR({}) at candle {}: Rust {:?}, TA-Lib {}", period, index, actual, expected);
+        }
+    }
+
+    // TA-Lib's ATR over one candle is its true range, from the second candle on
+    #[test]
+    fn true_range_matches_talib(candles in ohlcv(2..300)) {
+        let true_range = candles.true_range();
+
+        for (index, expected) in talib_atr(&candles, 1) {
+            prop_assert!(close(true_range[index], expected),
+                         "True range at candle {}: Rust {}, TA-Lib {}", index, true_range[index], expected);
+        }
+    }
+
+    #[test]
+    fn linear_regression_matches_talib((period, values) in period_and_series()) {
+        let rust = linear_regression(&present(&values), period);
+        let value = StatisticFunctions::linearreg(&values, period);
+        let slope = StatisticFunctions::linearreg_slope(&values, period);
+        let intercept = StatisticFunctions::linearreg_intercept(&values, period);
+        let forecast = StatisticFunctions::tsf(&values, period);
+        let properties: [(&str, _, fn(&Regression) -> f64); 4] = [
+            ("LINEARREG", value, |regression| regression.value),
+            ("LINEARREG_SLOPE", slope, |regression| regression.slope),
+            ("LINEARREG_INTERCEPT", intercept, |regression| regression.intercept),
+            ("TSF", forecast, |regression| regression.forecast),
+        ];
+
+        for (function, talib, property) in properties {
+            let talib = talib_values(talib.unwrap_or_else(|e| panic!("TA-Lib {} failed: {}", function, e)));
+            prop_assert_eq!(rust.len(), talib.len());
+            for ((index, regression), (talib_index, expected)) in rust.iter().zip(talib) {
+                prop_assert_eq!(*index, talib_index);
+                let actual = property(regression);
+                prop_assert!(close_within(actual, expected, STATISTICS_TOLERANCE),
+                             "{}({}) at {}: Rust {}, TA-Lib {}", function, period, index, actual, expected);
+            }
+        }
+    }
+
+    // The z-score of the last value of a window, with TA-Lib's population standard deviation
+    #[test]
+    fn zscore_matches_talib((period, values) in period_and_series()) {
+        let rust = zscore(&present(&values), period);
+        let std_dev = talib_values(StatisticFunctions::stddev(&values, period, 1.0).expect("TA-Lib STDDEV failed"));
+
+        prop_assert_eq!(rust.len(), std_dev.len());
+        for ((index, actual), (talib_index, std_dev)) in rust.into_iter().zip(std_dev) {
+            prop_assert_eq!(index, talib_index);
+            let window = &values[index + 1 - period..=index];
+            let mean = window.iter().sum::<f64>() / period as f64;
+            let expected = (values[index] - mean) / std_dev;
+            prop_assert!(close_within(actual, expected, STATISTICS_TOLERANCE),
+                         "ZSCORE({}) at {}: Rust {}, TA-Lib {}", period, index, actual, expected);
+        }
+    }
+
+    #[test]
+    fn correlation_matches_talib((period, x, y) in period_and_pair(-1_000.0..1_000.0)) {
+        let rust = correlation(&present(&x), &present(&y), period);
+        let talib = talib_values(StatisticFunctions::correl(&x, &y, period).expect("TA-Lib CORREL failed"));
+
+        prop_assert_eq!(rust.len(), talib.len());
+        for ((index, actual), (talib_index, expected)) in rust.into_iter().zip(talib) {
+            prop_assert_eq!(index, talib_index);
+            prop_assert!(close_within(actual, expected, STATISTICS_TOLERANCE),
+                         "CORREL({}) at {}: Rust {}, TA-Lib {}", period, index, actual, expected);
+        }
+    }
+
+    // TA-Lib's BETA takes the benchmark first and calculates the returns of both prices
+    #[test]
+    fn beta_matches_talib((period, asset, benchmark) in period_and_pair(50.0..150.0)) {
+        let rust = beta(&returns(&present(&asset)), &returns(&present(&benchmark)), period);
+        let talib = talib_values(StatisticFunctions::beta(&benchmark, &asset, period).expect("TA-Lib BETA failed"));
+
+        prop_assert_eq!(rust.len(), talib.len());
+        for ((index, actual), (talib_index, expected)) in rust.into_iter().zip(talib) {
+            prop_assert_eq!(index, talib_index);
+            prop_assert!(close_within(actual, expected, STATISTICS_TOLERANCE),
+                         "BETA({}) at {}: Rust {}, TA-Lib {}", period, index, actual, expected);
+        }
+    }
+}
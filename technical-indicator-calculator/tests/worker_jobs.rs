@@ -0,0 +1,45 @@
+// Outcomes of calculation jobs run by the worker
+//
+// These tests use the PostgreSQL database and Redis configured in config.toml or the DB_*
+// and REDIS_URL variables, so they only build with the postgres-tests feature:
+// `cargo test --features postgres-tests`. Every test uses its own symbol.
+#![cfg(feature = "postgres-tests")]
+
+use serde_json::json;
+use std::sync::Arc;
+use technical_indicator_calculator::cache::redis::RedisManager;
+use technical_indicator_calculator::config::AppConfig;
+use technical_indicator_calculator::processor::job::{CalculationJob, IndicatorType};
+use technical_indicator_calculator::processor::worker::{Worker, WorkerConfig};
+
+#[tokio::test]
+async fn skipped_job_clears_its_in_progress_key() {
+    let config = AppConfig::load().expect("failed to load the configuration");
+    let pg = Arc::new(config.database.connect(2).await.expect("failed to connect to PostgreSQL"));
+    pg.init_tables().await.expect("failed to create the tables");
+    let redis = Arc::new(
+        RedisManager::new(&config.redis.url, config.redis.cache_ttl_seconds, 2)
+            .await
+            .expect("failed to connect to Redis"),
+    );
+
+    // A symbol without candles, so the job finishes without calculating anything
+    let symbol = format!("TEST{}", uuid::Uuid::new_v4().simple());
+    let job = CalculationJob::new(
+        symbol,
+        "1h".to_string(),
+        IndicatorType::Overlap,
+        "SMA".to_string(),
+        json!({"timeperiod": 20}),
+    );
+
+    // Marked as in progress the way the dispatcher does before queueing it
+    let key = job.cache_key();
+    redis.set(&key, &json!({"status": "processing", "job_id": job.id}), None).await.unwrap();
+    assert!(redis.exists(&key).await.unwrap());
+
+    let worker = Worker::new(pg, redis.clone(), WorkerConfig::default(), 1);
+    worker.handle_job("test", job).await;
+
+    assert!(!redis.exists(&key).await.unwrap(), "the skipped job is still marked as in progress");
+}